@@ -0,0 +1,273 @@
+//! The whole CQRS flow in one process, without PostgreSQL, Kafka or Redis
+//!
+//! Commands are handled against an in-memory event store, stored events go out on
+//! an in-memory bus, a projection task builds order views from them and a small
+//! query API serves those views to the typed [`QueryClient`]. A scripted order is
+//! created, confirmed, shipped and delivered, reading it back after every step.
+//!
+//! The command side and the projection here are demo stand-ins for the command
+//! service and `read_model::OrderProjection`, which need PostgreSQL; only the
+//! domain, the event store, the view repository and the client are the real ones.
+//!
+//! ```text
+//! cargo run -p cqrs-client --example all_in_one
+//! ```
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use cqrs_client::{ClientConfig, QueryClient};
+use domain::aggregates::order::OrderAggregate;
+use domain::events::order_events::*;
+use domain::events::{DomainEvent, EventEnvelope, EventMetadata};
+use event_store::{Event, EventStore, InMemoryEventStore};
+use read_model::{InMemoryOrderViewRepository, OrderView, OrderViewRepository};
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// Command side: rebuilds orders from the event store and records new events
+struct Commands {
+    store: Arc<dyn EventStore>,
+    bus: broadcast::Sender<EventEnvelope>,
+}
+
+impl Commands {
+    async fn create_order(
+        &self,
+        customer_id: Uuid,
+        items: Vec<OrderItem>,
+    ) -> Result<Uuid, BoxError> {
+        let (aggregate, event) = OrderAggregate::create(customer_id, items)?;
+        self.record(aggregate.id, 0, &event).await?;
+        Ok(aggregate.id)
+    }
+
+    async fn load(&self, order_id: Uuid) -> Result<(OrderAggregate, i64), BoxError> {
+        let mut aggregate = OrderAggregate::default();
+        let mut version = 0;
+        for event in self.store.load_events(order_id).await? {
+            version = event.sequence_number;
+            match event.event_type.as_str() {
+                "OrderCreated" => {
+                    aggregate.apply_order_created(&serde_json::from_value(event.payload)?)
+                }
+                "OrderConfirmed" => {
+                    aggregate.apply_order_confirmed(&serde_json::from_value(event.payload)?)
+                }
+                "OrderShipped" => {
+                    aggregate.apply_order_shipped(&serde_json::from_value(event.payload)?)
+                }
+                "OrderDelivered" => {
+                    aggregate.apply_order_delivered(&serde_json::from_value(event.payload)?)
+                }
+                "OrderCancelled" => {
+                    aggregate.apply_order_cancelled(&serde_json::from_value(event.payload)?)
+                }
+                "OrderItemPriceCorrected" => aggregate
+                    .apply_order_item_price_corrected(&serde_json::from_value(event.payload)?),
+                _ => {}
+            }
+        }
+        Ok((aggregate, version))
+    }
+
+    /// Append the event at the version after `version`, then publish it
+    async fn record<E: DomainEvent>(
+        &self,
+        order_id: Uuid,
+        version: i64,
+        event: &E,
+    ) -> Result<(), BoxError> {
+        let envelope = EventEnvelope::builder(order_id, "Order")
+            .with_metadata(EventMetadata::new())
+            .with_sequence_number(version + 1)
+            .build(event)?;
+        self.store
+            .append_events(order_id, version, vec![Event::from(&envelope)])
+            .await?;
+        // Nobody listening is not an error for the command side
+        let _ = self.bus.send(envelope);
+        Ok(())
+    }
+}
+
+/// Projection: one view per order, kept from the events on the bus
+///
+/// The single bus delivers an order's events in version order, so unlike the real
+/// projection nothing is buffered or checked for duplicates.
+async fn project(
+    views: &InMemoryOrderViewRepository,
+    envelope: EventEnvelope,
+) -> Result<(), BoxError> {
+    let version = envelope.sequence_number.unwrap_or_default();
+    if envelope.event_type == "OrderCreated" {
+        let event: OrderCreatedEvent = serde_json::from_value(envelope.payload)?;
+        views.upsert(OrderView {
+            order_id: event.order_id,
+            customer_id: event.customer_id,
+            order_number: event.order_number,
+            status: "CREATED".to_string(),
+            total_amount: event.total_amount,
+            currency: event.currency,
+            items: serde_json::to_value(&event.items)?,
+            shipping_address: None,
+            tracking_number: None,
+            carrier: None,
+            customer_name: None,
+            customer_email: None,
+            created_at: event.created_at,
+            updated_at: event.created_at,
+            version,
+        });
+        return Ok(());
+    }
+
+    let Some(mut view) = views.get_by_id(envelope.aggregate_id).await? else {
+        return Ok(());
+    };
+    match envelope.event_type.as_str() {
+        "OrderConfirmed" => view.status = "CONFIRMED".to_string(),
+        "OrderShipped" => {
+            let event: OrderShippedEvent = serde_json::from_value(envelope.payload)?;
+            view.status = "SHIPPED".to_string();
+            view.tracking_number = Some(event.tracking_number);
+            view.carrier = Some(event.carrier);
+        }
+        "OrderDelivered" => view.status = "DELIVERED".to_string(),
+        "OrderCancelled" => view.status = "CANCELLED".to_string(),
+        _ => return Ok(()),
+    }
+    view.updated_at = envelope.timestamp;
+    view.version = version;
+    views.upsert(view);
+    Ok(())
+}
+
+/// Query API: the subset of the query service's routes the script reads through
+fn query_api(views: InMemoryOrderViewRepository) -> Router {
+    async fn get_order(
+        State(views): State<InMemoryOrderViewRepository>,
+        Path(order_id): Path<Uuid>,
+    ) -> Result<Json<OrderView>, StatusCode> {
+        match views.get_by_id(order_id).await {
+            Ok(Some(order)) => Ok(Json(order)),
+            Ok(None) => Err(StatusCode::NOT_FOUND),
+            Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    }
+
+    Router::new()
+        .route("/api/v1/orders/:id", get(get_order))
+        .with_state(views)
+}
+
+/// Read the order until the projection has caught up with `version`
+async fn read_back(
+    queries: &QueryClient,
+    order_id: Uuid,
+    version: i64,
+) -> Result<OrderView, BoxError> {
+    for _ in 0..50 {
+        if let Some(order) = queries.get_order(order_id).await? {
+            if order.version >= version {
+                return Ok(order);
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    Err(format!("order {} did not reach version {}", order_id, version).into())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), BoxError> {
+    let store = Arc::new(InMemoryEventStore::new());
+    let (bus, mut events) = broadcast::channel::<EventEnvelope>(256);
+    let views = InMemoryOrderViewRepository::new();
+
+    // Projection: order views follow the events on the bus
+    let projected = views.clone();
+    tokio::spawn(async move {
+        while let Ok(envelope) = events.recv().await {
+            let event_type = envelope.event_type.clone();
+            if let Err(e) = project(&projected, envelope).await {
+                eprintln!("projection failed for {}: {}", event_type, e);
+            }
+        }
+    });
+
+    // Query API on an ephemeral port
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, query_api(views)).await });
+    let queries = QueryClient::new(ClientConfig::new(format!("http://{}", address)));
+    println!("Query API listening on http://{}", address);
+
+    let commands = Commands {
+        store: store.clone(),
+        bus,
+    };
+
+    // The scripted lifecycle
+    let items = vec![
+        OrderItem::new(Uuid::new_v4(), "BOOK-001".to_string(), 2, 19.99),
+        OrderItem::new(Uuid::new_v4(), "MUG-042".to_string(), 1, 8.50),
+    ];
+    let order_id = commands.create_order(Uuid::new_v4(), items).await?;
+    let order = read_back(&queries, order_id, 1).await?;
+    println!(
+        "created    {} total {:.2} {}",
+        order.order_number, order.total_amount, order.currency
+    );
+
+    let (aggregate, version) = commands.load(order_id).await?;
+    commands
+        .record(order_id, version, &aggregate.confirm()?)
+        .await?;
+    let order = read_back(&queries, order_id, version + 1).await?;
+    println!(
+        "confirmed  status {} (version {})",
+        order.status, order.version
+    );
+
+    let (aggregate, version) = commands.load(order_id).await?;
+    let shipped = aggregate.ship("1Z999AA10123456784".to_string(), "UPS".to_string())?;
+    commands.record(order_id, version, &shipped).await?;
+    let order = read_back(&queries, order_id, version + 1).await?;
+    println!(
+        "shipped    status {} via {} ({})",
+        order.status,
+        order.carrier.as_deref().unwrap_or("-"),
+        order.tracking_number.as_deref().unwrap_or("-")
+    );
+
+    let (aggregate, version) = commands.load(order_id).await?;
+    commands
+        .record(order_id, version, &aggregate.deliver()?)
+        .await?;
+    let order = read_back(&queries, order_id, version + 1).await?;
+    println!(
+        "delivered  status {} (version {})",
+        order.status, order.version
+    );
+
+    // Commands are checked against the rebuilt aggregate
+    let (aggregate, _) = commands.load(order_id).await?;
+    if let Err(e) = aggregate.cancel("changed my mind".to_string()) {
+        println!("cancel     rejected: {}", e);
+    }
+
+    println!("\nEvent stream for {}:", order_id);
+    for event in store.load_events(order_id).await? {
+        println!("  v{} {}", event.sequence_number, event.event_type);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,536 @@
+//! Drive a mix of order commands against the command service at a target rate
+//!
+//! Usage: loadgen [--soak]
+//!
+//! Configured through the environment:
+//!
+//! - `LOADGEN_COMMAND_URL` / `LOADGEN_QUERY_URL`: service roots (default `http://localhost:8080`
+//!   and `http://localhost:8081`)
+//! - `LOADGEN_RPS`: commands started per second (default 50)
+//! - `LOADGEN_DURATION_SECS`: how long to send commands for (default 30)
+//! - `LOADGEN_MIX`: relative weights per command, e.g. `create=50,confirm=30,cancel=20`
+//! - `LOADGEN_MAX_IN_FLIGHT`: commands allowed to run at once; ticks beyond it are skipped
+//!   and reported (default 200)
+//! - `LOADGEN_HOT_ORDERS`: confirms and cancels target the N most recently created orders,
+//!   so a smaller N means more version conflicts (default 20)
+//! - `LOADGEN_CONVERGENCE_TIMEOUT_SECS`: how long to wait for the read model to catch up
+//!   afterwards (default 60)
+//!
+//! Reports latency percentiles and how many commands succeeded, hit a version conflict
+//! (409), were rejected for the order's state or failed outright. It then polls the query
+//! service until every order shows the status of the last command accepted for it, and
+//! exits with a failure if any order hasn't converged by the timeout.
+//!
+//! `--soak` runs until interrupted (or for `LOADGEN_DURATION_SECS` if set) and checks
+//! invariants against the database as it goes; see [`soak`].
+
+use cqrs_client::models::CancelOrderRequest;
+use cqrs_client::{ClientConfig, ClientError, CommandClient, QueryClient};
+use domain::commands::order_commands::{CreateOrderCommand, CreateOrderItem, ShippingAddress};
+use hyper::StatusCode;
+use std::collections::{HashMap, VecDeque};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::time::MissedTickBehavior;
+use uuid::Uuid;
+
+mod soak;
+
+const OPERATIONS: [Operation; 3] = [Operation::Create, Operation::Confirm, Operation::Cancel];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Operation {
+    Create,
+    Confirm,
+    Cancel,
+}
+
+impl Operation {
+    fn name(self) -> &'static str {
+        match self {
+            Operation::Create => "create",
+            Operation::Confirm => "confirm",
+            Operation::Cancel => "cancel",
+        }
+    }
+}
+
+/// Relative weight of each operation
+#[derive(Debug, Clone, PartialEq)]
+struct Mix {
+    weights: Vec<(Operation, u32)>,
+    total: u32,
+}
+
+impl Mix {
+    /// Parse `create=50,confirm=30,cancel=20`; operations not listed get no weight
+    fn parse(spec: &str) -> Option<Self> {
+        let mut weights = Vec::new();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, weight) = entry.split_once('=')?;
+            let operation = OPERATIONS
+                .into_iter()
+                .find(|operation| operation.name() == name.trim())?;
+            let weight: u32 = weight.trim().parse().ok()?;
+            weights.push((operation, weight));
+        }
+        let total = weights.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return None;
+        }
+        Some(Self { weights, total })
+    }
+
+    /// Operation for the `n`th command; spreads the weights evenly over every `total` commands
+    fn pick(&self, n: u64) -> Operation {
+        let mut slot = (n % self.total as u64) as u32;
+        for &(operation, weight) in &self.weights {
+            if slot < weight {
+                return operation;
+            }
+            slot -= weight;
+        }
+        Operation::Create
+    }
+}
+
+/// Outcomes and latencies of one operation
+#[derive(Debug, Default)]
+struct OperationStats {
+    latencies: Vec<Duration>,
+    succeeded: u64,
+    conflicts: u64,
+    rejected: u64,
+    failed: u64,
+}
+
+impl OperationStats {
+    fn record(&mut self, latency: Duration, result: &Result<(), ClientError>) {
+        self.latencies.push(latency);
+        match result {
+            Ok(()) => self.succeeded += 1,
+            Err(e) if e.status() == Some(StatusCode::CONFLICT) => self.conflicts += 1,
+            Err(e) if e.status().is_some_and(|status| status.is_client_error()) => {
+                self.rejected += 1
+            }
+            Err(_) => self.failed += 1,
+        }
+    }
+
+    fn total(&self) -> u64 {
+        self.succeeded + self.conflicts + self.rejected + self.failed
+    }
+}
+
+/// Nearest-rank percentile of sorted latencies
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Later statuses win, so an order's expected status doesn't depend on the order
+/// in which concurrent responses came back
+fn status_rank(status: &str) -> u8 {
+    match status {
+        "CREATED" => 0,
+        "CONFIRMED" => 1,
+        _ => 2,
+    }
+}
+
+/// Orders created so far and the status of the last command accepted for each
+#[derive(Default)]
+struct Orders {
+    created: VecDeque<(Uuid, Instant)>,
+    expected: HashMap<Uuid, String>,
+}
+
+impl Orders {
+    fn accept(&mut self, order_id: Uuid, status: String) {
+        let expected = self.expected.entry(order_id).or_insert_with(|| {
+            self.created.push_back((order_id, Instant::now()));
+            status.clone()
+        });
+        if status_rank(&status) > status_rank(expected) {
+            *expected = status;
+        }
+    }
+
+    /// One of the `hot` most recently created orders
+    fn target(&self, n: u64, hot: usize) -> Option<Uuid> {
+        let hot = hot.clamp(1, self.created.len().max(1));
+        let offset = (n % hot as u64) as usize;
+        self.created
+            .iter()
+            .rev()
+            .nth(offset)
+            .map(|&(order_id, _)| order_id)
+    }
+
+    /// Stop tracking orders created before `cutoff` and return them
+    fn take_created_before(&mut self, cutoff: Instant) -> Vec<Uuid> {
+        let mut taken = Vec::new();
+        while let Some(&(order_id, _)) = self
+            .created
+            .front()
+            .filter(|&&(_, created_at)| created_at < cutoff)
+        {
+            self.created.pop_front();
+            self.expected.remove(&order_id);
+            taken.push(order_id);
+        }
+        taken
+    }
+}
+
+struct LoadGen {
+    commands: CommandClient,
+    orders: Mutex<Orders>,
+    stats: Mutex<HashMap<Operation, OperationStats>>,
+    hot_orders: usize,
+    /// Ticks dropped because `max_in_flight` commands were already running
+    skipped: AtomicU64,
+}
+
+impl LoadGen {
+    /// Start commands at `rps` until `stop` completes, then wait for those in flight
+    async fn drive(
+        self: &Arc<Self>,
+        mix: &Mix,
+        rps: u32,
+        max_in_flight: usize,
+        stop: impl std::future::Future<Output = ()>,
+    ) {
+        let max_in_flight = max_in_flight.max(1);
+        let in_flight = Arc::new(Semaphore::new(max_in_flight));
+        let mut ticker = tokio::time::interval(Duration::from_secs(1) / rps);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        tokio::pin!(stop);
+        let mut n = 0u64;
+
+        loop {
+            tokio::select! {
+                _ = &mut stop => break,
+                _ = ticker.tick() => {}
+            }
+            let Ok(permit) = in_flight.clone().try_acquire_owned() else {
+                self.skipped.fetch_add(1, Ordering::Relaxed);
+                continue;
+            };
+            let loadgen = self.clone();
+            let operation = mix.pick(n);
+            tokio::spawn(async move {
+                loadgen.run(n, operation).await;
+                drop(permit);
+            });
+            n += 1;
+        }
+        let _ = in_flight.acquire_many(max_in_flight as u32).await;
+    }
+
+    /// Print and reset the outcomes recorded since the last call
+    fn report(&self, elapsed: Duration) {
+        let mut stats = std::mem::take(&mut *self.stats.lock().unwrap());
+        let skipped = self.skipped.swap(0, Ordering::Relaxed);
+        print_stats(&mut stats, elapsed, skipped);
+    }
+
+    async fn run(&self, n: u64, operation: Operation) {
+        let target = match operation {
+            Operation::Create => None,
+            _ => self.orders.lock().unwrap().target(n, self.hot_orders),
+        };
+        // Nothing to confirm or cancel until the first order exists
+        let operation = if target.is_none() {
+            Operation::Create
+        } else {
+            operation
+        };
+
+        let start = Instant::now();
+        let accepted = match (operation, target) {
+            (Operation::Confirm, Some(order_id)) => self
+                .commands
+                .confirm_order(order_id, Some(Uuid::new_v4()))
+                .await
+                .map(|response| (response.order_id, response.status)),
+            (Operation::Cancel, Some(order_id)) => self
+                .commands
+                .cancel_order(
+                    order_id,
+                    &CancelOrderRequest {
+                        reason: "loadgen".to_string(),
+                        command_id: Some(Uuid::new_v4()),
+                    },
+                )
+                .await
+                .map(|response| (response.order_id, response.status)),
+            _ => self
+                .commands
+                .create_order(&sample_order())
+                .await
+                .map(|response| (response.order_id, response.status)),
+        };
+        let latency = start.elapsed();
+
+        let result = accepted.map(|(order_id, status)| {
+            self.orders.lock().unwrap().accept(order_id, status);
+        });
+        self.stats
+            .lock()
+            .unwrap()
+            .entry(operation)
+            .or_default()
+            .record(latency, &result);
+    }
+}
+
+fn sample_order() -> CreateOrderCommand {
+    CreateOrderCommand {
+        customer_id: Uuid::new_v4(),
+        items: vec![CreateOrderItem {
+            product_id: Uuid::new_v4(),
+            sku: "LOADGEN-001".to_string(),
+            quantity: 1,
+            unit_price: 9.99,
+        }],
+        shipping_address: ShippingAddress {
+            street: "1 Load Test Way".to_string(),
+            city: "Springfield".to_string(),
+            state: "IL".to_string(),
+            zip: "62701".to_string(),
+            country: "US".to_string(),
+        },
+        command_id: Some(Uuid::new_v4()),
+    }
+}
+
+/// Print outcome counts and latency percentiles per command over `elapsed`
+fn print_stats(stats: &mut HashMap<Operation, OperationStats>, elapsed: Duration, skipped: u64) {
+    let sent: u64 = stats.values().map(OperationStats::total).sum();
+    println!(
+        "\n{} commands in {:.1}s ({:.1}/s), {} ticks skipped",
+        sent,
+        elapsed.as_secs_f64(),
+        sent as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        skipped
+    );
+    println!(
+        "{:<8} {:>7} {:>7} {:>9} {:>8} {:>6} {:>8} {:>8} {:>8} {:>8}",
+        "command", "sent", "ok", "conflicts", "rejected", "failed", "p50", "p95", "p99", "max"
+    );
+    for operation in OPERATIONS {
+        let Some(stats) = stats.get_mut(&operation) else {
+            continue;
+        };
+        stats.latencies.sort();
+        let ms = |latency: Duration| format!("{:.1}ms", latency.as_secs_f64() * 1000.0);
+        println!(
+            "{:<8} {:>7} {:>7} {:>9} {:>8} {:>6} {:>8} {:>8} {:>8} {:>8}",
+            operation.name(),
+            stats.total(),
+            stats.succeeded,
+            stats.conflicts,
+            stats.rejected,
+            stats.failed,
+            ms(percentile(&stats.latencies, 50.0)),
+            ms(percentile(&stats.latencies, 95.0)),
+            ms(percentile(&stats.latencies, 99.0)),
+            ms(stats.latencies.last().copied().unwrap_or_default()),
+        );
+    }
+    let conflicts: u64 = stats.values().map(|stats| stats.conflicts).sum();
+    if sent > 0 {
+        println!(
+            "Conflict rate: {:.2}%",
+            conflicts as f64 * 100.0 / sent as f64
+        );
+    }
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Poll the query service until every order shows its expected status
+///
+/// Returns the orders that still differ when `timeout` runs out.
+async fn await_convergence(
+    queries: &QueryClient,
+    expected: &HashMap<Uuid, String>,
+    timeout: Duration,
+) -> Vec<(Uuid, String, Option<String>)> {
+    let deadline = Instant::now() + timeout;
+    let mut pending: Vec<Uuid> = expected.keys().copied().collect();
+    let mut last_seen: HashMap<Uuid, Option<String>> = HashMap::new();
+
+    loop {
+        let mut still_pending = Vec::new();
+        for order_id in pending {
+            let status = match queries.get_order(order_id).await {
+                Ok(view) => view.map(|view| view.status),
+                Err(e) => {
+                    eprintln!("Failed to read order {}: {}", order_id, e);
+                    None
+                }
+            };
+            if status.as_deref() != Some(expected[&order_id].as_str()) {
+                last_seen.insert(order_id, status);
+                still_pending.push(order_id);
+            }
+        }
+        pending = still_pending;
+
+        if pending.is_empty() || Instant::now() >= deadline {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    pending
+        .into_iter()
+        .map(|order_id| {
+            let seen = last_seen.remove(&order_id).flatten();
+            (order_id, expected[&order_id].clone(), seen)
+        })
+        .collect()
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let soak = std::env::args().skip(1).any(|arg| arg == "--soak");
+    let command_url: String = env_or("LOADGEN_COMMAND_URL", "http://localhost:8080".to_string());
+    let query_url: String = env_or("LOADGEN_QUERY_URL", "http://localhost:8081".to_string());
+    let rps: u32 = env_or("LOADGEN_RPS", 50).max(1);
+    let duration_secs: Option<u64> = std::env::var("LOADGEN_DURATION_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok());
+    let max_in_flight: usize = env_or("LOADGEN_MAX_IN_FLIGHT", 200);
+    let hot_orders: usize = env_or("LOADGEN_HOT_ORDERS", 20);
+    let convergence_timeout = Duration::from_secs(env_or("LOADGEN_CONVERGENCE_TIMEOUT_SECS", 60));
+    let mix_spec: String = env_or("LOADGEN_MIX", "create=50,confirm=30,cancel=20".to_string());
+
+    let Some(mix) = Mix::parse(&mix_spec) else {
+        eprintln!(
+            "Invalid LOADGEN_MIX '{}', expected e.g. create=50,confirm=30,cancel=20",
+            mix_spec
+        );
+        return ExitCode::from(2);
+    };
+
+    // Retries would hide conflicts and skew latencies, so every command gets one attempt
+    let commands = CommandClient::new(ClientConfig::new(command_url).with_max_retries(0));
+    let queries = QueryClient::new(ClientConfig::new(query_url));
+
+    if let Err(e) = commands.health().await {
+        eprintln!("Command service is not reachable: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    let loadgen = Arc::new(LoadGen {
+        commands,
+        orders: Mutex::new(Orders::default()),
+        stats: Mutex::new(HashMap::new()),
+        hot_orders,
+        skipped: AtomicU64::new(0),
+    });
+
+    if soak {
+        let config = soak::SoakConfig::from_env();
+        let stop = async {
+            match duration_secs {
+                Some(secs) => tokio::time::sleep(Duration::from_secs(secs)).await,
+                None => {
+                    let _ = tokio::signal::ctrl_c().await;
+                }
+            }
+        };
+        return soak::run(loadgen, &mix, rps, max_in_flight, config, stop).await;
+    }
+    let duration = Duration::from_secs(duration_secs.unwrap_or(30));
+    println!(
+        "Sending {} commands/s for {:?} ({})",
+        rps, duration, mix_spec
+    );
+    let started = Instant::now();
+    loadgen
+        .drive(&mix, rps, max_in_flight, tokio::time::sleep(duration))
+        .await;
+    loadgen.report(started.elapsed());
+
+    let expected = std::mem::take(&mut loadgen.orders.lock().unwrap().expected);
+    println!(
+        "\nWaiting up to {:?} for {} orders to converge in the read model",
+        convergence_timeout,
+        expected.len()
+    );
+    let converge_start = Instant::now();
+    let diverged = await_convergence(&queries, &expected, convergence_timeout).await;
+    if diverged.is_empty() {
+        println!(
+            "Read model converged after {:.1}s",
+            converge_start.elapsed().as_secs_f64()
+        );
+        ExitCode::SUCCESS
+    } else {
+        println!("{} orders did not converge:", diverged.len());
+        for (order_id, expected, seen) in diverged.iter().take(20) {
+            println!(
+                "  {}: expected {}, read model has {}",
+                order_id,
+                expected,
+                seen.as_deref().unwrap_or("nothing")
+            );
+        }
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mix_spreads_weights() {
+        let mix = Mix::parse("create=2, cancel=1").unwrap();
+        let picked: Vec<_> = (0..6).map(|n| mix.pick(n)).collect();
+        assert_eq!(
+            picked.iter().filter(|op| **op == Operation::Create).count(),
+            4
+        );
+        assert!(!picked.contains(&Operation::Confirm));
+
+        assert_eq!(Mix::parse("create=0"), None);
+        assert_eq!(Mix::parse("ship=10"), None);
+    }
+
+    #[test]
+    fn test_expected_status_keeps_latest_transition() {
+        let mut orders = Orders::default();
+        let order_id = Uuid::new_v4();
+        orders.accept(order_id, "CREATED".to_string());
+        orders.accept(order_id, "CANCELLED".to_string());
+        // A confirm acknowledged after the cancel still happened before it
+        orders.accept(order_id, "CONFIRMED".to_string());
+
+        assert_eq!(orders.expected[&order_id], "CANCELLED");
+        assert_eq!(orders.target(7, 20), Some(order_id));
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let sorted: Vec<_> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&sorted, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&sorted, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&[], 99.0), Duration::ZERO);
+    }
+}
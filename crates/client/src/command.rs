@@ -0,0 +1,184 @@
+use domain::commands::order_commands::CreateOrderCommand;
+use event_store::DeleteMode;
+use hyper::Method;
+use saga::SagaState;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::error::ClientError;
+use crate::models::*;
+use crate::transport::{query_string, ApiRequest, ClientConfig, Transport};
+
+/// Typed client for the command service
+///
+/// Commands are retried only when they carry a `command_id`, since the service
+/// replays the original response for a repeated ID instead of applying it twice.
+#[derive(Clone)]
+pub struct CommandClient {
+    transport: Transport,
+}
+
+impl CommandClient {
+    pub fn new(config: ClientConfig) -> Self {
+        Self {
+            transport: Transport::new(config),
+        }
+    }
+
+    /// Send every request of the returned client with the given correlation ID
+    pub fn with_correlation_id(&self, correlation_id: Uuid) -> Self {
+        Self {
+            transport: self.transport.clone().with_correlation_id(correlation_id),
+        }
+    }
+
+    pub async fn health(&self) -> Result<HealthResponse, ClientError> {
+        self.transport.send(ApiRequest::get("/health".to_string())).await
+    }
+
+    pub async fn create_order(
+        &self,
+        command: &CreateOrderCommand,
+    ) -> Result<CreateOrderResponse, ClientError> {
+        let request = ApiRequest::with_json(Method::POST, "/api/v1/orders".to_string(), command)?
+            .retryable(command.command_id.is_some());
+        self.transport.send(request).await
+    }
+
+    pub async fn confirm_order(
+        &self,
+        order_id: Uuid,
+        command_id: Option<Uuid>,
+    ) -> Result<OrderStatusResponse, ClientError> {
+        self.transition(order_id, "confirm", command_id).await
+    }
+
+    pub async fn cancel_order(
+        &self,
+        order_id: Uuid,
+        request: &CancelOrderRequest,
+    ) -> Result<OrderStatusResponse, ClientError> {
+        let request = ApiRequest::with_json(
+            Method::PUT,
+            format!("/api/v1/orders/{}/cancel", order_id),
+            request,
+        )?
+        .retryable(request.command_id.is_some());
+        self.transport.send(request).await
+    }
+
+    pub async fn ship_order(
+        &self,
+        order_id: Uuid,
+        request: &ShipOrderRequest,
+    ) -> Result<ShipOrderResponse, ClientError> {
+        let request = ApiRequest::with_json(
+            Method::PUT,
+            format!("/api/v1/orders/{}/ship", order_id),
+            request,
+        )?
+        .retryable(request.command_id.is_some());
+        self.transport.send(request).await
+    }
+
+    pub async fn deliver_order(
+        &self,
+        order_id: Uuid,
+        command_id: Option<Uuid>,
+    ) -> Result<OrderStatusResponse, ClientError> {
+        self.transition(order_id, "deliver", command_id).await
+    }
+
+    /// Ship many orders; retried only if every item carries a `command_id`
+    pub async fn bulk_ship_orders(
+        &self,
+        orders: &[BulkShipOrderItem],
+    ) -> Result<BulkShipOrdersResponse, ClientError> {
+        let retryable = orders.iter().all(|item| item.command_id.is_some());
+        let request = ApiRequest::with_json(
+            Method::POST,
+            "/api/v1/orders/bulk/ship".to_string(),
+            &json!({ "orders": orders }),
+        )?
+        .retryable(retryable);
+        self.transport.send(request).await
+    }
+
+    pub async fn delete_order(
+        &self,
+        order_id: Uuid,
+        mode: DeleteMode,
+    ) -> Result<DeleteOrderResponse, ClientError> {
+        let mode = match mode {
+            DeleteMode::Soft => "soft",
+            DeleteMode::Hard => "hard",
+        };
+        let request = ApiRequest {
+            method: Method::DELETE,
+            path: format!("/api/v1/orders/{}?mode={}", order_id, mode),
+            body: None,
+            retryable: false,
+            admin: true,
+        };
+        self.transport.send(request).await
+    }
+
+    /// Admin: every event sharing a correlation ID
+    pub async fn trace_correlation(
+        &self,
+        correlation_id: Uuid,
+        limit: Option<i64>,
+    ) -> Result<TraceCorrelationResponse, ClientError> {
+        let query = query_string(&[("limit", limit.map(|l| l.to_string()))]);
+        self.transport
+            .send(
+                ApiRequest::get(format!(
+                    "/api/v1/admin/events/correlation/{}{}",
+                    correlation_id, query
+                ))
+                .admin(),
+            )
+            .await
+    }
+
+    /// Admin: sagas waiting for an operator
+    pub async fn list_interventions(
+        &self,
+        limit: Option<i64>,
+    ) -> Result<InterventionListResponse, ClientError> {
+        let query = query_string(&[("limit", limit.map(|l| l.to_string()))]);
+        self.transport
+            .send(ApiRequest::get(format!("/api/v1/admin/sagas/interventions{}", query)).admin())
+            .await
+    }
+
+    /// Admin: mark a saga waiting for an operator as resolved
+    pub async fn resolve_intervention(
+        &self,
+        saga_id: Uuid,
+        resolution: &str,
+    ) -> Result<SagaState, ClientError> {
+        let request = ApiRequest::with_json(
+            Method::POST,
+            format!("/api/v1/admin/sagas/{}/resolve", saga_id),
+            &json!({ "resolution": resolution }),
+        )?
+        .admin();
+        self.transport.send(request).await
+    }
+
+    async fn transition(
+        &self,
+        order_id: Uuid,
+        action: &str,
+        command_id: Option<Uuid>,
+    ) -> Result<OrderStatusResponse, ClientError> {
+        let request = ApiRequest::with_json(
+            Method::PUT,
+            format!("/api/v1/orders/{}/{}", order_id, action),
+            &json!({ "command_id": command_id }),
+        )?
+        .retryable(command_id.is_some());
+        self.transport.send(request).await
+    }
+}
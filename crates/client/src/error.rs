@@ -0,0 +1,86 @@
+use domain::api::ErrorResponse;
+use hyper::StatusCode;
+use thiserror::Error;
+
+/// A field the command service rejected, as returned in `details`
+pub use domain::api::FieldError;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// The service answered with a non-success status
+    #[error("HTTP {status}: {message}")]
+    Api {
+        status: StatusCode,
+        message: String,
+        details: Vec<FieldError>,
+    },
+
+    #[error("Request timed out after {0:?}")]
+    Timeout(std::time::Duration),
+
+    #[error("Transport error: {0}")]
+    Transport(String),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+}
+
+impl ClientError {
+    /// Status code of an API error, if the service answered at all
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ClientError::Api { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(StatusCode::NOT_FOUND)
+    }
+
+    /// Build an API error from a response body
+    ///
+    /// The command service answers with `{"error", "details"}` JSON while the query
+    /// service answers with plain text; both are accepted.
+    pub(crate) fn from_response(status: StatusCode, body: &[u8]) -> Self {
+        match serde_json::from_slice::<ErrorResponse>(body) {
+            Ok(body) => ClientError::Api {
+                status,
+                message: body.error,
+                details: body.details,
+            },
+            Err(_) => ClientError::Api {
+                status,
+                message: String::from_utf8_lossy(body).into_owned(),
+                details: Vec::new(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_body_formats() {
+        let json = ClientError::from_response(
+            StatusCode::BAD_REQUEST,
+            br#"{"error":"Validation error","details":[{"field":"items","code":"length","message":"Order must have at least one item"}]}"#,
+        );
+        match json {
+            ClientError::Api { message, details, .. } => {
+                assert_eq!(message, "Validation error");
+                assert_eq!(details[0].field, "items");
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        let text = ClientError::from_response(StatusCode::NOT_FOUND, b"Order not found: 42");
+        assert!(text.is_not_found());
+        assert_eq!(text.to_string(), "HTTP 404 Not Found: Order not found: 42");
+    }
+}
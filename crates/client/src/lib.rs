@@ -0,0 +1,27 @@
+//! Typed async HTTP clients for the command and query services
+//!
+//! ```no_run
+//! use cqrs_client::{ClientConfig, QueryClient};
+//!
+//! # async fn example(order_id: uuid::Uuid) -> Result<(), cqrs_client::ClientError> {
+//! let queries = QueryClient::new(ClientConfig::new("http://localhost:8081"));
+//! if let Some(order) = queries.get_order(order_id).await? {
+//!     println!("{} is {}", order.order_number, order.status);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Admin calls, such as `resolve_intervention` or `list_projection_errors`, need the
+//! service's admin token: `ClientConfig::new(url).with_admin_token(token)`.
+
+pub mod command;
+pub mod error;
+pub mod models;
+pub mod query;
+mod transport;
+
+pub use command::CommandClient;
+pub use error::{ClientError, FieldError};
+pub use query::QueryClient;
+pub use transport::{ClientConfig, CORRELATION_ID_HEADER};
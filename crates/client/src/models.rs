@@ -0,0 +1,191 @@
+//! Request and response bodies of the command and query services
+//!
+//! Read model rows, sagas and stored events reuse the types of their crates; the
+//! envelopes around them mirror the service handlers.
+
+use chrono::{DateTime, Utc};
+use event_store::{DeleteMode, Event};
+use read_model::{
+    OrderStatsBucket, OrderView, PaymentHistoryEntry, PaymentView, ProjectionError,
+    SagaTypeStats, SagaView, StatsGroupBy, TimelineEntry,
+};
+use saga::PendingSagas;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HealthResponse {
+    pub status: String,
+    pub service: String,
+    pub version: String,
+}
+
+// Command service
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShipOrderRequest {
+    pub tracking_number: String,
+    pub carrier: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CancelOrderRequest {
+    pub reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkShipOrderItem {
+    pub order_id: Uuid,
+    pub tracking_number: String,
+    pub carrier: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateOrderResponse {
+    pub order_id: Uuid,
+    pub order_number: String,
+    pub status: String,
+}
+
+/// Response of the confirm, cancel and deliver commands
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderStatusResponse {
+    pub order_id: Uuid,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShipOrderResponse {
+    pub order_id: Uuid,
+    pub status: String,
+    pub tracking_number: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkShipOrderResult {
+    pub order_id: Uuid,
+    pub success: bool,
+    pub status: Option<String>,
+    pub tracking_number: Option<String>,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub details: Vec<crate::error::FieldError>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkShipOrdersResponse {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkShipOrderResult>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeleteOrderResponse {
+    pub order_id: Uuid,
+    pub mode: DeleteMode,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TraceCorrelationResponse {
+    pub correlation_id: Uuid,
+    pub aggregate_ids: Vec<Uuid>,
+    pub events: Vec<Event>,
+}
+
+/// Sagas queued for manual intervention
+pub type InterventionListResponse = PendingSagas;
+
+// Query service
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderListResponse {
+    pub orders: Vec<OrderView>,
+    /// Set when listing a customer's orders
+    pub total: Option<i64>,
+    /// Set when listing orders by status
+    pub status: Option<String>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderTimelineResponse {
+    pub order_id: Uuid,
+    pub entries: Vec<TimelineEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProductInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderItemView {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub quantity: u32,
+    pub unit_price: f64,
+    pub line_total: f64,
+    pub product: Option<ProductInfo>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderSummaryResponse {
+    pub order_id: Uuid,
+    pub order_number: String,
+    pub status: String,
+    pub currency: String,
+    pub items: Vec<OrderItemView>,
+    pub item_count: u32,
+    pub total_amount: f64,
+    pub shipping_address: Option<serde_json::Value>,
+    pub tracking_number: Option<String>,
+    pub carrier: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderStatsResponse {
+    pub group_by: StatsGroupBy,
+    pub buckets: Vec<OrderStatsBucket>,
+    pub total_count: i64,
+    pub total_amount: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SagaListResponse {
+    pub sagas: Vec<SagaView>,
+    pub stats: Vec<SagaTypeStats>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderPaymentsResponse {
+    pub order_id: Uuid,
+    pub payments: Vec<PaymentView>,
+    pub history: Vec<PaymentHistoryEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UnsettledAuthorizationsResponse {
+    pub payments: Vec<PaymentView>,
+    pub count: usize,
+    pub older_than_hours: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProjectionErrorListResponse {
+    pub errors: Vec<ProjectionError>,
+    pub count: usize,
+}
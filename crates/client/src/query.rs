@@ -0,0 +1,189 @@
+use hyper::Method;
+use read_model::{InventoryView, OrderView, ProjectionError, StatsGroupBy};
+use uuid::Uuid;
+
+use crate::error::ClientError;
+use crate::models::*;
+use crate::transport::{encode, query_string, ApiRequest, ClientConfig, Transport};
+
+/// Typed client for the query service; every read is retried on transient failures
+#[derive(Clone)]
+pub struct QueryClient {
+    transport: Transport,
+}
+
+impl QueryClient {
+    pub fn new(config: ClientConfig) -> Self {
+        Self {
+            transport: Transport::new(config),
+        }
+    }
+
+    /// Send every request of the returned client with the given correlation ID
+    pub fn with_correlation_id(&self, correlation_id: Uuid) -> Self {
+        Self {
+            transport: self.transport.clone().with_correlation_id(correlation_id),
+        }
+    }
+
+    pub async fn health(&self) -> Result<HealthResponse, ClientError> {
+        self.get("/health".to_string()).await
+    }
+
+    /// Get an order, or `None` if the read model does not have it
+    pub async fn get_order(&self, order_id: Uuid) -> Result<Option<OrderView>, ClientError> {
+        not_found_as_none(self.get(format!("/api/v1/orders/{}", order_id)).await)
+    }
+
+    pub async fn get_order_by_number(
+        &self,
+        order_number: &str,
+    ) -> Result<Option<OrderView>, ClientError> {
+        not_found_as_none(
+            self.get(format!("/api/v1/orders/number/{}", encode(order_number)))
+                .await,
+        )
+    }
+
+    pub async fn get_order_summary(
+        &self,
+        order_id: Uuid,
+    ) -> Result<OrderSummaryResponse, ClientError> {
+        self.get(format!("/api/v1/orders/{}/summary", order_id)).await
+    }
+
+    pub async fn get_order_timeline(
+        &self,
+        order_id: Uuid,
+    ) -> Result<OrderTimelineResponse, ClientError> {
+        self.get(format!("/api/v1/orders/{}/timeline", order_id)).await
+    }
+
+    pub async fn get_order_payments(
+        &self,
+        order_id: Uuid,
+    ) -> Result<OrderPaymentsResponse, ClientError> {
+        self.get(format!("/api/v1/orders/{}/payments", order_id)).await
+    }
+
+    pub async fn list_customer_orders(
+        &self,
+        customer_id: Uuid,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<OrderListResponse, ClientError> {
+        let query = query_string(&[
+            ("limit", limit.map(|l| l.to_string())),
+            ("offset", offset.map(|o| o.to_string())),
+        ]);
+        self.get(format!("/api/v1/customers/{}/orders{}", customer_id, query))
+            .await
+    }
+
+    pub async fn list_orders_by_status(
+        &self,
+        status: &str,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<OrderListResponse, ClientError> {
+        let query = query_string(&[
+            ("limit", limit.map(|l| l.to_string())),
+            ("offset", offset.map(|o| o.to_string())),
+        ]);
+        self.get(format!("/api/v1/orders/status/{}{}", encode(status), query))
+            .await
+    }
+
+    pub async fn order_stats(
+        &self,
+        group_by: StatsGroupBy,
+        limit: Option<i64>,
+    ) -> Result<OrderStatsResponse, ClientError> {
+        let group_by = match group_by {
+            StatsGroupBy::Status => "status",
+            StatsGroupBy::Day => "day",
+            StatsGroupBy::Customer => "customer",
+        };
+        let query = query_string(&[
+            ("group_by", Some(group_by.to_string())),
+            ("limit", limit.map(|l| l.to_string())),
+        ]);
+        self.get(format!("/api/v1/orders/stats{}", query)).await
+    }
+
+    pub async fn list_sagas(
+        &self,
+        status: Option<&str>,
+        saga_type: Option<&str>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<SagaListResponse, ClientError> {
+        let query = query_string(&[
+            ("status", status.map(str::to_string)),
+            ("type", saga_type.map(str::to_string)),
+            ("limit", limit.map(|l| l.to_string())),
+            ("offset", offset.map(|o| o.to_string())),
+        ]);
+        self.get(format!("/api/v1/sagas{}", query)).await
+    }
+
+    /// Stock level of a SKU, or `None` if the SKU has never been stocked
+    pub async fn get_inventory(&self, sku: &str) -> Result<Option<InventoryView>, ClientError> {
+        not_found_as_none(self.get(format!("/api/v1/inventory/{}", encode(sku))).await)
+    }
+
+    /// Authorizations older than `older_than_hours` that were never captured or voided
+    pub async fn list_unsettled_authorizations(
+        &self,
+        older_than_hours: Option<i64>,
+        limit: Option<i64>,
+    ) -> Result<UnsettledAuthorizationsResponse, ClientError> {
+        let query = query_string(&[
+            ("older_than_hours", older_than_hours.map(|h| h.to_string())),
+            ("limit", limit.map(|l| l.to_string())),
+        ]);
+        self.get(format!("/api/v1/payments/unsettled{}", query)).await
+    }
+
+    /// Admin: events the projection service quarantined
+    pub async fn list_projection_errors(
+        &self,
+        status: Option<&str>,
+        limit: Option<i64>,
+    ) -> Result<ProjectionErrorListResponse, ClientError> {
+        let query = query_string(&[
+            ("status", status.map(str::to_string)),
+            ("limit", limit.map(|l| l.to_string())),
+        ]);
+        self.transport
+            .send(ApiRequest::get(format!("/api/v1/admin/projection-errors{}", query)).admin())
+            .await
+    }
+
+    /// Admin: queue a quarantined event for another attempt
+    pub async fn requeue_projection_error(
+        &self,
+        error_id: Uuid,
+    ) -> Result<ProjectionError, ClientError> {
+        let request = ApiRequest {
+            method: Method::POST,
+            path: format!("/api/v1/admin/projection-errors/{}/requeue", error_id),
+            body: None,
+            retryable: false,
+            admin: true,
+        };
+        self.transport.send(request).await
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, path: String) -> Result<T, ClientError> {
+        self.transport.send(ApiRequest::get(path)).await
+    }
+}
+
+fn not_found_as_none<T>(result: Result<T, ClientError>) -> Result<Option<T>, ClientError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
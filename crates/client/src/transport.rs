@@ -0,0 +1,379 @@
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::header::{HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use hyper::{Method, Request, StatusCode};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::error::ClientError;
+
+/// Header carrying the correlation ID of a call across services
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+/// Connection settings shared by the command and query clients
+#[derive(Clone)]
+pub struct ClientConfig {
+    base_url: String,
+    timeout: Duration,
+    max_retries: u32,
+    retry_backoff: Duration,
+    admin_token: Option<String>,
+}
+
+impl fmt::Debug for ClientConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientConfig")
+            .field("base_url", &self.base_url)
+            .field("timeout", &self.timeout)
+            .field("max_retries", &self.max_retries)
+            .field("retry_backoff", &self.retry_backoff)
+            .field("admin_token", &self.admin_token.as_ref().map(|_| "[REDACTED]"))
+            .finish()
+    }
+}
+
+impl ClientConfig {
+    /// `base_url` is the service root, e.g. `http://command-service:8080`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            timeout: Duration::from_secs(10),
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(100),
+            admin_token: None,
+        }
+    }
+
+    /// Bearer token sent on admin routes (`ADMIN_API_TOKEN` or one of `ADMIN_API_TOKENS`
+    /// of the service); without it they are refused
+    pub fn with_admin_token(mut self, token: impl Into<String>) -> Self {
+        self.admin_token = Some(token.into());
+        self
+    }
+
+    /// Time allowed for a single attempt, including reading the response
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Extra attempts for retryable requests after the first one fails
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Delay before the first retry; doubled for every further retry
+    pub fn with_retry_backoff(mut self, retry_backoff: Duration) -> Self {
+        self.retry_backoff = retry_backoff;
+        self
+    }
+}
+
+/// A request to send, rebuilt for every attempt
+pub(crate) struct ApiRequest {
+    pub method: Method,
+    pub path: String,
+    pub body: Option<Bytes>,
+    /// Whether repeating the request is safe; reads always are, commands only when
+    /// they carry a `command_id` the service deduplicates on
+    pub retryable: bool,
+    /// Whether the route requires the admin token
+    pub admin: bool,
+}
+
+impl ApiRequest {
+    pub fn get(path: String) -> Self {
+        Self {
+            method: Method::GET,
+            path,
+            body: None,
+            retryable: true,
+            admin: false,
+        }
+    }
+
+    pub fn with_json<B: Serialize>(
+        method: Method,
+        path: String,
+        body: &B,
+    ) -> Result<Self, ClientError> {
+        Ok(Self {
+            retryable: false,
+            admin: false,
+            method,
+            path,
+            body: Some(Bytes::from(serde_json::to_vec(body)?)),
+        })
+    }
+
+    pub fn retryable(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Send the configured admin token with the request
+    pub fn admin(mut self) -> Self {
+        self.admin = true;
+        self
+    }
+}
+
+/// Plain HTTP/1.1 transport with per-attempt timeouts and retries
+///
+/// Connection failures, timeouts, 429 and 502-504 responses are retried for requests
+/// marked retryable; everything else is returned to the caller as is.
+#[derive(Clone)]
+pub(crate) struct Transport {
+    client: Client<HttpConnector, Full<Bytes>>,
+    config: ClientConfig,
+    correlation_id: Option<Uuid>,
+}
+
+impl Transport {
+    pub fn new(config: ClientConfig) -> Self {
+        Self {
+            client: Client::builder(TokioExecutor::new()).build_http(),
+            config,
+            correlation_id: None,
+        }
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: Uuid) -> Self {
+        self.correlation_id = Some(correlation_id);
+        self
+    }
+
+    pub async fn send<T: DeserializeOwned>(&self, request: ApiRequest) -> Result<T, ClientError> {
+        // One ID for all attempts, so retries show up as the same call downstream
+        let correlation_id = self.correlation_id.unwrap_or_else(Uuid::new_v4);
+        let attempts = if request.retryable {
+            self.config.max_retries + 1
+        } else {
+            1
+        };
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let result = self.attempt(&request, correlation_id).await;
+
+            let retry = attempt < attempts
+                && match &result {
+                    Ok((status, _)) => is_retryable_status(*status),
+                    Err(ClientError::Timeout(_)) | Err(ClientError::Transport(_)) => true,
+                    Err(_) => false,
+                };
+
+            if !retry {
+                let (status, body) = result?;
+                if !status.is_success() {
+                    return Err(ClientError::from_response(status, &body));
+                }
+                return Ok(serde_json::from_slice(&body)?);
+            }
+
+            let delay = self.config.retry_backoff.saturating_mul(1 << (attempt - 1).min(10));
+            warn!(
+                "{} {} failed (attempt {}/{}, correlation_id: {}), retrying in {:?}",
+                request.method, request.path, attempt, attempts, correlation_id, delay
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn attempt(
+        &self,
+        request: &ApiRequest,
+        correlation_id: Uuid,
+    ) -> Result<(StatusCode, Bytes), ClientError> {
+        let uri = format!("{}{}", self.config.base_url, request.path);
+        let mut builder = Request::builder()
+            .method(request.method.clone())
+            .uri(&uri)
+            .header(ACCEPT, HeaderValue::from_static("application/json"))
+            .header(CORRELATION_ID_HEADER, correlation_id.to_string());
+        if request.body.is_some() {
+            builder = builder.header(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        }
+        if let Some(token) = self.config.admin_token.as_deref().filter(|_| request.admin) {
+            builder = builder.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let http_request = builder
+            .body(Full::new(request.body.clone().unwrap_or_default()))
+            .map_err(|e| ClientError::InvalidRequest(format!("{}: {}", uri, e)))?;
+
+        debug!("{} {} (correlation_id: {})", request.method, uri, correlation_id);
+
+        let exchange = async {
+            let response = self
+                .client
+                .request(http_request)
+                .await
+                .map_err(|e| ClientError::Transport(e.to_string()))?;
+            let status = response.status();
+            let body = response
+                .into_body()
+                .collect()
+                .await
+                .map_err(|e| ClientError::Transport(e.to_string()))?
+                .to_bytes();
+            Ok((status, body))
+        };
+
+        tokio::time::timeout(self.config.timeout, exchange)
+            .await
+            .map_err(|_| ClientError::Timeout(self.config.timeout))?
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Percent-encode a value for use as a path segment or query value
+pub(crate) fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Build a query string from the parameters that are set
+pub(crate) fn query_string(params: &[(&str, Option<String>)]) -> String {
+    let pairs: Vec<String> = params
+        .iter()
+        .filter_map(|(key, value)| value.as_ref().map(|v| format!("{}={}", key, encode(v))))
+        .collect();
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("?{}", pairs.join("&"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::HeaderMap, routing::get, Json, Router};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_query_string_encoding() {
+        assert_eq!(
+            query_string(&[("status", Some("A B&C".to_string())), ("limit", None)]),
+            "?status=A%20B%26C"
+        );
+        assert_eq!(query_string(&[("limit", None)]), "");
+    }
+
+    #[tokio::test]
+    async fn test_retries_unavailable_and_keeps_correlation_id() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let seen = calls.clone();
+        let router = Router::new().route(
+            "/flaky",
+            get(move |headers: HeaderMap| {
+                let seen = seen.clone();
+                async move {
+                    let call = seen.fetch_add(1, Ordering::SeqCst);
+                    let correlation = headers[CORRELATION_ID_HEADER].to_str().unwrap().to_string();
+                    if call == 0 {
+                        Err(axum::http::StatusCode::SERVICE_UNAVAILABLE)
+                    } else {
+                        Ok(Json(correlation))
+                    }
+                }
+            }),
+        );
+        let base_url = serve(router).await;
+
+        let correlation_id = Uuid::new_v4();
+        let transport = Transport::new(
+            ClientConfig::new(base_url).with_retry_backoff(Duration::from_millis(1)),
+        )
+        .with_correlation_id(correlation_id);
+
+        let echoed: String = transport.send(ApiRequest::get("/flaky".to_string())).await.unwrap();
+        assert_eq!(echoed, correlation_id.to_string());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_requests_fail_fast() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let seen = calls.clone();
+        let router = Router::new().route(
+            "/create",
+            axum::routing::post(move || {
+                let seen = seen.clone();
+                async move {
+                    seen.fetch_add(1, Ordering::SeqCst);
+                    axum::http::StatusCode::SERVICE_UNAVAILABLE
+                }
+            }),
+        );
+        let base_url = serve(router).await;
+
+        let transport = Transport::new(ClientConfig::new(base_url));
+        let request =
+            ApiRequest::with_json(Method::POST, "/create".to_string(), &serde_json::json!({}))
+                .unwrap();
+        let error = transport.send::<serde_json::Value>(request).await.unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_admin_token_is_sent_on_admin_requests_only() {
+        let router = Router::new().route(
+            "/whoami",
+            get(|headers: HeaderMap| async move {
+                Json(
+                    headers
+                        .get(AUTHORIZATION)
+                        .map(|value| value.to_str().unwrap().to_string()),
+                )
+            }),
+        );
+        let base_url = serve(router).await;
+        let transport = Transport::new(ClientConfig::new(base_url).with_admin_token("s3cret"));
+
+        let admin: Option<String> = transport
+            .send(ApiRequest::get("/whoami".to_string()).admin())
+            .await
+            .unwrap();
+        assert_eq!(admin.as_deref(), Some("Bearer s3cret"));
+        let public: Option<String> =
+            transport.send(ApiRequest::get("/whoami".to_string())).await.unwrap();
+        assert_eq!(public, None);
+        assert!(!format!("{:?}", transport.config).contains("s3cret"));
+    }
+}
@@ -0,0 +1,186 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Admin the shared `ADMIN_API_TOKEN` authenticates as
+pub const SHARED_ADMIN: &str = "admin";
+
+/// Bearer token of one admin
+#[derive(Clone)]
+pub struct AdminToken {
+    name: Arc<str>,
+    token: Arc<str>,
+}
+
+impl AdminToken {
+    pub fn new(name: &str, token: &str) -> Self {
+        Self {
+            name: Arc::from(name),
+            token: Arc::from(token),
+        }
+    }
+
+    /// Parse `ADMIN_API_TOKENS`: comma-separated `name:token` pairs
+    ///
+    /// Entries without a name or a token are skipped.
+    pub fn parse_list(value: &str) -> Vec<Self> {
+        value
+            .split(',')
+            .filter_map(|entry| entry.split_once(':'))
+            .map(|(name, token)| (name.trim(), token.trim()))
+            .filter(|(name, token)| !name.is_empty() && !token.is_empty())
+            .map(|(name, token)| Self::new(name, token))
+            .collect()
+    }
+
+    /// Tokens from `ADMIN_API_TOKEN` (as [`SHARED_ADMIN`]) and `ADMIN_API_TOKENS`
+    pub fn from_env() -> Vec<Self> {
+        let mut tokens: Vec<Self> = std::env::var("ADMIN_API_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty())
+            .map(|token| Self::new(SHARED_ADMIN, &token))
+            .into_iter()
+            .collect();
+        if let Ok(value) = std::env::var("ADMIN_API_TOKENS") {
+            tokens.extend(Self::parse_list(&value));
+        }
+        if tokens.is_empty() {
+            info!("ADMIN_API_TOKEN and ADMIN_API_TOKENS not set, protected admin routes are disabled");
+        }
+        tokens
+    }
+}
+
+/// Every token accepted on a service's admin routes, the state of [`require_admin_token`]
+#[derive(Clone, Default)]
+pub struct AdminTokens(Arc<[AdminToken]>);
+
+impl AdminTokens {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<AdminToken>> for AdminTokens {
+    fn from(tokens: Vec<AdminToken>) -> Self {
+        Self(tokens.into())
+    }
+}
+
+/// The admin a request was authenticated as
+///
+/// Added to the request extensions by [`require_admin_token`]; handlers behind it
+/// record decisions under this name rather than one taken from the request body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminIdentity(pub Arc<str>);
+
+impl AdminIdentity {
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Middleware for admin routes that expose stored data or change sagas and orders
+///
+/// Requests must carry `Authorization: Bearer <token>` with `ADMIN_API_TOKEN` or
+/// one of the tokens in `ADMIN_API_TOKENS`. Without a configured token these routes
+/// are refused outright rather than left open.
+pub async fn require_admin_token(
+    State(tokens): State<AdminTokens>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if tokens.is_empty() {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Admin API is disabled: no admin token configured",
+            })),
+        )
+            .into_response();
+    }
+
+    let Some(identity) = authenticate(request.headers(), &tokens.0) else {
+        warn!(path = %request.uri().path(), "Rejected admin request without a valid token");
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(serde_json::json!({ "error": "Missing or invalid admin token" })),
+        )
+            .into_response();
+    };
+
+    request.extensions_mut().insert(identity);
+    next.run(request).await
+}
+
+/// Match the presented bearer token against every configured token
+///
+/// All tokens are compared so the timing does not reveal which one came close.
+fn authenticate(headers: &HeaderMap, tokens: &[AdminToken]) -> Option<AdminIdentity> {
+    let presented = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))?
+        .trim();
+    tokens.iter().fold(None, |found, admin| {
+        if constant_time_eq(presented.as_bytes(), admin.token.as_bytes()) {
+            Some(AdminIdentity(admin.name.clone()))
+        } else {
+            found
+        }
+    })
+}
+
+/// Compare without returning early, so response timing does not leak the token
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(authorization: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_str(authorization).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_bearer_token_must_match() {
+        let tokens = [AdminToken::new(SHARED_ADMIN, "s3cret")];
+        assert_eq!(
+            authenticate(&headers("Bearer s3cret"), &tokens),
+            Some(AdminIdentity(Arc::from(SHARED_ADMIN)))
+        );
+        assert_eq!(authenticate(&headers("Bearer wrong"), &tokens), None);
+        assert_eq!(authenticate(&headers("Basic s3cret"), &tokens), None);
+        assert_eq!(authenticate(&HeaderMap::new(), &tokens), None);
+    }
+
+    #[test]
+    fn test_named_tokens_identify_the_admin() {
+        let tokens = AdminToken::parse_list("alice:t0k-a, bob:t0k-b,:orphan,carol:");
+        assert_eq!(tokens.len(), 2);
+        let identity = authenticate(&headers("Bearer t0k-b"), &tokens).unwrap();
+        assert_eq!(identity.name(), "bob");
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"abcd"));
+    }
+}
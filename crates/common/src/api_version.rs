@@ -0,0 +1,346 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{request::Parts, HeaderValue, StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Header a client sets to pick a version for an unversioned `/api/...` path; every
+/// versioned response carries it too
+pub const API_VERSION_HEADER: &str = "api-version";
+
+/// Set to `true` on responses from a deprecated version
+pub const DEPRECATION_HEADER: &str = "deprecation";
+
+/// Major version of the HTTP API, the `N` in `/api/vN/...`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiVersion(pub u16);
+
+impl ApiVersion {
+    pub const V1: ApiVersion = ApiVersion(1);
+
+    /// Path prefix the version's routes are mounted under, e.g. `/api/v1`
+    pub fn prefix(self) -> String {
+        format!("/api/v{}", self.0)
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+impl FromStr for ApiVersion {
+    type Err = String;
+
+    /// `2` or `v2`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        s.strip_prefix(['v', 'V'])
+            .unwrap_or(s)
+            .parse()
+            .map(ApiVersion)
+            .map_err(|_| format!("invalid API version '{}'", s))
+    }
+}
+
+/// The version a request was negotiated to; `V1` outside [`negotiate`]
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ApiVersion {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<ApiVersion>()
+            .copied()
+            .unwrap_or(ApiVersion::V1))
+    }
+}
+
+/// Mount the routes of every version under its own prefix
+///
+/// `routes` is called once per version with paths relative to the prefix, so a
+/// version only differs where it swaps in another handler, e.g.
+/// `get(if version >= ApiVersion(2) { get_order_v2 } else { get_order })`.
+/// Route templates keep the full path (`/api/v1/orders/:id`) in metrics and logs.
+pub fn mount<S>(versions: &[ApiVersion], routes: impl Fn(ApiVersion) -> Router<S>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    versions.iter().fold(Router::new(), |router, &version| {
+        router.nest(&version.prefix(), routes(version))
+    })
+}
+
+/// Which versions a service serves and how unversioned requests are treated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiVersions {
+    supported: Vec<ApiVersion>,
+    default: ApiVersion,
+    deprecated: Vec<ApiVersion>,
+}
+
+impl ApiVersions {
+    /// Serve `supported`, sending unversioned requests to the oldest of them so
+    /// clients written before versioning keep their responses
+    pub fn new(supported: &[ApiVersion]) -> Self {
+        let mut supported = supported.to_vec();
+        supported.sort();
+        supported.dedup();
+        let default = supported.first().copied().unwrap_or(ApiVersion::V1);
+        Self {
+            supported,
+            default,
+            deprecated: Vec::new(),
+        }
+    }
+
+    /// `supported` with `API_DEFAULT_VERSION` and `API_DEPRECATED_VERSIONS`
+    /// (comma-separated) applied; versions the service doesn't serve are ignored
+    pub fn from_env(supported: &[ApiVersion]) -> Self {
+        let mut versions = Self::new(supported);
+        if let Some(default) = std::env::var("API_DEFAULT_VERSION")
+            .ok()
+            .and_then(|value| value.parse().ok())
+        {
+            versions = versions.with_default(default);
+        }
+        if let Ok(deprecated) = std::env::var("API_DEPRECATED_VERSIONS") {
+            for version in deprecated.split(',').filter_map(|v| v.parse().ok()) {
+                versions = versions.with_deprecated(version);
+            }
+        }
+        versions
+    }
+
+    /// Version for requests that don't name one
+    pub fn with_default(mut self, version: ApiVersion) -> Self {
+        if self.is_supported(version) {
+            self.default = version;
+        } else {
+            tracing::warn!(
+                "API default version {} is not served, keeping {}",
+                version,
+                self.default
+            );
+        }
+        self
+    }
+
+    /// Mark a version as deprecated; its responses carry `Deprecation: true`
+    pub fn with_deprecated(mut self, version: ApiVersion) -> Self {
+        if self.is_supported(version) && !self.deprecated.contains(&version) {
+            self.deprecated.push(version);
+        }
+        self
+    }
+
+    pub fn supported(&self) -> &[ApiVersion] {
+        &self.supported
+    }
+
+    pub fn default_version(&self) -> ApiVersion {
+        self.default
+    }
+
+    pub fn is_supported(&self, version: ApiVersion) -> bool {
+        self.supported.contains(&version)
+    }
+
+    pub fn is_deprecated(&self, version: ApiVersion) -> bool {
+        self.deprecated.contains(&version)
+    }
+
+    /// Version of a request to `path` and the versioned path to route it to if it
+    /// was unversioned; `None` for paths outside `/api`
+    fn resolve(
+        &self,
+        path: &str,
+        requested: Option<&str>,
+    ) -> Option<Result<(ApiVersion, Option<String>), String>> {
+        let rest = path.strip_prefix("/api/")?;
+        let segment = rest.split('/').next().unwrap_or_default();
+        let in_path = segment
+            .strip_prefix('v')
+            .filter(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+            .map(|digits| digits.parse().map(ApiVersion));
+        let requested = match requested.map(str::parse::<ApiVersion>).transpose() {
+            Ok(requested) => requested,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let resolved = match in_path {
+            Some(Ok(version)) => match requested {
+                Some(header) if header != version => Err(format!(
+                    "{} header asks for {} but the path is {}",
+                    API_VERSION_HEADER, header, version
+                )),
+                _ => Ok((version, None)),
+            },
+            Some(Err(_)) => Err(format!("invalid API version '{}'", segment)),
+            None => {
+                let version = requested.unwrap_or(self.default);
+                Ok((version, Some(format!("{}/{}", version.prefix(), rest))))
+            }
+        };
+
+        Some(resolved.and_then(|(version, path)| {
+            if self.is_supported(version) {
+                Ok((version, path))
+            } else {
+                Err(format!("API version {} is not supported", version))
+            }
+        }))
+    }
+}
+
+/// Negotiate the API version of every request before it is routed
+///
+/// `/api/vN/...` requests keep their version; an [`API_VERSION_HEADER`] that names
+/// another one is rejected. Unversioned `/api/...` requests are routed to the version
+/// in the header, or to the default one. Unknown versions get a 400 listing the
+/// supported ones. The version is available to handlers as an [`ApiVersion`]
+/// extractor and echoed in the response.
+///
+/// The path is rewritten, so wrap the whole `Router` with this rather than adding it
+/// with `Router::layer`, which only runs once a route has matched.
+pub async fn negotiate(
+    State(versions): State<ApiVersions>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let requested = request
+        .headers()
+        .get(API_VERSION_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let resolved = match versions.resolve(request.uri().path(), requested) {
+        Some(resolved) => resolved,
+        None => return next.run(request).await,
+    };
+
+    let version = match resolved {
+        Ok((version, None)) => version,
+        Ok((version, Some(path))) => {
+            match rewrite_path(request.uri(), &path) {
+                Some(uri) => *request.uri_mut() = uri,
+                None => return unsupported(&versions, "invalid request path".to_string()),
+            }
+            version
+        }
+        Err(message) => return unsupported(&versions, message),
+    };
+
+    request.extensions_mut().insert(version);
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&version.0.to_string()) {
+        headers.insert(API_VERSION_HEADER, value);
+    }
+    if versions.is_deprecated(version) {
+        headers.insert(DEPRECATION_HEADER, HeaderValue::from_static("true"));
+    }
+    response
+}
+
+fn rewrite_path(uri: &Uri, path: &str) -> Option<Uri> {
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path.to_string(),
+    };
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(path_and_query.parse().ok()?);
+    Uri::from_parts(parts).ok()
+}
+
+fn unsupported(versions: &ApiVersions, message: String) -> Response {
+    let supported: Vec<String> = versions.supported().iter().map(|v| v.to_string()).collect();
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({
+            "error": message,
+            "supported_versions": supported,
+        })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V2: ApiVersion = ApiVersion(2);
+
+    fn versions() -> ApiVersions {
+        ApiVersions::new(&[V2, ApiVersion::V1])
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!("v2".parse(), Ok(V2));
+        assert_eq!(" 1 ".parse(), Ok(ApiVersion::V1));
+        assert!("two".parse::<ApiVersion>().is_err());
+        assert_eq!(V2.prefix(), "/api/v2");
+    }
+
+    #[test]
+    fn test_versioned_paths_keep_their_version() {
+        let versions = versions();
+        assert_eq!(
+            versions.resolve("/api/v2/orders/1", None),
+            Some(Ok((V2, None)))
+        );
+        assert_eq!(
+            versions.resolve("/api/v1/orders/1", Some("1")),
+            Some(Ok((ApiVersion::V1, None)))
+        );
+        assert!(matches!(
+            versions.resolve("/api/v1/orders/1", Some("2")),
+            Some(Err(_))
+        ));
+        assert!(matches!(
+            versions.resolve("/api/v3/orders", None),
+            Some(Err(_))
+        ));
+        assert_eq!(versions.resolve("/health", Some("2")), None);
+    }
+
+    #[test]
+    fn test_unversioned_paths_are_negotiated() {
+        let versions = versions();
+        assert_eq!(versions.default_version(), ApiVersion::V1);
+        assert_eq!(
+            versions.resolve("/api/orders/1/cancel", None),
+            Some(Ok((
+                ApiVersion::V1,
+                Some("/api/v1/orders/1/cancel".to_string())
+            )))
+        );
+        assert_eq!(
+            versions.resolve("/api/orders", Some("v2")),
+            Some(Ok((V2, Some("/api/v2/orders".to_string()))))
+        );
+
+        let versions = versions.with_default(V2).with_deprecated(ApiVersion::V1);
+        assert_eq!(
+            versions.resolve("/api/sagas", None),
+            Some(Ok((V2, Some("/api/v2/sagas".to_string()))))
+        );
+        assert!(versions.is_deprecated(ApiVersion::V1));
+    }
+
+    #[test]
+    fn test_rewrite_keeps_query() {
+        let uri: Uri = "/api/orders/stats?from=2024-01-01".parse().unwrap();
+        assert_eq!(
+            rewrite_path(&uri, "/api/v1/orders/stats").map(|uri| uri.to_string()),
+            Some("/api/v1/orders/stats?from=2024-01-01".to_string())
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use crate::metrics::{record_bulkhead_rejected, record_bulkhead_usage};
+
+/// Limits for one downstream dependency
+#[derive(Debug, Clone)]
+pub struct BulkheadConfig {
+    /// Calls allowed to run at the same time
+    pub max_concurrent: usize,
+    /// How long a call may wait for a free slot before it is rejected
+    pub max_wait: Duration,
+}
+
+impl Default for BulkheadConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 32,
+            max_wait: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Semaphore-based bulkhead capping concurrent calls to a dependency
+///
+/// Where the circuit breaker reacts to a dependency failing, the bulkhead keeps a
+/// slow one from tying up every task: once `max_concurrent` calls are in flight,
+/// further calls wait up to `max_wait` for a slot and are then rejected.
+pub struct Bulkhead {
+    name: String,
+    config: BulkheadConfig,
+    semaphore: Semaphore,
+}
+
+impl Bulkhead {
+    pub fn new(name: String, config: BulkheadConfig) -> Self {
+        let max_concurrent = config.max_concurrent.max(1);
+        record_bulkhead_usage(&name, 0, max_concurrent);
+
+        Self {
+            name,
+            config: BulkheadConfig {
+                max_concurrent,
+                ..config
+            },
+            semaphore: Semaphore::new(max_concurrent),
+        }
+    }
+
+    /// Run `f` once a slot is free, or fail if none frees up in time
+    pub async fn call<F, T>(&self, f: F) -> Result<T, BulkheadFull>
+    where
+        F: Future<Output = T>,
+    {
+        let _permit = self.acquire().await?;
+        Ok(f.await)
+    }
+
+    /// Calls currently running
+    pub fn in_flight(&self) -> usize {
+        self.config.max_concurrent - self.semaphore.available_permits()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn acquire(&self) -> Result<BulkheadPermit<'_>, BulkheadFull> {
+        match tokio::time::timeout(self.config.max_wait, self.semaphore.acquire()).await {
+            Ok(Ok(permit)) => {
+                self.report_usage();
+                Ok(BulkheadPermit {
+                    bulkhead: self,
+                    permit: Some(permit),
+                })
+            }
+            _ => {
+                record_bulkhead_rejected(&self.name);
+                tracing::warn!(
+                    dependency = %self.name,
+                    max_concurrent = self.config.max_concurrent,
+                    max_wait_ms = %self.config.max_wait.as_millis(),
+                    "Bulkhead full, rejecting call"
+                );
+                Err(BulkheadFull {
+                    name: self.name.clone(),
+                    max_concurrent: self.config.max_concurrent,
+                })
+            }
+        }
+    }
+
+    fn report_usage(&self) {
+        record_bulkhead_usage(&self.name, self.in_flight(), self.config.max_concurrent);
+    }
+}
+
+/// Slot held for the duration of a call; frees it and updates the gauge on drop
+struct BulkheadPermit<'a> {
+    bulkhead: &'a Bulkhead,
+    permit: Option<SemaphorePermit<'a>>,
+}
+
+impl Drop for BulkheadPermit<'_> {
+    fn drop(&mut self) {
+        drop(self.permit.take());
+        self.bulkhead.report_usage();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Bulkhead '{name}' is full ({max_concurrent} concurrent calls)")]
+pub struct BulkheadFull {
+    pub name: String,
+    pub max_concurrent: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn bulkhead(max_concurrent: usize, max_wait: Duration) -> Arc<Bulkhead> {
+        Arc::new(Bulkhead::new(
+            "test-dependency".to_string(),
+            BulkheadConfig {
+                max_concurrent,
+                max_wait,
+            },
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_bulkhead_runs_call() {
+        let bulkhead = bulkhead(1, Duration::from_millis(10));
+
+        let result = bulkhead.call(async { 42 }).await;
+        assert_eq!(result, Ok(42));
+        assert_eq!(bulkhead.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_bulkhead_rejects_when_full() {
+        let bulkhead = bulkhead(1, Duration::from_millis(20));
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+
+        let holder = bulkhead.clone();
+        let running = tokio::spawn(async move {
+            holder
+                .call(async {
+                    let _ = released.await;
+                })
+                .await
+        });
+        while bulkhead.in_flight() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let rejected = bulkhead.call(async { 1 }).await;
+        assert_eq!(
+            rejected,
+            Err(BulkheadFull {
+                name: "test-dependency".to_string(),
+                max_concurrent: 1,
+            })
+        );
+
+        release.send(()).unwrap();
+        assert!(running.await.unwrap().is_ok());
+        assert_eq!(bulkhead.call(async { 2 }).await, Ok(2));
+    }
+
+    #[tokio::test]
+    async fn test_bulkhead_waits_for_free_slot() {
+        let bulkhead = bulkhead(1, Duration::from_secs(5));
+
+        let holder = bulkhead.clone();
+        let running = tokio::spawn(async move {
+            holder
+                .call(tokio::time::sleep(Duration::from_millis(20)))
+                .await
+        });
+        while bulkhead.in_flight() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        assert_eq!(bulkhead.call(async { 3 }).await, Ok(3));
+        assert!(running.await.unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_bulkhead_config_default() {
+        let config = BulkheadConfig::default();
+        assert_eq!(config.max_concurrent, 32);
+        assert_eq!(config.max_wait, Duration::from_secs(1));
+    }
+}
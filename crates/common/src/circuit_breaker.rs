@@ -1,12 +1,22 @@
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Mutex, RwLock};
 use async_trait::async_trait;
 use crate::metrics::{record_circuit_breaker_state, record_circuit_breaker_transition, CircuitBreakerState as MetricsState};
 
+/// Seconds since the Unix epoch, or 0 if the system clock is set before it
+fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
 /// Circuit breaker states
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CircuitBreakerState {
     Closed,
     Open,
@@ -19,8 +29,22 @@ pub struct CircuitBreaker {
     config: CircuitBreakerConfig,
     failure_count: Arc<AtomicU32>,
     success_count: Arc<AtomicU32>,
+    half_open_probes: Arc<AtomicU32>,
     last_failure_time: Arc<AtomicU64>,
     state: Arc<RwLock<CircuitBreakerState>>,
+    window: Arc<Mutex<CallWindow>>,
+}
+
+/// Which recent calls decide whether a closed breaker opens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SlidingWindow {
+    /// Open after `failure_threshold` failures in a row
+    #[default]
+    ConsecutiveFailures,
+    /// Rates over the last `size` calls
+    CountBased { size: usize },
+    /// Rates over the calls that finished in the last `duration`
+    TimeBased { duration: Duration },
 }
 
 #[derive(Debug, Clone)]
@@ -29,6 +53,17 @@ pub struct CircuitBreakerConfig {
     pub success_threshold: u32,
     pub timeout: Duration,
     pub half_open_timeout: Duration,
+    /// Calls let through at once while half-open; the rest fail as if the breaker were open
+    pub permitted_calls_in_half_open: u32,
+    pub sliding_window: SlidingWindow,
+    /// Calls the window must hold before its rates can open the breaker
+    pub minimum_calls: usize,
+    /// Percentage of failed calls (timeouts included) in the window that opens the breaker
+    pub failure_rate_threshold: f64,
+    /// Calls taking longer than this count as slow, whether or not they succeed
+    pub slow_call_duration_threshold: Duration,
+    /// Percentage of slow calls in the window that opens the breaker
+    pub slow_call_rate_threshold: f64,
 }
 
 impl Default for CircuitBreakerConfig {
@@ -38,10 +73,80 @@ impl Default for CircuitBreakerConfig {
             success_threshold: 2,
             timeout: Duration::from_secs(60),
             half_open_timeout: Duration::from_secs(30),
+            permitted_calls_in_half_open: 2,
+            sliding_window: SlidingWindow::ConsecutiveFailures,
+            minimum_calls: 100,
+            failure_rate_threshold: 50.0,
+            slow_call_duration_threshold: Duration::from_secs(60),
+            slow_call_rate_threshold: 100.0,
         }
     }
 }
 
+/// What [`CircuitBreaker::snapshot`] reports about a breaker
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct CircuitBreakerSnapshot {
+    pub name: String,
+    pub state: CircuitBreakerState,
+    pub consecutive_failures: u32,
+    /// Half-open probe calls still running
+    pub half_open_probes: u32,
+}
+
+/// A half-open probe in flight, given back when the call finishes or is dropped
+struct ProbePermit(Arc<AtomicU32>);
+
+impl Drop for ProbePermit {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Outcomes of the calls in a closed breaker's sliding window
+#[derive(Debug, Default)]
+struct CallWindow {
+    /// When each call finished, whether it failed and whether it was slow
+    calls: VecDeque<(Instant, bool, bool)>,
+}
+
+impl CallWindow {
+    /// Record a call and drop the ones that fell out of the window
+    fn record(&mut self, window: SlidingWindow, failed: bool, slow: bool) {
+        let now = Instant::now();
+        self.calls.push_back((now, failed, slow));
+        match window {
+            SlidingWindow::ConsecutiveFailures => {}
+            SlidingWindow::CountBased { size } => {
+                while self.calls.len() > size {
+                    self.calls.pop_front();
+                }
+            }
+            SlidingWindow::TimeBased { duration } => {
+                while let Some((at, _, _)) = self.calls.front() {
+                    if now.duration_since(*at) <= duration {
+                        break;
+                    }
+                    self.calls.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Failure and slow-call rates in percent, once the window holds `minimum_calls`
+    fn rates(&self, minimum_calls: usize) -> Option<(f64, f64)> {
+        let total = self.calls.len();
+        if total == 0 || total < minimum_calls {
+            return None;
+        }
+        let failed = self.calls.iter().filter(|(_, failed, _)| *failed).count();
+        let slow = self.calls.iter().filter(|(_, _, slow)| *slow).count();
+        Some((
+            failed as f64 * 100.0 / total as f64,
+            slow as f64 * 100.0 / total as f64,
+        ))
+    }
+}
+
 impl CircuitBreaker {
     pub fn new(name: String, config: CircuitBreakerConfig) -> Self {
         // Initialize metrics
@@ -52,8 +157,28 @@ impl CircuitBreaker {
             config,
             failure_count: Arc::new(AtomicU32::new(0)),
             success_count: Arc::new(AtomicU32::new(0)),
+            half_open_probes: Arc::new(AtomicU32::new(0)),
             last_failure_time: Arc::new(AtomicU64::new(0)),
             state: Arc::new(RwLock::new(CircuitBreakerState::Closed)),
+            window: Arc::new(Mutex::new(CallWindow::default())),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Current state and counters, for health endpoints
+    pub async fn snapshot(&self) -> CircuitBreakerSnapshot {
+        CircuitBreakerSnapshot {
+            name: self.name.clone(),
+            state: self.get_state().await,
+            consecutive_failures: self.failure_count.load(Ordering::Relaxed),
+            half_open_probes: self.half_open_probes.load(Ordering::Acquire),
         }
     }
 
@@ -62,18 +187,20 @@ impl CircuitBreaker {
     where
         F: std::future::Future<Output = Result<T, E>>,
     {
-        // Check if we should attempt the call
-        if !self.check_state().await {
+        // Check if we should attempt the call; a half-open probe holds its
+        // permit until the call is over
+        let Some(_probe) = self.check_state().await else {
             return Err(CircuitBreakerError::Open);
-        }
+        };
 
         // Execute the function with timeout
-        let start = std::time::Instant::now();
+        let start = Instant::now();
         let result = tokio::time::timeout(self.config.timeout, f).await;
+        let slow = start.elapsed() > self.config.slow_call_duration_threshold;
 
         match result {
             Ok(Ok(value)) => {
-                self.on_success().await;
+                self.on_success(slow).await;
                 tracing::debug!(
                     service = %self.name,
                     duration_ms = %start.elapsed().as_millis(),
@@ -82,7 +209,7 @@ impl CircuitBreaker {
                 Ok(value)
             }
             Ok(Err(err)) => {
-                self.on_failure().await;
+                self.on_failure(slow).await;
                 tracing::warn!(
                     service = %self.name,
                     duration_ms = %start.elapsed().as_millis(),
@@ -91,7 +218,7 @@ impl CircuitBreaker {
                 Err(CircuitBreakerError::CallFailed(err))
             }
             Err(_) => {
-                self.on_failure().await;
+                self.on_failure(slow).await;
                 tracing::error!(
                     service = %self.name,
                     timeout_secs = %self.config.timeout.as_secs(),
@@ -103,49 +230,71 @@ impl CircuitBreaker {
     }
 
     /// Check current state and transition if needed
-    /// Returns true if the call should proceed, false if circuit is open
-    async fn check_state(&self) -> bool {
+    /// Returns `None` if the call must not proceed, and a probe permit if it runs while half-open
+    async fn check_state(&self) -> Option<Option<ProbePermit>> {
         let mut state = self.state.write().await;
 
         match *state {
             CircuitBreakerState::Closed => {
                 // Normal operation
-                true
+                Some(None)
             }
             CircuitBreakerState::Open => {
                 // Check if we should transition to half-open
-                let now = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
                 let last_failure = self.last_failure_time.load(Ordering::Relaxed);
 
-                if now - last_failure > self.config.half_open_timeout.as_secs() {
+                if unix_secs().saturating_sub(last_failure) > self.config.half_open_timeout.as_secs() {
                     *state = CircuitBreakerState::HalfOpen;
                     self.success_count.store(0, Ordering::Relaxed);
                     record_circuit_breaker_transition(&self.name, MetricsState::Open, MetricsState::HalfOpen);
                     record_circuit_breaker_state(&self.name, MetricsState::HalfOpen);
                     tracing::info!(service = %self.name, "Circuit breaker transitioned to HalfOpen");
-                    true
+                    self.probe_permit().map(Some)
                 } else {
-                    false
+                    None
                 }
             }
             CircuitBreakerState::HalfOpen => {
                 // Allow limited requests
-                true
+                let permit = self.probe_permit();
+                if permit.is_none() {
+                    tracing::debug!(service = %self.name, "Circuit breaker half-open probe limit reached");
+                }
+                permit.map(Some)
             }
         }
     }
 
+    /// Take one of the half-open probe slots, if any is free
+    ///
+    /// Probes still running from an earlier half-open period keep their slots.
+    fn probe_permit(&self) -> Option<ProbePermit> {
+        let permitted = self.config.permitted_calls_in_half_open;
+        self.half_open_probes
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |probes| {
+                (probes < permitted).then_some(probes + 1)
+            })
+            .ok()
+            .map(|_| ProbePermit(self.half_open_probes.clone()))
+    }
+
     /// Handle successful call
-    async fn on_success(&self) {
+    async fn on_success(&self, slow: bool) {
         let mut state = self.state.write().await;
 
         match *state {
             CircuitBreakerState::Closed => {
                 // Reset failure count on success
                 self.failure_count.store(0, Ordering::Relaxed);
+
+                // Successful calls can still open the breaker by being slow
+                if self.window_exceeded(false, slow).await {
+                    *state = CircuitBreakerState::Open;
+                    self.last_failure_time.store(unix_secs(), Ordering::Relaxed);
+                    record_circuit_breaker_transition(&self.name, MetricsState::Closed, MetricsState::Open);
+                    record_circuit_breaker_state(&self.name, MetricsState::Open);
+                    tracing::warn!(service = %self.name, "Circuit breaker opened on slow calls");
+                }
             }
             CircuitBreakerState::HalfOpen => {
                 let success_count = self.success_count.fetch_add(1, Ordering::Relaxed) + 1;
@@ -165,19 +314,19 @@ impl CircuitBreaker {
     }
 
     /// Handle failed call
-    async fn on_failure(&self) {
+    async fn on_failure(&self, slow: bool) {
         let mut state = self.state.write().await;
         let failure_count = self.failure_count.fetch_add(1, Ordering::Relaxed) + 1;
 
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        self.last_failure_time.store(now, Ordering::Relaxed);
+        self.last_failure_time.store(unix_secs(), Ordering::Relaxed);
 
         match *state {
             CircuitBreakerState::Closed => {
-                if failure_count >= self.config.failure_threshold {
+                let exceeded = match self.config.sliding_window {
+                    SlidingWindow::ConsecutiveFailures => failure_count >= self.config.failure_threshold,
+                    _ => self.window_exceeded(true, slow).await,
+                };
+                if exceeded {
                     *state = CircuitBreakerState::Open;
                     record_circuit_breaker_transition(&self.name, MetricsState::Closed, MetricsState::Open);
                     record_circuit_breaker_state(&self.name, MetricsState::Open);
@@ -198,6 +347,36 @@ impl CircuitBreaker {
         }
     }
 
+    /// Record a call made while closed; true if the window's rates now open the breaker
+    ///
+    /// The window starts over when it opens the breaker.
+    async fn window_exceeded(&self, failed: bool, slow: bool) -> bool {
+        if self.config.sliding_window == SlidingWindow::ConsecutiveFailures {
+            return false;
+        }
+
+        let mut window = self.window.lock().await;
+        window.record(self.config.sliding_window, failed, slow);
+        let Some((failure_rate, slow_call_rate)) = window.rates(self.config.minimum_calls) else {
+            return false;
+        };
+        if failure_rate < self.config.failure_rate_threshold
+            && slow_call_rate < self.config.slow_call_rate_threshold
+        {
+            return false;
+        }
+
+        tracing::warn!(
+            service = %self.name,
+            failure_rate = %failure_rate,
+            slow_call_rate = %slow_call_rate,
+            calls = %window.calls.len(),
+            "Circuit breaker window exceeded its thresholds"
+        );
+        window.calls.clear();
+        true
+    }
+
     /// Get current state (for testing/monitoring)
     pub async fn get_state(&self) -> CircuitBreakerState {
         *self.state.read().await
@@ -210,6 +389,7 @@ impl CircuitBreaker {
         self.failure_count.store(0, Ordering::Relaxed);
         self.success_count.store(0, Ordering::Relaxed);
         self.last_failure_time.store(0, Ordering::Relaxed);
+        self.window.lock().await.calls.clear();
         record_circuit_breaker_state(&self.name, MetricsState::Closed);
     }
 }
@@ -330,6 +510,151 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_count_window_opens_on_failure_rate() {
+        let cb = CircuitBreaker::new(
+            "test-service".to_string(),
+            CircuitBreakerConfig {
+                sliding_window: SlidingWindow::CountBased { size: 4 },
+                minimum_calls: 4,
+                failure_rate_threshold: 50.0,
+                ..Default::default()
+            },
+        );
+
+        // Alternating results never reach 5 consecutive failures, but half fail
+        let _ = cb.call(async { Ok::<_, TestError>(1) }).await;
+        let _ = cb.call(async { Err::<i32, _>(TestError) }).await;
+        let _ = cb.call(async { Ok::<_, TestError>(1) }).await;
+        assert_eq!(cb.get_state().await, CircuitBreakerState::Closed);
+
+        let _ = cb.call(async { Err::<i32, _>(TestError) }).await;
+        assert_eq!(cb.get_state().await, CircuitBreakerState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_window_needs_minimum_calls() {
+        let cb = CircuitBreaker::new(
+            "test-service".to_string(),
+            CircuitBreakerConfig {
+                sliding_window: SlidingWindow::TimeBased {
+                    duration: Duration::from_secs(10),
+                },
+                minimum_calls: 10,
+                failure_threshold: 1,
+                ..Default::default()
+            },
+        );
+
+        for _ in 0..9 {
+            let _ = cb.call(async { Err::<i32, _>(TestError) }).await;
+        }
+        assert_eq!(cb.get_state().await, CircuitBreakerState::Closed);
+
+        let _ = cb.call(async { Err::<i32, _>(TestError) }).await;
+        assert_eq!(cb.get_state().await, CircuitBreakerState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_slow_successful_calls_open_the_breaker() {
+        let cb = CircuitBreaker::new(
+            "test-service".to_string(),
+            CircuitBreakerConfig {
+                sliding_window: SlidingWindow::CountBased { size: 2 },
+                minimum_calls: 2,
+                slow_call_duration_threshold: Duration::from_millis(10),
+                slow_call_rate_threshold: 100.0,
+                ..Default::default()
+            },
+        );
+        let slow = || async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok::<_, TestError>(1)
+        };
+
+        assert!(cb.call(slow()).await.is_ok());
+        let _ = cb.call(async { Ok::<_, TestError>(1) }).await;
+        assert_eq!(cb.get_state().await, CircuitBreakerState::Closed);
+
+        assert!(cb.call(slow()).await.is_ok());
+        assert!(cb.call(slow()).await.is_ok());
+        assert_eq!(cb.get_state().await, CircuitBreakerState::Open);
+    }
+
+    #[test]
+    fn test_time_window_drops_old_calls() {
+        let mut window = CallWindow::default();
+        let sliding_window = SlidingWindow::TimeBased {
+            duration: Duration::from_millis(50),
+        };
+        window.record(sliding_window, true, false);
+        std::thread::sleep(Duration::from_millis(60));
+        window.record(sliding_window, false, false);
+
+        assert_eq!(window.rates(1), Some((0.0, 0.0)));
+        assert_eq!(window.rates(2), None);
+    }
+
+    async fn half_open(config: CircuitBreakerConfig) -> Arc<CircuitBreaker> {
+        let cb = CircuitBreaker::new("test-service".to_string(), config);
+        *cb.state.write().await = CircuitBreakerState::HalfOpen;
+        Arc::new(cb)
+    }
+
+    #[tokio::test]
+    async fn test_half_open_limits_concurrent_probes() {
+        let cb = half_open(CircuitBreakerConfig {
+            permitted_calls_in_half_open: 2,
+            ..Default::default()
+        })
+        .await;
+
+        let mut callers = tokio::task::JoinSet::new();
+        for _ in 0..5 {
+            let cb = cb.clone();
+            callers.spawn(async move {
+                cb.call(async {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    Ok::<_, TestError>(1)
+                })
+                .await
+            });
+        }
+
+        let (mut probes, mut rejected) = (0, 0);
+        while let Some(result) = callers.join_next().await {
+            match result.unwrap() {
+                Ok(_) => probes += 1,
+                Err(CircuitBreakerError::Open) => rejected += 1,
+                Err(e) => panic!("unexpected error: {}", e),
+            }
+        }
+        assert_eq!((probes, rejected), (2, 3));
+        assert_eq!(cb.get_state().await, CircuitBreakerState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_permit_released_when_call_is_dropped() {
+        let cb = half_open(CircuitBreakerConfig {
+            permitted_calls_in_half_open: 1,
+            ..Default::default()
+        })
+        .await;
+
+        let abandoned = tokio::time::timeout(
+            Duration::from_millis(10),
+            cb.call(async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Ok::<_, TestError>(1)
+            }),
+        )
+        .await;
+        assert!(abandoned.is_err());
+
+        assert_eq!(cb.call(async { Ok::<_, TestError>(2) }).await.unwrap(), 2);
+        assert_eq!(cb.half_open_probes.load(Ordering::Acquire), 0);
+    }
+
     #[tokio::test]
     async fn test_circuit_breaker_config_default() {
         let config = CircuitBreakerConfig::default();
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerSnapshot};
+
+/// Named circuit breakers, one per dependency, created on first use
+///
+/// Each dependency gets its own thresholds if configured with
+/// [`with_config`](Self::with_config) and the defaults otherwise. Looking a name up
+/// again returns the same breaker, so every caller of a dependency shares its state.
+pub struct CircuitBreakerRegistry {
+    defaults: CircuitBreakerConfig,
+    configs: HashMap<String, CircuitBreakerConfig>,
+    breakers: RwLock<HashMap<String, Arc<CircuitBreaker>>>,
+}
+
+impl Default for CircuitBreakerRegistry {
+    fn default() -> Self {
+        Self::new(CircuitBreakerConfig::default())
+    }
+}
+
+impl CircuitBreakerRegistry {
+    pub fn new(defaults: CircuitBreakerConfig) -> Self {
+        Self {
+            defaults,
+            configs: HashMap::new(),
+            breakers: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Use `config` for the named dependency instead of the defaults
+    pub fn with_config(mut self, name: impl Into<String>, config: CircuitBreakerConfig) -> Self {
+        self.configs.insert(name.into(), config);
+        self
+    }
+
+    /// Override the named dependencies' thresholds from the environment
+    ///
+    /// `kafka-publisher` reads `CIRCUIT_BREAKER_KAFKA_PUBLISHER_FAILURE_THRESHOLD`,
+    /// `_SUCCESS_THRESHOLD`, `_TIMEOUT_MS`, `_RESET_SECS` and `_HALF_OPEN_CALLS`;
+    /// unset or invalid values keep the configured ones.
+    pub fn with_env_overrides(mut self, names: &[&str]) -> Self {
+        for name in names {
+            let prefix = env_prefix(name);
+            let config = apply_overrides(self.config(name), |key| {
+                std::env::var(format!("{}_{}", prefix, key)).ok()
+            });
+            self.configs.insert(name.to_string(), config);
+        }
+        self
+    }
+
+    /// Thresholds the named dependency's breaker is created with
+    pub fn config(&self, name: &str) -> CircuitBreakerConfig {
+        self.configs.get(name).unwrap_or(&self.defaults).clone()
+    }
+
+    /// The named dependency's breaker, created from its config on first use
+    pub fn get(&self, name: &str) -> Arc<CircuitBreaker> {
+        if let Some(breaker) = self.breakers.read().unwrap().get(name) {
+            return breaker.clone();
+        }
+        self.breakers
+            .write()
+            .unwrap()
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(CircuitBreaker::new(name.to_string(), self.config(name))))
+            .clone()
+    }
+
+    /// State of every breaker created so far, by name
+    pub async fn snapshot(&self) -> Vec<CircuitBreakerSnapshot> {
+        let mut breakers: Vec<Arc<CircuitBreaker>> =
+            self.breakers.read().unwrap().values().cloned().collect();
+        breakers.sort_by(|a, b| a.name().cmp(b.name()));
+
+        let mut snapshots = Vec::with_capacity(breakers.len());
+        for breaker in breakers {
+            snapshots.push(breaker.snapshot().await);
+        }
+        snapshots
+    }
+}
+
+/// `kafka-publisher` -> `CIRCUIT_BREAKER_KAFKA_PUBLISHER`
+fn env_prefix(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("CIRCUIT_BREAKER_{}", name)
+}
+
+fn apply_overrides(
+    mut config: CircuitBreakerConfig,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> CircuitBreakerConfig {
+    fn parse<T: FromStr>(value: Option<String>) -> Option<T> {
+        value.and_then(|value| value.trim().parse().ok())
+    }
+
+    if let Some(threshold) = parse(lookup("FAILURE_THRESHOLD")) {
+        config.failure_threshold = threshold;
+    }
+    if let Some(threshold) = parse(lookup("SUCCESS_THRESHOLD")) {
+        config.success_threshold = threshold;
+    }
+    if let Some(millis) = parse(lookup("TIMEOUT_MS")) {
+        config.timeout = Duration::from_millis(millis);
+    }
+    if let Some(secs) = parse(lookup("RESET_SECS")) {
+        config.half_open_timeout = Duration::from_secs(secs);
+    }
+    if let Some(calls) = parse(lookup("HALF_OPEN_CALLS")) {
+        config.permitted_calls_in_half_open = calls;
+    }
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit_breaker::CircuitBreakerState;
+
+    #[tokio::test]
+    async fn test_breakers_are_shared_and_configured_per_dependency() {
+        let registry = CircuitBreakerRegistry::default().with_config(
+            "fraud-service",
+            CircuitBreakerConfig {
+                failure_threshold: 1,
+                ..Default::default()
+            },
+        );
+
+        let fraud = registry.get("fraud-service");
+        assert!(Arc::ptr_eq(&fraud, &registry.get("fraud-service")));
+        assert_eq!(fraud.config().failure_threshold, 1);
+        assert_eq!(
+            registry.get("kafka-publisher").config().failure_threshold,
+            5
+        );
+
+        let _ = fraud.call(async { Err::<(), _>("down") }).await;
+        let snapshot = registry.snapshot().await;
+        let states: Vec<(&str, CircuitBreakerState)> = snapshot
+            .iter()
+            .map(|breaker| (breaker.name.as_str(), breaker.state))
+            .collect();
+        assert_eq!(
+            states,
+            [
+                ("fraud-service", CircuitBreakerState::Open),
+                ("kafka-publisher", CircuitBreakerState::Closed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overrides_keep_unset_and_invalid_values() {
+        let env: HashMap<&str, &str> = HashMap::from([
+            ("FAILURE_THRESHOLD", "10"),
+            ("TIMEOUT_MS", "250"),
+            ("RESET_SECS", "soon"),
+        ]);
+        let config = apply_overrides(CircuitBreakerConfig::default(), |key| {
+            env.get(key).map(|value| value.to_string())
+        });
+
+        assert_eq!(config.failure_threshold, 10);
+        assert_eq!(config.timeout, Duration::from_millis(250));
+        assert_eq!(config.half_open_timeout, Duration::from_secs(30));
+        assert_eq!(config.success_threshold, 2);
+        assert_eq!(
+            env_prefix("kafka-publisher"),
+            "CIRCUIT_BREAKER_KAFKA_PUBLISHER"
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::future::Future;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::Instant;
+
+use crate::metrics::record_deadline_exceeded;
+
+/// Header a client sets to ask for a shorter deadline than the service's own
+pub const REQUEST_TIMEOUT_HEADER: &str = "x-request-timeout-ms";
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("deadline exceeded")]
+pub struct DeadlineExceeded;
+
+tokio::task_local! {
+    static CURRENT: Deadline;
+}
+
+/// When the work on behalf of a request has to be finished
+///
+/// A deadline set with [`scope`](Self::scope) is the current one for everything the
+/// scoped future awaits, so the event store, the publisher and retries below an HTTP
+/// handler all stop at the same instant instead of each running its own clock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Deadline {
+    expires_at: Option<Instant>,
+}
+
+impl Deadline {
+    /// No deadline: operations wait as long as they take
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn after(timeout: Duration) -> Self {
+        Self {
+            expires_at: Some(Instant::now() + timeout),
+        }
+    }
+
+    /// The deadline of the scope this task runs in, or none outside one
+    pub fn current() -> Self {
+        CURRENT.try_with(|deadline| *deadline).unwrap_or_default()
+    }
+
+    pub fn expires_at(&self) -> Option<Instant> {
+        self.expires_at
+    }
+
+    /// Time left, zero once passed; `None` without a deadline
+    pub fn remaining(&self) -> Option<Duration> {
+        self.expires_at
+            .map(|at| at.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining() == Some(Duration::ZERO)
+    }
+
+    /// Whichever of the two deadlines comes first
+    pub fn min(self, other: Deadline) -> Self {
+        let expires_at = match (self.expires_at, other.expires_at) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        Self { expires_at }
+    }
+
+    /// `timeout`, shortened to the time left before this deadline
+    pub fn cap(&self, timeout: Duration) -> Duration {
+        self.remaining().map_or(timeout, |left| left.min(timeout))
+    }
+
+    /// Run `future` with this deadline as the current one, cancelling it when it passes
+    ///
+    /// Inside another scope the earlier of the two deadlines applies.
+    pub async fn scope<F: Future>(self, future: F) -> Result<F::Output, DeadlineExceeded> {
+        let deadline = self.min(Deadline::current());
+        CURRENT.scope(deadline, deadline.run(future)).await
+    }
+
+    /// Run `future`, cancelling it when this deadline passes
+    pub async fn run<F: Future>(&self, future: F) -> Result<F::Output, DeadlineExceeded> {
+        match self.expires_at {
+            Some(at) => tokio::time::timeout_at(at, future)
+                .await
+                .map_err(|_| DeadlineExceeded),
+            None => Ok(future.await),
+        }
+    }
+}
+
+/// Run one step of a request, cancelling it when the current deadline passes
+///
+/// `operation` names the step in logs and metrics.
+pub async fn within<F: Future>(operation: &str, future: F) -> Result<F::Output, DeadlineExceeded> {
+    Deadline::current().run(future).await.inspect_err(|_| {
+        record_deadline_exceeded(operation);
+        tracing::warn!(operation = %operation, "Deadline exceeded");
+    })
+}
+
+/// Run an operation for at most `timeout`, and never past the current deadline
+pub async fn timeout<F: Future>(
+    operation: &str,
+    timeout: Duration,
+    future: F,
+) -> Result<F::Output, DeadlineExceeded> {
+    Deadline::after(timeout)
+        .scope(future)
+        .await
+        .inspect_err(|_| {
+            record_deadline_exceeded(operation);
+            tracing::warn!(
+                operation = %operation,
+                timeout_ms = timeout.as_millis() as u64,
+                "Deadline exceeded"
+            );
+        })
+}
+
+/// Give every request a deadline and answer 504 when it passes
+///
+/// The deadline is the state's timeout, or the [`REQUEST_TIMEOUT_HEADER`] value when a
+/// client asks for less. Work still running when it passes is dropped, which rolls back
+/// a transaction that has not started committing. A commit already under way is left
+/// to finish, so a 504 does not say whether the command took effect; clients retry
+/// with the same command ID.
+pub async fn propagate(State(timeout): State<Duration>, request: Request, next: Next) -> Response {
+    let timeout = request
+        .headers()
+        .get(REQUEST_TIMEOUT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map_or(timeout, |ms| timeout.min(Duration::from_millis(ms)));
+
+    match Deadline::after(timeout).scope(next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            record_deadline_exceeded("http");
+            (
+                StatusCode::GATEWAY_TIMEOUT,
+                Json(serde_json::json!({
+                    "error": format!("Request did not complete within {}ms", timeout.as_millis()),
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scope_sets_current_deadline() {
+        assert_eq!(Deadline::current(), Deadline::none());
+
+        let outer = Deadline::after(Duration::from_millis(100));
+        let inner = outer
+            .scope(async {
+                assert_eq!(Deadline::current(), outer);
+                // A nested scope can only shorten the deadline
+                Deadline::after(Duration::from_secs(60))
+                    .scope(async { Deadline::current() })
+                    .await
+            })
+            .await;
+        assert_eq!(inner, Ok(Ok(outer)));
+        assert!(outer.cap(Duration::from_secs(5)) <= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_operations_stop_at_the_current_deadline() {
+        let result = Deadline::after(Duration::from_millis(20))
+            .scope(async {
+                // Asks for a minute but only has what is left of the request
+                timeout("test.slow", Duration::from_secs(60), async {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                })
+                .await
+            })
+            .await;
+        assert_eq!(result, Ok(Err(DeadlineExceeded)));
+
+        let unbounded = within("test.unbounded", async { 7 }).await;
+        assert_eq!(unbounded, Ok(7));
+    }
+}
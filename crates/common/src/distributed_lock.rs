@@ -0,0 +1,193 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum LockError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+/// Work executed while a lock is held
+pub type LockedTask<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// Named lock shared across service replicas
+///
+/// Background singletons (sweepers, schedulers, relays) call `run_exclusive`
+/// on every tick; only the replica that wins the lock does the work, which
+/// acts as per-tick leader election.
+#[async_trait]
+pub trait DistributedLock: Send + Sync {
+    /// Run `task` only if the named lock is free, holding it until the task finishes
+    ///
+    /// Returns `false` without running the task when another holder has the lock.
+    async fn run_exclusive(&self, name: &str, task: LockedTask<'_>) -> Result<bool, LockError>;
+}
+
+/// Postgres advisory lock, scoped to a transaction so it is released even if
+/// the holder panics or the connection drops
+pub struct PgAdvisoryLock {
+    pool: PgPool,
+}
+
+impl PgAdvisoryLock {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl DistributedLock for PgAdvisoryLock {
+    async fn run_exclusive(&self, name: &str, task: LockedTask<'_>) -> Result<bool, LockError> {
+        let mut tx = self.pool.begin().await?;
+
+        let acquired: bool = sqlx::query_scalar("SELECT pg_try_advisory_xact_lock($1)")
+            .bind(lock_key(name))
+            .fetch_one(&mut *tx)
+            .await?;
+
+        if !acquired {
+            tracing::debug!(lock = %name, "Lock held elsewhere, skipping");
+            return Ok(false);
+        }
+
+        task.await;
+        tx.commit().await?;
+        Ok(true)
+    }
+}
+
+/// In-process lock for single-replica deployments and tests
+#[derive(Default)]
+pub struct LocalLock {
+    held: Mutex<HashSet<String>>,
+}
+
+impl LocalLock {
+    fn held(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+        self.held.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Releases a [`LocalLock`] name when dropped, so a task that panics or is
+/// cancelled mid-way does not keep the lock forever
+struct LocalLockGuard<'a> {
+    lock: &'a LocalLock,
+    name: &'a str,
+}
+
+impl Drop for LocalLockGuard<'_> {
+    fn drop(&mut self) {
+        self.lock.held().remove(self.name);
+    }
+}
+
+#[async_trait]
+impl DistributedLock for LocalLock {
+    async fn run_exclusive(&self, name: &str, task: LockedTask<'_>) -> Result<bool, LockError> {
+        if !self.held().insert(name.to_string()) {
+            return Ok(false);
+        }
+
+        let _guard = LocalLockGuard { lock: self, name };
+        task.await;
+        Ok(true)
+    }
+}
+
+/// Stable 64-bit advisory lock key for a lock name (FNV-1a)
+pub fn lock_key(name: &str) -> i64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in name.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::sync::oneshot;
+
+    #[test]
+    fn test_lock_key_is_stable() {
+        assert_eq!(lock_key("saga-recovery"), lock_key("saga-recovery"));
+        assert_ne!(lock_key("saga-recovery"), lock_key("outbox-relay"));
+        assert_eq!(lock_key(""), 0xcbf2_9ce4_8422_2325_u64 as i64);
+    }
+
+    #[tokio::test]
+    async fn test_local_lock_is_exclusive() {
+        let lock = Arc::new(LocalLock::default());
+        let (release_tx, release_rx) = oneshot::channel::<()>();
+        let (started_tx, started_rx) = oneshot::channel::<()>();
+
+        let holder = {
+            let lock = lock.clone();
+            tokio::spawn(async move {
+                lock.run_exclusive(
+                    "sweeper",
+                    Box::pin(async move {
+                        started_tx.send(()).unwrap();
+                        release_rx.await.unwrap();
+                    }),
+                )
+                .await
+                .unwrap()
+            })
+        };
+
+        started_rx.await.unwrap();
+        let ran = lock
+            .run_exclusive("sweeper", Box::pin(async {}))
+            .await
+            .unwrap();
+        assert!(!ran);
+        assert!(lock.run_exclusive("other", Box::pin(async {})).await.unwrap());
+
+        release_tx.send(()).unwrap();
+        assert!(holder.await.unwrap());
+        assert!(lock.run_exclusive("sweeper", Box::pin(async {})).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_local_lock_is_released_when_the_task_panics_or_is_cancelled() {
+        let lock = Arc::new(LocalLock::default());
+
+        let panicked = {
+            let lock = lock.clone();
+            tokio::spawn(async move {
+                lock.run_exclusive("sweeper", Box::pin(async { panic!("boom") }))
+                    .await
+            })
+        };
+        assert!(panicked.await.is_err());
+        assert!(lock.run_exclusive("sweeper", Box::pin(async {})).await.unwrap());
+
+        let (started_tx, started_rx) = oneshot::channel::<()>();
+        let cancelled = {
+            let lock = lock.clone();
+            tokio::spawn(async move {
+                lock.run_exclusive(
+                    "sweeper",
+                    Box::pin(async move {
+                        started_tx.send(()).unwrap();
+                        std::future::pending::<()>().await
+                    }),
+                )
+                .await
+            })
+        };
+        started_rx.await.unwrap();
+        assert!(!lock.run_exclusive("sweeper", Box::pin(async {})).await.unwrap());
+        cancelled.abort();
+        assert!(cancelled.await.unwrap_err().is_cancelled());
+        assert!(lock.run_exclusive("sweeper", Box::pin(async {})).await.unwrap());
+    }
+}
@@ -0,0 +1,46 @@
+use axum::{extract::MatchedPath, extract::Request, middleware::Next, response::Response};
+use std::time::Instant;
+
+use crate::metrics::{self, HTTP_REQUESTS_IN_FLIGHT};
+use crate::slo;
+
+/// Record request count, latency and in-flight requests per route and status
+///
+/// Each request also counts towards the route's SLOs (see [`crate::slo`]).
+///
+/// Routes are labelled by their template (`/api/v1/orders/:id`), so add this with
+/// `route_layer`, after every route, where the matched path is known:
+///
+/// ```ignore
+/// router.route_layer(axum::middleware::from_fn(common::http_metrics::track_http_metrics))
+/// ```
+pub async fn track_http_metrics(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let in_flight = HTTP_REQUESTS_IN_FLIGHT.with_label_values(&[&method, &route]);
+    in_flight.inc();
+    // Decrements even if the client goes away and the handler is dropped
+    let _guard = InFlightGuard(&in_flight);
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    let status = response.status().as_u16();
+    let elapsed = start.elapsed();
+    metrics::record_http_request(&method, &route, status, elapsed.as_secs_f64());
+    slo::record(&route, status, elapsed);
+
+    response
+}
+
+struct InFlightGuard<'a>(&'a prometheus::IntGauge);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
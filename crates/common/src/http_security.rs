@@ -0,0 +1,240 @@
+use axum::{
+    extract::Request,
+    http::{header, HeaderName, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::api_version::API_VERSION_HEADER;
+use crate::deadline::REQUEST_TIMEOUT_HEADER;
+use crate::request_log::{CORRELATION_ID_HEADER, USER_ID_HEADER};
+
+/// Which browser origins may call a service, and how
+///
+/// Without allowed origins no CORS headers are sent, so browsers keep refusing
+/// cross-origin calls, as before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsConfig {
+    /// Exact origins such as `https://admin.example.com`, or `*` for any
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<Method>,
+    pub allowed_headers: Vec<HeaderName>,
+    /// Response headers scripts may read
+    pub expose_headers: Vec<HeaderName>,
+    /// Send cookies and `Authorization`; only honoured with explicit origins
+    pub allow_credentials: bool,
+    /// How long browsers may cache a preflight response
+    pub max_age: Duration,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: vec![
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::PATCH,
+                Method::DELETE,
+            ],
+            allowed_headers: vec![
+                header::CONTENT_TYPE,
+                header::AUTHORIZATION,
+                HeaderName::from_static(CORRELATION_ID_HEADER),
+                HeaderName::from_static(USER_ID_HEADER),
+                HeaderName::from_static(REQUEST_TIMEOUT_HEADER),
+                HeaderName::from_static(API_VERSION_HEADER),
+            ],
+            expose_headers: vec![
+                HeaderName::from_static(CORRELATION_ID_HEADER),
+                HeaderName::from_static(API_VERSION_HEADER),
+                header::RETRY_AFTER,
+            ],
+            allow_credentials: false,
+            max_age: Duration::from_secs(600),
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Settings from `CORS_ALLOWED_ORIGINS`, `CORS_ALLOWED_METHODS`,
+    /// `CORS_ALLOWED_HEADERS` (all comma-separated), `CORS_ALLOW_CREDENTIALS` and
+    /// `CORS_MAX_AGE_SECS`, with the defaults for those unset
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        let list = |key: &str| -> Option<Vec<String>> {
+            lookup(key).map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+        };
+
+        Self {
+            allowed_origins: list("CORS_ALLOWED_ORIGINS").unwrap_or_default(),
+            allowed_methods: list("CORS_ALLOWED_METHODS")
+                .map(|methods| {
+                    methods
+                        .iter()
+                        .filter_map(|method| method.to_ascii_uppercase().parse().ok())
+                        .collect()
+                })
+                .unwrap_or(defaults.allowed_methods),
+            allowed_headers: list("CORS_ALLOWED_HEADERS")
+                .map(|headers| headers.iter().filter_map(|h| h.parse().ok()).collect())
+                .unwrap_or(defaults.allowed_headers),
+            expose_headers: defaults.expose_headers,
+            allow_credentials: lookup("CORS_ALLOW_CREDENTIALS")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(false),
+            max_age: lookup("CORS_MAX_AGE_SECS")
+                .and_then(|value| value.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.max_age),
+        }
+    }
+
+    /// Also let scripts read this response header
+    pub fn with_exposed_header(mut self, name: &'static str) -> Self {
+        self.expose_headers.push(HeaderName::from_static(name));
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.allowed_origins.is_empty()
+    }
+
+    fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|origin| origin == "*")
+    }
+
+    /// Layer answering preflight requests and adding CORS headers to responses
+    pub fn layer(&self) -> CorsLayer {
+        if !self.is_enabled() {
+            return CorsLayer::new();
+        }
+
+        let origins = if self.allows_any_origin() {
+            AllowOrigin::any()
+        } else {
+            AllowOrigin::list(
+                self.allowed_origins
+                    .iter()
+                    .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+            )
+        };
+        let layer = CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(self.allowed_methods.clone())
+            .allow_headers(self.allowed_headers.clone())
+            .expose_headers(self.expose_headers.clone())
+            .max_age(self.max_age);
+
+        // Browsers reject credentials with a wildcard origin, and tower-http panics on it
+        if self.allow_credentials && !self.allows_any_origin() {
+            layer.allow_credentials(true)
+        } else {
+            if self.allow_credentials {
+                tracing::warn!("CORS_ALLOW_CREDENTIALS ignored with a wildcard origin");
+            }
+            layer
+        }
+    }
+}
+
+/// Headers added to every response unless the handler set them itself
+const SECURITY_HEADERS: [(HeaderName, &str); 5] = [
+    (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+    (header::X_FRAME_OPTIONS, "DENY"),
+    (header::REFERRER_POLICY, "no-referrer"),
+    (
+        header::CONTENT_SECURITY_POLICY,
+        "default-src 'none'; frame-ancestors 'none'",
+    ),
+    (
+        header::STRICT_TRANSPORT_SECURITY,
+        "max-age=31536000; includeSubDomains",
+    ),
+];
+
+/// Add standard security headers to every response
+///
+/// The APIs only return JSON and text, so the content security policy forbids
+/// loading anything and the responses may not be framed or sniffed as another type.
+pub async fn security_headers(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    for (name, value) in SECURITY_HEADERS {
+        headers
+            .entry(name)
+            .or_insert(HeaderValue::from_static(value));
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(vars: &[(&str, &str)]) -> CorsConfig {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        CorsConfig::from_lookup(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn test_cors_is_off_without_origins() {
+        let config = config(&[]);
+        assert!(!config.is_enabled());
+        assert_eq!(config, CorsConfig::default());
+    }
+
+    #[test]
+    fn test_cors_from_env() {
+        let config = config(&[
+            (
+                "CORS_ALLOWED_ORIGINS",
+                "https://admin.example.com, http://localhost:3000",
+            ),
+            ("CORS_ALLOWED_METHODS", "get,post"),
+            ("CORS_ALLOW_CREDENTIALS", "true"),
+            ("CORS_MAX_AGE_SECS", "60"),
+        ]);
+        assert_eq!(
+            config.allowed_origins,
+            vec!["https://admin.example.com", "http://localhost:3000"]
+        );
+        assert_eq!(config.allowed_methods, vec![Method::GET, Method::POST]);
+        assert!(config.allow_credentials);
+        assert_eq!(config.max_age, Duration::from_secs(60));
+        assert_eq!(
+            config.allowed_headers,
+            CorsConfig::default().allowed_headers
+        );
+    }
+
+    #[test]
+    fn test_wildcard_origin_with_credentials_builds() {
+        // tower-http panics when credentials are combined with a wildcard origin
+        let config = config(&[
+            ("CORS_ALLOWED_ORIGINS", "*"),
+            ("CORS_ALLOW_CREDENTIALS", "true"),
+        ]);
+        let _ = axum::Router::<()>::new()
+            .route("/", axum::routing::get(|| async {}))
+            .layer(config.layer());
+    }
+}
@@ -1,5 +1,22 @@
+pub mod admin_auth;
+pub mod api_version;
+pub mod bulkhead;
 pub mod circuit_breaker;
+pub mod circuit_breaker_registry;
 pub mod config;
+pub mod deadline;
+#[cfg(feature = "postgres")]
+pub mod distributed_lock;
 pub mod errors;
+pub mod http_metrics;
+pub mod http_security;
 pub mod metrics;
+pub mod preflight;
+pub mod redaction;
+#[cfg(feature = "redis")]
+pub mod redis_connection;
+pub mod request_log;
+pub mod retry;
+pub mod slo;
+#[cfg(feature = "telemetry")]
 pub mod telemetry;
@@ -1,7 +1,8 @@
 use lazy_static::lazy_static;
 use prometheus::{
-    register_counter_vec, register_histogram_vec, register_int_gauge_vec, CounterVec,
-    Encoder, HistogramVec, IntGaugeVec, TextEncoder,
+    register_counter_vec, register_gauge, register_gauge_vec, register_histogram_vec,
+    register_int_gauge, register_int_gauge_vec, CounterVec, Encoder, Gauge, GaugeVec,
+    HistogramVec, IntGauge, IntGaugeVec, TextEncoder,
 };
 
 lazy_static! {
@@ -21,6 +22,51 @@ lazy_static! {
     )
     .expect("metric cannot be created");
 
+    // HTTP metrics
+    pub static ref HTTP_REQUESTS: CounterVec = register_counter_vec!(
+        "cqrs_http_requests_total",
+        "Total number of HTTP requests handled",
+        &["method", "route", "status"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref HTTP_REQUEST_DURATION: HistogramVec = register_histogram_vec!(
+        "cqrs_http_request_duration_seconds",
+        "HTTP request duration in seconds",
+        &["method", "route", "status"],
+        vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref HTTP_REQUESTS_IN_FLIGHT: IntGaugeVec = register_int_gauge_vec!(
+        "cqrs_http_requests_in_flight",
+        "Number of HTTP requests currently being handled",
+        &["method", "route"]
+    )
+    .expect("metric cannot be created");
+
+    // SLO metrics, derived by `slo::record` from the HTTP requests above
+    pub static ref SLO_OBJECTIVE: GaugeVec = register_gauge_vec!(
+        "cqrs_slo_objective_ratio",
+        "Target fraction of good requests per route and SLO (availability or latency)",
+        &["route", "slo"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref SLO_RATIO: GaugeVec = register_gauge_vec!(
+        "cqrs_slo_ratio",
+        "Fraction of good requests per route and SLO over a trailing window",
+        &["route", "slo", "window"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref SLO_BURN_RATE: GaugeVec = register_gauge_vec!(
+        "cqrs_slo_burn_rate",
+        "Rate the error budget is spent at per route and SLO over a trailing window (1 = exactly on budget)",
+        &["route", "slo", "window"]
+    )
+    .expect("metric cannot be created");
+
     // Event metrics
     pub static ref EVENT_COUNTER: CounterVec = register_counter_vec!(
         "cqrs_events_total",
@@ -53,6 +99,26 @@ lazy_static! {
     )
     .expect("metric cannot be created");
 
+    pub static ref QUERY_HEDGES: CounterVec = register_counter_vec!(
+        "cqrs_query_hedges_total",
+        "Total number of slow reads that were hedged, by which read answered",
+        &["query_type", "outcome"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref READ_REPLICA_LAG: Gauge = register_gauge!(
+        "cqrs_read_replica_lag_seconds",
+        "Replication lag of the read replica at its last check"
+    )
+    .expect("metric cannot be created");
+
+    pub static ref READ_ROUTING: CounterVec = register_counter_vec!(
+        "cqrs_read_routing_total",
+        "Total number of read model queries by the database they were sent to",
+        &["target"]
+    )
+    .expect("metric cannot be created");
+
     // Saga metrics
     pub static ref SAGA_COUNTER: CounterVec = register_counter_vec!(
         "cqrs_sagas_total",
@@ -76,6 +142,34 @@ lazy_static! {
     )
     .expect("metric cannot be created");
 
+    pub static ref SAGA_STEP_COUNTER: CounterVec = register_counter_vec!(
+        "cqrs_saga_steps_total",
+        "Total number of saga step attempts by outcome",
+        &["saga_type", "step", "action", "outcome"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref SAGA_STEP_DURATION: HistogramVec = register_histogram_vec!(
+        "cqrs_saga_step_duration_seconds",
+        "Saga step attempt duration in seconds",
+        &["saga_type", "step", "action"],
+        vec![0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref SAGA_STEPS_IN_FLIGHT: IntGaugeVec = register_int_gauge_vec!(
+        "cqrs_saga_steps_in_flight",
+        "Number of saga step attempts currently running",
+        &["saga_type", "step", "action"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref SAGAS_REQUIRING_INTERVENTION: IntGauge = register_int_gauge!(
+        "cqrs_sagas_requiring_intervention",
+        "Number of sagas whose compensation failed and need manual intervention"
+    )
+    .expect("metric cannot be created");
+
     // Cache metrics
     pub static ref CACHE_HIT_COUNTER: CounterVec = register_counter_vec!(
         "cqrs_cache_requests_total",
@@ -99,6 +193,28 @@ lazy_static! {
     )
     .expect("metric cannot be created");
 
+    // Bulkhead metrics
+    pub static ref BULKHEAD_IN_FLIGHT: IntGaugeVec = register_int_gauge_vec!(
+        "cqrs_bulkhead_in_flight",
+        "Number of calls currently holding a bulkhead permit",
+        &["dependency"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref BULKHEAD_LIMIT: IntGaugeVec = register_int_gauge_vec!(
+        "cqrs_bulkhead_limit",
+        "Maximum number of concurrent calls allowed by a bulkhead",
+        &["dependency"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref BULKHEAD_REJECTED: CounterVec = register_counter_vec!(
+        "cqrs_bulkhead_rejected_total",
+        "Total number of calls rejected because a bulkhead stayed full",
+        &["dependency"]
+    )
+    .expect("metric cannot be created");
+
     // Event store metrics
     pub static ref EVENT_STORE_OPERATIONS: CounterVec = register_counter_vec!(
         "cqrs_event_store_operations_total",
@@ -115,6 +231,28 @@ lazy_static! {
     )
     .expect("metric cannot be created");
 
+    pub static ref EVENT_STORE_APPEND_DURATION: HistogramVec = register_histogram_vec!(
+        "cqrs_event_store_append_duration_seconds",
+        "Event store append duration in seconds per aggregate type",
+        &["aggregate_type"],
+        vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref EVENT_STORE_APPENDED_EVENTS: CounterVec = register_counter_vec!(
+        "cqrs_event_store_appended_events_total",
+        "Total number of events appended per aggregate type",
+        &["aggregate_type"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref EVENT_STORE_SLOW_APPENDS: CounterVec = register_counter_vec!(
+        "cqrs_event_store_slow_appends_total",
+        "Total number of appends exceeding the slow append threshold",
+        &["aggregate_type"]
+    )
+    .expect("metric cannot be created");
+
     // Projection lag metrics
     pub static ref PROJECTION_LAG: HistogramVec = register_histogram_vec!(
         "cqrs_projection_lag_seconds",
@@ -124,6 +262,114 @@ lazy_static! {
     )
     .expect("metric cannot be created");
 
+    // Projection consistency metrics
+    pub static ref PROJECTION_CONSISTENCY_CHECKED: CounterVec = register_counter_vec!(
+        "cqrs_projection_consistency_checked_total",
+        "Total number of aggregates compared against their projected view",
+        &["projection"]
+    )
+    .expect("metric cannot be created");
+
+    // Event time vs processing time: steady drift with a current watermark means late
+    // events, a watermark falling behind the clock means slow processing
+    pub static ref PROJECTION_DRIFT: HistogramVec = register_histogram_vec!(
+        "cqrs_projection_drift_seconds",
+        "Time from an event being stored to a projection applying it",
+        &["projection"],
+        vec![0.1, 0.5, 1.0, 5.0, 30.0, 60.0, 300.0, 900.0, 3600.0]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref PROJECTION_EVENT_TIME: GaugeVec = register_gauge_vec!(
+        "cqrs_projection_event_time_seconds",
+        "Creation time (Unix seconds) of the newest event a projection has applied",
+        &["projection"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref PROJECTION_DIVERGENCES: CounterVec = register_counter_vec!(
+        "cqrs_projection_divergences_total",
+        "Total number of divergent fields found by the projection consistency checker",
+        &["projection", "field"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref PROJECTION_BUFFERED_EVENTS: IntGaugeVec = register_int_gauge_vec!(
+        "cqrs_projection_buffered_events",
+        "Number of out-of-order events a projection holds until an earlier version arrives",
+        &["projection"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref PROJECTION_BUFFER_EXPIRED: CounterVec = register_counter_vec!(
+        "cqrs_projection_buffer_expired_total",
+        "Total number of buffered events quarantined after waiting too long for their gap",
+        &["projection"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref PROJECTION_REPAIRS: CounterVec = register_counter_vec!(
+        "cqrs_projection_repairs_total",
+        "Total number of divergent views re-projected from their events",
+        &["projection"]
+    )
+    .expect("metric cannot be created");
+
+    // Consumer metrics
+    pub static ref CONSUMER_POISON_MESSAGES: CounterVec = register_counter_vec!(
+        "cqrs_consumer_poison_messages_total",
+        "Total number of messages routed to a dead letter topic after repeatedly failing to deserialize",
+        &["consumer", "topic"]
+    )
+    .expect("metric cannot be created");
+
+    // Admission control metrics
+    pub static ref COMMAND_ADMISSION: CounterVec = register_counter_vec!(
+        "cqrs_command_admission_total",
+        "Total number of commands delayed or shed by admission control",
+        &["outcome", "reason"]
+    )
+    .expect("metric cannot be created");
+
+    // Concurrency conflict metrics
+    pub static ref COMMAND_CONFLICTS: CounterVec = register_counter_vec!(
+        "cqrs_command_conflicts_total",
+        "Total number of commands rejected by an optimistic concurrency conflict",
+        &["event_type", "kind"]
+    )
+    .expect("metric cannot be created");
+
+    // Retry metrics
+    pub static ref RETRIES: CounterVec = register_counter_vec!(
+        "cqrs_retries_total",
+        "Total number of failed attempts by what happened next",
+        &["operation", "outcome"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref DEADLINES_EXCEEDED: CounterVec = register_counter_vec!(
+        "cqrs_deadline_exceeded_total",
+        "Total number of operations cancelled by their request deadline",
+        &["operation"]
+    )
+    .expect("metric cannot be created");
+
+    // Job queue metrics
+    pub static ref JOB_COUNTER: CounterVec = register_counter_vec!(
+        "cqrs_jobs_total",
+        "Total number of job runs by what happened to the job",
+        &["queue", "outcome"]
+    )
+    .expect("metric cannot be created");
+
+    pub static ref JOB_DURATION: HistogramVec = register_histogram_vec!(
+        "cqrs_job_duration_seconds",
+        "Job run duration in seconds",
+        &["queue"],
+        vec![0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0]
+    )
+    .expect("metric cannot be created");
+
     // Idempotency metrics
     pub static ref IDEMPOTENCY_CHECK: CounterVec = register_counter_vec!(
         "cqrs_idempotency_checks_total",
@@ -153,6 +399,19 @@ pub fn record_command(command_type: &str, success: bool, duration_secs: f64) {
         .observe(duration_secs);
 }
 
+/// Helper function to record a finished HTTP request
+///
+/// `route` is the matched route template, e.g. `/api/v1/orders/:id`, not the raw path
+pub fn record_http_request(method: &str, route: &str, status: u16, duration_secs: f64) {
+    let status = status.to_string();
+    HTTP_REQUESTS
+        .with_label_values(&[method, route, &status])
+        .inc();
+    HTTP_REQUEST_DURATION
+        .with_label_values(&[method, route, &status])
+        .observe(duration_secs);
+}
+
 /// Helper function to record event processing
 pub fn record_event(event_type: &str, success: bool, duration_secs: f64) {
     let status = if success { "success" } else { "error" };
@@ -175,6 +434,25 @@ pub fn record_query(query_type: &str, success: bool, duration_secs: f64) {
         .observe(duration_secs);
 }
 
+/// Helper function to record a slow read that was hedged or would have been
+///
+/// `outcome` is `primary`, `fallback` or `budget_exhausted`
+pub fn record_query_hedge(query_type: &str, outcome: &str) {
+    QUERY_HEDGES
+        .with_label_values(&[query_type, outcome])
+        .inc();
+}
+
+/// Helper function to record the replication lag seen on the read replica
+pub fn record_read_replica_lag(lag_secs: f64) {
+    READ_REPLICA_LAG.set(lag_secs);
+}
+
+/// Helper function to record where a read model query was sent, `primary` or `replica`
+pub fn record_read_routing(target: &str) {
+    READ_ROUTING.with_label_values(&[target]).inc();
+}
+
 /// Helper function to record saga execution
 pub fn record_saga(saga_type: &str, success: bool, duration_secs: f64) {
     let status = if success { "success" } else { "error" };
@@ -193,6 +471,63 @@ pub fn record_saga_compensation(saga_type: &str, step: &str) {
         .inc();
 }
 
+/// Start timing one attempt of a saga step; `action` is `execute` or `compensate`
+///
+/// The attempt counts as in flight until the timer is finished or dropped. One
+/// dropped without being finished, e.g. because its future was, is recorded with
+/// the outcome `dropped`.
+pub fn start_saga_step(saga_type: &str, step: &str, action: &str) -> SagaStepTimer {
+    let labels = [saga_type.to_string(), step.to_string(), action.to_string()];
+    let in_flight = SAGA_STEPS_IN_FLIGHT.with_label_values(&[saga_type, step, action]);
+    in_flight.inc();
+    SagaStepTimer {
+        labels,
+        in_flight,
+        start: std::time::Instant::now(),
+        finished: false,
+    }
+}
+
+/// An attempt of a saga step being timed, see [`start_saga_step`]
+pub struct SagaStepTimer {
+    labels: [String; 3],
+    in_flight: IntGauge,
+    start: std::time::Instant,
+    finished: bool,
+}
+
+impl SagaStepTimer {
+    /// Record the attempt's duration and outcome, e.g. `success` or `timeout`
+    pub fn finish(mut self, outcome: &str) {
+        self.record(outcome);
+    }
+
+    fn record(&mut self, outcome: &str) {
+        self.finished = true;
+        let [saga_type, step, action] = &self.labels;
+        SAGA_STEP_COUNTER
+            .with_label_values(&[saga_type, step, action, outcome])
+            .inc();
+        SAGA_STEP_DURATION
+            .with_label_values(&[saga_type, step, action])
+            .observe(self.start.elapsed().as_secs_f64());
+    }
+}
+
+impl Drop for SagaStepTimer {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.record("dropped");
+        }
+        self.in_flight.dec();
+    }
+}
+
+/// Helper function to record the size of the manual intervention queue
+pub fn record_sagas_requiring_intervention(count: i64) {
+    SAGAS_REQUIRING_INTERVENTION.set(count);
+}
+
 /// Helper function to record cache hit/miss
 pub fn record_cache_request(cache_type: &str, hit: bool) {
     let status = if hit { "hit" } else { "miss" };
@@ -220,6 +555,21 @@ pub fn record_circuit_breaker_transition(service: &str, from: CircuitBreakerStat
         .inc();
 }
 
+/// Helper function to record how many calls a bulkhead lets through and how many are running
+pub fn record_bulkhead_usage(dependency: &str, in_flight: usize, limit: usize) {
+    BULKHEAD_IN_FLIGHT
+        .with_label_values(&[dependency])
+        .set(in_flight as i64);
+    BULKHEAD_LIMIT
+        .with_label_values(&[dependency])
+        .set(limit as i64);
+}
+
+/// Helper function to record a call rejected by a full bulkhead
+pub fn record_bulkhead_rejected(dependency: &str) {
+    BULKHEAD_REJECTED.with_label_values(&[dependency]).inc();
+}
+
 /// Helper function to record event store operation
 pub fn record_event_store_operation(operation: &str, success: bool, duration_secs: f64) {
     let status = if success { "success" } else { "error" };
@@ -231,6 +581,23 @@ pub fn record_event_store_operation(operation: &str, success: bool, duration_sec
         .observe(duration_secs);
 }
 
+/// Helper function to record an append for an aggregate type
+pub fn record_event_store_append(aggregate_type: &str, event_count: usize, duration_secs: f64) {
+    EVENT_STORE_APPEND_DURATION
+        .with_label_values(&[aggregate_type])
+        .observe(duration_secs);
+    EVENT_STORE_APPENDED_EVENTS
+        .with_label_values(&[aggregate_type])
+        .inc_by(event_count as f64);
+}
+
+/// Helper function to record an append that exceeded the slow threshold
+pub fn record_slow_append(aggregate_type: &str) {
+    EVENT_STORE_SLOW_APPENDS
+        .with_label_values(&[aggregate_type])
+        .inc();
+}
+
 /// Helper function to record projection lag
 pub fn record_projection_lag(projection_type: &str, lag_secs: f64) {
     PROJECTION_LAG
@@ -238,12 +605,101 @@ pub fn record_projection_lag(projection_type: &str, lag_secs: f64) {
         .observe(lag_secs);
 }
 
+/// Helper function to record a consistency check of a projection
+///
+/// `divergent_fields` names each field that differed from the state derived from events
+pub fn record_projection_consistency(projection: &str, checked: usize, divergent_fields: &[&str]) {
+    PROJECTION_CONSISTENCY_CHECKED
+        .with_label_values(&[projection])
+        .inc_by(checked as f64);
+    for field in divergent_fields {
+        PROJECTION_DIVERGENCES
+            .with_label_values(&[projection, field])
+            .inc();
+    }
+}
+
+/// Helper function to record a projection applying an event
+///
+/// `event_time` is when the event was stored and `processed_time` when it was
+/// applied, both in Unix seconds. The event time gauge only moves forward, so a late
+/// event shows up as drift without pulling the watermark back.
+pub fn record_projection_drift(projection: &str, event_time: f64, processed_time: f64) {
+    PROJECTION_DRIFT
+        .with_label_values(&[projection])
+        .observe((processed_time - event_time).max(0.0));
+    let watermark = PROJECTION_EVENT_TIME.with_label_values(&[projection]);
+    if event_time > watermark.get() {
+        watermark.set(event_time);
+    }
+}
+
+/// Helper function to record a projection's buffer after expiring old events
+pub fn record_projection_buffer(projection: &str, buffered: i64, expired: u64) {
+    PROJECTION_BUFFERED_EVENTS
+        .with_label_values(&[projection])
+        .set(buffered);
+    PROJECTION_BUFFER_EXPIRED
+        .with_label_values(&[projection])
+        .inc_by(expired as f64);
+}
+
+/// Helper function to record a view repaired by the consistency checker
+pub fn record_projection_repair(projection: &str) {
+    PROJECTION_REPAIRS.with_label_values(&[projection]).inc();
+}
+
+/// Helper function to record a poison message taken off a topic
+pub fn record_poison_message(consumer: &str, topic: &str) {
+    CONSUMER_POISON_MESSAGES
+        .with_label_values(&[consumer, topic])
+        .inc();
+}
+
+/// Helper function to record a command delayed or shed under load
+///
+/// `outcome` is `delayed` or `shed`; `reason` is the signal that tripped, e.g. `latency`
+pub fn record_command_admission(outcome: &str, reason: &str) {
+    COMMAND_ADMISSION
+        .with_label_values(&[outcome, reason])
+        .inc();
+}
+
+/// Helper function to record a command that lost an optimistic concurrency race
+///
+/// `kind` is `already_applied` when another writer appended the same event type,
+/// otherwise `contention`
+pub fn record_command_conflict(event_type: &str, kind: &str) {
+    COMMAND_CONFLICTS
+        .with_label_values(&[event_type, kind])
+        .inc();
+}
+
 /// Helper function to record idempotency check
 pub fn record_idempotency_check(duplicate: bool) {
     let status = if duplicate { "duplicate" } else { "new" };
     IDEMPOTENCY_CHECK.with_label_values(&[status]).inc();
 }
 
+/// Helper function to record a failed attempt: retried, exhausted, not_retryable or
+/// deadline, or recovered when a retry finally succeeded
+pub fn record_retry(operation: &str, outcome: &str) {
+    RETRIES.with_label_values(&[operation, outcome]).inc();
+}
+
+/// Helper function to record a job run; `outcome` is `completed`, `retried` or `dead_lettered`
+pub fn record_job(queue: &str, outcome: &str, duration_secs: f64) {
+    JOB_COUNTER.with_label_values(&[queue, outcome]).inc();
+    JOB_DURATION
+        .with_label_values(&[queue])
+        .observe(duration_secs);
+}
+
+/// Helper function to record an operation cancelled by its deadline
+pub fn record_deadline_exceeded(operation: &str) {
+    DEADLINES_EXCEEDED.with_label_values(&[operation]).inc();
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum CircuitBreakerState {
     Closed,
@@ -277,6 +733,123 @@ mod tests {
         assert!(metrics.contains("cqrs_events_total"));
     }
 
+    #[test]
+    fn test_record_event_store_append() {
+        record_event_store_append("Order", 2, 0.02);
+        record_slow_append("Order");
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains("cqrs_event_store_append_duration_seconds"));
+        assert!(metrics.contains("cqrs_event_store_slow_appends_total"));
+    }
+
+    #[test]
+    fn test_record_sagas_requiring_intervention() {
+        record_sagas_requiring_intervention(3);
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains("cqrs_sagas_requiring_intervention 3"));
+    }
+
+    #[test]
+    fn test_saga_step_timer() {
+        let timer = start_saga_step("TestSaga", "reserve", "execute");
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains(
+            "cqrs_saga_steps_in_flight{action=\"execute\",saga_type=\"TestSaga\",step=\"reserve\"} 1"
+        ));
+
+        timer.finish("timeout");
+        drop(start_saga_step("TestSaga", "reserve", "execute"));
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains(
+            "cqrs_saga_steps_in_flight{action=\"execute\",saga_type=\"TestSaga\",step=\"reserve\"} 0"
+        ));
+        assert!(metrics.contains("outcome=\"timeout\""));
+        assert!(metrics.contains("outcome=\"dropped\""));
+        assert!(metrics.contains("cqrs_saga_step_duration_seconds"));
+    }
+
+    #[test]
+    fn test_record_poison_message() {
+        record_poison_message("projection-service", "order-events");
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains("cqrs_consumer_poison_messages_total"));
+    }
+
+    #[test]
+    fn test_record_read_routing() {
+        record_read_replica_lag(0.25);
+        record_read_routing("replica");
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains("cqrs_read_replica_lag_seconds"));
+        assert!(metrics.contains("cqrs_read_routing_total"));
+    }
+
+    #[test]
+    fn test_record_query_hedge() {
+        record_query_hedge("get_order", "fallback");
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains("cqrs_query_hedges_total"));
+    }
+
+    #[test]
+    fn test_record_bulkhead_metrics() {
+        record_bulkhead_usage("test-dependency", 3, 8);
+        record_bulkhead_rejected("test-dependency");
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains("cqrs_bulkhead_in_flight"));
+        assert!(metrics.contains("cqrs_bulkhead_limit"));
+        assert!(metrics.contains("cqrs_bulkhead_rejected_total"));
+    }
+
+    #[test]
+    fn test_record_http_request() {
+        record_http_request("GET", "/api/v1/orders/:id", 200, 0.012);
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains("cqrs_http_requests_total"));
+        assert!(metrics.contains("cqrs_http_request_duration_seconds"));
+    }
+
+    #[test]
+    fn test_record_command_admission() {
+        record_command_admission("shed", "latency");
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains("cqrs_command_admission_total"));
+    }
+
+    #[test]
+    fn test_record_command_conflict() {
+        record_command_conflict("OrderConfirmed", "already_applied");
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains("cqrs_command_conflicts_total"));
+    }
+
+    #[test]
+    fn test_record_projection_consistency() {
+        record_projection_consistency("orders", 10, &["status"]);
+        record_projection_repair("orders");
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains("cqrs_projection_divergences_total"));
+        assert!(metrics.contains("cqrs_projection_repairs_total"));
+    }
+
+    #[test]
+    fn test_record_projection_drift() {
+        record_projection_drift("drift-test", 100.0, 102.5);
+        record_projection_drift("drift-test", 50.0, 103.0);
+        let watermark = PROJECTION_EVENT_TIME.with_label_values(&["drift-test"]);
+        assert_eq!(watermark.get(), 100.0);
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains("cqrs_projection_drift_seconds"));
+    }
+
+    #[test]
+    fn test_record_job() {
+        record_job("test-queue", "dead_lettered", 0.2);
+        let metrics = gather_metrics().unwrap();
+        assert!(metrics.contains(r#"cqrs_jobs_total{outcome="dead_lettered",queue="test-queue"} 1"#));
+        assert!(metrics.contains("cqrs_job_duration_seconds"));
+    }
+
     #[test]
     fn test_circuit_breaker_state() {
         let state = CircuitBreakerState::Open;
@@ -0,0 +1,311 @@
+#[cfg(feature = "postgres")]
+use sqlx::{postgres::PgPoolOptions, PgPool};
+#[cfg(feature = "postgres")]
+use std::collections::HashSet;
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How a failed check affects startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The service can't work without it; startup is aborted
+    Fatal,
+    /// The service runs degraded or fixes it itself, e.g. by creating missing topics
+    Warning,
+}
+
+/// Result of one preflight check
+#[derive(Debug, Clone)]
+pub struct CheckOutcome {
+    pub name: String,
+    pub severity: Severity,
+    /// What was found on success, or what is wrong
+    pub result: Result<String, String>,
+}
+
+impl CheckOutcome {
+    pub fn is_fatal(&self) -> bool {
+        self.result.is_err() && self.severity == Severity::Fatal
+    }
+}
+
+impl fmt::Display for CheckOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.result, self.severity) {
+            (Ok(detail), _) => write!(f, "[ OK ] {}: {}", self.name, detail),
+            (Err(problem), Severity::Fatal) => write!(f, "[FAIL] {}: {}", self.name, problem),
+            (Err(problem), Severity::Warning) => write!(f, "[WARN] {}: {}", self.name, problem),
+        }
+    }
+}
+
+/// Dependency checks a service runs at startup, before it takes traffic
+///
+/// Every check runs, so the report lists all problems at once instead of the first
+/// request failing on one of them. [`finish`](Self::finish) prints the report and
+/// exits non-zero if any fatal check failed.
+///
+/// ```no_run
+/// use common::preflight::{Preflight, Severity};
+///
+/// # async fn example() {
+/// let mut preflight = Preflight::new("command-service");
+/// preflight.config::<u64>(&["OUTBOX_POLL_INTERVAL_MS"]);
+/// preflight
+///     .database("postgres://localhost/cqrs_events", &["events", "events.correlation_id"])
+///     .await;
+/// preflight
+///     .check("redis", Severity::Warning, async { Ok::<_, String>("reachable".to_string()) })
+///     .await;
+/// preflight.finish();
+/// # }
+/// ```
+pub struct Preflight {
+    service: String,
+    timeout: Duration,
+    outcomes: Vec<CheckOutcome>,
+}
+
+impl Preflight {
+    pub fn new(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            timeout: Duration::from_secs(5),
+            outcomes: Vec::new(),
+        }
+    }
+
+    /// How long a single check may take before it counts as failed
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Whether preflight checks are enabled (`PREFLIGHT_CHECKS`, default true)
+    pub fn enabled() -> bool {
+        std::env::var("PREFLIGHT_CHECKS")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .unwrap_or(true)
+    }
+
+    pub fn record(&mut self, name: impl Into<String>, severity: Severity, result: Result<String, String>) {
+        self.outcomes.push(CheckOutcome {
+            name: name.into(),
+            severity,
+            result,
+        });
+    }
+
+    /// Run a check, failing it if it takes longer than the timeout
+    pub async fn check<F, E>(&mut self, name: impl Into<String>, severity: Severity, check: F)
+    where
+        F: Future<Output = Result<String, E>>,
+        E: fmt::Display,
+    {
+        let result = match tokio::time::timeout(self.timeout, check).await {
+            Ok(result) => result.map_err(|e| e.to_string()),
+            Err(_) => Err(format!("timed out after {}ms", self.timeout.as_millis())),
+        };
+        self.record(name, severity, result);
+    }
+
+    /// Check that the given environment variables, where set, parse as `T`
+    ///
+    /// Services fall back to defaults for values that don't parse, which hides typos.
+    pub fn config<T: FromStr>(&mut self, names: &[&str]) {
+        let invalid: Vec<String> = names
+            .iter()
+            .filter_map(|name| {
+                let value = std::env::var(name).ok()?;
+                value.parse::<T>().is_err().then(|| format!("{}={:?}", name, value))
+            })
+            .collect();
+        let result = if invalid.is_empty() {
+            Ok(format!("{} settings valid", names.len()))
+        } else {
+            Err(format!("invalid values, defaults would be used: {}", invalid.join(", ")))
+        };
+        let name = format!("config ({})", std::any::type_name::<T>());
+        self.record(name, Severity::Fatal, result);
+    }
+
+    /// Connect to the database and check the schema has the `required` tables and
+    /// columns, given as `table` or `table.column`
+    ///
+    /// Migrations are applied with `psql` rather than tracked, so their effect on the
+    /// schema is what is checked. Returns the connection pool when the database is
+    /// reachable, for further checks.
+    #[cfg(feature = "postgres")]
+    pub async fn database(&mut self, database_url: &str, required: &[&str]) -> Option<PgPool> {
+        let connected = tokio::time::timeout(
+            self.timeout,
+            PgPoolOptions::new()
+                .max_connections(1)
+                .acquire_timeout(self.timeout)
+                .connect(database_url),
+        )
+        .await;
+        let pool = match connected {
+            Ok(Ok(pool)) => pool,
+            Ok(Err(e)) => {
+                self.record("database", Severity::Fatal, Err(e.to_string()));
+                return None;
+            }
+            Err(_) => {
+                let problem = format!("timed out after {}ms", self.timeout.as_millis());
+                self.record("database", Severity::Fatal, Err(problem));
+                return None;
+            }
+        };
+        self.record("database", Severity::Fatal, Ok("connected".to_string()));
+
+        let schema = async {
+            let tables: Vec<&str> = required
+                .iter()
+                .map(|entry| entry.split_once('.').map_or(*entry, |(table, _)| table))
+                .collect();
+            let columns: Vec<(String, String)> = sqlx::query_as(
+                r#"
+                SELECT table_name::text, column_name::text
+                FROM information_schema.columns
+                WHERE table_schema = current_schema() AND table_name = ANY($1)
+                "#,
+            )
+            .bind(&tables)
+            .fetch_all(&pool)
+            .await?;
+
+            let missing = missing_schema(required, &columns);
+            Ok::<_, sqlx::Error>(if missing.is_empty() {
+                Ok(format!("{} tables and columns present", required.len()))
+            } else {
+                Err(format!("missing {}; apply pending migrations", missing.join(", ")))
+            })
+        };
+        let result = match schema.await {
+            Ok(result) => result,
+            Err(e) => Err(e.to_string()),
+        };
+        self.record("schema", Severity::Fatal, result);
+
+        Some(pool)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn outcomes(&self) -> &[CheckOutcome] {
+        &self.outcomes
+    }
+
+    pub fn has_fatal(&self) -> bool {
+        self.outcomes.iter().any(CheckOutcome::is_fatal)
+    }
+
+    /// Every check's outcome, one per line
+    pub fn report(&self) -> String {
+        let fatal = self.outcomes.iter().filter(|outcome| outcome.is_fatal()).count();
+        let warnings = self
+            .outcomes
+            .iter()
+            .filter(|outcome| outcome.result.is_err() && !outcome.is_fatal())
+            .count();
+
+        let mut report = format!("Preflight checks for {}:\n", self.service);
+        for outcome in &self.outcomes {
+            report.push_str(&format!("  {}\n", outcome));
+        }
+        report.push_str(&format!("{} fatal, {} warnings", fatal, warnings));
+        report
+    }
+
+    /// Print the report and exit with status 1 if any fatal check failed
+    pub fn finish(self) {
+        eprintln!("{}", self.report());
+        if self.has_fatal() {
+            tracing::error!("Preflight checks failed for {}, exiting", self.service);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Entries of `required` (`table` or `table.column`) not among the `(table, column)`
+/// pairs present
+#[cfg(feature = "postgres")]
+fn missing_schema(required: &[&str], present: &[(String, String)]) -> Vec<String> {
+    let tables: HashSet<&str> = present.iter().map(|(table, _)| table.as_str()).collect();
+    let columns: HashSet<(&str, &str)> = present
+        .iter()
+        .map(|(table, column)| (table.as_str(), column.as_str()))
+        .collect();
+
+    required
+        .iter()
+        .filter(|entry| match entry.split_once('.') {
+            Some(column) => !columns.contains(&column),
+            None => !tables.contains(*entry),
+        })
+        .map(|entry| entry.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "postgres")]
+    fn test_missing_schema() {
+        let present = vec![
+            ("events".to_string(), "event_id".to_string()),
+            ("events".to_string(), "correlation_id".to_string()),
+        ];
+
+        assert!(missing_schema(&["events", "events.correlation_id"], &present).is_empty());
+        assert_eq!(
+            missing_schema(&["events.user_id", "event_outbox"], &present),
+            ["events.user_id", "event_outbox"]
+        );
+    }
+
+    #[test]
+    fn test_config_reports_unparseable_values() {
+        std::env::set_var("PREFLIGHT_TEST_PORT", "80a");
+        let mut preflight = Preflight::new("test-service");
+        preflight.config::<u16>(&["PREFLIGHT_TEST_PORT", "PREFLIGHT_TEST_UNSET"]);
+
+        let outcome = &preflight.outcomes()[0];
+        assert_eq!(outcome.name, "config (u16)");
+        assert_eq!(
+            outcome.result,
+            Err("invalid values, defaults would be used: PREFLIGHT_TEST_PORT=\"80a\"".to_string())
+        );
+        assert!(preflight.has_fatal());
+    }
+
+    #[tokio::test]
+    async fn test_report_fails_on_fatal_only() {
+        let mut preflight = Preflight::new("test-service").with_timeout(Duration::from_millis(50));
+        preflight
+            .check("kafka", Severity::Warning, async { Err::<String, _>("no brokers") })
+            .await;
+        assert!(!preflight.has_fatal());
+
+        preflight
+            .check("slow", Severity::Fatal, async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok::<_, String>("done".to_string())
+            })
+            .await;
+        assert!(preflight.has_fatal());
+
+        let report = preflight.report();
+        assert!(report.contains("[WARN] kafka: no brokers"));
+        assert!(report.contains("[FAIL] slow: timed out after 50ms"));
+        assert!(report.ends_with("1 fatal, 1 warnings"));
+    }
+}
@@ -0,0 +1,338 @@
+use regex::Regex;
+use serde_json::Value;
+use std::borrow::Cow;
+
+/// What a masked value is replaced with
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Field names masked unless `LOG_REDACT_FIELDS` says otherwise: contact details
+/// and payment data
+pub const DEFAULT_REDACTED_FIELDS: &[&str] = &[
+    "email",
+    "address",
+    "phone",
+    "card_number",
+    "cvv",
+    "iban",
+    "account_number",
+    "payment_details",
+    "password",
+];
+
+/// Masks personal data in structured values and log lines
+///
+/// A field is masked when its name contains one of the configured names, ignoring
+/// case, so `address` also covers `shipping_address`. Besides JSON keys, `name: value`
+/// and `name=value` pairs inside strings are masked, which catches the `Debug` output
+/// of whole commands and events in log messages. A value that is a struct, tuple or
+/// list, such as `Address { .. }` or `Some([..])`, is masked up to its balanced close.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    fields: Vec<String>,
+    inline: Option<Regex>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new(DEFAULT_REDACTED_FIELDS)
+    }
+}
+
+impl Redactor {
+    pub fn new<S: AsRef<str>>(fields: &[S]) -> Self {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|field| field.as_ref().trim().to_ascii_lowercase())
+            .filter(|field| !field.is_empty())
+            .collect();
+        let names: Vec<String> = fields.iter().map(|field| regex::escape(field)).collect();
+        // Only the name; where the value ends is found by `value_end`
+        let inline = (!fields.is_empty())
+            .then(|| {
+                Regex::new(&format!(r#"(?i)\b\w*(?:{})\w*"?\s*[:=]\s*"#, names.join("|"))).ok()
+            })
+            .flatten();
+        Self { fields, inline }
+    }
+
+    /// Fields from `LOG_REDACT_FIELDS` (comma-separated), or the defaults when unset;
+    /// set it empty to turn redaction off
+    pub fn from_env() -> Self {
+        match std::env::var("LOG_REDACT_FIELDS") {
+            Ok(fields) => Self::new(&fields.split(',').collect::<Vec<_>>()),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.fields.is_empty()
+    }
+
+    /// Whether a field of this name is masked
+    pub fn is_sensitive(&self, name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        self.fields.iter().any(|field| name.contains(field.as_str()))
+    }
+
+    /// Mask sensitive fields of `value` in place, at any depth
+    pub fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::Object(fields) => {
+                for (name, value) in fields.iter_mut() {
+                    if self.is_sensitive(name) {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_value(value);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_value(item)),
+            Value::String(text) => {
+                if let Cow::Owned(redacted) = self.redact_text(text) {
+                    *text = redacted;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// `text` with the values of sensitive `name: value` pairs masked
+    pub fn redact_text<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let Some(pattern) = &self.inline else {
+            return Cow::Borrowed(text);
+        };
+
+        let mut redacted = String::new();
+        let mut copied = 0;
+        let mut next = 0;
+        while let Some(name) = pattern.find_at(text, next) {
+            // Names inside a masked value go with it
+            let end = value_end(text, name.end());
+            if end > name.end() {
+                redacted.push_str(&text[copied..name.end()]);
+                redacted.push_str(&format!("\"{}\"", REDACTED));
+                copied = end;
+            }
+            next = end;
+        }
+
+        if copied == 0 {
+            return Cow::Borrowed(text);
+        }
+        redacted.push_str(&text[copied..]);
+        Cow::Owned(redacted)
+    }
+
+    /// Mask one log line: a JSON object field by field, anything else as text
+    pub fn redact_line<'a>(&self, line: &'a [u8]) -> Cow<'a, [u8]> {
+        if !self.is_enabled() {
+            return Cow::Borrowed(line);
+        }
+        let Ok(text) = std::str::from_utf8(line) else {
+            return Cow::Borrowed(line);
+        };
+        let body = text.trim_end_matches('\n');
+        let newline = &text[body.len()..];
+
+        match serde_json::from_str::<Value>(body) {
+            Ok(mut value) if value.is_object() => {
+                self.redact_value(&mut value);
+                Cow::Owned(format!("{}{}", value, newline).into_bytes())
+            }
+            _ => match self.redact_text(text) {
+                Cow::Owned(redacted) => Cow::Owned(redacted.into_bytes()),
+                Cow::Borrowed(_) => Cow::Borrowed(line),
+            },
+        }
+    }
+}
+
+/// End of the value starting at `start`: a quoted string, or everything up to the next
+/// `,`, `;`, space or unmatched closing bracket outside brackets and strings
+///
+/// A space followed by an opening bracket continues the value, as in `Address { .. }`.
+fn value_end(text: &str, start: usize) -> usize {
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                if depth == 0 {
+                    return (i + 1).min(bytes.len());
+                }
+            }
+            b'(' | b'{' | b'[' => depth += 1,
+            b')' | b'}' | b']' => {
+                if depth == 0 {
+                    return i;
+                }
+                depth -= 1;
+            }
+            b',' | b';' if depth == 0 => return i,
+            c if c.is_ascii_whitespace() && depth == 0 => {
+                let next = text[i..].trim_start();
+                if !next.starts_with(['{', '(', '[']) {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+#[cfg(feature = "telemetry")]
+pub use writer::{RedactingMakeWriter, RedactingWriter};
+
+#[cfg(feature = "telemetry")]
+mod writer {
+    use std::io::{self, Write};
+    use tracing_subscriber::fmt::MakeWriter;
+
+    use super::Redactor;
+
+    /// Log writer masking each line with a [`Redactor`] before it reaches `inner`
+    pub struct RedactingMakeWriter<M> {
+        inner: M,
+        redactor: Redactor,
+    }
+
+    impl<M> RedactingMakeWriter<M> {
+        pub fn new(inner: M, redactor: Redactor) -> Self {
+            Self { inner, redactor }
+        }
+    }
+
+    impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingMakeWriter<M> {
+        type Writer = RedactingWriter<'a, M::Writer>;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            RedactingWriter {
+                inner: self.inner.make_writer(),
+                redactor: &self.redactor,
+            }
+        }
+    }
+
+    /// Writer for one log event; the formatter writes each event in a single call
+    pub struct RedactingWriter<'a, W> {
+        inner: W,
+        redactor: &'a Redactor,
+    }
+
+    impl<W: Write> Write for RedactingWriter<'_, W> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.inner.write_all(&self.redactor.redact_line(buf))?;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.inner.flush()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::commands::order_commands::{CreateOrderCommand, CreateOrderItem, ShippingAddress};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_redacts_nested_fields() {
+        let mut value = serde_json::json!({
+            "order_id": "o-1",
+            "customer": {"email": "jane@example.com", "name": "Jane"},
+            "shipping_address": {"street": "1 Main St"},
+            "items": [{"sku": "A", "card_number": "4242424242424242"}],
+        });
+        Redactor::default().redact_value(&mut value);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "order_id": "o-1",
+                "customer": {"email": REDACTED, "name": "Jane"},
+                "shipping_address": REDACTED,
+                "items": [{"sku": "A", "card_number": REDACTED}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_redacts_debug_output_in_messages() {
+        let redactor = Redactor::default();
+        let message = r#"Received CreateOrder { customer_email: "jane@example.com", total: 10 }"#;
+        assert_eq!(
+            redactor.redact_text(message),
+            r#"Received CreateOrder { customer_email: "[REDACTED]", total: 10 }"#
+        );
+        assert_eq!(
+            redactor.redact_text("retrying with phone=555-0100, attempt=2"),
+            r#"retrying with phone="[REDACTED]", attempt=2"#
+        );
+    }
+
+    #[test]
+    fn test_redacts_nested_debug_values_up_to_their_close() {
+        let command = CreateOrderCommand {
+            customer_id: Uuid::nil(),
+            items: vec![CreateOrderItem {
+                product_id: Uuid::nil(),
+                sku: "SKU-1".to_string(),
+                quantity: 2,
+                unit_price: 9.5,
+            }],
+            shipping_address: ShippingAddress {
+                street: "1 Main St".to_string(),
+                city: "Springfield".to_string(),
+                state: "IL".to_string(),
+                zip: "62701".to_string(),
+                country: "US".to_string(),
+            },
+            command_id: None,
+        };
+        let redactor = Redactor::default();
+
+        let message = format!("Handling {:?}", command);
+        let redacted = redactor.redact_text(&message);
+        assert!(redacted.contains(r#"shipping_address: "[REDACTED]", command_id: None }"#));
+        assert!(redacted.contains(r#"sku: "SKU-1""#));
+        for leaked in ["1 Main St", "Springfield", "62701"] {
+            assert!(!redacted.contains(leaked), "{} leaked in {}", leaked, redacted);
+        }
+
+        let pretty = redactor.redact_text(&format!("{:#?}", command)).into_owned();
+        assert!(!pretty.contains("Springfield"), "{}", pretty);
+        assert!(pretty.contains("command_id: None"));
+
+        assert_eq!(
+            redactor.redact_text(r#"email: Some(["a@x.io", "b@x.io"]), total: 10"#),
+            r#"email: "[REDACTED]", total: 10"#
+        );
+    }
+
+    #[test]
+    fn test_redacts_json_log_lines() {
+        let line = br#"{"level":"INFO","email":"jane@example.com","message":"address: \"1 Main St\""}
+"#;
+        let redacted = Redactor::default().redact_line(line);
+        let value: Value = serde_json::from_slice(&redacted).unwrap();
+        assert_eq!(value["email"], REDACTED);
+        assert_eq!(value["message"], r#"address: "[REDACTED]""#);
+        assert!(redacted.ends_with(b"\n"));
+    }
+
+    #[test]
+    fn test_empty_field_list_disables_redaction() {
+        let redactor = Redactor::new(&[""]);
+        assert!(!redactor.is_enabled());
+        let line = br#"{"email":"jane@example.com"}"#;
+        assert!(matches!(redactor.redact_line(line), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,394 @@
+use redis::aio::{ConnectionLike, ConnectionManager};
+use redis::cluster::ClusterClient;
+use redis::cluster_async::ClusterConnection;
+use redis::sentinel::{Sentinel, SentinelNodeConnectionInfo};
+use redis::{
+    Client, Cmd, ConnectionAddr, ConnectionInfo, ErrorKind, IntoConnectionInfo, Pipeline,
+    RedisConnectionInfo, RedisFuture, RedisResult, TlsMode, Value,
+};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+/// How the Redis deployment is laid out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedisTopology {
+    /// A single server, or a proxy in front of several
+    Standalone { url: String },
+    /// Redis Cluster: keys are spread over the nodes' hash slots, and any reachable
+    /// node lets the client discover the rest
+    Cluster { nodes: Vec<String> },
+    /// A primary with replicas, watched by Sentinel, which names the current primary
+    Sentinel {
+        sentinels: Vec<String>,
+        master_name: String,
+    },
+}
+
+/// Where and how to connect to Redis
+///
+/// `username`, `password` and `tls` apply to every data node, on top of whatever the
+/// URLs say. Sentinel URLs are used as given, since sentinels usually have their own
+/// password: put it in the URL and use `rediss://` for TLS.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RedisConfig {
+    pub topology: RedisTopology,
+    /// ACL user; a password alone authenticates as `default`
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Connect to data nodes over TLS; `rediss://` URLs do so regardless
+    pub tls: bool,
+}
+
+impl fmt::Debug for RedisConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedisConfig")
+            .field("topology", &self.topology)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .field("tls", &self.tls)
+            .finish()
+    }
+}
+
+impl RedisConfig {
+    pub fn standalone(url: impl Into<String>) -> Self {
+        Self::new(RedisTopology::Standalone { url: url.into() })
+    }
+
+    pub fn cluster(nodes: Vec<String>) -> Self {
+        Self::new(RedisTopology::Cluster { nodes })
+    }
+
+    pub fn sentinel(sentinels: Vec<String>, master_name: impl Into<String>) -> Self {
+        Self::new(RedisTopology::Sentinel {
+            sentinels,
+            master_name: master_name.into(),
+        })
+    }
+
+    fn new(topology: RedisTopology) -> Self {
+        Self {
+            topology,
+            username: None,
+            password: None,
+            tls: false,
+        }
+    }
+
+    pub fn with_auth(mut self, username: Option<String>, password: Option<String>) -> Self {
+        self.username = username;
+        self.password = password;
+        self
+    }
+
+    pub fn with_tls(mut self, tls: bool) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Read the deployment from the environment
+    ///
+    /// `REDIS_MODE` is `standalone` (the default, using `REDIS_URL`), `cluster`
+    /// (`REDIS_CLUSTER_NODES`, comma-separated) or `sentinel` (`REDIS_SENTINELS`,
+    /// comma-separated, and `REDIS_SENTINEL_MASTER`). `REDIS_USERNAME`,
+    /// `REDIS_PASSWORD` and `REDIS_TLS` apply to all of them.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let url = lookup("REDIS_URL").unwrap_or_else(|| "redis://localhost:6379".to_string());
+        let list = |key: &str| -> Vec<String> {
+            lookup(key)
+                .map(|value| {
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|url| !url.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let non_empty = |key: &str| lookup(key).filter(|value| !value.is_empty());
+
+        let config = match lookup("REDIS_MODE").as_deref().map(str::trim) {
+            Some("cluster") => {
+                let nodes = list("REDIS_CLUSTER_NODES");
+                Self::cluster(if nodes.is_empty() { vec![url] } else { nodes })
+            }
+            Some("sentinel") => Self::sentinel(
+                list("REDIS_SENTINELS"),
+                non_empty("REDIS_SENTINEL_MASTER").unwrap_or_else(|| "mymaster".to_string()),
+            ),
+            _ => Self::standalone(url),
+        };
+        config
+            .with_auth(non_empty("REDIS_USERNAME"), non_empty("REDIS_PASSWORD"))
+            .with_tls(
+                lookup("REDIS_TLS")
+                    .and_then(|value| bool::from_str(value.trim()).ok())
+                    .unwrap_or(false),
+            )
+    }
+
+    /// Check the URLs without connecting
+    pub fn validate(&self) -> RedisResult<()> {
+        match &self.topology {
+            RedisTopology::Standalone { url } => self.node_info(url).map(|_| ()),
+            RedisTopology::Cluster { nodes } => self.cluster_nodes(nodes).map(|_| ()),
+            RedisTopology::Sentinel { sentinels, .. } => {
+                Sentinel::build(sentinels.clone()).map(|_| ())
+            }
+        }
+    }
+
+    /// Connect to the deployment
+    ///
+    /// Connections reconnect by themselves; a Sentinel connection also asks the
+    /// sentinels for the new primary when the old one stops accepting writes.
+    pub async fn connect(&self) -> RedisResult<RedisConnection> {
+        let connection = match &self.topology {
+            RedisTopology::Standalone { url } => {
+                let client = Client::open(self.node_info(url)?)?;
+                Connection::Standalone(ConnectionManager::new(client).await?)
+            }
+            RedisTopology::Cluster { nodes } => {
+                let mut builder = ClusterClient::builder(self.cluster_nodes(nodes)?);
+                if let Some(username) = &self.username {
+                    builder = builder.username(username.clone());
+                }
+                if let Some(password) = &self.password {
+                    builder = builder.password(password.clone());
+                }
+                if self.tls {
+                    builder = builder.tls(TlsMode::Secure);
+                }
+                Connection::Cluster(builder.build()?.get_async_connection().await?)
+            }
+            RedisTopology::Sentinel {
+                sentinels,
+                master_name,
+            } => {
+                let primary = SentinelPrimary {
+                    sentinel: tokio::sync::Mutex::new(Sentinel::build(sentinels.clone())?),
+                    master_name: master_name.clone(),
+                    node_info: SentinelNodeConnectionInfo {
+                        tls_mode: self.tls.then_some(TlsMode::Secure),
+                        redis_connection_info: Some(RedisConnectionInfo {
+                            db: 0,
+                            username: self.username.clone(),
+                            password: self.password.clone(),
+                        }),
+                    },
+                    current: RwLock::new(None),
+                };
+                primary.resolve().await?;
+                Connection::Sentinel(Arc::new(primary))
+            }
+        };
+        Ok(RedisConnection(connection))
+    }
+
+    fn node_info(&self, url: &str) -> RedisResult<ConnectionInfo> {
+        let mut info = url.into_connection_info()?;
+        if self.username.is_some() {
+            info.redis.username = self.username.clone();
+        }
+        if self.password.is_some() {
+            info.redis.password = self.password.clone();
+        }
+        if self.tls {
+            if let ConnectionAddr::Tcp(host, port) = info.addr {
+                info.addr = ConnectionAddr::TcpTls {
+                    host,
+                    port,
+                    insecure: false,
+                    tls_params: None,
+                };
+            }
+        }
+        Ok(info)
+    }
+
+    fn cluster_nodes(&self, nodes: &[String]) -> RedisResult<Vec<ConnectionInfo>> {
+        if nodes.is_empty() {
+            return Err((
+                ErrorKind::InvalidClientConfig,
+                "No Redis cluster nodes configured",
+            )
+                .into());
+        }
+        nodes.iter().map(|url| self.node_info(url)).collect()
+    }
+}
+
+/// A connection to a Redis deployment of any [`RedisTopology`]
+///
+/// Cheap to clone; clones share the underlying connections. It implements
+/// [`ConnectionLike`], so `redis::AsyncCommands` work on it as on a single-node
+/// connection.
+#[derive(Clone)]
+pub struct RedisConnection(Connection);
+
+#[derive(Clone)]
+enum Connection {
+    Standalone(ConnectionManager),
+    Cluster(ClusterConnection),
+    Sentinel(Arc<SentinelPrimary>),
+}
+
+/// The primary Sentinel currently names, looked up again after a failover
+struct SentinelPrimary {
+    sentinel: tokio::sync::Mutex<Sentinel>,
+    master_name: String,
+    node_info: SentinelNodeConnectionInfo,
+    current: RwLock<Option<ConnectionManager>>,
+}
+
+impl SentinelPrimary {
+    async fn resolve(&self) -> RedisResult<ConnectionManager> {
+        let client = self
+            .sentinel
+            .lock()
+            .await
+            .async_master_for(&self.master_name, Some(&self.node_info))
+            .await?;
+        let connection = ConnectionManager::new(client).await?;
+        *self.current.write().unwrap() = Some(connection.clone());
+        tracing::info!(master = %self.master_name, "Connected to Redis primary named by Sentinel");
+        Ok(connection)
+    }
+
+    async fn connection(&self) -> RedisResult<ConnectionManager> {
+        let current = self.current.read().unwrap().clone();
+        match current {
+            Some(connection) => Ok(connection),
+            None => self.resolve().await,
+        }
+    }
+
+    /// After a failover the old primary refuses writes or connections; forget it so
+    /// the next command goes to the new one
+    fn observe<T>(&self, result: &RedisResult<T>) {
+        if let Err(e) = result {
+            if e.kind() == ErrorKind::ReadOnly
+                || e.is_connection_refusal()
+                || e.is_connection_dropped()
+            {
+                tracing::warn!(master = %self.master_name, error = %e, "Redis primary unavailable, asking Sentinel again");
+                *self.current.write().unwrap() = None;
+            }
+        }
+    }
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match &mut self.0 {
+            Connection::Standalone(connection) => connection.req_packed_command(cmd),
+            Connection::Cluster(connection) => connection.req_packed_command(cmd),
+            Connection::Sentinel(primary) => Box::pin(async move {
+                let mut connection = primary.connection().await?;
+                let result = connection.req_packed_command(cmd).await;
+                primary.observe(&result);
+                result
+            }),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match &mut self.0 {
+            Connection::Standalone(connection) => {
+                connection.req_packed_commands(cmd, offset, count)
+            }
+            Connection::Cluster(connection) => connection.req_packed_commands(cmd, offset, count),
+            Connection::Sentinel(primary) => Box::pin(async move {
+                let mut connection = primary.connection().await?;
+                let result = connection.req_packed_commands(cmd, offset, count).await;
+                primary.observe(&result);
+                result
+            }),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match &self.0 {
+            Connection::Standalone(connection) => connection.get_db(),
+            Connection::Cluster(_) | Connection::Sentinel(_) => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn from(env: &[(&str, &str)]) -> RedisConfig {
+        let env: HashMap<&str, &str> = env.iter().copied().collect();
+        RedisConfig::from_lookup(|key| env.get(key).map(|value| value.to_string()))
+    }
+
+    #[test]
+    fn test_config_from_env() {
+        assert_eq!(from(&[]), RedisConfig::standalone("redis://localhost:6379"));
+
+        let cluster = from(&[
+            ("REDIS_MODE", "cluster"),
+            ("REDIS_CLUSTER_NODES", "redis://a:7000, redis://b:7001,"),
+            ("REDIS_PASSWORD", "secret"),
+            ("REDIS_TLS", "true"),
+        ]);
+        assert_eq!(
+            cluster,
+            RedisConfig::cluster(vec!["redis://a:7000".into(), "redis://b:7001".into()])
+                .with_auth(None, Some("secret".into()))
+                .with_tls(true)
+        );
+        assert!(!format!("{:?}", cluster).contains("secret"));
+
+        let sentinel = from(&[
+            ("REDIS_MODE", "sentinel"),
+            ("REDIS_SENTINELS", "redis://s1:26379,redis://s2:26379"),
+        ]);
+        assert_eq!(
+            sentinel.topology,
+            RedisTopology::Sentinel {
+                sentinels: vec!["redis://s1:26379".into(), "redis://s2:26379".into()],
+                master_name: "mymaster".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_auth_and_tls_apply_to_every_node() {
+        let config = RedisConfig::cluster(vec!["redis://a:7000".into(), "redis://b:7001".into()])
+            .with_auth(Some("app".into()), Some("secret".into()))
+            .with_tls(true);
+        let nodes = match &config.topology {
+            RedisTopology::Cluster { nodes } => config.cluster_nodes(nodes).unwrap(),
+            _ => unreachable!(),
+        };
+
+        for node in nodes {
+            assert!(matches!(
+                node.addr,
+                ConnectionAddr::TcpTls {
+                    insecure: false,
+                    ..
+                }
+            ));
+            assert_eq!(node.redis.username.as_deref(), Some("app"));
+            assert_eq!(node.redis.password.as_deref(), Some("secret"));
+        }
+        assert!(RedisConfig::cluster(vec![]).validate().is_err());
+        assert!(RedisConfig::standalone("not a url").validate().is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+use tracing::{Instrument, Level};
+use uuid::Uuid;
+
+/// Header carrying the correlation ID of a call across services
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+/// Header naming the user a request is made on behalf of
+pub const USER_ID_HEADER: &str = "x-user-id";
+
+/// Log one structured line per HTTP request
+///
+/// Every service logs requests with the same fields: `http.method`, `http.route`
+/// (the route template), `http.path`, `http.status`, `latency_ms`, `correlation_id`
+/// and `user_id`. The handler runs inside a span holding the last two, so its own
+/// log lines carry them as well. A request without a correlation ID gets a new one,
+/// and it is echoed in the response either way. 5xx responses log at `error`, 4xx
+/// at `warn`, the rest at `info`.
+pub async fn log_requests(request: Request, next: Next) -> Response {
+    let correlation_id = header(&request, CORRELATION_ID_HEADER)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let user_id = header(&request, USER_ID_HEADER);
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let span = tracing::info_span!(
+        "http_request",
+        correlation_id = %correlation_id,
+        user_id = user_id.as_deref(),
+    );
+
+    let start = Instant::now();
+    let mut response = next.run(request).instrument(span).await;
+    let latency_ms = start.elapsed().as_millis() as u64;
+    let status = response.status().as_u16();
+
+    macro_rules! log_request {
+        ($level:expr) => {
+            tracing::event!(
+                target: "http",
+                $level,
+                http.method = %method,
+                http.route = %route,
+                http.path = %path,
+                http.status = status,
+                latency_ms,
+                correlation_id = %correlation_id,
+                user_id = user_id.as_deref(),
+                "{} {} {}",
+                method,
+                path,
+                status
+            )
+        };
+    }
+    match status {
+        500.. => log_request!(Level::ERROR),
+        400..=499 => log_request!(Level::WARN),
+        _ => log_request!(Level::INFO),
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&correlation_id) {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static(CORRELATION_ID_HEADER), value);
+    }
+    response
+}
+
+/// Span to handle one consumed Kafka message in
+///
+/// Carries the `correlation_id` and `user_id` of the event's metadata under the same
+/// names as the [`log_requests`] span, so the consumers' log lines turn up in the same
+/// query as those of the request that produced the event.
+pub fn message_span(correlation_id: Option<Uuid>, user_id: Option<Uuid>) -> tracing::Span {
+    tracing::info_span!(
+        "kafka_message",
+        correlation_id = correlation_id.map(tracing::field::display),
+        user_id = user_id.map(tracing::field::display),
+    )
+}
+
+/// Non-empty value of a header, if it is valid UTF-8
+fn header(request: &Request, name: &str) -> Option<String> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    #[test]
+    fn test_header_ignores_blank_values() {
+        let request = Request::builder()
+            .header(CORRELATION_ID_HEADER, " abc ")
+            .header(USER_ID_HEADER, "  ")
+            .body(Body::empty())
+            .unwrap();
+
+        assert_eq!(
+            header(&request, CORRELATION_ID_HEADER).as_deref(),
+            Some("abc")
+        );
+        assert_eq!(header(&request, USER_ID_HEADER), None);
+    }
+}
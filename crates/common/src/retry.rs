@@ -0,0 +1,271 @@
+use rand::Rng;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::deadline::Deadline;
+use crate::metrics::record_retry;
+
+/// How long to wait before each retry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    /// The same delay before every retry
+    Fixed(Duration),
+    /// Double the delay on every retry, capped at `max`
+    Exponential { initial: Duration, max: Duration },
+    /// A random delay up to the exponential one (full jitter), so callers that
+    /// failed together do not retry together
+    ExponentialJitter { initial: Duration, max: Duration },
+}
+
+impl Backoff {
+    /// Delay before the given retry (1 = first retry)
+    pub fn delay(&self, retry: u32) -> Duration {
+        match *self {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => exponential(initial, max, retry),
+            Backoff::ExponentialJitter { initial, max } => {
+                let ceiling = exponential(initial, max, retry).as_millis() as u64;
+                Duration::from_millis(rand::thread_rng().gen_range(0..=ceiling))
+            }
+        }
+    }
+}
+
+fn exponential(initial: Duration, max: Duration, retry: u32) -> Duration {
+    let exponent = retry.saturating_sub(1).min(31);
+    initial.saturating_mul(1u32 << exponent).min(max)
+}
+
+/// When and how often [`retry`] runs an operation again
+///
+/// Policies compose from a maximum number of attempts, a [`Backoff`], an overall
+/// deadline and a predicate picking the errors worth retrying.
+pub struct RetryPolicy<E> {
+    operation: String,
+    max_attempts: u32,
+    backoff: Backoff,
+    deadline: Option<Duration>,
+    retryable: Arc<dyn Fn(&E) -> bool + Send + Sync>,
+}
+
+impl<E> Clone for RetryPolicy<E> {
+    fn clone(&self) -> Self {
+        Self {
+            operation: self.operation.clone(),
+            max_attempts: self.max_attempts,
+            backoff: self.backoff,
+            deadline: self.deadline,
+            retryable: self.retryable.clone(),
+        }
+    }
+}
+
+impl<E> RetryPolicy<E> {
+    /// Three attempts with exponential backoff from 100ms, retrying every error
+    ///
+    /// `operation` names the operation in logs and metrics.
+    pub fn new(operation: impl Into<String>) -> Self {
+        Self {
+            operation: operation.into(),
+            max_attempts: 3,
+            backoff: Backoff::Exponential {
+                initial: Duration::from_millis(100),
+                max: Duration::from_secs(5),
+            },
+            deadline: None,
+            retryable: Arc::new(|_| true),
+        }
+    }
+
+    /// Attempts in total, including the first; at least 1
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Give up rather than start a retry that would begin later than `deadline`
+    /// after the first attempt; an attempt already running is not cut short
+    ///
+    /// Retries also stop at the current [`Deadline`], with or without this.
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Retry only errors for which `retryable` returns true; others are returned at once
+    pub fn with_retryable(
+        mut self,
+        retryable: impl Fn(&E) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retryable = Arc::new(retryable);
+        self
+    }
+
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+}
+
+/// Run `op` until it succeeds or `policy` gives up, returning the last error
+pub async fn retry<T, E, F, Fut>(policy: &RetryPolicy<E>, mut op: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    let started = Instant::now();
+    let mut attempt = 1;
+    loop {
+        let error = match op().await {
+            Ok(value) => {
+                if attempt > 1 {
+                    record_retry(&policy.operation, "recovered");
+                }
+                return Ok(value);
+            }
+            Err(error) => error,
+        };
+
+        if !(policy.retryable)(&error) {
+            record_retry(&policy.operation, "not_retryable");
+            return Err(error);
+        }
+        if attempt >= policy.max_attempts {
+            record_retry(&policy.operation, "exhausted");
+            tracing::error!(
+                operation = %policy.operation,
+                attempts = attempt,
+                error = %error,
+                "Operation failed, giving up"
+            );
+            return Err(error);
+        }
+
+        let delay = policy.backoff.delay(attempt);
+        let past_deadline = policy
+            .deadline
+            .is_some_and(|deadline| started.elapsed() + delay > deadline);
+        let past_request_deadline = Deadline::current()
+            .remaining()
+            .is_some_and(|left| delay >= left);
+        if past_deadline || past_request_deadline {
+            record_retry(&policy.operation, "deadline");
+            tracing::error!(
+                operation = %policy.operation,
+                attempts = attempt,
+                error = %error,
+                "Operation failed, no time left to retry"
+            );
+            return Err(error);
+        }
+
+        record_retry(&policy.operation, "retried");
+        tracing::warn!(
+            operation = %policy.operation,
+            attempt = attempt,
+            max_attempts = policy.max_attempts,
+            delay_ms = delay.as_millis() as u64,
+            error = %error,
+            "Operation failed, retrying"
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn quick(operation: &str) -> RetryPolicy<String> {
+        RetryPolicy::new(operation).with_backoff(Backoff::Fixed(Duration::from_millis(1)))
+    }
+
+    /// An operation failing `failures` times before it succeeds, counting its calls
+    async fn flaky(calls: &AtomicU32, failures: u32) -> Result<u32, String> {
+        let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+        if call <= failures {
+            Err(format!("failure {}", call))
+        } else {
+            Ok(call)
+        }
+    }
+
+    #[test]
+    fn test_exponential_backoff_is_capped() {
+        let backoff = Backoff::Exponential {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+        };
+        assert_eq!(backoff.delay(1), Duration::from_millis(100));
+        assert_eq!(backoff.delay(3), Duration::from_millis(400));
+        assert_eq!(backoff.delay(10), Duration::from_secs(1));
+        assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(1));
+
+        let jitter = Backoff::ExponentialJitter {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+        };
+        assert!(jitter.delay(2) <= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let calls = AtomicU32::new(0);
+        let result = retry(&quick("test.success"), || flaky(&calls, 2)).await;
+        assert_eq!(result, Ok(3));
+    }
+
+    #[tokio::test]
+    async fn test_returns_last_error_after_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let policy = quick("test.exhausted").with_max_attempts(2);
+        let result = retry(&policy, || flaky(&calls, 5)).await;
+        assert_eq!(result, Err("failure 2".to_string()));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_errors_not_retryable_are_returned_at_once() {
+        let calls = AtomicU32::new(0);
+        let policy = quick("test.predicate").with_retryable(|e: &String| e != "failure 1");
+        let result = retry(&policy, || flaky(&calls, 5)).await;
+        assert_eq!(result, Err("failure 1".to_string()));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_stops_retries() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new("test.deadline")
+            .with_max_attempts(10)
+            .with_backoff(Backoff::Fixed(Duration::from_millis(30)))
+            .with_deadline(Duration::from_millis(50));
+        let result = retry(&policy, || flaky(&calls, 10)).await;
+        assert_eq!(result, Err("failure 2".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_request_deadline_stops_retries() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new("test.request_deadline")
+            .with_max_attempts(10)
+            .with_backoff(Backoff::Fixed(Duration::from_millis(30)));
+        let result = Deadline::after(Duration::from_millis(50))
+            .scope(retry(&policy, || flaky(&calls, 10)))
+            .await;
+        assert_eq!(result, Ok(Err("failure 2".to_string())));
+    }
+}
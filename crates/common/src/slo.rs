@@ -0,0 +1,307 @@
+use lazy_static::lazy_static;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::metrics::{SLO_BURN_RATE, SLO_OBJECTIVE, SLO_RATIO};
+
+/// Windows the ratios and burn rates are reported over, as used by multi-window
+/// burn-rate alerts (a fast window to catch spikes, a slow one to confirm them)
+const WINDOWS: [(&str, u64); 2] = [("5m", 5), ("1h", 60)];
+
+/// Longest window, in one-minute buckets
+const RETAINED_MINUTES: u64 = 60;
+
+/// Service level objectives for one route
+#[derive(Debug, Clone, PartialEq)]
+pub struct SloObjective {
+    /// Fraction of requests that must not fail with a 5xx, e.g. `0.999`
+    pub availability: f64,
+    /// Requests slower than this count against the latency objective
+    pub latency_threshold: Duration,
+    /// Fraction of requests that must finish within `latency_threshold`, e.g. `0.99`
+    pub latency: f64,
+}
+
+impl Default for SloObjective {
+    fn default() -> Self {
+        Self {
+            availability: 0.999,
+            latency_threshold: Duration::from_millis(300),
+            latency: 0.99,
+        }
+    }
+}
+
+impl SloObjective {
+    /// Parse `availability%,latency_ms,latency%`, e.g. `99.9,250,99`
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut parts = spec.split(',').map(str::trim);
+        let availability: f64 = parts.next()?.parse().ok()?;
+        let latency_ms: u64 = parts.next()?.parse().ok()?;
+        let latency: f64 = parts.next()?.parse().ok()?;
+        if parts.next().is_some()
+            || !(0.0..100.0).contains(&availability)
+            || !(0.0..100.0).contains(&latency)
+        {
+            return None;
+        }
+
+        Some(Self {
+            availability: availability / 100.0,
+            latency_threshold: Duration::from_millis(latency_ms),
+            latency: latency / 100.0,
+        })
+    }
+}
+
+/// Objectives per route template, with a default for routes not listed
+#[derive(Debug, Clone, Default)]
+pub struct SloConfig {
+    pub default: SloObjective,
+    pub routes: HashMap<String, SloObjective>,
+}
+
+impl SloConfig {
+    /// Read `SLO_DEFAULT` and `SLO_OBJECTIVES`
+    ///
+    /// `SLO_DEFAULT` is an objective as accepted by [`SloObjective::parse`];
+    /// `SLO_OBJECTIVES` lists per-route overrides as `route=objective` separated by `;`,
+    /// e.g. `/api/v1/orders=99.5,500,95;/api/v1/orders/:id=99.9,100,99`.
+    /// Malformed entries are skipped with a warning.
+    pub fn from_env() -> Self {
+        let default = std::env::var("SLO_DEFAULT")
+            .ok()
+            .and_then(|spec| {
+                let objective = SloObjective::parse(&spec);
+                if objective.is_none() {
+                    tracing::warn!("Ignoring malformed SLO_DEFAULT: {}", spec);
+                }
+                objective
+            })
+            .unwrap_or_default();
+
+        let mut routes = HashMap::new();
+        for entry in std::env::var("SLO_OBJECTIVES")
+            .unwrap_or_default()
+            .split(';')
+        {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+            match entry
+                .split_once('=')
+                .and_then(|(route, spec)| Some((route.trim(), SloObjective::parse(spec)?)))
+            {
+                Some((route, objective)) => {
+                    routes.insert(route.to_string(), objective);
+                }
+                None => tracing::warn!("Ignoring malformed SLO objective: {}", entry),
+            }
+        }
+
+        Self { default, routes }
+    }
+
+    pub fn objective(&self, route: &str) -> &SloObjective {
+        self.routes.get(route).unwrap_or(&self.default)
+    }
+}
+
+/// Requests seen in one minute
+#[derive(Debug, Clone, Copy, Default)]
+struct Bucket {
+    minute: u64,
+    total: u64,
+    failed: u64,
+    slow: u64,
+}
+
+/// Windowed request counts per route, from which the SLO gauges are derived
+#[derive(Default)]
+struct SloTracker {
+    routes: HashMap<String, VecDeque<Bucket>>,
+}
+
+/// Good-request ratio and burn rate for one objective over one window
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Compliance {
+    ratio: f64,
+    burn_rate: f64,
+}
+
+impl SloTracker {
+    /// Count a request and return the route's compliance per window:
+    /// `(window, availability, latency)`
+    fn record(
+        &mut self,
+        route: &str,
+        objective: &SloObjective,
+        failed: bool,
+        slow: bool,
+        minute: u64,
+    ) -> Vec<(&'static str, Compliance, Compliance)> {
+        let buckets = self.routes.entry(route.to_string()).or_default();
+        while buckets
+            .front()
+            .is_some_and(|bucket| bucket.minute + RETAINED_MINUTES <= minute)
+        {
+            buckets.pop_front();
+        }
+        if buckets.back().is_none_or(|bucket| bucket.minute != minute) {
+            buckets.push_back(Bucket {
+                minute,
+                ..Bucket::default()
+            });
+        }
+        if let Some(bucket) = buckets.back_mut() {
+            bucket.total += 1;
+            bucket.failed += failed as u64;
+            bucket.slow += slow as u64;
+        }
+
+        WINDOWS
+            .iter()
+            .map(|&(window, minutes)| {
+                let (total, failed, slow) = buckets
+                    .iter()
+                    .filter(|bucket| bucket.minute + minutes > minute)
+                    .fold((0, 0, 0), |(total, failed, slow), bucket| {
+                        (
+                            total + bucket.total,
+                            failed + bucket.failed,
+                            slow + bucket.slow,
+                        )
+                    });
+                (
+                    window,
+                    compliance(total, failed, objective.availability),
+                    compliance(total, slow, objective.latency),
+                )
+            })
+            .collect()
+    }
+}
+
+/// How fast the error budget of `objective` is being spent: 1.0 uses it up exactly
+/// over the SLO period, 14.4 over a 30-day period exhausts it in two days
+fn compliance(total: u64, bad: u64, objective: f64) -> Compliance {
+    let ratio = if total == 0 {
+        1.0
+    } else {
+        1.0 - bad as f64 / total as f64
+    };
+    let budget = (1.0 - objective).max(f64::EPSILON);
+    Compliance {
+        ratio,
+        burn_rate: (1.0 - ratio) / budget,
+    }
+}
+
+lazy_static! {
+    static ref CONFIG: RwLock<SloConfig> = RwLock::new(SloConfig::default());
+    static ref TRACKER: Mutex<SloTracker> = Mutex::new(SloTracker::default());
+}
+
+/// Replace the objectives requests are measured against
+pub fn configure(config: SloConfig) {
+    for (route, objective) in &config.routes {
+        tracing::info!(
+            "SLO for {}: {}% available, {}% within {}ms",
+            route,
+            objective.availability * 100.0,
+            objective.latency * 100.0,
+            objective.latency_threshold.as_millis()
+        );
+    }
+    if let Ok(mut current) = CONFIG.write() {
+        *current = config;
+    }
+}
+
+/// Count a finished request against its route's objectives and update the SLO gauges
+pub fn record(route: &str, status: u16, latency: Duration) {
+    let objective = match CONFIG.read() {
+        Ok(config) => config.objective(route).clone(),
+        Err(_) => return,
+    };
+    let minute = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 60;
+
+    let windows = match TRACKER.lock() {
+        Ok(mut tracker) => tracker.record(
+            route,
+            &objective,
+            status >= 500,
+            latency > objective.latency_threshold,
+            minute,
+        ),
+        Err(_) => return,
+    };
+
+    SLO_OBJECTIVE
+        .with_label_values(&[route, "availability"])
+        .set(objective.availability);
+    SLO_OBJECTIVE
+        .with_label_values(&[route, "latency"])
+        .set(objective.latency);
+    for (window, availability, latency) in windows {
+        for (slo, compliance) in [("availability", availability), ("latency", latency)] {
+            SLO_RATIO
+                .with_label_values(&[route, slo, window])
+                .set(compliance.ratio);
+            SLO_BURN_RATE
+                .with_label_values(&[route, slo, window])
+                .set(compliance.burn_rate);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_objective() {
+        let objective = SloObjective::parse("99.9, 250, 99").unwrap();
+        assert!((objective.availability - 0.999).abs() < 1e-9);
+        assert_eq!(objective.latency_threshold, Duration::from_millis(250));
+        assert!((objective.latency - 0.99).abs() < 1e-9);
+
+        assert_eq!(SloObjective::parse("99.9,250"), None);
+        assert_eq!(SloObjective::parse("120,250,99"), None);
+    }
+
+    #[test]
+    fn test_burn_rate_relative_to_budget() {
+        // 1% failing against a 99.9% objective spends the budget ten times too fast
+        let spent = compliance(1_000, 10, 0.999);
+        assert!((spent.ratio - 0.99).abs() < 1e-9);
+        assert!((spent.burn_rate - 10.0).abs() < 1e-6);
+
+        assert_eq!(compliance(0, 0, 0.999).burn_rate, 0.0);
+    }
+
+    #[test]
+    fn test_windows_forget_old_minutes() {
+        let mut tracker = SloTracker::default();
+        let objective = SloObjective::default();
+
+        tracker.record("/orders", &objective, true, false, 100);
+        let windows = tracker.record("/orders", &objective, false, false, 110);
+
+        let (_, five_minutes, _) = windows[0];
+        let (_, hour, _) = windows[1];
+        assert_eq!(five_minutes.ratio, 1.0);
+        assert_eq!(hour.ratio, 0.5);
+
+        let windows = tracker.record("/orders", &objective, false, true, 169);
+        let (_, hour, latency) = windows[1];
+        assert_eq!(hour.ratio, 1.0);
+        assert_eq!(latency.ratio, 0.5);
+    }
+}
@@ -2,6 +2,8 @@ use opentelemetry::global;
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
+use crate::redaction::{RedactingMakeWriter, Redactor};
+
 /// Telemetry configuration
 #[derive(Debug, Clone)]
 pub struct TelemetryConfig {
@@ -30,11 +32,18 @@ pub fn init_telemetry(config: TelemetryConfig) -> Result<(), Box<dyn std::error:
     let env_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new(&config.log_level));
 
+    // Event fields sit at the top level and span fields (e.g. correlation_id from
+    // `request_log`) under `span`, the same in every service. Personal data is masked
+    // on the way out (see `redaction`); spans exported to Jaeger are not.
     let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_writer(RedactingMakeWriter::new(std::io::stdout, Redactor::from_env()))
         .with_target(true)
         .with_level(true)
         .with_thread_ids(true)
-        .json();
+        .json()
+        .flatten_event(true)
+        .with_current_span(true)
+        .with_span_list(false);
 
     // Build subscriber with or without Jaeger tracing
     if config.enable_jaeger {
@@ -0,0 +1,211 @@
+//! No-panic policy for the crates under `crates/` and the services under `services/`
+//!
+//! Code returns typed errors instead of calling `.unwrap()` or `.expect(..)`, so a
+//! bad payload or a misbehaving clock fails one request rather than the process.
+//! Test modules and `bin/` targets are exempt, and so is unwrapping a lock guard:
+//! a poisoned lock means another thread already panicked while holding it. So are
+//! `lazy_static!` blocks, whose metric registrations only fail on a duplicate name.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Calls that may be followed by `.unwrap()` or `.expect(..)`
+const ALLOWED_BEFORE_UNWRAP: &[&str] = &[".lock()", ".read()", ".write()"];
+
+/// Calls that panic on an error or a missing value
+const PANICKING_CALLS: &[&str] = &[".unwrap()", ".expect("];
+
+/// Directories holding the workspace's crates and services
+const SOURCE_ROOTS: &[&str] = &["crates", "services"];
+
+fn workspace_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+/// Sources of every crate, without `bin/` targets
+fn library_sources(dir: &Path, files: &mut Vec<PathBuf>) {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("failed to read {}: {}", dir.display(), e))
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "bin") {
+                library_sources(&path, files);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+/// Byte offset just past the `}` closing the block opened at `open`
+///
+/// Braces inside string and character literals are skipped.
+fn block_end(source: &[u8], open: usize) -> usize {
+    let (mut depth, mut i) = (0, open);
+    while i < source.len() {
+        match source[i] {
+            b'"' => {
+                i += 1;
+                while i < source.len() && source[i] != b'"' {
+                    i += if source[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'\'' if source.get(i + 2) == Some(&b'\'') => i += 2,
+            b'\'' if source.get(i + 1) == Some(&b'\\') => {
+                while i + 1 < source.len() && source[i + 1] != b'\'' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    source.len()
+}
+
+/// The source with `#[cfg(test)]` modules and `lazy_static!` blocks blanked out,
+/// keeping line numbers
+fn without_exempt_blocks(source: &str) -> String {
+    let mut stripped = source.to_string();
+    for marker in ["#[cfg(test)]", "lazy_static!"] {
+        stripped = blank_blocks(&stripped, marker);
+    }
+    stripped
+}
+
+/// Blank out the block after each `marker`: a module for an attribute, the
+/// braces of a macro call otherwise
+fn blank_blocks(source: &str, marker: &str) -> String {
+    let mut stripped = source.to_string();
+    let mut from = 0;
+    while let Some(found) = stripped[from..].find(marker) {
+        let start = from + found;
+        let rest = stripped[start + marker.len()..].trim_start();
+        let exempt = match marker {
+            "#[cfg(test)]" => rest.starts_with("mod ") || rest.starts_with("pub mod "),
+            _ => rest.starts_with('{'),
+        };
+        let open = stripped[start..].find('{').map(|offset| start + offset);
+        match open {
+            Some(open) if exempt => {
+                let end = block_end(stripped.as_bytes(), open);
+                let blank: String = stripped[start..end]
+                    .chars()
+                    .map(|c| if c == '\n' { '\n' } else { ' ' })
+                    .collect();
+                stripped.replace_range(start..end, &blank);
+                from = end;
+            }
+            _ => from = start + 1,
+        }
+    }
+    stripped
+}
+
+/// `path:line` of every `.unwrap()` or `.expect(..)` the policy does not allow
+fn violations(path: &Path, source: &str) -> Vec<String> {
+    let source = without_exempt_blocks(source);
+    let code: String = source
+        .lines()
+        .map(|line| if line.trim_start().starts_with("//") { "" } else { line })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut found: Vec<usize> = PANICKING_CALLS
+        .iter()
+        .flat_map(|call| code.match_indices(call))
+        .map(|(at, _)| at)
+        .filter(|at| {
+            let before = code[..*at].trim_end();
+            !ALLOWED_BEFORE_UNWRAP.iter().any(|call| before.ends_with(call))
+        })
+        .collect();
+    found.sort();
+    found
+        .into_iter()
+        .map(|at| {
+            let line = code[..at].matches('\n').count() + 1;
+            format!("{}:{}", path.display(), line)
+        })
+        .collect()
+}
+
+#[test]
+fn test_crates_and_services_do_not_panic() {
+    let root = workspace_dir();
+    let mut found = Vec::new();
+    let mut crates: Vec<PathBuf> = SOURCE_ROOTS
+        .iter()
+        .flat_map(|dir| {
+            let dir = root.join(dir);
+            fs::read_dir(&dir)
+                .unwrap_or_else(|e| panic!("failed to read {}: {}", dir.display(), e))
+        })
+        .filter_map(|entry| entry.ok().map(|entry| entry.path().join("src")))
+        .filter(|src| src.is_dir())
+        .collect();
+    crates.sort();
+
+    for src in crates {
+        let mut files = Vec::new();
+        library_sources(&src, &mut files);
+        for file in files {
+            let source = fs::read_to_string(&file)
+                .unwrap_or_else(|e| panic!("failed to read {}: {}", file.display(), e));
+            found.extend(violations(file.strip_prefix(&root).unwrap_or(&file), &source));
+        }
+    }
+
+    assert!(
+        found.is_empty(),
+        "`.unwrap()` or `.expect(..)` outside tests; return an error instead:\n  {}",
+        found.join("\n  ")
+    );
+}
+
+#[test]
+fn test_policy_ignores_tests_comments_and_lock_guards() {
+    let source = r#"
+lazy_static! {
+    static ref TOTAL: Counter = register_counter!("total", "Total").expect("registers");
+}
+
+fn load(map: &Mutex<Map>) -> Option<u32> {
+    // value.unwrap() in a comment
+    let guard = map
+        .lock()
+        .unwrap();
+    let text = "{";
+    guard.get().copied()
+}
+
+fn parse(text: &str) -> u32 {
+    text.parse().unwrap()
+}
+
+fn first(items: &[u32]) -> u32 {
+    *items.first().expect("not empty")
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("1").to_string().parse::<u32>().unwrap(), 1);
+    }
+}
+"#;
+
+    assert_eq!(violations(Path::new("lib.rs"), source), ["lib.rs:16", "lib.rs:20"]);
+}
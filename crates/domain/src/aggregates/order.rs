@@ -130,6 +130,15 @@ impl OrderAggregate {
         self.version += 1;
     }
 
+    /// Apply OrderItemPriceCorrected event
+    pub fn apply_order_item_price_corrected(&mut self, event: &OrderItemPriceCorrectedEvent) {
+        for item in self.items.iter_mut().filter(|i| i.product_id == event.product_id) {
+            item.unit_price = event.corrected_unit_price;
+        }
+        self.total_amount = event.total_amount;
+        self.version += 1;
+    }
+
     /// Confirm order
     pub fn confirm(&self) -> Result<OrderConfirmedEvent, OrderError> {
         match self.status {
@@ -191,6 +200,49 @@ impl OrderAggregate {
             }),
         }
     }
+
+    /// Correct the unit price an item was recorded with, in any status
+    pub fn correct_item_price(
+        &self,
+        product_id: Uuid,
+        corrected_unit_price: f64,
+        reason: String,
+    ) -> Result<OrderItemPriceCorrectedEvent, OrderError> {
+        if corrected_unit_price <= 0.0 {
+            return Err(OrderError::InvalidPrice);
+        }
+        let item = self
+            .items
+            .iter()
+            .find(|i| i.product_id == product_id)
+            .ok_or(OrderError::ItemNotFound(product_id))?;
+        if item.unit_price == corrected_unit_price {
+            return Err(OrderError::PriceUnchanged);
+        }
+
+        let total_amount = self
+            .items
+            .iter()
+            .map(|i| {
+                if i.product_id == product_id {
+                    corrected_unit_price * i.quantity as f64
+                } else {
+                    i.total_price()
+                }
+            })
+            .sum();
+
+        Ok(OrderItemPriceCorrectedEvent {
+            order_id: self.id,
+            product_id,
+            previous_unit_price: item.unit_price,
+            corrected_unit_price,
+            previous_total_amount: self.total_amount,
+            total_amount,
+            reason,
+            corrected_at: Utc::now(),
+        })
+    }
 }
 
 impl Default for OrderAggregate {
@@ -224,6 +276,12 @@ pub enum OrderError {
 
     #[error("Order is cancelled")]
     OrderCancelled,
+
+    #[error("Order has no item for product {0}")]
+    ItemNotFound(Uuid),
+
+    #[error("Corrected price is the same as the recorded one")]
+    PriceUnchanged,
 }
 
 #[cfg(test)]
@@ -402,4 +460,41 @@ mod tests {
         let result = aggregate.deliver();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_correct_item_price() {
+        let corrected_product = Uuid::new_v4();
+        let items = vec![
+            OrderItem::new(corrected_product, "SKU-001".to_string(), 2, 10.0),
+            OrderItem::new(Uuid::new_v4(), "SKU-002".to_string(), 1, 5.0),
+        ];
+        let (mut aggregate, _) = OrderAggregate::create(Uuid::new_v4(), items).unwrap();
+        aggregate.status = OrderStatus::Delivered;
+
+        let event = aggregate
+            .correct_item_price(corrected_product, 8.5, "Wrong list price".to_string())
+            .unwrap();
+        assert_eq!(event.previous_unit_price, 10.0);
+        assert_eq!(event.previous_total_amount, 25.0);
+        assert_eq!(event.total_amount, 22.0);
+
+        aggregate.apply_order_item_price_corrected(&event);
+        assert_eq!(aggregate.items[0].unit_price, 8.5);
+        assert_eq!(aggregate.items[1].unit_price, 5.0);
+        assert_eq!(aggregate.total_amount, 22.0);
+        assert_eq!(aggregate.version, 1);
+
+        assert!(matches!(
+            aggregate.correct_item_price(corrected_product, 8.5, "Again".to_string()),
+            Err(OrderError::PriceUnchanged)
+        ));
+        assert!(matches!(
+            aggregate.correct_item_price(corrected_product, 0.0, "Free".to_string()),
+            Err(OrderError::InvalidPrice)
+        ));
+        assert!(matches!(
+            aggregate.correct_item_price(Uuid::new_v4(), 1.0, "Unknown".to_string()),
+            Err(OrderError::ItemNotFound(_))
+        ));
+    }
 }
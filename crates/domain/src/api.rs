@@ -0,0 +1,158 @@
+//! Error body shared by the HTTP APIs
+//!
+//! The command service answers every failed request with an [`ErrorResponse`] and
+//! clients decode it with the same types, so its schema is published under
+//! `schemas/responses/` alongside the other response contracts.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Error body returned by every command endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+    /// Per-field validation failures; omitted for other errors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+    /// What another writer appended first; only set on concurrency conflicts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<ConflictDetails>,
+}
+
+impl ErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self {
+            error: error.into(),
+            details: Vec::new(),
+            conflict: None,
+        }
+    }
+
+    /// Concurrency conflict describing the events that got in first
+    pub fn conflict(error: impl Into<String>, conflict: ConflictDetails) -> Self {
+        Self {
+            error: error.into(),
+            details: Vec::new(),
+            conflict: Some(conflict),
+        }
+    }
+
+    /// Validation failure listing every field that failed
+    pub fn validation(details: Vec<FieldError>) -> Self {
+        Self {
+            error: "Validation error".to_string(),
+            details,
+            conflict: None,
+        }
+    }
+}
+
+/// A single failed validation rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct FieldError {
+    /// Path of the field, e.g. `items[0].quantity`
+    pub field: String,
+    /// Rule that failed, e.g. `length` or `range`
+    pub code: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            code: code.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// An event another writer appended after the version a failed append expected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ConflictingEvent {
+    pub event_type: String,
+    pub version: i64,
+}
+
+/// Whether the events that won a concurrency race already did what the command wanted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// Another writer appended the event this command would have
+    AlreadyApplied,
+    /// Something else changed the aggregate; worth retrying against the new state
+    Contention,
+}
+
+impl ConflictKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConflictKind::AlreadyApplied => "already_applied",
+            ConflictKind::Contention => "contention",
+        }
+    }
+}
+
+/// Why a command lost an optimistic concurrency race
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ConflictDetails {
+    pub kind: ConflictKind,
+    pub expected_version: i64,
+    pub actual_version: i64,
+    pub conflicting_events: Vec<ConflictingEvent>,
+}
+
+impl ConflictDetails {
+    /// Classify a conflict for a command that tried to append `event_type`
+    pub fn new(
+        event_type: &str,
+        expected_version: i64,
+        actual_version: i64,
+        conflicting_events: Vec<ConflictingEvent>,
+    ) -> Self {
+        let already_applied = conflicting_events
+            .iter()
+            .any(|event| event.event_type == event_type);
+        Self {
+            kind: if already_applied {
+                ConflictKind::AlreadyApplied
+            } else {
+                ConflictKind::Contention
+            },
+            expected_version,
+            actual_version,
+            conflicting_events,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_details_omitted_for_plain_errors() {
+        let json = serde_json::to_value(ErrorResponse::new("Order not found")).unwrap();
+        assert_eq!(json, serde_json::json!({"error": "Order not found"}));
+    }
+
+    #[test]
+    fn test_conflict_kind() {
+        let confirmed = vec![ConflictingEvent {
+            event_type: "OrderConfirmed".to_string(),
+            version: 2,
+        }];
+        assert_eq!(
+            ConflictDetails::new("OrderConfirmed", 1, 2, confirmed.clone()).kind,
+            ConflictKind::AlreadyApplied
+        );
+        assert_eq!(
+            ConflictDetails::new("OrderCancelled", 1, 2, confirmed).kind,
+            ConflictKind::Contention
+        );
+        assert_eq!(
+            serde_json::to_value(ConflictKind::AlreadyApplied).unwrap(),
+            ConflictKind::AlreadyApplied.as_str()
+        );
+    }
+}
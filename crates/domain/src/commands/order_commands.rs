@@ -1,21 +1,26 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
 /// Command to create a new order
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 pub struct CreateOrderCommand {
     pub customer_id: Uuid,
 
-    #[validate(length(min = 1, message = "Order must have at least one item"))]
+    #[validate(length(min = 1, message = "Order must have at least one item"), nested)]
     pub items: Vec<CreateOrderItem>,
 
     #[validate(nested)]
     pub shipping_address: ShippingAddress,
+
+    /// Optional client-supplied ID used to deduplicate retried commands
+    #[serde(default)]
+    pub command_id: Option<Uuid>,
 }
 
 /// Order item in the create order command
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 pub struct CreateOrderItem {
     pub product_id: Uuid,
 
@@ -30,7 +35,7 @@ pub struct CreateOrderItem {
 }
 
 /// Shipping address for the order
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 pub struct ShippingAddress {
     #[validate(length(min = 1, message = "Street cannot be empty"))]
     pub street: String,
@@ -49,22 +54,29 @@ pub struct ShippingAddress {
 }
 
 /// Command to confirm an order
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ConfirmOrderCommand {
     pub order_id: Uuid,
+    /// Optional client-supplied ID used to deduplicate retried commands
+    #[serde(default)]
+    pub command_id: Option<Uuid>,
 }
 
 /// Command to cancel an order
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 pub struct CancelOrderCommand {
     pub order_id: Uuid,
 
     #[validate(length(min = 1, message = "Cancellation reason cannot be empty"))]
     pub reason: String,
+
+    /// Optional client-supplied ID used to deduplicate retried commands
+    #[serde(default)]
+    pub command_id: Option<Uuid>,
 }
 
 /// Command to ship an order
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 pub struct ShipOrderCommand {
     pub order_id: Uuid,
 
@@ -73,12 +85,19 @@ pub struct ShipOrderCommand {
 
     #[validate(length(min = 1, message = "Carrier cannot be empty"))]
     pub carrier: String,
+
+    /// Optional client-supplied ID used to deduplicate retried commands
+    #[serde(default)]
+    pub command_id: Option<Uuid>,
 }
 
 /// Command to mark an order as delivered
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DeliverOrderCommand {
     pub order_id: Uuid,
+    /// Optional client-supplied ID used to deduplicate retried commands
+    #[serde(default)]
+    pub command_id: Option<Uuid>,
 }
 
 #[cfg(test)]
@@ -102,6 +121,7 @@ mod tests {
                 zip: "62701".to_string(),
                 country: "US".to_string(),
             },
+            command_id: None,
         };
 
         assert!(cmd.validate().is_ok());
@@ -119,6 +139,7 @@ mod tests {
                 zip: "62701".to_string(),
                 country: "US".to_string(),
             },
+            command_id: None,
         };
 
         assert!(cmd.validate().is_err());
@@ -166,6 +187,7 @@ mod tests {
         let cmd = CancelOrderCommand {
             order_id: Uuid::new_v4(),
             reason: "".to_string(),
+            command_id: None,
         };
 
         assert!(cmd.validate().is_err());
@@ -177,9 +199,17 @@ mod tests {
             order_id: Uuid::new_v4(),
             tracking_number: "1Z999AA10123456784".to_string(),
             carrier: "UPS".to_string(),
+            command_id: None,
         };
 
         assert!(cmd.validate().is_ok());
     }
+
+    #[test]
+    fn test_command_id_defaults_to_none() {
+        let cmd: ConfirmOrderCommand =
+            serde_json::from_value(serde_json::json!({"order_id": Uuid::new_v4()})).unwrap();
+        assert!(cmd.command_id.is_none());
+    }
 }
 
@@ -0,0 +1,53 @@
+use super::DomainEvent;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Event emitted by the customer service when a customer signs up
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CustomerRegisteredEvent {
+    pub customer_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub registered_at: DateTime<Utc>,
+}
+
+impl DomainEvent for CustomerRegisteredEvent {
+    fn event_type() -> &'static str {
+        "CustomerRegistered"
+    }
+}
+
+/// Event emitted when a customer changes their name or email
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CustomerProfileUpdatedEvent {
+    pub customer_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DomainEvent for CustomerProfileUpdatedEvent {
+    fn event_type() -> &'static str {
+        "CustomerProfileUpdated"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_customer_registered_event() {
+        let event = CustomerRegisteredEvent {
+            customer_id: Uuid::new_v4(),
+            name: "Ada Lovelace".to_string(),
+            email: "ada@example.com".to_string(),
+            registered_at: Utc::now(),
+        };
+
+        assert_eq!(CustomerRegisteredEvent::event_type(), "CustomerRegistered");
+        assert_eq!(event.email, "ada@example.com");
+    }
+}
@@ -1,10 +1,11 @@
 use super::DomainEvent;
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Event emitted when inventory is reserved for an order
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InventoryReservedEvent {
     pub reservation_id: Uuid,
     pub order_id: Uuid,
@@ -19,7 +20,7 @@ impl DomainEvent for InventoryReservedEvent {
 }
 
 /// Event emitted when reserved inventory is released (compensation)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InventoryReleasedEvent {
     pub reservation_id: Uuid,
     pub order_id: Uuid,
@@ -35,7 +36,7 @@ impl DomainEvent for InventoryReleasedEvent {
 }
 
 /// Event emitted when inventory reservation fails
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InventoryReservationFailedEvent {
     pub order_id: Uuid,
     pub items: Vec<InventoryItem>,
@@ -50,7 +51,7 @@ impl DomainEvent for InventoryReservationFailedEvent {
 }
 
 /// Event emitted when stock is replenished
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StockReplenishedEvent {
     pub product_id: Uuid,
     pub sku: String,
@@ -64,7 +65,7 @@ impl DomainEvent for StockReplenishedEvent {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InventoryItem {
     pub product_id: Uuid,
     pub sku: String,
@@ -1,13 +1,18 @@
 pub mod order_events;
+pub mod customer_events;
 pub mod inventory_events;
 pub mod payment_events;
+pub mod product_events;
+pub mod stream_events;
+pub mod saga_events;
 
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Base event envelope wrapping all domain events
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EventEnvelope {
     pub event_id: Uuid,
     pub aggregate_id: Uuid,
@@ -16,37 +21,107 @@ pub struct EventEnvelope {
     pub event_version: i32,
     pub payload: serde_json::Value,
     pub metadata: EventMetadata,
+    /// When the event was raised; `created_at` once stored
     pub timestamp: DateTime<Utc>,
+    /// Aggregate version; unset until the event is stored
     pub sequence_number: Option<i64>,
 }
 
 impl EventEnvelope {
-    /// Create a new event envelope from a domain event
-    pub fn new<T: DomainEvent>(
+    /// Wrap a domain event, serializing it as the payload
+    pub fn from_domain_event<T: DomainEvent>(
         aggregate_id: Uuid,
-        aggregate_type: String,
-        event: T,
+        aggregate_type: &str,
+        event: &T,
         metadata: EventMetadata,
-    ) -> Self {
-        Self {
+    ) -> Result<Self, serde_json::Error> {
+        Ok(Self {
             event_id: Uuid::new_v4(),
             aggregate_id,
-            aggregate_type,
+            aggregate_type: aggregate_type.to_string(),
             event_type: T::event_type().to_string(),
             event_version: T::event_version(),
-            payload: serde_json::to_value(&event).unwrap(),
+            payload: serde_json::to_value(event)?,
             metadata,
             timestamp: Utc::now(),
             sequence_number: None,
+        })
+    }
+
+    /// Start building an envelope for an event of the given aggregate
+    pub fn builder(aggregate_id: Uuid, aggregate_type: impl Into<String>) -> EventEnvelopeBuilder {
+        EventEnvelopeBuilder {
+            aggregate_id,
+            aggregate_type: aggregate_type.into(),
+            metadata: EventMetadata::new(),
+            sequence_number: None,
         }
     }
+
+    /// Stamp the envelope with the aggregate version the event was stored at
+    pub fn with_sequence_number(mut self, sequence_number: i64) -> Self {
+        self.sequence_number = Some(sequence_number);
+        self
+    }
+}
+
+/// Builder for [`EventEnvelope`]; metadata defaults to [`EventMetadata::new`]
+#[derive(Debug, Clone)]
+pub struct EventEnvelopeBuilder {
+    aggregate_id: Uuid,
+    aggregate_type: String,
+    metadata: EventMetadata,
+    sequence_number: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl EventEnvelopeBuilder {
+    pub fn with_metadata(mut self, metadata: EventMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Aggregate version the event will be stored at
+    pub fn with_sequence_number(mut self, sequence_number: i64) -> Self {
+        self.sequence_number = Some(sequence_number);
+        self
+    }
+
+    /// Wrap the event, failing only if it cannot be serialized
+    pub fn build<T: DomainEvent>(self, event: &T) -> Result<EventEnvelope, serde_json::Error> {
+        let mut envelope =
+            EventEnvelope::from_domain_event(self.aggregate_id, &self.aggregate_type, event, self.metadata)?;
+        envelope.sequence_number = self.sequence_number;
+        Ok(envelope)
+    }
+}
+
+const RESERVED_METADATA_FIELDS: [&str; 6] = [
+    "correlation_id",
+    "causation_id",
+    "user_id",
+    "command_id",
+    "corrects_event_id",
+    "idempotency_key",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct EventMetadata {
     pub correlation_id: Uuid,
     pub causation_id: Uuid,
     pub user_id: Option<Uuid>,
+    /// Client-supplied ID of the command that produced the event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_id: Option<Uuid>,
+    /// Event this one corrects; the corrected event itself is never modified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub corrects_event_id: Option<Uuid>,
+    /// Key consumers deduplicate the event's side effects by; the same for every
+    /// retry of the operation that published it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// Custom fields, stored alongside the standard ones
+    #[serde(flatten, default)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl EventMetadata {
@@ -57,6 +132,10 @@ impl EventMetadata {
             correlation_id: id,
             causation_id: id,
             user_id: None,
+            command_id: None,
+            corrects_event_id: None,
+            idempotency_key: None,
+            extra: serde_json::Map::new(),
         }
     }
 
@@ -66,6 +145,10 @@ impl EventMetadata {
             correlation_id,
             causation_id: Uuid::new_v4(),
             user_id: None,
+            command_id: None,
+            corrects_event_id: None,
+            idempotency_key: None,
+            extra: serde_json::Map::new(),
         }
     }
 
@@ -74,6 +157,33 @@ impl EventMetadata {
         self.user_id = Some(user_id);
         self
     }
+
+    /// Add the originating command ID to metadata
+    pub fn with_command_id(mut self, command_id: Option<Uuid>) -> Self {
+        self.command_id = command_id;
+        self
+    }
+
+    /// Add the key consumers deduplicate the event by
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Mark the event as a correction of the stored event `event_id`
+    pub fn correcting(mut self, event_id: Uuid) -> Self {
+        self.corrects_event_id = Some(event_id);
+        self
+    }
+
+    /// Add a custom field; standard field names are ignored so they can't be overwritten
+    pub fn with_extra(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        let key = key.into();
+        if !RESERVED_METADATA_FIELDS.contains(&key.as_str()) {
+            self.extra.insert(key, value);
+        }
+        self
+    }
 }
 
 impl Default for EventMetadata {
@@ -83,7 +193,10 @@ impl Default for EventMetadata {
 }
 
 /// Trait for all domain events
-pub trait DomainEvent: Serialize + for<'de> Deserialize<'de> {
+///
+/// The `JsonSchema` bound keeps every payload covered by the published schemas
+/// (see [`crate::schema`]).
+pub trait DomainEvent: Serialize + for<'de> Deserialize<'de> + JsonSchema {
     /// Get the event type name
     fn event_type() -> &'static str;
 
@@ -99,23 +212,57 @@ pub trait DomainEvent: Serialize + for<'de> Deserialize<'de> {
         aggregate_type: &str,
         metadata: EventMetadata,
     ) -> Result<EventEnvelope, serde_json::Error> {
-        Ok(EventEnvelope {
-            event_id: Uuid::new_v4(),
-            aggregate_id,
-            aggregate_type: aggregate_type.to_string(),
-            event_type: Self::event_type().to_string(),
-            event_version: Self::event_version(),
-            payload: serde_json::to_value(self)?,
-            metadata,
-            timestamp: Utc::now(),
-            sequence_number: None,
-        })
+        EventEnvelope::from_domain_event(aggregate_id, aggregate_type, self, metadata)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::events::order_events::OrderConfirmedEvent;
+
+    fn confirmed(order_id: Uuid) -> OrderConfirmedEvent {
+        OrderConfirmedEvent {
+            order_id,
+            confirmed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_envelope_from_domain_event() {
+        let order_id = Uuid::new_v4();
+        let metadata = EventMetadata::new();
+        let envelope =
+            EventEnvelope::from_domain_event(order_id, "Order", &confirmed(order_id), metadata.clone()).unwrap();
+
+        assert_eq!(envelope.aggregate_id, order_id);
+        assert_eq!(envelope.aggregate_type, "Order");
+        assert_eq!(envelope.event_type, OrderConfirmedEvent::event_type());
+        assert_eq!(envelope.event_version, OrderConfirmedEvent::event_version());
+        assert_eq!(envelope.payload["order_id"], order_id.to_string());
+        assert_eq!(envelope.metadata.correlation_id, metadata.correlation_id);
+        assert!(envelope.sequence_number.is_none());
+    }
+
+    #[test]
+    fn test_envelope_builder() {
+        let order_id = Uuid::new_v4();
+        let command_id = Uuid::new_v4();
+        let envelope = EventEnvelope::builder(order_id, "Order")
+            .with_metadata(EventMetadata::new().with_command_id(Some(command_id)))
+            .with_sequence_number(3)
+            .build(&confirmed(order_id))
+            .unwrap();
+
+        assert_eq!(envelope.sequence_number, Some(3));
+        assert_eq!(envelope.metadata.command_id, Some(command_id));
+
+        let defaulted = EventEnvelope::builder(order_id, "Order")
+            .build(&confirmed(order_id))
+            .unwrap();
+        assert_eq!(defaulted.metadata.correlation_id, defaulted.metadata.causation_id);
+        assert_ne!(defaulted.event_id, envelope.event_id);
+    }
 
     #[test]
     fn test_event_metadata_new() {
@@ -138,4 +285,49 @@ mod tests {
         let metadata = EventMetadata::new().with_user(user_id);
         assert_eq!(metadata.user_id, Some(user_id));
     }
+
+    #[test]
+    fn test_event_metadata_extra_fields_are_flattened() {
+        let metadata = EventMetadata::new().with_extra("tenant", serde_json::json!("acme"));
+        let value = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(value["tenant"], "acme");
+        assert!(value.get("extra").is_none());
+
+        let parsed: EventMetadata = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, metadata);
+
+        let correlation_id = metadata.correlation_id;
+        let metadata = metadata.with_extra("correlation_id", serde_json::json!("overwritten"));
+        assert_eq!(metadata.correlation_id, correlation_id);
+        assert!(!metadata.extra.contains_key("correlation_id"));
+    }
+
+    #[test]
+    fn test_event_metadata_command_id_is_optional() {
+        let metadata = EventMetadata::new();
+        let value = serde_json::to_value(&metadata).unwrap();
+        assert!(value.get("command_id").is_none());
+
+        let command_id = Uuid::new_v4();
+        let metadata = EventMetadata::new().with_command_id(Some(command_id));
+        let value = serde_json::to_value(&metadata).unwrap();
+        let parsed: EventMetadata = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.command_id, Some(command_id));
+    }
+
+    #[test]
+    fn test_event_metadata_correcting() {
+        let metadata = EventMetadata::new();
+        let value = serde_json::to_value(&metadata).unwrap();
+        assert!(value.get("corrects_event_id").is_none());
+
+        let corrected = Uuid::new_v4();
+        let metadata = metadata.correcting(corrected);
+        let value = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(value["corrects_event_id"], corrected.to_string());
+
+        let parsed: EventMetadata = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.corrects_event_id, Some(corrected));
+        assert!(parsed.extra.is_empty());
+    }
 }
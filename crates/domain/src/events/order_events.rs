@@ -1,9 +1,10 @@
 use super::DomainEvent;
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct OrderItem {
     pub product_id: Uuid,
     pub sku: String,
@@ -26,7 +27,7 @@ impl OrderItem {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OrderCreatedEvent {
     pub order_id: Uuid,
     pub customer_id: Uuid,
@@ -43,7 +44,7 @@ impl DomainEvent for OrderCreatedEvent {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OrderConfirmedEvent {
     pub order_id: Uuid,
     pub confirmed_at: DateTime<Utc>,
@@ -55,7 +56,7 @@ impl DomainEvent for OrderConfirmedEvent {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OrderCancelledEvent {
     pub order_id: Uuid,
     pub reason: String,
@@ -68,7 +69,7 @@ impl DomainEvent for OrderCancelledEvent {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OrderShippedEvent {
     pub order_id: Uuid,
     pub tracking_number: String,
@@ -82,7 +83,7 @@ impl DomainEvent for OrderShippedEvent {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OrderDeliveredEvent {
     pub order_id: Uuid,
     pub delivered_at: DateTime<Utc>,
@@ -94,6 +95,29 @@ impl DomainEvent for OrderDeliveredEvent {
     }
 }
 
+/// Corrects the unit price an order item was recorded with
+///
+/// Published with [`EventMetadata::correcting`](super::EventMetadata::correcting)
+/// pointing at the event that recorded the wrong price, which stays in the log as is.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OrderItemPriceCorrectedEvent {
+    pub order_id: Uuid,
+    pub product_id: Uuid,
+    pub previous_unit_price: f64,
+    pub corrected_unit_price: f64,
+    pub previous_total_amount: f64,
+    /// Order total with the corrected price applied
+    pub total_amount: f64,
+    pub reason: String,
+    pub corrected_at: DateTime<Utc>,
+}
+
+impl DomainEvent for OrderItemPriceCorrectedEvent {
+    fn event_type() -> &'static str {
+        "OrderItemPriceCorrected"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
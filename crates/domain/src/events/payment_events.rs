@@ -1,10 +1,11 @@
 use super::DomainEvent;
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Event emitted when payment is authorized (but not captured)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PaymentAuthorizedEvent {
     pub payment_id: Uuid,
     pub order_id: Uuid,
@@ -22,7 +23,7 @@ impl DomainEvent for PaymentAuthorizedEvent {
 }
 
 /// Event emitted when authorized payment is captured
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PaymentCapturedEvent {
     pub payment_id: Uuid,
     pub order_id: Uuid,
@@ -39,7 +40,7 @@ impl DomainEvent for PaymentCapturedEvent {
 }
 
 /// Event emitted when payment authorization is voided (compensation)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PaymentVoidedEvent {
     pub payment_id: Uuid,
     pub order_id: Uuid,
@@ -56,7 +57,7 @@ impl DomainEvent for PaymentVoidedEvent {
 }
 
 /// Event emitted when payment fails
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PaymentFailedEvent {
     pub payment_id: Uuid,
     pub order_id: Uuid,
@@ -73,7 +74,7 @@ impl DomainEvent for PaymentFailedEvent {
 }
 
 /// Event emitted when payment is refunded
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PaymentRefundedEvent {
     pub payment_id: Uuid,
     pub order_id: Uuid,
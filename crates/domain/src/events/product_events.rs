@@ -0,0 +1,72 @@
+use super::DomainEvent;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Event emitted by the catalog when a product is added
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProductCreatedEvent {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub unit_price: f64,
+    pub currency: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DomainEvent for ProductCreatedEvent {
+    fn event_type() -> &'static str {
+        "ProductCreated"
+    }
+}
+
+/// Event emitted when a product's list price changes
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProductPriceChangedEvent {
+    pub product_id: Uuid,
+    pub unit_price: f64,
+    pub currency: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl DomainEvent for ProductPriceChangedEvent {
+    fn event_type() -> &'static str {
+        "ProductPriceChanged"
+    }
+}
+
+/// Event emitted when a product can no longer be ordered
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProductDiscontinuedEvent {
+    pub product_id: Uuid,
+    pub discontinued_at: DateTime<Utc>,
+}
+
+impl DomainEvent for ProductDiscontinuedEvent {
+    fn event_type() -> &'static str {
+        "ProductDiscontinued"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_product_created_event() {
+        let event = ProductCreatedEvent {
+            product_id: Uuid::new_v4(),
+            sku: "SKU-001".to_string(),
+            name: "Widget".to_string(),
+            description: None,
+            unit_price: 19.99,
+            currency: "USD".to_string(),
+            created_at: Utc::now(),
+        };
+
+        assert_eq!(ProductCreatedEvent::event_type(), "ProductCreated");
+        assert_eq!(event.sku, "SKU-001");
+    }
+}
@@ -0,0 +1,145 @@
+use super::DomainEvent;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Event emitted when a saga instance is created
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SagaStartedEvent {
+    pub saga_id: Uuid,
+    pub saga_type: String,
+    pub total_steps: usize,
+    pub started_at: DateTime<Utc>,
+}
+
+impl DomainEvent for SagaStartedEvent {
+    fn event_type() -> &'static str {
+        "SagaStarted"
+    }
+}
+
+/// Event emitted when a saga step completes
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SagaStepCompletedEvent {
+    pub saga_id: Uuid,
+    pub saga_type: String,
+    pub step_name: String,
+    pub step_index: usize,
+    pub attempt: u32,
+    pub completed_at: DateTime<Utc>,
+}
+
+impl DomainEvent for SagaStepCompletedEvent {
+    fn event_type() -> &'static str {
+        "SagaStepCompleted"
+    }
+}
+
+/// Event emitted when a saga step fails, whether or not it will be retried
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SagaStepFailedEvent {
+    pub saga_id: Uuid,
+    pub saga_type: String,
+    pub step_name: String,
+    pub step_index: usize,
+    pub attempt: u32,
+    pub error: String,
+    pub will_retry: bool,
+    pub failed_at: DateTime<Utc>,
+}
+
+impl DomainEvent for SagaStepFailedEvent {
+    fn event_type() -> &'static str {
+        "SagaStepFailed"
+    }
+}
+
+/// Event emitted when every step of a saga completed
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SagaCompletedEvent {
+    pub saga_id: Uuid,
+    pub saga_type: String,
+    pub duration_ms: i64,
+    pub completed_at: DateTime<Utc>,
+}
+
+impl DomainEvent for SagaCompletedEvent {
+    fn event_type() -> &'static str {
+        "SagaCompleted"
+    }
+}
+
+/// Event emitted when a failed saga has been rolled back
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SagaCompensatedEvent {
+    pub saga_id: Uuid,
+    pub saga_type: String,
+    pub duration_ms: i64,
+    /// Set when an operator resolved the saga manually
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+    pub compensated_at: DateTime<Utc>,
+}
+
+impl DomainEvent for SagaCompensatedEvent {
+    fn event_type() -> &'static str {
+        "SagaCompensated"
+    }
+}
+
+/// Event emitted when compensation failed and the saga needs an operator
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SagaInterventionRequiredEvent {
+    pub saga_id: Uuid,
+    pub saga_type: String,
+    pub reason: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+impl DomainEvent for SagaInterventionRequiredEvent {
+    fn event_type() -> &'static str {
+        "SagaInterventionRequired"
+    }
+}
+
+/// Event emitted when a saga stops at a step until someone approves or rejects it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SagaPausedEvent {
+    pub saga_id: Uuid,
+    pub saga_type: String,
+    pub step_name: String,
+    pub step_index: usize,
+    pub reason: String,
+    pub paused_at: DateTime<Utc>,
+}
+
+impl DomainEvent for SagaPausedEvent {
+    fn event_type() -> &'static str {
+        "SagaPaused"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventMetadata;
+
+    #[test]
+    fn test_saga_started_envelope() {
+        let saga_id = Uuid::new_v4();
+        let event = SagaStartedEvent {
+            saga_id,
+            saga_type: "OrderProcessingSaga".to_string(),
+            total_steps: 3,
+            started_at: Utc::now(),
+        };
+
+        let envelope = event
+            .to_envelope(saga_id, "Saga", EventMetadata::new())
+            .unwrap();
+        assert_eq!(envelope.event_type, "SagaStarted");
+        assert_eq!(envelope.aggregate_id, saga_id);
+        assert_eq!(envelope.payload["total_steps"], 3);
+    }
+}
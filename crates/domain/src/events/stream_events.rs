@@ -0,0 +1,32 @@
+use super::DomainEvent;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Event emitted when an aggregate's stream is deleted from the event store
+///
+/// `mode` is either "soft" (tombstoned) or "hard" (scavenged). Projections
+/// should drop any read model rows for the aggregate in both cases.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StreamDeletedEvent {
+    pub aggregate_id: Uuid,
+    pub mode: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl DomainEvent for StreamDeletedEvent {
+    fn event_type() -> &'static str {
+        "StreamDeleted"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_deleted_event_type() {
+        assert_eq!(StreamDeletedEvent::event_type(), "StreamDeleted");
+    }
+}
@@ -1,5 +1,8 @@
 pub mod aggregates;
+pub mod api;
 pub mod commands;
 pub mod events;
+pub mod schema;
+pub mod schema_compat;
 pub mod value_objects;
 pub mod errors;
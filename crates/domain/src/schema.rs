@@ -0,0 +1,135 @@
+//! JSON Schemas for event payloads and command bodies
+//!
+//! These are the contracts between services: the command service publishes events
+//! and accepts commands in these shapes, and the projection service, saga
+//! orchestrator and HTTP clients consume them. The generated schemas are committed
+//! under `schemas/` at the repository root and checked by the contract tests, so a
+//! change to any of these types shows up as a schema diff in review.
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::RootSchema;
+use schemars::JsonSchema;
+use std::collections::BTreeMap;
+
+use crate::commands::order_commands::*;
+use crate::events::customer_events::*;
+use crate::events::inventory_events::*;
+use crate::events::order_events::*;
+use crate::events::payment_events::*;
+use crate::events::product_events::*;
+use crate::events::saga_events::*;
+use crate::events::stream_events::*;
+use crate::events::{DomainEvent, EventEnvelope};
+
+/// Schema of one event payload, keyed by its `event_type` and `event_version`
+#[derive(Debug, Clone)]
+pub struct EventSchema {
+    pub event_type: &'static str,
+    pub event_version: i32,
+    pub schema: RootSchema,
+    decode: fn(serde_json::Value) -> Result<serde_json::Value, serde_json::Error>,
+}
+
+impl EventSchema {
+    fn of<T: DomainEvent>() -> Self {
+        Self {
+            event_type: T::event_type(),
+            event_version: T::event_version(),
+            schema: root_schema::<T>(),
+            decode: |payload| serde_json::to_value(serde_json::from_value::<T>(payload)?),
+        }
+    }
+
+    /// Deserialize a payload into the current event struct and serialize it back
+    pub fn decode(
+        &self,
+        payload: serde_json::Value,
+    ) -> Result<serde_json::Value, serde_json::Error> {
+        (self.decode)(payload)
+    }
+
+    /// File name of the schema and its fixtures, e.g. `OrderCreated.v1.json`
+    pub fn file_name(&self) -> String {
+        format!("{}.v{}.json", self.event_type, self.event_version)
+    }
+}
+
+/// Schemas of every event payload the services publish, ordered by event type
+pub fn event_schemas() -> Vec<EventSchema> {
+    let mut schemas = vec![
+        EventSchema::of::<OrderCreatedEvent>(),
+        EventSchema::of::<OrderConfirmedEvent>(),
+        EventSchema::of::<OrderCancelledEvent>(),
+        EventSchema::of::<OrderShippedEvent>(),
+        EventSchema::of::<OrderDeliveredEvent>(),
+        EventSchema::of::<OrderItemPriceCorrectedEvent>(),
+        EventSchema::of::<CustomerRegisteredEvent>(),
+        EventSchema::of::<CustomerProfileUpdatedEvent>(),
+        EventSchema::of::<InventoryReservedEvent>(),
+        EventSchema::of::<InventoryReleasedEvent>(),
+        EventSchema::of::<InventoryReservationFailedEvent>(),
+        EventSchema::of::<StockReplenishedEvent>(),
+        EventSchema::of::<PaymentAuthorizedEvent>(),
+        EventSchema::of::<PaymentCapturedEvent>(),
+        EventSchema::of::<PaymentVoidedEvent>(),
+        EventSchema::of::<PaymentFailedEvent>(),
+        EventSchema::of::<PaymentRefundedEvent>(),
+        EventSchema::of::<ProductCreatedEvent>(),
+        EventSchema::of::<ProductPriceChangedEvent>(),
+        EventSchema::of::<ProductDiscontinuedEvent>(),
+        EventSchema::of::<SagaStartedEvent>(),
+        EventSchema::of::<SagaStepCompletedEvent>(),
+        EventSchema::of::<SagaStepFailedEvent>(),
+        EventSchema::of::<SagaCompletedEvent>(),
+        EventSchema::of::<SagaCompensatedEvent>(),
+        EventSchema::of::<SagaInterventionRequiredEvent>(),
+        EventSchema::of::<SagaPausedEvent>(),
+        EventSchema::of::<StreamDeletedEvent>(),
+    ];
+    schemas.sort_by_key(|s| (s.event_type, s.event_version));
+    schemas
+}
+
+/// Schemas of the command bodies accepted by the command service, keyed by type name
+pub fn command_schemas() -> BTreeMap<&'static str, RootSchema> {
+    BTreeMap::from([
+        ("CreateOrderCommand", root_schema::<CreateOrderCommand>()),
+        ("ConfirmOrderCommand", root_schema::<ConfirmOrderCommand>()),
+        ("CancelOrderCommand", root_schema::<CancelOrderCommand>()),
+        ("ShipOrderCommand", root_schema::<ShipOrderCommand>()),
+        ("DeliverOrderCommand", root_schema::<DeliverOrderCommand>()),
+    ])
+}
+
+/// Schema of the envelope every event is published in
+pub fn envelope_schema() -> RootSchema {
+    root_schema::<EventEnvelope>()
+}
+
+fn root_schema<T: JsonSchema>() -> RootSchema {
+    SchemaGenerator::default().into_root_schema_for::<T>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_schemas_are_unique() {
+        let schemas = event_schemas();
+        let mut names: Vec<String> = schemas.iter().map(|s| s.file_name()).collect();
+        names.dedup();
+        assert_eq!(names.len(), schemas.len());
+    }
+
+    #[test]
+    fn test_event_schema_requires_payload_fields() {
+        let schema = EventSchema::of::<OrderShippedEvent>();
+        assert_eq!(schema.file_name(), "OrderShipped.v1.json");
+
+        let required = &schema.schema.schema.object.as_ref().unwrap().required;
+        for field in ["order_id", "tracking_number", "carrier", "shipped_at"] {
+            assert!(required.contains(field), "{} should be required", field);
+        }
+    }
+}
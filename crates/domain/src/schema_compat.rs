@@ -0,0 +1,309 @@
+//! Event versioning policy
+//!
+//! Stored events are never rewritten, so every payload version ever published must
+//! still be readable. Fixtures of each version are committed as
+//! `<EventType>.v<N>.json` and kept after the event moves on to a new version.
+//! [`check_fixtures`] loads them and checks that the current structs still
+//! deserialize each one, after running it through the registered upcasters if it
+//! is older than the current version.
+//!
+//! An incompatible change to an event (a new required field, a renamed or retyped
+//! field) therefore needs an `event_version` bump and an upcaster from the previous
+//! version registered in [`upcasters`].
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::schema::{event_schemas, EventSchema};
+
+#[derive(Debug, Error, PartialEq)]
+pub enum SchemaCompatError {
+    #[error("{file}: unknown event type {event_type}")]
+    UnknownEventType { file: String, event_type: String },
+
+    #[error("{file}: version {version} is newer than the current version {current}")]
+    FutureVersion {
+        file: String,
+        version: i32,
+        current: i32,
+    },
+
+    #[error("no upcaster registered for {event_type} v{from_version}")]
+    MissingUpcaster {
+        event_type: String,
+        from_version: i32,
+    },
+
+    #[error("upcasting {event_type} v{from_version} failed: {reason}")]
+    UpcastFailed {
+        event_type: String,
+        from_version: i32,
+        reason: String,
+    },
+
+    #[error("{file}: does not deserialize into the current struct: {reason}")]
+    Incompatible { file: String, reason: String },
+
+    #[error("{file}: {reason}")]
+    InvalidFixture { file: String, reason: String },
+}
+
+type UpcastFn = Box<dyn Fn(Value) -> Result<Value, String> + Send + Sync>;
+
+/// Upcasters keyed by event type and the version they upgrade from
+///
+/// Each upcaster turns a payload of version N into version N + 1; older payloads
+/// are chained through every step up to the current version.
+pub struct UpcasterRegistry {
+    upcasters: HashMap<(String, i32), UpcastFn>,
+    current_versions: HashMap<&'static str, i32>,
+}
+
+impl UpcasterRegistry {
+    pub fn new() -> Self {
+        Self {
+            upcasters: HashMap::new(),
+            current_versions: event_schemas()
+                .into_iter()
+                .map(|s| (s.event_type, s.event_version))
+                .collect(),
+        }
+    }
+
+    /// Register the upgrade of `event_type` payloads from `from_version` to the next version
+    pub fn with_upcaster<F>(mut self, event_type: &str, from_version: i32, upcast: F) -> Self
+    where
+        F: Fn(Value) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        self.upcasters
+            .insert((event_type.to_string(), from_version), Box::new(upcast));
+        self
+    }
+
+    /// Current version of an event type, if it is known
+    pub fn current_version(&self, event_type: &str) -> Option<i32> {
+        self.current_versions.get(event_type).copied()
+    }
+
+    /// Bring a payload of any older version up to the current version of its event type
+    ///
+    /// Payloads that are already current, or of an unknown event type, are returned as is.
+    pub fn upcast(
+        &self,
+        event_type: &str,
+        version: i32,
+        mut payload: Value,
+    ) -> Result<(i32, Value), SchemaCompatError> {
+        let current = match self.current_version(event_type) {
+            Some(current) => current,
+            None => return Ok((version, payload)),
+        };
+
+        let mut version = version;
+        while version < current {
+            let upcast = self
+                .upcasters
+                .get(&(event_type.to_string(), version))
+                .ok_or_else(|| SchemaCompatError::MissingUpcaster {
+                    event_type: event_type.to_string(),
+                    from_version: version,
+                })?;
+            payload = upcast(payload).map_err(|reason| SchemaCompatError::UpcastFailed {
+                event_type: event_type.to_string(),
+                from_version: version,
+                reason,
+            })?;
+            version += 1;
+        }
+        Ok((version, payload))
+    }
+}
+
+impl Default for UpcasterRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Upcasters for every event whose payload changed incompatibly since v1
+pub fn upcasters() -> UpcasterRegistry {
+    UpcasterRegistry::new()
+}
+
+/// Check one fixture of `event_type` at `version` against the current structs
+pub fn check_fixture(
+    registry: &UpcasterRegistry,
+    file: &str,
+    event_type: &str,
+    version: i32,
+    payload: Value,
+) -> Result<(), SchemaCompatError> {
+    let schema: EventSchema = event_schemas()
+        .into_iter()
+        .find(|s| s.event_type == event_type)
+        .ok_or_else(|| SchemaCompatError::UnknownEventType {
+            file: file.to_string(),
+            event_type: event_type.to_string(),
+        })?;
+
+    if version > schema.event_version {
+        return Err(SchemaCompatError::FutureVersion {
+            file: file.to_string(),
+            version,
+            current: schema.event_version,
+        });
+    }
+
+    let (_, payload) = registry.upcast(event_type, version, payload)?;
+    schema
+        .decode(payload)
+        .map(|_| ())
+        .map_err(|e| SchemaCompatError::Incompatible {
+            file: file.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// Check every `<EventType>.v<N>.json` fixture in `dir`, returning all failures
+pub fn check_fixtures(dir: &Path, registry: &UpcasterRegistry) -> Vec<SchemaCompatError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            return vec![SchemaCompatError::InvalidFixture {
+                file: dir.display().to_string(),
+                reason: e.to_string(),
+            }]
+        }
+    };
+
+    let mut files: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    let mut errors = Vec::new();
+    for path in files {
+        let file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let result = parse_fixture_name(&file)
+            .ok_or_else(|| SchemaCompatError::InvalidFixture {
+                file: file.clone(),
+                reason: "expected a name like OrderCreated.v1.json".to_string(),
+            })
+            .and_then(|(event_type, version)| {
+                let payload = fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+                    .map_err(|reason| SchemaCompatError::InvalidFixture {
+                        file: file.clone(),
+                        reason,
+                    })?;
+                check_fixture(registry, &file, event_type, version, payload)
+            });
+
+        if let Err(e) = result {
+            errors.push(e);
+        }
+    }
+    errors
+}
+
+/// Split `OrderCreated.v1.json` into its event type and version
+fn parse_fixture_name(file: &str) -> Option<(&str, i32)> {
+    let stem = file.strip_suffix(".json")?;
+    let (event_type, version) = stem.rsplit_once(".v")?;
+    Some((event_type, version.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn shipped_payload() -> Value {
+        json!({
+            "order_id": "6f1c2a4e-3b5d-4c7e-9f80-1a2b3c4d5e6f",
+            "tracking_number": "1Z999AA10123456784",
+            "carrier": "UPS",
+            "shipped_at": "2024-03-14T09:26:53Z"
+        })
+    }
+
+    #[test]
+    fn test_parse_fixture_name() {
+        assert_eq!(
+            parse_fixture_name("OrderCreated.v1.json"),
+            Some(("OrderCreated", 1))
+        );
+        assert_eq!(parse_fixture_name("OrderCreated.json"), None);
+        assert_eq!(parse_fixture_name("OrderCreated.vX.json"), None);
+    }
+
+    #[test]
+    fn test_incompatible_payload_is_rejected() {
+        let mut payload = shipped_payload();
+        payload.as_object_mut().unwrap().remove("carrier");
+
+        let result = check_fixture(
+            &upcasters(),
+            "OrderShipped.v1.json",
+            "OrderShipped",
+            1,
+            payload,
+        );
+        assert!(matches!(
+            result,
+            Err(SchemaCompatError::Incompatible { .. })
+        ));
+    }
+
+    #[test]
+    fn test_older_version_needs_an_upcaster() {
+        // Pretend v0 of OrderShipped had no carrier field
+        let mut old = shipped_payload();
+        old.as_object_mut().unwrap().remove("carrier");
+
+        let result = check_fixture(
+            &upcasters(),
+            "OrderShipped.v0.json",
+            "OrderShipped",
+            0,
+            old.clone(),
+        );
+        assert_eq!(
+            result,
+            Err(SchemaCompatError::MissingUpcaster {
+                event_type: "OrderShipped".to_string(),
+                from_version: 0,
+            })
+        );
+
+        let registry = UpcasterRegistry::new().with_upcaster("OrderShipped", 0, |mut payload| {
+            payload["carrier"] = json!("UNKNOWN");
+            Ok(payload)
+        });
+        assert!(check_fixture(&registry, "OrderShipped.v0.json", "OrderShipped", 0, old).is_ok());
+    }
+
+    #[test]
+    fn test_future_version_is_rejected() {
+        let result = check_fixture(
+            &upcasters(),
+            "OrderShipped.v9.json",
+            "OrderShipped",
+            9,
+            shipped_payload(),
+        );
+        assert!(matches!(
+            result,
+            Err(SchemaCompatError::FutureVersion { current: 1, .. })
+        ));
+    }
+}
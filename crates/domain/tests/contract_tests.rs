@@ -0,0 +1,238 @@
+//! Contract tests for the schemas in `domain::schema`
+//!
+//! - The schemas committed under `schemas/` must match the ones generated from the
+//!   current types. Run `make schemas` to regenerate them after an intended change.
+//! - Every event and command has a JSON fixture under `tests/fixtures/`, which must
+//!   validate against its schema and deserialize into the current struct.
+//! - API response bodies are covered the same way. Those built from read model views
+//!   and saga state are defined outside this crate and listed in [`responses`].
+//! - Fixtures of older event versions stay committed and must still deserialize,
+//!   through an upcaster if needed (see `domain::schema_compat`).
+
+use domain::api::ErrorResponse;
+use domain::commands::order_commands::*;
+use domain::events::EventEnvelope;
+use domain::schema::{command_schemas, envelope_schema, event_schemas};
+use domain::schema_compat::{check_fixtures, upcasters};
+use jsonschema::JSONSchema;
+use read_model::OrderView;
+use saga::{PendingSagas, SagaState};
+use schemars::schema::RootSchema;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn schemas_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../schemas")
+}
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn read_json(path: &Path) -> Value {
+    let content = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e));
+    serde_json::from_str(&content)
+        .unwrap_or_else(|e| panic!("{} is not valid JSON: {}", path.display(), e))
+}
+
+/// Schema of an API response body and a round trip through its type
+struct ResponseContract {
+    name: &'static str,
+    schema: RootSchema,
+    round_trip: fn(Value) -> Result<Value, serde_json::Error>,
+}
+
+impl ResponseContract {
+    fn of<T: JsonSchema + Serialize + DeserializeOwned>(name: &'static str) -> Self {
+        Self {
+            name,
+            schema: schemars::schema_for!(T),
+            round_trip: |body| serde_json::to_value(serde_json::from_value::<T>(body)?),
+        }
+    }
+}
+
+/// Response bodies the command and query services return, keyed by type name
+fn responses() -> Vec<ResponseContract> {
+    vec![
+        ResponseContract::of::<ErrorResponse>("ErrorResponse"),
+        ResponseContract::of::<OrderView>("OrderView"),
+        ResponseContract::of::<PendingSagas>("PendingSagas"),
+        ResponseContract::of::<SagaState>("SagaState"),
+    ]
+}
+
+/// Every schema that is published, keyed by its path relative to `schemas/`
+fn generated_schemas() -> BTreeMap<String, RootSchema> {
+    let mut schemas = BTreeMap::new();
+    for event in event_schemas() {
+        schemas.insert(format!("events/{}", event.file_name()), event.schema);
+    }
+    for (name, schema) in command_schemas() {
+        schemas.insert(format!("commands/{}.json", name), schema);
+    }
+    for response in responses() {
+        schemas.insert(format!("responses/{}.json", response.name), response.schema);
+    }
+    schemas.insert("EventEnvelope.json".to_string(), envelope_schema());
+    schemas
+}
+
+fn assert_valid(schema: &RootSchema, instance: &Value, what: &str) {
+    let schema = serde_json::to_value(schema).unwrap();
+    let compiled = JSONSchema::compile(&schema)
+        .unwrap_or_else(|e| panic!("schema for {} does not compile: {}", what, e));
+    let errors: Vec<String> = match compiled.validate(instance) {
+        Ok(()) => return,
+        Err(errors) => errors
+            .map(|e| format!("{} at '{}'", e, e.instance_path))
+            .collect(),
+    };
+    panic!(
+        "{} does not match its schema:\n  {}",
+        what,
+        errors.join("\n  ")
+    );
+}
+
+fn assert_decodes<T: DeserializeOwned>(name: &str) {
+    let path = fixtures_dir().join(format!("commands/{}.json", name));
+    if let Err(e) = serde_json::from_value::<T>(read_json(&path)) {
+        panic!("{} does not deserialize: {}", path.display(), e);
+    }
+}
+
+#[test]
+fn test_committed_schemas_are_up_to_date() {
+    let dir = schemas_dir();
+    let generated = generated_schemas();
+
+    if std::env::var_os("UPDATE_SCHEMAS").is_some() {
+        for (path, schema) in &generated {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, serde_json::to_string_pretty(schema).unwrap() + "\n").unwrap();
+        }
+        return;
+    }
+
+    let mut stale = Vec::new();
+    for (path, schema) in &generated {
+        let committed = dir.join(path);
+        if !committed.exists() || read_json(&committed) != serde_json::to_value(schema).unwrap() {
+            stale.push(path.clone());
+        }
+    }
+    assert!(
+        stale.is_empty(),
+        "schemas differ from the current types, run `make schemas` and commit the result: {:?}",
+        stale
+    );
+}
+
+#[test]
+fn test_event_fixtures_match_schemas() {
+    for event in event_schemas() {
+        let path = fixtures_dir().join("events").join(event.file_name());
+        assert!(path.exists(), "missing fixture {}", path.display());
+        let fixture = read_json(&path);
+
+        assert_valid(&event.schema, &fixture, &path.display().to_string());
+
+        let decoded = event
+            .decode(fixture.clone())
+            .unwrap_or_else(|e| panic!("{} does not deserialize: {}", path.display(), e));
+        assert_eq!(
+            decoded,
+            fixture,
+            "{} does not survive a round trip through the current struct",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn test_all_event_versions_still_deserialize() {
+    let errors = check_fixtures(&fixtures_dir().join("events"), &upcasters());
+    let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert!(
+        errors.is_empty(),
+        "incompatible event changes, bump event_version and register an upcaster:\n  {}",
+        errors.join("\n  ")
+    );
+}
+
+#[test]
+fn test_command_fixtures_match_schemas() {
+    for (name, schema) in command_schemas() {
+        let path = fixtures_dir().join(format!("commands/{}.json", name));
+        assert!(path.exists(), "missing fixture {}", path.display());
+        assert_valid(&schema, &read_json(&path), &path.display().to_string());
+    }
+
+    assert_decodes::<CreateOrderCommand>("CreateOrderCommand");
+    assert_decodes::<ConfirmOrderCommand>("ConfirmOrderCommand");
+    assert_decodes::<CancelOrderCommand>("CancelOrderCommand");
+    assert_decodes::<ShipOrderCommand>("ShipOrderCommand");
+    assert_decodes::<DeliverOrderCommand>("DeliverOrderCommand");
+}
+
+#[test]
+fn test_response_fixtures_match_schemas() {
+    for response in responses() {
+        let path = fixtures_dir().join(format!("responses/{}.json", response.name));
+        assert!(path.exists(), "missing fixture {}", path.display());
+        let fixture = read_json(&path);
+
+        assert_valid(&response.schema, &fixture, &path.display().to_string());
+
+        let decoded = (response.round_trip)(fixture.clone())
+            .unwrap_or_else(|e| panic!("{} does not deserialize: {}", path.display(), e));
+        assert_eq!(
+            decoded,
+            fixture,
+            "{} does not survive a round trip through the current struct",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn test_envelope_fixture_matches_schema() {
+    let path = fixtures_dir().join("EventEnvelope.json");
+    let fixture = read_json(&path);
+    assert_valid(&envelope_schema(), &fixture, "EventEnvelope.json");
+
+    let envelope: EventEnvelope = serde_json::from_value(fixture).unwrap();
+    let event = event_schemas()
+        .into_iter()
+        .find(|s| s.event_type == envelope.event_type && s.event_version == envelope.event_version)
+        .expect("envelope fixture should carry a known event type");
+    assert_valid(
+        &event.schema,
+        &envelope.payload,
+        "EventEnvelope.json payload",
+    );
+}
+
+#[test]
+fn test_schema_rejects_drifted_payload() {
+    let event = event_schemas()
+        .into_iter()
+        .find(|s| s.event_type == "OrderShipped")
+        .unwrap();
+    let schema = serde_json::to_value(&event.schema).unwrap();
+    let compiled = JSONSchema::compile(&schema).unwrap();
+
+    let mut payload = read_json(&fixtures_dir().join("events").join(event.file_name()));
+    let carrier = payload.as_object_mut().unwrap().remove("carrier").unwrap();
+    payload["shipping_carrier"] = carrier;
+
+    assert!(!compiled.is_valid(&payload));
+}
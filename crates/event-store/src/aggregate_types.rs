@@ -0,0 +1,150 @@
+use domain::events::customer_events::*;
+use domain::events::inventory_events::*;
+use domain::events::order_events::*;
+use domain::events::payment_events::*;
+use domain::events::product_events::*;
+use domain::events::DomainEvent;
+use std::collections::{HashMap, HashSet};
+
+use crate::{Event, EventStoreError, TOMBSTONE_EVENT_TYPE};
+
+/// Aggregate types the store accepts and the event types each may record
+///
+/// Streams always keep the aggregate type of their first event; this registry also
+/// stops an event landing under an aggregate type it doesn't belong to, e.g. an
+/// `InventoryReserved` event appended to an `Order` stream.
+#[derive(Default)]
+pub struct AggregateTypeRegistry {
+    event_types: HashMap<String, HashSet<String>>,
+}
+
+impl AggregateTypeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry for the aggregates defined in `domain`
+    pub fn from_domain() -> Self {
+        let mut registry = Self::new();
+        registry.register(
+            "Order",
+            [
+                OrderCreatedEvent::event_type(),
+                OrderConfirmedEvent::event_type(),
+                OrderCancelledEvent::event_type(),
+                OrderShippedEvent::event_type(),
+                OrderDeliveredEvent::event_type(),
+                OrderItemPriceCorrectedEvent::event_type(),
+            ],
+        );
+        registry.register(
+            "Customer",
+            [
+                CustomerRegisteredEvent::event_type(),
+                CustomerProfileUpdatedEvent::event_type(),
+            ],
+        );
+        registry.register(
+            "Inventory",
+            [
+                InventoryReservedEvent::event_type(),
+                InventoryReleasedEvent::event_type(),
+                InventoryReservationFailedEvent::event_type(),
+                StockReplenishedEvent::event_type(),
+            ],
+        );
+        registry.register(
+            "Payment",
+            [
+                PaymentAuthorizedEvent::event_type(),
+                PaymentCapturedEvent::event_type(),
+                PaymentVoidedEvent::event_type(),
+                PaymentFailedEvent::event_type(),
+                PaymentRefundedEvent::event_type(),
+            ],
+        );
+        registry.register(
+            "Product",
+            [
+                ProductCreatedEvent::event_type(),
+                ProductPriceChangedEvent::event_type(),
+                ProductDiscontinuedEvent::event_type(),
+            ],
+        );
+        registry
+    }
+
+    /// Allow `event_types` on streams of `aggregate_type`, in addition to any already allowed
+    pub fn register<I, S>(&mut self, aggregate_type: impl Into<String>, event_types: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.event_types
+            .entry(aggregate_type.into())
+            .or_default()
+            .extend(event_types.into_iter().map(Into::into));
+    }
+
+    pub fn is_registered(&self, aggregate_type: &str) -> bool {
+        self.event_types.contains_key(aggregate_type)
+    }
+
+    /// Reject events of unknown aggregate types or of event types their aggregate doesn't record
+    ///
+    /// Tombstones are accepted for every registered aggregate type.
+    pub fn check(&self, event: &Event) -> Result<(), EventStoreError> {
+        let allowed = self
+            .event_types
+            .get(&event.aggregate_type)
+            .is_some_and(|event_types| {
+                event.event_type == TOMBSTONE_EVENT_TYPE || event_types.contains(&event.event_type)
+            });
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(EventStoreError::UnregisteredEventType {
+                aggregate_type: event.aggregate_type.clone(),
+                event_type: event.event_type.clone(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::events::EventMetadata;
+    use uuid::Uuid;
+
+    fn event(aggregate_type: &str, event_type: &str) -> Event {
+        Event::new(
+            Uuid::new_v4(),
+            aggregate_type.to_string(),
+            event_type.to_string(),
+            1,
+            serde_json::json!({}),
+            EventMetadata::new(),
+        )
+    }
+
+    #[test]
+    fn test_domain_events_are_accepted_under_their_aggregate() {
+        let registry = AggregateTypeRegistry::from_domain();
+        assert!(registry.check(&event("Order", "OrderConfirmed")).is_ok());
+        assert!(registry.check(&event("Inventory", "StockReplenished")).is_ok());
+        assert!(registry.check(&event("Customer", TOMBSTONE_EVENT_TYPE)).is_ok());
+    }
+
+    #[test]
+    fn test_events_under_the_wrong_aggregate_are_rejected() {
+        let registry = AggregateTypeRegistry::from_domain();
+        assert!(matches!(
+            registry.check(&event("Order", "InventoryReserved")),
+            Err(EventStoreError::UnregisteredEventType { .. })
+        ));
+        assert!(registry.check(&event("Shipment", "OrderShipped")).is_err());
+        assert!(!registry.is_registered("Shipment"));
+    }
+}
@@ -1,8 +1,15 @@
-use redis::{AsyncCommands, Client, RedisError};
+use async_trait::async_trait;
+use common::metrics::record_idempotency_check;
+use common::redis_connection::{RedisConfig, RedisConnection};
+use redis::{AsyncCommands, RedisError, Script};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
+use tokio::sync::OnceCell;
 use uuid::Uuid;
 
+pub use crate::idempotency_store::{IdempotencyClaim, IdempotencyStatus};
+use crate::idempotency_store::{IdempotencyError, IdempotencyStore};
+
 /// Idempotency key for tracking processed commands/events
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IdempotencyKey {
@@ -10,55 +17,195 @@ pub struct IdempotencyKey {
     pub result: Option<serde_json::Value>,
 }
 
+/// Prefix of the value held under a key while a caller is processing it, followed by
+/// the id of that caller's claim
+const PROCESSING_MARKER: &str = "__processing__";
+
+/// Claims a free key with the marker, or returns what the key already holds
+const TRY_BEGIN_SCRIPT: &str = r#"
+if redis.call('SET', KEYS[1], ARGV[1], 'NX', 'EX', ARGV[2]) then
+    return false
+end
+return redis.call('GET', KEYS[1])
+"#;
+
+/// Stores the result while the key holds the caller's marker or has expired, never
+/// over another caller's claim or result
+const COMPLETE_SCRIPT: &str = r#"
+local current = redis.call('GET', KEYS[1])
+if current == false or current == ARGV[1] then
+    redis.call('SET', KEYS[1], ARGV[2], 'EX', ARGV[3])
+    return 1
+end
+return 0
+"#;
+
+/// Deletes the key only while it still holds the caller's marker
+const FAIL_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+/// Value marking a key as claimed by `claim`
+fn processing_marker(claim: IdempotencyClaim) -> String {
+    format!("{}:{}", PROCESSING_MARKER, claim.id())
+}
+
 /// Redis-based idempotency checker
 pub struct IdempotencyChecker {
-    client: Client,
+    config: RedisConfig,
+    connection: OnceCell<RedisConnection>,
     ttl_seconds: u64,
+    processing_timeout: Duration,
 }
 
 impl IdempotencyChecker {
-    /// Create a new idempotency checker
+    /// Create a new idempotency checker for a single Redis node
     pub fn new(redis_url: &str, ttl_seconds: u64) -> Result<Self, RedisError> {
-        let client = Client::open(redis_url)?;
+        Self::with_config(RedisConfig::standalone(redis_url), ttl_seconds)
+    }
+
+    /// Create an idempotency checker for a Redis Cluster, Sentinel or single node
+    ///
+    /// The configuration is checked here, but Redis is only contacted on first use.
+    pub fn with_config(config: RedisConfig, ttl_seconds: u64) -> Result<Self, RedisError> {
+        config.validate()?;
         Ok(Self {
-            client,
+            config,
+            connection: OnceCell::new(),
             ttl_seconds,
+            processing_timeout: Duration::from_secs(30),
         })
     }
 
+    /// How long a key claimed by [`try_begin`](Self::try_begin) stays in progress
+    /// before another caller may claim it, in case the first one died (default 30s)
+    pub fn with_processing_timeout(mut self, timeout: Duration) -> Self {
+        self.processing_timeout = timeout.max(Duration::from_secs(1));
+        self
+    }
+
+    async fn connection(&self) -> Result<RedisConnection, RedisError> {
+        self.connection
+            .get_or_try_init(|| self.config.connect())
+            .await
+            .cloned()
+    }
+
     /// Check if a command/event has already been processed
-    /// Returns Some(result) if already processed, None if new
+    /// Returns Some(result) if already processed, None if new or still in progress
+    ///
+    /// Checking and then recording is not atomic: two concurrent duplicates can both
+    /// see `None`. Use [`try_begin`](Self::try_begin) where that matters.
     pub async fn check(
         &self,
         idempotency_key: &str,
     ) -> Result<Option<serde_json::Value>, RedisError> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.connection().await?;
         let key = self.format_key(idempotency_key);
 
         let result: Option<String> = conn.get(&key).await?;
 
-        match result {
-            Some(data) => {
-                let value: serde_json::Value = serde_json::from_str(&data)
-                    .map_err(|e| RedisError::from((redis::ErrorKind::TypeError, "Invalid JSON", e.to_string())))?;
-                Ok(Some(value))
-            }
-            None => Ok(None),
+        match parse_status(result)? {
+            Some(IdempotencyStatus::Completed(value)) => Ok(Some(value)),
+            _ => Ok(None),
         }
     }
 
+    /// Atomically claim a key for processing
+    ///
+    /// Exactly one of several concurrent callers gets [`IdempotencyStatus::Started`] and
+    /// must finish with [`complete`](Self::complete) or [`fail`](Self::fail); the others
+    /// see `InProgress` until then, and `Completed` with the recorded result afterwards.
+    pub async fn try_begin(&self, idempotency_key: &str) -> Result<IdempotencyStatus, RedisError> {
+        let mut conn = self.connection().await?;
+        let key = self.format_key(idempotency_key);
+        let claim = IdempotencyClaim::new();
+
+        let existing: Option<String> = Script::new(TRY_BEGIN_SCRIPT)
+            .key(&key)
+            .arg(processing_marker(claim))
+            .arg(self.processing_timeout.as_secs())
+            .invoke_async(&mut conn)
+            .await?;
+
+        let status = parse_status(existing)?.unwrap_or(IdempotencyStatus::Started(claim));
+        record_idempotency_check(!matches!(status, IdempotencyStatus::Started(_)));
+        tracing::debug!(
+            idempotency_key = %idempotency_key,
+            status = ?status,
+            "Idempotency key claim"
+        );
+        Ok(status)
+    }
+
+    /// Record the result of a key claimed with [`try_begin`](Self::try_begin)
+    ///
+    /// Returns false, recording nothing, if another caller has claimed the key or
+    /// recorded a result since.
+    pub async fn complete(
+        &self,
+        idempotency_key: &str,
+        claim: IdempotencyClaim,
+        result: &serde_json::Value,
+    ) -> Result<bool, RedisError> {
+        let mut conn = self.connection().await?;
+        let key = self.format_key(idempotency_key);
+        let value = serde_json::to_string(result)
+            .map_err(|e| RedisError::from((redis::ErrorKind::TypeError, "Serialization failed", e.to_string())))?;
+
+        let stored: i64 = Script::new(COMPLETE_SCRIPT)
+            .key(&key)
+            .arg(processing_marker(claim))
+            .arg(value)
+            .arg(self.ttl_seconds)
+            .invoke_async(&mut conn)
+            .await?;
+
+        tracing::debug!(
+            idempotency_key = %idempotency_key,
+            stored = stored == 1,
+            "Completed idempotency key"
+        );
+        Ok(stored == 1)
+    }
+
+    /// Release a key claimed with [`try_begin`](Self::try_begin) without a result, so
+    /// a retry can claim it again
+    ///
+    /// A result or another caller's claim under the key is left in place.
+    pub async fn fail(
+        &self,
+        idempotency_key: &str,
+        claim: IdempotencyClaim,
+    ) -> Result<(), RedisError> {
+        let mut conn = self.connection().await?;
+        let key = self.format_key(idempotency_key);
+
+        let _: i64 = Script::new(FAIL_SCRIPT)
+            .key(&key)
+            .arg(processing_marker(claim))
+            .invoke_async(&mut conn)
+            .await?;
+
+        tracing::debug!(idempotency_key = %idempotency_key, "Released idempotency key");
+        Ok(())
+    }
+
     /// Record that a command/event has been processed
     pub async fn record(
         &self,
         idempotency_key: &str,
         result: &serde_json::Value,
     ) -> Result<(), RedisError> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.connection().await?;
         let key = self.format_key(idempotency_key);
         let value = serde_json::to_string(result)
             .map_err(|e| RedisError::from((redis::ErrorKind::TypeError, "Serialization failed", e.to_string())))?;
 
-        conn.set_ex(&key, value, self.ttl_seconds).await?;
+        let _: () = conn.set_ex(&key, value, self.ttl_seconds).await?;
 
         tracing::debug!(
             idempotency_key = %idempotency_key,
@@ -71,25 +218,79 @@ impl IdempotencyChecker {
 
     /// Delete an idempotency record (useful for testing)
     pub async fn delete(&self, idempotency_key: &str) -> Result<(), RedisError> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.connection().await?;
         let key = self.format_key(idempotency_key);
-        conn.del(&key).await?;
+        let _: () = conn.del(&key).await?;
         Ok(())
     }
 
     /// Check if a key exists
     pub async fn exists(&self, idempotency_key: &str) -> Result<bool, RedisError> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.connection().await?;
         let key = self.format_key(idempotency_key);
         let exists: bool = conn.exists(&key).await?;
         Ok(exists)
     }
 
+    /// Check that Redis is reachable
+    pub async fn ping(&self) -> Result<(), RedisError> {
+        let mut conn = self.connection().await?;
+        redis::cmd("PING").query_async::<_, String>(&mut conn).await?;
+        Ok(())
+    }
+
     fn format_key(&self, idempotency_key: &str) -> String {
         format!("idempotency:{}", idempotency_key)
     }
 }
 
+#[async_trait]
+impl IdempotencyStore for IdempotencyChecker {
+    async fn check(&self, key: &str) -> Result<Option<serde_json::Value>, IdempotencyError> {
+        Ok(IdempotencyChecker::check(self, key).await?)
+    }
+
+    async fn record(&self, key: &str, result: &serde_json::Value) -> Result<(), IdempotencyError> {
+        Ok(IdempotencyChecker::record(self, key, result).await?)
+    }
+
+    async fn try_begin(&self, key: &str) -> Result<IdempotencyStatus, IdempotencyError> {
+        Ok(IdempotencyChecker::try_begin(self, key).await?)
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        claim: IdempotencyClaim,
+        result: &serde_json::Value,
+    ) -> Result<(), IdempotencyError> {
+        if IdempotencyChecker::complete(self, key, claim, result).await? {
+            Ok(())
+        } else {
+            Err(IdempotencyError::ClaimLost(key.to_string()))
+        }
+    }
+
+    async fn fail(&self, key: &str, claim: IdempotencyClaim) -> Result<(), IdempotencyError> {
+        Ok(IdempotencyChecker::fail(self, key, claim).await?)
+    }
+
+    async fn ping(&self) -> Result<(), IdempotencyError> {
+        Ok(IdempotencyChecker::ping(self).await?)
+    }
+}
+
+/// What a stored value means: the processing marker or a recorded JSON result
+fn parse_status(data: Option<String>) -> Result<Option<IdempotencyStatus>, RedisError> {
+    match data.as_deref() {
+        None => Ok(None),
+        Some(data) if data.starts_with(PROCESSING_MARKER) => Ok(Some(IdempotencyStatus::InProgress)),
+        Some(data) => serde_json::from_str(data)
+            .map(|value| Some(IdempotencyStatus::Completed(value)))
+            .map_err(|e| RedisError::from((redis::ErrorKind::TypeError, "Invalid JSON", e.to_string()))),
+    }
+}
+
 /// Generate an idempotency key from command ID or event ID
 pub fn generate_idempotency_key(id: &Uuid, operation: &str) -> String {
     format!("{}:{}", operation, id)
@@ -119,5 +320,21 @@ mod tests {
         assert!(key.contains(&id.to_string()));
     }
 
+    #[test]
+    fn test_parse_status() {
+        assert_eq!(parse_status(None).unwrap(), None);
+        assert_eq!(
+            parse_status(Some(processing_marker(IdempotencyClaim::new()))).unwrap(),
+            Some(IdempotencyStatus::InProgress)
+        );
+        assert_eq!(
+            parse_status(Some(r#"{"order_id":"o-1"}"#.to_string())).unwrap(),
+            Some(IdempotencyStatus::Completed(
+                serde_json::json!({"order_id": "o-1"})
+            ))
+        );
+        assert!(parse_status(Some("not json".to_string())).is_err());
+    }
+
     // Note: Integration tests that require Redis would go in tests/integration/
 }
@@ -0,0 +1,135 @@
+use async_trait::async_trait;
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// A caller's claim on a key, taken by [`IdempotencyStore::try_begin`]
+///
+/// `complete` and `fail` only act while the key is still held by this claim, so a
+/// caller whose claim timed out and was taken over cannot release or overwrite the
+/// new owner's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdempotencyClaim(Uuid);
+
+impl IdempotencyClaim {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for IdempotencyClaim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Outcome of [`IdempotencyStore::try_begin`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdempotencyStatus {
+    /// The key was free and now belongs to the caller, who must `complete` or `fail` it
+    /// with this claim
+    Started(IdempotencyClaim),
+    /// Another caller claimed the key and has not finished yet
+    InProgress,
+    /// The key was already processed; carries the recorded result
+    Completed(serde_json::Value),
+}
+
+#[derive(Debug, Error)]
+pub enum IdempotencyError {
+    #[cfg(feature = "redis")]
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+
+    #[cfg(feature = "postgres")]
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Claim on idempotency key {0} was taken over by another caller")]
+    ClaimLost(String),
+}
+
+/// Where processed commands and events are remembered
+///
+/// Implemented by the Redis `IdempotencyChecker` and by `PgIdempotencyStore`, which
+/// keeps keys as durable as the database for deployments that cannot afford to lose
+/// them whenever Redis restarts.
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// Result recorded for the key; `None` if new or still in progress
+    async fn check(&self, key: &str) -> Result<Option<serde_json::Value>, IdempotencyError>;
+
+    /// Record that the key has been processed, whether or not it was claimed first
+    async fn record(&self, key: &str, result: &serde_json::Value) -> Result<(), IdempotencyError>;
+
+    /// Atomically claim the key; only one concurrent caller gets `Started`
+    async fn try_begin(&self, key: &str) -> Result<IdempotencyStatus, IdempotencyError>;
+
+    /// Record the result of a key claimed with `try_begin`
+    ///
+    /// Fails with [`IdempotencyError::ClaimLost`] if another caller has claimed the key
+    /// since; a key whose claim merely expired is still recorded.
+    async fn complete(
+        &self,
+        key: &str,
+        claim: IdempotencyClaim,
+        result: &serde_json::Value,
+    ) -> Result<(), IdempotencyError>;
+
+    /// Release a key claimed with `try_begin` without a result, so a retry can claim it
+    ///
+    /// Does nothing once the key holds a result or another caller's claim.
+    async fn fail(&self, key: &str, claim: IdempotencyClaim) -> Result<(), IdempotencyError>;
+
+    /// Check that the backing store is reachable
+    async fn ping(&self) -> Result<(), IdempotencyError>;
+}
+
+/// Which [`IdempotencyStore`] a service uses, from `IDEMPOTENCY_BACKEND`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdempotencyBackend {
+    #[default]
+    Redis,
+    Postgres,
+}
+
+impl IdempotencyBackend {
+    /// `IDEMPOTENCY_BACKEND` (`redis` or `postgres`), defaulting to Redis
+    pub fn from_env() -> Self {
+        std::env::var("IDEMPOTENCY_BACKEND")
+            .unwrap_or_else(|_| "redis".to_string())
+            .parse()
+            .unwrap_or_default()
+    }
+}
+
+impl FromStr for IdempotencyBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "redis" => Ok(Self::Redis),
+            "postgres" | "postgresql" => Ok(Self::Postgres),
+            other => Err(format!(
+                "unknown idempotency backend '{}', expected redis or postgres",
+                other
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_backend() {
+        assert_eq!("redis".parse(), Ok(IdempotencyBackend::Redis));
+        assert_eq!(" Postgres ".parse(), Ok(IdempotencyBackend::Postgres));
+        assert!("memcached".parse::<IdempotencyBackend>().is_err());
+    }
+}
@@ -0,0 +1,291 @@
+use super::{
+    ConflictingEvent, DeleteMode, Event, EventStore, EventStoreError, MAX_CONFLICTING_EVENTS,
+    TOMBSTONE_EVENT_TYPE,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::events::stream_events::StreamDeletedEvent;
+use domain::events::{DomainEvent, EventMetadata};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct Streams {
+    events: HashMap<Uuid, Vec<Event>>,
+    processed_commands: HashSet<Uuid>,
+}
+
+/// Event store kept in process memory, for tests and local demos
+///
+/// Appends are checked like [`PostgresEventStore`](crate::PostgresEventStore) checks
+/// them: optimistic concurrency, one aggregate type per stream, closed deleted streams
+/// and command IDs claimed once. Hash chaining, schema validation and the aggregate
+/// type registry are not applied.
+#[derive(Default)]
+pub struct InMemoryEventStore {
+    streams: Mutex<Streams>,
+}
+
+impl InMemoryEventStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every stored event, in no particular order across aggregates
+    pub fn all_events(&self) -> Vec<Event> {
+        let streams = self.streams.lock().unwrap();
+        streams.events.values().flatten().cloned().collect()
+    }
+
+    fn stream(&self, aggregate_id: Uuid) -> Vec<Event> {
+        let streams = self.streams.lock().unwrap();
+        streams.events.get(&aggregate_id).cloned().unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl EventStore for InMemoryEventStore {
+    async fn append_events(
+        &self,
+        aggregate_id: Uuid,
+        expected_version: i64,
+        events: Vec<Event>,
+    ) -> Result<(), EventStoreError> {
+        let Some(first) = events.first() else {
+            return Ok(());
+        };
+        let aggregate_type = first.aggregate_type.clone();
+        if let Some(other) = events.iter().find(|e| e.aggregate_type != aggregate_type) {
+            return Err(EventStoreError::AggregateTypeMismatch {
+                aggregate_id,
+                expected: aggregate_type,
+                actual: other.aggregate_type.clone(),
+            });
+        }
+
+        let mut streams = self.streams.lock().unwrap();
+        let stream = streams.events.get(&aggregate_id).map(Vec::as_slice).unwrap_or_default();
+
+        if stream.iter().any(|e| e.event_type == TOMBSTONE_EVENT_TYPE) {
+            return Err(EventStoreError::StreamDeleted(aggregate_id));
+        }
+        if let Some(existing) = stream.first().filter(|e| e.aggregate_type != aggregate_type) {
+            return Err(EventStoreError::AggregateTypeMismatch {
+                aggregate_id,
+                expected: existing.aggregate_type.clone(),
+                actual: aggregate_type,
+            });
+        }
+
+        let current = stream.last().map_or(0, |e| e.sequence_number);
+        if current != expected_version {
+            let conflicting = stream
+                .iter()
+                .filter(|e| e.sequence_number > expected_version)
+                .take(MAX_CONFLICTING_EVENTS as usize)
+                .map(|e| ConflictingEvent {
+                    event_type: e.event_type.clone(),
+                    version: e.sequence_number,
+                })
+                .collect();
+            return Err(EventStoreError::ConcurrencyConflict {
+                expected: expected_version,
+                actual: current,
+                conflicting,
+            });
+        }
+
+        let command_ids: HashSet<Uuid> = events.iter().filter_map(Event::command_id).collect();
+        if let Some(command_id) = command_ids
+            .iter()
+            .find(|command_id| streams.processed_commands.contains(command_id))
+        {
+            return Err(EventStoreError::DuplicateCommand(*command_id));
+        }
+        streams.processed_commands.extend(command_ids);
+
+        let stream = streams.events.entry(aggregate_id).or_default();
+        for (i, mut event) in events.into_iter().enumerate() {
+            event.aggregate_id = aggregate_id;
+            event.sequence_number = expected_version + i as i64 + 1;
+            stream.push(event);
+        }
+        Ok(())
+    }
+
+    async fn load_events(&self, aggregate_id: Uuid) -> Result<Vec<Event>, EventStoreError> {
+        Ok(self.stream(aggregate_id))
+    }
+
+    async fn load_events_from_version(
+        &self,
+        aggregate_id: Uuid,
+        from_version: i64,
+    ) -> Result<Vec<Event>, EventStoreError> {
+        let mut events = self.stream(aggregate_id);
+        events.retain(|e| e.sequence_number > from_version);
+        Ok(events)
+    }
+
+    async fn load_events_page(
+        &self,
+        aggregate_id: Uuid,
+        after_version: i64,
+        page_size: i64,
+    ) -> Result<Vec<Event>, EventStoreError> {
+        let mut events = self.load_events_from_version(aggregate_id, after_version).await?;
+        events.truncate(page_size.max(0) as usize);
+        Ok(events)
+    }
+
+    async fn get_current_version(&self, aggregate_id: Uuid) -> Result<i64, EventStoreError> {
+        Ok(self.stream(aggregate_id).last().map_or(0, |e| e.sequence_number))
+    }
+
+    async fn delete_stream(&self, aggregate_id: Uuid, mode: DeleteMode) -> Result<(), EventStoreError> {
+        match mode {
+            DeleteMode::Soft => {
+                let last = self
+                    .stream(aggregate_id)
+                    .pop()
+                    .ok_or(EventStoreError::AggregateNotFound(aggregate_id))?;
+                if last.event_type == TOMBSTONE_EVENT_TYPE {
+                    return Ok(());
+                }
+
+                let payload = StreamDeletedEvent {
+                    aggregate_id,
+                    mode: "soft".to_string(),
+                    deleted_at: Utc::now(),
+                };
+                let tombstone = Event::new(
+                    aggregate_id,
+                    last.aggregate_type,
+                    TOMBSTONE_EVENT_TYPE.to_string(),
+                    StreamDeletedEvent::event_version(),
+                    serde_json::to_value(payload)?,
+                    EventMetadata::new(),
+                );
+                self.append_events(aggregate_id, last.sequence_number, vec![tombstone])
+                    .await
+            }
+            DeleteMode::Hard => {
+                let mut streams = self.streams.lock().unwrap();
+                match streams.events.remove(&aggregate_id) {
+                    Some(_) => Ok(()),
+                    None => Err(EventStoreError::AggregateNotFound(aggregate_id)),
+                }
+            }
+        }
+    }
+
+    async fn is_stream_deleted(&self, aggregate_id: Uuid) -> Result<bool, EventStoreError> {
+        Ok(self
+            .stream(aggregate_id)
+            .iter()
+            .any(|e| e.event_type == TOMBSTONE_EVENT_TYPE))
+    }
+
+    async fn find_events_by_command_id(
+        &self,
+        command_id: Uuid,
+    ) -> Result<Vec<Event>, EventStoreError> {
+        let mut events: Vec<Event> = self
+            .all_events()
+            .into_iter()
+            .filter(|e| e.command_id() == Some(command_id))
+            .collect();
+        events.sort_by_key(|e| (e.created_at, e.sequence_number));
+        Ok(events)
+    }
+
+    async fn find_events_by_correlation_id(
+        &self,
+        correlation_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Event>, EventStoreError> {
+        let mut events: Vec<Event> = self
+            .all_events()
+            .into_iter()
+            .filter(|e| e.correlation_id() == correlation_id)
+            .collect();
+        events.sort_by_key(|e| (e.created_at, e.aggregate_id, e.sequence_number));
+        events.truncate(limit.max(0) as usize);
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(aggregate_id: Uuid, aggregate_type: &str, metadata: EventMetadata) -> Event {
+        Event::new(
+            aggregate_id,
+            aggregate_type.to_string(),
+            "OrderCreated".to_string(),
+            1,
+            serde_json::json!({}),
+            metadata,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_append_checks_expected_version() {
+        let store = InMemoryEventStore::new();
+        let aggregate_id = Uuid::new_v4();
+
+        store
+            .append_events(aggregate_id, 0, vec![event(aggregate_id, "Order", EventMetadata::new())])
+            .await
+            .unwrap();
+        let stale = store
+            .append_events(aggregate_id, 0, vec![event(aggregate_id, "Order", EventMetadata::new())])
+            .await;
+
+        match stale {
+            Err(EventStoreError::ConcurrencyConflict { expected, actual, conflicting }) => {
+                assert_eq!((expected, actual), (0, 1));
+                assert_eq!(conflicting[0].version, 1);
+            }
+            other => panic!("expected a concurrency conflict, got {:?}", other),
+        }
+        assert_eq!(store.get_current_version(aggregate_id).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_command_ids_and_deleted_streams() {
+        let store = InMemoryEventStore::new();
+        let aggregate_id = Uuid::new_v4();
+        let command_id = Uuid::new_v4();
+        let metadata = EventMetadata::new().with_command_id(Some(command_id));
+
+        store
+            .append_events(aggregate_id, 0, vec![event(aggregate_id, "Order", metadata.clone())])
+            .await
+            .unwrap();
+        assert_eq!(store.find_events_by_command_id(command_id).await.unwrap().len(), 1);
+        assert!(matches!(
+            store
+                .append_events(aggregate_id, 1, vec![event(aggregate_id, "Order", metadata)])
+                .await,
+            Err(EventStoreError::DuplicateCommand(id)) if id == command_id
+        ));
+        assert!(matches!(
+            store
+                .append_events(aggregate_id, 1, vec![event(aggregate_id, "Customer", EventMetadata::new())])
+                .await,
+            Err(EventStoreError::AggregateTypeMismatch { .. })
+        ));
+
+        store.delete_stream(aggregate_id, DeleteMode::Soft).await.unwrap();
+        assert!(store.is_stream_deleted(aggregate_id).await.unwrap());
+        assert!(matches!(
+            store
+                .append_events(aggregate_id, 2, vec![event(aggregate_id, "Order", EventMetadata::new())])
+                .await,
+            Err(EventStoreError::StreamDeleted(_))
+        ));
+    }
+}
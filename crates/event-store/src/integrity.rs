@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::Event;
+
+/// Hash value used as the predecessor of the first event in a stream
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Compute the chained hash for an event
+///
+/// The hash covers the previous event's hash plus the event's identity,
+/// position, type and payload, so altering, removing or reordering any row
+/// breaks every hash after it.
+pub fn compute_event_hash(prev_hash: &str, event: &Event, version: i64) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(prev_hash.as_bytes());
+    hasher.update(event.event_id.as_bytes());
+    hasher.update(event.aggregate_id.as_bytes());
+    hasher.update(version.to_be_bytes());
+    hasher.update(event.event_type.as_bytes());
+    hasher.update(event.event_version.to_be_bytes());
+    hasher.update(event.payload.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Outcome of verifying an aggregate's hash chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamVerification {
+    pub aggregate_id: Uuid,
+    pub events_checked: usize,
+    /// Events appended before hash chaining was enabled
+    pub unhashed_events: usize,
+    /// Version of the first event whose hash does not match
+    pub first_invalid_version: Option<i64>,
+}
+
+impl StreamVerification {
+    pub fn is_valid(&self) -> bool {
+        self.first_invalid_version.is_none()
+    }
+}
+
+/// Walks an aggregate's events in version order and checks their hash chain
+///
+/// Unhashed events are accepted only before the first hashed one, i.e. those
+/// appended before hash chaining was enabled. Once the chain has started, an event
+/// without a hash breaks it, as a row whose hash was cleared would otherwise let
+/// the chain restart from the genesis hash after any edit.
+pub struct ChainVerifier {
+    verification: StreamVerification,
+    expected_prev: Option<String>,
+}
+
+impl ChainVerifier {
+    pub fn new(aggregate_id: Uuid) -> Self {
+        Self {
+            verification: StreamVerification {
+                aggregate_id,
+                events_checked: 0,
+                unhashed_events: 0,
+                first_invalid_version: None,
+            },
+            expected_prev: None,
+        }
+    }
+
+    /// Check the next event with its stored hashes; false once the chain is broken
+    pub fn check(&mut self, event: &Event, prev_hash: Option<&str>, hash: Option<&str>) -> bool {
+        if !self.verification.is_valid() {
+            return false;
+        }
+        self.verification.events_checked += 1;
+
+        let Some(hash) = hash else {
+            if self.expected_prev.is_some() {
+                self.verification.first_invalid_version = Some(event.sequence_number);
+                return false;
+            }
+            self.verification.unhashed_events += 1;
+            return true;
+        };
+
+        let expected_prev = self.expected_prev.as_deref().unwrap_or(GENESIS_HASH);
+        let recomputed = compute_event_hash(expected_prev, event, event.sequence_number);
+        if prev_hash != Some(expected_prev) || recomputed != hash {
+            self.verification.first_invalid_version = Some(event.sequence_number);
+            return false;
+        }
+
+        self.expected_prev = Some(hash.to_string());
+        true
+    }
+
+    pub fn finish(self) -> StreamVerification {
+        self.verification
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::events::EventMetadata;
+
+    fn test_event() -> Event {
+        Event::new(
+            Uuid::new_v4(),
+            "Order".to_string(),
+            "OrderCreated".to_string(),
+            1,
+            serde_json::json!({"total_amount": 100.5}),
+            EventMetadata::new(),
+        )
+    }
+
+    #[test]
+    fn test_hash_is_deterministic() {
+        let event = test_event();
+        assert_eq!(
+            compute_event_hash(GENESIS_HASH, &event, 1),
+            compute_event_hash(GENESIS_HASH, &event, 1)
+        );
+    }
+
+    #[test]
+    fn test_hash_depends_on_previous_hash() {
+        let event = test_event();
+        let first = compute_event_hash(GENESIS_HASH, &event, 1);
+        let chained = compute_event_hash(&first, &event, 1);
+        assert_ne!(first, chained);
+    }
+
+    #[test]
+    fn test_hash_detects_payload_change() {
+        let mut event = test_event();
+        let original = compute_event_hash(GENESIS_HASH, &event, 1);
+
+        event.payload = serde_json::json!({"total_amount": 1.0});
+        assert_ne!(original, compute_event_hash(GENESIS_HASH, &event, 1));
+    }
+
+    /// An event with its stored `prev_hash` and `hash`
+    type StoredEvent = (Event, Option<String>, Option<String>);
+
+    /// Events at versions 1..=count, each hashed when `hashed(version)` is true
+    fn stream(count: i64, hashed: impl Fn(i64) -> bool) -> Vec<StoredEvent> {
+        let aggregate_id = Uuid::new_v4();
+        let mut prev = GENESIS_HASH.to_string();
+        (1..=count)
+            .map(|version| {
+                let mut event = test_event();
+                event.aggregate_id = aggregate_id;
+                event.sequence_number = version;
+                if !hashed(version) {
+                    return (event, None, None);
+                }
+                let hash = compute_event_hash(&prev, &event, version);
+                let prev_hash = std::mem::replace(&mut prev, hash.clone());
+                (event, Some(prev_hash), Some(hash))
+            })
+            .collect()
+    }
+
+    fn verify(events: &[StoredEvent]) -> StreamVerification {
+        let mut verifier = ChainVerifier::new(events[0].0.aggregate_id);
+        for (event, prev_hash, hash) in events {
+            if !verifier.check(event, prev_hash.as_deref(), hash.as_deref()) {
+                break;
+            }
+        }
+        verifier.finish()
+    }
+
+    #[test]
+    fn test_unhashed_events_before_the_chain_are_accepted() {
+        let verification = verify(&stream(4, |version| version > 2));
+        assert!(verification.is_valid());
+        assert_eq!(verification.events_checked, 4);
+        assert_eq!(verification.unhashed_events, 2);
+    }
+
+    #[test]
+    fn test_unhashed_event_after_the_chain_started_is_invalid() {
+        let verification = verify(&stream(4, |version| version != 3));
+        assert_eq!(verification.first_invalid_version, Some(3));
+        assert_eq!(verification.unhashed_events, 0);
+    }
+
+    #[test]
+    fn test_cleared_hash_does_not_restart_the_chain() {
+        // Tampering with version 2 and clearing its hash must not let version 3
+        // pass by chaining from the genesis hash again
+        let mut events = stream(2, |_| true);
+        events[1].0.payload = serde_json::json!({"total_amount": 0.0});
+        events[1].1 = None;
+        events[1].2 = None;
+        let mut tail = test_event();
+        tail.aggregate_id = events[0].0.aggregate_id;
+        tail.sequence_number = 3;
+        let hash = compute_event_hash(GENESIS_HASH, &tail, 3);
+        events.push((tail, Some(GENESIS_HASH.to_string()), Some(hash)));
+
+        assert_eq!(verify(&events).first_invalid_version, Some(2));
+    }
+}
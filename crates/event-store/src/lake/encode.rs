@@ -0,0 +1,123 @@
+use arrow_array::{
+    ArrayRef, Int32Array, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::sync::Arc;
+
+use super::LakeEvent;
+use crate::EventStoreError;
+
+/// Columns of a lake file; the aggregate type and day are partition columns, taken
+/// from the file's path rather than stored in it
+fn schema() -> SchemaRef {
+    let timestamp = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+    Arc::new(Schema::new(vec![
+        Field::new("position", DataType::Int64, false),
+        Field::new("event_id", DataType::Utf8, false),
+        Field::new("aggregate_id", DataType::Utf8, false),
+        Field::new("event_type", DataType::Utf8, false),
+        Field::new("event_version", DataType::Int32, false),
+        Field::new("version", DataType::Int64, false),
+        Field::new("payload", DataType::Utf8, false),
+        Field::new("metadata", DataType::Utf8, false),
+        Field::new("correlation_id", DataType::Utf8, true),
+        Field::new("causation_id", DataType::Utf8, true),
+        Field::new("user_id", DataType::Utf8, true),
+        Field::new("created_at", timestamp, false),
+    ]))
+}
+
+/// A Snappy-compressed Parquet file holding `events`; JSON columns stay JSON
+pub(super) fn encode(events: &[&LakeEvent]) -> Result<Vec<u8>, EventStoreError> {
+    let schema = schema();
+    let strings = |value: fn(&LakeEvent) -> String| -> ArrayRef {
+        Arc::new(StringArray::from(
+            events.iter().map(|event| value(event)).collect::<Vec<_>>(),
+        ))
+    };
+    let optional_strings = |value: fn(&LakeEvent) -> Option<String>| -> ArrayRef {
+        Arc::new(StringArray::from(
+            events.iter().map(|event| value(event)).collect::<Vec<_>>(),
+        ))
+    };
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Int64Array::from(
+            events
+                .iter()
+                .map(|event| event.position)
+                .collect::<Vec<_>>(),
+        )),
+        strings(|event| event.event_id.to_string()),
+        strings(|event| event.aggregate_id.to_string()),
+        strings(|event| event.event_type.clone()),
+        Arc::new(Int32Array::from(
+            events
+                .iter()
+                .map(|event| event.event_version)
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(Int64Array::from(
+            events.iter().map(|event| event.version).collect::<Vec<_>>(),
+        )),
+        strings(|event| event.payload.to_string()),
+        strings(|event| event.metadata.to_string()),
+        optional_strings(|event| event.correlation_id.map(|id| id.to_string())),
+        optional_strings(|event| event.causation_id.map(|id| id.to_string())),
+        optional_strings(|event| event.user_id.map(|id| id.to_string())),
+        Arc::new(
+            TimestampMicrosecondArray::from(
+                events
+                    .iter()
+                    .map(|event| event.created_at.timestamp_micros())
+                    .collect::<Vec<_>>(),
+            )
+            .with_timezone("UTC"),
+        ),
+    ];
+
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(lake_error)?;
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(Vec::new(), schema, Some(props)).map_err(lake_error)?;
+    writer.write(&batch).map_err(lake_error)?;
+    writer.into_inner().map_err(lake_error)
+}
+
+fn lake_error(error: impl std::fmt::Display) -> EventStoreError {
+    EventStoreError::LakeError(format!("failed to encode Parquet file: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lake::tests::event;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int64Type;
+    use chrono::Utc;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    #[test]
+    fn test_encode_round_trip() {
+        let events = [event(7, "Order", Utc::now()), event(9, "Order", Utc::now())];
+        let file = encode(&events.iter().collect::<Vec<_>>()).unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(file))
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.collect::<Result<_, _>>().unwrap();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.schema().fields(), schema().fields());
+
+        let positions = batch.column(0).as_primitive::<Int64Type>();
+        assert_eq!(positions.values(), &[7, 9]);
+        let payloads = batch.column(6).as_string::<i32>();
+        assert_eq!(payloads.value(0), r#"{"total":10}"#);
+        assert!(batch.column(9).is_null(0));
+    }
+}
@@ -1,17 +1,58 @@
+pub mod aggregate_types;
+#[cfg(feature = "redis")]
 pub mod idempotency;
+pub mod idempotency_store;
+pub mod in_memory;
+pub mod integrity;
+#[cfg(feature = "lake")]
+pub mod lake;
+#[cfg(feature = "postgres")]
 pub mod postgres_event_store;
+#[cfg(feature = "postgres")]
+pub mod postgres_idempotency;
 pub mod replay;
+#[cfg(feature = "postgres")]
+pub mod replication;
+pub mod schema_validation;
 
+pub use aggregate_types::AggregateTypeRegistry;
+#[cfg(feature = "redis")]
 pub use idempotency::{IdempotencyChecker, generate_idempotency_key};
-pub use postgres_event_store::PostgresEventStore;
+pub use idempotency_store::{
+    IdempotencyBackend, IdempotencyClaim, IdempotencyError, IdempotencyStatus, IdempotencyStore,
+};
+pub use in_memory::InMemoryEventStore;
+pub use integrity::StreamVerification;
+#[cfg(feature = "lake")]
+pub use lake::{
+    EventLakeSink, LakeBatch, LakeCursor, LakeFile, LakePartition, LocalObjectStore, ObjectStore,
+    S3Config, S3ObjectStore,
+};
+#[cfg(feature = "postgres")]
+pub use postgres_event_store::{AppendObserver, PostgresEventStore, DEFAULT_SLOW_APPEND_THRESHOLD};
+#[cfg(feature = "postgres")]
+pub use postgres_idempotency::PgIdempotencyStore;
 pub use replay::{EventReplayService, Rebuildable, ReplayConfig, ReplayStats};
+#[cfg(feature = "postgres")]
+pub use replication::{EventReplicator, ReplicationBatch, ReplicationCheckpoint};
+pub use schema_validation::{PayloadValidator, SchemaCompileError};
+
+pub use domain::api::ConflictingEvent;
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use common::bulkhead::BulkheadFull;
+use common::deadline::DeadlineExceeded;
+use domain::events::{EventEnvelope, EventMetadata};
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Uuid;
 
+/// An event as persisted in the event store
+///
+/// This is the stored form of [`EventEnvelope`], which is what gets published;
+/// convert between them with `From` rather than copying fields.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Event {
     pub event_id: Uuid,
@@ -20,7 +61,7 @@ pub struct Event {
     pub event_type: String,
     pub event_version: i32,
     pub payload: serde_json::Value,
-    pub metadata: serde_json::Value,
+    pub metadata: EventMetadata,
     pub sequence_number: i64,
     pub created_at: DateTime<Utc>,
 }
@@ -32,7 +73,7 @@ impl Event {
         event_type: String,
         event_version: i32,
         payload: serde_json::Value,
-        metadata: serde_json::Value,
+        metadata: EventMetadata,
     ) -> Self {
         Self {
             event_id: Uuid::new_v4(),
@@ -46,6 +87,101 @@ impl Event {
             created_at: Utc::now(),
         }
     }
+
+    /// Client-supplied command ID recorded in the event metadata, if any
+    pub fn command_id(&self) -> Option<Uuid> {
+        self.metadata.command_id
+    }
+
+    pub fn correlation_id(&self) -> Uuid {
+        self.metadata.correlation_id
+    }
+
+    pub fn causation_id(&self) -> Uuid {
+        self.metadata.causation_id
+    }
+
+    /// User ID recorded in the event metadata, if any
+    pub fn user_id(&self) -> Option<Uuid> {
+        self.metadata.user_id
+    }
+
+    /// ID of the event this one corrects, if it is a correction
+    pub fn corrects_event_id(&self) -> Option<Uuid> {
+        self.metadata.corrects_event_id
+    }
+}
+
+/// Parse the metadata column of a stored event, rejecting rows that don't match [`EventMetadata`]
+pub fn parse_metadata(event_id: Uuid, metadata: serde_json::Value) -> Result<EventMetadata, EventStoreError> {
+    serde_json::from_value(metadata).map_err(|e| EventStoreError::InvalidMetadata {
+        event_id,
+        reason: e.to_string(),
+    })
+}
+
+impl From<&EventEnvelope> for Event {
+    /// The store assigns the stored version itself, so an unset sequence number becomes 0
+    fn from(envelope: &EventEnvelope) -> Self {
+        Self::from(envelope.clone())
+    }
+}
+
+impl From<EventEnvelope> for Event {
+    fn from(envelope: EventEnvelope) -> Self {
+        Self {
+            event_id: envelope.event_id,
+            aggregate_id: envelope.aggregate_id,
+            aggregate_type: envelope.aggregate_type,
+            event_type: envelope.event_type,
+            event_version: envelope.event_version,
+            payload: envelope.payload,
+            metadata: envelope.metadata,
+            sequence_number: envelope.sequence_number.unwrap_or(0),
+            created_at: envelope.timestamp,
+        }
+    }
+}
+
+impl From<Event> for EventEnvelope {
+    fn from(event: Event) -> Self {
+        Self {
+            event_id: event.event_id,
+            aggregate_id: event.aggregate_id,
+            aggregate_type: event.aggregate_type,
+            event_type: event.event_type,
+            event_version: event.event_version,
+            payload: event.payload,
+            metadata: event.metadata,
+            timestamp: event.created_at,
+            sequence_number: Some(event.sequence_number),
+        }
+    }
+}
+
+impl From<&Event> for EventEnvelope {
+    fn from(event: &Event) -> Self {
+        Self::from(event.clone())
+    }
+}
+
+/// Default number of events fetched per page when streaming an aggregate
+pub const DEFAULT_PAGE_SIZE: i64 = 500;
+
+/// Event type of the tombstone appended when a stream is soft-deleted
+pub const TOMBSTONE_EVENT_TYPE: &str = "StreamDeleted";
+
+/// Most events reported back on a concurrency conflict
+pub(crate) const MAX_CONFLICTING_EVENTS: i64 = 20;
+
+/// How a stream should be removed from the event store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeleteMode {
+    /// Append a tombstone event; history is kept but excluded from replay
+    Soft,
+    /// Physically remove every event (and snapshot) for the aggregate
+    Hard,
 }
 
 /// Event store trait for persisting and retrieving events
@@ -69,18 +205,90 @@ pub trait EventStore: Send + Sync {
         from_version: i64,
     ) -> Result<Vec<Event>, EventStoreError>;
 
+    /// Load a single page of events with a version greater than `after_version`
+    async fn load_events_page(
+        &self,
+        aggregate_id: Uuid,
+        after_version: i64,
+        page_size: i64,
+    ) -> Result<Vec<Event>, EventStoreError>;
+
     /// Get the current version of an aggregate
     async fn get_current_version(&self, aggregate_id: Uuid) -> Result<i64, EventStoreError>;
+
+    /// Delete an aggregate's stream, either with a tombstone or by scavenging it
+    async fn delete_stream(&self, aggregate_id: Uuid, mode: DeleteMode) -> Result<(), EventStoreError>;
+
+    /// Check whether an aggregate's stream has been soft-deleted
+    async fn is_stream_deleted(&self, aggregate_id: Uuid) -> Result<bool, EventStoreError>;
+
+    /// Load the events produced by a client-supplied command ID, if any
+    async fn find_events_by_command_id(
+        &self,
+        command_id: Uuid,
+    ) -> Result<Vec<Event>, EventStoreError>;
+
+    /// Load every event sharing a correlation ID, across all aggregates, oldest first
+    async fn find_events_by_correlation_id(
+        &self,
+        correlation_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Event>, EventStoreError>;
+
+    /// Stream events for an aggregate, fetching at most `page_size` rows at a time
+    ///
+    /// Unlike `load_events`, memory usage is bounded by the page size rather
+    /// than the length of the stream.
+    fn load_events_paged(
+        &self,
+        aggregate_id: Uuid,
+        after_version: i64,
+        page_size: i64,
+    ) -> BoxStream<'_, Result<Event, EventStoreError>> {
+        let page_size = page_size.max(1);
+
+        stream::try_unfold(Some(after_version), move |cursor| async move {
+            let Some(after_version) = cursor else {
+                return Ok::<_, EventStoreError>(None);
+            };
+
+            let page = self
+                .load_events_page(aggregate_id, after_version, page_size)
+                .await?;
+
+            if page.is_empty() {
+                return Ok(None);
+            }
+
+            // A short page means we've reached the end of the stream
+            let next = if (page.len() as i64) < page_size {
+                None
+            } else {
+                page.last().map(|event| event.sequence_number)
+            };
+
+            Ok(Some((page, next)))
+        })
+        .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum EventStoreError {
+    /// `conflicting` lists the events appended since `expected`, oldest first
     #[error("Concurrency conflict: expected version {expected}, got {actual}")]
-    ConcurrencyConflict { expected: i64, actual: i64 },
+    ConcurrencyConflict {
+        expected: i64,
+        actual: i64,
+        conflicting: Vec<ConflictingEvent>,
+    },
 
     #[error("Aggregate not found: {0}")]
     AggregateNotFound(Uuid),
 
+    #[cfg(feature = "postgres")]
     #[error("Database error: {0}")]
     DatabaseError(#[from] sqlx::Error),
 
@@ -89,11 +297,61 @@ pub enum EventStoreError {
 
     #[error("Invalid version: {0}")]
     InvalidVersion(String),
+
+    #[error("Stream has been deleted: {0}")]
+    StreamDeleted(Uuid),
+
+    #[error("Command already processed: {0}")]
+    DuplicateCommand(Uuid),
+
+    #[error("Event store overloaded: {0}")]
+    Overloaded(#[from] BulkheadFull),
+
+    /// The request's deadline passed before the operation finished
+    #[error("Event store operation cancelled: {0}")]
+    DeadlineExceeded(#[from] DeadlineExceeded),
+
+    #[error("Invalid metadata on event {event_id}: {reason}")]
+    InvalidMetadata { event_id: Uuid, reason: String },
+
+    #[error("Stream {aggregate_id} holds {expected} events, refusing to append {actual} events")]
+    AggregateTypeMismatch {
+        aggregate_id: Uuid,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("{event_type} is not a registered {aggregate_type} event")]
+    UnregisteredEventType {
+        aggregate_type: String,
+        event_type: String,
+    },
+
+    #[error("Invalid {event_type} v{event_version} payload: {reason}")]
+    InvalidPayload {
+        event_type: String,
+        event_version: i32,
+        reason: String,
+    },
+
+    /// The replica holds a different event at a version the source is replicating
+    #[error("Replica of {aggregate_id} diverged at version {version}: source has event {source_event_id}, replica has {replica_event_id}")]
+    ReplicationConflict {
+        aggregate_id: Uuid,
+        version: i64,
+        source_event_id: Uuid,
+        replica_event_id: Uuid,
+    },
+
+    /// Writing to the event lake failed
+    #[error("Event lake error: {0}")]
+    LakeError(String),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn test_event_creation() {
@@ -104,11 +362,194 @@ mod tests {
             "OrderCreated".to_string(),
             1,
             serde_json::json!({"test": "data"}),
-            serde_json::json!({}),
+            EventMetadata::new(),
         );
 
         assert_eq!(event.aggregate_id, aggregate_id);
         assert_eq!(event.event_type, "OrderCreated");
         assert_eq!(event.event_version, 1);
     }
+
+    #[test]
+    fn test_event_from_envelope() {
+        use domain::events::order_events::OrderConfirmedEvent;
+
+        let order_id = Uuid::new_v4();
+        let command_id = Uuid::new_v4();
+        let metadata = EventMetadata::new().with_command_id(Some(command_id));
+        let envelope = EventEnvelope::builder(order_id, "Order")
+            .with_metadata(metadata.clone())
+            .with_sequence_number(2)
+            .build(&OrderConfirmedEvent {
+                order_id,
+                confirmed_at: Utc::now(),
+            })
+            .unwrap();
+
+        let event = Event::from(&envelope);
+        assert_eq!(event.event_id, envelope.event_id);
+        assert_eq!(event.event_type, "OrderConfirmed");
+        assert_eq!(event.payload, envelope.payload);
+        assert_eq!(event.sequence_number, 2);
+        assert_eq!(event.created_at, envelope.timestamp);
+        assert_eq!(event.command_id(), Some(command_id));
+        assert_eq!(event.metadata, metadata);
+
+        let round_trip = EventEnvelope::from(event);
+        assert_eq!(round_trip.event_id, envelope.event_id);
+        assert_eq!(round_trip.timestamp, envelope.timestamp);
+        assert_eq!(round_trip.sequence_number, Some(2));
+        assert_eq!(round_trip.metadata.command_id, Some(command_id));
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let event_id = Uuid::new_v4();
+        let correlation_id = Uuid::new_v4();
+        let metadata = parse_metadata(
+            event_id,
+            serde_json::json!({
+                "correlation_id": correlation_id,
+                "causation_id": correlation_id,
+                "user_id": null,
+                "tenant": "acme",
+            }),
+        )
+        .unwrap();
+        assert_eq!(metadata.correlation_id, correlation_id);
+        assert_eq!(metadata.extra["tenant"], "acme");
+
+        let result = parse_metadata(event_id, serde_json::json!({}));
+        assert!(matches!(
+            result,
+            Err(EventStoreError::InvalidMetadata { event_id: id, .. }) if id == event_id
+        ));
+    }
+
+    #[test]
+    fn test_event_command_id() {
+        let command_id = Uuid::new_v4();
+        let event = Event::new(
+            Uuid::new_v4(),
+            "Order".to_string(),
+            "OrderCreated".to_string(),
+            1,
+            serde_json::json!({}),
+            EventMetadata::new().with_command_id(Some(command_id)),
+        );
+        assert_eq!(event.command_id(), Some(command_id));
+
+        let event = Event::new(
+            Uuid::new_v4(),
+            "Order".to_string(),
+            "OrderCreated".to_string(),
+            1,
+            serde_json::json!({}),
+            EventMetadata::new(),
+        );
+        assert!(event.command_id().is_none());
+    }
+
+    #[test]
+    fn test_event_metadata_ids() {
+        let correlation_id = Uuid::new_v4();
+        let event = Event::new(
+            Uuid::new_v4(),
+            "Order".to_string(),
+            "OrderCreated".to_string(),
+            1,
+            serde_json::json!({}),
+            EventMetadata::with_correlation(correlation_id),
+        );
+
+        assert_eq!(event.correlation_id(), correlation_id);
+        assert_eq!(event.causation_id(), event.metadata.causation_id);
+        assert!(event.user_id().is_none());
+    }
+
+    fn order_event(aggregate_id: Uuid, event_type: &str) -> Event {
+        Event::new(
+            aggregate_id,
+            "Order".to_string(),
+            event_type.to_string(),
+            1,
+            serde_json::json!({}),
+            EventMetadata::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_deleted_streams_are_excluded_from_replay() {
+        let store = Arc::new(InMemoryEventStore::new());
+        let (deleted, purged, live) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        for aggregate_id in [deleted, purged, live] {
+            store
+                .append_events(aggregate_id, 0, vec![order_event(aggregate_id, "OrderCreated")])
+                .await
+                .unwrap();
+        }
+
+        store.delete_stream(deleted, DeleteMode::Soft).await.unwrap();
+        store.delete_stream(purged, DeleteMode::Hard).await.unwrap();
+        assert!(store.is_stream_deleted(deleted).await.unwrap());
+        assert!(!store.is_stream_deleted(live).await.unwrap());
+        assert_eq!(store.get_current_version(deleted).await.unwrap(), 2);
+        assert!(store.load_events(purged).await.unwrap().is_empty());
+        assert!(matches!(
+            store
+                .append_events(deleted, 2, vec![order_event(deleted, "OrderConfirmed")])
+                .await,
+            Err(EventStoreError::StreamDeleted(id)) if id == deleted
+        ));
+
+        let replayed = Arc::new(Mutex::new(Vec::new()));
+        let config = ReplayConfig {
+            aggregate_ids: Some(vec![deleted, purged, live]),
+            ..ReplayConfig::default()
+        };
+        let stats = EventReplayService::new(store)
+            .replay_events(config, |event| {
+                let replayed = replayed.clone();
+                async move {
+                    replayed.lock().unwrap().push(event.aggregate_id);
+                    Ok(())
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(stats.processed_events, 1);
+        assert_eq!(*replayed.lock().unwrap(), vec![live]);
+    }
+
+    #[tokio::test]
+    async fn test_load_events_paged_spans_pages() {
+        let aggregate_id = Uuid::new_v4();
+        let events = (1..=7).map(|_| order_event(aggregate_id, "OrderCreated")).collect();
+        let store = InMemoryEventStore::new();
+        store.append_events(aggregate_id, 0, events).await.unwrap();
+
+        let loaded: Vec<Event> = store
+            .load_events_paged(aggregate_id, 0, 3)
+            .try_collect()
+            .await
+            .unwrap();
+        let versions: Vec<i64> = loaded.iter().map(|e| e.sequence_number).collect();
+        assert_eq!(versions, vec![1, 2, 3, 4, 5, 6, 7]);
+
+        let loaded: Vec<Event> = store
+            .load_events_paged(aggregate_id, 5, 3)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(loaded.len(), 2);
+    }
+
+    #[test]
+    fn test_delete_mode_deserialization() {
+        let mode: DeleteMode = serde_json::from_str("\"soft\"").unwrap();
+        assert_eq!(mode, DeleteMode::Soft);
+        let mode: DeleteMode = serde_json::from_str("\"hard\"").unwrap();
+        assert_eq!(mode, DeleteMode::Hard);
+    }
 }
@@ -1,29 +1,241 @@
-use super::{Event, EventStore, EventStoreError};
+use super::{
+    parse_metadata, ConflictingEvent, DeleteMode, Event, EventStore, EventStoreError,
+    MAX_CONFLICTING_EVENTS, TOMBSTONE_EVENT_TYPE,
+};
+use crate::aggregate_types::AggregateTypeRegistry;
+use crate::integrity::{compute_event_hash, ChainVerifier, StreamVerification, GENESIS_HASH};
+use crate::schema_validation::PayloadValidator;
 use async_trait::async_trait;
-use sqlx::{PgPool, Row};
-use tracing::{debug, error, info};
+use chrono::Utc;
+use common::bulkhead::Bulkhead;
+use common::metrics;
+use domain::events::stream_events::StreamDeletedEvent;
+use domain::events::{DomainEvent, EventMetadata};
+use futures::TryStreamExt;
+use sqlx::postgres::PgRow;
+use sqlx::{PgConnection, PgPool, Row};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+/// Appends slower than this are logged and counted as slow by default
+pub const DEFAULT_SLOW_APPEND_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// Callback told how long each append took, whether or not it succeeded
+pub type AppendObserver = Arc<dyn Fn(Duration) + Send + Sync>;
+
 /// PostgreSQL implementation of the event store
 pub struct PostgresEventStore {
     pool: PgPool,
+    hash_chaining: bool,
+    slow_append_threshold: Duration,
+    append_observer: Option<AppendObserver>,
+    append_bulkhead: Option<Arc<Bulkhead>>,
+    payload_validator: Option<Arc<PayloadValidator>>,
+    aggregate_types: Option<Arc<AggregateTypeRegistry>>,
 }
 
 impl PostgresEventStore {
     /// Create a new PostgreSQL event store
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            hash_chaining: false,
+            slow_append_threshold: DEFAULT_SLOW_APPEND_THRESHOLD,
+            append_observer: None,
+            append_bulkhead: None,
+            payload_validator: None,
+            aggregate_types: None,
+        }
+    }
+
+    /// Enable or disable per-event hash chaining on append
+    pub fn with_hash_chaining(mut self, enabled: bool) -> Self {
+        self.hash_chaining = enabled;
+        self
+    }
+
+    /// Set the duration above which an append is reported as slow
+    pub fn with_slow_append_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_append_threshold = threshold;
+        self
+    }
+
+    /// Report the duration of every append to `observer`
+    pub fn with_append_observer(mut self, observer: AppendObserver) -> Self {
+        self.append_observer = Some(observer);
+        self
+    }
+
+    /// Cap the number of appends running at once; time spent waiting counts towards the append
+    pub fn with_append_bulkhead(mut self, bulkhead: Arc<Bulkhead>) -> Self {
+        self.append_bulkhead = Some(bulkhead);
+        self
+    }
+
+    /// Reject appends whose payloads don't match their registered JSON Schema
+    pub fn with_payload_validator(mut self, validator: Arc<PayloadValidator>) -> Self {
+        self.payload_validator = Some(validator);
+        self
+    }
+
+    /// Reject events whose aggregate type or event type isn't in `registry`
+    pub fn with_aggregate_types(mut self, registry: Arc<AggregateTypeRegistry>) -> Self {
+        self.aggregate_types = Some(registry);
+        self
     }
 
     /// Get the database pool (useful for testing)
     pub fn pool(&self) -> &PgPool {
         &self.pool
     }
-}
 
-#[async_trait]
-impl EventStore for PostgresEventStore {
-    async fn append_events(
+    /// Verify the hash chain of an aggregate's event stream
+    ///
+    /// Events written before hash chaining was enabled are counted but not checked;
+    /// an unhashed event after the first hashed one breaks the chain.
+    pub async fn verify_stream(
+        &self,
+        aggregate_id: Uuid,
+    ) -> Result<StreamVerification, EventStoreError> {
+        let mut rows = sqlx::query(
+            r#"
+            SELECT event_id, aggregate_id, aggregate_type, event_type,
+                   event_version, payload, metadata, version as sequence_number, created_at,
+                   prev_hash, hash
+            FROM events
+            WHERE aggregate_id = $1
+            ORDER BY version ASC
+            "#,
+        )
+        .bind(aggregate_id)
+        .fetch(&self.pool);
+
+        let mut verifier = ChainVerifier::new(aggregate_id);
+        while let Some(row) = rows.try_next().await? {
+            let event = row_to_event(&row)?;
+            let prev_hash: Option<String> = row.get("prev_hash");
+            let hash: Option<String> = row.get("hash");
+            if !verifier.check(&event, prev_hash.as_deref(), hash.as_deref()) {
+                warn!(
+                    "Hash chain broken for aggregate {} at version {}",
+                    aggregate_id, event.sequence_number
+                );
+                break;
+            }
+        }
+
+        Ok(verifier.finish())
+    }
+
+    /// Append events inside the caller's transaction
+    ///
+    /// Nothing becomes visible until the caller commits, so the events can be written
+    /// atomically with other rows, such as outbox entries. Instrumented and bulkheaded
+    /// like [`EventStore::append_events`].
+    pub async fn append_events_in(
+        &self,
+        conn: &mut PgConnection,
+        aggregate_id: Uuid,
+        expected_version: i64,
+        events: Vec<Event>,
+    ) -> Result<(), EventStoreError> {
+        let aggregate_type = aggregate_type_of(&events);
+        let event_count = events.len();
+        let start = Instant::now();
+
+        let append = self.write_events(conn, aggregate_id, expected_version, events);
+        let result = match &self.append_bulkhead {
+            Some(bulkhead) => bulkhead.call(append).await.unwrap_or_else(|e| Err(e.into())),
+            None => append.await,
+        };
+
+        self.observe_append(
+            aggregate_id,
+            &aggregate_type,
+            event_count,
+            start.elapsed(),
+            result.is_ok(),
+        );
+        result
+    }
+
+    /// Delete a stream on `conn`, typically inside the transaction of the command
+    /// asking for it, so whatever that command records commits along with the delete
+    pub async fn delete_stream_in(
+        &self,
+        conn: &mut PgConnection,
+        aggregate_id: Uuid,
+        mode: DeleteMode,
+    ) -> Result<(), EventStoreError> {
+        match mode {
+            DeleteMode::Soft => {
+                let last: Option<(i64, String, String)> = sqlx::query_as(
+                    r#"
+                    SELECT version, aggregate_type, event_type
+                    FROM events
+                    WHERE aggregate_id = $1
+                    ORDER BY version DESC
+                    LIMIT 1
+                    "#,
+                )
+                .bind(aggregate_id)
+                .fetch_optional(&mut *conn)
+                .await?;
+
+                let (version, aggregate_type, event_type) =
+                    last.ok_or(EventStoreError::AggregateNotFound(aggregate_id))?;
+
+                if event_type == TOMBSTONE_EVENT_TYPE {
+                    debug!("Stream {} is already soft-deleted", aggregate_id);
+                    return Ok(());
+                }
+
+                let payload = StreamDeletedEvent {
+                    aggregate_id,
+                    mode: "soft".to_string(),
+                    deleted_at: Utc::now(),
+                };
+                let tombstone = Event::new(
+                    aggregate_id,
+                    aggregate_type,
+                    TOMBSTONE_EVENT_TYPE.to_string(),
+                    StreamDeletedEvent::event_version(),
+                    serde_json::to_value(payload)?,
+                    EventMetadata::new(),
+                );
+
+                self.append_events_in(conn, aggregate_id, version, vec![tombstone]).await?;
+
+                info!("Soft-deleted stream for aggregate {} at version {}", aggregate_id, version + 1);
+            }
+            DeleteMode::Hard => {
+                let deleted = sqlx::query("DELETE FROM events WHERE aggregate_id = $1")
+                    .bind(aggregate_id)
+                    .execute(&mut *conn)
+                    .await?
+                    .rows_affected();
+
+                if deleted == 0 {
+                    return Err(EventStoreError::AggregateNotFound(aggregate_id));
+                }
+
+                sqlx::query("DELETE FROM snapshots WHERE aggregate_id = $1")
+                    .bind(aggregate_id)
+                    .execute(&mut *conn)
+                    .await?;
+
+                warn!("Scavenged {} events for aggregate {}", deleted, aggregate_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Append events in a transaction of their own (metrics are recorded by the caller)
+    async fn append_events_inner(
         &self,
         aggregate_id: Uuid,
         expected_version: i64,
@@ -34,6 +246,85 @@ impl EventStore for PostgresEventStore {
         }
 
         let mut tx = self.pool.begin().await?;
+        self.write_events(&mut tx, aggregate_id, expected_version, events).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Check and insert events on `tx` without committing
+    async fn write_events(
+        &self,
+        tx: &mut PgConnection,
+        aggregate_id: Uuid,
+        expected_version: i64,
+        events: Vec<Event>,
+    ) -> Result<(), EventStoreError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        // A stream holds events of a single aggregate type
+        let aggregate_type = &events[0].aggregate_type;
+        if let Some(other) = events.iter().find(|e| &e.aggregate_type != aggregate_type) {
+            return Err(EventStoreError::AggregateTypeMismatch {
+                aggregate_id,
+                expected: aggregate_type.clone(),
+                actual: other.aggregate_type.clone(),
+            });
+        }
+
+        if let Some(registry) = &self.aggregate_types {
+            for event in &events {
+                if let Err(e) = registry.check(event) {
+                    warn!("Rejecting event {} for aggregate {}: {}", event.event_id, aggregate_id, e);
+                    return Err(e);
+                }
+            }
+        }
+
+        // Nothing is written if any payload in the batch is malformed
+        if let Some(validator) = &self.payload_validator {
+            for event in &events {
+                if let Err(e) = validator.validate(event) {
+                    warn!("Rejecting event {} for aggregate {}: {}", event.event_id, aggregate_id, e);
+                    return Err(e);
+                }
+            }
+        }
+
+        // Deleted streams are closed for writes
+        let deleted: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM events WHERE aggregate_id = $1 AND event_type = $2)",
+        )
+        .bind(aggregate_id)
+        .bind(TOMBSTONE_EVENT_TYPE)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if deleted {
+            return Err(EventStoreError::StreamDeleted(aggregate_id));
+        }
+
+        // The first event fixes the stream's aggregate type
+        let stream_type: Option<String> = sqlx::query_scalar(
+            "SELECT aggregate_type FROM events WHERE aggregate_id = $1 ORDER BY version LIMIT 1",
+        )
+        .bind(aggregate_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(stream_type) = stream_type.filter(|t| t != aggregate_type) {
+            error!(
+                "Refusing {} events for aggregate {}, its stream holds {} events",
+                aggregate_type, aggregate_id, stream_type
+            );
+            return Err(EventStoreError::AggregateTypeMismatch {
+                aggregate_id,
+                expected: stream_type,
+                actual: aggregate_type.clone(),
+            });
+        }
 
         // Check current version (optimistic locking)
         let current_version: Option<i64> = sqlx::query_scalar(
@@ -55,23 +346,86 @@ impl EventStore for PostgresEventStore {
                 "Concurrency conflict for aggregate {}: expected {}, got {}",
                 aggregate_id, expected_version, current
             );
+            // Tell the caller what changed, so it can tell a lost race from a stale command
+            let conflicting = sqlx::query_as::<_, (String, i64)>(
+                r#"
+                SELECT event_type, version
+                FROM events
+                WHERE aggregate_id = $1 AND version > $2
+                ORDER BY version
+                LIMIT $3
+                "#,
+            )
+            .bind(aggregate_id)
+            .bind(expected_version)
+            .bind(MAX_CONFLICTING_EVENTS)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|(event_type, version)| ConflictingEvent { event_type, version })
+            .collect();
+
             return Err(EventStoreError::ConcurrencyConflict {
                 expected: expected_version,
                 actual: current,
+                conflicting,
             });
         }
 
+        // Claim client-supplied command IDs so a retried command can't apply twice
+        let mut command_ids: Vec<Uuid> = events.iter().filter_map(Event::command_id).collect();
+        command_ids.sort();
+        command_ids.dedup();
+
+        for command_id in command_ids {
+            let claimed = sqlx::query(
+                r#"
+                INSERT INTO processed_commands (command_id, aggregate_id)
+                VALUES ($1, $2)
+                ON CONFLICT (command_id) DO NOTHING
+                "#,
+            )
+            .bind(command_id)
+            .bind(aggregate_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+            if claimed == 0 {
+                warn!("Rejecting duplicate command {} for aggregate {}", command_id, aggregate_id);
+                return Err(EventStoreError::DuplicateCommand(command_id));
+            }
+        }
+
+        // Seed the hash chain from the latest event in the stream
+        let mut prev_hash = if self.hash_chaining {
+            let last_hash: Option<Option<String>> = sqlx::query_scalar(
+                "SELECT hash FROM events WHERE aggregate_id = $1 ORDER BY version DESC LIMIT 1",
+            )
+            .bind(aggregate_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            Some(last_hash.flatten().unwrap_or_else(|| GENESIS_HASH.to_string()))
+        } else {
+            None
+        };
+
         // Insert events
         for (i, event) in events.iter().enumerate() {
             let version = expected_version + i as i64 + 1;
+            let hash = prev_hash
+                .as_deref()
+                .map(|prev| compute_event_hash(prev, event, version));
 
             sqlx::query(
                 r#"
                 INSERT INTO events (
                     event_id, aggregate_id, aggregate_type, event_type,
-                    event_version, payload, metadata, version, created_at
+                    event_version, payload, metadata, version, created_at,
+                    prev_hash, hash, correlation_id, causation_id, user_id
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
                 "#,
             )
             .bind(event.event_id)
@@ -80,20 +434,27 @@ impl EventStore for PostgresEventStore {
             .bind(&event.event_type)
             .bind(event.event_version)
             .bind(&event.payload)
-            .bind(&event.metadata)
+            .bind(serde_json::to_value(&event.metadata)?)
             .bind(version)
             .bind(event.created_at)
+            .bind(&prev_hash)
+            .bind(&hash)
+            .bind(event.correlation_id())
+            .bind(event.causation_id())
+            .bind(event.user_id())
             .execute(&mut *tx)
             .await?;
 
+            if hash.is_some() {
+                prev_hash = hash;
+            }
+
             debug!(
                 "Inserted event {} for aggregate {} at version {}",
                 event.event_id, aggregate_id, version
             );
         }
 
-        tx.commit().await?;
-
         info!(
             "Successfully appended {} events for aggregate {} at version {}",
             events.len(),
@@ -104,40 +465,124 @@ impl EventStore for PostgresEventStore {
         Ok(())
     }
 
+    /// Record metrics for an append and report slow ones
+    fn observe_append(
+        &self,
+        aggregate_id: Uuid,
+        aggregate_type: &str,
+        event_count: usize,
+        elapsed: Duration,
+        success: bool,
+    ) {
+        metrics::record_event_store_operation("append_events", success, elapsed.as_secs_f64());
+        metrics::record_event_store_append(aggregate_type, event_count, elapsed.as_secs_f64());
+        if let Some(observer) = &self.append_observer {
+            observer(elapsed);
+        }
+
+        if elapsed >= self.slow_append_threshold {
+            metrics::record_slow_append(aggregate_type);
+            warn!(
+                aggregate_id = %aggregate_id,
+                aggregate_type = %aggregate_type,
+                event_count = event_count,
+                elapsed_ms = elapsed.as_millis() as u64,
+                threshold_ms = self.slow_append_threshold.as_millis() as u64,
+                "Slow event store append"
+            );
+        }
+    }
+}
+
+/// Aggregate type of a batch, for metric labels
+fn aggregate_type_of(events: &[Event]) -> String {
+    events
+        .first()
+        .map(|event| event.aggregate_type.clone())
+        .unwrap_or_default()
+}
+
+/// Map an `events` row (with `version` aliased as `sequence_number`) to an Event
+///
+/// Fails with `InvalidMetadata` if the metadata column doesn't match `EventMetadata`.
+fn row_to_event(row: &PgRow) -> Result<Event, EventStoreError> {
+    let event_id: Uuid = row.get("event_id");
+    Ok(Event {
+        event_id,
+        aggregate_id: row.get("aggregate_id"),
+        aggregate_type: row.get("aggregate_type"),
+        event_type: row.get("event_type"),
+        event_version: row.get("event_version"),
+        payload: row.get("payload"),
+        metadata: parse_metadata(event_id, row.get("metadata"))?,
+        sequence_number: row.get("sequence_number"),
+        created_at: row.get("created_at"),
+    })
+}
+
+/// Run an event store operation, recording its outcome and duration
+async fn timed<T, F>(operation: &str, operation_future: F) -> Result<T, EventStoreError>
+where
+    F: Future<Output = Result<T, EventStoreError>>,
+{
+    let start = Instant::now();
+    let result = operation_future.await;
+    metrics::record_event_store_operation(operation, result.is_ok(), start.elapsed().as_secs_f64());
+    result
+}
+
+#[async_trait]
+impl EventStore for PostgresEventStore {
+    async fn append_events(
+        &self,
+        aggregate_id: Uuid,
+        expected_version: i64,
+        events: Vec<Event>,
+    ) -> Result<(), EventStoreError> {
+        let aggregate_type = aggregate_type_of(&events);
+        let event_count = events.len();
+        let start = Instant::now();
+
+        let append = self.append_events_inner(aggregate_id, expected_version, events);
+        let result = match &self.append_bulkhead {
+            Some(bulkhead) => bulkhead.call(append).await.unwrap_or_else(|e| Err(e.into())),
+            None => append.await,
+        };
+
+        self.observe_append(
+            aggregate_id,
+            &aggregate_type,
+            event_count,
+            start.elapsed(),
+            result.is_ok(),
+        );
+        result
+    }
+
     async fn load_events(&self, aggregate_id: Uuid) -> Result<Vec<Event>, EventStoreError> {
-        debug!("Loading events for aggregate {}", aggregate_id);
+        timed("load_events", async move {
+            debug!("Loading events for aggregate {}", aggregate_id);
 
-        let rows = sqlx::query(
-            r#"
-            SELECT event_id, aggregate_id, aggregate_type, event_type,
-                   event_version, payload, metadata, version as sequence_number, created_at
-            FROM events
-            WHERE aggregate_id = $1
-            ORDER BY version ASC
-            "#,
-        )
-        .bind(aggregate_id)
-        .fetch_all(&self.pool)
-        .await?;
+            let rows = sqlx::query(
+                r#"
+                SELECT event_id, aggregate_id, aggregate_type, event_type,
+                       event_version, payload, metadata, version as sequence_number, created_at
+                FROM events
+                WHERE aggregate_id = $1
+                ORDER BY version ASC
+                "#,
+            )
+            .bind(aggregate_id)
+            .fetch_all(&self.pool)
+            .await?;
 
-        let events: Vec<Event> = rows
-            .iter()
-            .map(|row| Event {
-                event_id: row.get("event_id"),
-                aggregate_id: row.get("aggregate_id"),
-                aggregate_type: row.get("aggregate_type"),
-                event_type: row.get("event_type"),
-                event_version: row.get("event_version"),
-                payload: row.get("payload"),
-                metadata: row.get("metadata"),
-                sequence_number: row.get("sequence_number"),
-                created_at: row.get("created_at"),
-            })
-            .collect();
+            let events: Vec<Event> = rows.iter().map(row_to_event).collect::<Result<_, _>>()?;
 
-        debug!("Loaded {} events for aggregate {}", events.len(), aggregate_id);
+            debug!("Loaded {} events for aggregate {}", events.len(), aggregate_id);
 
-        Ok(events)
+            Ok(events)
+        })
+        .await
     }
 
     async fn load_events_from_version(
@@ -145,59 +590,165 @@ impl EventStore for PostgresEventStore {
         aggregate_id: Uuid,
         from_version: i64,
     ) -> Result<Vec<Event>, EventStoreError> {
-        debug!(
-            "Loading events for aggregate {} from version {}",
-            aggregate_id, from_version
-        );
+        timed("load_events_from_version", async move {
+            debug!(
+                "Loading events for aggregate {} from version {}",
+                aggregate_id, from_version
+            );
 
-        let rows = sqlx::query(
-            r#"
-            SELECT event_id, aggregate_id, aggregate_type, event_type,
-                   event_version, payload, metadata, version as sequence_number, created_at
-            FROM events
-            WHERE aggregate_id = $1 AND version > $2
-            ORDER BY version ASC
-            "#,
-        )
-        .bind(aggregate_id)
-        .bind(from_version)
-        .fetch_all(&self.pool)
-        .await?;
+            let rows = sqlx::query(
+                r#"
+                SELECT event_id, aggregate_id, aggregate_type, event_type,
+                       event_version, payload, metadata, version as sequence_number, created_at
+                FROM events
+                WHERE aggregate_id = $1 AND version > $2
+                ORDER BY version ASC
+                "#,
+            )
+            .bind(aggregate_id)
+            .bind(from_version)
+            .fetch_all(&self.pool)
+            .await?;
 
-        let events: Vec<Event> = rows
-            .iter()
-            .map(|row| Event {
-                event_id: row.get("event_id"),
-                aggregate_id: row.get("aggregate_id"),
-                aggregate_type: row.get("aggregate_type"),
-                event_type: row.get("event_type"),
-                event_version: row.get("event_version"),
-                payload: row.get("payload"),
-                metadata: row.get("metadata"),
-                sequence_number: row.get("sequence_number"),
-                created_at: row.get("created_at"),
-            })
-            .collect();
+            let events: Vec<Event> = rows.iter().map(row_to_event).collect::<Result<_, _>>()?;
 
-        debug!(
-            "Loaded {} events for aggregate {} from version {}",
-            events.len(),
-            aggregate_id,
-            from_version
-        );
+            debug!(
+                "Loaded {} events for aggregate {} from version {}",
+                events.len(),
+                aggregate_id,
+                from_version
+            );
+
+            Ok(events)
+        })
+        .await
+    }
+
+    async fn load_events_page(
+        &self,
+        aggregate_id: Uuid,
+        after_version: i64,
+        page_size: i64,
+    ) -> Result<Vec<Event>, EventStoreError> {
+        timed("load_events_page", async move {
+            let rows = sqlx::query(
+                r#"
+                SELECT event_id, aggregate_id, aggregate_type, event_type,
+                       event_version, payload, metadata, version as sequence_number, created_at
+                FROM events
+                WHERE aggregate_id = $1 AND version > $2
+                ORDER BY version ASC
+                LIMIT $3
+                "#,
+            )
+            .bind(aggregate_id)
+            .bind(after_version)
+            .bind(page_size)
+            .fetch_all(&self.pool)
+            .await?;
 
-        Ok(events)
+            let events: Vec<Event> = rows.iter().map(row_to_event).collect::<Result<_, _>>()?;
+
+            debug!(
+                "Loaded page of {} events for aggregate {} after version {}",
+                events.len(),
+                aggregate_id,
+                after_version
+            );
+
+            Ok(events)
+        })
+        .await
     }
 
     async fn get_current_version(&self, aggregate_id: Uuid) -> Result<i64, EventStoreError> {
-        let version: Option<i64> = sqlx::query_scalar(
-            "SELECT MAX(version) FROM events WHERE aggregate_id = $1",
-        )
-        .bind(aggregate_id)
-        .fetch_optional(&self.pool)
-        .await?;
+        timed("get_current_version", async move {
+            let version: Option<i64> = sqlx::query_scalar(
+                "SELECT MAX(version) FROM events WHERE aggregate_id = $1",
+            )
+            .bind(aggregate_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+            Ok(version.unwrap_or(0))
+        })
+        .await
+    }
+
+    async fn delete_stream(&self, aggregate_id: Uuid, mode: DeleteMode) -> Result<(), EventStoreError> {
+        timed("delete_stream", async move {
+            let mut tx = self.pool.begin().await?;
+            self.delete_stream_in(&mut tx, aggregate_id, mode).await?;
+            tx.commit().await?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn is_stream_deleted(&self, aggregate_id: Uuid) -> Result<bool, EventStoreError> {
+        timed("is_stream_deleted", async move {
+            let deleted: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM events WHERE aggregate_id = $1 AND event_type = $2)",
+            )
+            .bind(aggregate_id)
+            .bind(TOMBSTONE_EVENT_TYPE)
+            .fetch_one(&self.pool)
+            .await?;
+
+            Ok(deleted)
+        })
+        .await
+    }
+
+    async fn find_events_by_command_id(
+        &self,
+        command_id: Uuid,
+    ) -> Result<Vec<Event>, EventStoreError> {
+        timed("find_events_by_command_id", async move {
+            let rows = sqlx::query(
+                r#"
+                SELECT event_id, aggregate_id, aggregate_type, event_type,
+                       event_version, payload, metadata, version as sequence_number, created_at
+                FROM events
+                WHERE metadata @> jsonb_build_object('command_id', $1::text)
+                ORDER BY created_at ASC, version ASC
+                "#,
+            )
+            .bind(command_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+            rows.iter().map(row_to_event).collect()
+        })
+        .await
+    }
+
+    async fn find_events_by_correlation_id(
+        &self,
+        correlation_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Event>, EventStoreError> {
+        timed("find_events_by_correlation_id", async move {
+            debug!("Loading events for correlation {}", correlation_id);
+
+            let rows = sqlx::query(
+                r#"
+                SELECT event_id, aggregate_id, aggregate_type, event_type,
+                       event_version, payload, metadata, version as sequence_number, created_at
+                FROM events
+                WHERE correlation_id = $1
+                ORDER BY created_at ASC, aggregate_id, version ASC
+                LIMIT $2
+                "#,
+            )
+            .bind(correlation_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
 
-        Ok(version.unwrap_or(0))
+            rows.iter().map(row_to_event).collect()
+        })
+        .await
     }
 }
 
@@ -0,0 +1,200 @@
+use async_trait::async_trait;
+use common::metrics::record_idempotency_check;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::idempotency_store::{
+    IdempotencyClaim, IdempotencyError, IdempotencyStatus, IdempotencyStore,
+};
+
+/// Idempotency keys in the `idempotency_keys` table
+///
+/// A claimed key is a row with a NULL result and the claim's id in `claim_token` until
+/// it is completed. Rows are not read
+/// once `expires_at` has passed, and [`spawn_cleanup`](Self::spawn_cleanup) deletes
+/// them, so expiry behaves like the Redis TTL without losing keys on a restart.
+pub struct PgIdempotencyStore {
+    pool: PgPool,
+    ttl: Duration,
+    processing_timeout: Duration,
+}
+
+impl PgIdempotencyStore {
+    pub fn new(pool: PgPool, ttl_seconds: u64) -> Self {
+        Self {
+            pool,
+            ttl: Duration::from_secs(ttl_seconds),
+            processing_timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// How long a key claimed by `try_begin` stays in progress before another caller
+    /// may claim it, in case the first one died (default 30s)
+    pub fn with_processing_timeout(mut self, timeout: Duration) -> Self {
+        self.processing_timeout = timeout;
+        self
+    }
+
+    /// Delete expired keys, returning how many were removed
+    pub async fn purge_expired(&self) -> Result<u64, IdempotencyError> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Purge expired keys every `interval`
+    pub fn spawn_cleanup(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                match self.purge_expired().await {
+                    Ok(0) => {}
+                    Ok(purged) => tracing::debug!("Purged {} expired idempotency keys", purged),
+                    Err(e) => tracing::warn!("Failed to purge expired idempotency keys: {}", e),
+                }
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for PgIdempotencyStore {
+    async fn check(&self, key: &str) -> Result<Option<serde_json::Value>, IdempotencyError> {
+        let result: Option<Option<serde_json::Value>> = sqlx::query_scalar(
+            r#"
+            SELECT result FROM idempotency_keys
+            WHERE idempotency_key = $1 AND expires_at > NOW()
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(result.flatten())
+    }
+
+    async fn record(&self, key: &str, result: &serde_json::Value) -> Result<(), IdempotencyError> {
+        sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (idempotency_key, result, expires_at)
+            VALUES ($1, $2, NOW() + make_interval(secs => $3))
+            ON CONFLICT (idempotency_key) DO UPDATE
+                SET result = EXCLUDED.result, claim_token = NULL, expires_at = EXCLUDED.expires_at
+            "#,
+        )
+        .bind(key)
+        .bind(result)
+        .bind(self.ttl.as_secs_f64())
+        .execute(&self.pool)
+        .await?;
+
+        tracing::debug!(
+            idempotency_key = %key,
+            ttl_seconds = %self.ttl.as_secs(),
+            "Recorded idempotency key"
+        );
+        Ok(())
+    }
+
+    async fn try_begin(&self, key: &str) -> Result<IdempotencyStatus, IdempotencyError> {
+        // The claim and the read are two statements, so a key released by `fail` in
+        // between is claimed again on the next pass
+        let claim = IdempotencyClaim::new();
+        let status = loop {
+            let claimed = sqlx::query(
+                r#"
+                INSERT INTO idempotency_keys (idempotency_key, result, claim_token, expires_at)
+                VALUES ($1, NULL, $3, NOW() + make_interval(secs => $2))
+                ON CONFLICT (idempotency_key) DO UPDATE
+                    SET result = NULL,
+                        claim_token = EXCLUDED.claim_token,
+                        expires_at = EXCLUDED.expires_at
+                    WHERE idempotency_keys.expires_at <= NOW()
+                "#,
+            )
+            .bind(key)
+            .bind(self.processing_timeout.as_secs_f64())
+            .bind(claim.id())
+            .execute(&self.pool)
+            .await?
+            .rows_affected()
+                > 0;
+            if claimed {
+                break IdempotencyStatus::Started(claim);
+            }
+
+            let existing: Option<Option<serde_json::Value>> = sqlx::query_scalar(
+                "SELECT result FROM idempotency_keys WHERE idempotency_key = $1",
+            )
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+            match existing {
+                Some(Some(result)) => break IdempotencyStatus::Completed(result),
+                Some(None) => break IdempotencyStatus::InProgress,
+                None => continue,
+            }
+        };
+
+        record_idempotency_check(!matches!(status, IdempotencyStatus::Started(_)));
+        tracing::debug!(idempotency_key = %key, status = ?status, "Idempotency key claim");
+        Ok(status)
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        claim: IdempotencyClaim,
+        result: &serde_json::Value,
+    ) -> Result<(), IdempotencyError> {
+        // Only over the caller's own claim, or a row no one holds any more
+        let stored = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (idempotency_key, result, expires_at)
+            VALUES ($1, $2, NOW() + make_interval(secs => $3))
+            ON CONFLICT (idempotency_key) DO UPDATE
+                SET result = EXCLUDED.result, claim_token = NULL, expires_at = EXCLUDED.expires_at
+                WHERE idempotency_keys.claim_token = $4
+                   OR (idempotency_keys.expires_at <= NOW() AND idempotency_keys.result IS NULL)
+            "#,
+        )
+        .bind(key)
+        .bind(result)
+        .bind(self.ttl.as_secs_f64())
+        .bind(claim.id())
+        .execute(&self.pool)
+        .await?
+        .rows_affected()
+            > 0;
+        if !stored {
+            return Err(IdempotencyError::ClaimLost(key.to_string()));
+        }
+
+        tracing::debug!(idempotency_key = %key, "Completed idempotency key");
+        Ok(())
+    }
+
+    async fn fail(&self, key: &str, claim: IdempotencyClaim) -> Result<(), IdempotencyError> {
+        // A result or another caller's claim under the key is left in place
+        sqlx::query(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE idempotency_key = $1 AND result IS NULL AND claim_token = $2
+            "#,
+        )
+        .bind(key)
+        .bind(claim.id())
+        .execute(&self.pool)
+        .await?;
+
+        tracing::debug!(idempotency_key = %key, "Released idempotency key");
+        Ok(())
+    }
+
+    async fn ping(&self) -> Result<(), IdempotencyError> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+}
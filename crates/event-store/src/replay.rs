@@ -1,6 +1,7 @@
 use crate::{Event, EventStore, EventStoreError};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
@@ -33,6 +34,14 @@ impl Default for ReplayConfig {
     }
 }
 
+impl ReplayConfig {
+    /// Stop at events created after `as_of`, replacing any `to_timestamp`
+    pub fn as_of(mut self, as_of: DateTime<Utc>) -> Self {
+        self.to_timestamp = Some(as_of);
+        self
+    }
+}
+
 /// Statistics for event replay
 #[derive(Debug, Clone, Default)]
 pub struct ReplayStats {
@@ -81,33 +90,57 @@ impl<E: EventStore> EventReplayService<E> {
 
         let mut stats = self.stats.write().await;
         stats.start_time = Some(Utc::now());
+        stats.total_events = 0;
         stats.processed_events = 0;
         stats.failed_events = 0;
         drop(stats);
 
-        // Get all events based on config
-        let events = self.fetch_events(&config).await?;
+        let aggregate_ids = match &config.aggregate_ids {
+            Some(aggregate_ids) => aggregate_ids.clone(),
+            None => {
+                // For all aggregates, we'd need a method to fetch all events
+                // This is a simplified implementation
+                // In a real system, you'd query the database directly
+                warn!("Replaying all aggregates requires direct database access");
+                Vec::new()
+            }
+        };
 
-        let total_events = events.len();
-        info!("Found {} events to replay", total_events);
+        // Stream each aggregate page by page so large streams aren't buffered in memory
+        let page_size = config.batch_size.max(1) as i64;
+        for aggregate_id in aggregate_ids {
+            // Soft-deleted streams are excluded from replay
+            if self.event_store.is_stream_deleted(aggregate_id).await? {
+                info!(aggregate_id = %aggregate_id, "Skipping deleted stream during replay");
+                continue;
+            }
 
-        {
-            let mut stats = self.stats.write().await;
-            stats.total_events = total_events;
-        }
+            let mut events = self
+                .event_store
+                .load_events_paged(aggregate_id, 0, page_size);
+
+            while let Some(event) = events.try_next().await? {
+                if !self.matches_filter(&event, &config) {
+                    continue;
+                }
 
-        // Process events in batches
-        for chunk in events.chunks(config.batch_size) {
-            for event in chunk {
-                match handler(event.clone()).await {
+                {
+                    let mut stats = self.stats.write().await;
+                    stats.total_events += 1;
+                }
+
+                let event_id = event.event_id;
+                let event_type = event.event_type.clone();
+
+                match handler(event).await {
                     Ok(_) => {
                         let mut stats = self.stats.write().await;
                         stats.processed_events += 1;
                     }
                     Err(e) => {
                         warn!(
-                            event_id = %event.event_id,
-                            event_type = %event.event_type,
+                            event_id = %event_id,
+                            event_type = %event_type,
                             error = %e,
                             "Failed to process event during replay"
                         );
@@ -154,54 +187,28 @@ impl<E: EventStore> EventReplayService<E> {
         self.stats.read().await.clone()
     }
 
-    /// Fetch events based on replay configuration
-    async fn fetch_events(&self, config: &ReplayConfig) -> Result<Vec<Event>, EventStoreError> {
-        // If specific aggregate IDs are provided, fetch their events
-        if let Some(aggregate_ids) = &config.aggregate_ids {
-            let mut all_events = Vec::new();
-            for aggregate_id in aggregate_ids {
-                let events = self.event_store.load_events(*aggregate_id).await?;
-                all_events.extend(events);
+    /// Check whether an event passes the timestamp and event type filters
+    fn matches_filter(&self, event: &Event, config: &ReplayConfig) -> bool {
+        // Filter by timestamp
+        if let Some(from) = config.from_timestamp {
+            if event.created_at < from {
+                return false;
+            }
+        }
+        if let Some(to) = config.to_timestamp {
+            if event.created_at > to {
+                return false;
             }
-
-            // Filter by timestamp and event type
-            Ok(self.filter_events(all_events, config))
-        } else {
-            // For all aggregates, we'd need a method to fetch all events
-            // This is a simplified implementation
-            // In a real system, you'd query the database directly
-            warn!("Replaying all aggregates requires direct database access");
-            Ok(vec![])
         }
-    }
-
-    /// Filter events based on configuration
-    fn filter_events(&self, events: Vec<Event>, config: &ReplayConfig) -> Vec<Event> {
-        events
-            .into_iter()
-            .filter(|event| {
-                // Filter by timestamp
-                if let Some(from) = config.from_timestamp {
-                    if event.created_at < from {
-                        return false;
-                    }
-                }
-                if let Some(to) = config.to_timestamp {
-                    if event.created_at > to {
-                        return false;
-                    }
-                }
 
-                // Filter by event type
-                if let Some(event_types) = &config.event_types {
-                    if !event_types.contains(&event.event_type) {
-                        return false;
-                    }
-                }
+        // Filter by event type
+        if let Some(event_types) = &config.event_types {
+            if !event_types.contains(&event.event_type) {
+                return false;
+            }
+        }
 
-                true
-            })
-            .collect()
+        true
     }
 }
 
@@ -230,6 +237,19 @@ pub trait Rebuildable: Send + Sync {
 
         Ok(stats)
     }
+
+    /// Rebuild the projection as it stood at `as_of`
+    ///
+    /// Only events created at or before `as_of` are replayed, so the projection ends
+    /// up holding the state of that instant, e.g. the order book at month end.
+    async fn rebuild_as_of<E: EventStore>(
+        &self,
+        replay_service: &EventReplayService<E>,
+        config: ReplayConfig,
+        as_of: DateTime<Utc>,
+    ) -> Result<ReplayStats, Box<dyn std::error::Error + Send + Sync>> {
+        self.rebuild(replay_service, config.as_of(as_of)).await
+    }
 }
 
 #[cfg(test)]
@@ -246,6 +266,14 @@ mod tests {
         assert_eq!(config.batch_size, 100);
     }
 
+    #[test]
+    fn test_replay_config_as_of() {
+        let as_of = Utc::now();
+        let config = ReplayConfig::default().as_of(as_of);
+        assert_eq!(config.to_timestamp, Some(as_of));
+        assert!(config.from_timestamp.is_none());
+    }
+
     #[test]
     fn test_replay_stats_duration() {
         let mut stats = ReplayStats::default();
@@ -0,0 +1,362 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection, PgPool};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::EventStoreError;
+
+/// An event row as stored, copied verbatim so the replica keeps the same IDs,
+/// versions, timestamps and hash chain
+#[derive(Debug, Clone, FromRow)]
+struct EventRow {
+    event_id: Uuid,
+    aggregate_id: Uuid,
+    aggregate_type: String,
+    event_type: String,
+    event_version: i32,
+    payload: serde_json::Value,
+    metadata: serde_json::Value,
+    version: i64,
+    created_at: DateTime<Utc>,
+    prev_hash: Option<String>,
+    hash: Option<String>,
+    correlation_id: Option<Uuid>,
+    causation_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+}
+
+/// A source event with its place in the source's global log
+#[derive(Debug, Clone, FromRow)]
+struct SourceEvent {
+    #[sqlx(flatten)]
+    event: EventRow,
+    transaction_id: i64,
+    global_position: i64,
+}
+
+const EVENT_COLUMNS: &str = "event_id, aggregate_id, aggregate_type, event_type, event_version, \
+    payload, metadata, version, created_at, prev_hash, hash, correlation_id, causation_id, user_id";
+
+/// Position in the source log up to which events have been replicated
+///
+/// Events are read in `(transaction_id, position)` order, the cursor of the global
+/// event log; `created_at` and `event_id` describe the last event copied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplicationCheckpoint {
+    pub transaction_id: i64,
+    pub position: i64,
+    pub created_at: DateTime<Utc>,
+    pub event_id: Uuid,
+}
+
+/// Outcome of one [`EventReplicator::replicate_batch`] call
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplicationBatch {
+    /// Events read from the source log
+    pub read: usize,
+    /// Events written to the replica, including those copied to fill gaps
+    pub copied: usize,
+    /// Events the replica already had, e.g. because an earlier gap fill copied them
+    pub already_present: usize,
+    pub checkpoint: Option<ReplicationCheckpoint>,
+}
+
+/// Where a source event goes in the replica's copy of its stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placement {
+    /// The replica already holds this event
+    Present,
+    /// Next in the stream
+    Append,
+    /// Versions after `replica_version` are missing and must be copied first
+    FillGap { replica_version: i64 },
+    /// The replica holds a different event at this version
+    Conflict { replica_event_id: Uuid },
+}
+
+/// Decide how to apply `event` given the replica's event at the same version, if
+/// any, and the replica's current stream version
+fn place(event: &EventRow, at_version: Option<Uuid>, replica_version: i64) -> Placement {
+    match at_version {
+        Some(event_id) if event_id == event.event_id => Placement::Present,
+        Some(replica_event_id) => Placement::Conflict { replica_event_id },
+        None if replica_version + 1 < event.version => Placement::FillGap { replica_version },
+        None => Placement::Append,
+    }
+}
+
+/// Copies the event log to a standby region's event store
+///
+/// The source is tailed in `(transaction_id, global_position)` order from a checkpoint
+/// kept in the replica's `event_replication_checkpoints` table, reading only events of
+/// transactions older than every open one. An event that becomes visible later belongs
+/// to a newer transaction and sorts after the checkpoint, so a transaction committing
+/// late is never skipped. An event arriving ahead of a missing version still makes the
+/// replicator copy the stream's missing versions from the source first.
+///
+/// The replica is passive, so a different event at a version it already holds means
+/// something wrote to it directly. The batch is rolled back and
+/// [`EventStoreError::ReplicationConflict`] returned without moving the checkpoint;
+/// replication stays stuck on that event until an operator resolves it.
+pub struct EventReplicator {
+    name: String,
+    source: PgPool,
+    replica: PgPool,
+    batch_size: i64,
+}
+
+impl EventReplicator {
+    /// `name` identifies the replication link the checkpoint is kept under
+    pub fn new(name: impl Into<String>, source: PgPool, replica: PgPool) -> Self {
+        Self {
+            name: name.into(),
+            source,
+            replica,
+            batch_size: 500,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn batch_size(&self) -> i64 {
+        self.batch_size
+    }
+
+    pub async fn checkpoint(&self) -> Result<Option<ReplicationCheckpoint>, EventStoreError> {
+        let row: Option<(i64, i64, DateTime<Utc>, Uuid)> = sqlx::query_as(
+            r#"
+            SELECT last_transaction_id, last_position, last_created_at, last_event_id
+            FROM event_replication_checkpoints
+            WHERE name = $1
+            "#,
+        )
+        .bind(&self.name)
+        .fetch_optional(&self.replica)
+        .await?;
+
+        Ok(row.map(
+            |(transaction_id, position, created_at, event_id)| ReplicationCheckpoint {
+                transaction_id,
+                position,
+                created_at,
+                event_id,
+            },
+        ))
+    }
+
+    /// Copy the next batch of events after the checkpoint to the replica
+    ///
+    /// The events and the new checkpoint are written in one replica transaction.
+    pub async fn replicate_batch(&self) -> Result<ReplicationBatch, EventStoreError> {
+        let after = self.checkpoint().await?;
+        let (after_transaction_id, after_position) =
+            after.map_or((0, 0), |c| (c.transaction_id, c.position));
+
+        let events = sqlx::query_as::<_, SourceEvent>(&format!(
+            r#"
+            SELECT {EVENT_COLUMNS},
+                   transaction_id::text::bigint AS transaction_id, global_position
+            FROM events
+            WHERE (transaction_id, global_position) > ($1::text::xid8, $2)
+              AND transaction_id < pg_snapshot_xmin(pg_current_snapshot())
+            ORDER BY transaction_id, global_position
+            LIMIT $3
+            "#
+        ))
+        .bind(after_transaction_id.to_string())
+        .bind(after_position)
+        .bind(self.batch_size)
+        .fetch_all(&self.source)
+        .await?;
+
+        let mut batch = ReplicationBatch {
+            read: events.len(),
+            checkpoint: after,
+            ..ReplicationBatch::default()
+        };
+        let Some(last) = events.last() else {
+            return Ok(batch);
+        };
+        let checkpoint = ReplicationCheckpoint {
+            transaction_id: last.transaction_id,
+            position: last.global_position,
+            created_at: last.event.created_at,
+            event_id: last.event.event_id,
+        };
+
+        let mut tx = self.replica.begin().await?;
+        for source_event in &events {
+            self.apply(&mut tx, &source_event.event, &mut batch).await?;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO event_replication_checkpoints
+                (name, last_transaction_id, last_position, last_created_at, last_event_id, updated_at)
+            VALUES ($1, $2, $3, $4, $5, NOW())
+            ON CONFLICT (name) DO UPDATE
+            SET last_transaction_id = EXCLUDED.last_transaction_id,
+                last_position = EXCLUDED.last_position,
+                last_created_at = EXCLUDED.last_created_at,
+                last_event_id = EXCLUDED.last_event_id,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(&self.name)
+        .bind(checkpoint.transaction_id)
+        .bind(checkpoint.position)
+        .bind(checkpoint.created_at)
+        .bind(checkpoint.event_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        batch.checkpoint = Some(checkpoint);
+        debug!(
+            replication = %self.name,
+            copied = batch.copied,
+            already_present = batch.already_present,
+            "Replicated batch"
+        );
+        Ok(batch)
+    }
+
+    async fn apply(
+        &self,
+        replica: &mut PgConnection,
+        event: &EventRow,
+        batch: &mut ReplicationBatch,
+    ) -> Result<(), EventStoreError> {
+        let at_version: Option<Uuid> = sqlx::query_scalar(
+            "SELECT event_id FROM events WHERE aggregate_id = $1 AND version = $2",
+        )
+        .bind(event.aggregate_id)
+        .bind(event.version)
+        .fetch_optional(&mut *replica)
+        .await?;
+        let replica_version: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(version), 0) FROM events WHERE aggregate_id = $1",
+        )
+        .bind(event.aggregate_id)
+        .fetch_one(&mut *replica)
+        .await?;
+
+        match place(event, at_version, replica_version) {
+            Placement::Present => batch.already_present += 1,
+            Placement::Append => {
+                insert_event(replica, event).await?;
+                batch.copied += 1;
+            }
+            Placement::FillGap { replica_version } => {
+                let missing = sqlx::query_as::<_, EventRow>(&format!(
+                    r#"
+                    SELECT {EVENT_COLUMNS}
+                    FROM events
+                    WHERE aggregate_id = $1 AND version > $2 AND version < $3
+                    ORDER BY version
+                    "#
+                ))
+                .bind(event.aggregate_id)
+                .bind(replica_version)
+                .bind(event.version)
+                .fetch_all(&self.source)
+                .await?;
+
+                info!(
+                    replication = %self.name,
+                    aggregate_id = %event.aggregate_id,
+                    from_version = replica_version + 1,
+                    missing = missing.len(),
+                    "Filling gap in replicated stream"
+                );
+                for missing_event in &missing {
+                    insert_event(replica, missing_event).await?;
+                }
+                insert_event(replica, event).await?;
+                batch.copied += missing.len() + 1;
+            }
+            Placement::Conflict { replica_event_id } => {
+                return Err(EventStoreError::ReplicationConflict {
+                    aggregate_id: event.aggregate_id,
+                    version: event.version,
+                    source_event_id: event.event_id,
+                    replica_event_id,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn insert_event(replica: &mut PgConnection, event: &EventRow) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        r#"
+        INSERT INTO events ({EVENT_COLUMNS})
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+        "#
+    ))
+    .bind(event.event_id)
+    .bind(event.aggregate_id)
+    .bind(&event.aggregate_type)
+    .bind(&event.event_type)
+    .bind(event.event_version)
+    .bind(&event.payload)
+    .bind(&event.metadata)
+    .bind(event.version)
+    .bind(event.created_at)
+    .bind(&event.prev_hash)
+    .bind(&event.hash)
+    .bind(event.correlation_id)
+    .bind(event.causation_id)
+    .bind(event.user_id)
+    .execute(replica)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(version: i64) -> EventRow {
+        EventRow {
+            event_id: Uuid::new_v4(),
+            aggregate_id: Uuid::new_v4(),
+            aggregate_type: "Order".to_string(),
+            event_type: "OrderConfirmed".to_string(),
+            event_version: 1,
+            payload: serde_json::json!({}),
+            metadata: serde_json::json!({}),
+            version,
+            created_at: Utc::now(),
+            prev_hash: None,
+            hash: None,
+            correlation_id: None,
+            causation_id: None,
+            user_id: None,
+        }
+    }
+
+    #[test]
+    fn test_placement_by_replica_stream() {
+        let event = row(3);
+
+        assert_eq!(place(&event, None, 2), Placement::Append);
+        assert_eq!(place(&event, Some(event.event_id), 5), Placement::Present);
+        assert_eq!(
+            place(&event, None, 0),
+            Placement::FillGap { replica_version: 0 }
+        );
+
+        let other = Uuid::new_v4();
+        assert_eq!(
+            place(&event, Some(other), 3),
+            Placement::Conflict {
+                replica_event_id: other
+            }
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use domain::schema::event_schemas;
+use jsonschema::JSONSchema;
+use std::collections::HashMap;
+use thiserror::Error;
+
+use crate::{Event, EventStoreError};
+
+/// Most schema violations reported for one payload
+const MAX_REPORTED_ERRORS: usize = 5;
+
+#[derive(Debug, Error)]
+#[error("Schema for {event_type} v{event_version} does not compile: {reason}")]
+pub struct SchemaCompileError {
+    pub event_type: String,
+    pub event_version: i32,
+    pub reason: String,
+}
+
+/// JSON Schemas for event payloads, keyed by event type and version
+///
+/// Payloads of event types or versions without a registered schema are accepted.
+#[derive(Default)]
+pub struct PayloadValidator {
+    schemas: HashMap<(String, i32), JSONSchema>,
+}
+
+impl PayloadValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validator for every event payload the services publish (see `domain::schema`)
+    pub fn from_event_schemas() -> Result<Self, SchemaCompileError> {
+        let mut validator = Self::new();
+        for schema in event_schemas() {
+            let json = serde_json::to_value(&schema.schema).map_err(|e| SchemaCompileError {
+                event_type: schema.event_type.to_string(),
+                event_version: schema.event_version,
+                reason: e.to_string(),
+            })?;
+            validator.register(schema.event_type, schema.event_version, &json)?;
+        }
+        Ok(validator)
+    }
+
+    /// Register the schema for one event type and version, replacing any existing one
+    pub fn register(
+        &mut self,
+        event_type: impl Into<String>,
+        event_version: i32,
+        schema: &serde_json::Value,
+    ) -> Result<(), SchemaCompileError> {
+        let event_type = event_type.into();
+        let compiled = JSONSchema::compile(schema).map_err(|e| SchemaCompileError {
+            event_type: event_type.clone(),
+            event_version,
+            reason: e.to_string(),
+        })?;
+        self.schemas.insert((event_type, event_version), compiled);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.schemas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+    }
+
+    /// Check an event's payload against the schema registered for its type and version
+    pub fn validate(&self, event: &Event) -> Result<(), EventStoreError> {
+        let Some(schema) = self
+            .schemas
+            .get(&(event.event_type.clone(), event.event_version))
+        else {
+            return Ok(());
+        };
+
+        schema.validate(&event.payload).map_err(|errors| {
+            let reasons: Vec<String> = errors
+                .take(MAX_REPORTED_ERRORS)
+                .map(|e| {
+                    let path = e.instance_path.to_string();
+                    if path.is_empty() {
+                        e.to_string()
+                    } else {
+                        format!("{}: {}", path, e)
+                    }
+                })
+                .collect();
+            EventStoreError::InvalidPayload {
+                event_type: event.event_type.clone(),
+                event_version: event.event_version,
+                reason: reasons.join("; "),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::events::EventMetadata;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn event(event_type: &str, event_version: i32, payload: serde_json::Value) -> Event {
+        Event::new(
+            Uuid::new_v4(),
+            "Order".to_string(),
+            event_type.to_string(),
+            event_version,
+            payload,
+            EventMetadata::new(),
+        )
+    }
+
+    #[test]
+    fn test_valid_payload_is_accepted() {
+        let validator = PayloadValidator::from_event_schemas().unwrap();
+        assert!(!validator.is_empty());
+
+        let payload = json!({ "order_id": Uuid::new_v4(), "confirmed_at": "2024-01-01T00:00:00Z" });
+        assert!(validator.validate(&event("OrderConfirmed", 1, payload)).is_ok());
+    }
+
+    #[test]
+    fn test_malformed_payload_is_rejected() {
+        let validator = PayloadValidator::from_event_schemas().unwrap();
+
+        let payload = json!({ "order_id": "not-a-uuid-but-a-string", "confirmed_at": 42 });
+        let result = validator.validate(&event("OrderConfirmed", 1, payload));
+        match result {
+            Err(EventStoreError::InvalidPayload {
+                event_type,
+                event_version,
+                reason,
+            }) => {
+                assert_eq!(event_type, "OrderConfirmed");
+                assert_eq!(event_version, 1);
+                assert!(reason.contains("/confirmed_at"), "{}", reason);
+            }
+            other => panic!("expected InvalidPayload, got {:?}", other),
+        }
+
+        let result = validator.validate(&event("OrderConfirmed", 1, json!({})));
+        assert!(matches!(result, Err(EventStoreError::InvalidPayload { .. })));
+    }
+
+    #[test]
+    fn test_unregistered_types_and_versions_are_accepted() {
+        let validator = PayloadValidator::from_event_schemas().unwrap();
+
+        assert!(validator.validate(&event("SomethingElse", 1, json!(null))).is_ok());
+        assert!(validator.validate(&event("OrderConfirmed", 99, json!({}))).is_ok());
+    }
+
+    #[test]
+    fn test_register_rejects_invalid_schema() {
+        let mut validator = PayloadValidator::new();
+        let result = validator.register("Broken", 1, &json!({ "type": 12 }));
+        assert!(result.is_err());
+        assert!(validator.is_empty());
+    }
+}
@@ -1,11 +1,14 @@
 use rdkafka::config::ClientConfig;
 use rdkafka::consumer::{BaseConsumer, Consumer};
 use rdkafka::message::Message;
+use rdkafka::Offset;
 use serde::de::DeserializeOwned;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
 
+use crate::reconnect::{self, ConsumerHealth};
+
 #[derive(Debug, Error)]
 pub enum ConsumerError {
     #[error("Failed to create Kafka consumer: {0}")]
@@ -18,9 +21,47 @@ pub enum ConsumerError {
     NoPayload,
 }
 
+impl ConsumerError {
+    /// Whether the consumer must be recreated before it can be used again
+    pub fn is_fatal(&self) -> bool {
+        match self {
+            ConsumerError::ConsumerCreation(e) => reconnect::is_fatal(e),
+            _ => false,
+        }
+    }
+}
+
+/// A message payload together with where it was read from
+#[derive(Debug, Clone)]
+pub struct ReceivedMessage {
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    pub key: Option<Vec<u8>>,
+    pub payload: Vec<u8>,
+}
+
+impl ReceivedMessage {
+    /// Copy a Kafka message, or `None` if it has no payload
+    pub fn from_message<M: Message>(message: &M) -> Option<Self> {
+        Some(Self {
+            topic: message.topic().to_string(),
+            partition: message.partition(),
+            offset: message.offset(),
+            key: message.key().map(<[u8]>::to_vec),
+            payload: message.payload()?.to_vec(),
+        })
+    }
+}
+
 /// Kafka event consumer for consuming events from a topic
 pub struct EventConsumer {
     consumer: BaseConsumer,
+    brokers: String,
+    group_id: String,
+    topics: Vec<String>,
+    auto_commit: bool,
+    health: ConsumerHealth,
 }
 
 impl EventConsumer {
@@ -29,16 +70,54 @@ impl EventConsumer {
         brokers: &str,
         group_id: &str,
         topics: &[&str],
+    ) -> Result<Self, ConsumerError> {
+        Self::build(brokers, group_id, topics, true)
+    }
+
+    /// Create a consumer whose offsets are only committed by explicit `commit` calls,
+    /// so messages are not acknowledged before they have been processed
+    pub fn new_with_manual_commit(
+        brokers: &str,
+        group_id: &str,
+        topics: &[&str],
+    ) -> Result<Self, ConsumerError> {
+        Self::build(brokers, group_id, topics, false)
+    }
+
+    fn build(
+        brokers: &str,
+        group_id: &str,
+        topics: &[&str],
+        auto_commit: bool,
     ) -> Result<Self, ConsumerError> {
         info!(
-            "Creating Kafka consumer with group_id: {}, topics: {:?}",
-            group_id, topics
+            "Creating Kafka consumer with group_id: {}, topics: {:?}, auto_commit: {}",
+            group_id, topics, auto_commit
         );
 
+        let consumer = Self::create_consumer(brokers, group_id, topics, auto_commit)?;
+
+        info!("Kafka consumer created successfully");
+        Ok(Self {
+            consumer,
+            brokers: brokers.to_string(),
+            group_id: group_id.to_string(),
+            topics: topics.iter().map(|topic| topic.to_string()).collect(),
+            auto_commit,
+            health: ConsumerHealth::new(),
+        })
+    }
+
+    fn create_consumer(
+        brokers: &str,
+        group_id: &str,
+        topics: &[&str],
+        auto_commit: bool,
+    ) -> Result<BaseConsumer, ConsumerError> {
         let consumer: BaseConsumer = ClientConfig::new()
             .set("group.id", group_id)
             .set("bootstrap.servers", brokers)
-            .set("enable.auto.commit", "true")
+            .set("enable.auto.commit", auto_commit.to_string())
             .set("auto.commit.interval.ms", "5000")
             .set("auto.offset.reset", "earliest")
             .set("enable.partition.eof", "false")
@@ -47,13 +126,46 @@ impl EventConsumer {
             .create()?;
 
         consumer.subscribe(topics)?;
+        Ok(consumer)
+    }
 
-        info!("Kafka consumer created successfully");
-        Ok(Self { consumer })
+    /// Report health through a shared handle (e.g. one mirrored to a readiness file)
+    pub fn with_health(mut self, health: ConsumerHealth) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Health flag updated on every poll, for readiness probes
+    pub fn health(&self) -> ConsumerHealth {
+        self.health.clone()
+    }
+
+    /// Replace the underlying client after a fatal error
+    pub fn reconnect(&mut self) -> Result<(), ConsumerError> {
+        warn!(
+            "Recreating Kafka consumer with group_id: {}, topics: {:?}",
+            self.group_id, self.topics
+        );
+
+        let topics: Vec<&str> = self.topics.iter().map(String::as_str).collect();
+        self.consumer =
+            Self::create_consumer(&self.brokers, &self.group_id, &topics, self.auto_commit)?;
+        Ok(())
     }
 
     /// Poll for a message with a timeout
     pub async fn poll(&self, timeout: Duration) -> Result<Option<Vec<u8>>, ConsumerError> {
+        Ok(self
+            .poll_received(timeout)
+            .await?
+            .map(|message| message.payload))
+    }
+
+    /// Poll for a message with a timeout, keeping the topic it came from
+    pub async fn poll_received(
+        &self,
+        timeout: Duration,
+    ) -> Result<Option<ReceivedMessage>, ConsumerError> {
         // Convert timeout to Option<Duration> for poll
         let poll_timeout = if timeout.as_millis() > 0 {
             Some(timeout)
@@ -63,6 +175,7 @@ impl EventConsumer {
 
         match self.consumer.poll(poll_timeout) {
             Some(Ok(message)) => {
+                self.health.set_healthy(true);
                 debug!(
                     "Received message from topic: {}, partition: {}, offset: {}",
                     message.topic(),
@@ -70,8 +183,8 @@ impl EventConsumer {
                     message.offset()
                 );
 
-                match message.payload() {
-                    Some(payload) => Ok(Some(payload.to_vec())),
+                match ReceivedMessage::from_message(&message) {
+                    Some(received) => Ok(Some(received)),
                     None => {
                         warn!("Message has no payload");
                         Err(ConsumerError::NoPayload)
@@ -80,16 +193,51 @@ impl EventConsumer {
             }
             Some(Err(e)) => {
                 error!("Kafka error while polling: {}", e);
+                self.health.set_healthy(false);
                 Err(ConsumerError::ConsumerCreation(e))
             }
             None => {
-                // Timeout reached with no message
+                // Timeout reached with no message; an idle topic is not a failure
                 debug!("No message received within timeout");
+                self.health.set_healthy(true);
                 Ok(None)
             }
         }
     }
 
+    /// Collect up to `max_messages` messages, waiting at most `linger` for the batch to fill
+    ///
+    /// An error after some messages were received returns the partial batch; the error
+    /// surfaces again on the next poll if it persists.
+    pub async fn poll_batch(
+        &self,
+        max_messages: usize,
+        linger: Duration,
+    ) -> Result<Vec<ReceivedMessage>, ConsumerError> {
+        let deadline = Instant::now() + linger;
+        let mut batch = Vec::with_capacity(max_messages);
+
+        while batch.len() < max_messages {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+
+            match self.poll_received(remaining).await {
+                Ok(Some(message)) => batch.push(message),
+                Ok(None) => break,
+                Err(ConsumerError::NoPayload) => continue,
+                Err(e) if batch.is_empty() => return Err(e),
+                Err(e) => {
+                    warn!("Returning partial batch of {} after error: {}", batch.len(), e);
+                    break;
+                }
+            }
+        }
+
+        Ok(batch)
+    }
+
     /// Poll and deserialize message
     pub async fn poll_message<T: DeserializeOwned>(
         &self,
@@ -104,6 +252,20 @@ impl EventConsumer {
         }
     }
 
+    /// Rewind the message's partition so the message is polled again
+    ///
+    /// Offsets must not be committed until the message has been handled, or the
+    /// rewind is lost if the consumer restarts.
+    pub fn redeliver(&self, message: &ReceivedMessage) -> Result<(), ConsumerError> {
+        self.consumer.seek(
+            &message.topic,
+            message.partition,
+            Offset::Offset(message.offset),
+            Duration::from_secs(5),
+        )?;
+        Ok(())
+    }
+
     /// Commit the current offsets
     pub fn commit(&self) -> Result<(), ConsumerError> {
         self.consumer
@@ -120,6 +282,7 @@ impl EventConsumer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rdkafka::message::{OwnedMessage, Timestamp};
 
     #[test]
     fn test_consumer_creation_invalid_broker() {
@@ -127,4 +290,37 @@ mod tests {
         // Should succeed in creation (connection happens on poll)
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_received_message_keeps_position() {
+        let message = OwnedMessage::new(
+            Some(b"{}".to_vec()),
+            Some(b"key".to_vec()),
+            "order-events".to_string(),
+            Timestamp::NotAvailable,
+            2,
+            42,
+            None,
+        );
+        let received = ReceivedMessage::from_message(&message).unwrap();
+        assert_eq!((received.partition, received.offset), (2, 42));
+        assert_eq!(received.key.as_deref(), Some(&b"key"[..]));
+
+        let empty = OwnedMessage::new(
+            None,
+            None,
+            "order-events".to_string(),
+            Timestamp::NotAvailable,
+            0,
+            0,
+            None,
+        );
+        assert!(ReceivedMessage::from_message(&empty).is_none());
+    }
+
+    #[test]
+    fn test_consumer_starts_unhealthy() {
+        let consumer = EventConsumer::new("invalid:9092", "test-group", &["test-topic"]).unwrap();
+        assert!(!consumer.health().is_healthy());
+    }
 }
@@ -0,0 +1,119 @@
+use rdkafka::config::ClientConfig;
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::consumer::ReceivedMessage;
+use crate::publisher::PublisherError;
+
+/// Publishes messages a consumer gave up on to a dead letter topic
+///
+/// The original key and payload are kept byte for byte; where the message came
+/// from and why it failed travel as `dlq.*` headers.
+pub struct DeadLetterPublisher {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl DeadLetterPublisher {
+    pub fn new(brokers: &str, topic: String) -> Result<Self, PublisherError> {
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("message.timeout.ms", "5000")
+            .set("acks", "all")
+            .create()
+            .map_err(|e| PublisherError::ProducerCreation(e.to_string()))?;
+
+        info!("Dead letter publisher created for topic: {}", topic);
+        Ok(Self { producer, topic })
+    }
+
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Publish `message` with the error that made the consumer give up on it
+    pub async fn publish(
+        &self,
+        message: &ReceivedMessage,
+        error: &str,
+        attempts: u32,
+    ) -> Result<(), PublisherError> {
+        let headers = dead_letter_headers(message, error, attempts)
+            .iter()
+            .fold(OwnedHeaders::new(), |headers, (key, value)| {
+                headers.insert(Header {
+                    key,
+                    value: Some(value.as_str()),
+                })
+            });
+
+        let mut record = FutureRecord::to(&self.topic)
+            .payload(&message.payload)
+            .headers(headers);
+        if let Some(key) = &message.key {
+            record = record.key(key);
+        }
+
+        match self
+            .producer
+            .send(record, Timeout::After(Duration::from_secs(5)))
+            .await
+        {
+            Ok((partition, offset)) => {
+                info!(
+                    "Dead-lettered message from {}/{}@{} to '{}', partition {}, offset {}",
+                    message.topic,
+                    message.partition,
+                    message.offset,
+                    self.topic,
+                    partition,
+                    offset
+                );
+                Ok(())
+            }
+            Err((err, _)) => {
+                warn!("Failed to publish to dead letter topic '{}': {}", self.topic, err);
+                Err(PublisherError::PublishFailed(err.to_string()))
+            }
+        }
+    }
+}
+
+fn dead_letter_headers(
+    message: &ReceivedMessage,
+    error: &str,
+    attempts: u32,
+) -> Vec<(&'static str, String)> {
+    vec![
+        ("dlq.source.topic", message.topic.clone()),
+        ("dlq.source.partition", message.partition.to_string()),
+        ("dlq.source.offset", message.offset.to_string()),
+        ("dlq.error", error.to_string()),
+        ("dlq.attempts", attempts.to_string()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dead_letter_headers_record_origin() {
+        let message = ReceivedMessage {
+            topic: "order-events".to_string(),
+            partition: 3,
+            offset: 1042,
+            key: None,
+            payload: b"not json".to_vec(),
+        };
+
+        let headers = dead_letter_headers(&message, "expected value at line 1", 3);
+        assert!(headers.contains(&("dlq.source.topic", "order-events".to_string())));
+        assert!(headers.contains(&("dlq.source.partition", "3".to_string())));
+        assert!(headers.contains(&("dlq.source.offset", "1042".to_string())));
+        assert!(headers.contains(&("dlq.attempts", "3".to_string())));
+    }
+}
@@ -0,0 +1,415 @@
+//! Durable background jobs: enqueue, claim, retry and dead-letter
+//!
+//! Producers enqueue a [`NewJob`] onto a named queue and a [`JobWorker`] runs each
+//! one through a [`JobHandler`]. A job is claimed for a lease; one whose worker dies
+//! is handed out again once the lease runs out, so handlers must be idempotent.
+//! Failed jobs are retried with backoff and, once out of attempts or rejected
+//! outright, kept aside as dead letters until an operator requeues them.
+
+#[cfg(feature = "postgres")]
+mod postgres;
+mod worker;
+
+#[cfg(feature = "postgres")]
+pub use postgres::PgJobStore;
+pub use worker::JobWorker;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum JobError {
+    #[error("Job store error: {0}")]
+    Store(String),
+
+    /// The job failed but may succeed if run again
+    #[error("Job failed: {0}")]
+    Failed(String),
+
+    /// The job can never succeed; it is dead-lettered without further attempts
+    #[error("Job rejected: {0}")]
+    Rejected(String),
+}
+
+#[cfg(feature = "postgres")]
+impl From<sqlx::Error> for JobError {
+    fn from(error: sqlx::Error) -> Self {
+        JobError::Store(error.to_string())
+    }
+}
+
+/// A job to add to a queue
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewJob {
+    pub id: Uuid,
+    pub queue: String,
+    pub payload: serde_json::Value,
+    pub ordering_key: Option<String>,
+    pub delay: Duration,
+}
+
+impl NewJob {
+    pub fn new(queue: impl Into<String>, payload: serde_json::Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            queue: queue.into(),
+            payload,
+            ordering_key: None,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Use a known ID, so enqueueing the same job twice adds it once
+    pub fn with_id(mut self, id: Uuid) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Run jobs sharing `key` one at a time, in the order they were enqueued
+    ///
+    /// A job retrying holds back the ones after it; a dead-lettered one does not.
+    pub fn with_ordering_key(mut self, key: impl Into<String>) -> Self {
+        self.ordering_key = Some(key.into());
+        self
+    }
+
+    /// Don't run the job before `delay` has passed
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// A job claimed from its queue, or sitting in the dead letters
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+    pub id: Uuid,
+    pub queue: String,
+    pub payload: serde_json::Value,
+    pub ordering_key: Option<String>,
+    /// Times the job has been claimed, including this one
+    pub attempts: u32,
+    /// Why the previous attempt failed
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Where queued jobs are kept
+///
+/// [`PgJobStore`] keeps them in Postgres, where they can be enqueued in the same
+/// transaction as the change they follow from; [`InMemoryJobStore`] is for tests.
+/// `complete`, `retry` and `dead_letter` only apply to the attempt passed in, so a
+/// worker whose lease ran out can't finish a job someone else has claimed since.
+#[async_trait]
+pub trait JobStore: Send + Sync {
+    /// Add `job` to its queue; does nothing if a job with its ID is already there
+    async fn enqueue(&self, job: NewJob) -> Result<(), JobError>;
+
+    /// Claim up to `limit` due jobs from `queue`, oldest first, for `lease`
+    async fn claim(&self, queue: &str, limit: usize, lease: Duration)
+        -> Result<Vec<Job>, JobError>;
+
+    /// Remove a job that ran successfully
+    async fn complete(&self, job: &Job) -> Result<(), JobError>;
+
+    /// Run the job again after `delay`
+    async fn retry(&self, job: &Job, delay: Duration, error: &str) -> Result<(), JobError>;
+
+    /// Move the job to the dead letters
+    async fn dead_letter(&self, job: &Job, error: &str) -> Result<(), JobError>;
+
+    /// Dead-lettered jobs of `queue`, oldest first
+    async fn dead_letters(&self, queue: &str, limit: usize) -> Result<Vec<Job>, JobError>;
+
+    /// Put a dead-lettered job back in its queue with a fresh set of attempts;
+    /// returns `false` if there is no such dead letter
+    async fn requeue(&self, id: Uuid) -> Result<bool, JobError>;
+
+    /// Jobs of `queue` waiting or running, not counting dead letters
+    async fn depth(&self, queue: &str) -> Result<u64, JobError>;
+}
+
+/// What a [`JobWorker`] does with each job of its queue
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    /// Run the job; `JobError::Rejected` dead-letters it, other errors retry it
+    async fn handle(&self, job: &Job) -> Result<(), JobError>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JobState {
+    Pending { run_at: Instant },
+    Running { locked_until: Instant },
+    Dead,
+}
+
+#[derive(Debug)]
+struct StoredJob {
+    job: Job,
+    state: JobState,
+}
+
+/// Job store that keeps jobs in memory, for tests and local demos
+#[derive(Default)]
+pub struct InMemoryJobStore {
+    jobs: Mutex<Vec<StoredJob>>,
+}
+
+impl InMemoryJobStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Jobs waiting or running, across all queues
+    pub fn len(&self) -> usize {
+        self.lock()
+            .iter()
+            .filter(|stored| stored.state != JobState::Dead)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<StoredJob>> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Apply `update` to the stored job if `job` is still its current attempt
+    fn update_attempt(&self, job: &Job, update: impl FnOnce(&mut StoredJob)) {
+        let mut jobs = self.lock();
+        if let Some(stored) = jobs.iter_mut().find(|stored| {
+            stored.job.id == job.id
+                && stored.job.attempts == job.attempts
+                && matches!(stored.state, JobState::Running { .. })
+        }) {
+            update(stored);
+        }
+    }
+}
+
+#[async_trait]
+impl JobStore for InMemoryJobStore {
+    async fn enqueue(&self, job: NewJob) -> Result<(), JobError> {
+        let mut jobs = self.lock();
+        if jobs.iter().any(|stored| stored.job.id == job.id) {
+            return Ok(());
+        }
+        jobs.push(StoredJob {
+            job: Job {
+                id: job.id,
+                queue: job.queue,
+                payload: job.payload,
+                ordering_key: job.ordering_key,
+                attempts: 0,
+                last_error: None,
+                created_at: Utc::now(),
+            },
+            state: JobState::Pending {
+                run_at: Instant::now() + job.delay,
+            },
+        });
+        Ok(())
+    }
+
+    async fn claim(
+        &self,
+        queue: &str,
+        limit: usize,
+        lease: Duration,
+    ) -> Result<Vec<Job>, JobError> {
+        let now = Instant::now();
+        let mut jobs = self.lock();
+        // Ordering keys with an earlier job still waiting or running
+        let mut held = HashSet::new();
+        let mut claimed = Vec::new();
+        for stored in jobs.iter_mut().filter(|stored| stored.job.queue == queue) {
+            if claimed.len() >= limit {
+                break;
+            }
+            let due = match stored.state {
+                JobState::Pending { run_at } => run_at <= now,
+                JobState::Running { locked_until } => locked_until <= now,
+                JobState::Dead => continue,
+            };
+            if let Some(key) = &stored.job.ordering_key {
+                if !held.insert(key.clone()) {
+                    continue;
+                }
+            }
+            if due {
+                stored.job.attempts += 1;
+                stored.state = JobState::Running {
+                    locked_until: now + lease,
+                };
+                claimed.push(stored.job.clone());
+            }
+        }
+        Ok(claimed)
+    }
+
+    async fn complete(&self, job: &Job) -> Result<(), JobError> {
+        let mut jobs = self.lock();
+        jobs.retain(|stored| {
+            !(stored.job.id == job.id
+                && stored.job.attempts == job.attempts
+                && matches!(stored.state, JobState::Running { .. }))
+        });
+        Ok(())
+    }
+
+    async fn retry(&self, job: &Job, delay: Duration, error: &str) -> Result<(), JobError> {
+        self.update_attempt(job, |stored| {
+            stored.job.last_error = Some(error.to_string());
+            stored.state = JobState::Pending {
+                run_at: Instant::now() + delay,
+            };
+        });
+        Ok(())
+    }
+
+    async fn dead_letter(&self, job: &Job, error: &str) -> Result<(), JobError> {
+        self.update_attempt(job, |stored| {
+            stored.job.last_error = Some(error.to_string());
+            stored.state = JobState::Dead;
+        });
+        Ok(())
+    }
+
+    async fn dead_letters(&self, queue: &str, limit: usize) -> Result<Vec<Job>, JobError> {
+        Ok(self
+            .lock()
+            .iter()
+            .filter(|stored| stored.job.queue == queue && stored.state == JobState::Dead)
+            .take(limit)
+            .map(|stored| stored.job.clone())
+            .collect())
+    }
+
+    async fn requeue(&self, id: Uuid) -> Result<bool, JobError> {
+        let mut jobs = self.lock();
+        let Some(stored) = jobs
+            .iter_mut()
+            .find(|stored| stored.job.id == id && stored.state == JobState::Dead)
+        else {
+            return Ok(false);
+        };
+        stored.job.attempts = 0;
+        stored.state = JobState::Pending {
+            run_at: Instant::now(),
+        };
+        Ok(true)
+    }
+
+    async fn depth(&self, queue: &str) -> Result<u64, JobError> {
+        Ok(self
+            .lock()
+            .iter()
+            .filter(|stored| stored.job.queue == queue && stored.state != JobState::Dead)
+            .count() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEASE: Duration = Duration::from_secs(60);
+
+    fn job(key: &str, n: u32) -> NewJob {
+        NewJob::new("test", serde_json::json!({ "n": n })).with_ordering_key(key)
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_is_idempotent() {
+        let store = InMemoryJobStore::new();
+        let job = NewJob::new("test", serde_json::json!({}));
+        store.enqueue(job.clone()).await.unwrap();
+        store.enqueue(job).await.unwrap();
+        assert_eq!(store.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_claim_holds_back_later_jobs_of_a_key() {
+        let store = InMemoryJobStore::new();
+        for (key, n) in [("a", 1), ("a", 2), ("b", 3)] {
+            store.enqueue(job(key, n)).await.unwrap();
+        }
+        store
+            .enqueue(NewJob::new("other", serde_json::json!({})))
+            .await
+            .unwrap();
+
+        let claimed = store.claim("test", 10, LEASE).await.unwrap();
+        let payloads: Vec<_> = claimed.iter().map(|job| job.payload["n"].clone()).collect();
+        assert_eq!(payloads, [1, 3]);
+        assert!(store.claim("test", 10, LEASE).await.unwrap().is_empty());
+
+        // A retry keeps its place; the next job of the key waits for it
+        store
+            .retry(&claimed[0], Duration::ZERO, "boom")
+            .await
+            .unwrap();
+        let retried = store.claim("test", 10, LEASE).await.unwrap();
+        assert_eq!(retried.len(), 1);
+        assert_eq!(retried[0].id, claimed[0].id);
+        assert_eq!(retried[0].attempts, 2);
+        assert_eq!(retried[0].last_error.as_deref(), Some("boom"));
+
+        store.complete(&retried[0]).await.unwrap();
+        let next = store.claim("test", 10, LEASE).await.unwrap();
+        assert_eq!(next[0].payload["n"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_delayed_and_expired_claims() {
+        let store = InMemoryJobStore::new();
+        store
+            .enqueue(NewJob::new("test", serde_json::json!({})).with_delay(LEASE))
+            .await
+            .unwrap();
+        assert!(store.claim("test", 1, LEASE).await.unwrap().is_empty());
+
+        store.enqueue(job("a", 1)).await.unwrap();
+        let first = store.claim("test", 1, Duration::ZERO).await.unwrap();
+        let second = store.claim("test", 1, LEASE).await.unwrap();
+        assert_eq!(first[0].id, second[0].id);
+
+        // The first claim's lease ran out, so it can no longer finish the job
+        store.complete(&first[0]).await.unwrap();
+        assert_eq!(store.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters_release_their_key_and_can_be_requeued() {
+        let store = InMemoryJobStore::new();
+        store.enqueue(job("a", 1)).await.unwrap();
+        store.enqueue(job("a", 2)).await.unwrap();
+
+        let claimed = store.claim("test", 1, LEASE).await.unwrap();
+        store.dead_letter(&claimed[0], "bad payload").await.unwrap();
+        let dead = store.dead_letters("test", 10).await.unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].last_error.as_deref(), Some("bad payload"));
+        assert_eq!(store.depth("test").await.unwrap(), 1);
+        assert_eq!(store.depth("other").await.unwrap(), 0);
+
+        let next = store.claim("test", 10, LEASE).await.unwrap();
+        assert_eq!(next[0].payload["n"], 2);
+
+        assert!(store.requeue(dead[0].id).await.unwrap());
+        assert!(!store.requeue(dead[0].id).await.unwrap());
+        store.complete(&next[0]).await.unwrap();
+        let requeued = store.claim("test", 10, LEASE).await.unwrap();
+        assert_eq!(requeued[0].id, dead[0].id);
+        assert_eq!(requeued[0].attempts, 1);
+    }
+}
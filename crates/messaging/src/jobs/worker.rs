@@ -0,0 +1,315 @@
+use common::metrics::record_job;
+use common::retry::Backoff;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio::task::{JoinHandle, JoinSet};
+use tracing::{error, info, warn};
+
+use super::{Job, JobError, JobHandler, JobStore};
+
+/// Runs the jobs of one queue through a handler, a few at a time
+///
+/// Each attempt must finish within the lease it was claimed for; one that doesn't
+/// is abandoned and retried. Failed jobs are retried after `backoff` until
+/// `max_attempts` is reached, then dead-lettered.
+pub struct JobWorker {
+    store: Arc<dyn JobStore>,
+    queue: String,
+    handler: Arc<dyn JobHandler>,
+    concurrency: usize,
+    lease: Duration,
+    backoff: Backoff,
+    max_attempts: Option<u32>,
+}
+
+impl JobWorker {
+    pub fn new(
+        store: Arc<dyn JobStore>,
+        queue: impl Into<String>,
+        handler: Arc<dyn JobHandler>,
+    ) -> Self {
+        Self {
+            store,
+            queue: queue.into(),
+            handler,
+            concurrency: 4,
+            lease: Duration::from_secs(60),
+            backoff: Backoff::ExponentialJitter {
+                initial: Duration::from_secs(1),
+                max: Duration::from_secs(300),
+            },
+            max_attempts: Some(10),
+        }
+    }
+
+    /// Jobs run at once (default 4)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// How long an attempt may run before the job is handed out again (default 60s)
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Delay before each retry (default exponential with jitter, 1s up to 5 minutes)
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Attempts before a failing job is dead-lettered (default 10)
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts.max(1));
+        self
+    }
+
+    /// Retry failing jobs until they succeed; only rejected jobs are dead-lettered
+    pub fn retry_forever(mut self) -> Self {
+        self.max_attempts = None;
+        self
+    }
+
+    pub fn queue(&self) -> &str {
+        &self.queue
+    }
+
+    /// Run due jobs every `poll_interval`, or sooner when `wake` is notified
+    pub fn spawn(self, wake: Arc<Notify>, poll_interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(poll_interval);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = wake.notified() => {}
+                }
+                match self.run_once().await {
+                    Ok(0) => {}
+                    Ok(ran) => info!("Ran {} jobs from the {} queue", ran, self.queue),
+                    Err(e) => error!("Failed to claim jobs from the {} queue: {}", self.queue, e),
+                }
+            }
+        })
+    }
+
+    /// Run jobs until none are due; returns how many attempts were made
+    pub async fn run_once(&self) -> Result<usize, JobError> {
+        let mut running = JoinSet::new();
+        let mut ran = 0;
+
+        loop {
+            let free = self.concurrency - running.len();
+            let claimed = self.store.claim(&self.queue, free, self.lease).await?;
+            let exhausted = claimed.len() < free;
+            for job in claimed {
+                ran += 1;
+                running.spawn(self.attempt(job));
+            }
+
+            // Claim again once a slot frees up: finishing a job may release the next
+            // one with its ordering key
+            match running.join_next().await {
+                Some(Err(e)) => error!("Job on the {} queue panicked: {}", self.queue, e),
+                Some(Ok(())) => {}
+                None if exhausted => return Ok(ran),
+                None => {}
+            }
+        }
+    }
+
+    /// Run one attempt and settle the job according to its outcome
+    fn attempt(&self, job: Job) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let store = self.store.clone();
+        let handler = self.handler.clone();
+        let lease = self.lease;
+        let out_of_attempts = self.max_attempts.is_some_and(|max| job.attempts >= max);
+        let delay = self.backoff.delay(job.attempts);
+
+        async move {
+            let start = Instant::now();
+            let result = match tokio::time::timeout(lease, handler.handle(&job)).await {
+                Ok(result) => result,
+                Err(_) => Err(JobError::Failed(format!("timed out after {:?}", lease))),
+            };
+
+            let (outcome, settled) = match result {
+                Ok(()) => ("completed", store.complete(&job).await),
+                Err(e @ JobError::Rejected(_)) => {
+                    warn!("Job {} on the {} queue rejected: {}", job.id, job.queue, e);
+                    (
+                        "dead_lettered",
+                        store.dead_letter(&job, &e.to_string()).await,
+                    )
+                }
+                Err(e) if out_of_attempts => {
+                    warn!(
+                        "Job {} on the {} queue failed {} times, dead-lettering it: {}",
+                        job.id, job.queue, job.attempts, e
+                    );
+                    (
+                        "dead_lettered",
+                        store.dead_letter(&job, &e.to_string()).await,
+                    )
+                }
+                Err(e) => {
+                    warn!(
+                        "Job {} on the {} queue failed, retrying in {:?}: {}",
+                        job.id, job.queue, delay, e
+                    );
+                    ("retried", store.retry(&job, delay, &e.to_string()).await)
+                }
+            };
+            record_job(&job.queue, outcome, start.elapsed().as_secs_f64());
+
+            // The job is handed out again once its lease runs out
+            if let Err(e) = settled {
+                error!("Failed to record the outcome of job {}: {}", job.id, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::{InMemoryJobStore, NewJob};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Records the jobs it runs; fails the first `failures` attempts of every job
+    #[derive(Default)]
+    struct RecordingHandler {
+        failures: u32,
+        reject: bool,
+        ran: Mutex<Vec<u64>>,
+        running: AtomicUsize,
+        max_running: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl JobHandler for RecordingHandler {
+        async fn handle(&self, job: &Job) -> Result<(), JobError> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+
+            if self.reject {
+                return Err(JobError::Rejected("malformed".to_string()));
+            }
+            if job.attempts <= self.failures {
+                return Err(JobError::Failed("unavailable".to_string()));
+            }
+            let n = job.payload["n"].as_u64().unwrap_or_default();
+            self.ran.lock().unwrap().push(n);
+            Ok(())
+        }
+    }
+
+    fn worker(store: &Arc<InMemoryJobStore>, handler: &Arc<RecordingHandler>) -> JobWorker {
+        JobWorker::new(store.clone(), "test", handler.clone())
+            .with_backoff(Backoff::Fixed(Duration::ZERO))
+    }
+
+    async fn enqueue(store: &InMemoryJobStore, key: &str, n: u64) {
+        let job = NewJob::new("test", serde_json::json!({ "n": n })).with_ordering_key(key);
+        store.enqueue(job).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_runs_keys_in_order_and_in_parallel() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let handler = Arc::new(RecordingHandler::default());
+        for n in 0..12 {
+            enqueue(&store, &(n % 3).to_string(), n).await;
+        }
+
+        let ran = worker(&store, &handler)
+            .with_concurrency(3)
+            .run_once()
+            .await
+            .unwrap();
+
+        assert_eq!(ran, 12);
+        assert!(store.is_empty());
+        assert_eq!(handler.max_running.load(Ordering::SeqCst), 3);
+        let order = handler.ran.lock().unwrap().clone();
+        for key in 0..3 {
+            let runs: Vec<_> = order.iter().filter(|n| *n % 3 == key).collect();
+            assert!(runs.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", order);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_then_dead_letters() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let handler = Arc::new(RecordingHandler {
+            failures: 1,
+            ..Default::default()
+        });
+        enqueue(&store, "a", 1).await;
+
+        let ran = worker(&store, &handler).run_once().await.unwrap();
+        assert_eq!(ran, 2);
+        assert_eq!(*handler.ran.lock().unwrap(), [1]);
+
+        enqueue(&store, "a", 2).await;
+        let ran = worker(&store, &handler)
+            .with_max_attempts(1)
+            .run_once()
+            .await
+            .unwrap();
+        assert_eq!(ran, 1);
+        let dead = store.dead_letters("test", 10).await.unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(
+            dead[0].last_error.as_deref(),
+            Some("Job failed: unavailable")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_jobs_are_not_retried() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let handler = Arc::new(RecordingHandler {
+            reject: true,
+            ..Default::default()
+        });
+        enqueue(&store, "a", 1).await;
+
+        let ran = worker(&store, &handler)
+            .retry_forever()
+            .run_once()
+            .await
+            .unwrap();
+
+        assert_eq!(ran, 1);
+        assert_eq!(store.dead_letters("test", 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_attempts_past_their_lease_are_retried() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let handler = Arc::new(RecordingHandler::default());
+        enqueue(&store, "a", 1).await;
+
+        let ran = worker(&store, &handler)
+            .with_lease(Duration::from_millis(1))
+            .with_max_attempts(2)
+            .run_once()
+            .await
+            .unwrap();
+
+        assert_eq!(ran, 2);
+        assert!(handler.ran.lock().unwrap().is_empty());
+        let dead = store.dead_letters("test", 10).await.unwrap();
+        assert!(dead[0]
+            .last_error
+            .as_deref()
+            .is_some_and(|e| e.contains("timed out")));
+    }
+}
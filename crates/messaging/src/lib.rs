@@ -1,5 +1,33 @@
+#[cfg(feature = "kafka")]
 pub mod producer;
+pub mod publisher;
+#[cfg(feature = "kafka")]
 pub mod consumer;
+pub mod reconnect;
+pub mod jobs;
+#[cfg(feature = "kafka")]
+pub mod poison;
+#[cfg(feature = "kafka")]
+pub mod dead_letter;
+#[cfg(feature = "kafka")]
+pub mod topics;
 
+#[cfg(feature = "kafka")]
 pub use producer::EventPublisher;
-pub use consumer::EventConsumer;
+pub use publisher::{
+    BrokerHealth, InMemoryPublisher, PublishedMessage, Publisher, PublisherError,
+};
+#[cfg(feature = "kafka")]
+pub use consumer::{EventConsumer, ReceivedMessage};
+pub use reconnect::{ConsumerHealth, ReconnectBackoff};
+#[cfg(feature = "postgres")]
+pub use jobs::PgJobStore;
+pub use jobs::{InMemoryJobStore, Job, JobError, JobHandler, JobStore, JobWorker, NewJob};
+#[cfg(feature = "kafka")]
+pub use poison::{PoisonPillDetector, PoisonVerdict};
+#[cfg(feature = "kafka")]
+pub use dead_letter::DeadLetterPublisher;
+#[cfg(feature = "kafka")]
+pub use topics::{
+    preflight_topics, CleanupPolicy, TopicError, TopicManager, TopicSettings, TopicSpec,
+};
@@ -0,0 +1,197 @@
+use common::metrics;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::consumer::ReceivedMessage;
+
+/// What to do with a message that failed to deserialize
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoisonVerdict {
+    /// Deliver the message again
+    Retry { attempts: u32 },
+    /// Give up and route the message to the dead letter topic
+    Poison { attempts: u32 },
+}
+
+/// Counts deserialization failures per message to spot poison pills
+///
+/// Messages are identified by their `event_id` when the payload carries one, so a
+/// republished copy of a bad event shares its count, and by topic, partition and
+/// offset otherwise. Only the most recent `capacity` failing messages are tracked.
+pub struct PoisonPillDetector {
+    consumer: String,
+    max_attempts: u32,
+    capacity: usize,
+    failures: HashMap<String, u32>,
+    order: VecDeque<String>,
+}
+
+impl PoisonPillDetector {
+    /// `consumer` labels the metric and logs, usually the consumer group
+    pub fn new(consumer: &str, max_attempts: u32) -> Self {
+        Self {
+            consumer: consumer.to_string(),
+            max_attempts: max_attempts.max(1),
+            capacity: 10_000,
+            failures: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Cap on the number of failing messages tracked at once
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Count a failed attempt and decide whether the message is a poison pill
+    pub fn record_failure(&mut self, message: &ReceivedMessage, error: &str) -> PoisonVerdict {
+        let key = message_key(message);
+        let attempts = match self.failures.get_mut(&key) {
+            Some(attempts) => {
+                *attempts += 1;
+                *attempts
+            }
+            None => {
+                self.track(key.clone());
+                1
+            }
+        };
+
+        if attempts < self.max_attempts {
+            warn!(
+                consumer = %self.consumer,
+                topic = %message.topic,
+                partition = message.partition,
+                offset = message.offset,
+                attempts,
+                max_attempts = self.max_attempts,
+                error,
+                "Failed to deserialize message"
+            );
+            return PoisonVerdict::Retry { attempts };
+        }
+
+        self.failures.remove(&key);
+        metrics::record_poison_message(&self.consumer, &message.topic);
+        error!(
+            consumer = %self.consumer,
+            topic = %message.topic,
+            partition = message.partition,
+            offset = message.offset,
+            message_key = %key,
+            attempts,
+            error,
+            "Poison message detected, routing to dead letter topic"
+        );
+        PoisonVerdict::Poison { attempts }
+    }
+
+    /// Forget the failures of a message once it has been handled
+    pub fn clear(&mut self, message: &ReceivedMessage) {
+        if !self.failures.is_empty() {
+            self.failures.remove(&message_key(message));
+        }
+    }
+
+    fn track(&mut self, key: String) {
+        // The queue may still hold keys cleared since; evicting those is a no-op
+        while self.failures.len() >= self.capacity {
+            match self.order.pop_front() {
+                Some(oldest) => {
+                    self.failures.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+        if self.order.len() >= self.capacity * 2 {
+            let failures = &self.failures;
+            self.order.retain(|queued| failures.contains_key(queued));
+        }
+        self.order.push_back(key.clone());
+        self.failures.insert(key, 1);
+    }
+}
+
+#[derive(Deserialize)]
+struct EventIdOnly {
+    event_id: Uuid,
+}
+
+fn message_key(message: &ReceivedMessage) -> String {
+    match serde_json::from_slice::<EventIdOnly>(&message.payload) {
+        Ok(ids) => format!("event:{}", ids.event_id),
+        Err(_) => format!(
+            "offset:{}/{}@{}",
+            message.topic, message.partition, message.offset
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(offset: i64, payload: &str) -> ReceivedMessage {
+        ReceivedMessage {
+            topic: "order-events".to_string(),
+            partition: 0,
+            offset,
+            key: None,
+            payload: payload.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_poison_after_max_attempts() {
+        let mut detector = PoisonPillDetector::new("test", 3);
+        let bad = message(7, "not json");
+
+        assert_eq!(detector.record_failure(&bad, "e"), PoisonVerdict::Retry { attempts: 1 });
+        assert_eq!(detector.record_failure(&bad, "e"), PoisonVerdict::Retry { attempts: 2 });
+        assert_eq!(detector.record_failure(&bad, "e"), PoisonVerdict::Poison { attempts: 3 });
+
+        // Counting starts over if the same offset fails again later
+        assert_eq!(detector.record_failure(&bad, "e"), PoisonVerdict::Retry { attempts: 1 });
+    }
+
+    #[test]
+    fn test_counts_by_event_id_across_offsets() {
+        let mut detector = PoisonPillDetector::new("test", 2);
+        let payload = format!(r#"{{"event_id": "{}", "payload": 1}}"#, Uuid::new_v4());
+
+        assert_eq!(
+            detector.record_failure(&message(1, &payload), "e"),
+            PoisonVerdict::Retry { attempts: 1 }
+        );
+        assert_eq!(
+            detector.record_failure(&message(9, &payload), "e"),
+            PoisonVerdict::Poison { attempts: 2 }
+        );
+    }
+
+    #[test]
+    fn test_clear_resets_count() {
+        let mut detector = PoisonPillDetector::new("test", 2);
+        let bad = message(3, "not json");
+
+        detector.record_failure(&bad, "e");
+        detector.clear(&bad);
+        assert_eq!(detector.record_failure(&bad, "e"), PoisonVerdict::Retry { attempts: 1 });
+    }
+
+    #[test]
+    fn test_capacity_evicts_oldest() {
+        let mut detector = PoisonPillDetector::new("test", 2).with_capacity(2);
+        let first = message(1, "x");
+
+        detector.record_failure(&first, "e");
+        detector.record_failure(&message(2, "x"), "e");
+        detector.record_failure(&message(3, "x"), "e");
+
+        assert_eq!(detector.failures.len(), 2);
+        assert_eq!(detector.record_failure(&first, "e"), PoisonVerdict::Retry { attempts: 1 });
+    }
+}
@@ -1,28 +1,46 @@
+use async_trait::async_trait;
+use common::bulkhead::Bulkhead;
+use common::circuit_breaker::{CircuitBreaker, CircuitBreakerError};
+use common::deadline::{Deadline, DeadlineExceeded};
+use common::retry::{retry, Backoff, RetryPolicy};
 use rdkafka::config::ClientConfig;
-use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
 use rdkafka::util::Timeout;
 use serde::Serialize;
+use std::sync::Arc;
 use std::time::Duration;
-use thiserror::Error;
 use tracing::{info, warn};
 use uuid::Uuid;
 
-#[derive(Debug, Error)]
-pub enum PublisherError {
-    #[error("Failed to create Kafka producer: {0}")]
-    ProducerCreation(String),
-
-    #[error("Failed to serialize event: {0}")]
-    Serialization(#[from] serde_json::Error),
-
-    #[error("Failed to publish event: {0}")]
-    PublishFailed(String),
-}
+use crate::publisher::{BrokerHealth, Publisher, PublisherError};
 
 /// Kafka event publisher for publishing domain events
 pub struct EventPublisher {
     producer: FutureProducer,
     topic: String,
+    bulkhead: Option<Arc<Bulkhead>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    healthcheck_timeout: Duration,
+    retry_policy: RetryPolicy<PublisherError>,
+}
+
+/// How long one attempt waits for an acknowledgement without a circuit breaker
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Three attempts, retrying only failed deliveries; a full bulkhead or an open
+/// breaker is returned at once
+///
+/// Each attempt is bounded on its own, by [`SEND_TIMEOUT`] or the breaker's timeout,
+/// so the deadline is only there to cap the three of them and the backoff between.
+fn default_retry_policy() -> RetryPolicy<PublisherError> {
+    RetryPolicy::new("kafka.publish")
+        .with_max_attempts(3)
+        .with_backoff(Backoff::ExponentialJitter {
+            initial: Duration::from_millis(200),
+            max: Duration::from_secs(2),
+        })
+        .with_deadline(SEND_TIMEOUT * 3 + Duration::from_secs(2))
+        .with_retryable(|e| matches!(e, PublisherError::PublishFailed(_)))
 }
 
 impl EventPublisher {
@@ -53,7 +71,71 @@ impl EventPublisher {
 
         info!("Kafka producer created successfully for topic: {}", topic);
 
-        Ok(Self { producer, topic })
+        Ok(Self {
+            producer,
+            topic,
+            bulkhead: None,
+            circuit_breaker: None,
+            healthcheck_timeout: Duration::from_secs(5),
+            retry_policy: default_retry_policy(),
+        })
+    }
+
+    /// Cap the number of publishes awaiting acknowledgement at once
+    pub fn with_bulkhead(mut self, bulkhead: Arc<Bulkhead>) -> Self {
+        self.bulkhead = Some(bulkhead);
+        self
+    }
+
+    /// Run each attempt through `breaker`
+    ///
+    /// The breaker sits inside the retries: its timeout bounds one attempt in place
+    /// of the 5s send timeout, and once it opens the remaining attempts are skipped.
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// How failed deliveries are retried, on top of librdkafka's own retries
+    pub fn with_retry_policy(mut self, policy: RetryPolicy<PublisherError>) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// How long [`healthcheck`](Self::healthcheck) waits for broker metadata
+    pub fn with_healthcheck_timeout(mut self, timeout: Duration) -> Self {
+        self.healthcheck_timeout = timeout;
+        self
+    }
+
+    /// Fetch cluster metadata and report on the publisher's topic
+    ///
+    /// Creating a producer doesn't contact the brokers, so without this a wrong
+    /// broker address or missing topic only shows up when the first publish fails.
+    /// Errors if no metadata arrives within the healthcheck timeout; check
+    /// [`BrokerHealth::is_healthy`] for a topic that is missing or has partitions
+    /// without a leader.
+    pub async fn healthcheck(&self) -> Result<BrokerHealth, PublisherError> {
+        let producer = self.producer.clone();
+        let timeout = self.healthcheck_timeout;
+        let topic = self.topic.clone();
+
+        // Metadata for all topics, since asking for one may auto-create it
+        tokio::task::spawn_blocking(move || {
+            let metadata = producer
+                .client()
+                .fetch_metadata(None, timeout)
+                .map_err(|e| PublisherError::MetadataUnavailable(e.to_string()))?;
+            let leaders: Vec<i32> = metadata
+                .topics()
+                .iter()
+                .filter(|t| t.name() == topic)
+                .flat_map(|t| t.partitions().iter().map(|partition| partition.leader()))
+                .collect();
+            Ok(BrokerHealth::from_metadata(metadata.brokers().len(), leaders))
+        })
+        .await
+        .map_err(|e| PublisherError::MetadataUnavailable(e.to_string()))?
     }
 
     /// Publish an event to Kafka
@@ -88,17 +170,47 @@ impl EventPublisher {
         event: &T,
     ) -> Result<(), PublisherError> {
         let payload = serde_json::to_string(event)?;
+        self.send(key, &payload).await
+    }
+
+    async fn send(&self, key: Uuid, payload: &str) -> Result<(), PublisherError> {
+        retry(&self.retry_policy, || self.send_once(key, payload)).await
+    }
+
+    async fn send_once(&self, key: Uuid, payload: &str) -> Result<(), PublisherError> {
         let key_str = key.to_string();
 
         let record = FutureRecord::to(&self.topic)
             .key(&key_str)
-            .payload(&payload);
+            .payload(payload);
+
+        // Don't wait for an acknowledgement past the caller's deadline
+        let deadline = Deadline::current();
+        if deadline.is_expired() {
+            return Err(DeadlineExceeded.into());
+        }
+        let timeout = self
+            .circuit_breaker
+            .as_ref()
+            .map_or(SEND_TIMEOUT, |breaker| breaker.config().timeout);
+        let send = async {
+            let delivery = async {
+                self.producer
+                    .send(record, Timeout::After(deadline.cap(timeout)))
+                    .await
+                    .map_err(|(err, _)| err)
+            };
+            match &self.circuit_breaker {
+                Some(breaker) => breaker.call(delivery).await,
+                None => delivery.await.map_err(CircuitBreakerError::CallFailed),
+            }
+        };
+        let delivery = match &self.bulkhead {
+            Some(bulkhead) => bulkhead.call(send).await?,
+            None => send.await,
+        };
 
-        match self
-            .producer
-            .send(record, Timeout::After(Duration::from_secs(5)))
-            .await
-        {
+        match delivery {
             Ok((partition, offset)) => {
                 info!(
                     "Event published successfully to topic '{}', partition {}, offset {}",
@@ -106,7 +218,15 @@ impl EventPublisher {
                 );
                 Ok(())
             }
-            Err((err, _)) => {
+            Err(CircuitBreakerError::Open) => Err(PublisherError::CircuitOpen),
+            Err(CircuitBreakerError::Timeout) => {
+                warn!("No acknowledgement for event within {:?}", timeout);
+                Err(PublisherError::PublishFailed(format!(
+                    "no acknowledgement within {:?}",
+                    timeout
+                )))
+            }
+            Err(CircuitBreakerError::CallFailed(err)) => {
                 warn!("Failed to publish event: {}", err);
                 Err(PublisherError::PublishFailed(err.to_string()))
             }
@@ -126,6 +246,30 @@ impl EventPublisher {
         }
         Ok(())
     }
+
+    /// Messages handed to the producer that the brokers have not acknowledged yet
+    pub fn in_flight_count(&self) -> usize {
+        self.producer.in_flight_count().max(0) as usize
+    }
+}
+
+#[async_trait]
+impl Publisher for EventPublisher {
+    fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    async fn publish_payload(&self, key: Uuid, payload: &str) -> Result<(), PublisherError> {
+        self.send(key, payload).await
+    }
+
+    async fn healthcheck(&self) -> Result<BrokerHealth, PublisherError> {
+        EventPublisher::healthcheck(self).await
+    }
+
+    fn in_flight_count(&self) -> usize {
+        EventPublisher::in_flight_count(self)
+    }
 }
 
 #[cfg(test)]
@@ -143,6 +287,7 @@ mod tests {
         // This should succeed (creation doesn't validate connection)
         let result = EventPublisher::new("", "test-topic".to_string());
         assert!(result.is_ok());
+        assert_eq!(result.unwrap().in_flight_count(), 0);
     }
 
     #[tokio::test]
@@ -0,0 +1,209 @@
+use async_trait::async_trait;
+use common::bulkhead::BulkheadFull;
+use common::deadline::DeadlineExceeded;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum PublisherError {
+    #[error("Failed to create Kafka producer: {0}")]
+    ProducerCreation(String),
+
+    #[error("Failed to serialize event: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Failed to publish event: {0}")]
+    PublishFailed(String),
+
+    #[error("Kafka publisher overloaded: {0}")]
+    Overloaded(#[from] BulkheadFull),
+
+    #[error("Kafka publisher circuit breaker is open")]
+    CircuitOpen,
+
+    #[error("Kafka broker metadata unavailable: {0}")]
+    MetadataUnavailable(String),
+
+    #[error("Publish cancelled: {0}")]
+    DeadlineExceeded(#[from] DeadlineExceeded),
+}
+
+/// What the brokers reported about the publisher's topic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BrokerHealth {
+    /// Brokers in the cluster metadata
+    pub brokers: usize,
+    /// Partitions of the topic; 0 if the topic does not exist
+    pub partitions: usize,
+    /// Partitions with no leader, which cannot accept writes
+    pub leaderless_partitions: usize,
+}
+
+impl BrokerHealth {
+    /// Summarize metadata given the leader broker of each partition (-1 for none)
+    #[cfg_attr(not(feature = "kafka"), allow(dead_code))]
+    pub(crate) fn from_metadata(brokers: usize, leaders: impl IntoIterator<Item = i32>) -> Self {
+        let (mut partitions, mut leaderless_partitions) = (0, 0);
+        for leader in leaders {
+            partitions += 1;
+            if leader < 0 {
+                leaderless_partitions += 1;
+            }
+        }
+        Self {
+            brokers,
+            partitions,
+            leaderless_partitions,
+        }
+    }
+
+    /// Whether every partition of the topic can take writes
+    pub fn is_healthy(&self) -> bool {
+        self.brokers > 0 && self.partitions > 0 && self.leaderless_partitions == 0
+    }
+}
+
+/// Where events are published, keyed by the aggregate they belong to
+///
+/// [`EventPublisher`](crate::EventPublisher) publishes to Kafka; [`InMemoryPublisher`]
+/// keeps messages for tests to inspect. Publish typed events through
+/// `dyn Publisher`'s `publish`.
+#[async_trait]
+pub trait Publisher: Send + Sync {
+    /// Topic or destination published to
+    fn topic(&self) -> &str;
+
+    /// Publish an event already serialized to JSON
+    async fn publish_payload(&self, key: Uuid, payload: &str) -> Result<(), PublisherError>;
+
+    /// Check the destination can take messages
+    async fn healthcheck(&self) -> Result<BrokerHealth, PublisherError>;
+
+    /// Messages handed over but not acknowledged yet
+    fn in_flight_count(&self) -> usize {
+        0
+    }
+}
+
+impl dyn Publisher {
+    /// Serialize `event` to JSON and publish it
+    pub async fn publish<T: Serialize + ?Sized>(
+        &self,
+        key: Uuid,
+        event: &T,
+    ) -> Result<(), PublisherError> {
+        let payload = serde_json::to_string(event)?;
+        self.publish_payload(key, &payload).await
+    }
+}
+
+/// A message recorded by [`InMemoryPublisher`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublishedMessage {
+    pub key: Uuid,
+    pub payload: serde_json::Value,
+}
+
+/// Publisher that keeps messages in memory, for tests and local demos
+#[derive(Default)]
+pub struct InMemoryPublisher {
+    topic: String,
+    messages: Mutex<Vec<PublishedMessage>>,
+    unavailable: AtomicBool,
+}
+
+impl InMemoryPublisher {
+    pub fn new(topic: impl Into<String>) -> Self {
+        Self {
+            topic: topic.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Messages published so far, oldest first
+    pub fn messages(&self) -> Vec<PublishedMessage> {
+        self.messages.lock().unwrap().clone()
+    }
+
+    /// Make publishes and health checks fail, as if the brokers were down
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.unavailable.store(unavailable, Ordering::SeqCst);
+    }
+
+    fn is_unavailable(&self) -> bool {
+        self.unavailable.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl Publisher for InMemoryPublisher {
+    fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    async fn publish_payload(&self, key: Uuid, payload: &str) -> Result<(), PublisherError> {
+        if self.is_unavailable() {
+            return Err(PublisherError::PublishFailed("publisher unavailable".to_string()));
+        }
+        let payload = serde_json::from_str(payload)?;
+        self.messages.lock().unwrap().push(PublishedMessage { key, payload });
+        Ok(())
+    }
+
+    async fn healthcheck(&self) -> Result<BrokerHealth, PublisherError> {
+        if self.is_unavailable() {
+            return Err(PublisherError::MetadataUnavailable("publisher unavailable".to_string()));
+        }
+        Ok(BrokerHealth {
+            brokers: 1,
+            partitions: 1,
+            leaderless_partitions: 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_broker_health() {
+        assert!(BrokerHealth::from_metadata(3, [1, 2, 3]).is_healthy());
+
+        let leaderless = BrokerHealth::from_metadata(3, [1, -1, 3]);
+        assert_eq!(leaderless.partitions, 3);
+        assert_eq!(leaderless.leaderless_partitions, 1);
+        assert!(!leaderless.is_healthy());
+
+        let missing_topic = BrokerHealth::from_metadata(3, []);
+        assert_eq!(missing_topic.partitions, 0);
+        assert!(!missing_topic.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_publisher_records_messages() {
+        let publisher = Arc::new(InMemoryPublisher::new("order-events"));
+        let sink: Arc<dyn Publisher> = publisher.clone();
+        let key = Uuid::new_v4();
+
+        sink.publish(key, &serde_json::json!({ "event_type": "OrderCreated" }))
+            .await
+            .unwrap();
+        assert_eq!(
+            publisher.messages(),
+            [PublishedMessage {
+                key,
+                payload: serde_json::json!({ "event_type": "OrderCreated" }),
+            }]
+        );
+
+        publisher.set_unavailable(true);
+        assert!(sink.publish(key, &"late").await.is_err());
+        assert!(sink.healthcheck().await.is_err());
+        assert_eq!(publisher.messages().len(), 1);
+    }
+}
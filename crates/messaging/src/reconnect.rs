@@ -0,0 +1,154 @@
+#[cfg(feature = "kafka")]
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Shared flag reporting whether a consumer is currently able to talk to Kafka
+///
+/// Clones share the same state, so the consumer loop can update it while a
+/// readiness probe reads it. When a marker file is configured it exists exactly
+/// while the consumer is healthy, for exec-style probes (`test -f <path>`).
+#[derive(Debug, Clone, Default)]
+pub struct ConsumerHealth {
+    healthy: Arc<AtomicBool>,
+    marker_file: Option<Arc<PathBuf>>,
+}
+
+impl ConsumerHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mirror the health state into a file (created when healthy, removed otherwise)
+    pub fn with_marker_file(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        // A file left behind by a previous run must not report readiness
+        let _ = std::fs::remove_file(&path);
+        self.marker_file = Some(Arc::new(path));
+        self
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::SeqCst)
+    }
+
+    pub fn set_healthy(&self, healthy: bool) {
+        if self.healthy.swap(healthy, Ordering::SeqCst) == healthy {
+            return;
+        }
+
+        if healthy {
+            info!("Kafka consumer is healthy");
+        } else {
+            warn!("Kafka consumer is unhealthy");
+        }
+
+        if let Some(path) = &self.marker_file {
+            let result = if healthy {
+                std::fs::write(path.as_path(), b"ok")
+            } else {
+                std::fs::remove_file(path.as_path())
+            };
+            if let Err(e) = result {
+                warn!("Failed to update readiness file {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Exponential delay between attempts to reach Kafka after consecutive errors
+#[derive(Debug, Clone)]
+pub struct ReconnectBackoff {
+    initial_delay: Duration,
+    max_delay: Duration,
+    failures: u32,
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(500), Duration::from_secs(30))
+    }
+}
+
+impl ReconnectBackoff {
+    pub fn new(initial_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            initial_delay,
+            max_delay,
+            failures: 0,
+        }
+    }
+
+    /// Record a failure and return how long to wait before the next attempt
+    pub fn next_delay(&mut self) -> Duration {
+        let exponent = self.failures.min(16);
+        self.failures = self.failures.saturating_add(1);
+        self.initial_delay
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_delay)
+    }
+
+    /// Number of consecutive failures since the last success
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+/// Whether the error leaves the client instance unusable, so it must be recreated
+#[cfg(feature = "kafka")]
+pub fn is_fatal(error: &KafkaError) -> bool {
+    matches!(error, KafkaError::MessageConsumptionFatal(_))
+        || error.rdkafka_error_code() == Some(RDKafkaErrorCode::Fatal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_until_capped_and_resets() {
+        let mut backoff = ReconnectBackoff::new(Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(backoff.next_delay(), Duration::from_millis(100));
+        assert_eq!(backoff.next_delay(), Duration::from_millis(200));
+        assert_eq!(backoff.next_delay(), Duration::from_millis(400));
+        for _ in 0..40 {
+            assert!(backoff.next_delay() <= Duration::from_secs(1));
+        }
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+
+        backoff.reset();
+        assert_eq!(backoff.failures(), 0);
+        assert_eq!(backoff.next_delay(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_health_is_shared_and_mirrored_to_marker_file() {
+        let path = std::env::temp_dir().join(format!("consumer-ready-{}", uuid::Uuid::new_v4()));
+        let health = ConsumerHealth::new().with_marker_file(&path);
+        let probe = health.clone();
+        assert!(!probe.is_healthy());
+        assert!(!path.exists());
+
+        health.set_healthy(true);
+        assert!(probe.is_healthy());
+        assert!(path.exists());
+
+        health.set_healthy(false);
+        assert!(!probe.is_healthy());
+        assert!(!path.exists());
+    }
+
+    #[cfg(feature = "kafka")]
+    #[test]
+    fn test_fatal_error_detection() {
+        assert!(is_fatal(&KafkaError::MessageConsumptionFatal(RDKafkaErrorCode::Fatal)));
+        assert!(!is_fatal(&KafkaError::MessageConsumption(RDKafkaErrorCode::AllBrokersDown)));
+    }
+}
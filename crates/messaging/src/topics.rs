@@ -0,0 +1,574 @@
+use rdkafka::admin::{
+    AdminClient, AdminOptions, AlterConfig, ConfigEntry, ConfigSource, NewTopic,
+    ResourceSpecifier, TopicReplication,
+};
+use common::preflight::{Preflight, Severity};
+use rdkafka::client::DefaultClientContext;
+use rdkafka::config::ClientConfig;
+use rdkafka::error::RDKafkaErrorCode;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{info, warn};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TopicError {
+    #[error("Failed to create Kafka admin client: {0}")]
+    AdminCreation(String),
+
+    #[error("Kafka admin request failed: {0}")]
+    Admin(String),
+
+    #[error("Failed to create topic '{topic}': {reason}")]
+    CreateFailed { topic: String, reason: String },
+
+    /// The topic exists with a layout that cannot be changed in place
+    #[error("Topic '{topic}' does not match its spec: {reason}")]
+    Mismatch { topic: String, reason: String },
+}
+
+/// What the broker does with old segments of a topic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupPolicy {
+    /// Drop segments older than the retention
+    Delete,
+    /// Keep the latest message per key
+    Compact,
+    /// Keep the latest message per key, and drop even those past the retention
+    CompactDelete,
+}
+
+impl CleanupPolicy {
+    fn as_config(&self) -> &'static str {
+        match self {
+            CleanupPolicy::Delete => "delete",
+            CleanupPolicy::Compact => "compact",
+            CleanupPolicy::CompactDelete => "compact,delete",
+        }
+    }
+}
+
+/// Desired layout and settings of a topic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicSpec {
+    pub name: String,
+    pub partitions: i32,
+    pub replication_factor: i32,
+    /// `None` keeps messages forever
+    pub retention: Option<Duration>,
+    pub cleanup_policy: CleanupPolicy,
+}
+
+impl TopicSpec {
+    /// A deleting topic with 6 partitions, a replica per partition and 7 days retention
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            partitions: 6,
+            replication_factor: 1,
+            retention: Some(Duration::from_secs(7 * 24 * 3600)),
+            cleanup_policy: CleanupPolicy::Delete,
+        }
+    }
+
+    pub fn with_partitions(mut self, partitions: i32) -> Self {
+        self.partitions = partitions.max(1);
+        self
+    }
+
+    pub fn with_replication_factor(mut self, replication_factor: i32) -> Self {
+        self.replication_factor = replication_factor.max(1);
+        self
+    }
+
+    pub fn with_retention(mut self, retention: Option<Duration>) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    pub fn with_cleanup_policy(mut self, cleanup_policy: CleanupPolicy) -> Self {
+        self.cleanup_policy = cleanup_policy;
+        self
+    }
+
+    /// Topic-level configs this spec manages, as the broker reports them
+    pub fn configs(&self) -> Vec<(&'static str, String)> {
+        let retention_ms = self
+            .retention
+            .map(|retention| retention.as_millis().to_string())
+            .unwrap_or_else(|| "-1".to_string());
+
+        vec![
+            ("cleanup.policy", self.cleanup_policy.as_config().to_string()),
+            ("retention.ms", retention_ms),
+        ]
+    }
+
+    /// Check an existing topic's partition and replica counts against the spec
+    ///
+    /// More partitions than asked for is fine. Fewer is an error rather than
+    /// something to fix: adding partitions moves keys, breaking per-aggregate order.
+    pub fn check_layout(&self, partitions: i32, replication_factor: i32) -> Result<(), TopicError> {
+        let mismatch = |reason: String| TopicError::Mismatch {
+            topic: self.name.clone(),
+            reason,
+        };
+
+        if partitions < self.partitions {
+            return Err(mismatch(format!(
+                "{} partitions, expected at least {}",
+                partitions, self.partitions
+            )));
+        }
+        if replication_factor < self.replication_factor {
+            return Err(mismatch(format!(
+                "replication factor {}, expected at least {}",
+                replication_factor, self.replication_factor
+            )));
+        }
+        Ok(())
+    }
+
+    /// Configs to send in a non-incremental alter of the topic described by `entries`
+    ///
+    /// Such an alter resets every topic config it leaves out, so overrides set on the
+    /// topic outside the spec are sent with their current values alongside the managed
+    /// ones. A sensitive override is described without its value and cannot be kept.
+    fn alter_configs(&self, entries: &[ConfigEntry]) -> Result<Vec<(String, String)>, TopicError> {
+        let managed = self.configs();
+        let mut configs: Vec<(String, String)> = managed
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+
+        for entry in entries {
+            if entry.source != ConfigSource::DynamicTopic
+                || managed.iter().any(|(name, _)| *name == entry.name)
+            {
+                continue;
+            }
+            let value = entry.value.clone().ok_or_else(|| TopicError::Mismatch {
+                topic: self.name.clone(),
+                reason: format!("cannot keep the value of sensitive config {}", entry.name),
+            })?;
+            configs.push((entry.name.clone(), value));
+        }
+        Ok(configs)
+    }
+
+    /// Managed configs whose current value differs from the spec, as (name, current, desired)
+    pub fn config_drift(
+        &self,
+        current: &HashMap<String, Option<String>>,
+    ) -> Vec<(&'static str, Option<String>, String)> {
+        self.configs()
+            .into_iter()
+            .filter_map(|(name, desired)| {
+                let value = current.get(name).cloned().flatten();
+                (value.as_deref() != Some(desired.as_str())).then_some((name, value, desired))
+            })
+            .collect()
+    }
+}
+
+/// Defaults for the topics a service creates, read from the environment
+#[derive(Debug, Clone)]
+pub struct TopicSettings {
+    pub partitions: i32,
+    pub replication_factor: i32,
+    pub retention: Option<Duration>,
+    /// Topics that keep the latest message per key instead of expiring by age
+    pub compacted: Vec<String>,
+}
+
+impl TopicSettings {
+    /// `KAFKA_TOPIC_PARTITIONS`, `KAFKA_TOPIC_REPLICATION_FACTOR`,
+    /// `KAFKA_TOPIC_RETENTION_HOURS` (-1 keeps forever) and `KAFKA_COMPACTED_TOPICS`
+    pub fn from_env() -> Self {
+        let var = |name: &str, default: i64| {
+            std::env::var(name)
+                .unwrap_or_else(|_| default.to_string())
+                .parse()
+                .unwrap_or(default)
+        };
+        let retention_hours = var("KAFKA_TOPIC_RETENTION_HOURS", 168);
+
+        Self {
+            partitions: var("KAFKA_TOPIC_PARTITIONS", 6) as i32,
+            replication_factor: var("KAFKA_TOPIC_REPLICATION_FACTOR", 1) as i32,
+            retention: (retention_hours >= 0)
+                .then(|| Duration::from_secs(retention_hours as u64 * 3600)),
+            compacted: std::env::var("KAFKA_COMPACTED_TOPICS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|topic| !topic.is_empty())
+                .map(String::from)
+                .collect(),
+        }
+    }
+
+    /// Spec for `name` with these settings
+    pub fn spec(&self, name: &str) -> TopicSpec {
+        let cleanup_policy = if self.compacted.iter().any(|topic| topic == name) {
+            CleanupPolicy::Compact
+        } else {
+            CleanupPolicy::Delete
+        };
+
+        TopicSpec::new(name)
+            .with_partitions(self.partitions)
+            .with_replication_factor(self.replication_factor)
+            .with_retention(self.retention)
+            .with_cleanup_policy(cleanup_policy)
+    }
+}
+
+/// Creates and verifies topics at startup instead of relying on broker auto-create
+pub struct TopicManager {
+    admin: Arc<AdminClient<DefaultClientContext>>,
+    timeout: Duration,
+}
+
+impl TopicManager {
+    pub fn new(brokers: &str) -> Result<Self, TopicError> {
+        let admin = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .create()
+            .map_err(|e| TopicError::AdminCreation(e.to_string()))?;
+
+        Ok(Self {
+            admin: Arc::new(admin),
+            timeout: Duration::from_secs(10),
+        })
+    }
+
+    /// Partition count and smallest replica count of every topic the brokers know
+    ///
+    /// librdkafka blocks the calling thread while it waits for metadata, so the
+    /// request runs on the blocking pool.
+    async fn topic_layouts(&self) -> Result<HashMap<String, (i32, i32)>, TopicError> {
+        let admin = self.admin.clone();
+        let timeout = self.timeout;
+        tokio::task::spawn_blocking(move || {
+            // Metadata for all topics, since asking for one may auto-create it
+            let metadata = admin
+                .inner()
+                .fetch_metadata(None, timeout)
+                .map_err(|e| TopicError::Admin(e.to_string()))?;
+            Ok(metadata
+                .topics()
+                .iter()
+                .map(|topic| {
+                    let replicas = topic
+                        .partitions()
+                        .iter()
+                        .map(|partition| partition.replicas().len() as i32)
+                        .min()
+                        .unwrap_or(0);
+                    (topic.name().to_string(), (topic.partitions().len() as i32, replicas))
+                })
+                .collect())
+        })
+        .await
+        .map_err(|e| TopicError::Admin(e.to_string()))?
+    }
+
+    /// Names from `topics` the brokers don't know about
+    pub async fn missing_topics(&self, topics: &[&str]) -> Result<Vec<String>, TopicError> {
+        let existing = self.topic_layouts().await?;
+
+        Ok(topics
+            .iter()
+            .filter(|topic| !existing.contains_key(**topic))
+            .map(|topic| topic.to_string())
+            .collect())
+    }
+
+    /// Create missing topics and bring the retention and cleanup policy of existing
+    /// ones in line with their spec
+    ///
+    /// Existing topics with fewer partitions or replicas than their spec are left
+    /// alone and reported as a [`TopicError::Mismatch`], after the rest are handled.
+    pub async fn ensure(&self, specs: &[TopicSpec]) -> Result<(), TopicError> {
+        let existing = self.topic_layouts().await?;
+
+        let (present, missing): (Vec<&TopicSpec>, Vec<&TopicSpec>) = specs
+            .iter()
+            .partition(|spec| existing.contains_key(spec.name.as_str()));
+
+        self.create(&missing).await?;
+
+        // Every topic is checked; the first problem found is returned
+        let mut first_error = None;
+        for spec in present {
+            let (partitions, replicas) = existing[spec.name.as_str()];
+            let result = match spec.check_layout(partitions, replicas) {
+                Ok(()) => self.sync_configs(spec).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!("{}", e);
+                first_error.get_or_insert(e);
+            }
+        }
+
+        first_error.map_or(Ok(()), Err)
+    }
+
+    async fn create(&self, specs: &[&TopicSpec]) -> Result<(), TopicError> {
+        if specs.is_empty() {
+            return Ok(());
+        }
+
+        let configs: Vec<Vec<(&str, String)>> = specs.iter().map(|spec| spec.configs()).collect();
+        let new_topics: Vec<NewTopic> = specs
+            .iter()
+            .zip(&configs)
+            .map(|(spec, configs)| {
+                configs.iter().fold(
+                    NewTopic::new(
+                        &spec.name,
+                        spec.partitions,
+                        TopicReplication::Fixed(spec.replication_factor),
+                    ),
+                    |topic, (key, value)| topic.set(key, value),
+                )
+            })
+            .collect();
+
+        let results = self
+            .admin
+            .create_topics(&new_topics, &self.options())
+            .await
+            .map_err(|e| TopicError::Admin(e.to_string()))?;
+
+        for result in results {
+            match result {
+                Ok(topic) => info!("Created Kafka topic '{}'", topic),
+                // Another instance got there first
+                Err((topic, RDKafkaErrorCode::TopicAlreadyExists)) => {
+                    info!("Kafka topic '{}' already exists", topic)
+                }
+                Err((topic, code)) => {
+                    return Err(TopicError::CreateFailed {
+                        topic,
+                        reason: code.to_string(),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn sync_configs(&self, spec: &TopicSpec) -> Result<(), TopicError> {
+        let resource = ResourceSpecifier::Topic(&spec.name);
+        let described = self
+            .admin
+            .describe_configs([&resource], &self.options())
+            .await
+            .map_err(|e| TopicError::Admin(e.to_string()))?;
+
+        // Altering without knowing the current configs would reset the unmanaged ones
+        let entries = described
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                TopicError::Admin(format!("no configs described for '{}'", spec.name))
+            })?
+            .map_err(|code| {
+                TopicError::Admin(format!(
+                    "failed to describe configs of '{}': {}",
+                    spec.name, code
+                ))
+            })?
+            .entries;
+        let current: HashMap<String, Option<String>> = entries
+            .iter()
+            .map(|entry| (entry.name.clone(), entry.value.clone()))
+            .collect();
+
+        let drift = spec.config_drift(&current);
+        if drift.is_empty() {
+            info!("Kafka topic '{}' matches its spec", spec.name);
+            return Ok(());
+        }
+        for (name, current, desired) in &drift {
+            warn!(
+                "Kafka topic '{}' has {}={}, setting it to {}",
+                spec.name,
+                name,
+                current.as_deref().unwrap_or("unset"),
+                desired
+            );
+        }
+
+        // A non-incremental alter, so every managed config and unmanaged topic override
+        // is sent, not just the drifted ones
+        let configs = spec.alter_configs(&entries)?;
+        let alter = configs
+            .iter()
+            .fold(AlterConfig::new(resource), |alter, (key, value)| {
+                alter.set(key, value)
+            });
+        let results = self
+            .admin
+            .alter_configs([&alter], &self.options())
+            .await
+            .map_err(|e| TopicError::Admin(e.to_string()))?;
+
+        for result in results {
+            if let Err((_, code)) = result {
+                return Err(TopicError::Admin(format!(
+                    "failed to update configs of '{}': {}",
+                    spec.name, code
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn options(&self) -> AdminOptions {
+        AdminOptions::new().request_timeout(Some(self.timeout))
+    }
+}
+
+/// Preflight check that the brokers are reachable and know `topics`
+///
+/// Missing topics are only a warning when the service creates them at startup.
+pub async fn preflight_topics(
+    preflight: &mut Preflight,
+    brokers: &str,
+    topics: &[&str],
+    unreachable: Severity,
+    creates_topics: bool,
+) {
+    let missing = async { TopicManager::new(brokers)?.missing_topics(topics).await };
+    let missing = match tokio::time::timeout(preflight.timeout(), missing).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err(format!("timed out after {}ms", preflight.timeout().as_millis())),
+    };
+
+    match missing {
+        Ok(missing) => {
+            preflight.record("kafka", unreachable, Ok(format!("brokers {} reachable", brokers)));
+            let severity = if creates_topics { Severity::Warning } else { Severity::Fatal };
+            let result = if missing.is_empty() {
+                Ok(format!("{} topics present", topics.len()))
+            } else if creates_topics {
+                Err(format!("missing {}, creating them at startup", missing.join(", ")))
+            } else {
+                Err(format!("missing {}", missing.join(", ")))
+            };
+            preflight.record("kafka topics", severity, result);
+        }
+        Err(e) => preflight.record("kafka", unreachable, Err(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_configs() {
+        let spec = TopicSpec::new("order-events")
+            .with_retention(Some(Duration::from_secs(3600)))
+            .with_cleanup_policy(CleanupPolicy::CompactDelete);
+
+        assert_eq!(
+            spec.configs(),
+            vec![
+                ("cleanup.policy", "compact,delete".to_string()),
+                ("retention.ms", "3600000".to_string()),
+            ]
+        );
+
+        let forever = TopicSpec::new("order-events").with_retention(None);
+        assert_eq!(forever.configs()[1], ("retention.ms", "-1".to_string()));
+    }
+
+    #[test]
+    fn test_check_layout() {
+        let spec = TopicSpec::new("order-events")
+            .with_partitions(6)
+            .with_replication_factor(3);
+
+        assert!(spec.check_layout(6, 3).is_ok());
+        assert!(spec.check_layout(12, 3).is_ok());
+        assert!(matches!(
+            spec.check_layout(3, 3),
+            Err(TopicError::Mismatch { .. })
+        ));
+        assert!(matches!(
+            spec.check_layout(6, 1),
+            Err(TopicError::Mismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_config_drift() {
+        let spec = TopicSpec::new("order-events");
+        let mut current = HashMap::new();
+        current.insert("cleanup.policy".to_string(), Some("delete".to_string()));
+        current.insert("retention.ms".to_string(), Some("604800000".to_string()));
+        assert!(spec.config_drift(&current).is_empty());
+
+        current.insert("retention.ms".to_string(), Some("-1".to_string()));
+        assert_eq!(
+            spec.config_drift(&current),
+            vec![("retention.ms", Some("-1".to_string()), "604800000".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_alter_configs_keep_unmanaged_overrides() {
+        let entry = |name: &str, value: Option<&str>, source| ConfigEntry {
+            name: name.to_string(),
+            value: value.map(str::to_string),
+            is_default: source == ConfigSource::Default,
+            source,
+            is_read_only: false,
+            is_sensitive: value.is_none(),
+        };
+        let spec = TopicSpec::new("order-events");
+        let mut entries = vec![
+            entry("retention.ms", Some("-1"), ConfigSource::DynamicTopic),
+            entry("max.message.bytes", Some("2097152"), ConfigSource::DynamicTopic),
+            entry("min.insync.replicas", Some("2"), ConfigSource::StaticBroker),
+            entry("segment.ms", Some("604800000"), ConfigSource::Default),
+        ];
+
+        assert_eq!(
+            spec.alter_configs(&entries).unwrap(),
+            vec![
+                ("cleanup.policy".to_string(), "delete".to_string()),
+                ("retention.ms".to_string(), "604800000".to_string()),
+                ("max.message.bytes".to_string(), "2097152".to_string()),
+            ]
+        );
+
+        entries.push(entry("sasl.secret", None, ConfigSource::DynamicTopic));
+        assert!(matches!(
+            spec.alter_configs(&entries),
+            Err(TopicError::Mismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_settings_spec_compacts_listed_topics() {
+        let settings = TopicSettings {
+            partitions: 3,
+            replication_factor: 2,
+            retention: None,
+            compacted: vec!["customer-events".to_string()],
+        };
+
+        let compacted = settings.spec("customer-events");
+        assert_eq!(compacted.cleanup_policy, CleanupPolicy::Compact);
+        assert_eq!(compacted.partitions, 3);
+        assert_eq!(compacted.replication_factor, 2);
+        assert_eq!(settings.spec("order-events").cleanup_policy, CleanupPolicy::Delete);
+    }
+}
@@ -0,0 +1,401 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, PgConnection, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::ReadModelError;
+
+/// A stored event, as read from the event store by backfills and shadow rebuilds
+#[derive(Debug, Clone, FromRow)]
+pub struct BackfillEvent {
+    pub event_id: Uuid,
+    pub aggregate_id: Uuid,
+    pub event_type: String,
+    pub payload: Value,
+    /// Aggregate version the event was stored at
+    pub version: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Events of the given types after `after`, in `(created_at, event_id)` order,
+/// stopping at events created after `until` if given
+pub(crate) async fn load_events_after(
+    conn: &mut PgConnection,
+    event_types: &[String],
+    after: Option<(DateTime<Utc>, Uuid)>,
+    until: Option<DateTime<Utc>>,
+    limit: i64,
+) -> Result<Vec<BackfillEvent>, ReadModelError> {
+    let (after_created_at, after_event_id) = after.unzip();
+    let events = sqlx::query_as::<_, BackfillEvent>(
+        r#"
+        SELECT event_id, aggregate_id, event_type, payload, version, created_at
+        FROM events
+        WHERE event_type = ANY($1)
+          AND ($2::timestamptz IS NULL OR (created_at, event_id) > ($2, $3::uuid))
+          AND ($4::timestamptz IS NULL OR created_at <= $4)
+        ORDER BY created_at, event_id
+        LIMIT $5
+        "#,
+    )
+    .bind(event_types)
+    .bind(after_created_at)
+    .bind(after_event_id)
+    .bind(until)
+    .bind(limit)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(events)
+}
+
+/// A new version of a read model view, filled in from the event store
+///
+/// Typical use is a new column: `prepare` adds it, the live projection is changed to
+/// write it for new events, and `apply` fills it in for everything that happened
+/// before. `apply` must be idempotent, because events the live projection already
+/// handled may be applied again.
+#[async_trait]
+pub trait Backfill: Send + Sync {
+    /// Unique name progress is tracked under, e.g. `order_views.item_count`
+    fn name(&self) -> &str;
+
+    /// Read model view the new version belongs to, e.g. `order_views`
+    fn view(&self) -> &str;
+
+    /// View version made live by the cut-over
+    fn target_version(&self) -> i32;
+
+    /// Event types read from the event store
+    fn event_types(&self) -> Vec<String>;
+
+    /// Create the new column or table; must be safe to run again after a restart
+    async fn prepare(&self, conn: &mut PgConnection) -> Result<(), ReadModelError>;
+
+    /// Apply one historical event to the new version
+    async fn apply(
+        &self,
+        conn: &mut PgConnection,
+        event: &BackfillEvent,
+    ) -> Result<(), ReadModelError>;
+
+    /// Make the new version live, e.g. add constraints or replace a view
+    ///
+    /// Runs in the same transaction that processes the last events and records the
+    /// new view version, so readers see either the old version or the complete new one.
+    async fn cut_over(&self, _conn: &mut PgConnection) -> Result<(), ReadModelError> {
+        Ok(())
+    }
+}
+
+/// Stored progress of a backfill
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BackfillProgress {
+    pub name: String,
+    pub view_name: String,
+    pub target_version: i32,
+    /// One of `RUNNING`, `COMPLETED` or `FAILED`
+    pub status: String,
+    /// Position of the last event applied, in `(created_at, event_id)` order
+    pub last_created_at: Option<DateTime<Utc>>,
+    pub last_event_id: Option<Uuid>,
+    pub events_processed: i64,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl BackfillProgress {
+    pub fn is_completed(&self) -> bool {
+        self.status == "COMPLETED"
+    }
+
+    fn cursor(&self) -> Option<(DateTime<Utc>, Uuid)> {
+        self.last_created_at.zip(self.last_event_id)
+    }
+
+    fn advance(&mut self, events: &[BackfillEvent]) {
+        if let Some(last) = events.last() {
+            self.last_created_at = Some(last.created_at);
+            self.last_event_id = Some(last.event_id);
+            self.events_processed += events.len() as i64;
+        }
+    }
+}
+
+/// Runs backfills in batches, resuming from the stored progress after a restart
+///
+/// Each batch is applied and its progress recorded in one transaction, so the live
+/// view keeps serving while the backfill runs. Once a batch comes back short, the
+/// remaining events are applied, `Backfill::cut_over` runs and the view version in
+/// `read_model_versions` is bumped, all in a single transaction.
+pub struct BackfillRunner {
+    pool: PgPool,
+    batch_size: i64,
+}
+
+impl BackfillRunner {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            batch_size: 1000,
+        }
+    }
+
+    /// Events applied per transaction
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Current version of a view, 1 if it was never cut over
+    pub async fn view_version(&self, view: &str) -> Result<i32, ReadModelError> {
+        let version: Option<i32> =
+            sqlx::query_scalar("SELECT version FROM read_model_versions WHERE view_name = $1")
+                .bind(view)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(version.unwrap_or(1))
+    }
+
+    /// Progress of a backfill, if it was ever started
+    pub async fn progress(&self, name: &str) -> Result<Option<BackfillProgress>, ReadModelError> {
+        let progress = sqlx::query_as::<_, BackfillProgress>(
+            r#"
+            SELECT
+                name, view_name, target_version, status, last_created_at, last_event_id,
+                events_processed, error, started_at, updated_at, completed_at
+            FROM read_model_backfills
+            WHERE name = $1
+            "#,
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(progress)
+    }
+
+    /// Run a backfill to completion and cut over to its view version
+    ///
+    /// Returns straight away if the backfill already completed.
+    pub async fn run(&self, backfill: &dyn Backfill) -> Result<BackfillProgress, ReadModelError> {
+        let mut progress = self.start(backfill).await?;
+        if progress.is_completed() {
+            info!("Backfill {} already completed", backfill.name());
+            return Ok(progress);
+        }
+
+        match self.backfill(backfill, &mut progress).await {
+            Ok(()) => Ok(progress),
+            Err(e) => {
+                warn!(
+                    "Backfill {} failed after {} events: {}",
+                    backfill.name(),
+                    progress.events_processed,
+                    e
+                );
+                self.record_failure(backfill.name(), &e.to_string()).await?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn start(&self, backfill: &dyn Backfill) -> Result<BackfillProgress, ReadModelError> {
+        let mut tx = self.pool.begin().await?;
+        backfill.prepare(&mut tx).await?;
+
+        let progress = sqlx::query_as::<_, BackfillProgress>(
+            r#"
+            INSERT INTO read_model_backfills (name, view_name, target_version, status)
+            VALUES ($1, $2, $3, 'RUNNING')
+            ON CONFLICT (name) DO UPDATE
+            SET status = CASE
+                    WHEN read_model_backfills.status = 'COMPLETED' THEN 'COMPLETED'
+                    ELSE 'RUNNING'
+                END,
+                error = NULL,
+                updated_at = NOW()
+            RETURNING
+                name, view_name, target_version, status, last_created_at, last_event_id,
+                events_processed, error, started_at, updated_at, completed_at
+            "#,
+        )
+        .bind(backfill.name())
+        .bind(backfill.view())
+        .bind(backfill.target_version())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(progress)
+    }
+
+    async fn backfill(
+        &self,
+        backfill: &dyn Backfill,
+        progress: &mut BackfillProgress,
+    ) -> Result<(), ReadModelError> {
+        let event_types = backfill.event_types();
+        info!(
+            "Running backfill {} for {} v{} from {} processed events",
+            backfill.name(),
+            backfill.view(),
+            backfill.target_version(),
+            progress.events_processed
+        );
+
+        loop {
+            let mut tx = self.pool.begin().await?;
+            let events = load_events_after(
+                &mut tx,
+                &event_types,
+                progress.cursor(),
+                None,
+                self.batch_size,
+            )
+            .await?;
+            for event in &events {
+                backfill.apply(&mut tx, event).await?;
+            }
+            progress.advance(&events);
+
+            let finished = (events.len() as i64) < self.batch_size;
+            if finished {
+                backfill.cut_over(&mut tx).await?;
+                sqlx::query(
+                    r#"
+                    INSERT INTO read_model_versions (view_name, version, backfill_name)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (view_name) DO UPDATE
+                    SET version = EXCLUDED.version,
+                        backfill_name = EXCLUDED.backfill_name,
+                        cut_over_at = NOW()
+                    "#,
+                )
+                .bind(backfill.view())
+                .bind(backfill.target_version())
+                .bind(backfill.name())
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            let stored = self.record_progress(&mut tx, progress, finished).await?;
+            tx.commit().await?;
+            *progress = stored;
+
+            if finished {
+                info!(
+                    "Backfill {} completed after {} events; {} is now v{}",
+                    backfill.name(),
+                    progress.events_processed,
+                    backfill.view(),
+                    backfill.target_version()
+                );
+                return Ok(());
+            }
+            info!(
+                "Backfill {}: {} events processed",
+                backfill.name(),
+                progress.events_processed
+            );
+        }
+    }
+
+    async fn record_progress(
+        &self,
+        conn: &mut PgConnection,
+        progress: &BackfillProgress,
+        completed: bool,
+    ) -> Result<BackfillProgress, ReadModelError> {
+        let stored = sqlx::query_as::<_, BackfillProgress>(
+            r#"
+            UPDATE read_model_backfills
+            SET last_created_at = $2,
+                last_event_id = $3,
+                events_processed = $4,
+                status = CASE WHEN $5 THEN 'COMPLETED' ELSE status END,
+                completed_at = CASE WHEN $5 THEN NOW() ELSE completed_at END,
+                updated_at = NOW()
+            WHERE name = $1
+            RETURNING
+                name, view_name, target_version, status, last_created_at, last_event_id,
+                events_processed, error, started_at, updated_at, completed_at
+            "#,
+        )
+        .bind(&progress.name)
+        .bind(progress.last_created_at)
+        .bind(progress.last_event_id)
+        .bind(progress.events_processed)
+        .bind(completed)
+        .fetch_one(conn)
+        .await?;
+
+        Ok(stored)
+    }
+
+    async fn record_failure(&self, name: &str, error: &str) -> Result<(), ReadModelError> {
+        sqlx::query(
+            r#"
+            UPDATE read_model_backfills
+            SET status = 'FAILED', error = $2, updated_at = NOW()
+            WHERE name = $1
+            "#,
+        )
+        .bind(name)
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(created_at: DateTime<Utc>) -> BackfillEvent {
+        BackfillEvent {
+            event_id: Uuid::new_v4(),
+            aggregate_id: Uuid::new_v4(),
+            event_type: "OrderCreated".to_string(),
+            payload: serde_json::json!({}),
+            version: 1,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_progress_advances_to_last_event() {
+        let now = Utc::now();
+        let mut progress = BackfillProgress {
+            name: "order_views.item_count".to_string(),
+            view_name: "order_views".to_string(),
+            target_version: 2,
+            status: "RUNNING".to_string(),
+            last_created_at: None,
+            last_event_id: None,
+            events_processed: 0,
+            error: None,
+            started_at: now,
+            updated_at: now,
+            completed_at: None,
+        };
+        assert_eq!(progress.cursor(), None);
+
+        progress.advance(&[]);
+        assert_eq!(progress.cursor(), None);
+
+        let batch = vec![event(now), event(now + chrono::Duration::seconds(1))];
+        progress.advance(&batch);
+        assert_eq!(progress.events_processed, 2);
+        assert_eq!(progress.cursor(), Some((batch[1].created_at, batch[1].event_id)));
+        assert!(!progress.is_completed());
+    }
+}
@@ -1,3 +1,32 @@
+#[cfg(feature = "redis")]
 pub mod redis_cache;
 
+#[cfg(feature = "redis")]
 pub use redis_cache::RedisCache;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::OrderView;
+
+/// Where [`CachedOrderViewRepository`](crate::CachedOrderViewRepository) keeps order views
+///
+/// Cache failures are not errors: a failed read is a miss and a failed write is logged.
+#[async_trait]
+pub trait OrderViewCache: Send + Sync {
+    async fn get_order(&self, order_id: Uuid) -> Option<OrderView>;
+
+    async fn set_order(&self, order: &OrderView);
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl OrderViewCache for RedisCache {
+    async fn get_order(&self, order_id: Uuid) -> Option<OrderView> {
+        self.get(&order_id).await
+    }
+
+    async fn set_order(&self, order: &OrderView) {
+        self.set(&order.order_id, order).await;
+    }
+}
@@ -1,4 +1,4 @@
-use redis::aio::ConnectionManager;
+use common::redis_connection::{RedisConfig, RedisConnection};
 use redis::{AsyncCommands, RedisError};
 use serde::{de::DeserializeOwned, Serialize};
 use tracing::{debug, error, info, warn};
@@ -8,17 +8,24 @@ use crate::ReadModelError;
 
 /// Redis cache for order views
 pub struct RedisCache {
-    conn: ConnectionManager,
+    conn: RedisConnection,
     ttl_seconds: usize,
 }
 
 impl RedisCache {
-    /// Create new Redis cache
+    /// Create new Redis cache on a single node
     pub async fn new(redis_url: &str, ttl_seconds: usize) -> Result<Self, ReadModelError> {
-        let client = redis::Client::open(redis_url)
+        Self::connect(&RedisConfig::standalone(redis_url), ttl_seconds).await
+    }
+
+    /// Create a Redis cache on a Redis Cluster, Sentinel or single node
+    pub async fn connect(config: &RedisConfig, ttl_seconds: usize) -> Result<Self, ReadModelError> {
+        config
+            .validate()
             .map_err(|e| ReadModelError::CacheError(format!("Failed to create Redis client: {}", e)))?;
 
-        let conn = ConnectionManager::new(client)
+        let conn = config
+            .connect()
             .await
             .map_err(|e| ReadModelError::CacheError(format!("Failed to connect to Redis: {}", e)))?;
 
@@ -28,9 +35,38 @@ impl RedisCache {
 
     /// Get value from cache
     pub async fn get<T: DeserializeOwned>(&self, key: &Uuid) -> Option<T> {
-        let cache_key = format!("order:{}", key);
+        self.get_key(&format!("order:{}", key)).await
+    }
 
-        match self.conn.clone().get::<_, String>(&cache_key).await {
+    /// Set value in cache
+    pub async fn set<T: Serialize>(&self, key: &Uuid, value: &T) {
+        self.set_key(&format!("order:{}", key), value, self.ttl_seconds).await;
+    }
+
+    /// Get the long-lived copy kept for when the database is too slow to answer
+    ///
+    /// The copy may be older than anything `get` returns.
+    pub async fn get_fallback<T: DeserializeOwned>(&self, key: &Uuid) -> Option<T> {
+        self.get_key(&format!("order:fallback:{}", key)).await
+    }
+
+    /// Keep a copy of a value for `ttl_seconds`, past the regular cache TTL
+    pub async fn set_fallback<T: Serialize>(&self, key: &Uuid, value: &T, ttl_seconds: usize) {
+        self.set_key(&format!("order:fallback:{}", key), value, ttl_seconds).await;
+    }
+
+    /// Get a computed metrics document, such as the business KPIs
+    pub async fn get_metrics<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        self.get_key(&format!("metrics:{}", name)).await
+    }
+
+    /// Cache a computed metrics document for `ttl_seconds`
+    pub async fn set_metrics<T: Serialize>(&self, name: &str, value: &T, ttl_seconds: usize) {
+        self.set_key(&format!("metrics:{}", name), value, ttl_seconds).await;
+    }
+
+    async fn get_key<T: DeserializeOwned>(&self, cache_key: &str) -> Option<T> {
+        match self.conn.clone().get::<_, String>(cache_key).await {
             Ok(value) => {
                 debug!("Cache hit for key: {}", cache_key);
                 match serde_json::from_str::<T>(&value) {
@@ -52,21 +88,18 @@ impl RedisCache {
         }
     }
 
-    /// Set value in cache
-    pub async fn set<T: Serialize>(&self, key: &Uuid, value: &T) {
-        let cache_key = format!("order:{}", key);
-
+    async fn set_key<T: Serialize>(&self, cache_key: &str, value: &T, ttl_seconds: usize) {
         match serde_json::to_string(value) {
             Ok(json) => {
                 let result: Result<(), RedisError> = self
                     .conn
                     .clone()
-                    .set_ex(&cache_key, json, self.ttl_seconds as u64)
+                    .set_ex(cache_key, json, ttl_seconds as u64)
                     .await;
 
                 match result {
                     Ok(_) => {
-                        debug!("Cached value for key: {} with TTL: {}s", cache_key, self.ttl_seconds);
+                        debug!("Cached value for key: {} with TTL: {}s", cache_key, ttl_seconds);
                     }
                     Err(e) => {
                         error!("Failed to set cache for key {}: {}", cache_key, e);
@@ -143,6 +176,24 @@ mod tests {
         assert!(cached.is_none());
     }
 
+    #[tokio::test]
+    #[ignore] // Requires Redis to be running
+    async fn test_fallback_outlives_delete() {
+        let cache = RedisCache::new("redis://localhost:6379", 300)
+            .await
+            .expect("Failed to connect to Redis");
+
+        let key = Uuid::new_v4();
+        let value = serde_json::json!({"test": "data"});
+
+        cache.set(&key, &value).await;
+        cache.set_fallback(&key, &value, 3600).await;
+        cache.delete(&key).await;
+
+        let fallback: Option<serde_json::Value> = cache.get_fallback(&key).await;
+        assert_eq!(fallback, Some(value));
+    }
+
     #[tokio::test]
     #[ignore] // Requires Redis to be running
     async fn test_ping() {
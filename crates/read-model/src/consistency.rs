@@ -0,0 +1,441 @@
+use chrono::{Duration, Utc};
+use common::metrics;
+use domain::aggregates::order::OrderAggregate;
+use domain::events::order_events::*;
+use serde::Serialize;
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::backfill::BackfillEvent;
+use crate::projections::OrderProjection;
+use crate::repositories::OrderView;
+use crate::ReadModelError;
+
+/// Field reported for an order whose events can't be replayed, so its view can't be checked
+const UNREADABLE_EVENTS: &str = "events";
+
+/// One field of an order view that disagrees with the order's events
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldDivergence {
+    /// Column name, `view` when the whole row is missing or should not exist, or
+    /// `events` when an event of the order can't be decoded
+    pub field: &'static str,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// An order whose view has drifted from its events
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderDivergence {
+    pub order_id: Uuid,
+    pub fields: Vec<FieldDivergence>,
+    pub repaired: bool,
+}
+
+/// Outcome of one consistency check
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConsistencyReport {
+    pub checked: usize,
+    /// Sampled orders left out because their latest event may still be in flight
+    pub skipped: usize,
+    pub divergences: Vec<OrderDivergence>,
+}
+
+/// Samples orders, replays their events in memory and compares the result to `order_views`
+///
+/// Detects silent projection drift: lost or misapplied events that leave a view
+/// looking plausible but wrong. Orders with events newer than the settle period are
+/// skipped, so ordinary projection lag is not reported. With repair enabled each
+/// divergent view is re-projected from its events.
+pub struct ConsistencyChecker {
+    pool: PgPool,
+    projection: OrderProjection,
+    sample_size: i64,
+    settle: Duration,
+    repair: bool,
+}
+
+impl ConsistencyChecker {
+    pub fn new(pool: PgPool, sample_size: i64) -> Self {
+        Self {
+            pool,
+            projection: OrderProjection::new(),
+            sample_size: sample_size.max(1),
+            settle: Duration::seconds(60),
+            repair: false,
+        }
+    }
+
+    /// Leave out orders with events newer than `settle`
+    pub fn with_settle_period(mut self, settle: Duration) -> Self {
+        self.settle = settle;
+        self
+    }
+
+    /// Re-project divergent views from their events
+    pub fn with_repair(mut self, repair: bool) -> Self {
+        self.repair = repair;
+        self
+    }
+
+    /// Check one random sample of orders
+    pub async fn check(&self) -> Result<ConsistencyReport, ReadModelError> {
+        // ORDER BY random() scans every OrderCreated, which is fine at checker intervals
+        let order_ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT aggregate_id
+            FROM events
+            WHERE event_type = 'OrderCreated'
+            ORDER BY random()
+            LIMIT $1
+            "#,
+        )
+        .bind(self.sample_size)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let settled_before = Utc::now() - self.settle;
+        let mut report = ConsistencyReport::default();
+        let mut divergent_fields = Vec::new();
+
+        for order_id in order_ids {
+            let events = self.load_events(order_id).await?;
+            if events
+                .last()
+                .is_none_or(|last| last.created_at > settled_before)
+            {
+                report.skipped += 1;
+                continue;
+            }
+            report.checked += 1;
+
+            // One order with an event that won't decode must not hide the rest
+            let expected = match derive_order(&events) {
+                Ok(expected) => expected,
+                Err(e) => {
+                    warn!("Order {} has events that cannot be replayed: {}", order_id, e);
+                    divergent_fields.push(UNREADABLE_EVENTS);
+                    report.divergences.push(OrderDivergence {
+                        order_id,
+                        fields: vec![FieldDivergence {
+                            field: UNREADABLE_EVENTS,
+                            expected: "decodable".to_string(),
+                            actual: e.to_string(),
+                        }],
+                        repaired: false,
+                    });
+                    continue;
+                }
+            };
+            let actual = self.load_view(order_id).await?;
+            let fields = compare(expected.as_ref(), actual.as_ref());
+            if fields.is_empty() {
+                continue;
+            }
+
+            warn!(
+                "Order view {} diverges from its events: {:?}",
+                order_id, fields
+            );
+            divergent_fields.extend(fields.iter().map(|field| field.field));
+
+            if self.repair {
+                self.repair_view(order_id, &events).await?;
+            }
+            report.divergences.push(OrderDivergence {
+                order_id,
+                fields,
+                repaired: self.repair,
+            });
+        }
+
+        metrics::record_projection_consistency("orders", report.checked, &divergent_fields);
+        info!(
+            "Consistency check: {} orders checked, {} skipped, {} divergent",
+            report.checked,
+            report.skipped,
+            report.divergences.len()
+        );
+        Ok(report)
+    }
+
+    async fn load_events(&self, order_id: Uuid) -> Result<Vec<BackfillEvent>, ReadModelError> {
+        let events = sqlx::query_as::<_, BackfillEvent>(
+            r#"
+            SELECT event_id, aggregate_id, event_type, payload, version, created_at
+            FROM events
+            WHERE aggregate_id = $1
+            ORDER BY version
+            "#,
+        )
+        .bind(order_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    async fn load_view(&self, order_id: Uuid) -> Result<Option<OrderView>, ReadModelError> {
+        // Always the primary: a lagging replica would look like drift
+        let view = sqlx::query_as::<_, OrderView>(
+            r#"
+            SELECT
+                order_id, customer_id, order_number, status,
+                total_amount, currency, items, shipping_address,
+                tracking_number, carrier, customer_name, customer_email,
+                created_at, updated_at, version
+            FROM order_views
+            WHERE order_id = $1
+            "#,
+        )
+        .bind(order_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(view)
+    }
+
+    async fn repair_view(
+        &self,
+        order_id: Uuid,
+        events: &[BackfillEvent],
+    ) -> Result<(), ReadModelError> {
+        let mut tx = self.pool.begin().await?;
+        self.projection.reproject(&mut tx, order_id, events).await?;
+        tx.commit().await?;
+
+        metrics::record_projection_repair("orders");
+        info!("Repaired order view {}", order_id);
+        Ok(())
+    }
+}
+
+/// Order state after `events`, or `None` if the stream was deleted
+fn derive_order(events: &[BackfillEvent]) -> Result<Option<OrderAggregate>, ReadModelError> {
+    let mut order = OrderAggregate::new();
+
+    for event in events {
+        let payload = event.payload.clone();
+        match event.event_type.as_str() {
+            "OrderCreated" => {
+                order.apply_order_created(&serde_json::from_value::<OrderCreatedEvent>(payload)?)
+            }
+            "OrderConfirmed" => order
+                .apply_order_confirmed(&serde_json::from_value::<OrderConfirmedEvent>(payload)?),
+            "OrderCancelled" => order
+                .apply_order_cancelled(&serde_json::from_value::<OrderCancelledEvent>(payload)?),
+            "OrderShipped" => {
+                order.apply_order_shipped(&serde_json::from_value::<OrderShippedEvent>(payload)?)
+            }
+            "OrderDelivered" => order
+                .apply_order_delivered(&serde_json::from_value::<OrderDeliveredEvent>(payload)?),
+            "OrderItemPriceCorrected" => order.apply_order_item_price_corrected(
+                &serde_json::from_value::<OrderItemPriceCorrectedEvent>(payload)?,
+            ),
+            "StreamDeleted" => return Ok(None),
+            _ => continue,
+        }
+        // The view records the version the event was stored at
+        order.version = event.version;
+    }
+
+    Ok(Some(order))
+}
+
+/// Fields of `actual` that disagree with the state derived from events
+fn compare(expected: Option<&OrderAggregate>, actual: Option<&OrderView>) -> Vec<FieldDivergence> {
+    let (expected, actual) = match (expected, actual) {
+        (None, None) => return Vec::new(),
+        (Some(_), None) => {
+            return vec![FieldDivergence {
+                field: "view",
+                expected: "present".to_string(),
+                actual: "missing".to_string(),
+            }]
+        }
+        (None, Some(_)) => {
+            return vec![FieldDivergence {
+                field: "view",
+                expected: "deleted".to_string(),
+                actual: "present".to_string(),
+            }]
+        }
+        (Some(expected), Some(actual)) => (expected, actual),
+    };
+
+    let mut fields = Vec::new();
+    let mut check = |field: &'static str, expected: String, actual: String| {
+        if expected != actual {
+            fields.push(FieldDivergence {
+                field,
+                expected,
+                actual,
+            });
+        }
+    };
+
+    check(
+        "customer_id",
+        expected.customer_id.to_string(),
+        actual.customer_id.to_string(),
+    );
+    check(
+        "order_number",
+        expected.order_number.clone(),
+        actual.order_number.clone(),
+    );
+    check(
+        "status",
+        expected.status.as_str().to_string(),
+        actual.status.clone(),
+    );
+    // Stored as DECIMAL(12,2)
+    check(
+        "total_amount",
+        format!("{:.2}", expected.total_amount),
+        format!("{:.2}", actual.total_amount),
+    );
+    check(
+        "version",
+        expected.version.to_string(),
+        actual.version.to_string(),
+    );
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    fn stored(
+        order_id: Uuid,
+        version: i64,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> BackfillEvent {
+        BackfillEvent {
+            event_id: Uuid::new_v4(),
+            aggregate_id: order_id,
+            event_type: event_type.to_string(),
+            payload,
+            version,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn created(order_id: Uuid, customer_id: Uuid) -> BackfillEvent {
+        let event = OrderCreatedEvent {
+            order_id,
+            customer_id,
+            order_number: "ORD-1".to_string(),
+            items: vec![],
+            total_amount: 42.5,
+            currency: "USD".to_string(),
+            created_at: Utc::now(),
+        };
+        stored(
+            order_id,
+            1,
+            "OrderCreated",
+            serde_json::to_value(event).unwrap(),
+        )
+    }
+
+    fn view(order_id: Uuid, customer_id: Uuid, status: &str, version: i64) -> OrderView {
+        let now: DateTime<Utc> = Utc::now();
+        OrderView {
+            order_id,
+            customer_id,
+            order_number: "ORD-1".to_string(),
+            status: status.to_string(),
+            total_amount: 42.5,
+            currency: "USD".to_string(),
+            items: serde_json::json!([]),
+            shipping_address: None,
+            tracking_number: None,
+            carrier: None,
+            customer_name: None,
+            customer_email: None,
+            created_at: now,
+            updated_at: now,
+            version,
+        }
+    }
+
+    #[test]
+    fn test_matching_view_has_no_divergence() {
+        let (order_id, customer_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let confirmed = OrderConfirmedEvent {
+            order_id,
+            confirmed_at: Utc::now(),
+        };
+        let events = vec![
+            created(order_id, customer_id),
+            stored(
+                order_id,
+                2,
+                "OrderConfirmed",
+                serde_json::to_value(confirmed).unwrap(),
+            ),
+        ];
+
+        let expected = derive_order(&events).unwrap();
+        let actual = view(order_id, customer_id, "CONFIRMED", 2);
+        assert!(compare(expected.as_ref(), Some(&actual)).is_empty());
+    }
+
+    #[test]
+    fn test_stale_status_diverges() {
+        let (order_id, customer_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let cancelled = OrderCancelledEvent {
+            order_id,
+            reason: "changed mind".to_string(),
+            cancelled_at: Utc::now(),
+        };
+        let events = vec![
+            created(order_id, customer_id),
+            stored(
+                order_id,
+                2,
+                "OrderCancelled",
+                serde_json::to_value(cancelled).unwrap(),
+            ),
+        ];
+
+        let expected = derive_order(&events).unwrap();
+        let actual = view(order_id, customer_id, "CREATED", 1);
+        let fields: Vec<_> = compare(expected.as_ref(), Some(&actual))
+            .into_iter()
+            .map(|divergence| divergence.field)
+            .collect();
+        assert_eq!(fields, vec!["status", "version"]);
+    }
+
+    #[test]
+    fn test_undecodable_event_fails_the_replay() {
+        let (order_id, customer_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let events = vec![
+            created(order_id, customer_id),
+            stored(order_id, 2, "OrderShipped", serde_json::json!({ "order_id": 7 })),
+        ];
+
+        assert!(derive_order(&events).is_err());
+    }
+
+    #[test]
+    fn test_deleted_stream_expects_no_view() {
+        let (order_id, customer_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let events = vec![
+            created(order_id, customer_id),
+            stored(order_id, 2, "StreamDeleted", serde_json::json!({})),
+        ];
+
+        assert!(derive_order(&events).unwrap().is_none());
+        let actual = view(order_id, customer_id, "CREATED", 1);
+        assert_eq!(compare(None, Some(&actual))[0].field, "view");
+        assert!(compare(None, None).is_empty());
+    }
+}
@@ -1,10 +1,40 @@
+pub mod backfill;
 pub mod cache;
+pub mod consistency;
+pub mod notifications;
 pub mod projections;
+pub mod rebuild;
 pub mod repositories;
+#[cfg(feature = "opensearch")]
+pub mod search;
 
+pub use backfill::{Backfill, BackfillEvent, BackfillProgress, BackfillRunner};
+pub use cache::OrderViewCache;
+#[cfg(feature = "redis")]
 pub use cache::RedisCache;
-pub use projections::OrderProjection;
-pub use repositories::{OrderView, OrderViewRepository, PostgresOrderViewRepository};
+pub use consistency::{ConsistencyChecker, ConsistencyReport, FieldDivergence, OrderDivergence};
+pub use notifications::{OrderStatusChange, OrderStatusNotifier};
+pub use projections::{
+    BusinessMetricsProjection, CustomerProjection, InventoryProjection, OrderProjection,
+    PaymentProjection, ProductProjection, ProjectedEvent, Projection, ProjectionOutcome,
+    SagaProjection,
+};
+pub use rebuild::{
+    snapshot_schema_name, RebuildReport, ShadowRebuild, ShadowRebuilder, ShadowTable,
+    ShadowTableCheck, SnapshotReport,
+};
+pub use repositories::{
+    BusinessKpis, BusinessMetricsRepository, BusinessMetricsTotals, CachedOrderViewRepository,
+    CurrencyTotals, EventCursor, EventLogRepository, FacetBucket, InMemoryOrderViewRepository, InstrumentedOrderViewRepository, InventoryView,
+    InventoryViewRepository, OrderExportFilter, OrderField, OrderFields, OrderSearchQuery,
+    OrderSearchRepository, OrderSearchResults, OrderSearchSort, OrderStatsBucket, OrderTotals, OrderView,
+    OrderViewRepository, ParseEventCursorError, PartialOrderView, PaymentHistoryEntry, PaymentView, PaymentViewRepository, PositionedEvent,
+    PostgresBusinessMetricsRepository, PostgresEventLogRepository, PostgresInventoryViewRepository, PostgresOrderViewRepository, PostgresPaymentViewRepository,
+    PostgresProductViewRepository, PostgresProjectionErrorRepository, PostgresSagaViewRepository,
+    PostgresTimelineRepository, ProductView, ProductViewRepository, ProjectionError,
+    ProjectionErrorRepository, ReadReplica, SagaTypeStats, SagaView, SagaViewRepository,
+    StatsGroupBy, TimelineEntry, TimelineRepository, ORDER_FACETS,
+};
 
 use thiserror::Error;
 
@@ -19,6 +49,15 @@ pub enum ReadModelError {
     #[error("Cache error: {0}")]
     CacheError(String),
 
+    #[error("Search index error: {0}")]
+    SearchError(String),
+
     #[error("Order not found: {0}")]
     NotFound(uuid::Uuid),
+
+    #[error("Unsupported event type: {0}")]
+    UnsupportedEvent(String),
+
+    #[error("Invalid snapshot schema name: {0}")]
+    InvalidSchema(String),
 }
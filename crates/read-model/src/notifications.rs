@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Postgres channel `order_views` announces status changes on (see migration 022)
+pub const ORDER_STATUS_CHANNEL: &str = "order_status_changed";
+
+/// Changes buffered per subscriber before it starts missing them
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Pause before listening again after the connection fails
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// An order view whose status was just projected
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderStatusChange {
+    pub order_id: Uuid,
+    pub status: String,
+    pub version: i64,
+}
+
+/// Fans order status notifications out to in-process subscribers
+#[derive(Clone)]
+pub struct OrderStatusNotifier {
+    sender: broadcast::Sender<OrderStatusChange>,
+}
+
+impl Default for OrderStatusNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderStatusNotifier {
+    /// Notifier fed only through [`OrderStatusNotifier::publish`]
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Listen on `pool` in the background, reconnecting on failure
+    ///
+    /// `pool` must point at the primary; replicas do not deliver notifications.
+    pub fn spawn(pool: PgPool) -> Self {
+        let notifier = Self::new();
+        let publisher = notifier.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = publisher.listen(&pool).await {
+                    error!("Order status listener failed: {}", e);
+                }
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        });
+        notifier
+    }
+
+    async fn listen(&self, pool: &PgPool) -> Result<(), sqlx::Error> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(ORDER_STATUS_CHANNEL).await?;
+        info!(
+            "Listening for order status changes on {}",
+            ORDER_STATUS_CHANNEL
+        );
+
+        loop {
+            let notification = listener.recv().await?;
+            match serde_json::from_str::<OrderStatusChange>(notification.payload()) {
+                Ok(change) => self.publish(change),
+                Err(e) => warn!("Ignoring malformed order status notification: {}", e),
+            }
+        }
+    }
+
+    pub fn publish(&self, change: OrderStatusChange) {
+        // Nobody waiting is fine
+        let _ = self.sender.send(change);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderStatusChange> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_payload_parsing() {
+        let order_id = Uuid::new_v4();
+        let payload = format!(
+            r#"{{"order_id": "{}", "status": "SHIPPED", "version": 3}}"#,
+            order_id
+        );
+        let change: OrderStatusChange = serde_json::from_str(&payload).unwrap();
+        assert_eq!(change.order_id, order_id);
+        assert_eq!(change.status, "SHIPPED");
+        assert_eq!(change.version, 3);
+    }
+
+    #[tokio::test]
+    async fn test_publish_reaches_subscribers() {
+        let notifier = OrderStatusNotifier::new();
+        // Publishing without subscribers is not an error
+        notifier.publish(OrderStatusChange {
+            order_id: Uuid::new_v4(),
+            status: "CREATED".to_string(),
+            version: 1,
+        });
+
+        let mut receiver = notifier.subscribe();
+        let change = OrderStatusChange {
+            order_id: Uuid::new_v4(),
+            status: "CONFIRMED".to_string(),
+            version: 2,
+        };
+        notifier.publish(change.clone());
+        assert_eq!(receiver.recv().await.unwrap(), change);
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use domain::events::order_events::*;
+use sqlx::PgConnection;
+
+use crate::ReadModelError;
+
+/// Counts order events into the per-minute `business_metrics_minutely` buckets
+///
+/// Only called for events the order view actually applied, so redeliveries and
+/// replays are not counted twice. Events land in the minute they happened in,
+/// not the minute they were projected. Each bucket also keeps when the newest event
+/// counted in it was stored and when it was last updated. Order value is kept per
+/// currency in `business_metrics_order_value`.
+#[derive(Debug, Default, Clone)]
+pub struct BusinessMetricsProjection;
+
+impl BusinessMetricsProjection {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Count an order created, with its value in its currency
+    pub async fn handle_order_created(
+        &self,
+        conn: &mut PgConnection,
+        event: &OrderCreatedEvent,
+        stored_at: Option<DateTime<Utc>>,
+    ) -> Result<(), ReadModelError> {
+        self.increment(conn, event.created_at, stored_at, 1, 0).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO business_metrics_order_value (bucket, currency, orders_created, order_value)
+            VALUES (date_trunc('minute', $1), $2, 1, $3)
+            ON CONFLICT (bucket, currency) DO UPDATE
+            SET orders_created = business_metrics_order_value.orders_created + 1,
+                order_value = business_metrics_order_value.order_value + EXCLUDED.order_value,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(event.created_at)
+        .bind(&event.currency)
+        .bind(event.total_amount)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count an order cancelled
+    pub async fn handle_order_cancelled(
+        &self,
+        conn: &mut PgConnection,
+        event: &OrderCancelledEvent,
+        stored_at: Option<DateTime<Utc>>,
+    ) -> Result<(), ReadModelError> {
+        self.increment(conn, event.cancelled_at, stored_at, 0, 1)
+            .await
+    }
+
+    /// Count the change in order value from a price correction
+    ///
+    /// The difference lands in the minute of the correction, in the currency of the
+    /// order's view; the bucket the order was created in keeps the value it had then.
+    pub async fn handle_order_item_price_corrected(
+        &self,
+        conn: &mut PgConnection,
+        event: &OrderItemPriceCorrectedEvent,
+        stored_at: Option<DateTime<Utc>>,
+    ) -> Result<(), ReadModelError> {
+        self.increment(conn, event.corrected_at, stored_at, 0, 0).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO business_metrics_order_value (bucket, currency, order_value)
+            SELECT date_trunc('minute', $2), currency, $3
+            FROM order_views
+            WHERE order_id = $1
+            ON CONFLICT (bucket, currency) DO UPDATE
+            SET order_value = business_metrics_order_value.order_value + EXCLUDED.order_value,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(event.order_id)
+        .bind(event.corrected_at)
+        .bind(event.total_amount - event.previous_total_amount)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn increment(
+        &self,
+        conn: &mut PgConnection,
+        at: DateTime<Utc>,
+        stored_at: Option<DateTime<Utc>>,
+        created: i64,
+        cancelled: i64,
+    ) -> Result<(), ReadModelError> {
+        sqlx::query(
+            r#"
+            INSERT INTO business_metrics_minutely
+                (bucket, orders_created, orders_cancelled, updated_at, event_created_at,
+                 processed_at)
+            VALUES (date_trunc('minute', $1), $2, $3, NOW(), $4, NOW())
+            ON CONFLICT (bucket) DO UPDATE
+            SET orders_created = business_metrics_minutely.orders_created + EXCLUDED.orders_created,
+                orders_cancelled = business_metrics_minutely.orders_cancelled + EXCLUDED.orders_cancelled,
+                updated_at = NOW(),
+                event_created_at = GREATEST(
+                    business_metrics_minutely.event_created_at,
+                    EXCLUDED.event_created_at
+                ),
+                processed_at = NOW()
+            "#,
+        )
+        .bind(at)
+        .bind(created)
+        .bind(cancelled)
+        .bind(stored_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
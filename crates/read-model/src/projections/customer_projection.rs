@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use domain::events::customer_events::*;
+use sqlx::{PgConnection, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::ReadModelError;
+
+/// Handles projecting customer events into `customer_views` and the customer
+/// columns of `order_views`
+///
+/// Order views copy the customer's name and email so order lists need no extra
+/// lookups. Whenever a customer row changes, every order of that customer is
+/// brought in line with it; updates older than the row's `updated_at` are ignored.
+#[derive(Debug, Default, Clone)]
+pub struct CustomerProjection;
+
+impl CustomerProjection {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Handle CustomerRegistered event
+    pub async fn handle_customer_registered(
+        &self,
+        conn: &mut PgConnection,
+        event: &CustomerRegisteredEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting CustomerRegistered for customer_id: {}", event.customer_id);
+
+        self.upsert(conn, event.customer_id, &event.name, &event.email, event.registered_at)
+            .await
+    }
+
+    /// Handle CustomerProfileUpdated event
+    pub async fn handle_profile_updated(
+        &self,
+        conn: &mut PgConnection,
+        event: &CustomerProfileUpdatedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting CustomerProfileUpdated for customer_id: {}", event.customer_id);
+
+        self.upsert(conn, event.customer_id, &event.name, &event.email, event.updated_at)
+            .await
+    }
+
+    async fn upsert(
+        &self,
+        conn: &mut PgConnection,
+        customer_id: Uuid,
+        name: &str,
+        email: &str,
+        at: DateTime<Utc>,
+    ) -> Result<(), ReadModelError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO customer_views (customer_id, name, email, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $4)
+            ON CONFLICT (customer_id) DO UPDATE
+            SET name = EXCLUDED.name,
+                email = EXCLUDED.email,
+                updated_at = EXCLUDED.updated_at
+            WHERE customer_views.updated_at <= EXCLUDED.updated_at
+            "#,
+        )
+        .bind(customer_id)
+        .bind(name)
+        .bind(email)
+        .bind(at)
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            warn!(
+                "Customer update for customer_id: {} was not applied (stale)",
+                customer_id
+            );
+            return Ok(());
+        }
+
+        let orders = copy_to_order_views(conn, customer_id).await?;
+        if orders > 0 {
+            info!("Updated customer info on {} orders of customer_id: {}", orders, customer_id);
+        }
+
+        Ok(())
+    }
+
+    /// Fill in customer info on order views still missing it
+    ///
+    /// Catches up orders projected before their customer was known, including rows
+    /// that predate the customer columns. Runs in batches of `batch_size` orders,
+    /// each in its own statement, and returns how many orders were updated.
+    pub async fn backfill_order_views(
+        &self,
+        pool: &PgPool,
+        batch_size: i64,
+    ) -> Result<u64, ReadModelError> {
+        let mut total = 0;
+        loop {
+            let updated = sqlx::query(
+                r#"
+                UPDATE order_views ov
+                SET customer_name = cv.name, customer_email = cv.email
+                FROM customer_views cv
+                WHERE ov.customer_id = cv.customer_id
+                  AND ov.order_id IN (
+                      SELECT o.order_id
+                      FROM order_views o
+                      JOIN customer_views c ON c.customer_id = o.customer_id
+                      WHERE o.customer_name IS NULL
+                      LIMIT $1
+                  )
+                "#,
+            )
+            .bind(batch_size.max(1))
+            .execute(pool)
+            .await?
+            .rows_affected();
+
+            total += updated;
+            if updated == 0 {
+                break;
+            }
+        }
+
+        if total > 0 {
+            info!("Backfilled customer info on {} orders", total);
+        }
+        Ok(total)
+    }
+}
+
+/// Copy name and email from `customer_views` onto the orders of one customer
+async fn copy_to_order_views(
+    conn: &mut PgConnection,
+    customer_id: Uuid,
+) -> Result<u64, ReadModelError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE order_views ov
+        SET customer_name = cv.name, customer_email = cv.email
+        FROM customer_views cv
+        WHERE ov.customer_id = cv.customer_id
+          AND cv.customer_id = $1
+          AND (ov.customer_name IS DISTINCT FROM cv.name
+               OR ov.customer_email IS DISTINCT FROM cv.email)
+        "#,
+    )
+    .bind(customer_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
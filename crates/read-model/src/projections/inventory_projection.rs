@@ -0,0 +1,222 @@
+use chrono::{DateTime, Utc};
+use domain::events::inventory_events::*;
+use sqlx::PgConnection;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::ReadModelError;
+
+/// Handles projecting inventory events into the `inventory_reservation_views` and
+/// `inventory_views` read models
+///
+/// Like [`crate::PaymentProjection`], reservation rows are upserted and never rolled back
+/// to an older status. The per-SKU `reserved` counters only move when a reservation
+/// enters or leaves `RESERVED`, so redelivered or stale events leave them untouched.
+#[derive(Debug, Default, Clone)]
+pub struct InventoryProjection;
+
+const RESERVED: &str = "RESERVED";
+
+/// Change in reserved units when a reservation moves from `previous` to `next` status
+fn reserved_delta(previous: Option<&str>, next: &str, quantity: u32) -> i64 {
+    let was_reserved = previous == Some(RESERVED);
+    let is_reserved = next == RESERVED;
+    match (was_reserved, is_reserved) {
+        (false, true) => i64::from(quantity),
+        (true, false) => -i64::from(quantity),
+        _ => 0,
+    }
+}
+
+impl InventoryProjection {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Handle InventoryReserved event
+    pub async fn handle_inventory_reserved(
+        &self,
+        conn: &mut PgConnection,
+        event: &InventoryReservedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting InventoryReserved for order_id: {}", event.order_id);
+        self.upsert(
+            conn,
+            event.order_id,
+            Some(event.reservation_id),
+            RESERVED,
+            &event.items,
+            None,
+            event.reserved_at,
+        )
+        .await
+    }
+
+    /// Handle InventoryReleased event
+    pub async fn handle_inventory_released(
+        &self,
+        conn: &mut PgConnection,
+        event: &InventoryReleasedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting InventoryReleased for order_id: {}", event.order_id);
+        self.upsert(
+            conn,
+            event.order_id,
+            Some(event.reservation_id),
+            "RELEASED",
+            &event.items,
+            Some(&event.reason),
+            event.released_at,
+        )
+        .await
+    }
+
+    /// Handle InventoryReservationFailed event
+    pub async fn handle_reservation_failed(
+        &self,
+        conn: &mut PgConnection,
+        event: &InventoryReservationFailedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!(
+            "Projecting InventoryReservationFailed for order_id: {}",
+            event.order_id
+        );
+        self.upsert(
+            conn,
+            event.order_id,
+            None,
+            "FAILED",
+            &event.items,
+            Some(&event.reason),
+            event.failed_at,
+        )
+        .await
+    }
+
+    /// Handle StockReplenished event
+    pub async fn handle_stock_replenished(
+        &self,
+        conn: &mut PgConnection,
+        event: &StockReplenishedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting StockReplenished for sku: {}", event.sku);
+        self.adjust_stock(
+            conn,
+            event.product_id,
+            &event.sku,
+            i64::from(event.quantity),
+            0,
+            event.replenished_at,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn upsert(
+        &self,
+        conn: &mut PgConnection,
+        order_id: Uuid,
+        reservation_id: Option<Uuid>,
+        status: &str,
+        items: &[InventoryItem],
+        reason: Option<&str>,
+        occurred_at: DateTime<Utc>,
+    ) -> Result<(), ReadModelError> {
+        let previous: Option<(String, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT status, updated_at FROM inventory_reservation_views WHERE order_id = $1 FOR UPDATE",
+        )
+        .bind(order_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        if let Some((previous_status, updated_at)) = &previous {
+            if *updated_at > occurred_at {
+                debug!(
+                    "Skipping stale {} for order_id: {} (already {})",
+                    status, order_id, previous_status
+                );
+                return Ok(());
+            }
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO inventory_reservation_views (
+                order_id, reservation_id, status, items, reason, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $6)
+            ON CONFLICT (order_id) DO UPDATE
+            SET reservation_id = COALESCE(EXCLUDED.reservation_id, inventory_reservation_views.reservation_id),
+                status = EXCLUDED.status,
+                items = EXCLUDED.items,
+                reason = EXCLUDED.reason,
+                created_at = LEAST(inventory_reservation_views.created_at, EXCLUDED.created_at),
+                updated_at = EXCLUDED.updated_at
+            WHERE inventory_reservation_views.updated_at <= EXCLUDED.updated_at
+            "#,
+        )
+        .bind(order_id)
+        .bind(reservation_id)
+        .bind(status)
+        .bind(serde_json::to_value(items)?)
+        .bind(reason)
+        .bind(occurred_at)
+        .execute(&mut *conn)
+        .await?;
+
+        let previous_status = previous.as_ref().map(|(status, _)| status.as_str());
+        for item in items {
+            let delta = reserved_delta(previous_status, status, item.quantity);
+            if delta != 0 {
+                self.adjust_stock(conn, item.product_id, &item.sku, 0, delta, occurred_at)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn adjust_stock(
+        &self,
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        sku: &str,
+        on_hand_delta: i64,
+        reserved_delta: i64,
+        occurred_at: DateTime<Utc>,
+    ) -> Result<(), ReadModelError> {
+        sqlx::query(
+            r#"
+            INSERT INTO inventory_views (sku, product_id, on_hand, reserved, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (sku) DO UPDATE
+            SET on_hand = inventory_views.on_hand + EXCLUDED.on_hand,
+                reserved = inventory_views.reserved + EXCLUDED.reserved,
+                updated_at = GREATEST(inventory_views.updated_at, EXCLUDED.updated_at)
+            "#,
+        )
+        .bind(sku)
+        .bind(product_id)
+        .bind(on_hand_delta)
+        .bind(reserved_delta)
+        .bind(occurred_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserved_delta_follows_status_transitions() {
+        assert_eq!(reserved_delta(None, "RESERVED", 3), 3);
+        assert_eq!(reserved_delta(Some("RESERVED"), "RELEASED", 3), -3);
+        assert_eq!(reserved_delta(Some("RESERVED"), "RESERVED", 3), 0);
+        assert_eq!(reserved_delta(Some("RELEASED"), "RELEASED", 3), 0);
+        assert_eq!(reserved_delta(None, "FAILED", 3), 0);
+    }
+}
@@ -1,3 +1,17 @@
+pub mod business_metrics_projection;
+pub mod customer_projection;
+pub mod inventory_projection;
 pub mod order_projection;
+pub mod payment_projection;
+pub mod product_projection;
+pub mod projection;
+pub mod saga_projection;
 
-pub use order_projection::OrderProjection;
+pub use business_metrics_projection::BusinessMetricsProjection;
+pub use customer_projection::CustomerProjection;
+pub use inventory_projection::InventoryProjection;
+pub use order_projection::{OrderProjection, ProjectionOutcome};
+pub use payment_projection::PaymentProjection;
+pub use product_projection::ProductProjection;
+pub use projection::{ProjectedEvent, Projection};
+pub use saga_projection::SagaProjection;
@@ -0,0 +1,221 @@
+use chrono::{DateTime, Utc};
+use domain::events::payment_events::*;
+use sqlx::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::ReadModelError;
+
+/// Handles projecting payment events into the `payment_views` read model
+///
+/// Every event upserts the payment row, so it does not matter which event of a
+/// payment arrives first; an older event never overwrites a newer status. Each event
+/// is also appended to `payment_history`, once, regardless of arrival order.
+#[derive(Debug, Default, Clone)]
+pub struct PaymentProjection;
+
+/// Columns written by a single payment event
+struct PaymentUpdate<'a> {
+    payment_id: Uuid,
+    order_id: Uuid,
+    status: &'a str,
+    amount: f64,
+    currency: &'a str,
+    payment_method: Option<&'a str>,
+    authorization_code: Option<&'a str>,
+    transaction_id: Option<&'a str>,
+    reason: Option<&'a str>,
+    occurred_at: DateTime<Utc>,
+}
+
+impl PaymentProjection {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Handle PaymentAuthorized event
+    pub async fn handle_payment_authorized(
+        &self,
+        conn: &mut PgConnection,
+        event: &PaymentAuthorizedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting PaymentAuthorized for payment_id: {}", event.payment_id);
+        self.upsert(
+            conn,
+            PaymentUpdate {
+                payment_id: event.payment_id,
+                order_id: event.order_id,
+                status: "AUTHORIZED",
+                amount: event.amount,
+                currency: &event.currency,
+                payment_method: Some(&event.payment_method),
+                authorization_code: Some(&event.authorization_code),
+                transaction_id: None,
+                reason: None,
+                occurred_at: event.authorized_at,
+            },
+        )
+        .await
+    }
+
+    /// Handle PaymentCaptured event
+    pub async fn handle_payment_captured(
+        &self,
+        conn: &mut PgConnection,
+        event: &PaymentCapturedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting PaymentCaptured for payment_id: {}", event.payment_id);
+        self.upsert(
+            conn,
+            PaymentUpdate {
+                payment_id: event.payment_id,
+                order_id: event.order_id,
+                status: "CAPTURED",
+                amount: event.amount,
+                currency: &event.currency,
+                payment_method: None,
+                authorization_code: None,
+                transaction_id: Some(&event.transaction_id),
+                reason: None,
+                occurred_at: event.captured_at,
+            },
+        )
+        .await
+    }
+
+    /// Handle PaymentVoided event
+    pub async fn handle_payment_voided(
+        &self,
+        conn: &mut PgConnection,
+        event: &PaymentVoidedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting PaymentVoided for payment_id: {}", event.payment_id);
+        self.upsert(
+            conn,
+            PaymentUpdate {
+                payment_id: event.payment_id,
+                order_id: event.order_id,
+                status: "VOIDED",
+                amount: event.amount,
+                currency: &event.currency,
+                payment_method: None,
+                authorization_code: None,
+                transaction_id: None,
+                reason: Some(&event.reason),
+                occurred_at: event.voided_at,
+            },
+        )
+        .await
+    }
+
+    /// Handle PaymentFailed event
+    pub async fn handle_payment_failed(
+        &self,
+        conn: &mut PgConnection,
+        event: &PaymentFailedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting PaymentFailed for payment_id: {}", event.payment_id);
+        self.upsert(
+            conn,
+            PaymentUpdate {
+                payment_id: event.payment_id,
+                order_id: event.order_id,
+                status: "FAILED",
+                amount: event.amount,
+                currency: &event.currency,
+                payment_method: None,
+                authorization_code: None,
+                transaction_id: None,
+                reason: Some(&event.reason),
+                occurred_at: event.failed_at,
+            },
+        )
+        .await
+    }
+
+    /// Handle PaymentRefunded event
+    pub async fn handle_payment_refunded(
+        &self,
+        conn: &mut PgConnection,
+        event: &PaymentRefundedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting PaymentRefunded for payment_id: {}", event.payment_id);
+        self.upsert(
+            conn,
+            PaymentUpdate {
+                payment_id: event.payment_id,
+                order_id: event.order_id,
+                status: "REFUNDED",
+                amount: event.amount,
+                currency: &event.currency,
+                payment_method: None,
+                authorization_code: None,
+                transaction_id: None,
+                reason: Some(&event.reason),
+                occurred_at: event.refunded_at,
+            },
+        )
+        .await
+    }
+
+    async fn upsert(
+        &self,
+        conn: &mut PgConnection,
+        update: PaymentUpdate<'_>,
+    ) -> Result<(), ReadModelError> {
+        sqlx::query(
+            r#"
+            INSERT INTO payment_views (
+                payment_id, order_id, status, amount, currency, payment_method,
+                authorization_code, transaction_id, reason, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10)
+            ON CONFLICT (payment_id) DO UPDATE
+            SET status = EXCLUDED.status,
+                payment_method = COALESCE(EXCLUDED.payment_method, payment_views.payment_method),
+                authorization_code = COALESCE(EXCLUDED.authorization_code, payment_views.authorization_code),
+                transaction_id = COALESCE(EXCLUDED.transaction_id, payment_views.transaction_id),
+                reason = COALESCE(EXCLUDED.reason, payment_views.reason),
+                created_at = LEAST(payment_views.created_at, EXCLUDED.created_at),
+                updated_at = EXCLUDED.updated_at
+            WHERE payment_views.updated_at <= EXCLUDED.updated_at
+            "#,
+        )
+        .bind(update.payment_id)
+        .bind(update.order_id)
+        .bind(update.status)
+        .bind(update.amount)
+        .bind(update.currency)
+        .bind(update.payment_method)
+        .bind(update.authorization_code)
+        .bind(update.transaction_id)
+        .bind(update.reason)
+        .bind(update.occurred_at)
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO payment_history (
+                payment_id, order_id, status, amount, currency, authorization_code,
+                transaction_id, reason, occurred_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (payment_id, status, occurred_at) DO NOTHING
+            "#,
+        )
+        .bind(update.payment_id)
+        .bind(update.order_id)
+        .bind(update.status)
+        .bind(update.amount)
+        .bind(update.currency)
+        .bind(update.authorization_code)
+        .bind(update.transaction_id)
+        .bind(update.reason)
+        .bind(update.occurred_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
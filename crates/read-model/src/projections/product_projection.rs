@@ -0,0 +1,111 @@
+use domain::events::product_events::*;
+use sqlx::PgConnection;
+use tracing::{info, warn};
+
+use crate::ReadModelError;
+
+/// Handles projecting catalog events into the `product_views` read model
+///
+/// Updates older than the row's `updated_at` are ignored, so replays and late
+/// deliveries never roll a price back.
+#[derive(Debug, Default, Clone)]
+pub struct ProductProjection;
+
+impl ProductProjection {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Handle ProductCreated event
+    pub async fn handle_product_created(
+        &self,
+        conn: &mut PgConnection,
+        event: &ProductCreatedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting ProductCreated for product_id: {}", event.product_id);
+
+        sqlx::query(
+            r#"
+            INSERT INTO product_views (
+                product_id, sku, name, description, unit_price, currency, active,
+                created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, TRUE, $7, $7)
+            ON CONFLICT (product_id) DO UPDATE
+            SET sku = EXCLUDED.sku,
+                name = EXCLUDED.name,
+                description = EXCLUDED.description,
+                unit_price = EXCLUDED.unit_price,
+                currency = EXCLUDED.currency,
+                updated_at = EXCLUDED.updated_at
+            WHERE product_views.updated_at <= EXCLUDED.updated_at
+            "#,
+        )
+        .bind(event.product_id)
+        .bind(&event.sku)
+        .bind(&event.name)
+        .bind(&event.description)
+        .bind(event.unit_price)
+        .bind(&event.currency)
+        .bind(event.created_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle ProductPriceChanged event
+    pub async fn handle_price_changed(
+        &self,
+        conn: &mut PgConnection,
+        event: &ProductPriceChangedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting ProductPriceChanged for product_id: {}", event.product_id);
+
+        let result = sqlx::query(
+            r#"
+            UPDATE product_views
+            SET unit_price = $2, currency = $3, updated_at = $4
+            WHERE product_id = $1 AND updated_at <= $4
+            "#,
+        )
+        .bind(event.product_id)
+        .bind(event.unit_price)
+        .bind(&event.currency)
+        .bind(event.changed_at)
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            warn!(
+                "ProductPriceChanged for product_id: {} was not applied (unknown or stale)",
+                event.product_id
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Handle ProductDiscontinued event
+    pub async fn handle_product_discontinued(
+        &self,
+        conn: &mut PgConnection,
+        event: &ProductDiscontinuedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting ProductDiscontinued for product_id: {}", event.product_id);
+
+        sqlx::query(
+            r#"
+            UPDATE product_views
+            SET active = FALSE, updated_at = GREATEST(updated_at, $2)
+            WHERE product_id = $1
+            "#,
+        )
+        .bind(event.product_id)
+        .bind(event.discontinued_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
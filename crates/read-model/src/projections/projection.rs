@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::ReadModelError;
+
+/// An order event handed to a [`Projection`]
+#[derive(Debug, Clone)]
+pub struct ProjectedEvent {
+    pub aggregate_id: Uuid,
+    pub event_type: String,
+    pub payload: Value,
+    /// Aggregate version the event was stored at, if the publisher stamped it
+    pub version: Option<i64>,
+}
+
+/// A store order events are projected into besides the Postgres views, such as a
+/// search index
+///
+/// The projection service hands each batch of order events to its projections once
+/// the batch is committed to `order_views`, in the order they were consumed. The same
+/// events may be handed over again after a restart, so `project` must be idempotent.
+#[async_trait]
+pub trait Projection: Send + Sync {
+    /// Name used in logs, e.g. `order_search`
+    fn name(&self) -> &str;
+
+    /// Apply a batch of order events
+    async fn project(&self, events: &[ProjectedEvent]) -> Result<(), ReadModelError>;
+}
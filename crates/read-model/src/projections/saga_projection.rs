@@ -0,0 +1,218 @@
+use domain::events::saga_events::*;
+use sqlx::PgConnection;
+use tracing::info;
+
+use crate::ReadModelError;
+
+/// Handles projecting saga lifecycle events into the `saga_views` read model
+///
+/// Like [`crate::OrderProjection`], it writes through the caller's connection.
+#[derive(Debug, Default, Clone)]
+pub struct SagaProjection;
+
+impl SagaProjection {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Handle SagaStarted event
+    pub async fn handle_saga_started(
+        &self,
+        conn: &mut PgConnection,
+        event: &SagaStartedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting SagaStarted for saga_id: {}", event.saga_id);
+
+        sqlx::query(
+            r#"
+            INSERT INTO saga_views (
+                saga_id, saga_type, status, total_steps, started_at, updated_at
+            )
+            VALUES ($1, $2, 'RUNNING', $3, $4, $4)
+            ON CONFLICT (saga_id) DO NOTHING
+            "#,
+        )
+        .bind(event.saga_id)
+        .bind(&event.saga_type)
+        .bind(event.total_steps as i32)
+        .bind(event.started_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle SagaStepCompleted event
+    pub async fn handle_step_completed(
+        &self,
+        conn: &mut PgConnection,
+        event: &SagaStepCompletedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!(
+            "Projecting SagaStepCompleted ({}) for saga_id: {}",
+            event.step_name, event.saga_id
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE saga_views
+            SET completed_steps = GREATEST(completed_steps, $2),
+                last_step = $3,
+                status = CASE WHEN status = 'PAUSED_AWAITING_APPROVAL' THEN 'RUNNING' ELSE status END,
+                updated_at = $4
+            WHERE saga_id = $1
+            "#,
+        )
+        .bind(event.saga_id)
+        .bind(event.step_index as i32 + 1)
+        .bind(&event.step_name)
+        .bind(event.completed_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle SagaStepFailed event; a failure that won't be retried starts compensation
+    pub async fn handle_step_failed(
+        &self,
+        conn: &mut PgConnection,
+        event: &SagaStepFailedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!(
+            "Projecting SagaStepFailed ({}) for saga_id: {}",
+            event.step_name, event.saga_id
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE saga_views
+            SET failed_attempts = failed_attempts + 1,
+                status = CASE WHEN $2 THEN status ELSE 'COMPENSATING' END,
+                last_step = $3,
+                last_error = $4,
+                updated_at = $5
+            WHERE saga_id = $1
+            "#,
+        )
+        .bind(event.saga_id)
+        .bind(event.will_retry)
+        .bind(&event.step_name)
+        .bind(&event.error)
+        .bind(event.failed_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle SagaCompleted event
+    pub async fn handle_saga_completed(
+        &self,
+        conn: &mut PgConnection,
+        event: &SagaCompletedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting SagaCompleted for saga_id: {}", event.saga_id);
+        self.finish(
+            conn,
+            event.saga_id,
+            "COMPLETED",
+            Some(event.duration_ms),
+            event.completed_at,
+        )
+        .await
+    }
+
+    /// Handle SagaCompensated event
+    pub async fn handle_saga_compensated(
+        &self,
+        conn: &mut PgConnection,
+        event: &SagaCompensatedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting SagaCompensated for saga_id: {}", event.saga_id);
+        self.finish(
+            conn,
+            event.saga_id,
+            "COMPENSATED",
+            Some(event.duration_ms),
+            event.compensated_at,
+        )
+        .await
+    }
+
+    /// Handle SagaInterventionRequired event
+    pub async fn handle_intervention_required(
+        &self,
+        conn: &mut PgConnection,
+        event: &SagaInterventionRequiredEvent,
+    ) -> Result<(), ReadModelError> {
+        info!("Projecting SagaInterventionRequired for saga_id: {}", event.saga_id);
+
+        sqlx::query(
+            r#"
+            UPDATE saga_views
+            SET status = 'REQUIRES_INTERVENTION', last_error = $2, updated_at = $3
+            WHERE saga_id = $1
+            "#,
+        )
+        .bind(event.saga_id)
+        .bind(&event.reason)
+        .bind(event.failed_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Handle SagaPaused event; the next step completion or failure moves the view on
+    pub async fn handle_saga_paused(
+        &self,
+        conn: &mut PgConnection,
+        event: &SagaPausedEvent,
+    ) -> Result<(), ReadModelError> {
+        info!(
+            "Projecting SagaPaused ({}) for saga_id: {}",
+            event.step_name, event.saga_id
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE saga_views
+            SET status = 'PAUSED_AWAITING_APPROVAL', last_step = $2, updated_at = $3
+            WHERE saga_id = $1
+            "#,
+        )
+        .bind(event.saga_id)
+        .bind(&event.step_name)
+        .bind(event.paused_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn finish(
+        &self,
+        conn: &mut PgConnection,
+        saga_id: uuid::Uuid,
+        status: &str,
+        duration_ms: Option<i64>,
+        finished_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), ReadModelError> {
+        sqlx::query(
+            r#"
+            UPDATE saga_views
+            SET status = $2, duration_ms = $3, finished_at = $4, updated_at = $4
+            WHERE saga_id = $1
+            "#,
+        )
+        .bind(saga_id)
+        .bind(status)
+        .bind(duration_ms)
+        .bind(finished_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,512 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::backfill::{load_events_after, BackfillEvent};
+use crate::ReadModelError;
+
+/// Schema the shadow copies are rebuilt in
+pub const SHADOW_SCHEMA: &str = "projection_shadow";
+
+/// Schema the replaced live tables are moved to, kept until `drop_retired`
+pub const RETIRED_SCHEMA: &str = "projection_retired";
+
+/// Prefix of the schemas point-in-time snapshots are written to
+pub const SNAPSHOT_SCHEMA_PREFIX: &str = "as_of_";
+
+/// A table a projection writes, rebuilt as a shadow copy and swapped in
+#[derive(Debug, Clone, Copy)]
+pub struct ShadowTable {
+    pub name: &'static str,
+    /// Columns compared between the live and shadow copies; only columns derived
+    /// purely from events belong here (no `NOW()` defaults or data from other views)
+    pub checksum_columns: &'static [&'static str],
+    /// Whether the copies must agree before a swap; off for transient tables like buffers
+    pub verified: bool,
+}
+
+/// A projection that can be rebuilt next to its live tables
+///
+/// The projection's SQL uses unqualified table names. During a rebuild those resolve
+/// to the shadow copies through the `search_path`, so the same handlers that keep the
+/// live tables up to date fill the shadow ones. Tables the projection only reads keep
+/// resolving to the live schema.
+#[async_trait]
+pub trait ShadowRebuild: Send + Sync {
+    /// Name used in logs, e.g. `orders`
+    fn name(&self) -> &str;
+
+    /// Every table the projection writes
+    fn tables(&self) -> Vec<ShadowTable>;
+
+    /// Event types replayed into the shadow tables
+    fn event_types(&self) -> Vec<String>;
+
+    /// Apply one event to whichever tables the `search_path` resolves to
+    async fn apply(
+        &self,
+        conn: &mut PgConnection,
+        event: &BackfillEvent,
+    ) -> Result<(), ReadModelError>;
+
+    /// Runs in the swap transaction once the rebuilt tables are live,
+    /// e.g. to recreate triggers, which are not copied into the shadow tables
+    async fn after_swap(&self, _conn: &mut PgConnection) -> Result<(), ReadModelError> {
+        Ok(())
+    }
+}
+
+/// Live and shadow copies of one table, as compared before a swap
+#[derive(Debug, Clone, Serialize)]
+pub struct ShadowTableCheck {
+    pub table: String,
+    pub live_rows: i64,
+    pub shadow_rows: i64,
+    pub live_checksum: Option<String>,
+    pub shadow_checksum: Option<String>,
+    pub verified: bool,
+}
+
+impl ShadowTableCheck {
+    pub fn matches(&self) -> bool {
+        !self.verified
+            || (self.live_rows == self.shadow_rows && self.live_checksum == self.shadow_checksum)
+    }
+}
+
+/// Outcome of a rebuild
+#[derive(Debug, Clone, Serialize)]
+pub struct RebuildReport {
+    pub projection: String,
+    pub events_replayed: i64,
+    pub checks: Vec<ShadowTableCheck>,
+    /// Whether the shadow tables replaced the live ones
+    pub swapped: bool,
+}
+
+/// A projection rebuilt as of a past instant
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotReport {
+    pub projection: String,
+    pub schema: String,
+    pub as_of: DateTime<Utc>,
+    pub events_replayed: i64,
+    /// Rows in each snapshot table
+    pub rows: Vec<(String, i64)>,
+}
+
+/// Default snapshot schema for `as_of`, e.g. `as_of_20260930_235959`
+pub fn snapshot_schema_name(as_of: DateTime<Utc>) -> String {
+    format!(
+        "{}{}",
+        SNAPSHOT_SCHEMA_PREFIX,
+        as_of.format("%Y%m%d_%H%M%S")
+    )
+}
+
+/// Rebuilds projections into shadow tables while the live ones keep serving
+///
+/// 1. Each table is copied empty into `projection_shadow` (`LIKE ... INCLUDING ALL`).
+/// 2. Events are replayed into the copies in batches, one transaction each.
+/// 3. The swap transaction locks the live tables against writes (reads continue),
+///    replays the events that arrived meanwhile and compares row counts and checksums.
+///    If they match, or the swap is forced, the live tables move to `projection_retired`
+///    and the shadow ones to `public`; otherwise the transaction rolls back.
+///
+/// The live projection keeps consuming throughout. Events it applies after the swap
+/// and that the rebuild already replayed are discarded as duplicates by the version guards.
+pub struct ShadowRebuilder {
+    pool: PgPool,
+    batch_size: i64,
+}
+
+impl ShadowRebuilder {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            batch_size: 1000,
+        }
+    }
+
+    /// Events replayed per transaction
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Rebuild `projection` into shadow tables and swap them in
+    ///
+    /// With `force` the swap goes ahead even if the copies differ, e.g. when the
+    /// rebuild is repairing a live table known to be wrong. With `dry_run` the copies
+    /// are compared but never swapped.
+    pub async fn rebuild(
+        &self,
+        projection: &dyn ShadowRebuild,
+        force: bool,
+        dry_run: bool,
+    ) -> Result<RebuildReport, ReadModelError> {
+        let tables = projection.tables();
+        info!(
+            "Rebuilding {} projection into {} ({} tables)",
+            projection.name(),
+            SHADOW_SCHEMA,
+            tables.len()
+        );
+
+        self.create_tables(SHADOW_SCHEMA, &tables).await?;
+
+        let event_types = projection.event_types();
+        let mut cursor = None;
+        let mut events_replayed = 0i64;
+        loop {
+            let mut tx = self.pool.begin().await?;
+            use_schema(&mut tx, SHADOW_SCHEMA).await?;
+            let replayed = self
+                .replay_batch(&mut tx, projection, &event_types, &mut cursor, None)
+                .await?;
+            tx.commit().await?;
+
+            events_replayed += replayed as i64;
+            if (replayed as i64) < self.batch_size {
+                break;
+            }
+            info!(
+                "Rebuild of {}: {} events replayed",
+                projection.name(),
+                events_replayed
+            );
+        }
+
+        // Catch up, compare and swap while the live tables take no writes
+        let mut tx = self.pool.begin().await?;
+        for table in &tables {
+            sqlx::query(&format!(
+                "LOCK TABLE public.{} IN EXCLUSIVE MODE",
+                table.name
+            ))
+            .execute(&mut *tx)
+            .await?;
+        }
+        use_schema(&mut tx, SHADOW_SCHEMA).await?;
+        loop {
+            let replayed = self
+                .replay_batch(&mut tx, projection, &event_types, &mut cursor, None)
+                .await?;
+            events_replayed += replayed as i64;
+            if (replayed as i64) < self.batch_size {
+                break;
+            }
+        }
+        sqlx::query("SET LOCAL search_path TO public")
+            .execute(&mut *tx)
+            .await?;
+
+        let mut checks = Vec::with_capacity(tables.len());
+        for table in &tables {
+            checks.push(compare(&mut tx, table).await?);
+        }
+        for check in checks.iter().filter(|check| !check.matches()) {
+            warn!(
+                "Rebuild of {}: {} differs (live {} rows {:?}, shadow {} rows {:?})",
+                projection.name(),
+                check.table,
+                check.live_rows,
+                check.live_checksum,
+                check.shadow_rows,
+                check.shadow_checksum
+            );
+        }
+
+        let all_match = checks.iter().all(ShadowTableCheck::matches);
+        let swap = !dry_run && (all_match || force);
+        if swap {
+            swap_tables(&mut tx, &tables).await?;
+            projection.after_swap(&mut tx).await?;
+            tx.commit().await?;
+            info!(
+                "Rebuilt {} projection from {} events and swapped it in",
+                projection.name(),
+                events_replayed
+            );
+        } else {
+            tx.rollback().await?;
+            info!(
+                "Rebuilt {} projection from {} events; live tables kept{}",
+                projection.name(),
+                events_replayed,
+                if dry_run {
+                    " (dry run)"
+                } else {
+                    " because the copies differ"
+                }
+            );
+        }
+
+        Ok(RebuildReport {
+            projection: projection.name().to_string(),
+            events_replayed,
+            checks,
+            swapped: swap,
+        })
+    }
+
+    /// Drop the tables replaced by the last swap, once it is clear they are not needed
+    pub async fn drop_retired(&self) -> Result<(), ReadModelError> {
+        sqlx::query(&format!("DROP SCHEMA IF EXISTS {} CASCADE", RETIRED_SCHEMA))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Rebuild `projection` as it stood at `as_of` into `schema`, leaving the live tables alone
+    ///
+    /// Only events created at or before `as_of` are replayed. `schema` must start with
+    /// [`SNAPSHOT_SCHEMA_PREFIX`] and is replaced if it exists. Data the projection
+    /// reads from other views (such as customer names copied into `order_views`) comes
+    /// from their current state, not their state at `as_of`.
+    pub async fn snapshot_as_of(
+        &self,
+        projection: &dyn ShadowRebuild,
+        as_of: DateTime<Utc>,
+        schema: &str,
+    ) -> Result<SnapshotReport, ReadModelError> {
+        if !is_snapshot_schema(schema) {
+            return Err(ReadModelError::InvalidSchema(schema.to_string()));
+        }
+        let tables = projection.tables();
+        info!(
+            "Rebuilding {} projection as of {} into {}",
+            projection.name(),
+            as_of,
+            schema
+        );
+
+        self.create_tables(schema, &tables).await?;
+
+        let event_types = projection.event_types();
+        let mut cursor = None;
+        let mut events_replayed = 0i64;
+        loop {
+            let mut tx = self.pool.begin().await?;
+            use_schema(&mut tx, schema).await?;
+            let replayed = self
+                .replay_batch(&mut tx, projection, &event_types, &mut cursor, Some(as_of))
+                .await?;
+            tx.commit().await?;
+
+            events_replayed += replayed as i64;
+            if (replayed as i64) < self.batch_size {
+                break;
+            }
+        }
+
+        let mut rows = Vec::with_capacity(tables.len());
+        for table in &tables {
+            let count: i64 =
+                sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}.{}", schema, table.name))
+                    .fetch_one(&self.pool)
+                    .await?;
+            rows.push((table.name.to_string(), count));
+        }
+        info!(
+            "Snapshot of {} as of {} written to {} from {} events",
+            projection.name(),
+            as_of,
+            schema,
+            events_replayed
+        );
+
+        Ok(SnapshotReport {
+            projection: projection.name().to_string(),
+            schema: schema.to_string(),
+            as_of,
+            events_replayed,
+            rows,
+        })
+    }
+
+    /// Recreate `schema` holding empty copies of `tables`
+    async fn create_tables(
+        &self,
+        schema: &str,
+        tables: &[ShadowTable],
+    ) -> Result<(), ReadModelError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(&format!("DROP SCHEMA IF EXISTS {} CASCADE", schema))
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(&format!("CREATE SCHEMA {}", schema))
+            .execute(&mut *tx)
+            .await?;
+        for table in tables {
+            sqlx::query(&format!(
+                "CREATE TABLE {schema}.{table} (LIKE public.{table} INCLUDING ALL)",
+                schema = schema,
+                table = table.name
+            ))
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn replay_batch(
+        &self,
+        conn: &mut PgConnection,
+        projection: &dyn ShadowRebuild,
+        event_types: &[String],
+        cursor: &mut Option<(DateTime<Utc>, Uuid)>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<usize, ReadModelError> {
+        let events = load_events_after(conn, event_types, *cursor, until, self.batch_size).await?;
+        for event in &events {
+            projection.apply(conn, event).await?;
+        }
+        if let Some(last) = events.last() {
+            *cursor = Some((last.created_at, last.event_id));
+        }
+        Ok(events.len())
+    }
+}
+
+/// Resolve unqualified table names to the copies in `schema` for the rest of the transaction
+async fn use_schema(conn: &mut PgConnection, schema: &str) -> Result<(), ReadModelError> {
+    sqlx::query(&format!("SET LOCAL search_path TO {}, public", schema))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Whether `schema` is a name snapshots may be written to (and dropped from)
+fn is_snapshot_schema(schema: &str) -> bool {
+    schema.len() > SNAPSHOT_SCHEMA_PREFIX.len()
+        && schema.len() <= 63
+        && schema.starts_with(SNAPSHOT_SCHEMA_PREFIX)
+        && schema
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Order-independent checksum over the given columns of every row
+fn checksum_query(schema: &str, table: &ShadowTable) -> String {
+    let columns = if table.checksum_columns.is_empty() {
+        "NULL".to_string()
+    } else {
+        table.checksum_columns.join(", ")
+    };
+    format!(
+        "SELECT COUNT(*), md5(string_agg(row_hash, '' ORDER BY row_hash)) \
+         FROM (SELECT md5(ROW({columns})::text) AS row_hash FROM {schema}.{table}) AS hashed",
+        columns = columns,
+        schema = schema,
+        table = table.name
+    )
+}
+
+async fn compare(
+    conn: &mut PgConnection,
+    table: &ShadowTable,
+) -> Result<ShadowTableCheck, ReadModelError> {
+    let (live_rows, live_checksum): (i64, Option<String>) =
+        sqlx::query_as(&checksum_query("public", table))
+            .fetch_one(&mut *conn)
+            .await?;
+    let (shadow_rows, shadow_checksum): (i64, Option<String>) =
+        sqlx::query_as(&checksum_query(SHADOW_SCHEMA, table))
+            .fetch_one(&mut *conn)
+            .await?;
+
+    Ok(ShadowTableCheck {
+        table: table.name.to_string(),
+        live_rows,
+        shadow_rows,
+        live_checksum,
+        shadow_checksum,
+        verified: table.verified,
+    })
+}
+
+async fn swap_tables(
+    conn: &mut PgConnection,
+    tables: &[ShadowTable],
+) -> Result<(), ReadModelError> {
+    sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS {}", RETIRED_SCHEMA))
+        .execute(&mut *conn)
+        .await?;
+    for table in tables {
+        sqlx::query(&format!(
+            "DROP TABLE IF EXISTS {}.{}",
+            RETIRED_SCHEMA, table.name
+        ))
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query(&format!(
+            "ALTER TABLE public.{} SET SCHEMA {}",
+            table.name, RETIRED_SCHEMA
+        ))
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query(&format!(
+            "ALTER TABLE {}.{} SET SCHEMA public",
+            SHADOW_SCHEMA, table.name
+        ))
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(live_rows: i64, shadow_rows: i64, verified: bool) -> ShadowTableCheck {
+        ShadowTableCheck {
+            table: "order_views".to_string(),
+            live_rows,
+            shadow_rows,
+            live_checksum: Some("a".to_string()),
+            shadow_checksum: Some("a".to_string()),
+            verified,
+        }
+    }
+
+    #[test]
+    fn test_unverified_tables_always_match() {
+        assert!(check(10, 10, true).matches());
+        assert!(!check(10, 9, true).matches());
+        assert!(check(10, 9, false).matches());
+
+        let mut differing = check(10, 10, true);
+        differing.shadow_checksum = Some("b".to_string());
+        assert!(!differing.matches());
+    }
+
+    #[test]
+    fn test_checksum_query_uses_listed_columns() {
+        let table = ShadowTable {
+            name: "order_views",
+            checksum_columns: &["order_id", "status"],
+            verified: true,
+        };
+        let query = checksum_query(SHADOW_SCHEMA, &table);
+        assert!(query.contains("ROW(order_id, status)"));
+        assert!(query.contains("FROM projection_shadow.order_views"));
+    }
+
+    #[test]
+    fn test_snapshot_schema_names() {
+        let as_of = "2026-09-30T23:59:59Z".parse().unwrap();
+        let schema = snapshot_schema_name(as_of);
+        assert_eq!(schema, "as_of_20260930_235959");
+        assert!(is_snapshot_schema(&schema));
+
+        assert!(!is_snapshot_schema("public"));
+        assert!(!is_snapshot_schema("as_of_"));
+        assert!(!is_snapshot_schema("as_of_x; DROP TABLE events"));
+    }
+}
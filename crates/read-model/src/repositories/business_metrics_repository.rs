@@ -0,0 +1,160 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use std::collections::BTreeMap;
+
+use crate::ReadModelError;
+
+/// Orders created in one currency and their value, summed over a range of buckets
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct CurrencyTotals {
+    pub currency: String,
+    pub orders_created: i64,
+    pub order_value: f64,
+}
+
+/// Order counters summed over a range of minute buckets
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BusinessMetricsTotals {
+    pub orders_created: i64,
+    pub orders_cancelled: i64,
+    /// Order value per currency; amounts in different currencies are never added up
+    pub order_value: Vec<CurrencyTotals>,
+}
+
+/// Business KPIs over a trailing window, for the ops dashboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusinessKpis {
+    pub window_minutes: i64,
+    pub orders_per_minute: f64,
+    /// Average order value per currency
+    pub average_order_value: BTreeMap<String, f64>,
+    /// Orders cancelled in the window per order created in it
+    pub cancellation_rate: f64,
+    pub computed_at: DateTime<Utc>,
+}
+
+impl BusinessKpis {
+    pub fn from_totals(window_minutes: i64, totals: BusinessMetricsTotals, computed_at: DateTime<Utc>) -> Self {
+        let ratio = |numerator: f64, denominator: f64| {
+            if denominator > 0.0 {
+                numerator / denominator
+            } else {
+                0.0
+            }
+        };
+        let created = totals.orders_created as f64;
+
+        Self {
+            window_minutes,
+            orders_per_minute: ratio(created, window_minutes as f64),
+            average_order_value: totals
+                .order_value
+                .iter()
+                .map(|value| {
+                    let average = ratio(value.order_value, value.orders_created as f64);
+                    (value.currency.clone(), average)
+                })
+                .collect(),
+            cancellation_rate: ratio(totals.orders_cancelled as f64, created),
+            computed_at,
+        }
+    }
+}
+
+/// Repository for reading the per-minute business metrics
+#[async_trait]
+pub trait BusinessMetricsRepository: Send + Sync {
+    /// Counters of all buckets starting at or after `since`
+    async fn totals_since(&self, since: DateTime<Utc>) -> Result<BusinessMetricsTotals, ReadModelError>;
+}
+
+/// PostgreSQL implementation of BusinessMetricsRepository
+pub struct PostgresBusinessMetricsRepository {
+    pool: PgPool,
+}
+
+impl PostgresBusinessMetricsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BusinessMetricsRepository for PostgresBusinessMetricsRepository {
+    async fn totals_since(&self, since: DateTime<Utc>) -> Result<BusinessMetricsTotals, ReadModelError> {
+        let (orders_created, orders_cancelled): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(orders_created), 0)::BIGINT,
+                   COALESCE(SUM(orders_cancelled), 0)::BIGINT
+            FROM business_metrics_minutely
+            WHERE bucket >= date_trunc('minute', $1)
+            "#,
+        )
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let order_value = sqlx::query_as::<_, CurrencyTotals>(
+            r#"
+            SELECT currency,
+                   SUM(orders_created)::BIGINT AS orders_created,
+                   SUM(order_value)::FLOAT8 AS order_value
+            FROM business_metrics_order_value
+            WHERE bucket >= date_trunc('minute', $1)
+            GROUP BY currency
+            ORDER BY currency
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(BusinessMetricsTotals {
+            orders_created,
+            orders_cancelled,
+            order_value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kpis_from_totals() {
+        let totals = BusinessMetricsTotals {
+            orders_created: 120,
+            orders_cancelled: 6,
+            order_value: vec![
+                CurrencyTotals {
+                    currency: "EUR".to_string(),
+                    orders_created: 20,
+                    order_value: 2000.0,
+                },
+                CurrencyTotals {
+                    currency: "USD".to_string(),
+                    orders_created: 100,
+                    order_value: 4000.0,
+                },
+            ],
+        };
+        let kpis = BusinessKpis::from_totals(60, totals, Utc::now());
+
+        assert_eq!(kpis.orders_per_minute, 2.0);
+        assert_eq!(kpis.average_order_value["EUR"], 100.0);
+        assert_eq!(kpis.average_order_value["USD"], 40.0);
+        assert_eq!(kpis.cancellation_rate, 0.05);
+    }
+
+    #[test]
+    fn test_kpis_without_orders_are_zero() {
+        let kpis = BusinessKpis::from_totals(60, BusinessMetricsTotals::default(), Utc::now());
+
+        assert_eq!(kpis.orders_per_minute, 0.0);
+        assert!(kpis.average_order_value.is_empty());
+        assert_eq!(kpis.cancellation_rate, 0.0);
+    }
+}
@@ -0,0 +1,215 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::ReadModelError;
+
+/// Place in the global log, written `{transaction_id}.{position}`
+///
+/// The log is read in `(transaction_id, position)` order. Only events of transactions
+/// older than every open one are read, and an event that becomes visible later belongs
+/// to a newer transaction, so it sorts after any cursor already handed out. Resuming
+/// by position alone would skip an event an older transaction appended at a lower
+/// position but committed later.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EventCursor {
+    pub transaction_id: i64,
+    pub position: i64,
+}
+
+impl EventCursor {
+    /// Before the first event
+    pub const START: EventCursor = EventCursor {
+        transaction_id: 0,
+        position: 0,
+    };
+}
+
+impl fmt::Display for EventCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.transaction_id, self.position)
+    }
+}
+
+/// Error parsing an [`EventCursor`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid event cursor {0:?}, expected {{transaction_id}}.{{position}} or 0")]
+pub struct ParseEventCursorError(String);
+
+impl FromStr for EventCursor {
+    type Err = ParseEventCursorError;
+
+    /// `0` is accepted for [`EventCursor::START`]
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value == "0" {
+            return Ok(Self::START);
+        }
+        let invalid = || ParseEventCursorError(value.to_string());
+        let (transaction_id, position) = value.split_once('.').ok_or_else(invalid)?;
+        let cursor = EventCursor {
+            transaction_id: transaction_id.parse().map_err(|_| invalid())?,
+            position: position.parse().map_err(|_| invalid())?,
+        };
+        if cursor.transaction_id < 0 || cursor.position < 0 {
+            return Err(invalid());
+        }
+        Ok(cursor)
+    }
+}
+
+/// A stored event with its place in the global log
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PositionedEvent {
+    /// Transaction that appended the event
+    pub transaction_id: i64,
+    pub position: i64,
+    pub event_id: Uuid,
+    pub aggregate_id: Uuid,
+    pub aggregate_type: String,
+    pub event_type: String,
+    /// Aggregate version the event was stored at
+    pub version: i64,
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PositionedEvent {
+    pub fn cursor(&self) -> EventCursor {
+        EventCursor {
+            transaction_id: self.transaction_id,
+            position: self.position,
+        }
+    }
+}
+
+/// Repository reading the global event log by position (migrations/033)
+#[async_trait]
+pub trait EventLogRepository: Send + Sync {
+    /// Cursor of an aggregate's event at `version`
+    async fn cursor_of(
+        &self,
+        aggregate_id: Uuid,
+        version: i64,
+    ) -> Result<Option<EventCursor>, ReadModelError>;
+
+    /// Up to `limit` events after `after`, in cursor order
+    ///
+    /// Only events appended by transactions older than every open one are returned,
+    /// so an append still in flight cannot later commit behind the last cursor read.
+    async fn events_after(
+        &self,
+        after: EventCursor,
+        limit: i64,
+    ) -> Result<Vec<PositionedEvent>, ReadModelError>;
+}
+
+/// PostgreSQL implementation reading the events table directly
+pub struct PostgresEventLogRepository {
+    pool: PgPool,
+}
+
+impl PostgresEventLogRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl EventLogRepository for PostgresEventLogRepository {
+    async fn cursor_of(
+        &self,
+        aggregate_id: Uuid,
+        version: i64,
+    ) -> Result<Option<EventCursor>, ReadModelError> {
+        let cursor: Option<(i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT transaction_id::text::bigint, global_position
+            FROM events
+            WHERE aggregate_id = $1 AND version = $2
+            "#,
+        )
+        .bind(aggregate_id)
+        .bind(version)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(cursor.map(|(transaction_id, position)| EventCursor {
+            transaction_id,
+            position,
+        }))
+    }
+
+    async fn events_after(
+        &self,
+        after: EventCursor,
+        limit: i64,
+    ) -> Result<Vec<PositionedEvent>, ReadModelError> {
+        let events = sqlx::query_as::<_, PositionedEvent>(
+            r#"
+            SELECT transaction_id::text::bigint AS transaction_id, global_position AS position,
+                   event_id, aggregate_id, aggregate_type, event_type, version, payload, created_at
+            FROM events
+            WHERE (transaction_id, global_position) > ($1::text::xid8, $2)
+              AND transaction_id < pg_snapshot_xmin(pg_current_snapshot())
+            ORDER BY transaction_id, global_position
+            LIMIT $3
+            "#,
+        )
+        .bind(after.transaction_id.to_string())
+        .bind(after.position)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positioned_event_serialization() {
+        let event = PositionedEvent {
+            transaction_id: 7,
+            position: 42,
+            event_id: Uuid::new_v4(),
+            aggregate_id: Uuid::new_v4(),
+            aggregate_type: "Order".to_string(),
+            event_type: "OrderCreated".to_string(),
+            version: 1,
+            payload: serde_json::json!({"total_amount": 10.0}),
+            created_at: Utc::now(),
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["position"], 42);
+        assert_eq!(json["event_type"], "OrderCreated");
+        assert_eq!(event.cursor().to_string(), "7.42");
+    }
+
+    #[test]
+    fn test_cursor_parsing() {
+        assert_eq!("0".parse::<EventCursor>(), Ok(EventCursor::START));
+        assert_eq!(
+            "812.1042".parse::<EventCursor>(),
+            Ok(EventCursor {
+                transaction_id: 812,
+                position: 1042
+            })
+        );
+        assert!("1042".parse::<EventCursor>().is_err());
+        assert!("1.-2".parse::<EventCursor>().is_err());
+        assert!("a.b".parse::<EventCursor>().is_err());
+
+        // Cursor order is transaction first: a late commit sorts after earlier reads
+        let read = EventCursor { transaction_id: 10, position: 50 };
+        let late = EventCursor { transaction_id: 11, position: 40 };
+        assert!(late > read);
+    }
+}
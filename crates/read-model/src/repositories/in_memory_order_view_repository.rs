@@ -0,0 +1,357 @@
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+use super::order_view_repository::{
+    OrderExportFilter, OrderFields, OrderStatsBucket, OrderTotals, OrderView, OrderViewRepository,
+    PartialOrderView, StatsGroupBy,
+};
+use crate::ReadModelError;
+
+type Orders = Arc<RwLock<HashMap<Uuid, OrderView>>>;
+
+/// Order views kept in process memory, for tests, examples and local demos
+///
+/// Queries sort and paginate like [`PostgresOrderViewRepository`](crate::PostgresOrderViewRepository):
+/// lists are newest first, exports oldest first by `(created_at, order_id)`, and a
+/// negative limit or offset counts as zero. Ties the Postgres version leaves to the
+/// database are broken by order ID.
+#[derive(Default, Clone)]
+pub struct InMemoryOrderViewRepository {
+    orders: Orders,
+}
+
+impl InMemoryOrderViewRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert an order view, replacing any with the same order ID
+    pub fn upsert(&self, order: OrderView) {
+        self.orders.write().unwrap().insert(order.order_id, order);
+    }
+
+    pub fn remove(&self, order_id: Uuid) -> Option<OrderView> {
+        self.orders.write().unwrap().remove(&order_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Orders matching `filter`, sorted by `key` and paginated like `LIMIT`/`OFFSET`
+    fn page<K: Ord>(
+        &self,
+        filter: impl Fn(&OrderView) -> bool,
+        key: impl Fn(&OrderView) -> K,
+        limit: i64,
+        offset: i64,
+    ) -> Vec<OrderView> {
+        let mut orders: Vec<OrderView> = self
+            .orders
+            .read()
+            .unwrap()
+            .values()
+            .filter(|order| filter(order))
+            .cloned()
+            .collect();
+        orders.sort_by_key(|order| key(order));
+        orders
+            .into_iter()
+            .skip(offset.max(0) as usize)
+            .take(limit.max(0) as usize)
+            .collect()
+    }
+
+    fn find(&self, predicate: impl Fn(&OrderView) -> bool) -> Option<OrderView> {
+        self.orders
+            .read()
+            .unwrap()
+            .values()
+            .find(|order| predicate(order))
+            .cloned()
+    }
+}
+
+/// Newest first, like `ORDER BY created_at DESC`
+fn newest_first(order: &OrderView) -> (Reverse<chrono::DateTime<chrono::Utc>>, Uuid) {
+    (Reverse(order.created_at), order.order_id)
+}
+
+fn project_all(orders: Vec<OrderView>, fields: &OrderFields) -> Result<Vec<PartialOrderView>, ReadModelError> {
+    orders.iter().map(|order| fields.project(order)).collect()
+}
+
+#[async_trait]
+impl OrderViewRepository for InMemoryOrderViewRepository {
+    async fn get_by_id(&self, order_id: Uuid) -> Result<Option<OrderView>, ReadModelError> {
+        Ok(self.orders.read().unwrap().get(&order_id).cloned())
+    }
+
+    async fn list_by_customer(
+        &self,
+        customer_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrderView>, ReadModelError> {
+        Ok(self.page(|order| order.customer_id == customer_id, newest_first, limit, offset))
+    }
+
+    async fn list_by_status(
+        &self,
+        status: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrderView>, ReadModelError> {
+        Ok(self.page(|order| order.status == status, newest_first, limit, offset))
+    }
+
+    async fn list_recently_updated(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrderView>, ReadModelError> {
+        Ok(self.page(
+            |_| true,
+            |order| (Reverse(order.updated_at), order.order_id),
+            limit,
+            offset,
+        ))
+    }
+
+    async fn search_by_order_number(
+        &self,
+        order_number: &str,
+    ) -> Result<Option<OrderView>, ReadModelError> {
+        Ok(self.find(|order| order.order_number == order_number))
+    }
+
+    async fn get_fields_by_id(
+        &self,
+        order_id: Uuid,
+        fields: &OrderFields,
+    ) -> Result<Option<PartialOrderView>, ReadModelError> {
+        self.get_by_id(order_id)
+            .await?
+            .map(|order| fields.project(&order))
+            .transpose()
+    }
+
+    async fn list_fields_by_customer(
+        &self,
+        customer_id: Uuid,
+        fields: &OrderFields,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PartialOrderView>, ReadModelError> {
+        let orders = self.list_by_customer(customer_id, limit, offset).await?;
+        project_all(orders, fields)
+    }
+
+    async fn list_fields_by_status(
+        &self,
+        status: &str,
+        fields: &OrderFields,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PartialOrderView>, ReadModelError> {
+        let orders = self.list_by_status(status, limit, offset).await?;
+        project_all(orders, fields)
+    }
+
+    async fn search_fields_by_order_number(
+        &self,
+        order_number: &str,
+        fields: &OrderFields,
+    ) -> Result<Option<PartialOrderView>, ReadModelError> {
+        self.find(|order| order.order_number == order_number)
+            .map(|order| fields.project(&order))
+            .transpose()
+    }
+
+    /// Pages are read as the stream is polled, so orders added during an export
+    /// after the current position are included, as with keyset pagination in Postgres
+    fn export(
+        &self,
+        filter: OrderExportFilter,
+        page_size: i64,
+    ) -> BoxStream<'static, Result<Vec<OrderView>, ReadModelError>> {
+        let repository = self.clone();
+
+        stream::unfold(Some(None), move |cursor| {
+            let repository = repository.clone();
+            let filter = filter.clone();
+            async move {
+                let after = cursor?;
+                let orders = repository.page(
+                    |order| {
+                        filter.status.as_ref().is_none_or(|status| &order.status == status)
+                            && filter.from.is_none_or(|from| order.created_at >= from)
+                            && filter.to.is_none_or(|to| order.created_at < to)
+                            && after.is_none_or(|after| (order.created_at, order.order_id) > after)
+                    },
+                    |order| (order.created_at, order.order_id),
+                    page_size,
+                    0,
+                );
+
+                let next = match orders.last() {
+                    Some(last) if orders.len() as i64 >= page_size => {
+                        Some(Some((last.created_at, last.order_id)))
+                    }
+                    Some(_) => None,
+                    None => return None,
+                };
+                Some((Ok(orders), next))
+            }
+        })
+        .boxed()
+    }
+
+    async fn count_by_customer(&self, customer_id: Uuid) -> Result<i64, ReadModelError> {
+        let orders = self.orders.read().unwrap();
+        Ok(orders.values().filter(|order| order.customer_id == customer_id).count() as i64)
+    }
+
+    async fn order_stats(
+        &self,
+        group_by: StatsGroupBy,
+        limit: i64,
+    ) -> Result<Vec<OrderStatsBucket>, ReadModelError> {
+        let mut buckets: HashMap<String, OrderStatsBucket> = HashMap::new();
+        for order in self.orders.read().unwrap().values() {
+            let key = match group_by {
+                StatsGroupBy::Status => order.status.clone(),
+                StatsGroupBy::Day => order.created_at.format("%Y-%m-%d").to_string(),
+                StatsGroupBy::Customer => order.customer_id.to_string(),
+            };
+            let bucket = buckets.entry(key.clone()).or_insert(OrderStatsBucket {
+                key,
+                count: 0,
+                total_amount: 0.0,
+            });
+            bucket.count += 1;
+            bucket.total_amount += order.total_amount;
+        }
+
+        let mut buckets: Vec<OrderStatsBucket> = buckets.into_values().collect();
+        match group_by {
+            StatsGroupBy::Day => buckets.sort_by(|a, b| a.key.cmp(&b.key)),
+            StatsGroupBy::Status | StatsGroupBy::Customer => {
+                buckets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)))
+            }
+        }
+        buckets.truncate(limit.max(0) as usize);
+        Ok(buckets)
+    }
+
+    async fn order_totals(&self) -> Result<OrderTotals, ReadModelError> {
+        let orders = self.orders.read().unwrap();
+        Ok(OrderTotals {
+            count: orders.len() as i64,
+            total_amount: orders.values().map(|order| order.total_amount).sum(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use futures::TryStreamExt;
+
+    fn order(customer_id: Uuid, status: &str, minutes: i64, total_amount: f64) -> OrderView {
+        let created_at = Utc.with_ymd_and_hms(2024, 1, 1, 23, 0, 0).unwrap() + Duration::minutes(minutes);
+        OrderView {
+            order_id: Uuid::new_v4(),
+            customer_id,
+            order_number: format!("ORD-{}", minutes),
+            status: status.to_string(),
+            total_amount,
+            currency: "USD".to_string(),
+            items: serde_json::json!([]),
+            shipping_address: None,
+            tracking_number: None,
+            carrier: None,
+            customer_name: None,
+            customer_email: None,
+            created_at,
+            updated_at: created_at,
+            version: 1,
+        }
+    }
+
+    fn repository(customer_id: Uuid) -> InMemoryOrderViewRepository {
+        let repository = InMemoryOrderViewRepository::new();
+        repository.upsert(order(customer_id, "CREATED", 0, 10.0));
+        repository.upsert(order(customer_id, "SHIPPED", 30, 20.0));
+        repository.upsert(order(customer_id, "CREATED", 90, 5.0));
+        repository.upsert(order(Uuid::new_v4(), "CREATED", 120, 1.0));
+        repository
+    }
+
+    #[tokio::test]
+    async fn test_lists_are_newest_first_and_paginated() {
+        let customer_id = Uuid::new_v4();
+        let repository = repository(customer_id);
+
+        let page = repository.list_by_customer(customer_id, 2, 1).await.unwrap();
+        let numbers: Vec<&str> = page.iter().map(|o| o.order_number.as_str()).collect();
+        assert_eq!(numbers, ["ORD-30", "ORD-0"]);
+        assert_eq!(repository.count_by_customer(customer_id).await.unwrap(), 3);
+        assert!(repository.list_by_status("CREATED", -1, 0).await.unwrap().is_empty());
+
+        let fields = OrderFields::parse("status").unwrap();
+        let partial = repository
+            .search_fields_by_order_number("ORD-30", &fields)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(partial["status"], "SHIPPED");
+    }
+
+    #[tokio::test]
+    async fn test_export_pages_oldest_first() {
+        let repository = repository(Uuid::new_v4());
+        let filter = OrderExportFilter {
+            status: Some("CREATED".to_string()),
+            ..OrderExportFilter::default()
+        };
+
+        let pages: Vec<Vec<OrderView>> = repository.export(filter, 2).try_collect().await.unwrap();
+        let numbers: Vec<Vec<&str>> = pages
+            .iter()
+            .map(|page| page.iter().map(|o| o.order_number.as_str()).collect())
+            .collect();
+        assert_eq!(numbers, [vec!["ORD-0", "ORD-90"], vec!["ORD-120"]]);
+    }
+
+    #[tokio::test]
+    async fn test_order_stats_buckets() {
+        let repository = repository(Uuid::new_v4());
+
+        let by_status = repository.order_stats(StatsGroupBy::Status, 10).await.unwrap();
+        assert_eq!(by_status[0].key, "CREATED");
+        assert_eq!(by_status[0].count, 3);
+        assert_eq!(by_status[0].total_amount, 16.0);
+
+        let by_day = repository.order_stats(StatsGroupBy::Day, 10).await.unwrap();
+        let days: Vec<&str> = by_day.iter().map(|b| b.key.as_str()).collect();
+        assert_eq!(days, ["2024-01-01", "2024-01-02"]);
+
+        // Totals cover every order, including those in buckets cut by the limit
+        let top = repository.order_stats(StatsGroupBy::Status, 1).await.unwrap();
+        let totals = repository.order_totals().await.unwrap();
+        let all: i64 = by_status.iter().map(|b| b.count).sum();
+        assert_eq!(top.len(), 1);
+        assert_eq!(totals.count, all);
+        assert!(totals.count > top[0].count);
+    }
+}
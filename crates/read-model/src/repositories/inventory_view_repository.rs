@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::ReadModelError;
+
+/// Read model representation of the stock level of one SKU
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InventoryView {
+    pub sku: String,
+    pub product_id: Uuid,
+    pub on_hand: i64,
+    pub reserved: i64,
+    pub available: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Repository for querying inventory views
+#[async_trait]
+pub trait InventoryViewRepository: Send + Sync {
+    /// Get the stock level of a SKU
+    async fn get_by_sku(&self, sku: &str) -> Result<Option<InventoryView>, ReadModelError>;
+}
+
+/// PostgreSQL implementation of InventoryViewRepository
+pub struct PostgresInventoryViewRepository {
+    pool: PgPool,
+}
+
+impl PostgresInventoryViewRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl InventoryViewRepository for PostgresInventoryViewRepository {
+    async fn get_by_sku(&self, sku: &str) -> Result<Option<InventoryView>, ReadModelError> {
+        let view = sqlx::query_as::<_, InventoryView>(
+            r#"
+            SELECT sku, product_id, on_hand, reserved, available, updated_at
+            FROM inventory_views
+            WHERE sku = $1
+            "#,
+        )
+        .bind(sku)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(view)
+    }
+}
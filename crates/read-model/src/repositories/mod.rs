@@ -1,3 +1,48 @@
+pub mod business_metrics_repository;
+pub mod event_log_repository;
+pub mod in_memory_order_view_repository;
+pub mod inventory_view_repository;
+pub mod order_search_repository;
+pub mod order_view_decorators;
 pub mod order_view_repository;
+pub mod payment_view_repository;
+pub mod product_view_repository;
+pub mod projection_error_repository;
+pub mod read_replica;
+pub mod saga_view_repository;
+pub mod timeline_repository;
 
-pub use order_view_repository::{OrderView, OrderViewRepository, PostgresOrderViewRepository};
+pub use business_metrics_repository::{
+    BusinessKpis, BusinessMetricsRepository, BusinessMetricsTotals, CurrencyTotals,
+    PostgresBusinessMetricsRepository,
+};
+pub use event_log_repository::{
+    EventCursor, EventLogRepository, ParseEventCursorError, PositionedEvent, PostgresEventLogRepository,
+};
+pub use in_memory_order_view_repository::InMemoryOrderViewRepository;
+pub use inventory_view_repository::{
+    InventoryView, InventoryViewRepository, PostgresInventoryViewRepository,
+};
+pub use order_search_repository::{
+    FacetBucket, OrderSearchQuery, OrderSearchRepository, OrderSearchResults, OrderSearchSort,
+    ORDER_FACETS,
+};
+pub use order_view_decorators::{CachedOrderViewRepository, InstrumentedOrderViewRepository};
+pub use order_view_repository::{
+    OrderExportFilter, OrderField, OrderFields, OrderStatsBucket, OrderTotals, OrderView,
+    OrderViewRepository, PartialOrderView, PostgresOrderViewRepository, StatsGroupBy,
+};
+pub use payment_view_repository::{
+    PaymentHistoryEntry, PaymentView, PaymentViewRepository, PostgresPaymentViewRepository,
+};
+pub use product_view_repository::{
+    PostgresProductViewRepository, ProductView, ProductViewRepository,
+};
+pub use projection_error_repository::{
+    PostgresProjectionErrorRepository, ProjectionError, ProjectionErrorRepository,
+};
+pub use read_replica::ReadReplica;
+pub use saga_view_repository::{
+    PostgresSagaViewRepository, SagaTypeStats, SagaView, SagaViewRepository,
+};
+pub use timeline_repository::{PostgresTimelineRepository, TimelineEntry, TimelineRepository};
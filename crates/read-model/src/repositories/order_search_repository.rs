@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use super::order_view_repository::OrderView;
+use crate::ReadModelError;
+
+/// Fields orders can be faceted on, with their counts returned next to the results
+pub const ORDER_FACETS: [&str; 3] = ["status", "currency", "carrier"];
+
+/// Order of search results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderSearchSort {
+    /// Best text match first; newest first without a text query
+    Relevance,
+    #[default]
+    Newest,
+    Oldest,
+    AmountDesc,
+    AmountAsc,
+}
+
+/// Filters of an order search; facet filters take any of several values
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderSearchQuery {
+    /// Free text matched against order number, customer name and email, and tracking number
+    pub text: Option<String>,
+    pub statuses: Vec<String>,
+    pub currencies: Vec<String>,
+    pub carriers: Vec<String>,
+    pub customer_id: Option<Uuid>,
+    /// Orders with at least one item of this SKU
+    pub sku: Option<String>,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    pub sort: OrderSearchSort,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// A facet value and how many matching orders have it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FacetBucket {
+    pub value: String,
+    pub count: u64,
+}
+
+/// A page of matching orders, the total number of matches and the facet counts
+///
+/// Each facet is counted with every filter applied except its own, so the counts
+/// show how many orders selecting another value of that facet would return.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrderSearchResults {
+    pub total: u64,
+    pub orders: Vec<OrderView>,
+    /// Buckets by facet field, busiest first
+    pub facets: BTreeMap<String, Vec<FacetBucket>>,
+}
+
+/// Full-text and faceted search over orders, served from a search index rather than
+/// `order_views`
+#[async_trait]
+pub trait OrderSearchRepository: Send + Sync {
+    async fn search(&self, query: &OrderSearchQuery) -> Result<OrderSearchResults, ReadModelError>;
+}
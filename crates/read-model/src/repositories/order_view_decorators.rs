@@ -0,0 +1,415 @@
+use async_trait::async_trait;
+use common::metrics;
+use futures::stream::BoxStream;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+use super::order_view_repository::{
+    OrderExportFilter, OrderFields, OrderStatsBucket, OrderTotals, OrderView, OrderViewRepository,
+    PartialOrderView, StatsGroupBy,
+};
+use crate::cache::OrderViewCache;
+use crate::ReadModelError;
+
+/// Serves single-order lookups from a cache, filling it from the wrapped repository
+///
+/// `get_by_id` and `get_fields_by_id` read through the cache; orders found by ID or
+/// order number are cached. Lists, counts and exports always go to the repository.
+pub struct CachedOrderViewRepository {
+    inner: Arc<dyn OrderViewRepository>,
+    cache: Arc<dyn OrderViewCache>,
+}
+
+impl CachedOrderViewRepository {
+    pub fn new(inner: Arc<dyn OrderViewRepository>, cache: Arc<dyn OrderViewCache>) -> Self {
+        Self { inner, cache }
+    }
+
+    async fn cached(&self, order_id: Uuid) -> Option<OrderView> {
+        let cached = self.cache.get_order(order_id).await;
+        metrics::record_cache_request("order", cached.is_some());
+        cached
+    }
+
+    async fn fill(&self, order: Option<OrderView>) -> Option<OrderView> {
+        if let Some(order) = &order {
+            self.cache.set_order(order).await;
+        }
+        order
+    }
+}
+
+#[async_trait]
+impl OrderViewRepository for CachedOrderViewRepository {
+    async fn get_by_id(&self, order_id: Uuid) -> Result<Option<OrderView>, ReadModelError> {
+        if let Some(order) = self.cached(order_id).await {
+            return Ok(Some(order));
+        }
+        let order = self.inner.get_by_id(order_id).await?;
+        Ok(self.fill(order).await)
+    }
+
+    async fn list_by_customer(
+        &self,
+        customer_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrderView>, ReadModelError> {
+        self.inner.list_by_customer(customer_id, limit, offset).await
+    }
+
+    async fn list_by_status(
+        &self,
+        status: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrderView>, ReadModelError> {
+        self.inner.list_by_status(status, limit, offset).await
+    }
+
+    async fn list_recently_updated(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrderView>, ReadModelError> {
+        self.inner.list_recently_updated(limit, offset).await
+    }
+
+    async fn search_by_order_number(
+        &self,
+        order_number: &str,
+    ) -> Result<Option<OrderView>, ReadModelError> {
+        let order = self.inner.search_by_order_number(order_number).await?;
+        Ok(self.fill(order).await)
+    }
+
+    /// Projected from the cached copy when there is one; partial reads are not cached
+    async fn get_fields_by_id(
+        &self,
+        order_id: Uuid,
+        fields: &OrderFields,
+    ) -> Result<Option<PartialOrderView>, ReadModelError> {
+        if let Some(order) = self.cached(order_id).await {
+            return fields.project(&order).map(Some);
+        }
+        self.inner.get_fields_by_id(order_id, fields).await
+    }
+
+    async fn list_fields_by_customer(
+        &self,
+        customer_id: Uuid,
+        fields: &OrderFields,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PartialOrderView>, ReadModelError> {
+        self.inner
+            .list_fields_by_customer(customer_id, fields, limit, offset)
+            .await
+    }
+
+    async fn list_fields_by_status(
+        &self,
+        status: &str,
+        fields: &OrderFields,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PartialOrderView>, ReadModelError> {
+        self.inner
+            .list_fields_by_status(status, fields, limit, offset)
+            .await
+    }
+
+    async fn search_fields_by_order_number(
+        &self,
+        order_number: &str,
+        fields: &OrderFields,
+    ) -> Result<Option<PartialOrderView>, ReadModelError> {
+        self.inner
+            .search_fields_by_order_number(order_number, fields)
+            .await
+    }
+
+    fn export(
+        &self,
+        filter: OrderExportFilter,
+        page_size: i64,
+    ) -> BoxStream<'static, Result<Vec<OrderView>, ReadModelError>> {
+        self.inner.export(filter, page_size)
+    }
+
+    async fn count_by_customer(&self, customer_id: Uuid) -> Result<i64, ReadModelError> {
+        self.inner.count_by_customer(customer_id).await
+    }
+
+    async fn order_stats(
+        &self,
+        group_by: StatsGroupBy,
+        limit: i64,
+    ) -> Result<Vec<OrderStatsBucket>, ReadModelError> {
+        self.inner.order_stats(group_by, limit).await
+    }
+
+    async fn order_totals(&self) -> Result<OrderTotals, ReadModelError> {
+        self.inner.order_totals().await
+    }
+}
+
+/// Records the count, outcome and duration of every query to the wrapped repository
+///
+/// Queries are labelled `orders.<method>` in `cqrs_queries_total` and
+/// `cqrs_query_duration_seconds`. Exports are streamed page by page and not timed.
+pub struct InstrumentedOrderViewRepository {
+    inner: Arc<dyn OrderViewRepository>,
+}
+
+impl InstrumentedOrderViewRepository {
+    pub fn new(inner: Arc<dyn OrderViewRepository>) -> Self {
+        Self { inner }
+    }
+}
+
+async fn timed<T>(
+    query_type: &str,
+    query: impl Future<Output = Result<T, ReadModelError>>,
+) -> Result<T, ReadModelError> {
+    let start = Instant::now();
+    let result = query.await;
+    metrics::record_query(query_type, result.is_ok(), start.elapsed().as_secs_f64());
+    result
+}
+
+#[async_trait]
+impl OrderViewRepository for InstrumentedOrderViewRepository {
+    async fn get_by_id(&self, order_id: Uuid) -> Result<Option<OrderView>, ReadModelError> {
+        timed("orders.get_by_id", self.inner.get_by_id(order_id)).await
+    }
+
+    async fn list_by_customer(
+        &self,
+        customer_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrderView>, ReadModelError> {
+        timed(
+            "orders.list_by_customer",
+            self.inner.list_by_customer(customer_id, limit, offset),
+        )
+        .await
+    }
+
+    async fn list_by_status(
+        &self,
+        status: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrderView>, ReadModelError> {
+        timed(
+            "orders.list_by_status",
+            self.inner.list_by_status(status, limit, offset),
+        )
+        .await
+    }
+
+    async fn list_recently_updated(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrderView>, ReadModelError> {
+        timed(
+            "orders.list_recently_updated",
+            self.inner.list_recently_updated(limit, offset),
+        )
+        .await
+    }
+
+    async fn search_by_order_number(
+        &self,
+        order_number: &str,
+    ) -> Result<Option<OrderView>, ReadModelError> {
+        timed(
+            "orders.search_by_order_number",
+            self.inner.search_by_order_number(order_number),
+        )
+        .await
+    }
+
+    async fn get_fields_by_id(
+        &self,
+        order_id: Uuid,
+        fields: &OrderFields,
+    ) -> Result<Option<PartialOrderView>, ReadModelError> {
+        timed(
+            "orders.get_fields_by_id",
+            self.inner.get_fields_by_id(order_id, fields),
+        )
+        .await
+    }
+
+    async fn list_fields_by_customer(
+        &self,
+        customer_id: Uuid,
+        fields: &OrderFields,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PartialOrderView>, ReadModelError> {
+        timed(
+            "orders.list_fields_by_customer",
+            self.inner
+                .list_fields_by_customer(customer_id, fields, limit, offset),
+        )
+        .await
+    }
+
+    async fn list_fields_by_status(
+        &self,
+        status: &str,
+        fields: &OrderFields,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PartialOrderView>, ReadModelError> {
+        timed(
+            "orders.list_fields_by_status",
+            self.inner.list_fields_by_status(status, fields, limit, offset),
+        )
+        .await
+    }
+
+    async fn search_fields_by_order_number(
+        &self,
+        order_number: &str,
+        fields: &OrderFields,
+    ) -> Result<Option<PartialOrderView>, ReadModelError> {
+        timed(
+            "orders.search_fields_by_order_number",
+            self.inner.search_fields_by_order_number(order_number, fields),
+        )
+        .await
+    }
+
+    fn export(
+        &self,
+        filter: OrderExportFilter,
+        page_size: i64,
+    ) -> BoxStream<'static, Result<Vec<OrderView>, ReadModelError>> {
+        self.inner.export(filter, page_size)
+    }
+
+    async fn count_by_customer(&self, customer_id: Uuid) -> Result<i64, ReadModelError> {
+        timed(
+            "orders.count_by_customer",
+            self.inner.count_by_customer(customer_id),
+        )
+        .await
+    }
+
+    async fn order_stats(
+        &self,
+        group_by: StatsGroupBy,
+        limit: i64,
+    ) -> Result<Vec<OrderStatsBucket>, ReadModelError> {
+        timed("orders.order_stats", self.inner.order_stats(group_by, limit)).await
+    }
+
+    async fn order_totals(&self) -> Result<OrderTotals, ReadModelError> {
+        timed("orders.order_totals", self.inner.order_totals()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mockall::mock;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    mock! {
+        Orders {}
+
+        #[async_trait]
+        impl OrderViewRepository for Orders {
+            async fn get_by_id(&self, order_id: Uuid) -> Result<Option<OrderView>, ReadModelError>;
+            async fn list_by_customer(&self, customer_id: Uuid, limit: i64, offset: i64) -> Result<Vec<OrderView>, ReadModelError>;
+            async fn list_by_status(&self, status: &str, limit: i64, offset: i64) -> Result<Vec<OrderView>, ReadModelError>;
+            async fn list_recently_updated(&self, limit: i64, offset: i64) -> Result<Vec<OrderView>, ReadModelError>;
+            async fn search_by_order_number(&self, order_number: &str) -> Result<Option<OrderView>, ReadModelError>;
+            async fn get_fields_by_id(&self, order_id: Uuid, fields: &OrderFields) -> Result<Option<PartialOrderView>, ReadModelError>;
+            async fn list_fields_by_customer(&self, customer_id: Uuid, fields: &OrderFields, limit: i64, offset: i64) -> Result<Vec<PartialOrderView>, ReadModelError>;
+            async fn list_fields_by_status(&self, status: &str, fields: &OrderFields, limit: i64, offset: i64) -> Result<Vec<PartialOrderView>, ReadModelError>;
+            async fn search_fields_by_order_number(&self, order_number: &str, fields: &OrderFields) -> Result<Option<PartialOrderView>, ReadModelError>;
+            fn export(&self, filter: OrderExportFilter, page_size: i64) -> BoxStream<'static, Result<Vec<OrderView>, ReadModelError>>;
+            async fn count_by_customer(&self, customer_id: Uuid) -> Result<i64, ReadModelError>;
+            async fn order_stats(&self, group_by: StatsGroupBy, limit: i64) -> Result<Vec<OrderStatsBucket>, ReadModelError>;
+            async fn order_totals(&self) -> Result<OrderTotals, ReadModelError>;
+        }
+    }
+
+    #[derive(Default)]
+    struct MapCache(Mutex<HashMap<Uuid, OrderView>>);
+
+    #[async_trait]
+    impl OrderViewCache for MapCache {
+        async fn get_order(&self, order_id: Uuid) -> Option<OrderView> {
+            self.0.lock().unwrap().get(&order_id).cloned()
+        }
+
+        async fn set_order(&self, order: &OrderView) {
+            self.0.lock().unwrap().insert(order.order_id, order.clone());
+        }
+    }
+
+    fn order() -> OrderView {
+        OrderView {
+            order_id: Uuid::new_v4(),
+            customer_id: Uuid::new_v4(),
+            order_number: "ORD-123".to_string(),
+            status: "CREATED".to_string(),
+            total_amount: 25.0,
+            currency: "USD".to_string(),
+            items: serde_json::json!([]),
+            shipping_address: None,
+            tracking_number: None,
+            carrier: None,
+            customer_name: None,
+            customer_email: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_repository_reads_through_once() {
+        let order = order();
+        let order_id = order.order_id;
+        let mut inner = MockOrders::new();
+        let stored = order.clone();
+        inner
+            .expect_get_by_id()
+            .times(1)
+            .returning(move |_| Ok(Some(stored.clone())));
+        let repository = CachedOrderViewRepository::new(Arc::new(inner), Arc::new(MapCache::default()));
+
+        assert_eq!(repository.get_by_id(order_id).await.unwrap().unwrap().version, 1);
+        assert_eq!(repository.get_by_id(order_id).await.unwrap().unwrap().version, 1);
+
+        let fields = OrderFields::parse("status").unwrap();
+        let partial = repository.get_fields_by_id(order_id, &fields).await.unwrap().unwrap();
+        assert_eq!(partial["status"], "CREATED");
+    }
+
+    #[tokio::test]
+    async fn test_instrumented_repository_passes_errors_through() {
+        let mut inner = MockOrders::new();
+        inner
+            .expect_count_by_customer()
+            .returning(|_| Err(ReadModelError::CacheError("down".to_string())));
+        let repository = InstrumentedOrderViewRepository::new(Arc::new(inner));
+
+        assert!(repository.count_by_customer(Uuid::new_v4()).await.is_err());
+        let gathered = metrics::gather_metrics().unwrap();
+        assert!(gathered.contains("orders.count_by_customer"));
+    }
+}
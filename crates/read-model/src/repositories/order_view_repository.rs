@@ -1,13 +1,18 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures::stream::{self, BoxStream, StreamExt};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, PgPool, Row};
+use std::sync::Arc;
 use uuid::Uuid;
 
+use super::read_replica::ReadReplica;
 use crate::ReadModelError;
 
 /// Read model representation of an order
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct OrderView {
     pub order_id: Uuid,
     pub customer_id: Uuid,
@@ -19,11 +24,231 @@ pub struct OrderView {
     pub shipping_address: Option<serde_json::Value>,
     pub tracking_number: Option<String>,
     pub carrier: Option<String>,
+    /// Copied from `customer_views`; `None` until the customer is known
+    pub customer_name: Option<String>,
+    pub customer_email: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub version: i64,
 }
 
+/// Dimension used to bucket order statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsGroupBy {
+    Status,
+    Day,
+    Customer,
+}
+
+impl StatsGroupBy {
+    /// SQL expression producing the bucket key
+    fn key_expr(&self) -> &'static str {
+        match self {
+            StatsGroupBy::Status => "status",
+            StatsGroupBy::Day => "to_char(date_trunc('day', created_at), 'YYYY-MM-DD')",
+            StatsGroupBy::Customer => "customer_id::text",
+        }
+    }
+
+    /// Ordering of buckets: chronological for days, busiest first otherwise
+    fn order_by(&self) -> &'static str {
+        match self {
+            StatsGroupBy::Day => "key ASC",
+            StatsGroupBy::Status | StatsGroupBy::Customer => "count DESC, key ASC",
+        }
+    }
+}
+
+/// Aggregated order count and amount for a single bucket
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OrderStatsBucket {
+    pub key: String,
+    pub count: i64,
+    pub total_amount: f64,
+}
+
+/// Order count and amount sum across all orders
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct OrderTotals {
+    pub count: i64,
+    pub total_amount: f64,
+}
+
+/// Column of an order view that clients can select with `?fields=`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderField {
+    OrderId,
+    CustomerId,
+    OrderNumber,
+    Status,
+    TotalAmount,
+    Currency,
+    Items,
+    ShippingAddress,
+    TrackingNumber,
+    Carrier,
+    CustomerName,
+    CustomerEmail,
+    CreatedAt,
+    UpdatedAt,
+    Version,
+}
+
+impl OrderField {
+    pub const ALL: [OrderField; 15] = [
+        OrderField::OrderId,
+        OrderField::CustomerId,
+        OrderField::OrderNumber,
+        OrderField::Status,
+        OrderField::TotalAmount,
+        OrderField::Currency,
+        OrderField::Items,
+        OrderField::ShippingAddress,
+        OrderField::TrackingNumber,
+        OrderField::Carrier,
+        OrderField::CustomerName,
+        OrderField::CustomerEmail,
+        OrderField::CreatedAt,
+        OrderField::UpdatedAt,
+        OrderField::Version,
+    ];
+
+    /// Column name, which is also the field name in `OrderView` JSON
+    pub fn name(&self) -> &'static str {
+        match self {
+            OrderField::OrderId => "order_id",
+            OrderField::CustomerId => "customer_id",
+            OrderField::OrderNumber => "order_number",
+            OrderField::Status => "status",
+            OrderField::TotalAmount => "total_amount",
+            OrderField::Currency => "currency",
+            OrderField::Items => "items",
+            OrderField::ShippingAddress => "shipping_address",
+            OrderField::TrackingNumber => "tracking_number",
+            OrderField::Carrier => "carrier",
+            OrderField::CustomerName => "customer_name",
+            OrderField::CustomerEmail => "customer_email",
+            OrderField::CreatedAt => "created_at",
+            OrderField::UpdatedAt => "updated_at",
+            OrderField::Version => "version",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|field| field.name() == name)
+    }
+
+    /// Read this column from a row as it would serialize in an `OrderView`
+    fn decode(&self, row: &PgRow) -> Result<serde_json::Value, ReadModelError> {
+        let name = self.name();
+        let value = match self {
+            OrderField::OrderId | OrderField::CustomerId => {
+                serde_json::to_value(row.try_get::<Uuid, _>(name)?)?
+            }
+            OrderField::OrderNumber | OrderField::Status | OrderField::Currency => {
+                serde_json::Value::String(row.try_get(name)?)
+            }
+            OrderField::TotalAmount => serde_json::to_value(row.try_get::<f64, _>(name)?)?,
+            OrderField::Items => row.try_get(name)?,
+            OrderField::ShippingAddress => row
+                .try_get::<Option<serde_json::Value>, _>(name)?
+                .unwrap_or(serde_json::Value::Null),
+            OrderField::TrackingNumber
+            | OrderField::Carrier
+            | OrderField::CustomerName
+            | OrderField::CustomerEmail => {
+                serde_json::to_value(row.try_get::<Option<String>, _>(name)?)?
+            }
+            OrderField::CreatedAt | OrderField::UpdatedAt => {
+                serde_json::to_value(row.try_get::<DateTime<Utc>, _>(name)?)?
+            }
+            OrderField::Version => serde_json::to_value(row.try_get::<i64, _>(name)?)?,
+        };
+        Ok(value)
+    }
+}
+
+/// An order view with only some of its fields, keyed by field name
+pub type PartialOrderView = serde_json::Map<String, serde_json::Value>;
+
+/// Non-empty set of order fields to return
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderFields(Vec<OrderField>);
+
+impl OrderFields {
+    /// Parse a comma-separated list of field names, e.g. `order_id,status,total_amount`
+    ///
+    /// Returns the unknown name on failure.
+    pub fn parse(list: &str) -> Result<Self, String> {
+        let mut fields = Vec::new();
+        for name in list.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            let field = OrderField::from_name(name).ok_or_else(|| name.to_string())?;
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+        if fields.is_empty() {
+            return Err(list.to_string());
+        }
+        Ok(Self(fields))
+    }
+
+    pub fn fields(&self) -> &[OrderField] {
+        &self.0
+    }
+
+    fn select_list(&self) -> String {
+        self.0
+            .iter()
+            .map(|field| field.name())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn read_row(&self, row: &PgRow) -> Result<PartialOrderView, ReadModelError> {
+        self.0
+            .iter()
+            .map(|field| Ok((field.name().to_string(), field.decode(row)?)))
+            .collect()
+    }
+
+    /// Keep only the selected fields of a full order view
+    pub fn project(&self, order: &OrderView) -> Result<PartialOrderView, ReadModelError> {
+        let mut full = match serde_json::to_value(order)? {
+            serde_json::Value::Object(map) => map,
+            _ => PartialOrderView::new(),
+        };
+        Ok(self
+            .0
+            .iter()
+            .filter_map(|field| {
+                full.remove(field.name())
+                    .map(|value| (field.name().to_string(), value))
+            })
+            .collect())
+    }
+}
+
+/// Orders to export; all bounds are optional
+#[derive(Debug, Clone, Default)]
+pub struct OrderExportFilter {
+    pub status: Option<String>,
+    /// Created at or after
+    pub from: Option<DateTime<Utc>>,
+    /// Created before
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Position of an export in `(created_at, order_id)` order
+#[derive(Debug, Clone, Copy)]
+enum ExportCursor {
+    Start,
+    After(DateTime<Utc>, Uuid),
+    Done,
+}
+
 /// Repository for querying order views
 #[async_trait]
 pub trait OrderViewRepository: Send + Sync {
@@ -46,24 +271,103 @@ pub trait OrderViewRepository: Send + Sync {
         offset: i64,
     ) -> Result<Vec<OrderView>, ReadModelError>;
 
+    /// Most recently updated orders first
+    async fn list_recently_updated(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrderView>, ReadModelError>;
+
     /// Search orders by order number
     async fn search_by_order_number(
         &self,
         order_number: &str,
     ) -> Result<Option<OrderView>, ReadModelError>;
 
+    /// Get the selected fields of a single order, selecting only those columns
+    async fn get_fields_by_id(
+        &self,
+        order_id: Uuid,
+        fields: &OrderFields,
+    ) -> Result<Option<PartialOrderView>, ReadModelError>;
+
+    /// List the selected fields of a customer's orders
+    async fn list_fields_by_customer(
+        &self,
+        customer_id: Uuid,
+        fields: &OrderFields,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PartialOrderView>, ReadModelError>;
+
+    /// List the selected fields of orders with a status
+    async fn list_fields_by_status(
+        &self,
+        status: &str,
+        fields: &OrderFields,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PartialOrderView>, ReadModelError>;
+
+    /// Search by order number, returning only the selected fields
+    async fn search_fields_by_order_number(
+        &self,
+        order_number: &str,
+        fields: &OrderFields,
+    ) -> Result<Option<PartialOrderView>, ReadModelError>;
+
+    /// Orders matching `filter`, oldest first, in pages of `page_size`
+    ///
+    /// Pages are fetched by keyset pagination as the stream is polled, so an export
+    /// never holds more than one page in memory.
+    fn export(
+        &self,
+        filter: OrderExportFilter,
+        page_size: i64,
+    ) -> BoxStream<'static, Result<Vec<OrderView>, ReadModelError>>;
+
     /// Count total orders for a customer
     async fn count_by_customer(&self, customer_id: Uuid) -> Result<i64, ReadModelError>;
+
+    /// Order counts and amount sums grouped by the given dimension
+    async fn order_stats(
+        &self,
+        group_by: StatsGroupBy,
+        limit: i64,
+    ) -> Result<Vec<OrderStatsBucket>, ReadModelError>;
+
+    /// Order count and amount sum over every order, not only the buckets `order_stats` returns
+    async fn order_totals(&self) -> Result<OrderTotals, ReadModelError>;
 }
 
 /// PostgreSQL implementation of OrderViewRepository
+///
+/// Projections write order views to the primary; with a read replica configured,
+/// queries go to the replica whenever it is within its allowed lag.
 pub struct PostgresOrderViewRepository {
     pool: PgPool,
+    replica: Option<Arc<ReadReplica>>,
 }
 
 impl PostgresOrderViewRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            replica: None,
+        }
+    }
+
+    /// Route queries to `replica` while it keeps up with the primary
+    pub fn with_read_replica(mut self, replica: ReadReplica) -> Self {
+        self.replica = Some(Arc::new(replica));
+        self
+    }
+
+    async fn reader(&self) -> &PgPool {
+        match &self.replica {
+            Some(replica) => replica.route(&self.pool).await,
+            None => &self.pool,
+        }
     }
 }
 
@@ -75,13 +379,14 @@ impl OrderViewRepository for PostgresOrderViewRepository {
             SELECT
                 order_id, customer_id, order_number, status,
                 total_amount, currency, items, shipping_address,
-                tracking_number, carrier, created_at, updated_at, version
+                tracking_number, carrier, customer_name, customer_email,
+                created_at, updated_at, version
             FROM order_views
             WHERE order_id = $1
             "#,
         )
         .bind(order_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader().await)
         .await?;
 
         Ok(order)
@@ -98,7 +403,8 @@ impl OrderViewRepository for PostgresOrderViewRepository {
             SELECT
                 order_id, customer_id, order_number, status,
                 total_amount, currency, items, shipping_address,
-                tracking_number, carrier, created_at, updated_at, version
+                tracking_number, carrier, customer_name, customer_email,
+                created_at, updated_at, version
             FROM order_views
             WHERE customer_id = $1
             ORDER BY created_at DESC
@@ -108,7 +414,7 @@ impl OrderViewRepository for PostgresOrderViewRepository {
         .bind(customer_id)
         .bind(limit)
         .bind(offset)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader().await)
         .await?;
 
         Ok(orders)
@@ -125,7 +431,8 @@ impl OrderViewRepository for PostgresOrderViewRepository {
             SELECT
                 order_id, customer_id, order_number, status,
                 total_amount, currency, items, shipping_address,
-                tracking_number, carrier, created_at, updated_at, version
+                tracking_number, carrier, customer_name, customer_email,
+                created_at, updated_at, version
             FROM order_views
             WHERE status = $1
             ORDER BY created_at DESC
@@ -135,7 +442,32 @@ impl OrderViewRepository for PostgresOrderViewRepository {
         .bind(status)
         .bind(limit)
         .bind(offset)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader().await)
+        .await?;
+
+        Ok(orders)
+    }
+
+    async fn list_recently_updated(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrderView>, ReadModelError> {
+        let orders = sqlx::query_as::<_, OrderView>(
+            r#"
+            SELECT
+                order_id, customer_id, order_number, status,
+                total_amount, currency, items, shipping_address,
+                tracking_number, carrier, customer_name, customer_email,
+                created_at, updated_at, version
+            FROM order_views
+            ORDER BY updated_at DESC
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.reader().await)
         .await?;
 
         Ok(orders)
@@ -150,18 +482,169 @@ impl OrderViewRepository for PostgresOrderViewRepository {
             SELECT
                 order_id, customer_id, order_number, status,
                 total_amount, currency, items, shipping_address,
-                tracking_number, carrier, created_at, updated_at, version
+                tracking_number, carrier, customer_name, customer_email,
+                created_at, updated_at, version
             FROM order_views
             WHERE order_number = $1
             "#,
         )
         .bind(order_number)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader().await)
         .await?;
 
         Ok(order)
     }
 
+    async fn get_fields_by_id(
+        &self,
+        order_id: Uuid,
+        fields: &OrderFields,
+    ) -> Result<Option<PartialOrderView>, ReadModelError> {
+        // Only fixed column names from OrderField are interpolated
+        let query = format!(
+            "SELECT {} FROM order_views WHERE order_id = $1",
+            fields.select_list()
+        );
+        let row = sqlx::query(&query)
+            .bind(order_id)
+            .fetch_optional(self.reader().await)
+            .await?;
+
+        row.map(|row| fields.read_row(&row)).transpose()
+    }
+
+    async fn list_fields_by_customer(
+        &self,
+        customer_id: Uuid,
+        fields: &OrderFields,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PartialOrderView>, ReadModelError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM order_views
+            WHERE customer_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            fields.select_list()
+        );
+        let rows = sqlx::query(&query)
+            .bind(customer_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(self.reader().await)
+            .await?;
+
+        rows.iter().map(|row| fields.read_row(row)).collect()
+    }
+
+    async fn list_fields_by_status(
+        &self,
+        status: &str,
+        fields: &OrderFields,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PartialOrderView>, ReadModelError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM order_views
+            WHERE status = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            fields.select_list()
+        );
+        let rows = sqlx::query(&query)
+            .bind(status)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(self.reader().await)
+            .await?;
+
+        rows.iter().map(|row| fields.read_row(row)).collect()
+    }
+
+    async fn search_fields_by_order_number(
+        &self,
+        order_number: &str,
+        fields: &OrderFields,
+    ) -> Result<Option<PartialOrderView>, ReadModelError> {
+        let query = format!(
+            "SELECT {} FROM order_views WHERE order_number = $1",
+            fields.select_list()
+        );
+        let row = sqlx::query(&query)
+            .bind(order_number)
+            .fetch_optional(self.reader().await)
+            .await?;
+
+        row.map(|row| fields.read_row(&row)).transpose()
+    }
+
+    fn export(
+        &self,
+        filter: OrderExportFilter,
+        page_size: i64,
+    ) -> BoxStream<'static, Result<Vec<OrderView>, ReadModelError>> {
+        let pool = self.pool.clone();
+        let replica = self.replica.clone();
+
+        stream::try_unfold(ExportCursor::Start, move |cursor| {
+            let pool = pool.clone();
+            let replica = replica.clone();
+            let filter = filter.clone();
+            async move {
+                let (after_created_at, after_order_id) = match cursor {
+                    ExportCursor::Start => (None, None),
+                    ExportCursor::After(created_at, order_id) => (Some(created_at), Some(order_id)),
+                    ExportCursor::Done => return Ok(None),
+                };
+                let reader = match &replica {
+                    Some(replica) => replica.route(&pool).await,
+                    None => &pool,
+                };
+
+                let orders = sqlx::query_as::<_, OrderView>(
+                    r#"
+                    SELECT
+                        order_id, customer_id, order_number, status,
+                        total_amount, currency, items, shipping_address,
+                        tracking_number, carrier, customer_name, customer_email,
+                        created_at, updated_at, version
+                    FROM order_views
+                    WHERE ($1::text IS NULL OR status = $1)
+                      AND ($2::timestamptz IS NULL OR created_at >= $2)
+                      AND ($3::timestamptz IS NULL OR created_at < $3)
+                      AND ($4::timestamptz IS NULL OR (created_at, order_id) > ($4, $5::uuid))
+                    ORDER BY created_at, order_id
+                    LIMIT $6
+                    "#,
+                )
+                .bind(&filter.status)
+                .bind(filter.from)
+                .bind(filter.to)
+                .bind(after_created_at)
+                .bind(after_order_id)
+                .bind(page_size)
+                .fetch_all(reader)
+                .await?;
+
+                let next = match orders.last() {
+                    Some(last) if orders.len() as i64 >= page_size => {
+                        ExportCursor::After(last.created_at, last.order_id)
+                    }
+                    Some(_) => ExportCursor::Done,
+                    None => return Ok(None),
+                };
+                Ok(Some((orders, next)))
+            }
+        })
+        .boxed()
+    }
+
     async fn count_by_customer(&self, customer_id: Uuid) -> Result<i64, ReadModelError> {
         let count: i64 = sqlx::query_scalar(
             r#"
@@ -171,11 +654,55 @@ impl OrderViewRepository for PostgresOrderViewRepository {
             "#,
         )
         .bind(customer_id)
-        .fetch_one(&self.pool)
+        .fetch_one(self.reader().await)
         .await?;
 
         Ok(count)
     }
+
+    async fn order_stats(
+        &self,
+        group_by: StatsGroupBy,
+        limit: i64,
+    ) -> Result<Vec<OrderStatsBucket>, ReadModelError> {
+        // Only fixed expressions from StatsGroupBy are interpolated
+        let query = format!(
+            r#"
+            SELECT
+                {key} AS key,
+                COUNT(*) AS count,
+                COALESCE(SUM(total_amount), 0)::FLOAT8 AS total_amount
+            FROM order_views
+            GROUP BY 1
+            ORDER BY {order_by}
+            LIMIT $1
+            "#,
+            key = group_by.key_expr(),
+            order_by = group_by.order_by(),
+        );
+
+        let buckets = sqlx::query_as::<_, OrderStatsBucket>(&query)
+            .bind(limit)
+            .fetch_all(self.reader().await)
+            .await?;
+
+        Ok(buckets)
+    }
+
+    async fn order_totals(&self) -> Result<OrderTotals, ReadModelError> {
+        let totals = sqlx::query_as::<_, OrderTotals>(
+            r#"
+            SELECT
+                COUNT(*) AS count,
+                COALESCE(SUM(total_amount), 0)::FLOAT8 AS total_amount
+            FROM order_views
+            "#,
+        )
+        .fetch_one(self.reader().await)
+        .await?;
+
+        Ok(totals)
+    }
 }
 
 #[cfg(test)]
@@ -195,6 +722,8 @@ mod tests {
             shipping_address: None,
             tracking_number: None,
             carrier: None,
+            customer_name: Some("Ada Lovelace".to_string()),
+            customer_email: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             version: 1,
@@ -206,4 +735,56 @@ mod tests {
         assert_eq!(order.order_id, deserialized.order_id);
         assert_eq!(order.order_number, deserialized.order_number);
     }
+
+    #[test]
+    fn test_order_fields_parsing() {
+        let fields = OrderFields::parse("order_id, status,order_id,total_amount").unwrap();
+        assert_eq!(
+            fields.fields(),
+            &[OrderField::OrderId, OrderField::Status, OrderField::TotalAmount]
+        );
+        assert_eq!(fields.select_list(), "order_id, status, total_amount");
+
+        assert_eq!(OrderFields::parse("status,password"), Err("password".to_string()));
+        assert!(OrderFields::parse(" , ").is_err());
+    }
+
+    #[test]
+    fn test_order_fields_project_full_view() {
+        let order = OrderView {
+            order_id: Uuid::new_v4(),
+            customer_id: Uuid::new_v4(),
+            order_number: "ORD-123".to_string(),
+            status: "SHIPPED".to_string(),
+            total_amount: 10.0,
+            currency: "USD".to_string(),
+            items: serde_json::json!([{ "sku": "A" }]),
+            shipping_address: None,
+            tracking_number: Some("TRK-1".to_string()),
+            carrier: None,
+            customer_name: None,
+            customer_email: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 2,
+        };
+
+        let fields = OrderFields::parse("status,shipping_address,order_id").unwrap();
+        let partial = fields.project(&order).unwrap();
+        assert_eq!(partial.len(), 3);
+        assert_eq!(partial["status"], "SHIPPED");
+        assert!(partial["shipping_address"].is_null());
+        assert!(!partial.contains_key("items"));
+
+        // Every field name matches the serialized view
+        let all = OrderFields(OrderField::ALL.to_vec()).project(&order).unwrap();
+        assert_eq!(all.len(), OrderField::ALL.len());
+    }
+
+    #[test]
+    fn test_stats_group_by_deserialization() {
+        let group_by: StatsGroupBy = serde_json::from_str("\"day\"").unwrap();
+        assert_eq!(group_by, StatsGroupBy::Day);
+        assert!(serde_json::from_str::<StatsGroupBy>("\"region\"").is_err());
+    }
 }
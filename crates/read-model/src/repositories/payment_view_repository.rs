@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::ReadModelError;
+
+/// Read model representation of a payment's current state
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PaymentView {
+    pub payment_id: Uuid,
+    pub order_id: Uuid,
+    /// One of `AUTHORIZED`, `CAPTURED`, `VOIDED`, `FAILED` or `REFUNDED`
+    pub status: String,
+    pub amount: f64,
+    pub currency: String,
+    pub payment_method: Option<String>,
+    pub authorization_code: Option<String>,
+    pub transaction_id: Option<String>,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One status change of a payment
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PaymentHistoryEntry {
+    pub payment_id: Uuid,
+    pub status: String,
+    pub amount: f64,
+    pub currency: String,
+    pub authorization_code: Option<String>,
+    pub transaction_id: Option<String>,
+    pub reason: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Repository for querying payment views
+#[async_trait]
+pub trait PaymentViewRepository: Send + Sync {
+    /// Payments of an order, oldest first
+    async fn list_by_order(&self, order_id: Uuid) -> Result<Vec<PaymentView>, ReadModelError>;
+
+    /// Status changes of all payments of an order, in the order they happened
+    async fn history_by_order(
+        &self,
+        order_id: Uuid,
+    ) -> Result<Vec<PaymentHistoryEntry>, ReadModelError>;
+
+    /// Authorizations authorized before `cutoff` that were never captured, voided or refunded
+    async fn list_unsettled_authorizations(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<PaymentView>, ReadModelError>;
+}
+
+/// PostgreSQL implementation of PaymentViewRepository
+pub struct PostgresPaymentViewRepository {
+    pool: PgPool,
+}
+
+impl PostgresPaymentViewRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PaymentViewRepository for PostgresPaymentViewRepository {
+    async fn list_by_order(&self, order_id: Uuid) -> Result<Vec<PaymentView>, ReadModelError> {
+        let payments = sqlx::query_as::<_, PaymentView>(
+            r#"
+            SELECT payment_id, order_id, status, amount::FLOAT8 AS amount, currency,
+                   payment_method, authorization_code, transaction_id, reason,
+                   created_at, updated_at
+            FROM payment_views
+            WHERE order_id = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(order_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(payments)
+    }
+
+    async fn history_by_order(
+        &self,
+        order_id: Uuid,
+    ) -> Result<Vec<PaymentHistoryEntry>, ReadModelError> {
+        let entries = sqlx::query_as::<_, PaymentHistoryEntry>(
+            r#"
+            SELECT payment_id, status, amount::FLOAT8 AS amount, currency,
+                   authorization_code, transaction_id, reason, occurred_at
+            FROM payment_history
+            WHERE order_id = $1
+            ORDER BY occurred_at ASC
+            "#,
+        )
+        .bind(order_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    async fn list_unsettled_authorizations(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<PaymentView>, ReadModelError> {
+        // A payment's row is created by its authorization, so created_at is the authorization time
+        let payments = sqlx::query_as::<_, PaymentView>(
+            r#"
+            SELECT payment_id, order_id, status, amount::FLOAT8 AS amount, currency,
+                   payment_method, authorization_code, transaction_id, reason,
+                   created_at, updated_at
+            FROM payment_views
+            WHERE status = 'AUTHORIZED' AND created_at < $1
+            ORDER BY created_at ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(cutoff)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(payments)
+    }
+}
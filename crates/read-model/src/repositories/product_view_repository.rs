@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::ReadModelError;
+
+/// Read model representation of a catalog product
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProductView {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub unit_price: f64,
+    pub currency: String,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Repository for querying product views
+#[async_trait]
+pub trait ProductViewRepository: Send + Sync {
+    /// Products with the given IDs; unknown IDs are left out
+    async fn get_by_ids(&self, product_ids: &[Uuid]) -> Result<Vec<ProductView>, ReadModelError>;
+}
+
+/// PostgreSQL implementation of ProductViewRepository
+pub struct PostgresProductViewRepository {
+    pool: PgPool,
+}
+
+impl PostgresProductViewRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ProductViewRepository for PostgresProductViewRepository {
+    async fn get_by_ids(&self, product_ids: &[Uuid]) -> Result<Vec<ProductView>, ReadModelError> {
+        if product_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let products = sqlx::query_as::<_, ProductView>(
+            r#"
+            SELECT product_id, sku, name, description, unit_price::FLOAT8 AS unit_price,
+                   currency, active, created_at, updated_at
+            FROM product_views
+            WHERE product_id = ANY($1)
+            "#,
+        )
+        .bind(product_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(products)
+    }
+}
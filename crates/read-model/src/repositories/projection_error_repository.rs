@@ -0,0 +1,219 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::ReadModelError;
+
+/// An event the projections could not apply, set aside so the partition keeps moving
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProjectionError {
+    pub error_id: Uuid,
+    pub aggregate_id: Uuid,
+    /// Kafka topic the event was consumed from
+    pub topic: Option<String>,
+    pub event_type: String,
+    pub event_version: Option<i64>,
+    pub payload: serde_json::Value,
+    pub error: String,
+    pub attempts: i32,
+    /// One of `QUARANTINED`, `REQUEUED` or `RESOLVED`
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Repository for quarantined projection events
+#[async_trait]
+pub trait ProjectionErrorRepository: Send + Sync {
+    /// Record an event that failed `attempts` times in a row
+    #[allow(clippy::too_many_arguments)]
+    async fn quarantine(
+        &self,
+        aggregate_id: Uuid,
+        topic: &str,
+        event_type: &str,
+        event_version: Option<i64>,
+        payload: &serde_json::Value,
+        error: &str,
+        attempts: i32,
+    ) -> Result<Uuid, ReadModelError>;
+
+    /// List quarantined events, newest first, optionally filtered by status
+    async fn list(
+        &self,
+        status: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<ProjectionError>, ReadModelError>;
+
+    /// Queue a quarantined event for another attempt; `None` if it is not quarantined
+    async fn requeue(&self, error_id: Uuid) -> Result<Option<ProjectionError>, ReadModelError>;
+
+    /// Requeued events, oldest first
+    async fn find_requeued(&self, limit: i64) -> Result<Vec<ProjectionError>, ReadModelError>;
+
+    /// Record the outcome of retrying a requeued event
+    async fn record_retry(
+        &self,
+        error_id: Uuid,
+        error: Option<&str>,
+    ) -> Result<(), ReadModelError>;
+}
+
+/// PostgreSQL implementation of ProjectionErrorRepository
+pub struct PostgresProjectionErrorRepository {
+    pool: PgPool,
+}
+
+impl PostgresProjectionErrorRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ProjectionErrorRepository for PostgresProjectionErrorRepository {
+    #[allow(clippy::too_many_arguments)]
+    async fn quarantine(
+        &self,
+        aggregate_id: Uuid,
+        topic: &str,
+        event_type: &str,
+        event_version: Option<i64>,
+        payload: &serde_json::Value,
+        error: &str,
+        attempts: i32,
+    ) -> Result<Uuid, ReadModelError> {
+        let error_id = Uuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO projection_errors (
+                error_id, aggregate_id, topic, event_type, event_version, payload, error, attempts
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(error_id)
+        .bind(aggregate_id)
+        .bind(topic)
+        .bind(event_type)
+        .bind(event_version)
+        .bind(payload)
+        .bind(error)
+        .bind(attempts)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(error_id)
+    }
+
+    async fn list(
+        &self,
+        status: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<ProjectionError>, ReadModelError> {
+        let errors = sqlx::query_as::<_, ProjectionError>(
+            r#"
+            SELECT error_id, aggregate_id, topic, event_type, event_version, payload, error,
+                   attempts, status, created_at, updated_at
+            FROM projection_errors
+            WHERE ($1::TEXT IS NULL OR status = $1)
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(errors)
+    }
+
+    async fn requeue(&self, error_id: Uuid) -> Result<Option<ProjectionError>, ReadModelError> {
+        let error = sqlx::query_as::<_, ProjectionError>(
+            r#"
+            UPDATE projection_errors
+            SET status = 'REQUEUED', updated_at = NOW()
+            WHERE error_id = $1 AND status = 'QUARANTINED'
+            RETURNING error_id, aggregate_id, topic, event_type, event_version, payload, error,
+                      attempts, status, created_at, updated_at
+            "#,
+        )
+        .bind(error_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(error)
+    }
+
+    async fn find_requeued(&self, limit: i64) -> Result<Vec<ProjectionError>, ReadModelError> {
+        let errors = sqlx::query_as::<_, ProjectionError>(
+            r#"
+            SELECT error_id, aggregate_id, topic, event_type, event_version, payload, error,
+                   attempts, status, created_at, updated_at
+            FROM projection_errors
+            WHERE status = 'REQUEUED'
+            ORDER BY created_at ASC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(errors)
+    }
+
+    async fn record_retry(
+        &self,
+        error_id: Uuid,
+        error: Option<&str>,
+    ) -> Result<(), ReadModelError> {
+        // A failed retry goes back to quarantine with the latest error
+        sqlx::query(
+            r#"
+            UPDATE projection_errors
+            SET status = CASE WHEN $2::TEXT IS NULL THEN 'RESOLVED' ELSE 'QUARANTINED' END,
+                error = COALESCE($2, error),
+                attempts = attempts + 1,
+                updated_at = NOW()
+            WHERE error_id = $1
+            "#,
+        )
+        .bind(error_id)
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projection_error_serialization() {
+        let error = ProjectionError {
+            error_id: Uuid::new_v4(),
+            aggregate_id: Uuid::new_v4(),
+            topic: Some("order-events".to_string()),
+            event_type: "OrderShipped".to_string(),
+            event_version: Some(3),
+            payload: serde_json::json!({"carrier": "UPS"}),
+            error: "Serialization error: missing field `tracking_number`".to_string(),
+            attempts: 3,
+            status: "QUARANTINED".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["status"], "QUARANTINED");
+        assert_eq!(json["event_version"], 3);
+    }
+}
@@ -0,0 +1,119 @@
+use common::metrics;
+use sqlx::PgPool;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Replication lag of a standby; zero when fully replayed or not a standby at all
+///
+/// NULL when the lag can't be known: the standby isn't streaming (no WAL received,
+/// or its WAL receiver is down) or hasn't replayed a transaction yet. A standby that
+/// lost its primary has received and replayed the same WAL and would otherwise look
+/// caught up. Reading `pg_stat_wal_receiver.status` takes `pg_read_all_stats`.
+const REPLICATION_LAG_SQL: &str = r#"
+    SELECT CASE
+        WHEN NOT pg_is_in_recovery() THEN 0
+        WHEN pg_last_wal_receive_lsn() IS NULL THEN NULL
+        WHEN NOT EXISTS (SELECT 1 FROM pg_stat_wal_receiver WHERE status = 'streaming') THEN NULL
+        WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0
+        ELSE EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp())::float8
+    END
+"#;
+
+/// A Postgres read replica that queries are routed to while it keeps up
+///
+/// The replica's lag is checked at most once per `check_interval`. While it is
+/// beyond `max_lag`, unknown, or the replica cannot be reached, reads go back to
+/// the primary.
+pub struct ReadReplica {
+    pool: PgPool,
+    max_lag: Duration,
+    check_interval: Duration,
+    /// When the lag was last checked and whether the replica was usable then
+    status: Mutex<Option<(Instant, bool)>>,
+}
+
+impl ReadReplica {
+    pub fn new(pool: PgPool, max_lag: Duration) -> Self {
+        Self {
+            pool,
+            max_lag,
+            check_interval: Duration::from_secs(1),
+            status: Mutex::new(None),
+        }
+    }
+
+    /// How often the replication lag is checked
+    pub fn with_check_interval(mut self, check_interval: Duration) -> Self {
+        self.check_interval = check_interval;
+        self
+    }
+
+    /// The replica pool, or `primary` if the replica is lagging or unreachable
+    pub async fn route<'a>(&'a self, primary: &'a PgPool) -> &'a PgPool {
+        if self.is_usable().await {
+            metrics::record_read_routing("replica");
+            &self.pool
+        } else {
+            metrics::record_read_routing("primary");
+            primary
+        }
+    }
+
+    async fn is_usable(&self) -> bool {
+        let last_check = *self.status.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((checked_at, usable)) = last_check {
+            if checked_at.elapsed() < self.check_interval {
+                return usable;
+            }
+        }
+
+        // Concurrent callers may check at the same time; the last answer wins
+        let usable = match sqlx::query_scalar::<_, Option<f64>>(REPLICATION_LAG_SQL)
+            .fetch_one(&self.pool)
+            .await
+        {
+            Ok(Some(lag)) => {
+                metrics::record_read_replica_lag(lag);
+                within_lag(lag, self.max_lag)
+            }
+            Ok(None) => {
+                warn!("Read replica is not streaming, its lag is unknown; reading from primary");
+                false
+            }
+            Err(e) => {
+                warn!("Failed to check read replica lag, reading from primary: {}", e);
+                false
+            }
+        };
+
+        let mut status = self.status.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, was_usable)) = *status {
+            if was_usable != usable {
+                info!(
+                    "Read replica {}",
+                    if usable { "caught up, routing reads to it" } else { "lagging, routing reads to primary" }
+                );
+            }
+        }
+        *status = Some((Instant::now(), usable));
+        usable
+    }
+}
+
+fn within_lag(lag_secs: f64, max_lag: Duration) -> bool {
+    lag_secs <= max_lag.as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_within_lag() {
+        let max_lag = Duration::from_millis(500);
+        assert!(within_lag(0.0, max_lag));
+        assert!(within_lag(0.5, max_lag));
+        assert!(!within_lag(2.0, max_lag));
+    }
+}
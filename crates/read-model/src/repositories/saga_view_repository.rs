@@ -0,0 +1,164 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::ReadModelError;
+
+/// Read model representation of a saga instance
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SagaView {
+    pub saga_id: Uuid,
+    pub saga_type: String,
+    pub status: String,
+    pub total_steps: i32,
+    pub completed_steps: i32,
+    pub failed_attempts: i32,
+    pub last_step: Option<String>,
+    pub last_error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<i64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Outcome counts and durations for one saga type
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SagaTypeStats {
+    pub saga_type: String,
+    pub total: i64,
+    pub running: i64,
+    pub completed: i64,
+    pub compensated: i64,
+    pub requires_intervention: i64,
+    pub awaiting_approval: i64,
+    /// Share of finished sagas that did not complete
+    pub failure_rate: f64,
+    pub avg_duration_ms: Option<f64>,
+    pub max_duration_ms: Option<i64>,
+}
+
+/// Repository for querying saga views
+#[async_trait]
+pub trait SagaViewRepository: Send + Sync {
+    /// List sagas, newest first, optionally filtered by status and type
+    async fn list_sagas(
+        &self,
+        status: Option<&str>,
+        saga_type: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SagaView>, ReadModelError>;
+
+    /// Per-type outcome statistics, optionally for a single type
+    async fn saga_type_stats(
+        &self,
+        saga_type: Option<&str>,
+    ) -> Result<Vec<SagaTypeStats>, ReadModelError>;
+}
+
+/// PostgreSQL implementation of SagaViewRepository
+pub struct PostgresSagaViewRepository {
+    pool: PgPool,
+}
+
+impl PostgresSagaViewRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SagaViewRepository for PostgresSagaViewRepository {
+    async fn list_sagas(
+        &self,
+        status: Option<&str>,
+        saga_type: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SagaView>, ReadModelError> {
+        let sagas = sqlx::query_as::<_, SagaView>(
+            r#"
+            SELECT saga_id, saga_type, status, total_steps, completed_steps, failed_attempts,
+                   last_step, last_error, started_at, finished_at, duration_ms, updated_at
+            FROM saga_views
+            WHERE ($1::text IS NULL OR status = $1)
+              AND ($2::text IS NULL OR saga_type = $2)
+            ORDER BY started_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(status)
+        .bind(saga_type)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sagas)
+    }
+
+    async fn saga_type_stats(
+        &self,
+        saga_type: Option<&str>,
+    ) -> Result<Vec<SagaTypeStats>, ReadModelError> {
+        let stats = sqlx::query_as::<_, SagaTypeStats>(
+            r#"
+            SELECT
+                saga_type,
+                COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE status IN ('RUNNING', 'COMPENSATING')) AS running,
+                COUNT(*) FILTER (WHERE status = 'COMPLETED') AS completed,
+                COUNT(*) FILTER (WHERE status = 'COMPENSATED') AS compensated,
+                COUNT(*) FILTER (WHERE status = 'REQUIRES_INTERVENTION') AS requires_intervention,
+                COUNT(*) FILTER (WHERE status = 'PAUSED_AWAITING_APPROVAL') AS awaiting_approval,
+                COALESCE(
+                    COUNT(*) FILTER (WHERE status IN ('COMPENSATED', 'REQUIRES_INTERVENTION'))::float8
+                        / NULLIF(COUNT(*) FILTER (
+                            WHERE status NOT IN ('RUNNING', 'COMPENSATING', 'PAUSED_AWAITING_APPROVAL')
+                        ), 0),
+                    0
+                ) AS failure_rate,
+                AVG(duration_ms)::float8 AS avg_duration_ms,
+                MAX(duration_ms) AS max_duration_ms
+            FROM saga_views
+            WHERE ($1::text IS NULL OR saga_type = $1)
+            GROUP BY saga_type
+            ORDER BY saga_type
+            "#,
+        )
+        .bind(saga_type)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saga_view_serialization() {
+        let view = SagaView {
+            saga_id: Uuid::new_v4(),
+            saga_type: "OrderProcessingSaga".to_string(),
+            status: "COMPLETED".to_string(),
+            total_steps: 3,
+            completed_steps: 3,
+            failed_attempts: 1,
+            last_step: Some("ConfirmOrder".to_string()),
+            last_error: None,
+            started_at: Utc::now(),
+            finished_at: Some(Utc::now()),
+            duration_ms: Some(1200),
+            updated_at: Utc::now(),
+        };
+
+        let json = serde_json::to_value(&view).unwrap();
+        assert_eq!(json["status"], "COMPLETED");
+        assert_eq!(json["duration_ms"], 1200);
+    }
+}
@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::ReadModelError;
+
+/// A single entry in an order's timeline
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TimelineEntry {
+    pub occurred_at: DateTime<Utc>,
+    /// One of `event`, `saga` or `saga_step`
+    pub source: String,
+    /// Event type, saga event type, or `SagaStarted` / `SagaStatus`
+    pub kind: String,
+    pub details: serde_json::Value,
+}
+
+/// Repository building a chronological view of everything that happened to an order
+#[async_trait]
+pub trait TimelineRepository: Send + Sync {
+    /// Order events, correlated events and saga progress, oldest first
+    async fn get_order_timeline(&self, order_id: Uuid) -> Result<Vec<TimelineEntry>, ReadModelError>;
+}
+
+/// PostgreSQL implementation reading the events and saga tables directly
+pub struct PostgresTimelineRepository {
+    pool: PgPool,
+}
+
+impl PostgresTimelineRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TimelineRepository for PostgresTimelineRepository {
+    async fn get_order_timeline(&self, order_id: Uuid) -> Result<Vec<TimelineEntry>, ReadModelError> {
+        // Sagas and payment/inventory events are linked to the order through the
+        // correlation IDs carried by the order's own events.
+        let entries = sqlx::query_as::<_, TimelineEntry>(
+            r#"
+            WITH order_correlations AS (
+                SELECT DISTINCT correlation_id
+                FROM events
+                WHERE aggregate_id = $1 AND correlation_id IS NOT NULL
+            ),
+            order_sagas AS (
+                SELECT saga_id, saga_type, status, state, created_at, updated_at
+                FROM saga_instances
+                WHERE state->'data'->>'order_id' = $1::text
+                   OR state->'data'->>'correlation_id' IN (SELECT correlation_id::text FROM order_correlations)
+            )
+            SELECT
+                created_at AS occurred_at,
+                'event' AS source,
+                event_type AS kind,
+                jsonb_build_object(
+                    'event_id', event_id,
+                    'aggregate_id', aggregate_id,
+                    'aggregate_type', aggregate_type,
+                    'version', version,
+                    'payload', payload
+                ) AS details
+            FROM events
+            WHERE aggregate_id = $1
+               OR correlation_id IN (SELECT correlation_id FROM order_correlations)
+            UNION ALL
+            SELECT
+                created_at,
+                'saga',
+                'SagaStarted',
+                jsonb_build_object('saga_id', saga_id, 'saga_type', saga_type)
+            FROM order_sagas
+            UNION ALL
+            SELECT
+                updated_at,
+                'saga',
+                'SagaStatus',
+                jsonb_build_object(
+                    'saga_id', saga_id,
+                    'status', status,
+                    'current_step', state->'current_step',
+                    'steps', state->'steps'
+                )
+            FROM order_sagas
+            UNION ALL
+            SELECT
+                log.processed_at,
+                'saga_step',
+                log.event_type,
+                jsonb_build_object('saga_id', log.saga_id, 'event_id', log.event_id, 'data', log.event_data)
+            FROM saga_event_log log
+            JOIN order_sagas ON order_sagas.saga_id = log.saga_id
+            ORDER BY occurred_at ASC
+            "#,
+        )
+        .bind(order_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeline_entry_serialization() {
+        let entry = TimelineEntry {
+            occurred_at: Utc::now(),
+            source: "saga".to_string(),
+            kind: "SagaStatus".to_string(),
+            details: serde_json::json!({"status": "RUNNING"}),
+        };
+
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["source"], "saga");
+        assert_eq!(json["details"]["status"], "RUNNING");
+    }
+}
@@ -0,0 +1,278 @@
+use base64::Engine;
+use bytes::Bytes;
+use common::retry::{retry, Backoff, RetryPolicy};
+use http_body_util::{BodyExt, Full};
+use hyper::header::{HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use hyper::{Method, Request, StatusCode};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use serde_json::Value;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::ReadModelError;
+
+/// Where the order index lives
+#[derive(Debug, Clone)]
+pub struct OpenSearchConfig {
+    /// Cluster root over plain HTTP, e.g. `http://opensearch:9200`
+    pub url: String,
+    pub index: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Time allowed for one request, including reading the response
+    pub timeout: Duration,
+}
+
+impl OpenSearchConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into().trim_end_matches('/').to_string(),
+            index: "orders".to_string(),
+            username: None,
+            password: None,
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Settings from `OPENSEARCH_URL`, `OPENSEARCH_INDEX`, `OPENSEARCH_USERNAME`,
+    /// `OPENSEARCH_PASSWORD` and `OPENSEARCH_TIMEOUT_MS`; `None` without a URL
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var("OPENSEARCH_URL")
+            .ok()
+            .filter(|url| !url.is_empty())?;
+        let mut config = Self::new(url);
+        if let Ok(index) = std::env::var("OPENSEARCH_INDEX") {
+            config.index = index;
+        }
+        config.username = std::env::var("OPENSEARCH_USERNAME").ok();
+        config.password = std::env::var("OPENSEARCH_PASSWORD").ok();
+        if let Some(timeout) = std::env::var("OPENSEARCH_TIMEOUT_MS")
+            .ok()
+            .and_then(|ms| ms.parse().ok())
+        {
+            config.timeout = Duration::from_millis(timeout);
+        }
+        Some(config)
+    }
+
+    pub fn with_index(mut self, index: impl Into<String>) -> Self {
+        self.index = index.into();
+        self
+    }
+}
+
+/// Why a request to the cluster failed
+#[derive(Debug, thiserror::Error)]
+enum RequestError {
+    #[error("{0}")]
+    Transport(String),
+    #[error("timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("HTTP {0}: {1}")]
+    Status(StatusCode, String),
+}
+
+impl RequestError {
+    /// Connection failures, timeouts, and the statuses a busy or restarting node returns
+    fn is_retryable(&self) -> bool {
+        match self {
+            RequestError::Transport(_) | RequestError::Timeout(_) => true,
+            RequestError::Status(status, _) => is_retryable_status(*status),
+        }
+    }
+}
+
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Minimal OpenSearch REST client for the order index
+///
+/// Requests that fail to connect, time out or get a 429/502-504 are retried with
+/// jittered backoff; other error statuses are returned at once.
+#[derive(Clone)]
+pub struct OpenSearchClient {
+    client: Client<HttpConnector, Full<Bytes>>,
+    config: OpenSearchConfig,
+    authorization: Option<HeaderValue>,
+    retry_policy: RetryPolicy<RequestError>,
+}
+
+impl OpenSearchClient {
+    pub fn new(config: OpenSearchConfig) -> Self {
+        let authorization = config.username.as_ref().and_then(|username| {
+            let credentials = format!("{}:{}", username, config.password.as_deref().unwrap_or(""));
+            let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+            HeaderValue::from_str(&format!("Basic {}", encoded)).ok()
+        });
+        Self {
+            client: Client::builder(TokioExecutor::new()).build_http(),
+            authorization,
+            retry_policy: RetryPolicy::new("opensearch.request")
+                .with_max_attempts(4)
+                .with_backoff(Backoff::ExponentialJitter {
+                    initial: Duration::from_millis(200),
+                    max: Duration::from_secs(5),
+                })
+                .with_retryable(RequestError::is_retryable),
+            config,
+        }
+    }
+
+    pub fn index(&self) -> &str {
+        &self.config.index
+    }
+
+    /// Create the index with `definition` (settings and mappings) if it is missing,
+    /// otherwise add any new fields of its mappings to the existing index; returns
+    /// whether the index was created
+    ///
+    /// Changing the type of an existing field needs a new index and a reindex; the
+    /// cluster rejects it and the error is returned.
+    pub async fn ensure_index(&self, definition: &Value) -> Result<bool, ReadModelError> {
+        let path = format!("/{}", self.config.index);
+        match self.send(Method::HEAD, &path, None).await {
+            Ok(_) => {
+                self.send_json(
+                    Method::PUT,
+                    &format!("{}/_mapping", path),
+                    &definition["mappings"],
+                )
+                .await?;
+                debug!("Updated mappings of index {}", self.config.index);
+                Ok(false)
+            }
+            Err(RequestError::Status(StatusCode::NOT_FOUND, _)) => {
+                match self.send_json(Method::PUT, &path, definition).await {
+                    Ok(_) => {
+                        info!("Created index {}", self.config.index);
+                        Ok(true)
+                    }
+                    // Another replica created it first
+                    Err(ReadModelError::SearchError(e))
+                        if e.contains("resource_already_exists_exception") =>
+                    {
+                        Ok(false)
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(search_error(&path, e)),
+        }
+    }
+
+    /// Send newline-delimited bulk actions, returning the response with one entry
+    /// per action under `items`
+    pub async fn bulk(&self, actions: String) -> Result<Value, ReadModelError> {
+        let body = Bytes::from(actions);
+        self.send(Method::POST, "/_bulk", Some((body, "application/x-ndjson")))
+            .await
+            .map_err(|e| search_error("/_bulk", e))
+    }
+
+    /// Run a search request against the index
+    pub async fn search(&self, request: &Value) -> Result<Value, ReadModelError> {
+        self.send_json(
+            Method::POST,
+            &format!("/{}/_search", self.config.index),
+            request,
+        )
+        .await
+    }
+
+    /// Check the cluster answers
+    pub async fn ping(&self) -> Result<(), ReadModelError> {
+        self.send(Method::GET, "/", None)
+            .await
+            .map(|_| ())
+            .map_err(|e| search_error("/", e))
+    }
+
+    async fn send_json(
+        &self,
+        method: Method,
+        path: &str,
+        body: &Value,
+    ) -> Result<Value, ReadModelError> {
+        let body = Bytes::from(serde_json::to_vec(body)?);
+        self.send(method, path, Some((body, "application/json")))
+            .await
+            .map_err(|e| search_error(path, e))
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<(Bytes, &'static str)>,
+    ) -> Result<Value, RequestError> {
+        retry(&self.retry_policy, || {
+            self.attempt(method.clone(), path, body.clone())
+        })
+        .await
+    }
+
+    async fn attempt(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<(Bytes, &'static str)>,
+    ) -> Result<Value, RequestError> {
+        let uri = format!("{}{}", self.config.url, path);
+        let mut builder = Request::builder().method(method.clone()).uri(&uri);
+        if let Some(authorization) = &self.authorization {
+            builder = builder.header(AUTHORIZATION, authorization.clone());
+        }
+        let (body, content_type) = body.unzip();
+        if let Some(content_type) = content_type {
+            builder = builder.header(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        }
+        let request = builder
+            .body(Full::new(body.unwrap_or_default()))
+            .map_err(|e| RequestError::Transport(format!("{}: {}", uri, e)))?;
+
+        let exchange = async {
+            let response = self
+                .client
+                .request(request)
+                .await
+                .map_err(|e| RequestError::Transport(e.to_string()))?;
+            let status = response.status();
+            let body = response
+                .into_body()
+                .collect()
+                .await
+                .map_err(|e| RequestError::Transport(e.to_string()))?
+                .to_bytes();
+            Ok::<_, RequestError>((status, body))
+        };
+        let (status, body) = tokio::time::timeout(self.config.timeout, exchange)
+            .await
+            .map_err(|_| RequestError::Timeout(self.config.timeout))??;
+
+        if !status.is_success() {
+            let message = String::from_utf8_lossy(&body).into_owned();
+            if is_retryable_status(status) {
+                warn!("{} {} returned {}, retrying", method, path, status);
+            }
+            return Err(RequestError::Status(status, message));
+        }
+        if body.is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_slice(&body)
+            .map_err(|e| RequestError::Transport(format!("invalid response from {}: {}", uri, e)))
+    }
+}
+
+fn search_error(path: &str, error: RequestError) -> ReadModelError {
+    ReadModelError::SearchError(format!("{}: {}", path, error))
+}
@@ -0,0 +1,5 @@
+pub mod client;
+pub mod order_index;
+
+pub use client::{OpenSearchClient, OpenSearchConfig};
+pub use order_index::{order_index_definition, OpenSearchOrderSearch, OrderSearchProjection};
@@ -0,0 +1,561 @@
+use async_trait::async_trait;
+use common::retry::Backoff;
+use hyper::StatusCode;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::client::{is_retryable_status, OpenSearchClient};
+use crate::projections::{ProjectedEvent, Projection};
+use crate::repositories::{
+    FacetBucket, OrderSearchQuery, OrderSearchRepository, OrderSearchResults, OrderSearchSort,
+    OrderView, ORDER_FACETS,
+};
+use crate::ReadModelError;
+
+/// Bulk requests sent for one batch before actions the cluster keeps rejecting
+/// with 429 or 5xx are reported as failed
+const MAX_BULK_ATTEMPTS: u32 = 4;
+
+/// Values returned per facet
+const FACET_SIZE: usize = 20;
+
+/// Settings and mappings of the order index
+///
+/// Documents are order views as the query service returns them. Only mapped fields
+/// are indexed (`dynamic: false`); everything is still kept in `_source`, so new
+/// view columns show up in results before they are searchable.
+pub fn order_index_definition() -> Value {
+    json!({
+        "settings": {
+            "index": {"refresh_interval": "1s"}
+        },
+        "mappings": {
+            "dynamic": false,
+            "properties": {
+                "order_id": {"type": "keyword"},
+                "customer_id": {"type": "keyword"},
+                "order_number": {"type": "keyword"},
+                "status": {"type": "keyword"},
+                "total_amount": {"type": "double"},
+                "currency": {"type": "keyword"},
+                "items": {
+                    "type": "nested",
+                    "properties": {
+                        "product_id": {"type": "keyword"},
+                        "sku": {"type": "keyword"},
+                        "quantity": {"type": "integer"},
+                        "unit_price": {"type": "double"}
+                    }
+                },
+                "shipping_address": {"type": "object", "enabled": false},
+                "tracking_number": {"type": "keyword"},
+                "carrier": {"type": "keyword"},
+                "customer_name": {
+                    "type": "text",
+                    "fields": {"keyword": {"type": "keyword", "ignore_above": 256}}
+                },
+                "customer_email": {"type": "keyword"},
+                "created_at": {"type": "date"},
+                "updated_at": {"type": "date"},
+                "version": {"type": "long"}
+            }
+        }
+    })
+}
+
+/// Keeps the OpenSearch order index in step with `order_views`
+///
+/// For every order touched by a batch the current view is read from the primary and
+/// indexed with its version as an external version, so replayed or reordered batches
+/// never overwrite a newer document. Orders whose view is gone are deleted.
+pub struct OrderSearchProjection {
+    pool: PgPool,
+    client: OpenSearchClient,
+}
+
+impl OrderSearchProjection {
+    pub fn new(pool: PgPool, client: OpenSearchClient) -> Self {
+        Self { pool, client }
+    }
+
+    /// Create the index or add new fields to its mappings, returning whether it was
+    /// created and so needs a [`reindex`](Self::reindex)
+    pub async fn ensure_index(&self) -> Result<bool, ReadModelError> {
+        self.client.ensure_index(&order_index_definition()).await
+    }
+
+    /// Index every order view, `batch_size` at a time, returning how many were indexed
+    pub async fn reindex(&self, batch_size: i64) -> Result<u64, ReadModelError> {
+        let mut after = Uuid::nil();
+        let mut indexed = 0;
+        loop {
+            let views = sqlx::query_as::<_, OrderView>(
+                r#"
+                SELECT
+                    order_id, customer_id, order_number, status,
+                    total_amount, currency, items, shipping_address,
+                    tracking_number, carrier, customer_name, customer_email,
+                    created_at, updated_at, version
+                FROM order_views
+                WHERE order_id > $1
+                ORDER BY order_id
+                LIMIT $2
+                "#,
+            )
+            .bind(after)
+            .bind(batch_size)
+            .fetch_all(&self.pool)
+            .await?;
+            let Some(last) = views.last() else {
+                break;
+            };
+            after = last.order_id;
+
+            self.write(&views, &[]).await?;
+            indexed += views.len() as u64;
+            if (views.len() as i64) < batch_size {
+                break;
+            }
+        }
+
+        info!("Reindexed {} orders into {}", indexed, self.client.index());
+        Ok(indexed)
+    }
+
+    /// Index `views` and delete `deleted`, retrying actions the cluster was too busy for
+    async fn write(&self, views: &[OrderView], deleted: &[Uuid]) -> Result<(), ReadModelError> {
+        let index = self.client.index();
+        let mut pending = Vec::with_capacity(views.len() + deleted.len());
+        for view in views {
+            pending.push((view.order_id, index_action(index, view)?));
+        }
+        for order_id in deleted {
+            pending.push((*order_id, delete_action(index, *order_id)));
+        }
+
+        let backoff = Backoff::ExponentialJitter {
+            initial: Duration::from_millis(200),
+            max: Duration::from_secs(5),
+        };
+        let mut attempt = 1;
+        while !pending.is_empty() {
+            let actions: String = pending.iter().map(|(_, action)| action.as_str()).collect();
+            let response = self.client.bulk(actions).await?;
+            let (retry, errors) = failed_items(&response);
+            if !errors.is_empty() {
+                return Err(ReadModelError::SearchError(format!(
+                    "{} orders were rejected by {}: {}",
+                    errors.len(),
+                    index,
+                    errors.join("; ")
+                )));
+            }
+            if retry.is_empty() {
+                break;
+            }
+            if attempt >= MAX_BULK_ATTEMPTS {
+                return Err(ReadModelError::SearchError(format!(
+                    "{} orders still rejected by {} after {} attempts",
+                    retry.len(),
+                    index,
+                    attempt
+                )));
+            }
+
+            warn!(
+                "{} of {} bulk actions on {} were throttled, retrying",
+                retry.len(),
+                pending.len(),
+                index
+            );
+            pending.retain(|(order_id, _)| retry.contains(order_id));
+            tokio::time::sleep(backoff.delay(attempt)).await;
+            attempt += 1;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Projection for OrderSearchProjection {
+    fn name(&self) -> &str {
+        "order_search"
+    }
+
+    async fn project(&self, events: &[ProjectedEvent]) -> Result<(), ReadModelError> {
+        let mut seen = HashSet::new();
+        let order_ids: Vec<Uuid> = events
+            .iter()
+            .map(|event| event.aggregate_id)
+            .filter(|order_id| seen.insert(*order_id))
+            .collect();
+        if order_ids.is_empty() {
+            return Ok(());
+        }
+
+        let views = sqlx::query_as::<_, OrderView>(
+            r#"
+            SELECT
+                order_id, customer_id, order_number, status,
+                total_amount, currency, items, shipping_address,
+                tracking_number, carrier, customer_name, customer_email,
+                created_at, updated_at, version
+            FROM order_views
+            WHERE order_id = ANY($1)
+            "#,
+        )
+        .bind(&order_ids)
+        .fetch_all(&self.pool)
+        .await?;
+        let found: HashSet<Uuid> = views.iter().map(|view| view.order_id).collect();
+        let deleted: Vec<Uuid> = order_ids
+            .into_iter()
+            .filter(|order_id| !found.contains(order_id))
+            .collect();
+
+        self.write(&views, &deleted).await
+    }
+}
+
+/// Order search served by the OpenSearch index
+pub struct OpenSearchOrderSearch {
+    client: OpenSearchClient,
+}
+
+impl OpenSearchOrderSearch {
+    pub fn new(client: OpenSearchClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl OrderSearchRepository for OpenSearchOrderSearch {
+    async fn search(&self, query: &OrderSearchQuery) -> Result<OrderSearchResults, ReadModelError> {
+        let response = self.client.search(&search_request(query)).await?;
+        parse_search_response(&response)
+    }
+}
+
+fn index_action(index: &str, view: &OrderView) -> Result<String, ReadModelError> {
+    let action = json!({
+        "index": {
+            "_index": index,
+            "_id": view.order_id,
+            "version": view.version,
+            "version_type": "external_gte",
+        }
+    });
+    Ok(format!("{}\n{}\n", action, serde_json::to_string(view)?))
+}
+
+fn delete_action(index: &str, order_id: Uuid) -> String {
+    format!(
+        "{}\n",
+        json!({"delete": {"_index": index, "_id": order_id}})
+    )
+}
+
+/// Orders of a bulk response worth sending again, and descriptions of the ones
+/// rejected for good
+///
+/// A version conflict means a newer view is already indexed, and deleting a missing
+/// document leaves the index as intended, so neither is a failure.
+fn failed_items(response: &Value) -> (HashSet<Uuid>, Vec<String>) {
+    let mut retry = HashSet::new();
+    let mut errors = Vec::new();
+    if response["errors"].as_bool() != Some(true) {
+        return (retry, errors);
+    }
+
+    let items = response["items"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for item in items {
+        let Some((action, result)) = item.as_object().and_then(|item| item.iter().next()) else {
+            continue;
+        };
+        let status = result["status"]
+            .as_u64()
+            .and_then(|status| StatusCode::from_u16(status as u16).ok())
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let already_applied = status == StatusCode::CONFLICT
+            || (action == "delete" && status == StatusCode::NOT_FOUND);
+        if status.is_success() || already_applied {
+            continue;
+        }
+
+        let id = result["_id"].as_str().unwrap_or_default();
+        match id.parse::<Uuid>() {
+            Ok(order_id) if is_retryable_status(status) => {
+                retry.insert(order_id);
+            }
+            _ => errors.push(format!(
+                "{} {}: {}",
+                action,
+                id,
+                result["error"]["reason"]
+                    .as_str()
+                    .unwrap_or(status.as_str())
+            )),
+        }
+    }
+
+    (retry, errors)
+}
+
+/// Search request for `query`
+///
+/// Facet filters go in `post_filter`, so they narrow the hits but not the facet
+/// counts; each facet's aggregation applies the other facets' filters itself.
+fn search_request(query: &OrderSearchQuery) -> Value {
+    let text = query
+        .text
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty());
+
+    let mut must = Vec::new();
+    if let Some(text) = text {
+        must.push(json!({
+            "multi_match": {
+                "query": text,
+                "fields": ["order_number^3", "tracking_number^2", "customer_name", "customer_email"],
+                "lenient": true,
+            }
+        }));
+    }
+
+    let mut filter = Vec::new();
+    if let Some(customer_id) = query.customer_id {
+        filter.push(json!({"term": {"customer_id": customer_id}}));
+    }
+    if let Some(sku) = &query.sku {
+        filter.push(json!({
+            "nested": {"path": "items", "query": {"term": {"items.sku": sku}}}
+        }));
+    }
+    if query.min_amount.is_some() || query.max_amount.is_some() {
+        filter.push(json!({
+            "range": {"total_amount": range(query.min_amount, query.max_amount)}
+        }));
+    }
+    if query.created_from.is_some() || query.created_to.is_some() {
+        filter.push(json!({
+            "range": {"created_at": range(query.created_from, query.created_to)}
+        }));
+    }
+
+    let facet_filters: Vec<(&str, Value)> = [
+        ("status", &query.statuses),
+        ("currency", &query.currencies),
+        ("carrier", &query.carriers),
+    ]
+    .into_iter()
+    .filter(|(_, values)| !values.is_empty())
+    .map(|(field, values)| (field, json!({"terms": {field: values}})))
+    .collect();
+
+    let aggs: serde_json::Map<String, Value> = ORDER_FACETS
+        .iter()
+        .map(|facet| {
+            let others: Vec<&Value> = facet_filters
+                .iter()
+                .filter(|(field, _)| field != facet)
+                .map(|(_, clause)| clause)
+                .collect();
+            let aggregation = json!({
+                "filter": {"bool": {"filter": others}},
+                "aggs": {"values": {"terms": {"field": facet, "size": FACET_SIZE}}},
+            });
+            (facet.to_string(), aggregation)
+        })
+        .collect();
+
+    let newest = json!([{"created_at": "desc"}, {"order_id": "asc"}]);
+    let sort = match query.sort {
+        OrderSearchSort::Relevance if text.is_some() => json!(["_score", {"created_at": "desc"}]),
+        OrderSearchSort::Relevance | OrderSearchSort::Newest => newest,
+        OrderSearchSort::Oldest => json!([{"created_at": "asc"}, {"order_id": "asc"}]),
+        OrderSearchSort::AmountDesc => json!([{"total_amount": "desc"}, {"order_id": "asc"}]),
+        OrderSearchSort::AmountAsc => json!([{"total_amount": "asc"}, {"order_id": "asc"}]),
+    };
+
+    let post_filter: Vec<&Value> = facet_filters.iter().map(|(_, clause)| clause).collect();
+    json!({
+        "from": query.offset,
+        "size": query.limit,
+        "track_total_hits": true,
+        "query": {"bool": {"must": must, "filter": filter}},
+        "post_filter": {"bool": {"filter": post_filter}},
+        "aggs": aggs,
+        "sort": sort,
+    })
+}
+
+fn range<T: serde::Serialize>(from: Option<T>, to: Option<T>) -> Value {
+    let mut range = serde_json::Map::new();
+    if let Some(from) = from {
+        range.insert("gte".to_string(), json!(from));
+    }
+    if let Some(to) = to {
+        range.insert("lte".to_string(), json!(to));
+    }
+    Value::Object(range)
+}
+
+fn parse_search_response(response: &Value) -> Result<OrderSearchResults, ReadModelError> {
+    let hits = response["hits"]["hits"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let orders = hits
+        .iter()
+        .map(|hit| serde_json::from_value::<OrderView>(hit["_source"].clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let facets: BTreeMap<String, Vec<FacetBucket>> = ORDER_FACETS
+        .iter()
+        .map(|facet| {
+            let buckets = response["aggregations"][*facet]["values"]["buckets"]
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|bucket| {
+                    let value = match &bucket["key"] {
+                        Value::String(key) => key.clone(),
+                        Value::Null => return None,
+                        key => key.to_string(),
+                    };
+                    Some(FacetBucket {
+                        value,
+                        count: bucket["doc_count"].as_u64().unwrap_or(0),
+                    })
+                })
+                .collect();
+            (facet.to_string(), buckets)
+        })
+        .collect();
+
+    Ok(OrderSearchResults {
+        total: response["hits"]["total"]["value"].as_u64().unwrap_or(0),
+        orders,
+        facets,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_facet_filters_do_not_narrow_their_own_counts() {
+        let query = OrderSearchQuery {
+            text: Some("ORD-42".to_string()),
+            statuses: vec!["SHIPPED".to_string()],
+            currencies: vec!["USD".to_string()],
+            min_amount: Some(10.0),
+            limit: 20,
+            ..Default::default()
+        };
+        let request = search_request(&query);
+
+        assert_eq!(request["size"], 20);
+        assert_eq!(
+            request["query"]["bool"]["must"][0]["multi_match"]["query"],
+            "ORD-42"
+        );
+        assert_eq!(
+            request["query"]["bool"]["filter"][0]["range"]["total_amount"],
+            json!({"gte": 10.0})
+        );
+        assert_eq!(
+            request["post_filter"]["bool"]["filter"],
+            json!([{"terms": {"status": ["SHIPPED"]}}, {"terms": {"currency": ["USD"]}}])
+        );
+        assert_eq!(
+            request["aggs"]["status"]["filter"]["bool"]["filter"],
+            json!([{"terms": {"currency": ["USD"]}}])
+        );
+        assert_eq!(
+            request["aggs"]["carrier"]["filter"]["bool"]["filter"]
+                .as_array()
+                .map(Vec::len),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_bulk_conflicts_are_not_failures() {
+        let throttled = Uuid::new_v4();
+        let response = json!({
+            "errors": true,
+            "items": [
+                {"index": {"_id": Uuid::new_v4().to_string(), "status": 201}},
+                {"index": {"_id": Uuid::new_v4().to_string(), "status": 409}},
+                {"delete": {"_id": Uuid::new_v4().to_string(), "status": 404}},
+                {"index": {"_id": throttled.to_string(), "status": 429}},
+                {"index": {
+                    "_id": "broken",
+                    "status": 400,
+                    "error": {"reason": "failed to parse field [total_amount]"}
+                }},
+            ]
+        });
+        let (retry, errors) = failed_items(&response);
+
+        assert_eq!(retry, HashSet::from([throttled]));
+        assert_eq!(
+            errors,
+            vec!["index broken: failed to parse field [total_amount]"]
+        );
+    }
+
+    #[test]
+    fn test_parse_search_response() {
+        let order_id = Uuid::new_v4();
+        let response = json!({
+            "hits": {
+                "total": {"value": 1},
+                "hits": [{"_source": {
+                    "order_id": order_id,
+                    "customer_id": Uuid::new_v4(),
+                    "order_number": "ORD-1",
+                    "status": "SHIPPED",
+                    "total_amount": 25.0,
+                    "currency": "USD",
+                    "items": [],
+                    "shipping_address": null,
+                    "tracking_number": "TRK1",
+                    "carrier": "UPS",
+                    "customer_name": null,
+                    "customer_email": null,
+                    "created_at": "2024-01-01T00:00:00Z",
+                    "updated_at": "2024-01-02T00:00:00Z",
+                    "version": 3
+                }}]
+            },
+            "aggregations": {
+                "status": {"values": {"buckets": [{"key": "SHIPPED", "doc_count": 1}]}}
+            }
+        });
+        let results = parse_search_response(&response).unwrap();
+
+        assert_eq!(results.total, 1);
+        assert_eq!(results.orders[0].order_id, order_id);
+        assert_eq!(
+            results.facets["status"],
+            vec![FacetBucket {
+                value: "SHIPPED".to_string(),
+                count: 1
+            }]
+        );
+        assert!(results.facets["carrier"].is_empty());
+    }
+}
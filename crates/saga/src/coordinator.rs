@@ -1,19 +1,103 @@
+use chrono::Utc;
+use domain::events::saga_events::{
+    SagaCompensatedEvent, SagaCompletedEvent, SagaInterventionRequiredEvent, SagaPausedEvent,
+    SagaStartedEvent, SagaStepCompletedEvent, SagaStepFailedEvent,
+};
+use domain::events::{DomainEvent, EventMetadata};
+use event_store::IdempotencyStatus;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use crate::errors::{Result, SagaError};
+use crate::event_sink::SagaEventSink;
+use crate::idempotency::StepIdempotencyStore;
 use crate::repository::SagaRepository;
 use crate::saga::{Saga, SagaState, SagaStatus};
+use crate::step::{StepApproval, StepLimits};
 
 /// Saga coordinator that orchestrates saga execution
-pub struct SagaCoordinator<R: SagaRepository> {
+pub struct SagaCoordinator<R: SagaRepository + ?Sized> {
     repository: Arc<R>,
+    idempotency_store: Option<Arc<dyn StepIdempotencyStore>>,
+    event_sink: Option<Arc<dyn SagaEventSink>>,
+    limits: StepLimits,
 }
 
-impl<R: SagaRepository> SagaCoordinator<R> {
+impl<R: SagaRepository + ?Sized> SagaCoordinator<R> {
     pub fn new(repository: Arc<R>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            idempotency_store: None,
+            event_sink: None,
+            limits: StepLimits::default(),
+        }
+    }
+
+    /// Abandon running steps when `token` is cancelled, e.g. by a shutdown handler
+    ///
+    /// Sagas stop at the step they were on, which is left pending and runs again
+    /// when the saga is resumed or recovered.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.limits.cancellation = token;
+        self
+    }
+
+    /// Abandon attempts of steps without a timeout of their own after `timeout`
+    pub fn with_step_timeout(mut self, timeout: Duration) -> Self {
+        self.limits.default_timeout = Some(timeout);
+        self
+    }
+
+    /// Cancel running and future steps; see [`with_cancellation`](Self::with_cancellation)
+    pub fn shutdown(&self) {
+        self.limits.cancellation.cancel();
+    }
+
+    pub fn is_shut_down(&self) -> bool {
+        self.limits.cancellation.is_cancelled()
+    }
+
+    /// Skip steps whose idempotency key shows they already ran (e.g. before a crash)
+    pub fn with_idempotency_store(mut self, store: Arc<dyn StepIdempotencyStore>) -> Self {
+        self.idempotency_store = Some(store);
+        self
+    }
+
+    /// Emit saga lifecycle events (started, step completed/failed, completed, compensated)
+    pub fn with_event_sink(mut self, sink: Arc<dyn SagaEventSink>) -> Self {
+        self.event_sink = Some(sink);
+        self
+    }
+
+    /// Publish a lifecycle event; failures are logged and never fail the saga
+    async fn emit<E: DomainEvent>(&self, state: &SagaState, event: E) {
+        let Some(sink) = &self.event_sink else {
+            return;
+        };
+
+        let metadata =
+            EventMetadata::with_correlation(state.correlation_id().unwrap_or(state.saga_id));
+
+        let result = match event.to_envelope(state.saga_id, "Saga", metadata) {
+            Ok(envelope) => sink.emit(&envelope).await,
+            Err(e) => Err(e.into()),
+        };
+
+        if let Err(e) = result {
+            warn!(
+                saga_id = %state.saga_id,
+                event_type = E::event_type(),
+                error = %e,
+                "Failed to emit saga lifecycle event"
+            );
+        }
+    }
+
+    fn duration_ms(state: &SagaState) -> i64 {
+        (Utc::now() - state.created_at).num_milliseconds()
     }
 
     /// Start a new saga
@@ -22,19 +106,88 @@ impl<R: SagaRepository> SagaCoordinator<R> {
         saga: &dyn Saga,
         saga_id: Uuid,
         data: serde_json::Value,
+    ) -> Result<SagaState> {
+        self.start_saga_in_partition(saga, saga_id, data, None).await
+    }
+
+    /// Start a new saga owned by whichever instance is assigned `partition`
+    ///
+    /// Fails with `DuplicateSaga` if a saga of the same type already exists
+    /// for the saga's correlation key.
+    pub async fn start_saga_in_partition(
+        &self,
+        saga: &dyn Saga,
+        saga_id: Uuid,
+        data: serde_json::Value,
+        partition: Option<i32>,
     ) -> Result<SagaState> {
         info!(
             saga_id = %saga_id,
             saga_type = saga.saga_type(),
+            partition = ?partition,
             "Starting new saga"
         );
 
-        let state = saga.create_state(saga_id, data).await?;
+        let mut state = saga.create_state(saga_id, data).await?;
+        state.correlation_key = saga.correlation_key(&state.data);
+        state.partition = partition;
         self.repository.save(&state).await?;
 
+        self.emit(
+            &state,
+            SagaStartedEvent {
+                saga_id,
+                saga_type: state.saga_type.clone(),
+                total_steps: state.steps.len(),
+                started_at: state.created_at,
+            },
+        )
+        .await;
+
         Ok(state)
     }
 
+    /// Start a saga, or return the existing one for the same business key
+    ///
+    /// The returned flag is `true` when a new saga was created. Replayed
+    /// triggering events therefore attach to the original saga.
+    pub async fn start_or_attach(
+        &self,
+        saga: &dyn Saga,
+        saga_id: Uuid,
+        data: serde_json::Value,
+        partition: Option<i32>,
+    ) -> Result<(SagaState, bool)> {
+        if let Some(existing) = self.find_existing(saga, &data).await? {
+            return Ok((existing, false));
+        }
+
+        match self
+            .start_saga_in_partition(saga, saga_id, data.clone(), partition)
+            .await
+        {
+            Ok(state) => Ok((state, true)),
+            // Lost a race with another instance starting the same saga
+            Err(SagaError::DuplicateSaga { saga_type, correlation_key }) => self
+                .find_existing(saga, &data)
+                .await?
+                .map(|existing| (existing, false))
+                .ok_or(SagaError::DuplicateSaga { saga_type, correlation_key }),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn find_existing(&self, saga: &dyn Saga, data: &serde_json::Value) -> Result<Option<SagaState>> {
+        match saga.correlation_key(data) {
+            Some(key) => {
+                self.repository
+                    .find_by_business_key(saga.saga_type(), &key)
+                    .await
+            }
+            None => Ok(None),
+        }
+    }
+
     /// Execute the next step of a saga
     pub async fn execute_step(
         &self,
@@ -52,37 +205,137 @@ impl<R: SagaRepository> SagaCoordinator<R> {
             "Executing saga step"
         );
 
-        match saga.execute_next_step(&mut state).await {
-            Ok(_) => {
-                self.repository.update(&state).await?;
+        if let Some(step) = state.current_step().filter(|step| step.awaits_approval()) {
+            let reason = format!("Step '{}' requires approval", step.name);
+            state.mark_awaiting_approval(reason);
+            return self.pause_for_approval(state).await;
+        }
 
-                if state.is_completed() {
-                    info!(
-                        saga_id = %state.saga_id,
-                        "Saga completed successfully"
-                    );
-                } else {
-                    info!(
-                        saga_id = %state.saga_id,
-                        current_step = state.current_step,
-                        "Saga step completed, advancing to next step"
-                    );
+        let rejection = state
+            .current_step()
+            .filter(|step| step.is_rejected())
+            .and_then(|step| step.approval.clone());
+        if let Some(approval) = rejection {
+            let step = state
+                .current_step_mut()
+                .ok_or_else(|| SagaError::StepNotFound("current step".to_string()))?;
+            step.status = crate::step::StepStatus::Failed;
+            step.error = Some(format!(
+                "Rejected by {}: {}",
+                approval.approver,
+                approval.comment.as_deref().unwrap_or("no reason given")
+            ));
+            warn!(
+                saga_id = %state.saga_id,
+                approver = %approval.approver,
+                "Saga step rejected, initiating compensation"
+            );
+            self.repository.update(&state).await?;
+            return self.compensate_saga(saga, state).await;
+        }
+
+        loop {
+            let step_index = state.current_step;
+            match self.execute_idempotent(saga, &mut state).await {
+                Ok(_) => {
+                    self.repository.update(&state).await?;
+
+                    if let Some(step) = state.steps.get(step_index).filter(|s| s.is_completed()) {
+                        self.emit(
+                            &state,
+                            SagaStepCompletedEvent {
+                                saga_id: state.saga_id,
+                                saga_type: state.saga_type.clone(),
+                                step_name: step.name.clone(),
+                                step_index,
+                                attempt: step.retry_count + 1,
+                                completed_at: Utc::now(),
+                            },
+                        )
+                        .await;
+                    }
+
+                    if state.is_completed() {
+                        self.emit_completed(&state).await;
+                        info!(
+                            saga_id = %state.saga_id,
+                            "Saga completed successfully"
+                        );
+                    } else {
+                        info!(
+                            saga_id = %state.saga_id,
+                            current_step = state.current_step,
+                            "Saga step completed, advancing to next step"
+                        );
+                    }
+
+                    return Ok(state);
                 }
+                Err(e) => {
+                    // Save failed state before retrying or compensating
+                    self.repository.update(&state).await?;
 
-                Ok(state)
-            }
-            Err(e) => {
-                error!(
-                    saga_id = %state.saga_id,
-                    error = %e,
-                    "Saga step failed, initiating compensation"
-                );
+                    if state.is_awaiting_approval() {
+                        return self.pause_for_approval(state).await;
+                    }
 
-                // Save failed state before compensation
-                self.repository.update(&state).await?;
+                    if let SagaError::Cancelled(_) = e {
+                        warn!(
+                            saga_id = %state.saga_id,
+                            current_step = state.current_step,
+                            "Saga step abandoned on shutdown, it runs again on resume"
+                        );
+                        return Err(e);
+                    }
+
+                    let retry = state
+                        .current_step()
+                        .filter(|step| e.is_retryable() && step.can_retry())
+                        .map(|step| (step.retry_count, step.retry_delay()));
+
+                    if let Some(step) = state.current_step() {
+                        self.emit(
+                            &state,
+                            SagaStepFailedEvent {
+                                saga_id: state.saga_id,
+                                saga_type: state.saga_type.clone(),
+                                step_name: step.name.clone(),
+                                step_index: state.current_step,
+                                attempt: step.retry_count,
+                                error: e.to_string(),
+                                will_retry: retry.is_some(),
+                                failed_at: Utc::now(),
+                            },
+                        )
+                        .await;
+                    }
+
+                    if let Some((attempt, delay)) = retry {
+                        warn!(
+                            saga_id = %state.saga_id,
+                            error = %e,
+                            attempt = attempt,
+                            delay_ms = delay.as_millis() as u64,
+                            "Saga step failed, retrying"
+                        );
+                        tokio::select! {
+                            _ = tokio::time::sleep(delay) => continue,
+                            _ = self.limits.cancellation.cancelled() => {
+                                return Err(SagaError::Cancelled(
+                                    "shut down while waiting to retry".to_string(),
+                                ));
+                            }
+                        }
+                    }
 
-                // Initiate compensation
-                self.compensate_saga(saga, state).await
+                    error!(
+                        saga_id = %state.saga_id,
+                        error = %e,
+                        "Saga step failed, initiating compensation"
+                    );
+
+                    return self.compensate_saga(saga, state).await;
+                }
             }
         }
     }
@@ -103,21 +356,140 @@ impl<R: SagaRepository> SagaCoordinator<R> {
         while state.has_more_steps() && !state.is_completed() {
             state = self.execute_step(saga, state).await?;
 
-            // If saga failed and was compensated, return the compensated state
-            if state.is_compensating() || state.is_failed() {
+            // If saga failed and was compensated, or awaits approval, return its state
+            if state.is_compensating()
+                || state.is_compensated()
+                || state.is_failed()
+                || state.is_awaiting_approval()
+            {
                 break;
             }
         }
 
         // Mark as completed if all steps succeeded
-        if state.has_more_steps() == false && !state.is_completed() && !state.is_failed() {
+        if !state.has_more_steps()
+            && !state.is_completed()
+            && !state.is_failed()
+            && !state.is_compensated()
+            && !state.is_awaiting_approval()
+        {
             state.mark_completed();
             self.repository.update(&state).await?;
+            self.emit_completed(&state).await;
         }
 
         Ok(state)
     }
 
+    /// Persist a saga that just paused for approval and announce it
+    async fn pause_for_approval(&self, state: SagaState) -> Result<SagaState> {
+        self.repository.update(&state).await?;
+
+        let reason = state.pause_reason.clone().unwrap_or_default();
+        warn!(
+            saga_id = %state.saga_id,
+            step = state.current_step,
+            reason = %reason,
+            "Saga paused awaiting approval"
+        );
+
+        if let Some(step) = state.current_step() {
+            self.emit(
+                &state,
+                SagaPausedEvent {
+                    saga_id: state.saga_id,
+                    saga_type: state.saga_type.clone(),
+                    step_name: step.name.clone(),
+                    step_index: state.current_step,
+                    reason,
+                    paused_at: state.updated_at,
+                },
+            )
+            .await;
+        }
+
+        Ok(state)
+    }
+
+    async fn emit_completed(&self, state: &SagaState) {
+        self.emit(
+            state,
+            SagaCompletedEvent {
+                saga_id: state.saga_id,
+                saga_type: state.saga_type.clone(),
+                duration_ms: Self::duration_ms(state),
+                completed_at: Utc::now(),
+            },
+        )
+        .await;
+    }
+
+    /// Execute the current step, reusing the recorded result if its idempotency key was seen
+    ///
+    /// The key is claimed before the step runs, so a second coordinator resuming the
+    /// same saga cannot run the step at the same time; it sees the key in progress and
+    /// retries later like after a transient failure.
+    async fn execute_idempotent(&self, saga: &dyn Saga, state: &mut SagaState) -> Result<()> {
+        let Some(store) = &self.idempotency_store else {
+            return saga.execute_next_step(state, &self.limits).await;
+        };
+        let Some(key) = state
+            .current_step()
+            .filter(|_| !state.is_completed())
+            .map(|step| step.idempotency_key(state.saga_id))
+        else {
+            return saga.execute_next_step(state, &self.limits).await;
+        };
+
+        let claim = match store.try_begin(&key).await {
+            Ok(IdempotencyStatus::Started(claim)) => claim,
+            Ok(IdempotencyStatus::Completed(result)) => {
+                info!(
+                    saga_id = %state.saga_id,
+                    idempotency_key = %key,
+                    "Step already executed, reusing recorded result"
+                );
+                if let Some(step) = state.current_step_mut() {
+                    step.mark_completed(result);
+                }
+                state.advance_step();
+                return Ok(());
+            }
+            Ok(IdempotencyStatus::InProgress) => {
+                let e = SagaError::Transient(format!("step {} is already running elsewhere", key));
+                if let Some(step) = state.current_step_mut() {
+                    step.mark_failed(e.to_string());
+                }
+                return Err(e);
+            }
+            Err(e) => {
+                // Counts as a failed attempt so an unavailable store cannot retry forever
+                if let Some(step) = state.current_step_mut() {
+                    step.mark_failed(e.to_string());
+                }
+                return Err(e);
+            }
+        };
+
+        let step_index = state.current_step;
+        let outcome = saga.execute_next_step(state, &self.limits).await;
+
+        let recorded = match state.steps.get(step_index).and_then(|step| step.result.as_ref()) {
+            Some(result) if outcome.is_ok() => store.complete(&key, claim, result).await,
+            _ => store.fail(&key, claim).await,
+        };
+        if let Err(e) = recorded {
+            warn!(
+                saga_id = %state.saga_id,
+                idempotency_key = %key,
+                error = %e,
+                "Failed to record step idempotency key"
+            );
+        }
+
+        outcome
+    }
+
     /// Compensate a saga (rollback all completed steps)
     pub async fn compensate_saga(
         &self,
@@ -129,23 +501,46 @@ impl<R: SagaRepository> SagaCoordinator<R> {
             "Starting saga compensation"
         );
 
-        match saga.compensate_all(&mut state).await {
+        match saga.compensate_all(&mut state, &self.limits).await {
             Ok(_) => {
                 self.repository.update(&state).await?;
                 info!(
                     saga_id = %state.saga_id,
                     "Saga compensated successfully"
                 );
+                self.emit(
+                    &state,
+                    SagaCompensatedEvent {
+                        saga_id: state.saga_id,
+                        saga_type: state.saga_type.clone(),
+                        duration_ms: Self::duration_ms(&state),
+                        resolution: None,
+                        compensated_at: Utc::now(),
+                    },
+                )
+                .await;
                 Ok(state)
             }
             Err(e) => {
                 error!(
                     saga_id = %state.saga_id,
                     error = %e,
-                    "Saga compensation failed"
+                    "Saga compensation failed, queued for manual intervention"
                 );
-                state.mark_failed();
+                if !state.requires_intervention() {
+                    state.mark_requires_intervention(format!("Compensation failed: {}", e));
+                }
                 self.repository.update(&state).await?;
+                self.emit(
+                    &state,
+                    SagaInterventionRequiredEvent {
+                        saga_id: state.saga_id,
+                        saga_type: state.saga_type.clone(),
+                        reason: state.intervention_reason.clone().unwrap_or_default(),
+                        failed_at: Utc::now(),
+                    },
+                )
+                .await;
                 Err(e)
             }
         }
@@ -162,10 +557,10 @@ impl<R: SagaRepository> SagaCoordinator<R> {
             return Ok(state);
         }
 
-        if state.is_failed() {
-            warn!(saga_id = %saga_id, "Cannot resume failed saga");
+        if state.is_failed() || state.requires_intervention() || state.is_awaiting_approval() {
+            warn!(saga_id = %saga_id, status = %state.status, "Cannot resume saga");
             return Err(SagaError::InvalidStateTransition {
-                from: "FAILED".to_string(),
+                from: state.status.to_string(),
                 to: "RUNNING".to_string(),
             });
         }
@@ -187,6 +582,110 @@ impl<R: SagaRepository> SagaCoordinator<R> {
         self.repository.find_by_status(status, limit).await
     }
 
+    /// Sagas whose compensation failed and that are waiting for an operator
+    pub async fn find_sagas_requiring_intervention(&self, limit: i64) -> Result<Vec<SagaState>> {
+        self.repository
+            .find_by_status(SagaStatus::RequiresIntervention, limit)
+            .await
+    }
+
+    /// Mark a saga in the intervention queue as resolved by an operator
+    ///
+    /// Of concurrent resolutions only one is recorded; the others fail with
+    /// `InvalidStateTransition` from the status the saga has moved to.
+    pub async fn resolve_intervention(&self, saga_id: Uuid, resolution: String) -> Result<SagaState> {
+        let mut state = self.repository.load(saga_id).await?;
+        state.resolve_intervention(resolution.clone())?;
+        let updated = self
+            .repository
+            .update_if_status(&state, SagaStatus::RequiresIntervention)
+            .await?;
+        if !updated {
+            let current = self.repository.load(saga_id).await?;
+            return Err(SagaError::InvalidStateTransition {
+                from: current.status.to_string(),
+                to: SagaStatus::Compensated.to_string(),
+            });
+        }
+
+        self.emit(
+            &state,
+            SagaCompensatedEvent {
+                saga_id,
+                saga_type: state.saga_type.clone(),
+                duration_ms: Self::duration_ms(&state),
+                resolution: Some(resolution),
+                compensated_at: Utc::now(),
+            },
+        )
+        .await;
+
+        info!(saga_id = %saga_id, "Saga intervention resolved");
+        Ok(state)
+    }
+
+    /// Sagas paused at a step until someone approves or rejects it
+    pub async fn find_sagas_awaiting_approval(&self, limit: i64) -> Result<Vec<SagaState>> {
+        self.repository
+            .find_by_status(SagaStatus::PausedAwaitingApproval, limit)
+            .await
+    }
+
+    /// Record a decision on the step a saga is paused at and continue the saga:
+    /// the step runs if approved, and the saga compensates if rejected
+    pub async fn decide_approval(
+        &self,
+        saga: &dyn Saga,
+        saga_id: Uuid,
+        approval: StepApproval,
+    ) -> Result<SagaState> {
+        let mut state = self.repository.load(saga_id).await?;
+        state.decide_approval(approval.clone())?;
+        self.repository.update(&state).await?;
+
+        info!(
+            saga_id = %saga_id,
+            approved = approval.approved,
+            approver = %approval.approver,
+            "Saga approval recorded"
+        );
+        self.run_saga(saga, state).await
+    }
+
+    /// Resume stalled sagas owned by the partitions assigned to this instance
+    pub async fn recover_partitions(
+        &self,
+        saga: &dyn Saga,
+        partitions: &[i32],
+        stale_after: std::time::Duration,
+        limit: i64,
+    ) -> Result<usize> {
+        if partitions.is_empty() {
+            return Ok(0);
+        }
+
+        let stale_before = Utc::now()
+            - chrono::Duration::from_std(stale_after)
+                .map_err(|e| SagaError::InternalError(e.to_string()))?;
+        let stale = self
+            .repository
+            .find_stale_in_partitions(partitions, stale_before, limit)
+            .await?;
+
+        let mut recovered = 0;
+        for state in stale.into_iter().filter(|s| s.saga_type == saga.saga_type()) {
+            let saga_id = state.saga_id;
+            info!(saga_id = %saga_id, partition = ?state.partition, "Recovering stalled saga");
+
+            match self.run_saga(saga, state).await {
+                Ok(_) => recovered += 1,
+                Err(e) => error!(saga_id = %saga_id, error = %e, "Failed to recover saga"),
+            }
+        }
+
+        Ok(recovered)
+    }
+
     /// Retry failed sagas (finds failed sagas and retries them)
     pub async fn retry_failed_sagas(&self, saga: &dyn Saga, limit: i64) -> Result<usize> {
         let failed_sagas = self.find_sagas_by_status(SagaStatus::Running, limit).await?;
@@ -219,9 +718,14 @@ impl<R: SagaRepository> SagaCoordinator<R> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::definition::SagaDefinition;
     use crate::step::{SagaStep, StepContext, StepExecutor};
     use async_trait::async_trait;
+    use crate::retry::BackoffPolicy;
+    use domain::events::EventEnvelope;
+    use event_store::IdempotencyClaim;
     use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
 
     struct MockRepository {
         states: std::sync::Mutex<HashMap<Uuid, SagaState>>,
@@ -238,7 +742,18 @@ mod tests {
     #[async_trait]
     impl SagaRepository for MockRepository {
         async fn save(&self, state: &SagaState) -> Result<()> {
-            self.states.lock().unwrap().insert(state.saga_id, state.clone());
+            let mut states = self.states.lock().unwrap();
+            if let Some(key) = &state.correlation_key {
+                if states.values().any(|s| {
+                    s.saga_type == state.saga_type && s.correlation_key.as_ref() == Some(key)
+                }) {
+                    return Err(SagaError::DuplicateSaga {
+                        saga_type: state.saga_type.clone(),
+                        correlation_key: key.clone(),
+                    });
+                }
+            }
+            states.insert(state.saga_id, state.clone());
             Ok(())
         }
 
@@ -247,6 +762,15 @@ mod tests {
             Ok(())
         }
 
+        async fn update_if_status(&self, state: &SagaState, expected: SagaStatus) -> Result<bool> {
+            let mut states = self.states.lock().unwrap();
+            if states.get(&state.saga_id).map(|s| s.status) != Some(expected) {
+                return Ok(false);
+            }
+            states.insert(state.saga_id, state.clone());
+            Ok(true)
+        }
+
         async fn load(&self, saga_id: Uuid) -> Result<SagaState> {
             self.states
                 .lock()
@@ -256,6 +780,16 @@ mod tests {
                 .ok_or_else(|| SagaError::SagaNotFound(saga_id.to_string()))
         }
 
+        async fn find_by_business_key(&self, saga_type: &str, key: &str) -> Result<Option<SagaState>> {
+            Ok(self
+                .states
+                .lock()
+                .unwrap()
+                .values()
+                .find(|s| s.saga_type == saga_type && s.correlation_key.as_deref() == Some(key))
+                .cloned())
+        }
+
         async fn find_by_status(&self, status: SagaStatus, _limit: i64) -> Result<Vec<SagaState>> {
             Ok(self
                 .states
@@ -267,10 +801,59 @@ mod tests {
                 .collect())
         }
 
+        async fn find_by_correlation_id(&self, correlation_id: Uuid, _limit: i64) -> Result<Vec<SagaState>> {
+            Ok(self
+                .states
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|s| s.correlation_id() == Some(correlation_id))
+                .cloned()
+                .collect())
+        }
+
+        async fn find_stale_in_partitions(
+            &self,
+            partitions: &[i32],
+            stale_before: chrono::DateTime<Utc>,
+            _limit: i64,
+        ) -> Result<Vec<SagaState>> {
+            Ok(self
+                .states
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|s| {
+                    s.status == SagaStatus::Running
+                        && s.partition.is_some_and(|p| partitions.contains(&p))
+                        && s.updated_at < stale_before
+                })
+                .cloned()
+                .collect())
+        }
+
+        async fn count_by_status(&self, status: SagaStatus) -> Result<i64> {
+            Ok(self
+                .states
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|s| s.status == status)
+                .count() as i64)
+        }
+
         async fn delete(&self, saga_id: Uuid) -> Result<()> {
             self.states.lock().unwrap().remove(&saga_id);
             Ok(())
         }
+
+        async fn save_definition(&self, _definition: &SagaDefinition) -> Result<()> {
+            Ok(())
+        }
+
+        async fn list_definitions(&self) -> Result<Vec<SagaDefinition>> {
+            Ok(Vec::new())
+        }
     }
 
     struct TestExecutor {
@@ -292,24 +875,179 @@ mod tests {
         }
     }
 
+    /// Asks for review instead of completing
+    struct ReviewExecutor;
+
+    #[async_trait]
+    impl StepExecutor for ReviewExecutor {
+        async fn execute(&self, _context: &StepContext) -> Result<serde_json::Value> {
+            Err(SagaError::PauseRequested("order needs review".to_string()))
+        }
+
+        async fn compensate(&self, _context: &StepContext) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Records who approved it as its result
+    struct ApprovedByExecutor;
+
+    #[async_trait]
+    impl StepExecutor for ApprovedByExecutor {
+        async fn execute(&self, context: &StepContext) -> Result<serde_json::Value> {
+            let approver = context.approval.as_ref().map(|a| a.approver.clone());
+            Ok(serde_json::json!({"approved_by": approver}))
+        }
+
+        async fn compensate(&self, _context: &StepContext) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Fails with the given error a fixed number of times, then succeeds
+    struct FlakyExecutor {
+        failures: u32,
+        attempts: Arc<AtomicU32>,
+        retryable: bool,
+    }
+
+    #[async_trait]
+    impl StepExecutor for FlakyExecutor {
+        async fn execute(&self, _context: &StepContext) -> Result<serde_json::Value> {
+            let attempt = self.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            if attempt > self.failures {
+                Ok(serde_json::json!({"attempt": attempt}))
+            } else if self.retryable {
+                Err(SagaError::Transient("temporarily unavailable".to_string()))
+            } else {
+                Err(SagaError::StepExecutionFailed("rejected".to_string()))
+            }
+        }
+
+        async fn compensate(&self, _context: &StepContext) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Never finishes on its own, like a call to a service that stopped answering
+    struct HangingExecutor {
+        attempts: Arc<AtomicU32>,
+    }
+
+    #[async_trait]
+    impl StepExecutor for HangingExecutor {
+        async fn execute(&self, _context: &StepContext) -> Result<serde_json::Value> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(serde_json::json!({}))
+        }
+
+        async fn compensate(&self, _context: &StepContext) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Executes fine, but compensation fails a fixed number of times
+    struct FlakyCompensationExecutor {
+        failures: u32,
+        attempts: Arc<AtomicU32>,
+    }
+
+    #[async_trait]
+    impl StepExecutor for FlakyCompensationExecutor {
+        async fn execute(&self, _context: &StepContext) -> Result<serde_json::Value> {
+            Ok(serde_json::json!({"reserved": true}))
+        }
+
+        async fn compensate(&self, _context: &StepContext) -> Result<()> {
+            let attempt = self.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            if attempt > self.failures {
+                Ok(())
+            } else {
+                Err(SagaError::CompensationFailed("release failed".to_string()))
+            }
+        }
+    }
+
+    /// Saga assembled from steps and their executors
     struct TestSaga {
+        saga_type: &'static str,
+        steps: Vec<SagaStep>,
         executors: HashMap<String, Box<dyn StepExecutor>>,
     }
 
     impl TestSaga {
-        fn new(should_fail: bool) -> Self {
-            let mut executors: HashMap<String, Box<dyn StepExecutor>> = HashMap::new();
-            executors.insert("step1".to_string(), Box::new(TestExecutor { should_fail: false }));
-            executors.insert("step2".to_string(), Box::new(TestExecutor { should_fail }));
+        fn new(saga_type: &'static str) -> Self {
+            Self {
+                saga_type,
+                steps: Vec::new(),
+                executors: HashMap::new(),
+            }
+        }
+
+        fn step(mut self, step: SagaStep, executor: impl StepExecutor + 'static) -> Self {
+            self.executors.insert(step.name.clone(), Box::new(executor));
+            self.steps.push(step);
+            self
+        }
+
+        /// Two plain steps, the second failing when asked to
+        fn two_step(should_fail: bool) -> Self {
+            Self::new("test_saga")
+                .step(SagaStep::new("step1".to_string(), 3), TestExecutor { should_fail: false })
+                .step(SagaStep::new("step2".to_string(), 3), TestExecutor { should_fail })
+        }
+
+        /// Second step needs approval before it runs
+        fn approval() -> Self {
+            Self::new("approval_saga")
+                .step(SagaStep::new("step1".to_string(), 3), TestExecutor { should_fail: false })
+                .step(
+                    SagaStep::new("gated".to_string(), 3).requiring_approval(),
+                    ApprovedByExecutor,
+                )
+        }
 
-            Self { executors }
+        fn flaky(failures: u32, retryable: bool, attempts: Arc<AtomicU32>) -> Self {
+            Self::new("flaky_saga")
+                .step(SagaStep::new("step1".to_string(), 3), TestExecutor { should_fail: false })
+                .step(
+                    SagaStep::new("flaky".to_string(), 3)
+                        .with_backoff(BackoffPolicy::Fixed { delay_ms: 0 }),
+                    FlakyExecutor {
+                        failures,
+                        attempts,
+                        retryable,
+                    },
+                )
+        }
+
+        fn hanging(attempts: Arc<AtomicU32>) -> Self {
+            Self::new("hanging_saga")
+                .step(SagaStep::new("step1".to_string(), 3), TestExecutor { should_fail: false })
+                .step(
+                    SagaStep::new("hang".to_string(), 2)
+                        .with_backoff(BackoffPolicy::Fixed { delay_ms: 0 }),
+                    HangingExecutor { attempts },
+                )
+        }
+
+        /// Reservation whose release fails `failures` times, followed by a failing charge
+        fn compensation(failures: u32, attempts: Arc<AtomicU32>) -> Self {
+            Self::new("compensation_saga")
+                .step(
+                    SagaStep::new("reserve".to_string(), 2)
+                        .with_backoff(BackoffPolicy::Fixed { delay_ms: 0 }),
+                    FlakyCompensationExecutor { failures, attempts },
+                )
+                .step(SagaStep::new("charge".to_string(), 0), TestExecutor { should_fail: true })
         }
     }
 
     #[async_trait]
     impl Saga for TestSaga {
         fn saga_type(&self) -> &str {
-            "test_saga"
+            self.saga_type
         }
 
         fn step_executors(&self) -> &HashMap<String, Box<dyn StepExecutor>> {
@@ -317,11 +1055,83 @@ mod tests {
         }
 
         async fn create_state(&self, saga_id: Uuid, data: serde_json::Value) -> Result<SagaState> {
-            let steps = vec![
-                SagaStep::new("step1".to_string(), 3),
-                SagaStep::new("step2".to_string(), 3),
-            ];
-            Ok(SagaState::new(saga_id, self.saga_type().to_string(), steps, data))
+            Ok(SagaState::new(saga_id, self.saga_type.to_string(), self.steps.clone(), data))
+        }
+
+        fn correlation_key(&self, data: &serde_json::Value) -> Option<String> {
+            data.get("order_id").and_then(|v| v.as_str()).map(str::to_string)
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryIdempotencyStore {
+        results: std::sync::Mutex<HashMap<String, serde_json::Value>>,
+        claims: std::sync::Mutex<HashMap<String, IdempotencyClaim>>,
+    }
+
+    #[async_trait]
+    impl StepIdempotencyStore for InMemoryIdempotencyStore {
+        async fn try_begin(&self, key: &str) -> Result<IdempotencyStatus> {
+            if let Some(result) = self.results.lock().unwrap().get(key) {
+                return Ok(IdempotencyStatus::Completed(result.clone()));
+            }
+            let mut claims = self.claims.lock().unwrap();
+            if claims.contains_key(key) {
+                return Ok(IdempotencyStatus::InProgress);
+            }
+            let claim = IdempotencyClaim::new();
+            claims.insert(key.to_string(), claim);
+            Ok(IdempotencyStatus::Started(claim))
+        }
+
+        async fn complete(
+            &self,
+            key: &str,
+            claim: IdempotencyClaim,
+            result: &serde_json::Value,
+        ) -> Result<()> {
+            let mut claims = self.claims.lock().unwrap();
+            if claims.get(key) != Some(&claim) {
+                return Err(SagaError::IdempotencyStoreError(format!("claim on {} lost", key)));
+            }
+            claims.remove(key);
+            self.results
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), result.clone());
+            Ok(())
+        }
+
+        async fn fail(&self, key: &str, claim: IdempotencyClaim) -> Result<()> {
+            let mut claims = self.claims.lock().unwrap();
+            if claims.get(key) == Some(&claim) {
+                claims.remove(key);
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingEventSink {
+        events: std::sync::Mutex<Vec<EventEnvelope>>,
+    }
+
+    impl RecordingEventSink {
+        fn event_types(&self) -> Vec<String> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|e| e.event_type.clone())
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl SagaEventSink for RecordingEventSink {
+        async fn emit(&self, envelope: &EventEnvelope) -> Result<()> {
+            self.events.lock().unwrap().push(envelope.clone());
+            Ok(())
         }
     }
 
@@ -329,7 +1139,7 @@ mod tests {
     async fn test_start_saga() {
         let repo = Arc::new(MockRepository::new());
         let coordinator = SagaCoordinator::new(repo.clone());
-        let saga = TestSaga::new(false);
+        let saga = TestSaga::two_step(false);
 
         let saga_id = Uuid::new_v4();
         let data = serde_json::json!({"test": "data"});
@@ -349,7 +1159,7 @@ mod tests {
     async fn test_run_saga_success() {
         let repo = Arc::new(MockRepository::new());
         let coordinator = SagaCoordinator::new(repo);
-        let saga = TestSaga::new(false);
+        let saga = TestSaga::two_step(false);
 
         let saga_id = Uuid::new_v4();
         let state = coordinator
@@ -362,4 +1172,503 @@ mod tests {
         assert_eq!(final_state.status, SagaStatus::Completed);
         assert_eq!(final_state.current_step, 2);
     }
+
+    #[tokio::test]
+    async fn test_pause_request_awaits_approval_at_step() {
+        let repo = Arc::new(MockRepository::new());
+        let sink = Arc::new(RecordingEventSink::default());
+        let coordinator = SagaCoordinator::new(repo.clone()).with_event_sink(sink.clone());
+        let saga = TestSaga::new("test_saga")
+            .step(SagaStep::new("step1".to_string(), 3), TestExecutor { should_fail: false })
+            .step(SagaStep::new("step2".to_string(), 3), ReviewExecutor);
+
+        let saga_id = Uuid::new_v4();
+        let state = coordinator
+            .start_saga(&saga, saga_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        let final_state = coordinator.run_saga(&saga, state).await.unwrap();
+
+        assert_eq!(final_state.status, SagaStatus::PausedAwaitingApproval);
+        assert_eq!(final_state.current_step, 1);
+        assert_eq!(final_state.steps[1].status, crate::step::StepStatus::Pending);
+        assert!(final_state.steps[1].awaits_approval());
+        assert_eq!(final_state.pause_reason.as_deref(), Some("order needs review"));
+        assert!(final_state.steps[0].is_completed());
+        assert_eq!(sink.event_types().last().unwrap(), "SagaPaused");
+
+        assert_eq!(
+            repo.load(saga_id).await.unwrap().status,
+            SagaStatus::PausedAwaitingApproval
+        );
+        assert!(coordinator.resume_saga(&saga, saga_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_approved_step_runs_with_approval() {
+        let coordinator = SagaCoordinator::new(Arc::new(MockRepository::new()));
+        let saga = TestSaga::approval();
+
+        let saga_id = Uuid::new_v4();
+        let state = coordinator
+            .start_saga(&saga, saga_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        let paused = coordinator.run_saga(&saga, state).await.unwrap();
+        assert_eq!(paused.status, SagaStatus::PausedAwaitingApproval);
+        assert_eq!(paused.current_step, 1);
+        assert!(paused.steps[1].result.is_none());
+        assert_eq!(
+            coordinator.find_sagas_awaiting_approval(10).await.unwrap().len(),
+            1
+        );
+
+        let approval = StepApproval {
+            approved: true,
+            approver: "ops@example.com".to_string(),
+            comment: Some("Known customer".to_string()),
+            decided_at: Utc::now(),
+        };
+        let final_state = coordinator
+            .decide_approval(&saga, saga_id, approval)
+            .await
+            .unwrap();
+
+        assert_eq!(final_state.status, SagaStatus::Completed);
+        assert_eq!(
+            final_state.steps[1].result,
+            Some(serde_json::json!({"approved_by": "ops@example.com"}))
+        );
+        assert!(coordinator.find_sagas_awaiting_approval(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_step_compensates_saga() {
+        let coordinator = SagaCoordinator::new(Arc::new(MockRepository::new()));
+        let saga = TestSaga::approval();
+
+        let saga_id = Uuid::new_v4();
+        let state = coordinator
+            .start_saga(&saga, saga_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        coordinator.run_saga(&saga, state).await.unwrap();
+
+        let rejection = StepApproval {
+            approved: false,
+            approver: "ops@example.com".to_string(),
+            comment: Some("Card reported stolen".to_string()),
+            decided_at: Utc::now(),
+        };
+        let final_state = coordinator
+            .decide_approval(&saga, saga_id, rejection.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(final_state.status, SagaStatus::Compensated);
+        assert!(final_state.steps[0].is_compensated());
+        assert!(final_state.steps[1].error.as_ref().unwrap().contains("Card reported stolen"));
+
+        // A decided saga cannot be decided again
+        assert!(matches!(
+            coordinator.decide_approval(&saga, saga_id, rejection).await,
+            Err(SagaError::InvalidStateTransition { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_retryable_step_failure_is_retried() {
+        let coordinator = SagaCoordinator::new(Arc::new(MockRepository::new()));
+        let attempts = Arc::new(AtomicU32::new(0));
+        let saga = TestSaga::flaky(2, true, attempts.clone());
+
+        let state = coordinator
+            .start_saga(&saga, Uuid::new_v4(), serde_json::json!({}))
+            .await
+            .unwrap();
+        let final_state = coordinator.run_saga(&saga, state).await.unwrap();
+
+        assert_eq!(final_state.status, SagaStatus::Completed);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(final_state.steps[1].retry_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_compensates_after_retries_exhausted() {
+        let coordinator = SagaCoordinator::new(Arc::new(MockRepository::new()));
+        let attempts = Arc::new(AtomicU32::new(0));
+        let saga = TestSaga::flaky(10, true, attempts.clone());
+
+        let state = coordinator
+            .start_saga(&saga, Uuid::new_v4(), serde_json::json!({}))
+            .await
+            .unwrap();
+        let final_state = coordinator.run_saga(&saga, state).await.unwrap();
+
+        assert_eq!(final_state.status, SagaStatus::Compensated);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_failure_compensates_immediately() {
+        let coordinator = SagaCoordinator::new(Arc::new(MockRepository::new()));
+        let attempts = Arc::new(AtomicU32::new(0));
+        let saga = TestSaga::flaky(1, false, attempts.clone());
+
+        let state = coordinator
+            .start_saga(&saga, Uuid::new_v4(), serde_json::json!({}))
+            .await
+            .unwrap();
+        let final_state = coordinator.run_saga(&saga, state).await.unwrap();
+
+        assert_eq!(final_state.status, SagaStatus::Compensated);
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_attempts_are_abandoned() {
+        let coordinator = SagaCoordinator::new(Arc::new(MockRepository::new()))
+            .with_step_timeout(Duration::from_millis(20));
+        let attempts = Arc::new(AtomicU32::new(0));
+        let saga = TestSaga::hanging(attempts.clone());
+
+        let state = coordinator
+            .start_saga(&saga, Uuid::new_v4(), serde_json::json!({}))
+            .await
+            .unwrap();
+        let final_state =
+            tokio::time::timeout(Duration::from_secs(5), coordinator.run_saga(&saga, state))
+                .await
+                .expect("step attempts should time out")
+                .unwrap();
+
+        assert_eq!(final_state.status, SagaStatus::Compensated);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert!(final_state.steps[1]
+            .error
+            .as_deref()
+            .is_some_and(|error| error.contains("timed out")));
+
+        let metrics = common::metrics::gather_metrics().unwrap();
+        assert!(metrics.contains(
+            r#"cqrs_saga_steps_total{action="execute",outcome="timeout",saga_type="hanging_saga",step="hang"}"#
+        ));
+        assert!(metrics.contains(
+            r#"cqrs_saga_steps_total{action="compensate",outcome="success",saga_type="hanging_saga",step="step1"}"#
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_abandons_running_step() {
+        let repo = Arc::new(MockRepository::new());
+        let coordinator = SagaCoordinator::new(repo.clone());
+        let attempts = Arc::new(AtomicU32::new(0));
+        let saga = TestSaga::hanging(attempts.clone());
+
+        let state = coordinator
+            .start_saga(&saga, Uuid::new_v4(), serde_json::json!({}))
+            .await
+            .unwrap();
+        let saga_id = state.saga_id;
+        let (result, _) = tokio::join!(coordinator.run_saga(&saga, state), async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            coordinator.shutdown();
+        });
+
+        assert!(matches!(result, Err(SagaError::Cancelled(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        // Left where it stopped, to run the step again on resume
+        let saved = repo.load(saga_id).await.unwrap();
+        assert_eq!(saved.status, SagaStatus::Running);
+        assert_eq!(saved.current_step, 1);
+        assert_eq!(saved.steps[1].status, crate::step::StepStatus::Pending);
+        assert_eq!(saved.steps[1].retry_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_compensation_is_retried() {
+        let coordinator = SagaCoordinator::new(Arc::new(MockRepository::new()));
+        let attempts = Arc::new(AtomicU32::new(0));
+        let saga = TestSaga::compensation(2, attempts.clone());
+
+        let state = coordinator
+            .start_saga(&saga, Uuid::new_v4(), serde_json::json!({}))
+            .await
+            .unwrap();
+        let final_state = coordinator.run_saga(&saga, state).await.unwrap();
+
+        assert_eq!(final_state.status, SagaStatus::Compensated);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert!(final_state.steps[0].is_compensated());
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_compensation_requires_intervention() {
+        let repo = Arc::new(MockRepository::new());
+        let coordinator = SagaCoordinator::new(repo.clone());
+        let attempts = Arc::new(AtomicU32::new(0));
+        let saga = TestSaga::compensation(10, attempts.clone());
+
+        let saga_id = Uuid::new_v4();
+        let state = coordinator
+            .start_saga(&saga, saga_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(coordinator.run_saga(&saga, state).await.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        let stored = repo.load(saga_id).await.unwrap();
+        assert_eq!(stored.status, SagaStatus::RequiresIntervention);
+        assert!(stored.intervention_reason.unwrap().contains("reserve"));
+
+        let queued = coordinator.find_sagas_requiring_intervention(10).await.unwrap();
+        assert_eq!(queued.len(), 1);
+        assert!(coordinator.resume_saga(&saga, saga_id).await.is_err());
+
+        let resolved = coordinator
+            .resolve_intervention(saga_id, "Inventory released manually".to_string())
+            .await
+            .unwrap();
+        assert_eq!(resolved.status, SagaStatus::Compensated);
+        assert!(coordinator.find_sagas_requiring_intervention(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_requires_intervention_status() {
+        let coordinator = SagaCoordinator::new(Arc::new(MockRepository::new()));
+        let saga = TestSaga::two_step(false);
+        let saga_id = Uuid::new_v4();
+        coordinator
+            .start_saga(&saga, saga_id, serde_json::json!({}))
+            .await
+            .unwrap();
+
+        let result = coordinator
+            .resolve_intervention(saga_id, "nothing to do".to_string())
+            .await;
+        assert!(matches!(result, Err(SagaError::InvalidStateTransition { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_step_results_are_recorded_by_idempotency_key() {
+        let store = Arc::new(InMemoryIdempotencyStore::default());
+        let coordinator = SagaCoordinator::new(Arc::new(MockRepository::new()))
+            .with_idempotency_store(store.clone());
+        let attempts = Arc::new(AtomicU32::new(0));
+        let saga = TestSaga::flaky(1, true, attempts.clone());
+
+        let saga_id = Uuid::new_v4();
+        let state = coordinator
+            .start_saga(&saga, saga_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        let final_state = coordinator.run_saga(&saga, state).await.unwrap();
+
+        assert_eq!(final_state.status, SagaStatus::Completed);
+        let results = store.results.lock().unwrap();
+        assert!(results.contains_key(&format!("saga:{}:step1:0", saga_id)));
+        // The failed first attempt is not recorded, only the successful retry
+        assert_eq!(
+            results[&format!("saga:{}:flaky:0", saga_id)],
+            serde_json::json!({"attempt": 2})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recorded_step_is_not_executed_again() {
+        let store = Arc::new(InMemoryIdempotencyStore::default());
+        let coordinator = SagaCoordinator::new(Arc::new(MockRepository::new()))
+            .with_idempotency_store(store.clone());
+        let attempts = Arc::new(AtomicU32::new(0));
+        let saga = TestSaga::flaky(0, true, attempts.clone());
+
+        let saga_id = Uuid::new_v4();
+        // Simulate a crash after the flaky step ran but before its result was persisted
+        store.results.lock().unwrap().insert(
+            format!("saga:{}:flaky:0", saga_id),
+            serde_json::json!({"attempt": 1}),
+        );
+
+        let state = coordinator
+            .start_saga(&saga, saga_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        let final_state = coordinator.run_saga(&saga, state).await.unwrap();
+
+        assert_eq!(final_state.status, SagaStatus::Completed);
+        assert_eq!(attempts.load(Ordering::SeqCst), 0);
+        assert_eq!(
+            final_state.steps[1].result,
+            Some(serde_json::json!({"attempt": 1}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_step_claimed_elsewhere_is_not_executed() {
+        let store = Arc::new(InMemoryIdempotencyStore::default());
+        let coordinator = SagaCoordinator::new(Arc::new(MockRepository::new()))
+            .with_idempotency_store(store.clone());
+        let attempts = Arc::new(AtomicU32::new(0));
+        let saga = TestSaga::flaky(0, true, attempts.clone());
+
+        let saga_id = Uuid::new_v4();
+        // Another coordinator is running the flaky step of the same saga
+        store
+            .claims
+            .lock()
+            .unwrap()
+            .insert(format!("saga:{}:flaky:0", saga_id), IdempotencyClaim::new());
+
+        let state = coordinator
+            .start_saga(&saga, saga_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        let final_state = coordinator.run_saga(&saga, state).await.unwrap();
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 0);
+        assert_ne!(final_state.status, SagaStatus::Completed);
+        assert!(final_state.steps[1]
+            .error
+            .as_deref()
+            .is_some_and(|error| error.contains("already running elsewhere")));
+    }
+
+    #[tokio::test]
+    async fn test_emits_lifecycle_events_on_success() {
+        let sink = Arc::new(RecordingEventSink::default());
+        let coordinator =
+            SagaCoordinator::new(Arc::new(MockRepository::new())).with_event_sink(sink.clone());
+        let attempts = Arc::new(AtomicU32::new(0));
+        let saga = TestSaga::flaky(1, true, attempts);
+
+        let saga_id = Uuid::new_v4();
+        let state = coordinator
+            .start_saga(&saga, saga_id, serde_json::json!({}))
+            .await
+            .unwrap();
+        coordinator.run_saga(&saga, state).await.unwrap();
+
+        assert_eq!(
+            sink.event_types(),
+            vec![
+                "SagaStarted",
+                "SagaStepCompleted",
+                "SagaStepFailed",
+                "SagaStepCompleted",
+                "SagaCompleted",
+            ]
+        );
+        let events = sink.events.lock().unwrap();
+        assert!(events.iter().all(|e| e.aggregate_id == saga_id));
+        assert_eq!(events[2].payload["will_retry"], true);
+        assert_eq!(events[3].payload["attempt"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_emits_compensated_event() {
+        let sink = Arc::new(RecordingEventSink::default());
+        let coordinator =
+            SagaCoordinator::new(Arc::new(MockRepository::new())).with_event_sink(sink.clone());
+        let correlation_id = Uuid::new_v4();
+        let saga = TestSaga::flaky(1, false, Arc::new(AtomicU32::new(0)));
+
+        let state = coordinator
+            .start_saga(
+                &saga,
+                Uuid::new_v4(),
+                serde_json::json!({"correlation_id": correlation_id}),
+            )
+            .await
+            .unwrap();
+        coordinator.run_saga(&saga, state).await.unwrap();
+
+        let types = sink.event_types();
+        assert_eq!(types.last().unwrap(), "SagaCompensated");
+        assert!(types.contains(&"SagaStepFailed".to_string()));
+        let events = sink.events.lock().unwrap();
+        assert!(events
+            .iter()
+            .all(|e| e.metadata.correlation_id == correlation_id));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_saga_for_same_correlation_key_is_rejected() {
+        let coordinator = SagaCoordinator::new(Arc::new(MockRepository::new()));
+        let saga = TestSaga::two_step(false);
+        let data = serde_json::json!({"order_id": Uuid::new_v4()});
+
+        let first = coordinator
+            .start_saga_in_partition(&saga, Uuid::new_v4(), data.clone(), Some(0))
+            .await
+            .unwrap();
+        assert!(first.correlation_key.is_some());
+
+        let second = coordinator
+            .start_saga_in_partition(&saga, Uuid::new_v4(), data, Some(1))
+            .await;
+        assert!(matches!(second, Err(SagaError::DuplicateSaga { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_recover_only_owned_partitions() {
+        let repo = Arc::new(MockRepository::new());
+        let coordinator = SagaCoordinator::new(repo.clone());
+        let saga = TestSaga::two_step(false);
+
+        let mut state = coordinator
+            .start_saga_in_partition(&saga, Uuid::new_v4(), serde_json::json!({}), Some(3))
+            .await
+            .unwrap();
+        state.updated_at = Utc::now() - chrono::Duration::minutes(5);
+        repo.update(&state).await.unwrap();
+
+        let stale_after = std::time::Duration::from_secs(60);
+        let recovered = coordinator
+            .recover_partitions(&saga, &[1, 2], stale_after, 10)
+            .await
+            .unwrap();
+        assert_eq!(recovered, 0);
+
+        let recovered = coordinator
+            .recover_partitions(&saga, &[3], stale_after, 10)
+            .await
+            .unwrap();
+        assert_eq!(recovered, 1);
+        assert!(repo.load(state.saga_id).await.unwrap().is_completed());
+    }
+
+    #[tokio::test]
+    async fn test_replayed_trigger_attaches_to_existing_saga() {
+        let repo = Arc::new(MockRepository::new());
+        let coordinator = SagaCoordinator::new(repo.clone());
+        let saga = TestSaga::two_step(false);
+        let order_id = Uuid::new_v4().to_string();
+        let data = serde_json::json!({"order_id": order_id});
+
+        let (first, created) = coordinator
+            .start_or_attach(&saga, Uuid::new_v4(), data.clone(), Some(0))
+            .await
+            .unwrap();
+        assert!(created);
+
+        let (second, created) = coordinator
+            .start_or_attach(&saga, Uuid::new_v4(), data, Some(0))
+            .await
+            .unwrap();
+        assert!(!created);
+        assert_eq!(second.saga_id, first.saga_id);
+
+        let found = repo
+            .find_by_business_key("test_saga", &order_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.saga_id, first.saga_id);
+        assert!(repo
+            .find_by_business_key("other_saga", &order_id)
+            .await
+            .unwrap()
+            .is_none());
+    }
 }
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::retry::BackoffPolicy;
+use crate::step::SagaStep;
+
+/// Machine-readable description of a saga's flow
+///
+/// Steps run in order. When one fails for good, the steps completed before it are
+/// compensated in reverse order. Sagas build their steps from their definition, so
+/// diagrams rendered from it show what actually runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SagaDefinition {
+    pub saga_type: String,
+    pub steps: Vec<StepDefinition>,
+}
+
+impl SagaDefinition {
+    pub fn new(saga_type: impl Into<String>) -> Self {
+        Self {
+            saga_type: saga_type.into(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Append a step to the flow
+    pub fn step(mut self, step: StepDefinition) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Fresh steps for a new saga instance
+    pub fn create_steps(&self) -> Vec<SagaStep> {
+        self.steps.iter().map(StepDefinition::to_step).collect()
+    }
+
+    /// Steps compensated when `failed_step` fails, in the order they are compensated
+    pub fn compensation_path(&self, failed_step: &str) -> Vec<&str> {
+        let mut path: Vec<&str> = self
+            .steps
+            .iter()
+            .take_while(|step| step.name != failed_step)
+            .map(|step| step.name.as_str())
+            .collect();
+        path.reverse();
+        path
+    }
+}
+
+/// One step of a [`SagaDefinition`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepDefinition {
+    pub name: String,
+    /// What compensating the step undoes; `None` if it has no side effects
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compensation: Option<String>,
+    pub max_retries: u32,
+    pub backoff: BackoffPolicy,
+    /// Longest a single attempt may take, if bounded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// When the saga pauses for approval before this step, if it can
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<String>,
+}
+
+impl StepDefinition {
+    pub fn new(name: impl Into<String>, max_retries: u32) -> Self {
+        Self {
+            name: name.into(),
+            compensation: None,
+            max_retries,
+            backoff: BackoffPolicy::default(),
+            timeout_ms: None,
+            approval: None,
+        }
+    }
+
+    /// Describe what compensating the step undoes
+    pub fn compensated_by(mut self, compensation: impl Into<String>) -> Self {
+        self.compensation = Some(compensation.into());
+        self
+    }
+
+    pub fn with_backoff(mut self, backoff: BackoffPolicy) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_ms = Some(timeout.as_millis() as u64);
+        self
+    }
+
+    /// Describe when the saga pauses for approval before this step
+    pub fn with_approval(mut self, condition: impl Into<String>) -> Self {
+        self.approval = Some(condition.into());
+        self
+    }
+
+    /// A pending step as defined; whether an instance needs approval is up to the saga
+    pub fn to_step(&self) -> SagaStep {
+        let mut step =
+            SagaStep::new(self.name.clone(), self.max_retries).with_backoff(self.backoff.clone());
+        step.timeout_ms = self.timeout_ms;
+        step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition() -> SagaDefinition {
+        SagaDefinition::new("TestSaga")
+            .step(StepDefinition::new("reserve", 3).compensated_by("release"))
+            .step(
+                StepDefinition::new("check", 1)
+                    .with_backoff(BackoffPolicy::Fixed { delay_ms: 500 })
+                    .with_timeout(Duration::from_secs(2)),
+            )
+            .step(StepDefinition::new("charge", 3).compensated_by("refund"))
+    }
+
+    #[test]
+    fn test_steps_are_created_from_definition() {
+        let steps = definition().create_steps();
+
+        let names: Vec<&str> = steps.iter().map(|step| step.name.as_str()).collect();
+        assert_eq!(names, ["reserve", "check", "charge"]);
+        assert_eq!(steps[1].max_retries, 1);
+        assert_eq!(steps[1].backoff, BackoffPolicy::Fixed { delay_ms: 500 });
+        assert!(!steps[2].requires_approval);
+    }
+
+    #[test]
+    fn test_compensation_path_runs_backwards_from_failure() {
+        let definition = definition();
+        assert_eq!(definition.compensation_path("charge"), ["check", "reserve"]);
+        assert!(definition.compensation_path("reserve").is_empty());
+    }
+
+    #[test]
+    fn test_definition_json() {
+        let value = serde_json::to_value(definition()).unwrap();
+
+        assert_eq!(value["saga_type"], "TestSaga");
+        assert_eq!(value["steps"][0]["compensation"], "release");
+        assert_eq!(value["steps"][1]["timeout_ms"], 2000);
+        assert_eq!(value["steps"][1]["backoff"]["type"], "fixed");
+        assert!(value["steps"][1].get("compensation").is_none());
+    }
+}
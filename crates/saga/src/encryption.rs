@@ -0,0 +1,348 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::errors::{Result, SagaError};
+
+/// Field of a stored saga state holding its encrypted envelope
+const ENVELOPE_FIELD: &str = "encrypted";
+
+/// Saga data kept in plaintext, so sagas can still be looked up and indexed by it
+const PLAINTEXT_DATA_FIELDS: [&str; 2] = ["correlation_id", "order_id"];
+
+/// AES-GCM nonce length in bytes
+const NONCE_LEN: usize = 12;
+
+/// Key-encryption keys wrapping the data key each saga state is encrypted with
+///
+/// Implement it over a KMS to keep the keys out of the service; [`StaticKeyProvider`]
+/// holds them in memory from configuration.
+#[async_trait]
+pub trait KeyProvider: Send + Sync {
+    /// Key new data keys are wrapped with
+    fn active_key_id(&self) -> &str;
+
+    async fn wrap_key(&self, key_id: &str, data_key: &[u8]) -> Result<Vec<u8>>;
+
+    async fn unwrap_key(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// AES-256 key-encryption keys from configuration
+pub struct StaticKeyProvider {
+    active: String,
+    keys: HashMap<String, Aes256Gcm>,
+}
+
+impl StaticKeyProvider {
+    /// 32-byte keys by ID; the first is active, the others still decrypt sagas
+    /// written before a rotation
+    pub fn new(keys: Vec<(String, Vec<u8>)>) -> Result<Self> {
+        let active = keys
+            .first()
+            .map(|(id, _)| id.clone())
+            .ok_or_else(|| SagaError::EncryptionError("no encryption keys given".to_string()))?;
+        let keys = keys
+            .into_iter()
+            .map(|(id, key)| {
+                let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| {
+                    SagaError::EncryptionError(format!("key '{}' must be 32 bytes", id))
+                })?;
+                Ok((id, cipher))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { active, keys })
+    }
+
+    /// Keys from `SAGA_ENCRYPTION_KEYS`, comma-separated `id:base64-key` pairs with
+    /// the active key first; `None` when unset
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var("SAGA_ENCRYPTION_KEYS") {
+            Ok(spec) if !spec.trim().is_empty() => Self::parse(&spec).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn parse(spec: &str) -> Result<Self> {
+        let keys = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (id, key) = entry.split_once(':').ok_or_else(|| {
+                    SagaError::EncryptionError(format!("expected id:base64-key, got '{}'", entry))
+                })?;
+                let key = BASE64.decode(key.trim()).map_err(|e| {
+                    SagaError::EncryptionError(format!("key '{}' is not base64: {}", id, e))
+                })?;
+                Ok((id.trim().to_string(), key))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(keys)
+    }
+
+    fn key(&self, key_id: &str) -> Result<&Aes256Gcm> {
+        self.keys
+            .get(key_id)
+            .ok_or_else(|| SagaError::EncryptionError(format!("unknown key '{}'", key_id)))
+    }
+}
+
+#[async_trait]
+impl KeyProvider for StaticKeyProvider {
+    fn active_key_id(&self) -> &str {
+        &self.active
+    }
+
+    async fn wrap_key(&self, key_id: &str, data_key: &[u8]) -> Result<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let wrapped = self
+            .key(key_id)?
+            .encrypt(&nonce, data_key)
+            .map_err(|_| SagaError::EncryptionError("failed to wrap data key".to_string()))?;
+        Ok([nonce.as_slice(), &wrapped].concat())
+    }
+
+    async fn unwrap_key(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>> {
+        if wrapped.len() < NONCE_LEN {
+            return Err(SagaError::EncryptionError(
+                "wrapped data key is truncated".to_string(),
+            ));
+        }
+        let (nonce, wrapped) = wrapped.split_at(NONCE_LEN);
+        self.key(key_id)?
+            .decrypt(Nonce::from_slice(nonce), wrapped)
+            .map_err(|_| {
+                SagaError::EncryptionError(format!("failed to unwrap data key with '{}'", key_id))
+            })
+    }
+}
+
+/// Encrypted part of a stored saga state
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    key_id: String,
+    wrapped_key: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// What an envelope's ciphertext holds
+#[derive(Serialize, Deserialize)]
+struct Sealed {
+    data: Value,
+    results: Vec<Value>,
+}
+
+/// Envelope encryption of the order and payment context in stored saga states
+///
+/// The saga data and step results are encrypted with a fresh data key per write,
+/// which the [`KeyProvider`] wraps. Everything else, and the IDs in
+/// `PLAINTEXT_DATA_FIELDS`, stays readable so sagas can still be queried.
+#[derive(Clone)]
+pub struct SagaCipher {
+    keys: Arc<dyn KeyProvider>,
+}
+
+impl SagaCipher {
+    pub fn new(keys: Arc<dyn KeyProvider>) -> Self {
+        Self { keys }
+    }
+
+    pub fn active_key_id(&self) -> &str {
+        self.keys.active_key_id()
+    }
+
+    /// Encrypt the data and step results of a serialized saga state
+    ///
+    /// The ciphertext is bound to `saga_id`, so it cannot be copied onto another saga.
+    pub async fn seal(&self, saga_id: Uuid, mut state: Value) -> Result<Value> {
+        let fields = state
+            .as_object_mut()
+            .ok_or_else(|| SagaError::EncryptionError("saga state is not an object".to_string()))?;
+
+        let data = fields.get_mut("data").map(Value::take).unwrap_or_default();
+        let visible = PLAINTEXT_DATA_FIELDS
+            .iter()
+            .filter_map(|field| {
+                data.get(field)
+                    .map(|value| (field.to_string(), value.clone()))
+            })
+            .collect();
+        fields.insert("data".to_string(), Value::Object(visible));
+
+        let results = fields
+            .get_mut("steps")
+            .and_then(Value::as_array_mut)
+            .map(|steps| {
+                steps
+                    .iter_mut()
+                    .map(|step| step.get_mut("result").map(Value::take).unwrap_or_default())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let plaintext = serde_json::to_vec(&Sealed { data, results })?;
+        let data_key = Aes256Gcm::generate_key(OsRng);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = Aes256Gcm::new(&data_key)
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plaintext,
+                    aad: saga_id.as_bytes(),
+                },
+            )
+            .map_err(|_| SagaError::EncryptionError("failed to encrypt saga state".to_string()))?;
+
+        let key_id = self.keys.active_key_id().to_string();
+        let wrapped_key = self.keys.wrap_key(&key_id, &data_key).await?;
+        let envelope = Envelope {
+            key_id,
+            wrapped_key: BASE64.encode(wrapped_key),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        fields.insert(ENVELOPE_FIELD.to_string(), serde_json::to_value(envelope)?);
+        Ok(state)
+    }
+
+    /// Restore a state written by [`seal`](Self::seal); plaintext states pass through
+    pub async fn open(&self, saga_id: Uuid, mut state: Value) -> Result<Value> {
+        let Some(fields) = state.as_object_mut() else {
+            return Ok(state);
+        };
+        let Some(envelope) = fields.remove(ENVELOPE_FIELD) else {
+            return Ok(state);
+        };
+        let envelope: Envelope = serde_json::from_value(envelope)?;
+
+        let data_key = self
+            .keys
+            .unwrap_key(&envelope.key_id, &decode(&envelope.wrapped_key)?)
+            .await?;
+        let nonce = decode(&envelope.nonce)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(SagaError::EncryptionError("invalid nonce".to_string()));
+        }
+        let ciphertext = decode(&envelope.ciphertext)?;
+        let plaintext = Aes256Gcm::new_from_slice(&data_key)
+            .map_err(|_| SagaError::EncryptionError("invalid data key".to_string()))?
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: saga_id.as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                SagaError::EncryptionError(format!("failed to decrypt state of saga {}", saga_id))
+            })?;
+        let sealed: Sealed = serde_json::from_slice(&plaintext)?;
+
+        fields.insert("data".to_string(), sealed.data);
+        if let Some(steps) = fields.get_mut("steps").and_then(Value::as_array_mut) {
+            for (step, result) in steps.iter_mut().zip(sealed.results) {
+                if let Some(step) = step.as_object_mut() {
+                    step.insert("result".to_string(), result);
+                }
+            }
+        }
+        Ok(state)
+    }
+}
+
+/// Whether a stored saga state was written by [`SagaCipher::seal`]
+pub fn is_encrypted(state: &Value) -> bool {
+    state.get(ENVELOPE_FIELD).is_some()
+}
+
+fn decode(value: &str) -> Result<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|e| SagaError::EncryptionError(format!("invalid envelope: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::saga::SagaState;
+    use crate::step::SagaStep;
+
+    fn keys(spec: &str) -> Arc<dyn KeyProvider> {
+        Arc::new(StaticKeyProvider::parse(spec).unwrap())
+    }
+
+    fn key(byte: u8) -> String {
+        BASE64.encode([byte; 32])
+    }
+
+    fn order_saga() -> SagaState {
+        let mut step = SagaStep::new("ProcessPayment".to_string(), 3);
+        step.mark_completed(serde_json::json!({"card_last4": "4242"}));
+        SagaState::new(
+            Uuid::new_v4(),
+            "OrderProcessingSaga".to_string(),
+            vec![step],
+            serde_json::json!({
+                "order_id": "o-1",
+                "correlation_id": "c-1",
+                "shipping_address": "1 Main St",
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_seal_hides_data_and_results_until_opened() {
+        let cipher = SagaCipher::new(keys(&format!("k1:{}", key(1))));
+        let saga = order_saga();
+        let state = serde_json::to_value(&saga).unwrap();
+
+        let sealed = cipher.seal(saga.saga_id, state.clone()).await.unwrap();
+        let stored = sealed.to_string();
+        assert!(is_encrypted(&sealed));
+        assert!(!stored.contains("1 Main St") && !stored.contains("4242"));
+        assert_eq!(
+            sealed["data"],
+            serde_json::json!({"order_id": "o-1", "correlation_id": "c-1"})
+        );
+
+        assert_eq!(
+            cipher.open(saga.saga_id, sealed.clone()).await.unwrap(),
+            state
+        );
+        // Bound to the saga it was written for
+        assert!(cipher.open(Uuid::new_v4(), sealed).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rotated_keys_still_open_older_states() {
+        let saga = order_saga();
+        let state = serde_json::to_value(&saga).unwrap();
+        let old = SagaCipher::new(keys(&format!("k1:{}", key(1))));
+        let sealed = old.seal(saga.saga_id, state.clone()).await.unwrap();
+
+        let rotated = SagaCipher::new(keys(&format!("k2:{}, k1:{}", key(2), key(1))));
+        assert_eq!(rotated.active_key_id(), "k2");
+        assert_eq!(
+            rotated.open(saga.saga_id, sealed.clone()).await.unwrap(),
+            state
+        );
+
+        let retired = SagaCipher::new(keys(&format!("k2:{}", key(2))));
+        assert!(retired.open(saga.saga_id, sealed).await.is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_keys() {
+        assert!(StaticKeyProvider::parse("").is_err());
+        assert!(StaticKeyProvider::parse("k1").is_err());
+        assert!(StaticKeyProvider::parse(&format!("k1:{}", BASE64.encode([1u8; 16]))).is_err());
+    }
+}
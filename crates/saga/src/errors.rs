@@ -5,6 +5,21 @@ pub enum SagaError {
     #[error("Step execution failed: {0}")]
     StepExecutionFailed(String),
 
+    #[error("Transient step failure: {0}")]
+    Transient(String),
+
+    /// The step cannot decide on its own; the saga pauses until it is reviewed
+    #[error("Step requires review: {0}")]
+    PauseRequested(String),
+
+    /// The attempt ran past its deadline
+    #[error("Step timed out: {0}")]
+    StepTimeout(String),
+
+    /// The coordinator is shutting down; the step runs again when the saga is resumed
+    #[error("Saga execution cancelled: {0}")]
+    Cancelled(String),
+
     #[error("Compensation failed: {0}")]
     CompensationFailed(String),
 
@@ -26,14 +41,55 @@ pub enum SagaError {
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
+    #[error("Saga {saga_type} already exists for {correlation_key}")]
+    DuplicateSaga {
+        saga_type: String,
+        correlation_key: String,
+    },
+
     #[error("Saga not found: {0}")]
     SagaNotFound(String),
 
     #[error("Step not found: {0}")]
     StepNotFound(String),
 
+    #[error("Idempotency store error: {0}")]
+    IdempotencyStoreError(String),
+
+    #[error("Encryption error: {0}")]
+    EncryptionError(String),
+
     #[error("Internal error: {0}")]
     InternalError(String),
 }
 
+impl SagaError {
+    /// Whether the failed operation may succeed if attempted again
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            SagaError::Transient(_)
+                | SagaError::StepTimeout(_)
+                | SagaError::DatabaseError(_)
+                | SagaError::IdempotencyStoreError(_)
+        )
+    }
+}
+
 pub type Result<T> = std::result::Result<T, SagaError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryable_classification() {
+        assert!(SagaError::Transient("timeout".to_string()).is_retryable());
+        assert!(SagaError::DatabaseError(sqlx::Error::PoolTimedOut).is_retryable());
+        assert!(!SagaError::StepExecutionFailed("declined".to_string()).is_retryable());
+        assert!(SagaError::StepTimeout("charge".to_string()).is_retryable());
+        assert!(!SagaError::Cancelled("shutdown".to_string()).is_retryable());
+        assert!(!SagaError::AlreadyCompleted.is_retryable());
+        assert!(!SagaError::PauseRequested("manual review".to_string()).is_retryable());
+    }
+}
@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use domain::events::EventEnvelope;
+use messaging::Publisher;
+use std::sync::Arc;
+
+use crate::errors::{Result, SagaError};
+
+/// Destination for saga lifecycle events (Kafka topic, event store, ...)
+#[async_trait]
+pub trait SagaEventSink: Send + Sync {
+    async fn emit(&self, envelope: &EventEnvelope) -> Result<()>;
+}
+
+/// Publishes saga lifecycle events to a dedicated topic, keyed by saga ID
+pub struct PublisherEventSink {
+    publisher: Arc<dyn Publisher>,
+}
+
+impl PublisherEventSink {
+    pub fn new(publisher: Arc<dyn Publisher>) -> Self {
+        Self { publisher }
+    }
+}
+
+#[async_trait]
+impl SagaEventSink for PublisherEventSink {
+    async fn emit(&self, envelope: &EventEnvelope) -> Result<()> {
+        self.publisher
+            .publish(envelope.aggregate_id, envelope)
+            .await
+            .map_err(|e| SagaError::Transient(format!("Failed to publish saga event: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::events::EventMetadata;
+    use messaging::InMemoryPublisher;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_emit_publishes_keyed_by_saga() {
+        let publisher = Arc::new(InMemoryPublisher::new("saga-events"));
+        let sink = PublisherEventSink::new(publisher.clone());
+        let saga_id = Uuid::new_v4();
+        let envelope = EventEnvelope {
+            event_id: Uuid::new_v4(),
+            aggregate_id: saga_id,
+            aggregate_type: "Saga".to_string(),
+            event_type: "SagaStarted".to_string(),
+            event_version: 1,
+            payload: serde_json::json!({}),
+            metadata: EventMetadata::new(),
+            timestamp: chrono::Utc::now(),
+            sequence_number: None,
+        };
+
+        sink.emit(&envelope).await.unwrap();
+        let messages = publisher.messages();
+        assert_eq!(messages[0].key, saga_id);
+        assert_eq!(messages[0].payload["event_type"], "SagaStarted");
+
+        publisher.set_unavailable(true);
+        assert!(matches!(sink.emit(&envelope).await, Err(SagaError::Transient(_))));
+    }
+}
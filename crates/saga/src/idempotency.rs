@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use event_store::{IdempotencyClaim, IdempotencyStatus, IdempotencyStore};
+
+use crate::errors::{Result, SagaError};
+
+/// Store recording the results of saga steps that have already run
+#[async_trait]
+pub trait StepIdempotencyStore: Send + Sync {
+    /// Atomically claim the key of a step about to run; only one caller gets `Started`
+    async fn try_begin(&self, key: &str) -> Result<IdempotencyStatus>;
+
+    /// Record the result of a step whose key was claimed with `try_begin`
+    async fn complete(
+        &self,
+        key: &str,
+        claim: IdempotencyClaim,
+        result: &serde_json::Value,
+    ) -> Result<()>;
+
+    /// Release the key of a step that did not complete, so a retry can claim it
+    async fn fail(&self, key: &str, claim: IdempotencyClaim) -> Result<()>;
+}
+
+/// Steps can be recorded in event-store's Redis `IdempotencyChecker` or `PgIdempotencyStore`
+#[async_trait]
+impl<T: IdempotencyStore> StepIdempotencyStore for T {
+    async fn try_begin(&self, key: &str) -> Result<IdempotencyStatus> {
+        IdempotencyStore::try_begin(self, key)
+            .await
+            .map_err(|e| SagaError::IdempotencyStoreError(e.to_string()))
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        claim: IdempotencyClaim,
+        result: &serde_json::Value,
+    ) -> Result<()> {
+        IdempotencyStore::complete(self, key, claim, result)
+            .await
+            .map_err(|e| SagaError::IdempotencyStoreError(e.to_string()))
+    }
+
+    async fn fail(&self, key: &str, claim: IdempotencyClaim) -> Result<()> {
+        IdempotencyStore::fail(self, key, claim)
+            .await
+            .map_err(|e| SagaError::IdempotencyStoreError(e.to_string()))
+    }
+}
@@ -3,9 +3,21 @@ pub mod step;
 pub mod coordinator;
 pub mod repository;
 pub mod errors;
+pub mod retry;
+pub mod idempotency;
+pub mod event_sink;
+pub mod definition;
+pub mod encryption;
 
-pub use saga::{Saga, SagaState, SagaStatus};
-pub use step::{SagaStep, StepStatus};
+pub use saga::{PendingSagas, Saga, SagaState, SagaStatus};
+pub use step::{
+    EmittedEvent, SagaStep, StepApproval, StepContext, StepLimits, StepStatus, EMITTED_EVENT_KEY,
+};
 pub use coordinator::SagaCoordinator;
-pub use repository::{SagaRepository, SagaInstance};
+pub use repository::{InMemorySagaRepository, SagaRepository, SagaInstance};
 pub use errors::SagaError;
+pub use retry::BackoffPolicy;
+pub use idempotency::StepIdempotencyStore;
+pub use event_sink::{PublisherEventSink, SagaEventSink};
+pub use definition::{SagaDefinition, StepDefinition};
+pub use encryption::{KeyProvider, SagaCipher, StaticKeyProvider};
@@ -2,8 +2,12 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
+use crate::definition::SagaDefinition;
+use crate::encryption::{is_encrypted, KeyProvider, SagaCipher};
 use crate::errors::{Result, SagaError};
 use crate::saga::{SagaState, SagaStatus};
 
@@ -32,7 +36,14 @@ impl SagaInstance {
         })
     }
 
+    /// Fails for an encrypted state, which only a repository with its keys can read
     pub fn to_saga_state(&self) -> Result<SagaState> {
+        if is_encrypted(&self.state) {
+            return Err(SagaError::EncryptionError(format!(
+                "state of saga {} is encrypted",
+                self.saga_id
+            )));
+        }
         Ok(serde_json::from_value(self.state.clone())?)
     }
 }
@@ -46,38 +57,151 @@ pub trait SagaRepository: Send + Sync {
     /// Update an existing saga instance
     async fn update(&self, state: &SagaState) -> Result<()>;
 
+    /// Update a saga instance only while its stored status is still `expected`
+    ///
+    /// Returns `false`, changing nothing, when the saga is missing or another writer
+    /// has already moved it to a different status.
+    async fn update_if_status(&self, state: &SagaState, expected: SagaStatus) -> Result<bool>;
+
     /// Load a saga instance by ID
     async fn load(&self, saga_id: Uuid) -> Result<SagaState>;
 
+    /// Find the saga of a type for a business (correlation) key, e.g. an order ID
+    async fn find_by_business_key(&self, saga_type: &str, key: &str) -> Result<Option<SagaState>>;
+
     /// Find sagas by status
     async fn find_by_status(&self, status: SagaStatus, limit: i64) -> Result<Vec<SagaState>>;
 
+    /// Sagas whose data carries the given business correlation ID, oldest first
+    async fn find_by_correlation_id(&self, correlation_id: Uuid, limit: i64) -> Result<Vec<SagaState>>;
+
+    /// Running sagas owned by the given partitions that have not progressed since `stale_before`
+    async fn find_stale_in_partitions(
+        &self,
+        partitions: &[i32],
+        stale_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<SagaState>>;
+
+    /// Count saga instances in the given status
+    async fn count_by_status(&self, status: SagaStatus) -> Result<i64>;
+
     /// Delete a saga instance
     async fn delete(&self, saga_id: Uuid) -> Result<()>;
+
+    /// Publish the definition of a saga type, replacing any earlier one
+    async fn save_definition(&self, definition: &SagaDefinition) -> Result<()>;
+
+    /// Published saga definitions, ordered by saga type
+    async fn list_definitions(&self) -> Result<Vec<SagaDefinition>>;
 }
 
 /// PostgreSQL implementation of SagaRepository
 pub struct PostgresSagaRepository {
     pool: PgPool,
+    cipher: Option<SagaCipher>,
 }
 
 impl PostgresSagaRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self { pool, cipher: None }
+    }
+
+    /// Encrypt the data and step results of sagas written from now on
+    ///
+    /// States are decrypted transparently on load, and those stored in plaintext
+    /// before encryption was enabled are still read.
+    pub fn with_encryption(mut self, keys: Arc<dyn KeyProvider>) -> Self {
+        self.cipher = Some(SagaCipher::new(keys));
+        self
+    }
+
+    /// Re-encrypt up to `limit` sagas not encrypted with the active key, including
+    /// those stored in plaintext; returns how many were rewritten
+    ///
+    /// A saga updated in the meantime is skipped, since the update already used the
+    /// active key. Call until it returns 0 after rotating keys.
+    pub async fn rotate_keys(&self, limit: i64) -> Result<u64> {
+        let Some(cipher) = &self.cipher else {
+            return Ok(0);
+        };
+
+        let rows: Vec<(Uuid, serde_json::Value)> = sqlx::query_as(
+            r#"
+            SELECT saga_id, state
+            FROM saga_instances
+            WHERE state->'encrypted'->>'key_id' IS DISTINCT FROM $1
+            LIMIT $2
+            "#,
+        )
+        .bind(cipher.active_key_id())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut rotated = 0;
+        for (saga_id, stored) in rows {
+            let state = cipher.open(saga_id, stored.clone()).await?;
+            let sealed = cipher.seal(saga_id, state).await?;
+            rotated += sqlx::query("UPDATE saga_instances SET state = $2 WHERE saga_id = $1 AND state = $3")
+                .bind(saga_id)
+                .bind(&sealed)
+                .bind(&stored)
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
+        }
+
+        if rotated > 0 {
+            tracing::info!(rotated, key_id = %cipher.active_key_id(), "Re-encrypted saga states");
+        }
+        Ok(rotated)
+    }
+
+    async fn encode(&self, state: &SagaState) -> Result<SagaInstance> {
+        let mut instance = SagaInstance::from_saga_state(state)?;
+        if let Some(cipher) = &self.cipher {
+            instance.state = cipher.seal(state.saga_id, instance.state).await?;
+        }
+        Ok(instance)
+    }
+
+    async fn decode(&self, instance: SagaInstance) -> Result<SagaState> {
+        if !is_encrypted(&instance.state) {
+            return instance.to_saga_state();
+        }
+        let cipher = self.cipher.as_ref().ok_or_else(|| {
+            SagaError::EncryptionError(format!(
+                "state of saga {} is encrypted but no keys are configured",
+                instance.saga_id
+            ))
+        })?;
+        let state = cipher.open(instance.saga_id, instance.state).await?;
+        Ok(serde_json::from_value(state)?)
+    }
+
+    async fn decode_all(&self, instances: Vec<SagaInstance>) -> Result<Vec<SagaState>> {
+        let mut states = Vec::with_capacity(instances.len());
+        for instance in instances {
+            states.push(self.decode(instance).await?);
+        }
+        Ok(states)
     }
 }
 
 #[async_trait]
 impl SagaRepository for PostgresSagaRepository {
     async fn save(&self, state: &SagaState) -> Result<()> {
-        let instance = SagaInstance::from_saga_state(state)?;
+        let instance = self.encode(state).await?;
 
-        sqlx::query(
+        let result = sqlx::query(
             r#"
             INSERT INTO saga_instances (
-                saga_id, saga_type, current_step, state, status, created_at, updated_at
+                saga_id, saga_type, current_step, state, status, created_at, updated_at,
+                correlation_key, kafka_partition
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (saga_type, correlation_key) DO NOTHING
             "#,
         )
         .bind(instance.saga_id)
@@ -87,9 +211,18 @@ impl SagaRepository for PostgresSagaRepository {
         .bind(&instance.status)
         .bind(instance.created_at)
         .bind(instance.updated_at)
+        .bind(&state.correlation_key)
+        .bind(state.partition)
         .execute(&self.pool)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(SagaError::DuplicateSaga {
+                saga_type: state.saga_type.clone(),
+                correlation_key: state.correlation_key.clone().unwrap_or_default(),
+            });
+        }
+
         tracing::info!(
             saga_id = %state.saga_id,
             saga_type = %state.saga_type,
@@ -100,7 +233,7 @@ impl SagaRepository for PostgresSagaRepository {
     }
 
     async fn update(&self, state: &SagaState) -> Result<()> {
-        let instance = SagaInstance::from_saga_state(state)?;
+        let instance = self.encode(state).await?;
 
         let result = sqlx::query(
             r#"
@@ -131,6 +264,28 @@ impl SagaRepository for PostgresSagaRepository {
         Ok(())
     }
 
+    async fn update_if_status(&self, state: &SagaState, expected: SagaStatus) -> Result<bool> {
+        let instance = self.encode(state).await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE saga_instances
+            SET current_step = $2, state = $3, status = $4, updated_at = $5
+            WHERE saga_id = $1 AND status = $6
+            "#,
+        )
+        .bind(instance.saga_id)
+        .bind(instance.current_step)
+        .bind(&instance.state)
+        .bind(&instance.status)
+        .bind(instance.updated_at)
+        .bind(expected.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     async fn load(&self, saga_id: Uuid) -> Result<SagaState> {
         let instance: SagaInstance = sqlx::query_as(
             r#"
@@ -144,7 +299,26 @@ impl SagaRepository for PostgresSagaRepository {
         .await?
         .ok_or_else(|| SagaError::SagaNotFound(saga_id.to_string()))?;
 
-        instance.to_saga_state()
+        self.decode(instance).await
+    }
+
+    async fn find_by_business_key(&self, saga_type: &str, key: &str) -> Result<Option<SagaState>> {
+        let instance: Option<SagaInstance> = sqlx::query_as(
+            r#"
+            SELECT saga_id, saga_type, current_step, state, status, created_at, updated_at
+            FROM saga_instances
+            WHERE saga_type = $1 AND correlation_key = $2
+            "#,
+        )
+        .bind(saga_type)
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match instance {
+            Some(instance) => Ok(Some(self.decode(instance).await?)),
+            None => Ok(None),
+        }
     }
 
     async fn find_by_status(&self, status: SagaStatus, limit: i64) -> Result<Vec<SagaState>> {
@@ -162,10 +336,60 @@ impl SagaRepository for PostgresSagaRepository {
         .fetch_all(&self.pool)
         .await?;
 
-        instances
-            .iter()
-            .map(|i| i.to_saga_state())
-            .collect()
+        self.decode_all(instances).await
+    }
+
+    async fn find_by_correlation_id(&self, correlation_id: Uuid, limit: i64) -> Result<Vec<SagaState>> {
+        let instances: Vec<SagaInstance> = sqlx::query_as(
+            r#"
+            SELECT saga_id, saga_type, current_step, state, status, created_at, updated_at
+            FROM saga_instances
+            WHERE state->'data'->>'correlation_id' = $1
+            ORDER BY created_at ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(correlation_id.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.decode_all(instances).await
+    }
+
+    async fn find_stale_in_partitions(
+        &self,
+        partitions: &[i32],
+        stale_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<SagaState>> {
+        let instances: Vec<SagaInstance> = sqlx::query_as(
+            r#"
+            SELECT saga_id, saga_type, current_step, state, status, created_at, updated_at
+            FROM saga_instances
+            WHERE status = 'RUNNING'
+              AND kafka_partition = ANY($1)
+              AND updated_at < $2
+            ORDER BY updated_at ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(partitions)
+        .bind(stale_before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.decode_all(instances).await
+    }
+
+    async fn count_by_status(&self, status: SagaStatus) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM saga_instances WHERE status = $1")
+            .bind(status.to_string())
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
     }
 
     async fn delete(&self, saga_id: Uuid) -> Result<()> {
@@ -178,6 +402,168 @@ impl SagaRepository for PostgresSagaRepository {
 
         Ok(())
     }
+
+    async fn save_definition(&self, definition: &SagaDefinition) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO saga_definitions (saga_type, definition, published_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (saga_type) DO UPDATE
+            SET definition = EXCLUDED.definition, published_at = NOW()
+            "#,
+        )
+        .bind(&definition.saga_type)
+        .bind(serde_json::to_value(definition)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_definitions(&self) -> Result<Vec<SagaDefinition>> {
+        let definitions: Vec<serde_json::Value> =
+            sqlx::query_scalar("SELECT definition FROM saga_definitions ORDER BY saga_type")
+                .fetch_all(&self.pool)
+                .await?;
+
+        definitions
+            .into_iter()
+            .map(|definition| Ok(serde_json::from_value(definition)?))
+            .collect()
+    }
+}
+
+/// Saga repository kept in process memory, for tests and local demos
+#[derive(Default)]
+pub struct InMemorySagaRepository {
+    sagas: Mutex<HashMap<Uuid, SagaState>>,
+    definitions: Mutex<BTreeMap<String, SagaDefinition>>,
+}
+
+impl InMemorySagaRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sagas matching `filter`, oldest first by `created_at`
+    fn find(&self, filter: impl Fn(&SagaState) -> bool) -> Vec<SagaState> {
+        let mut sagas: Vec<SagaState> = self
+            .sagas
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|state| filter(state))
+            .cloned()
+            .collect();
+        sagas.sort_by_key(|state| state.created_at);
+        sagas
+    }
+}
+
+#[async_trait]
+impl SagaRepository for InMemorySagaRepository {
+    async fn save(&self, state: &SagaState) -> Result<()> {
+        let mut sagas = self.sagas.lock().unwrap();
+        let duplicate = state.correlation_key.is_some()
+            && sagas.values().any(|saved| {
+                saved.saga_type == state.saga_type && saved.correlation_key == state.correlation_key
+            });
+        if duplicate {
+            return Err(SagaError::DuplicateSaga {
+                saga_type: state.saga_type.clone(),
+                correlation_key: state.correlation_key.clone().unwrap_or_default(),
+            });
+        }
+        sagas.insert(state.saga_id, state.clone());
+        Ok(())
+    }
+
+    async fn update(&self, state: &SagaState) -> Result<()> {
+        let mut sagas = self.sagas.lock().unwrap();
+        let saved = sagas
+            .get_mut(&state.saga_id)
+            .ok_or_else(|| SagaError::SagaNotFound(state.saga_id.to_string()))?;
+        *saved = state.clone();
+        Ok(())
+    }
+
+    async fn update_if_status(&self, state: &SagaState, expected: SagaStatus) -> Result<bool> {
+        let mut sagas = self.sagas.lock().unwrap();
+        match sagas.get_mut(&state.saga_id) {
+            Some(saved) if saved.status == expected => {
+                *saved = state.clone();
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn load(&self, saga_id: Uuid) -> Result<SagaState> {
+        self.sagas
+            .lock()
+            .unwrap()
+            .get(&saga_id)
+            .cloned()
+            .ok_or_else(|| SagaError::SagaNotFound(saga_id.to_string()))
+    }
+
+    async fn find_by_business_key(&self, saga_type: &str, key: &str) -> Result<Option<SagaState>> {
+        Ok(self
+            .find(|state| state.saga_type == saga_type && state.correlation_key.as_deref() == Some(key))
+            .pop())
+    }
+
+    async fn find_by_status(&self, status: SagaStatus, limit: i64) -> Result<Vec<SagaState>> {
+        let mut sagas = self.find(|state| state.status == status);
+        sagas.truncate(limit.max(0) as usize);
+        Ok(sagas)
+    }
+
+    async fn find_by_correlation_id(&self, correlation_id: Uuid, limit: i64) -> Result<Vec<SagaState>> {
+        let correlation_id = correlation_id.to_string();
+        let mut sagas = self.find(|state| {
+            state.data.get("correlation_id").and_then(|id| id.as_str()) == Some(&correlation_id)
+        });
+        sagas.truncate(limit.max(0) as usize);
+        Ok(sagas)
+    }
+
+    async fn find_stale_in_partitions(
+        &self,
+        partitions: &[i32],
+        stale_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<SagaState>> {
+        let mut sagas = self.find(|state| {
+            state.status == SagaStatus::Running
+                && state.partition.is_some_and(|partition| partitions.contains(&partition))
+                && state.updated_at < stale_before
+        });
+        sagas.sort_by_key(|state| state.updated_at);
+        sagas.truncate(limit.max(0) as usize);
+        Ok(sagas)
+    }
+
+    async fn count_by_status(&self, status: SagaStatus) -> Result<i64> {
+        Ok(self.find(|state| state.status == status).len() as i64)
+    }
+
+    async fn delete(&self, saga_id: Uuid) -> Result<()> {
+        self.sagas.lock().unwrap().remove(&saga_id);
+        Ok(())
+    }
+
+    async fn save_definition(&self, definition: &SagaDefinition) -> Result<()> {
+        self.definitions
+            .lock()
+            .unwrap()
+            .insert(definition.saga_type.clone(), definition.clone());
+        Ok(())
+    }
+
+    async fn list_definitions(&self) -> Result<Vec<SagaDefinition>> {
+        Ok(self.definitions.lock().unwrap().values().cloned().collect())
+    }
 }
 
 #[cfg(test)]
@@ -207,4 +593,48 @@ mod tests {
         assert_eq!(restored_state.saga_type, state.saga_type);
         assert_eq!(restored_state.status, state.status);
     }
+
+    #[tokio::test]
+    async fn test_in_memory_repository_enforces_business_keys() {
+        let repository = InMemorySagaRepository::new();
+        let mut state = SagaState::new(Uuid::new_v4(), "test_saga".to_string(), vec![], serde_json::json!({}));
+        state.correlation_key = Some("order-1".to_string());
+        repository.save(&state).await.unwrap();
+
+        let mut duplicate = state.clone();
+        duplicate.saga_id = Uuid::new_v4();
+        assert!(matches!(
+            repository.save(&duplicate).await,
+            Err(SagaError::DuplicateSaga { .. })
+        ));
+
+        state.status = SagaStatus::Completed;
+        repository.update(&state).await.unwrap();
+        let found = repository.find_by_business_key("test_saga", "order-1").await.unwrap();
+        assert_eq!(found.map(|s| s.status), Some(SagaStatus::Completed));
+        assert_eq!(repository.count_by_status(SagaStatus::Running).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_if_status_only_applies_once() {
+        let repository = InMemorySagaRepository::new();
+        let mut state = SagaState::new(Uuid::new_v4(), "test_saga".to_string(), vec![], serde_json::json!({}));
+        state.status = SagaStatus::PausedAwaitingApproval;
+        repository.save(&state).await.unwrap();
+
+        let mut approved = state.clone();
+        approved.status = SagaStatus::Running;
+        assert!(repository
+            .update_if_status(&approved, SagaStatus::PausedAwaitingApproval)
+            .await
+            .unwrap());
+
+        let mut rejected = state.clone();
+        rejected.status = SagaStatus::Compensating;
+        assert!(!repository
+            .update_if_status(&rejected, SagaStatus::PausedAwaitingApproval)
+            .await
+            .unwrap());
+        assert_eq!(repository.load(state.saga_id).await.unwrap().status, SagaStatus::Running);
+    }
 }
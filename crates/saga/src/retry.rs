@@ -0,0 +1,97 @@
+use common::retry::Backoff;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Delay policy applied between retries of a failed saga step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackoffPolicy {
+    /// Wait the same amount of time before every retry
+    Fixed { delay_ms: u64 },
+    /// Double the delay on every retry, capped at `max_delay_ms`
+    Exponential { initial_delay_ms: u64, max_delay_ms: u64 },
+    /// Exponential backoff with full jitter (random delay up to the exponential value)
+    ExponentialJitter { initial_delay_ms: u64, max_delay_ms: u64 },
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        BackoffPolicy::Exponential {
+            initial_delay_ms: 100,
+            max_delay_ms: 5_000,
+        }
+    }
+}
+
+impl BackoffPolicy {
+    /// Delay before the given retry attempt (1 = first retry)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        Backoff::from(self).delay(attempt)
+    }
+}
+
+impl From<&BackoffPolicy> for Backoff {
+    fn from(policy: &BackoffPolicy) -> Self {
+        match *policy {
+            BackoffPolicy::Fixed { delay_ms } => Backoff::Fixed(Duration::from_millis(delay_ms)),
+            BackoffPolicy::Exponential {
+                initial_delay_ms,
+                max_delay_ms,
+            } => Backoff::Exponential {
+                initial: Duration::from_millis(initial_delay_ms),
+                max: Duration::from_millis(max_delay_ms),
+            },
+            BackoffPolicy::ExponentialJitter {
+                initial_delay_ms,
+                max_delay_ms,
+            } => Backoff::ExponentialJitter {
+                initial: Duration::from_millis(initial_delay_ms),
+                max: Duration::from_millis(max_delay_ms),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_backoff() {
+        let policy = BackoffPolicy::Fixed { delay_ms: 250 };
+        assert_eq!(policy.delay(1), Duration::from_millis(250));
+        assert_eq!(policy.delay(5), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_exponential_backoff_is_capped() {
+        let policy = BackoffPolicy::Exponential {
+            initial_delay_ms: 100,
+            max_delay_ms: 1_000,
+        };
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(10), Duration::from_millis(1_000));
+        assert_eq!(policy.delay(u32::MAX), Duration::from_millis(1_000));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let policy = BackoffPolicy::ExponentialJitter {
+            initial_delay_ms: 100,
+            max_delay_ms: 1_000,
+        };
+        for attempt in 1..10 {
+            assert!(policy.delay(attempt) <= Duration::from_millis(1_000));
+        }
+    }
+
+    #[test]
+    fn test_policy_deserialization() {
+        let policy: BackoffPolicy =
+            serde_json::from_str(r#"{"type": "fixed", "delay_ms": 50}"#).unwrap();
+        assert_eq!(policy, BackoffPolicy::Fixed { delay_ms: 50 });
+    }
+}
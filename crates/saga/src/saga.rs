@@ -1,15 +1,20 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use common::deadline::Deadline;
+use common::metrics::start_saga_step;
+use common::retry::{retry, Backoff, RetryPolicy};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
 use uuid::Uuid;
 
+use crate::definition::SagaDefinition;
 use crate::errors::{Result, SagaError};
-use crate::step::{SagaStep, StepContext, StepExecutor};
+use crate::step::{SagaStep, StepApproval, StepContext, StepExecutor, StepLimits, StepStatus};
 
 /// Status of the entire saga
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum SagaStatus {
     /// Saga is running forward
     Running,
@@ -21,6 +26,10 @@ pub enum SagaStatus {
     Compensated,
     /// Saga failed completely (compensation also failed)
     Failed,
+    /// Compensation kept failing after retries; an operator must resolve the saga
+    RequiresIntervention,
+    /// Stopped at a step until someone approves or rejects it
+    PausedAwaitingApproval,
 }
 
 impl fmt::Display for SagaStatus {
@@ -31,12 +40,14 @@ impl fmt::Display for SagaStatus {
             SagaStatus::Compensating => write!(f, "COMPENSATING"),
             SagaStatus::Compensated => write!(f, "COMPENSATED"),
             SagaStatus::Failed => write!(f, "FAILED"),
+            SagaStatus::RequiresIntervention => write!(f, "REQUIRES_INTERVENTION"),
+            SagaStatus::PausedAwaitingApproval => write!(f, "PAUSED_AWAITING_APPROVAL"),
         }
     }
 }
 
 /// State of a saga instance
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SagaState {
     pub saga_id: Uuid,
     pub saga_type: String,
@@ -44,10 +55,31 @@ pub struct SagaState {
     pub current_step: usize,
     pub steps: Vec<SagaStep>,
     pub data: serde_json::Value,
+    /// Why the saga needs manual intervention, if it does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intervention_reason: Option<String>,
+    /// Why the saga is awaiting approval, if it is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pause_reason: Option<String>,
+    /// Business key (e.g. order ID); at most one saga of a type may exist per key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_key: Option<String>,
+    /// Kafka partition of the triggering event; its assignee owns the saga
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partition: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Sagas waiting on an operator, e.g. for intervention or approval
+///
+/// `total` counts the whole queue; `sagas` is at most one page of it.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PendingSagas {
+    pub total: i64,
+    pub sagas: Vec<SagaState>,
+}
+
 impl SagaState {
     pub fn new(saga_id: Uuid, saga_type: String, steps: Vec<SagaStep>, data: serde_json::Value) -> Self {
         let now = Utc::now();
@@ -58,11 +90,23 @@ impl SagaState {
             current_step: 0,
             steps,
             data,
+            intervention_reason: None,
+            pause_reason: None,
+            correlation_key: None,
+            partition: None,
             created_at: now,
             updated_at: now,
         }
     }
 
+    /// Business correlation ID carried in the saga data, if the saga has one
+    pub fn correlation_id(&self) -> Option<Uuid> {
+        self.data
+            .get("correlation_id")
+            .and_then(|v| v.as_str())
+            .and_then(|s| Uuid::parse_str(s).ok())
+    }
+
     pub fn is_completed(&self) -> bool {
         self.status == SagaStatus::Completed
     }
@@ -75,6 +119,18 @@ impl SagaState {
         self.status == SagaStatus::Failed
     }
 
+    pub fn is_compensated(&self) -> bool {
+        self.status == SagaStatus::Compensated
+    }
+
+    pub fn requires_intervention(&self) -> bool {
+        self.status == SagaStatus::RequiresIntervention
+    }
+
+    pub fn is_awaiting_approval(&self) -> bool {
+        self.status == SagaStatus::PausedAwaitingApproval
+    }
+
     pub fn has_more_steps(&self) -> bool {
         self.current_step < self.steps.len()
     }
@@ -112,6 +168,56 @@ impl SagaState {
         self.updated_at = Utc::now();
     }
 
+    pub fn mark_requires_intervention(&mut self, reason: String) {
+        self.status = SagaStatus::RequiresIntervention;
+        self.intervention_reason = Some(reason);
+        self.updated_at = Utc::now();
+    }
+
+    pub fn mark_awaiting_approval(&mut self, reason: String) {
+        self.status = SagaStatus::PausedAwaitingApproval;
+        self.pause_reason = Some(reason);
+        self.updated_at = Utc::now();
+    }
+
+    /// Record a reviewer's decision on the step the saga is paused at
+    ///
+    /// The saga is running again afterwards: an approved step is executed with the
+    /// approval in its context, a rejected one starts compensation.
+    pub fn decide_approval(&mut self, approval: StepApproval) -> Result<()> {
+        if !self.is_awaiting_approval() {
+            return Err(SagaError::InvalidStateTransition {
+                from: self.status.to_string(),
+                to: SagaStatus::Running.to_string(),
+            });
+        }
+
+        let step = self
+            .current_step_mut()
+            .ok_or_else(|| SagaError::StepNotFound("current step".to_string()))?;
+        step.approval = Some(approval);
+
+        self.status = SagaStatus::Running;
+        self.pause_reason = None;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Record that an operator resolved a saga stuck in manual intervention
+    pub fn resolve_intervention(&mut self, resolution: String) -> Result<()> {
+        if !self.requires_intervention() {
+            return Err(SagaError::InvalidStateTransition {
+                from: self.status.to_string(),
+                to: SagaStatus::Compensated.to_string(),
+            });
+        }
+
+        self.status = SagaStatus::Compensated;
+        self.intervention_reason = Some(resolution);
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
     /// Get steps that need compensation (completed steps in reverse order)
     pub fn get_compensation_steps(&self) -> Vec<(usize, &SagaStep)> {
         self.steps
@@ -135,8 +241,24 @@ pub trait Saga: Send + Sync {
     /// Create initial saga state
     async fn create_state(&self, saga_id: Uuid, data: serde_json::Value) -> Result<SagaState>;
 
-    /// Execute the next step
-    async fn execute_next_step(&self, state: &mut SagaState) -> Result<()> {
+    /// Steps the saga runs, for documentation and dashboards
+    ///
+    /// Sagas that don't describe their flow publish a definition without steps.
+    fn definition(&self) -> SagaDefinition {
+        SagaDefinition::new(self.saga_type())
+    }
+
+    /// Business key preventing duplicate sagas for the same entity
+    fn correlation_key(&self, _data: &serde_json::Value) -> Option<String> {
+        None
+    }
+
+    /// Execute the next step within `limits`
+    ///
+    /// An attempt still running when its deadline passes is abandoned and fails with
+    /// `StepTimeout`. One still running when `limits` is cancelled is abandoned and
+    /// the step left pending, failing with `Cancelled`, so it runs again on resume.
+    async fn execute_next_step(&self, state: &mut SagaState, limits: &StepLimits) -> Result<()> {
         if state.is_completed() {
             return Err(SagaError::AlreadyCompleted);
         }
@@ -147,10 +269,17 @@ pub trait Saga: Send + Sync {
         }
 
         // Get step information before borrowing mutably
-        let (saga_id, step_name, data) = {
+        let (saga_id, step_name, data, idempotency_key, approval, deadline) = {
             let step = state.current_step()
                 .ok_or_else(|| SagaError::StepNotFound("current step".to_string()))?;
-            (state.saga_id, step.name.clone(), state.data.clone())
+            (
+                state.saga_id,
+                step.name.clone(),
+                state.data.clone(),
+                step.idempotency_key(state.saga_id),
+                step.approval.clone(),
+                limits.deadline(step),
+            )
         };
 
         // Now mark step as running
@@ -162,34 +291,83 @@ pub trait Saga: Send + Sync {
             saga_id,
             step_name: step_name.clone(),
             data,
+            idempotency_key,
+            approval,
+            cancellation: limits.cancellation.clone(),
+            deadline,
         };
 
         let executor = self.step_executors()
             .get(&step_name)
-            .ok_or_else(|| SagaError::StepNotFound(step_name))?;
+            .ok_or_else(|| SagaError::StepNotFound(step_name.clone()))?;
+
+        let timer = start_saga_step(self.saga_type(), &step_name, "execute");
+        let outcome = tokio::select! {
+            biased;
+            _ = limits.cancellation.cancelled() => {
+                Err(SagaError::Cancelled(format!("step '{}' abandoned", step_name)))
+            }
+            result = deadline.scope(executor.execute(&context)) => {
+                result.unwrap_or_else(|_| Err(SagaError::StepTimeout(step_name.clone())))
+            }
+        };
+        timer.finish(step_outcome(&outcome));
 
-        match executor.execute(&context).await {
+        match outcome {
             Ok(result) => {
-                let step = state.current_step_mut().unwrap();
+                let step = state.current_step_mut()
+                    .ok_or_else(|| SagaError::StepNotFound("current step".to_string()))?;
                 step.mark_completed(result);
                 state.advance_step();
                 Ok(())
             }
+            Err(SagaError::PauseRequested(reason)) => {
+                // The step runs again, with the decision in its context, once approved
+                let step = state.current_step_mut()
+                    .ok_or_else(|| SagaError::StepNotFound("current step".to_string()))?;
+                step.status = StepStatus::Pending;
+                step.requires_approval = true;
+                step.approval = None;
+                state.mark_awaiting_approval(reason.clone());
+                Err(SagaError::PauseRequested(reason))
+            }
+            Err(SagaError::Cancelled(reason)) => {
+                // Not a failed attempt; it starts over when the saga is resumed
+                let step = state.current_step_mut()
+                    .ok_or_else(|| SagaError::StepNotFound("current step".to_string()))?;
+                step.status = StepStatus::Pending;
+                Err(SagaError::Cancelled(reason))
+            }
             Err(e) => {
-                let step = state.current_step_mut().unwrap();
+                let step = state.current_step_mut()
+                    .ok_or_else(|| SagaError::StepNotFound("current step".to_string()))?;
                 step.mark_failed(e.to_string());
                 Err(e)
             }
         }
     }
 
-    /// Compensate a specific step
-    async fn compensate_step(&self, state: &mut SagaState, step_index: usize) -> Result<()> {
+    /// Compensate a specific step, each attempt within the step's deadline
+    ///
+    /// Compensation is not abandoned when `limits` is cancelled: a saga left half
+    /// compensated would need manual intervention, so it is finished instead.
+    async fn compensate_step(
+        &self,
+        state: &mut SagaState,
+        step_index: usize,
+        limits: &StepLimits,
+    ) -> Result<()> {
         // Get step information first
-        let (saga_id, step_name, data, is_completed) = {
+        let (saga_id, step_name, data, is_completed, idempotency_key) = {
             let step = state.steps.get(step_index)
                 .ok_or_else(|| SagaError::StepNotFound(format!("step {}", step_index)))?;
-            (state.saga_id, step.name.clone(), state.data.clone(), step.is_completed())
+            (
+                state.saga_id,
+                step.name.clone(),
+                state.data.clone(),
+                step.is_completed(),
+                step.compensation_idempotency_key(state.saga_id),
+            )
         };
 
         if !is_completed {
@@ -197,27 +375,58 @@ pub trait Saga: Send + Sync {
         }
 
         // Mark step as compensating
-        let step = state.steps.get_mut(step_index).unwrap();
+        let step = state.steps.get_mut(step_index)
+            .ok_or_else(|| SagaError::StepNotFound(format!("step {}", step_index)))?;
         step.mark_compensating();
 
         let context = StepContext {
             saga_id,
             step_name: step_name.clone(),
             data,
+            idempotency_key,
+            approval: None,
+            cancellation: limits.cancellation.clone(),
+            deadline: Deadline::none(),
         };
 
         let executor = self.step_executors()
             .get(&step_name)
-            .ok_or_else(|| SagaError::StepNotFound(step_name))?;
+            .ok_or_else(|| SagaError::StepNotFound(step_name.clone()))?;
+
+        // Compensations must eventually succeed, so every error is retried
+        // using the step's own retry budget and backoff policy.
+        let policy = RetryPolicy::new(format!("saga.compensate.{}", step.name))
+            .with_max_attempts(step.max_retries.saturating_add(1))
+            .with_backoff(Backoff::from(&step.backoff));
+        let saga_type = self.saga_type();
+        let result = retry(&policy, || {
+            // Each attempt gets the step's full timeout
+            let context = StepContext {
+                deadline: limits.deadline(step),
+                ..context.clone()
+            };
+            let step_name = &step_name;
+            async move {
+                let timer = start_saga_step(saga_type, step_name, "compensate");
+                let result = context
+                    .deadline
+                    .scope(executor.compensate(&context))
+                    .await
+                    .unwrap_or_else(|_| Err(SagaError::StepTimeout(step_name.clone())));
+                timer.finish(step_outcome(&result));
+                result
+            }
+        })
+        .await;
 
-        match executor.compensate(&context).await {
+        let step = state.steps.get_mut(step_index)
+            .ok_or_else(|| SagaError::StepNotFound(format!("step {}", step_index)))?;
+        match result {
             Ok(_) => {
-                let step = state.steps.get_mut(step_index).unwrap();
                 step.mark_compensated();
                 Ok(())
             }
             Err(e) => {
-                let step = state.steps.get_mut(step_index).unwrap();
                 step.mark_compensation_failed(e.to_string());
                 Err(e)
             }
@@ -225,7 +434,7 @@ pub trait Saga: Send + Sync {
     }
 
     /// Compensate all completed steps (in reverse order)
-    async fn compensate_all(&self, state: &mut SagaState) -> Result<()> {
+    async fn compensate_all(&self, state: &mut SagaState, limits: &StepLimits) -> Result<()> {
         if state.is_compensating() {
             return Err(SagaError::AlreadyCompensating);
         }
@@ -240,14 +449,18 @@ pub trait Saga: Send + Sync {
             .collect();
 
         for index in compensation_indices {
-            if let Err(e) = self.compensate_step(state, index).await {
+            if let Err(e) = self.compensate_step(state, index, limits).await {
                 tracing::error!(
                     saga_id = %state.saga_id,
                     step_index = index,
                     error = %e,
-                    "Compensation failed for step"
+                    "Compensation failed for step, saga requires manual intervention"
                 );
-                state.mark_failed();
+                let reason = format!(
+                    "Compensation of step '{}' failed: {}",
+                    state.steps[index].name, e
+                );
+                state.mark_requires_intervention(reason);
                 return Err(e);
             }
         }
@@ -257,6 +470,18 @@ pub trait Saga: Send + Sync {
     }
 }
 
+/// Outcome label of a step attempt in the saga step metrics
+fn step_outcome<T>(result: &Result<T>) -> &'static str {
+    match result {
+        Ok(_) => "success",
+        Err(SagaError::Transient(_)) => "transient",
+        Err(SagaError::StepTimeout(_)) => "timeout",
+        Err(SagaError::Cancelled(_)) => "cancelled",
+        Err(SagaError::PauseRequested(_)) => "paused",
+        Err(_) => "failure",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -302,6 +527,31 @@ mod tests {
         assert!(!state.has_more_steps());
     }
 
+    #[test]
+    fn test_decide_approval_resumes_paused_saga() {
+        let steps = vec![SagaStep::new("charge".to_string(), 3).requiring_approval()];
+        let mut state = SagaState::new(Uuid::new_v4(), "test".to_string(), steps, serde_json::json!({}));
+
+        let approval = StepApproval {
+            approved: true,
+            approver: "ops@example.com".to_string(),
+            comment: None,
+            decided_at: Utc::now(),
+        };
+        assert!(matches!(
+            state.decide_approval(approval.clone()),
+            Err(SagaError::InvalidStateTransition { .. })
+        ));
+
+        state.mark_awaiting_approval("High-value order".to_string());
+        state.decide_approval(approval.clone()).unwrap();
+
+        assert_eq!(state.status, SagaStatus::Running);
+        assert_eq!(state.pause_reason, None);
+        assert_eq!(state.steps[0].approval, Some(approval));
+        assert!(!state.steps[0].awaits_approval());
+    }
+
     #[test]
     fn test_compensation_steps() {
         let saga_id = Uuid::new_v4();
@@ -1,11 +1,18 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use common::deadline::Deadline;
+use domain::events::EventEnvelope;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
 use crate::errors::Result;
+use crate::retry::BackoffPolicy;
 
 /// Status of a saga step
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum StepStatus {
     /// Step is pending execution
     Pending,
@@ -37,12 +44,90 @@ impl fmt::Display for StepStatus {
     }
 }
 
+/// Event a step published, recorded in its result under [`EMITTED_EVENT_KEY`]
+///
+/// Saga events go to Kafka rather than the event store, so this is what links a
+/// step to what it emitted when tracing a flow after the fact.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmittedEvent {
+    pub event_id: uuid::Uuid,
+    pub event_type: String,
+    pub aggregate_id: uuid::Uuid,
+}
+
+/// Key of the [`EmittedEvent`] in a step result
+pub const EMITTED_EVENT_KEY: &str = "emitted_event";
+
+impl From<&EventEnvelope> for EmittedEvent {
+    fn from(envelope: &EventEnvelope) -> Self {
+        Self {
+            event_id: envelope.event_id,
+            event_type: envelope.event_type.clone(),
+            aggregate_id: envelope.aggregate_id,
+        }
+    }
+}
+
+/// A reviewer's decision on a step the saga paused at
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct StepApproval {
+    pub approved: bool,
+    pub approver: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    pub decided_at: DateTime<Utc>,
+}
+
 /// Context passed to step execution and compensation functions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StepContext {
     pub saga_id: uuid::Uuid,
     pub step_name: String,
     pub data: serde_json::Value,
+    /// Deterministic key for the step, the same on every attempt, for deduplicating
+    /// side effects downstream
+    pub idempotency_key: String,
+    /// Approval the step was given after pausing the saga, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<StepApproval>,
+    /// Cancelled when the coordinator shuts down; the attempt is then abandoned
+    #[serde(skip)]
+    pub cancellation: CancellationToken,
+    /// When the attempt is abandoned as timed out; also the current
+    /// [`Deadline`] while the executor runs, so deadline-aware calls stop with it
+    #[serde(skip)]
+    pub deadline: Deadline,
+}
+
+impl StepContext {
+    /// Time left for the attempt, e.g. to bound an HTTP call; `None` without a deadline
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline.remaining()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+}
+
+/// Bounds the coordinator puts on step attempts
+#[derive(Debug, Clone, Default)]
+pub struct StepLimits {
+    /// Cancelled on shutdown: running attempts are abandoned and left to run again
+    /// when the saga is resumed
+    pub cancellation: CancellationToken,
+    /// Longest an attempt may take when its step sets no timeout of its own
+    pub default_timeout: Option<Duration>,
+}
+
+impl StepLimits {
+    /// Deadline of an attempt of `step` starting now, never past the current one
+    pub fn deadline(&self, step: &SagaStep) -> Deadline {
+        step.timeout()
+            .or(self.default_timeout)
+            .map_or(Deadline::none(), Deadline::after)
+            .min(Deadline::current())
+    }
 }
 
 /// Trait for executing saga steps
@@ -56,14 +141,27 @@ pub trait StepExecutor: Send + Sync {
 }
 
 /// A step in a saga
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SagaStep {
     pub name: String,
     pub status: StepStatus,
     pub retry_count: u32,
     pub max_retries: u32,
+    /// Times the step was compensated; a later run is a new execution with its own key
+    #[serde(default)]
+    pub execution: u32,
     pub result: Option<serde_json::Value>,
     pub error: Option<String>,
+    #[serde(default)]
+    pub backoff: BackoffPolicy,
+    /// The saga pauses before running this step until it is approved
+    #[serde(default)]
+    pub requires_approval: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<StepApproval>,
+    /// Longest a single attempt may take before it is abandoned and counted as failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
 }
 
 impl SagaStep {
@@ -73,11 +171,48 @@ impl SagaStep {
             status: StepStatus::Pending,
             retry_count: 0,
             max_retries,
+            execution: 0,
             result: None,
             error: None,
+            backoff: BackoffPolicy::default(),
+            requires_approval: false,
+            approval: None,
+            timeout_ms: None,
         }
     }
 
+    /// Set the delay policy used between retries of this step
+    pub fn with_backoff(mut self, backoff: BackoffPolicy) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Abandon attempts that run longer than `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_ms = Some(timeout.as_millis() as u64);
+        self
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(Duration::from_millis)
+    }
+
+    /// Pause the saga before this step until someone approves it
+    pub fn requiring_approval(mut self) -> Self {
+        self.requires_approval = true;
+        self
+    }
+
+    /// Whether the step may not run before a decision on it is recorded
+    pub fn awaits_approval(&self) -> bool {
+        self.requires_approval && self.approval.is_none()
+    }
+
+    /// Whether a reviewer rejected the step
+    pub fn is_rejected(&self) -> bool {
+        self.approval.as_ref().is_some_and(|approval| !approval.approved)
+    }
+
     pub fn mark_running(&mut self) {
         self.status = StepStatus::Running;
     }
@@ -101,6 +236,7 @@ impl SagaStep {
     pub fn mark_compensated(&mut self) {
         self.status = StepStatus::Compensated;
         self.error = None;
+        self.execution += 1;
     }
 
     pub fn mark_compensation_failed(&mut self, error: String) {
@@ -112,6 +248,34 @@ impl SagaStep {
         self.retry_count < self.max_retries
     }
 
+    /// Idempotency key for executing this step (saga id + step name + execution)
+    ///
+    /// Retries reuse the key, so a downstream service that already applied an attempt
+    /// whose response was lost recognizes the retry instead of applying it twice. Once
+    /// the step has been compensated, running it again is a new execution with a new
+    /// key, so the undone result is not returned for it.
+    pub fn idempotency_key(&self, saga_id: uuid::Uuid) -> String {
+        format!("saga:{}:{}:{}", saga_id, self.name, self.execution)
+    }
+
+    /// Idempotency key for compensating the current execution of this step
+    pub fn compensation_idempotency_key(&self, saga_id: uuid::Uuid) -> String {
+        format!("saga:{}:{}:{}:compensate", saga_id, self.name, self.execution)
+    }
+
+    /// Event the step recorded publishing, if any
+    pub fn emitted_event(&self) -> Option<EmittedEvent> {
+        self.result
+            .as_ref()?
+            .get(EMITTED_EVENT_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// Delay before the next retry, based on how many attempts have failed
+    pub fn retry_delay(&self) -> std::time::Duration {
+        self.backoff.delay(self.retry_count)
+    }
+
     pub fn is_completed(&self) -> bool {
         self.status == StepStatus::Completed
     }
@@ -138,6 +302,23 @@ mod tests {
         assert_eq!(step.max_retries, 3);
     }
 
+    #[test]
+    fn test_emitted_event_is_read_from_result() {
+        let mut step = SagaStep::new("reserve_inventory".to_string(), 3);
+        assert!(step.emitted_event().is_none());
+
+        let emitted = EmittedEvent {
+            event_id: uuid::Uuid::new_v4(),
+            event_type: "InventoryReserved".to_string(),
+            aggregate_id: uuid::Uuid::new_v4(),
+        };
+        step.mark_completed(serde_json::json!({
+            "reservation_id": uuid::Uuid::new_v4(),
+            EMITTED_EVENT_KEY: emitted,
+        }));
+        assert_eq!(step.emitted_event(), Some(emitted));
+    }
+
     #[test]
     fn test_step_lifecycle() {
         let mut step = SagaStep::new("test".to_string(), 3);
@@ -165,6 +346,53 @@ mod tests {
         assert!(!step.can_retry());
     }
 
+    #[test]
+    fn test_idempotency_key_is_stable_across_attempts() {
+        let saga_id = uuid::Uuid::new_v4();
+        let mut step = SagaStep::new("charge".to_string(), 3);
+
+        let first = step.idempotency_key(saga_id);
+        assert_eq!(first, format!("saga:{}:charge:0", saga_id));
+
+        step.mark_failed("timeout".to_string());
+        assert_eq!(first, step.idempotency_key(saga_id));
+        assert_ne!(first, step.compensation_idempotency_key(saga_id));
+    }
+
+    #[test]
+    fn test_idempotency_key_changes_after_compensation() {
+        let saga_id = uuid::Uuid::new_v4();
+        let mut step = SagaStep::new("charge".to_string(), 3);
+        let first = step.idempotency_key(saga_id);
+        let first_compensation = step.compensation_idempotency_key(saga_id);
+
+        step.mark_completed(serde_json::json!({}));
+        step.mark_compensating();
+        step.mark_compensated();
+        assert_eq!(step.idempotency_key(saga_id), format!("saga:{}:charge:1", saga_id));
+        assert_ne!(first, step.idempotency_key(saga_id));
+        assert_ne!(first_compensation, step.compensation_idempotency_key(saga_id));
+    }
+
+    #[test]
+    fn test_approval_gate() {
+        let mut step = SagaStep::new("charge".to_string(), 3);
+        assert!(!step.awaits_approval());
+
+        step = step.requiring_approval();
+        assert!(step.awaits_approval());
+        assert!(!step.is_rejected());
+
+        step.approval = Some(StepApproval {
+            approved: false,
+            approver: "ops@example.com".to_string(),
+            comment: Some("Suspicious address".to_string()),
+            decided_at: Utc::now(),
+        });
+        assert!(!step.awaits_approval());
+        assert!(step.is_rejected());
+    }
+
     #[test]
     fn test_compensation() {
         let mut step = SagaStep::new("test".to_string(), 3);
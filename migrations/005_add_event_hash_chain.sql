@@ -0,0 +1,6 @@
+-- Optional hash chaining for tamper detection on the event store
+ALTER TABLE events ADD COLUMN IF NOT EXISTS prev_hash VARCHAR(64);
+ALTER TABLE events ADD COLUMN IF NOT EXISTS hash VARCHAR(64);
+
+COMMENT ON COLUMN events.prev_hash IS 'Hash of the previous event in the aggregate stream (NULL when hash chaining is disabled)';
+COMMENT ON COLUMN events.hash IS 'SHA-256 over prev_hash and the event contents - used to detect tampering';
@@ -0,0 +1,13 @@
+-- Client-supplied command IDs claimed by the event store
+-- Lives next to the events so command deduplication survives a Redis flush
+CREATE TABLE IF NOT EXISTS processed_commands (
+    command_id UUID PRIMARY KEY,
+    aggregate_id UUID NOT NULL,
+    processed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_processed_commands_aggregate ON processed_commands(aggregate_id);
+
+COMMENT ON TABLE processed_commands IS 'Command IDs that have already produced events - guarantees exactly-once command effects';
+COMMENT ON COLUMN processed_commands.command_id IS 'Client-supplied command ID (also stored in events.metadata)';
+COMMENT ON COLUMN processed_commands.aggregate_id IS 'Aggregate the command was applied to';
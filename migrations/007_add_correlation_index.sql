@@ -0,0 +1,3 @@
+-- Index for tracing a single user action across aggregates
+CREATE INDEX IF NOT EXISTS idx_events_correlation
+    ON events ((metadata->>'correlation_id'), created_at);
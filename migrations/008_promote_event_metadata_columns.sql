@@ -0,0 +1,34 @@
+-- Promote frequently queried metadata fields to real columns
+-- The metadata JSON is still written in full; these columns are dual-written on append
+ALTER TABLE events ADD COLUMN IF NOT EXISTS correlation_id UUID;
+ALTER TABLE events ADD COLUMN IF NOT EXISTS causation_id UUID;
+ALTER TABLE events ADD COLUMN IF NOT EXISTS user_id UUID;
+
+-- Backfill existing rows from the metadata JSON. Each column is copied only if its own
+-- value is a UUID, so a malformed user_id does not keep a valid correlation_id out
+UPDATE events
+SET correlation_id = COALESCE(correlation_id, CASE
+        WHEN metadata->>'correlation_id' ~* '^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$'
+        THEN (metadata->>'correlation_id')::uuid END),
+    causation_id = COALESCE(causation_id, CASE
+        WHEN metadata->>'causation_id' ~* '^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$'
+        THEN (metadata->>'causation_id')::uuid END),
+    user_id = COALESCE(user_id, CASE
+        WHEN metadata->>'user_id' ~* '^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$'
+        THEN (metadata->>'user_id')::uuid END)
+WHERE (correlation_id IS NULL
+       AND metadata->>'correlation_id' ~* '^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$')
+   OR (causation_id IS NULL
+       AND metadata->>'causation_id' ~* '^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$')
+   OR (user_id IS NULL
+       AND metadata->>'user_id' ~* '^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$');
+
+-- Column indexes replace the JSON expression index used for correlation queries
+CREATE INDEX IF NOT EXISTS idx_events_correlation_id ON events(correlation_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_events_causation_id ON events(causation_id);
+CREATE INDEX IF NOT EXISTS idx_events_user_id ON events(user_id, created_at) WHERE user_id IS NOT NULL;
+DROP INDEX IF EXISTS idx_events_correlation;
+
+COMMENT ON COLUMN events.correlation_id IS 'Correlation ID copied from metadata - groups events caused by one user action';
+COMMENT ON COLUMN events.causation_id IS 'Causation ID copied from metadata - the message that caused this event';
+COMMENT ON COLUMN events.user_id IS 'User ID copied from metadata - used for audit filtering';
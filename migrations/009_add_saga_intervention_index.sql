@@ -0,0 +1,6 @@
+-- Sagas whose compensation failed wait here until an operator resolves them
+CREATE INDEX IF NOT EXISTS idx_saga_requires_intervention
+    ON saga_instances (updated_at)
+    WHERE status = 'REQUIRES_INTERVENTION';
+
+COMMENT ON COLUMN saga_instances.status IS 'RUNNING, COMPLETED, COMPENSATING, COMPENSATED, FAILED or REQUIRES_INTERVENTION';
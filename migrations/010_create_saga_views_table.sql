@@ -0,0 +1,26 @@
+-- Read model of saga progress, fed by saga lifecycle events
+CREATE TABLE IF NOT EXISTS saga_views (
+    saga_id UUID PRIMARY KEY,
+    saga_type VARCHAR(100) NOT NULL,
+    status VARCHAR(50) NOT NULL,
+    total_steps INT NOT NULL,
+    completed_steps INT NOT NULL DEFAULT 0,
+    failed_attempts INT NOT NULL DEFAULT 0,
+    last_step VARCHAR(100),
+    last_error TEXT,
+    started_at TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ,
+    duration_ms BIGINT,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+-- Index for status queries
+CREATE INDEX idx_saga_views_status ON saga_views(status, started_at DESC);
+
+-- Index for per-type queries and statistics
+CREATE INDEX idx_saga_views_type_status ON saga_views(saga_type, status);
+
+COMMENT ON TABLE saga_views IS 'Saga monitoring read model built from the saga-events topic';
+COMMENT ON COLUMN saga_views.status IS 'RUNNING, COMPENSATING, COMPLETED, COMPENSATED or REQUIRES_INTERVENTION';
+COMMENT ON COLUMN saga_views.failed_attempts IS 'Number of failed step attempts, including retried ones';
+COMMENT ON COLUMN saga_views.duration_ms IS 'Time from start to completion or compensation';
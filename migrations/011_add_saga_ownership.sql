@@ -0,0 +1,26 @@
+-- Business key and owning Kafka partition for each saga instance
+ALTER TABLE saga_instances ADD COLUMN IF NOT EXISTS correlation_key VARCHAR(255);
+ALTER TABLE saga_instances ADD COLUMN IF NOT EXISTS kafka_partition INT;
+
+-- Backfill order sagas, keeping only the oldest saga per order if duplicates already exist
+UPDATE saga_instances s
+SET correlation_key = s.state->'data'->>'order_id'
+FROM (
+    SELECT DISTINCT ON (state->'data'->>'order_id') saga_id
+    FROM saga_instances
+    WHERE saga_type = 'OrderProcessingSaga' AND state->'data'->>'order_id' IS NOT NULL
+    ORDER BY state->'data'->>'order_id', created_at ASC
+) oldest
+WHERE s.saga_id = oldest.saga_id;
+
+-- At most one saga of a type per business key (NULL keys are not constrained)
+CREATE UNIQUE INDEX IF NOT EXISTS idx_saga_type_correlation_key
+    ON saga_instances (saga_type, correlation_key);
+
+-- Index for recovering stalled sagas of the partitions an instance owns
+CREATE INDEX IF NOT EXISTS idx_saga_partition_running
+    ON saga_instances (kafka_partition, updated_at)
+    WHERE status = 'RUNNING';
+
+COMMENT ON COLUMN saga_instances.correlation_key IS 'Business key (e.g. order ID) preventing duplicate sagas';
+COMMENT ON COLUMN saga_instances.kafka_partition IS 'Partition of the triggering event; its assigned orchestrator owns the saga';
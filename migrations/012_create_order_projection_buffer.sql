@@ -0,0 +1,16 @@
+-- Order events that arrived before the version they build on, held until the gap is filled
+CREATE TABLE IF NOT EXISTS order_projection_buffer (
+    order_id UUID NOT NULL,
+    version BIGINT NOT NULL,
+    event_type VARCHAR(255) NOT NULL,
+    payload JSONB NOT NULL,
+    buffered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (order_id, version)
+);
+
+-- Index for spotting events stuck behind a gap that never filled
+CREATE INDEX IF NOT EXISTS idx_order_projection_buffer_buffered_at
+    ON order_projection_buffer (buffered_at);
+
+COMMENT ON TABLE order_projection_buffer IS 'Out-of-order order events waiting for earlier versions to be projected';
+COMMENT ON COLUMN order_views.version IS 'Aggregate version of the last event applied to this view';
@@ -0,0 +1,23 @@
+-- Events the projection service gave up on after repeated failures
+CREATE TABLE IF NOT EXISTS projection_errors (
+    error_id UUID PRIMARY KEY,
+    aggregate_id UUID NOT NULL,
+    event_type VARCHAR(255) NOT NULL,
+    event_version BIGINT,
+    payload JSONB NOT NULL,
+    error TEXT NOT NULL,
+    attempts INT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'QUARANTINED',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_projection_error_status CHECK (status IN ('QUARANTINED', 'REQUEUED', 'RESOLVED'))
+);
+
+-- Index for listing by status and picking up requeued events
+CREATE INDEX IF NOT EXISTS idx_projection_errors_status ON projection_errors (status, created_at);
+
+-- Index for finding every quarantined event of an aggregate
+CREATE INDEX IF NOT EXISTS idx_projection_errors_aggregate ON projection_errors (aggregate_id);
+
+COMMENT ON TABLE projection_errors IS 'Poison events skipped by the projection service, with listing and requeue support';
+COMMENT ON COLUMN projection_errors.status IS 'QUARANTINED (skipped), REQUEUED (waiting for retry) or RESOLVED (applied on retry)';
@@ -0,0 +1,35 @@
+-- Read model of payments, fed by the payment-events topic
+CREATE TABLE IF NOT EXISTS payment_views (
+    payment_id UUID PRIMARY KEY,
+    order_id UUID NOT NULL,
+    status VARCHAR(50) NOT NULL,
+    amount DECIMAL(12,2) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    payment_method VARCHAR(50),
+    authorization_code VARCHAR(100),
+    transaction_id VARCHAR(100),
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_views_order ON payment_views(order_id);
+
+-- Read model of inventory reservations per order, fed by the inventory-events topic
+CREATE TABLE IF NOT EXISTS inventory_reservation_views (
+    order_id UUID PRIMARY KEY,
+    reservation_id UUID,
+    status VARCHAR(50) NOT NULL,
+    items JSONB NOT NULL,
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_inventory_reservation_views_status ON inventory_reservation_views(status);
+
+-- Quarantined events remember their topic so requeued ones reach the right projection
+ALTER TABLE projection_errors ADD COLUMN IF NOT EXISTS topic VARCHAR(255);
+
+COMMENT ON COLUMN payment_views.status IS 'AUTHORIZED, CAPTURED, VOIDED, FAILED or REFUNDED';
+COMMENT ON COLUMN inventory_reservation_views.status IS 'RESERVED, RELEASED or FAILED';
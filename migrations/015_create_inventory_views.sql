@@ -0,0 +1,14 @@
+-- Stock levels per SKU, fed by the inventory-events topic
+CREATE TABLE IF NOT EXISTS inventory_views (
+    sku VARCHAR(100) PRIMARY KEY,
+    product_id UUID NOT NULL,
+    on_hand BIGINT NOT NULL DEFAULT 0,
+    reserved BIGINT NOT NULL DEFAULT 0,
+    available BIGINT GENERATED ALWAYS AS (on_hand - reserved) STORED,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_inventory_views_product ON inventory_views(product_id);
+
+COMMENT ON COLUMN inventory_views.reserved IS 'Units held by reservations currently in RESERVED status';
+COMMENT ON COLUMN inventory_views.available IS 'on_hand - reserved';
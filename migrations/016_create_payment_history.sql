@@ -0,0 +1,20 @@
+-- Every payment status change, kept for capture/refund history and reconciliation
+CREATE TABLE IF NOT EXISTS payment_history (
+    payment_id UUID NOT NULL,
+    order_id UUID NOT NULL,
+    status VARCHAR(50) NOT NULL,
+    amount DECIMAL(12,2) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    authorization_code VARCHAR(100),
+    transaction_id VARCHAR(100),
+    reason TEXT,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (payment_id, status, occurred_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_history_order ON payment_history(order_id, occurred_at);
+
+-- Index for finding authorizations that were never captured or voided
+CREATE INDEX IF NOT EXISTS idx_payment_views_status_created ON payment_views(status, created_at);
+
+COMMENT ON TABLE payment_history IS 'Append-only log of payment events per order, one row per status change';
@@ -0,0 +1,15 @@
+-- Product catalog read model, fed by the product-events topic
+CREATE TABLE IF NOT EXISTS product_views (
+    product_id UUID PRIMARY KEY,
+    sku VARCHAR(100) NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    unit_price DECIMAL(12,2) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+COMMENT ON COLUMN product_views.unit_price IS 'Current list price';
+COMMENT ON COLUMN product_views.active IS 'FALSE once the product is discontinued';
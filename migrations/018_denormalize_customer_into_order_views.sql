@@ -0,0 +1,19 @@
+-- Customer read model, fed by the customer-events topic
+CREATE TABLE IF NOT EXISTS customer_views (
+    customer_id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+-- Copied onto each order so order lists need no per-row customer lookup
+ALTER TABLE order_views ADD COLUMN IF NOT EXISTS customer_name VARCHAR(255);
+ALTER TABLE order_views ADD COLUMN IF NOT EXISTS customer_email VARCHAR(255);
+
+-- Orders without a customer yet are found by the projection service's backfill
+CREATE INDEX IF NOT EXISTS idx_order_views_missing_customer
+    ON order_views(customer_id) WHERE customer_name IS NULL;
+
+COMMENT ON COLUMN order_views.customer_name IS 'Customer name from customer_views; NULL until the customer is known';
+COMMENT ON COLUMN order_views.customer_email IS 'Customer email from customer_views; NULL until the customer is known';
@@ -0,0 +1,13 @@
+-- Per-minute order counters behind the business KPI endpoint, kept up to date by
+-- the order projection so KPIs never scan order_views
+CREATE TABLE IF NOT EXISTS business_metrics_minutely (
+    bucket TIMESTAMPTZ PRIMARY KEY,
+    orders_created BIGINT NOT NULL DEFAULT 0,
+    order_value DECIMAL(14,2) NOT NULL DEFAULT 0,
+    orders_cancelled BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON COLUMN business_metrics_minutely.bucket IS 'Start of the minute the events happened in';
+COMMENT ON COLUMN business_metrics_minutely.order_value IS 'Sum of total_amount of orders created, across currencies';
+COMMENT ON COLUMN business_metrics_minutely.orders_cancelled IS 'Orders cancelled in the minute, whenever they were created';
@@ -0,0 +1,3 @@
+-- Causation graphs look sagas up by the correlation ID carried in their data
+CREATE INDEX IF NOT EXISTS idx_saga_correlation_id
+    ON saga_instances ((state->'data'->>'correlation_id'));
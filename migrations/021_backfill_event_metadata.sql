@@ -0,0 +1,18 @@
+-- Stored metadata is read back as typed EventMetadata, which requires correlation and causation IDs
+-- Events written without them (e.g. early soft-delete tombstones) become their own correlation root
+-- Values that are present but not UUIDs are left as they are, and their columns NULL
+UPDATE events
+SET metadata = metadata || jsonb_build_object(
+        'correlation_id', COALESCE(metadata->>'correlation_id', event_id::text),
+        'causation_id', COALESCE(metadata->>'causation_id', event_id::text)
+    ),
+    correlation_id = COALESCE(correlation_id, CASE
+        WHEN metadata->>'correlation_id' IS NULL THEN event_id
+        WHEN metadata->>'correlation_id' ~* '^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$'
+        THEN (metadata->>'correlation_id')::uuid END),
+    causation_id = COALESCE(causation_id, CASE
+        WHEN metadata->>'causation_id' IS NULL THEN event_id
+        WHEN metadata->>'causation_id' ~* '^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$'
+        THEN (metadata->>'causation_id')::uuid END)
+WHERE metadata->>'correlation_id' IS NULL
+   OR metadata->>'causation_id' IS NULL;
@@ -0,0 +1,22 @@
+-- Announce order status changes on the order_status_changed channel so the query
+-- service can answer long-polling status requests without polling order_views
+CREATE OR REPLACE FUNCTION notify_order_status_changed() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' OR OLD.status IS DISTINCT FROM NEW.status THEN
+        PERFORM pg_notify(
+            'order_status_changed',
+            json_build_object(
+                'order_id', NEW.order_id,
+                'status', NEW.status,
+                'version', NEW.version
+            )::text
+        );
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS order_views_status_changed ON order_views;
+CREATE TRIGGER order_views_status_changed
+    AFTER INSERT OR UPDATE OF status ON order_views
+    FOR EACH ROW EXECUTE FUNCTION notify_order_status_changed();
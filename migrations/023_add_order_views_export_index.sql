@@ -0,0 +1,3 @@
+-- Order exports page through order_views by (created_at, order_id)
+CREATE INDEX IF NOT EXISTS idx_order_views_created_order
+    ON order_views (created_at, order_id);
@@ -0,0 +1,25 @@
+-- Shipped orders whose delivery is followed through their carrier, so OrderDelivered
+-- is emitted from tracking updates rather than a manual deliver command
+CREATE TABLE IF NOT EXISTS shipments (
+    order_id UUID PRIMARY KEY,
+    carrier VARCHAR(100) NOT NULL,
+    tracking_number VARCHAR(255) NOT NULL,
+    tracking_status VARCHAR(50),
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    last_checked_at TIMESTAMPTZ,
+    delivered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT shipments_status_check CHECK (status IN ('PENDING', 'DELIVERED', 'CLOSED'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_shipments_tracking_number
+    ON shipments (carrier, tracking_number);
+
+CREATE INDEX IF NOT EXISTS idx_shipments_pending
+    ON shipments (last_checked_at NULLS FIRST)
+    WHERE status = 'PENDING';
+
+COMMENT ON COLUMN shipments.carrier IS 'Carrier name, lowercased';
+COMMENT ON COLUMN shipments.tracking_status IS 'Latest status the carrier reported, e.g. IN_TRANSIT';
+COMMENT ON COLUMN shipments.status IS 'PENDING until delivered, or CLOSED when the order can no longer be delivered';
@@ -0,0 +1,7 @@
+-- Sagas paused at a step that needs someone to approve it
+CREATE INDEX IF NOT EXISTS idx_saga_awaiting_approval
+    ON saga_instances (updated_at)
+    WHERE status = 'PAUSED_AWAITING_APPROVAL';
+
+COMMENT ON COLUMN saga_instances.status IS 'RUNNING, COMPLETED, COMPENSATING, COMPENSATED, FAILED, REQUIRES_INTERVENTION or PAUSED_AWAITING_APPROVAL';
+COMMENT ON COLUMN saga_views.status IS 'RUNNING, COMPENSATING, COMPLETED, COMPENSATED, REQUIRES_INTERVENTION or PAUSED_AWAITING_APPROVAL';
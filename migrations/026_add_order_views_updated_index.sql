@@ -0,0 +1,2 @@
+-- Recently active orders, read by the query service to warm its cache on start
+CREATE INDEX IF NOT EXISTS idx_order_views_updated ON order_views (updated_at DESC);
@@ -0,0 +1,29 @@
+-- Online backfills that fill in a new read model version from the event store
+CREATE TABLE IF NOT EXISTS read_model_backfills (
+    name VARCHAR(200) PRIMARY KEY,
+    view_name VARCHAR(100) NOT NULL,
+    target_version INT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'RUNNING',
+    last_created_at TIMESTAMPTZ,
+    last_event_id UUID,
+    events_processed BIGINT NOT NULL DEFAULT 0,
+    error TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+-- Live version of each read model view; views never cut over are at version 1
+CREATE TABLE IF NOT EXISTS read_model_versions (
+    view_name VARCHAR(100) PRIMARY KEY,
+    version INT NOT NULL,
+    backfill_name VARCHAR(200),
+    cut_over_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Backfills page through events by (created_at, event_id)
+CREATE INDEX IF NOT EXISTS idx_events_created_event ON events (created_at, event_id);
+
+COMMENT ON COLUMN read_model_backfills.status IS 'RUNNING, COMPLETED or FAILED';
+COMMENT ON COLUMN read_model_backfills.last_created_at IS 'created_at of the last event applied; with last_event_id, where a restarted backfill resumes';
+COMMENT ON COLUMN read_model_versions.backfill_name IS 'Backfill whose cut-over made this version live';
@@ -0,0 +1,13 @@
+-- Events waiting to be published to Kafka, written in the same transaction as the
+-- events themselves so a crash between commit and publish can't lose them
+CREATE TABLE IF NOT EXISTS event_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    event_id UUID NOT NULL UNIQUE,
+    aggregate_id UUID NOT NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE event_outbox IS 'Rows are deleted once the relay has published them';
+COMMENT ON COLUMN event_outbox.aggregate_id IS 'Kafka partition key';
+COMMENT ON COLUMN event_outbox.payload IS 'EventEnvelope exactly as published';
@@ -0,0 +1,10 @@
+-- Replication progress, kept in the replica's database alongside the copied events
+CREATE TABLE IF NOT EXISTS event_replication_checkpoints (
+    name VARCHAR(100) PRIMARY KEY,
+    last_created_at TIMESTAMPTZ NOT NULL,
+    last_event_id UUID NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE event_replication_checkpoints IS 'How far each replication link has copied the source event log';
+COMMENT ON COLUMN event_replication_checkpoints.last_created_at IS 'created_at of the last replicated source event; with last_event_id, where replication resumes';
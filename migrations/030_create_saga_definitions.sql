@@ -0,0 +1,10 @@
+-- Saga flow definitions published by the saga orchestrator on startup
+CREATE TABLE IF NOT EXISTS saga_definitions (
+    saga_type VARCHAR(100) PRIMARY KEY,
+    definition JSONB NOT NULL,
+    published_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE saga_definitions IS 'Machine-readable saga flows (step order, compensations, retries, timeouts) for docs and dashboards';
+COMMENT ON COLUMN saga_definitions.definition IS 'SagaDefinition as JSON, replaced whenever an orchestrator starts';
+COMMENT ON COLUMN saga_definitions.published_at IS 'When an orchestrator last published the definition';
@@ -0,0 +1,12 @@
+-- Idempotency keys for deployments using IDEMPOTENCY_BACKEND=postgres instead of Redis
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    idempotency_key TEXT PRIMARY KEY,
+    result JSONB,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_expires_at ON idempotency_keys (expires_at);
+
+COMMENT ON COLUMN idempotency_keys.result IS 'NULL while the first caller is still processing the key';
+COMMENT ON COLUMN idempotency_keys.expires_at IS 'Rows past this are ignored and purged by the cleanup job';
@@ -0,0 +1,13 @@
+-- Event time next to processing time, to tell late events from slow processing
+ALTER TABLE order_views
+    ADD COLUMN IF NOT EXISTS event_created_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS processed_at TIMESTAMPTZ;
+
+ALTER TABLE business_metrics_minutely
+    ADD COLUMN IF NOT EXISTS event_created_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS processed_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN order_views.event_created_at IS 'When the last event applied to the view was stored';
+COMMENT ON COLUMN order_views.processed_at IS 'When the projection last applied an event to the view';
+COMMENT ON COLUMN business_metrics_minutely.event_created_at IS 'When the newest event counted in the bucket was stored';
+COMMENT ON COLUMN business_metrics_minutely.processed_at IS 'When the projection last counted an event in the bucket';
@@ -0,0 +1,76 @@
+-- Position of each event in the global log, returned by the query service so clients
+-- can bookmark a read and later fetch the events after it, and the transaction that
+-- appended it. A transaction still in flight may commit a lower position than one
+-- already visible, so the log is only read for events of transactions older than
+-- every open one and a bookmark never skips an event.
+--
+-- Staged so `events` is never rewritten: adding nullable columns without a default
+-- and setting defaults afterwards only touch the catalog, holding ACCESS EXCLUSIVE
+-- for a moment, and existing rows are numbered in batches that each commit on their
+-- own. Run with psql in autocommit mode (the default), as `make migrate` does.
+--
+-- Ordering caveat: the commit order of existing events was never recorded. They are
+-- numbered by (created_at, event_id), which can differ from the order concurrent
+-- appends committed in, and all share transaction_id 0, so they sort before every
+-- event appended once this migration has started. Readers should not take a cursor
+-- until the backfill has finished.
+
+ALTER TABLE events ADD COLUMN IF NOT EXISTS global_position BIGINT;
+ALTER TABLE events ADD COLUMN IF NOT EXISTS transaction_id xid8;
+
+CREATE SEQUENCE IF NOT EXISTS events_global_position_seq OWNED BY events.global_position;
+
+-- New events get both from here on, so the backfill below is the only pass needed
+ALTER TABLE events ALTER COLUMN global_position SET DEFAULT nextval('events_global_position_seq');
+ALTER TABLE events ALTER COLUMN transaction_id SET DEFAULT pg_current_xact_id();
+
+DO $$
+DECLARE
+    last_created TIMESTAMPTZ := '-infinity';
+    last_event UUID := '00000000-0000-0000-0000-000000000000';
+    numbered BIGINT;
+BEGIN
+    LOOP
+        WITH pending AS (
+            SELECT event_id, created_at FROM events
+            WHERE global_position IS NULL
+              AND (created_at, event_id) > (last_created, last_event)
+            ORDER BY created_at, event_id
+            LIMIT 10000
+        ),
+        batch AS (
+            SELECT event_id, created_at, nextval('events_global_position_seq') AS position
+            FROM pending
+        ),
+        numbered_rows AS (
+            UPDATE events e
+            SET global_position = batch.position,
+                transaction_id = '0'::xid8
+            FROM batch
+            WHERE e.event_id = batch.event_id
+            RETURNING batch.created_at, batch.event_id
+        )
+        SELECT count(*), max(created_at),
+               (array_agg(event_id ORDER BY created_at DESC, event_id DESC))[1]
+        INTO numbered, last_created, last_event
+        FROM numbered_rows;
+
+        EXIT WHEN numbered = 0;
+        COMMIT;
+    END LOOP;
+END $$;
+
+-- Validating a constraint added NOT VALID only takes SHARE UPDATE EXCLUSIVE, and
+-- SET NOT NULL then relies on it instead of scanning under ACCESS EXCLUSIVE
+ALTER TABLE events DROP CONSTRAINT IF EXISTS events_global_log_not_null;
+ALTER TABLE events ADD CONSTRAINT events_global_log_not_null
+    CHECK (global_position IS NOT NULL AND transaction_id IS NOT NULL) NOT VALID;
+ALTER TABLE events VALIDATE CONSTRAINT events_global_log_not_null;
+ALTER TABLE events ALTER COLUMN global_position SET NOT NULL;
+ALTER TABLE events ALTER COLUMN transaction_id SET NOT NULL;
+ALTER TABLE events DROP CONSTRAINT events_global_log_not_null;
+
+CREATE UNIQUE INDEX CONCURRENTLY IF NOT EXISTS idx_events_global_position ON events(global_position);
+
+COMMENT ON COLUMN events.global_position IS 'Position in the global event log, increasing in append order';
+COMMENT ON COLUMN events.transaction_id IS 'Transaction that appended the event; 0 for events appended before the column existed';
@@ -0,0 +1,37 @@
+-- Row-level changes of order_views, captured in the same transaction as the change
+-- and published to Kafka by the projection service's CDC emitter. The capture
+-- trigger is installed by the emitter when CDC_ENABLED is set, so nothing is
+-- recorded while the export is off.
+CREATE TABLE IF NOT EXISTS order_view_changes (
+    id BIGSERIAL PRIMARY KEY,
+    order_id UUID NOT NULL,
+    -- Debezium operation codes: c(reate), u(pdate), d(elete), r(ead) for snapshot rows
+    op TEXT NOT NULL CHECK (op IN ('c', 'u', 'd', 'r')),
+    before JSONB,
+    after JSONB,
+    transaction_id xid8 NOT NULL DEFAULT pg_current_xact_id(),
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE order_view_changes IS 'Pending order_views changes for the order-views-changed topic; rows are deleted once published';
+
+-- Updates that only move the event/processing timestamps (migrations/032) are not
+-- changes downstream consumers care about and would double the volume
+CREATE OR REPLACE FUNCTION capture_order_view_change() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' AND
+        to_jsonb(OLD) - 'event_created_at' - 'processed_at' =
+        to_jsonb(NEW) - 'event_created_at' - 'processed_at' THEN
+        RETURN NULL;
+    END IF;
+
+    INSERT INTO order_view_changes (order_id, op, before, after)
+    VALUES (
+        COALESCE(NEW.order_id, OLD.order_id),
+        CASE TG_OP WHEN 'INSERT' THEN 'c' WHEN 'UPDATE' THEN 'u' ELSE 'd' END,
+        CASE WHEN TG_OP = 'INSERT' THEN NULL ELSE to_jsonb(OLD) END,
+        CASE WHEN TG_OP = 'DELETE' THEN NULL ELSE to_jsonb(NEW) END
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,38 @@
+-- Event lake export progress. A batch is recorded as PENDING before its Parquet files
+-- are uploaded and marked COMMITTED, with its files, once every upload succeeded; a
+-- pending batch is redone over the same positions, so its files keep the same keys.
+CREATE TABLE IF NOT EXISTS event_lake_batches (
+    name VARCHAR(100) NOT NULL,
+    first_position BIGINT NOT NULL,
+    last_position BIGINT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'COMMITTED')),
+    event_count INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    committed_at TIMESTAMPTZ,
+    PRIMARY KEY (name, first_position)
+);
+
+-- At most one batch per export is in flight
+CREATE UNIQUE INDEX IF NOT EXISTS idx_event_lake_batches_pending
+    ON event_lake_batches(name) WHERE status = 'PENDING';
+CREATE INDEX IF NOT EXISTS idx_event_lake_batches_last_position
+    ON event_lake_batches(name, last_position DESC);
+
+CREATE TABLE IF NOT EXISTS event_lake_files (
+    object_key TEXT PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    first_position BIGINT NOT NULL,
+    aggregate_type VARCHAR(255) NOT NULL,
+    event_date DATE NOT NULL,
+    row_count INTEGER NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    committed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (name, first_position) REFERENCES event_lake_batches(name, first_position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_lake_files_partition
+    ON event_lake_files(aggregate_type, event_date);
+
+COMMENT ON TABLE event_lake_batches IS 'Ranges of the global event log exported to the event lake, by export name';
+COMMENT ON COLUMN event_lake_batches.last_position IS 'global_position of the last event in the batch; the highest committed one is where the export resumes';
+COMMENT ON TABLE event_lake_files IS 'Parquet files of committed event lake batches, one per aggregate type and day';
@@ -0,0 +1,30 @@
+-- Durable background jobs (messaging::jobs). Workers claim rows with SKIP LOCKED;
+-- a claim lasts until locked_until, after which another worker may take the job.
+CREATE TABLE IF NOT EXISTS jobs (
+    id UUID PRIMARY KEY,
+    -- Enqueue order; jobs sharing an ordering_key run one at a time in this order
+    seq BIGSERIAL NOT NULL UNIQUE,
+    queue TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    ordering_key TEXT,
+    status TEXT NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'RUNNING', 'DEAD')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    run_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_until TIMESTAMPTZ,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_jobs_queue_seq ON jobs (queue, seq) WHERE status <> 'DEAD';
+CREATE INDEX IF NOT EXISTS idx_jobs_ordering_key ON jobs (queue, ordering_key, seq)
+    WHERE status <> 'DEAD' AND ordering_key IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_jobs_dead ON jobs (queue, seq) WHERE status = 'DEAD';
+
+COMMENT ON TABLE jobs IS 'Rows are deleted once their job succeeds; DEAD rows wait for an operator to requeue them';
+COMMENT ON COLUMN jobs.attempts IS 'Times the job has been claimed, reset when a dead letter is requeued';
+
+-- The command service's outbox is now the outbox queue, keyed by aggregate so each
+-- aggregate's events are still published in commit order. event_outbox stays for
+-- one release: replicas of the previous release still write it during a rolling
+-- deploy, and the new relay moves its rows onto the queue. A later migration drops it.
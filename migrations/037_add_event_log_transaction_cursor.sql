@@ -0,0 +1,28 @@
+-- Readers of the global log resume from a (transaction_id, global_position) cursor.
+-- Events of transactions older than every open one never change, and any event that
+-- becomes visible later belongs to a newer transaction, so it sorts after the cursor.
+-- Resuming by global_position alone skipped an event appended at a lower position by
+-- a transaction that committed after a higher one had been read.
+CREATE INDEX IF NOT EXISTS idx_events_transaction_position
+    ON events(transaction_id, global_position);
+
+-- Event lake batches span a range of that cursor rather than of positions
+ALTER TABLE event_lake_batches ADD COLUMN IF NOT EXISTS first_transaction_id BIGINT;
+ALTER TABLE event_lake_batches ADD COLUMN IF NOT EXISTS last_transaction_id BIGINT;
+
+UPDATE event_lake_batches b
+SET first_transaction_id = COALESCE(
+        (SELECT e.transaction_id::text::bigint FROM events e WHERE e.global_position = b.first_position), 0),
+    last_transaction_id = COALESCE(
+        (SELECT e.transaction_id::text::bigint FROM events e WHERE e.global_position = b.last_position), 0)
+WHERE b.first_transaction_id IS NULL OR b.last_transaction_id IS NULL;
+
+ALTER TABLE event_lake_batches ALTER COLUMN first_transaction_id SET NOT NULL;
+ALTER TABLE event_lake_batches ALTER COLUMN last_transaction_id SET NOT NULL;
+
+DROP INDEX IF EXISTS idx_event_lake_batches_last_position;
+CREATE INDEX IF NOT EXISTS idx_event_lake_batches_last_cursor
+    ON event_lake_batches(name, last_transaction_id DESC, last_position DESC);
+
+COMMENT ON COLUMN event_lake_batches.last_transaction_id IS 'transaction_id of the last event in the batch; with last_position, the cursor the export resumes after';
+COMMENT ON COLUMN event_lake_batches.last_position IS 'global_position of the last event in the batch, in (transaction_id, global_position) order';
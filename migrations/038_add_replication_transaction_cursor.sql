@@ -0,0 +1,19 @@
+-- Replication resumes from the source's (transaction_id, global_position) cursor.
+-- Resuming by (created_at, event_id) skipped events of transactions that committed
+-- after later events had been copied.
+ALTER TABLE event_replication_checkpoints ADD COLUMN IF NOT EXISTS last_transaction_id BIGINT;
+ALTER TABLE event_replication_checkpoints ADD COLUMN IF NOT EXISTS last_position BIGINT;
+
+-- Source transaction IDs aren't known on the replica, so existing links start over
+-- from the beginning; events the replica already holds are skipped, and the events
+-- earlier links missed are copied.
+UPDATE event_replication_checkpoints
+SET last_transaction_id = 0,
+    last_position = 0
+WHERE last_transaction_id IS NULL OR last_position IS NULL;
+
+ALTER TABLE event_replication_checkpoints ALTER COLUMN last_transaction_id SET NOT NULL;
+ALTER TABLE event_replication_checkpoints ALTER COLUMN last_position SET NOT NULL;
+
+COMMENT ON COLUMN event_replication_checkpoints.last_transaction_id IS 'transaction_id of the last replicated source event; with last_position, where replication resumes';
+COMMENT ON COLUMN event_replication_checkpoints.last_created_at IS 'created_at of the last replicated source event, for reporting lag';
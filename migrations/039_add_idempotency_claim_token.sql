@@ -0,0 +1,5 @@
+-- Claim that holds an in-progress idempotency key, so only that caller can complete
+-- or release it once another caller may have taken over an expired claim
+ALTER TABLE idempotency_keys ADD COLUMN IF NOT EXISTS claim_token UUID;
+
+COMMENT ON COLUMN idempotency_keys.claim_token IS 'Claim of the caller processing the key; NULL once a result is recorded';
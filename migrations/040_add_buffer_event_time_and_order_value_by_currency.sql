@@ -0,0 +1,27 @@
+-- When each buffered order event was stored, so it is applied with its event time
+-- once the gap before it is filled
+ALTER TABLE order_projection_buffer ADD COLUMN IF NOT EXISTS event_created_at TIMESTAMPTZ;
+
+-- Order value per minute and currency, since amounts in different currencies cannot
+-- be added up; business_metrics_minutely keeps the counts
+CREATE TABLE IF NOT EXISTS business_metrics_order_value (
+    bucket TIMESTAMPTZ NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    orders_created BIGINT NOT NULL DEFAULT 0,
+    order_value DECIMAL(14,2) NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (bucket, currency)
+);
+
+-- Seeded from the order views: each order's current total, in the minute it was
+-- created. Deleted orders and the minute of later price corrections are not recovered.
+INSERT INTO business_metrics_order_value (bucket, currency, orders_created, order_value)
+SELECT date_trunc('minute', created_at), currency, COUNT(*), SUM(total_amount)
+FROM order_views
+GROUP BY 1, 2
+ON CONFLICT (bucket, currency) DO NOTHING;
+
+COMMENT ON COLUMN order_projection_buffer.event_created_at IS 'When the buffered event was stored, if known';
+COMMENT ON COLUMN business_metrics_order_value.orders_created IS 'Orders created in the minute in this currency';
+COMMENT ON COLUMN business_metrics_order_value.order_value IS 'Sum of total_amount of those orders, plus price corrections in the minute';
+COMMENT ON COLUMN business_metrics_minutely.order_value IS 'No longer updated; summed across currencies, see business_metrics_order_value';
@@ -0,0 +1,309 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use common::metrics;
+use messaging::JobStore;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::handlers::errors::ErrorResponse;
+use crate::outbox::OUTBOX_QUEUE;
+use crate::state::AppState;
+
+/// Weight of the newest sample in the append latency average
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+/// Thresholds at which non-critical commands are held back
+#[derive(Debug, Clone)]
+pub struct AdmissionConfig {
+    /// Average event store append latency considered elevated
+    pub latency_threshold: Duration,
+    /// Outbox jobs waiting to be published considered elevated
+    pub backlog_threshold: usize,
+    /// How long an elevated command waits for the pressure to ease before it is shed
+    pub max_delay: Duration,
+    /// Sent as `Retry-After` when a command is shed
+    pub retry_after: Duration,
+    /// Latency samples older than this are ignored, so a quiet service recovers
+    pub stale_after: Duration,
+}
+
+impl Default for AdmissionConfig {
+    fn default() -> Self {
+        Self {
+            latency_threshold: Duration::from_millis(250),
+            backlog_threshold: 1000,
+            max_delay: Duration::from_millis(200),
+            retry_after: Duration::from_secs(5),
+            stale_after: Duration::from_secs(10),
+        }
+    }
+}
+
+/// What to do with a non-critical command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    Admit,
+    /// Wait this long and check again
+    Delay(Duration),
+    /// Reject with 503, asking the client to come back after this long
+    Shed(Duration),
+}
+
+/// Holds back non-critical commands while the event store or Kafka is struggling
+///
+/// Pressure is the larger of the average append latency and the outbox backlog,
+/// each relative to its threshold. Below 1 every command is admitted; up to twice
+/// the threshold non-critical commands are delayed, and beyond that they are shed
+/// so the critical ones still get through.
+pub struct AdmissionController {
+    config: AdmissionConfig,
+    latency: Mutex<Option<(f64, Instant)>>,
+    /// Outbox depth as last sampled by [`spawn_backlog_sampler`]
+    backlog: AtomicUsize,
+}
+
+impl AdmissionController {
+    pub fn new(config: AdmissionConfig) -> Self {
+        Self {
+            config,
+            latency: Mutex::new(None),
+            backlog: AtomicUsize::new(0),
+        }
+    }
+
+    /// Record how many events are waiting in the outbox
+    pub fn record_backlog(&self, depth: usize) {
+        self.backlog.store(depth, Ordering::Relaxed);
+    }
+
+    /// Outbox depth as last recorded
+    pub fn backlog(&self) -> usize {
+        self.backlog.load(Ordering::Relaxed)
+    }
+
+    /// Feed the duration of an event store append into the latency average
+    pub fn record_append_latency(&self, elapsed: Duration) {
+        let sample = elapsed.as_secs_f64();
+        let mut latency = self.latency.lock().unwrap_or_else(|e| e.into_inner());
+        let average = match *latency {
+            Some((average, at)) if at.elapsed() < self.config.stale_after => {
+                average + LATENCY_SMOOTHING * (sample - average)
+            }
+            _ => sample,
+        };
+        *latency = Some((average, Instant::now()));
+    }
+
+    /// Current average append latency, or zero when there are no recent appends
+    pub fn append_latency(&self) -> Duration {
+        let latency = self.latency.lock().unwrap_or_else(|e| e.into_inner());
+        match *latency {
+            Some((average, at)) if at.elapsed() < self.config.stale_after => {
+                Duration::from_secs_f64(average)
+            }
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Decide on a non-critical command given the current outbox backlog
+    pub fn decide(&self, backlog: usize) -> Admission {
+        let latency = self.append_latency().as_secs_f64()
+            / self.config.latency_threshold.as_secs_f64().max(f64::EPSILON);
+        let backlog = backlog as f64 / self.config.backlog_threshold.max(1) as f64;
+
+        let pressure = latency.max(backlog);
+        if pressure < 1.0 {
+            Admission::Admit
+        } else if pressure < 2.0 && !self.config.max_delay.is_zero() {
+            Admission::Delay(self.config.max_delay)
+        } else {
+            Admission::Shed(self.config.retry_after)
+        }
+    }
+
+    /// Name of the signal currently over its threshold, for metrics and logs
+    fn reason(&self, backlog: usize) -> &'static str {
+        if backlog >= self.config.backlog_threshold {
+            "backlog"
+        } else {
+            "latency"
+        }
+    }
+}
+
+/// Middleware for the routes that are shed first under load
+///
+/// Non-critical commands start new work (creating orders, bulk shipments); the
+/// ones that move existing orders along are left alone.
+pub async fn admit_non_critical(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let Some(admission) = state.admission.as_ref() else {
+        return next.run(request).await;
+    };
+
+    let backlog = || admission.backlog();
+    let decision = match admission.decide(backlog()) {
+        Admission::Delay(delay) => {
+            metrics::record_command_admission("delayed", admission.reason(backlog()));
+            tokio::time::sleep(delay).await;
+            match admission.decide(backlog()) {
+                Admission::Admit => Admission::Admit,
+                _ => Admission::Shed(admission.config.retry_after),
+            }
+        }
+        decision => decision,
+    };
+
+    match decision {
+        Admission::Shed(retry_after) => {
+            let backlog = backlog();
+            let reason = admission.reason(backlog);
+            metrics::record_command_admission("shed", reason);
+            warn!(
+                path = %request.uri().path(),
+                reason,
+                append_latency_ms = admission.append_latency().as_millis() as u64,
+                backlog,
+                "Shedding command under load"
+            );
+            overloaded(retry_after)
+        }
+        _ => next.run(request).await,
+    }
+}
+
+/// Sample the outbox depth into `admission` every `poll_interval`
+///
+/// Events wait there until Kafka acknowledges them, so a growing queue means the
+/// broker is falling behind. A failed count keeps the previous sample.
+pub fn spawn_backlog_sampler(
+    admission: Arc<AdmissionController>,
+    jobs: Arc<dyn JobStore>,
+    poll_interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            match jobs.depth(OUTBOX_QUEUE).await {
+                Ok(depth) => {
+                    admission.record_backlog(usize::try_from(depth).unwrap_or(usize::MAX))
+                }
+                Err(e) => warn!("Failed to count the outbox backlog: {}", e),
+            }
+        }
+    })
+}
+
+fn overloaded(retry_after: Duration) -> Response {
+    let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let mut response = (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ErrorResponse::new("Service is overloaded, retry later")),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller() -> AdmissionController {
+        AdmissionController::new(AdmissionConfig {
+            latency_threshold: Duration::from_millis(100),
+            backlog_threshold: 10,
+            ..AdmissionConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_admits_when_idle() {
+        assert_eq!(controller().decide(0), Admission::Admit);
+    }
+
+    #[test]
+    fn test_latency_delays_then_sheds() {
+        let admission = controller();
+
+        admission.record_append_latency(Duration::from_millis(150));
+        assert_eq!(admission.decide(0), Admission::Delay(Duration::from_millis(200)));
+
+        for _ in 0..20 {
+            admission.record_append_latency(Duration::from_millis(500));
+        }
+        assert_eq!(admission.decide(0), Admission::Shed(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_latency_is_smoothed() {
+        let admission = controller();
+        admission.record_append_latency(Duration::from_millis(10));
+        admission.record_append_latency(Duration::from_millis(510));
+
+        // One slow append moves the average a fifth of the way
+        assert!((admission.append_latency().as_secs_f64() - 0.110).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_backlog_sheds() {
+        let admission = controller();
+        assert_eq!(admission.decide(15), Admission::Delay(Duration::from_millis(200)));
+        assert_eq!(admission.decide(20), Admission::Shed(Duration::from_secs(5)));
+        assert_eq!(admission.reason(20), "backlog");
+    }
+
+    #[tokio::test]
+    async fn test_backlog_sampler_counts_the_outbox() {
+        let admission = Arc::new(controller());
+        let jobs = Arc::new(messaging::InMemoryJobStore::new());
+        for _ in 0..12 {
+            jobs.enqueue(messaging::NewJob::new(OUTBOX_QUEUE, serde_json::json!({})))
+                .await
+                .unwrap();
+        }
+        jobs.enqueue(messaging::NewJob::new("other", serde_json::json!({})))
+            .await
+            .unwrap();
+
+        let sampler = spawn_backlog_sampler(admission.clone(), jobs, Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        sampler.abort();
+
+        assert_eq!(admission.backlog(), 12);
+        assert_eq!(
+            admission.decide(admission.backlog()),
+            Admission::Delay(Duration::from_millis(200))
+        );
+    }
+
+    #[test]
+    fn test_stale_latency_is_ignored() {
+        let admission = AdmissionController::new(AdmissionConfig {
+            latency_threshold: Duration::from_millis(100),
+            stale_after: Duration::from_millis(1),
+            ..AdmissionConfig::default()
+        });
+        admission.record_append_latency(Duration::from_secs(1));
+        std::thread::sleep(Duration::from_millis(5));
+
+        assert_eq!(admission.decide(0), Admission::Admit);
+    }
+
+    #[test]
+    fn test_overloaded_response_sets_retry_after() {
+        let response = overloaded(Duration::from_millis(2500));
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "3");
+    }
+}
@@ -0,0 +1,103 @@
+use domain::aggregates::order::OrderAggregate;
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// In-process LRU of recently rehydrated order aggregates
+///
+/// Each entry remembers the stream version it was built from. A cached entry
+/// is only a starting point: the loader still reads any events appended after
+/// that version, so successive commands only pay for the delta instead of the
+/// whole stream.
+pub struct AggregateCache {
+    entries: Mutex<LruCache<Uuid, (OrderAggregate, i64)>>,
+}
+
+impl AggregateCache {
+    /// Create a cache holding at most `capacity` aggregates
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Get a cached aggregate and the version it was rehydrated at
+    pub fn get(&self, aggregate_id: Uuid) -> Option<(OrderAggregate, i64)> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.get(&aggregate_id).cloned()
+    }
+
+    /// Cache an aggregate, never replacing a newer version with an older one
+    pub fn put(&self, aggregate_id: Uuid, aggregate: OrderAggregate, version: i64) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, cached_version)) = entries.peek(&aggregate_id) {
+            if *cached_version > version {
+                return;
+            }
+        }
+        entries.put(aggregate_id, (aggregate, version));
+    }
+
+    /// Drop a cached aggregate (e.g. after a failed append or stream deletion)
+    pub fn invalidate(&self, aggregate_id: Uuid) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.pop(&aggregate_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_and_get() {
+        let cache = AggregateCache::new(10);
+        let id = Uuid::new_v4();
+
+        cache.put(id, OrderAggregate::default(), 3);
+
+        let (_, version) = cache.get(id).unwrap();
+        assert_eq!(version, 3);
+    }
+
+    #[test]
+    fn test_put_does_not_regress_version() {
+        let cache = AggregateCache::new(10);
+        let id = Uuid::new_v4();
+
+        cache.put(id, OrderAggregate::default(), 5);
+        cache.put(id, OrderAggregate::default(), 2);
+
+        assert_eq!(cache.get(id).unwrap().1, 5);
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let cache = AggregateCache::new(10);
+        let id = Uuid::new_v4();
+
+        cache.put(id, OrderAggregate::default(), 1);
+        cache.invalidate(id);
+
+        assert!(cache.get(id).is_none());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = AggregateCache::new(2);
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let third = Uuid::new_v4();
+
+        cache.put(first, OrderAggregate::default(), 1);
+        cache.put(second, OrderAggregate::default(), 1);
+        cache.get(first);
+        cache.put(third, OrderAggregate::default(), 1);
+
+        assert!(cache.get(first).is_some());
+        assert!(cache.get(second).is_none());
+        assert!(cache.get(third).is_some());
+    }
+}
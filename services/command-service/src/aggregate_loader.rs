@@ -0,0 +1,268 @@
+use chrono::{DateTime, Utc};
+use domain::{aggregates::order::OrderAggregate, events::order_events::*};
+use event_store::{Event, EventStore, EventStoreError, DEFAULT_PAGE_SIZE, TOMBSTONE_EVENT_TYPE};
+use futures::TryStreamExt;
+use uuid::Uuid;
+
+use crate::aggregate_cache::AggregateCache;
+
+/// Rebuild an order aggregate by streaming its events page by page
+///
+/// Starts from the cached aggregate when one is available and only applies
+/// events appended since it was cached. Returns the aggregate together with
+/// the version of the last applied event, or `None` if the order has no
+/// events or its stream has been deleted.
+pub async fn load_order(
+    event_store: &dyn EventStore,
+    cache: &AggregateCache,
+    order_id: Uuid,
+) -> Result<Option<(OrderAggregate, i64)>, EventStoreError> {
+    let (mut aggregate, mut version) = cache
+        .get(order_id)
+        .unwrap_or_else(|| (OrderAggregate::default(), 0));
+    let cached_version = version;
+
+    let mut events = event_store.load_events_paged(order_id, version, DEFAULT_PAGE_SIZE);
+
+    while let Some(event) = events.try_next().await? {
+        if event.event_type == TOMBSTONE_EVENT_TYPE {
+            cache.invalidate(order_id);
+            return Ok(None);
+        }
+
+        version = event.sequence_number;
+        apply_event(&mut aggregate, event)?;
+    }
+
+    if version == 0 {
+        return Ok(None);
+    }
+
+    if version != cached_version {
+        cache.put(order_id, aggregate.clone(), version);
+    }
+
+    Ok(Some((aggregate, version)))
+}
+
+/// Point in an aggregate's history to rebuild it at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryPoint {
+    /// After the event with this stream version
+    Version(i64),
+    /// After the last event recorded at or before this instant
+    Timestamp(DateTime<Utc>),
+}
+
+impl HistoryPoint {
+    fn includes(&self, event: &Event) -> bool {
+        match self {
+            HistoryPoint::Version(version) => event.sequence_number <= *version,
+            HistoryPoint::Timestamp(at) => event.created_at <= *at,
+        }
+    }
+}
+
+/// An order as it stood at a [`HistoryPoint`]
+#[derive(Debug, Clone)]
+pub struct HistoricalOrder {
+    pub aggregate: OrderAggregate,
+    /// Stream version of the last event applied
+    pub version: i64,
+    /// When the last event applied was recorded
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Rebuild an order as it stood at `at` by replaying its stream up to that point
+///
+/// Never uses the aggregate cache, which only holds the latest state. Returns `None`
+/// if the order had no events by then or its stream had been deleted by then.
+pub async fn load_aggregate_at(
+    event_store: &dyn EventStore,
+    order_id: Uuid,
+    at: HistoryPoint,
+) -> Result<Option<HistoricalOrder>, EventStoreError> {
+    let mut aggregate = OrderAggregate::default();
+    let mut last: Option<(i64, DateTime<Utc>)> = None;
+
+    let mut events = event_store.load_events_paged(order_id, 0, DEFAULT_PAGE_SIZE);
+    while let Some(event) = events.try_next().await? {
+        // Versions grow with time, so nothing after this event is included either
+        if !at.includes(&event) {
+            break;
+        }
+        if event.event_type == TOMBSTONE_EVENT_TYPE {
+            return Ok(None);
+        }
+
+        last = Some((event.sequence_number, event.created_at));
+        apply_event(&mut aggregate, event)?;
+    }
+
+    Ok(last.map(|(version, recorded_at)| HistoricalOrder {
+        aggregate,
+        version,
+        recorded_at,
+    }))
+}
+
+/// ID of the event that recorded the current unit price of `product_id`
+///
+/// That is the last price correction for the product, or `OrderCreated` if it was
+/// never corrected. Returns `None` if the stream has no such event.
+pub async fn find_price_source(
+    event_store: &dyn EventStore,
+    order_id: Uuid,
+    product_id: Uuid,
+) -> Result<Option<Uuid>, EventStoreError> {
+    let mut source = None;
+
+    let mut events = event_store.load_events_paged(order_id, 0, DEFAULT_PAGE_SIZE);
+    while let Some(event) = events.try_next().await? {
+        if sets_price_of(&event, product_id) {
+            source = Some(event.event_id);
+        }
+    }
+
+    Ok(source)
+}
+
+fn sets_price_of(event: &Event, product_id: Uuid) -> bool {
+    match event.event_type.as_str() {
+        "OrderCreated" => true,
+        "OrderItemPriceCorrected" => {
+            event.payload["product_id"].as_str() == Some(product_id.to_string().as_str())
+        }
+        _ => false,
+    }
+}
+
+/// Apply a single stored event to the aggregate
+fn apply_event(aggregate: &mut OrderAggregate, event: Event) -> Result<(), EventStoreError> {
+    match event.event_type.as_str() {
+        "OrderCreated" => {
+            let domain_event: OrderCreatedEvent = serde_json::from_value(event.payload)?;
+            aggregate.apply_order_created(&domain_event);
+        }
+        "OrderConfirmed" => {
+            let domain_event: OrderConfirmedEvent = serde_json::from_value(event.payload)?;
+            aggregate.apply_order_confirmed(&domain_event);
+        }
+        "OrderCancelled" => {
+            let domain_event: OrderCancelledEvent = serde_json::from_value(event.payload)?;
+            aggregate.apply_order_cancelled(&domain_event);
+        }
+        "OrderShipped" => {
+            let domain_event: OrderShippedEvent = serde_json::from_value(event.payload)?;
+            aggregate.apply_order_shipped(&domain_event);
+        }
+        "OrderDelivered" => {
+            let domain_event: OrderDeliveredEvent = serde_json::from_value(event.payload)?;
+            aggregate.apply_order_delivered(&domain_event);
+        }
+        "OrderItemPriceCorrected" => {
+            let domain_event: OrderItemPriceCorrectedEvent = serde_json::from_value(event.payload)?;
+            aggregate.apply_order_item_price_corrected(&domain_event);
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use domain::aggregates::order::OrderStatus;
+    use domain::events::EventMetadata;
+
+    fn stored_event(event_type: &str, payload: serde_json::Value) -> Event {
+        Event::new(
+            Uuid::new_v4(),
+            "Order".to_string(),
+            event_type.to_string(),
+            1,
+            payload,
+            EventMetadata::new(),
+        )
+    }
+
+    #[test]
+    fn test_apply_event_updates_status() {
+        let order_id = Uuid::new_v4();
+        let mut aggregate = OrderAggregate::default();
+
+        let created = OrderCreatedEvent {
+            order_id,
+            customer_id: Uuid::new_v4(),
+            order_number: "ORD-123".to_string(),
+            items: vec![],
+            total_amount: 10.0,
+            currency: "USD".to_string(),
+            created_at: Utc::now(),
+        };
+        apply_event(
+            &mut aggregate,
+            stored_event("OrderCreated", serde_json::to_value(&created).unwrap()),
+        )
+        .unwrap();
+
+        let confirmed = OrderConfirmedEvent {
+            order_id,
+            confirmed_at: Utc::now(),
+        };
+        apply_event(
+            &mut aggregate,
+            stored_event("OrderConfirmed", serde_json::to_value(&confirmed).unwrap()),
+        )
+        .unwrap();
+
+        assert_eq!(aggregate.id, order_id);
+        assert_eq!(aggregate.status, OrderStatus::Confirmed);
+        assert_eq!(aggregate.version, 2);
+    }
+
+    #[test]
+    fn test_history_point_includes_events_up_to_it() {
+        let mut event = stored_event("OrderConfirmed", serde_json::json!({}));
+        event.sequence_number = 3;
+
+        assert!(HistoryPoint::Version(3).includes(&event));
+        assert!(!HistoryPoint::Version(2).includes(&event));
+        assert!(HistoryPoint::Timestamp(event.created_at).includes(&event));
+        assert!(
+            !HistoryPoint::Timestamp(event.created_at - chrono::Duration::seconds(1))
+                .includes(&event)
+        );
+    }
+
+    #[test]
+    fn test_price_set_by_creation_and_own_corrections() {
+        let product_id = Uuid::new_v4();
+        let correction = |product_id: Uuid| {
+            stored_event(
+                "OrderItemPriceCorrected",
+                serde_json::json!({ "product_id": product_id }),
+            )
+        };
+
+        assert!(sets_price_of(&stored_event("OrderCreated", serde_json::json!({})), product_id));
+        assert!(sets_price_of(&correction(product_id), product_id));
+        assert!(!sets_price_of(&correction(Uuid::new_v4()), product_id));
+        assert!(!sets_price_of(
+            &stored_event("OrderConfirmed", serde_json::json!({})),
+            product_id
+        ));
+    }
+
+    #[test]
+    fn test_apply_event_rejects_malformed_payload() {
+        let mut aggregate = OrderAggregate::default();
+        let result = apply_event(
+            &mut aggregate,
+            stored_event("OrderCreated", serde_json::json!({"bogus": true})),
+        );
+        assert!(matches!(result, Err(EventStoreError::SerializationError(_))));
+    }
+}
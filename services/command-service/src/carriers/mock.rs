@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::{
+    CarrierError, CarrierGateway, Shipment, ShipmentRequest, TrackingStatus, TrackingUpdate,
+};
+
+/// Simulated carrier for development and tests
+///
+/// Every shipment is delivered `delivery_after` after the carrier first hears of it,
+/// whether it booked the shipment itself or is first asked about its tracking number.
+pub struct MockCarrierGateway {
+    carrier: String,
+    delivery_after: Duration,
+    first_seen: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl MockCarrierGateway {
+    pub fn new(carrier: impl Into<String>, delivery_after: Duration) -> Self {
+        Self {
+            carrier: carrier.into(),
+            delivery_after,
+            first_seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn first_seen(&self, tracking_number: &str) -> DateTime<Utc> {
+        *self
+            .first_seen
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(tracking_number.to_string())
+            .or_insert_with(Utc::now)
+    }
+}
+
+#[async_trait]
+impl CarrierGateway for MockCarrierGateway {
+    fn carrier(&self) -> &str {
+        &self.carrier
+    }
+
+    /// Tracking numbers derive from the order, so booking again returns the same one
+    async fn create_shipment(&self, request: &ShipmentRequest) -> Result<Shipment, CarrierError> {
+        let tracking_number = format!(
+            "MOCK{}",
+            request.order_id.simple().to_string()[..12].to_uppercase()
+        );
+        self.first_seen(&tracking_number);
+        Ok(Shipment {
+            carrier: self.carrier.clone(),
+            tracking_number,
+        })
+    }
+
+    async fn tracking_status(
+        &self,
+        tracking_number: &str,
+    ) -> Result<Option<TrackingUpdate>, CarrierError> {
+        let shipped_at = self.first_seen(tracking_number);
+        let delivered_at = shipped_at
+            + chrono::Duration::from_std(self.delivery_after).unwrap_or(chrono::Duration::zero());
+
+        let update = if Utc::now() >= delivered_at {
+            TrackingUpdate {
+                tracking_number: tracking_number.to_string(),
+                status: TrackingStatus::Delivered,
+                occurred_at: delivered_at,
+            }
+        } else {
+            TrackingUpdate {
+                tracking_number: tracking_number.to_string(),
+                status: TrackingStatus::InTransit,
+                occurred_at: shipped_at,
+            }
+        };
+        Ok(Some(update))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_mock_shipment_is_delivered_after_delay() {
+        let gateway = MockCarrierGateway::new("Mock", Duration::from_millis(50));
+        let shipment = gateway
+            .create_shipment(&ShipmentRequest {
+                order_id: Uuid::new_v4(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(shipment.carrier, "Mock");
+        assert!(shipment.tracking_number.starts_with("MOCK"));
+
+        let update = gateway
+            .tracking_status(&shipment.tracking_number)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(update.status, TrackingStatus::InTransit);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let update = gateway
+            .tracking_status(&shipment.tracking_number)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(update.status, TrackingStatus::Delivered);
+    }
+
+    #[test]
+    fn test_mock_does_not_accept_webhooks() {
+        let gateway = MockCarrierGateway::new("Mock", Duration::ZERO);
+        let result = gateway.receive(TrackingUpdate {
+            tracking_number: "MOCK1".to_string(),
+            status: TrackingStatus::Delivered,
+            occurred_at: Utc::now(),
+        });
+        assert!(matches!(result, Err(CarrierError::Unsupported { .. })));
+    }
+}
@@ -0,0 +1,173 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+mod mock;
+mod webhook;
+
+pub use mock::MockCarrierGateway;
+pub use webhook::WebhookCarrierGateway;
+
+#[derive(Debug, Error)]
+pub enum CarrierError {
+    #[error("Carrier {carrier} does not support {operation}")]
+    Unsupported {
+        carrier: String,
+        operation: &'static str,
+    },
+}
+
+/// Shipment to book with a carrier
+#[derive(Debug, Clone)]
+pub struct ShipmentRequest {
+    pub order_id: Uuid,
+}
+
+/// Shipment booked with a carrier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shipment {
+    pub carrier: String,
+    pub tracking_number: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TrackingStatus {
+    InTransit,
+    OutForDelivery,
+    Delivered,
+    /// Delayed, damaged, returned or otherwise needing attention
+    Exception,
+}
+
+impl TrackingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrackingStatus::InTransit => "IN_TRANSIT",
+            TrackingStatus::OutForDelivery => "OUT_FOR_DELIVERY",
+            TrackingStatus::Delivered => "DELIVERED",
+            TrackingStatus::Exception => "EXCEPTION",
+        }
+    }
+}
+
+/// Latest tracking event the carrier reported for a shipment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackingUpdate {
+    pub tracking_number: String,
+    pub status: TrackingStatus,
+    /// When the carrier recorded the event; defaults to when it was received
+    #[serde(default = "Utc::now")]
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// A shipping carrier's API
+#[async_trait]
+pub trait CarrierGateway: Send + Sync {
+    /// Carrier name as given in ship commands, e.g. `UPS`
+    fn carrier(&self) -> &str;
+
+    async fn create_shipment(&self, request: &ShipmentRequest) -> Result<Shipment, CarrierError>;
+
+    /// Latest tracking event for a shipment; `None` while the carrier has none yet
+    async fn tracking_status(
+        &self,
+        tracking_number: &str,
+    ) -> Result<Option<TrackingUpdate>, CarrierError>;
+
+    /// Accept an update the carrier pushed to us, for carriers that report via webhooks
+    fn receive(&self, _update: TrackingUpdate) -> Result<(), CarrierError> {
+        Err(CarrierError::Unsupported {
+            carrier: self.carrier().to_string(),
+            operation: "webhooks",
+        })
+    }
+}
+
+/// Carrier gateways by case-insensitive carrier name
+#[derive(Clone, Default)]
+pub struct CarrierRegistry {
+    gateways: HashMap<String, Arc<dyn CarrierGateway>>,
+}
+
+impl CarrierRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_gateway(mut self, gateway: Arc<dyn CarrierGateway>) -> Self {
+        self.gateways
+            .insert(gateway.carrier().to_lowercase(), gateway);
+        self
+    }
+
+    pub fn get(&self, carrier: &str) -> Option<&Arc<dyn CarrierGateway>> {
+        self.gateways.get(&carrier.to_lowercase())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gateways.is_empty()
+    }
+
+    /// Registry from comma-separated carrier names: `MOCK_CARRIERS` are simulated and
+    /// deliver after `mock_delivery_after`, `WEBHOOK_CARRIERS` push tracking updates
+    pub fn from_names(
+        mock_carriers: &str,
+        webhook_carriers: &str,
+        mock_delivery_after: std::time::Duration,
+    ) -> Self {
+        let names = |list: &str| {
+            list.split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        let mut registry = Self::new();
+        for carrier in names(mock_carriers) {
+            registry = registry.with_gateway(Arc::new(MockCarrierGateway::new(
+                carrier,
+                mock_delivery_after,
+            )));
+        }
+        for carrier in names(webhook_carriers) {
+            registry = registry.with_gateway(Arc::new(WebhookCarrierGateway::new(carrier)));
+        }
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_registry_lookup_ignores_case() {
+        let registry = CarrierRegistry::from_names("Mock", " UPS , FedEx,", Duration::from_secs(1));
+
+        assert_eq!(registry.get("MOCK").unwrap().carrier(), "Mock");
+        assert_eq!(registry.get("ups").unwrap().carrier(), "UPS");
+        assert!(registry.get("fedex").is_some());
+        assert!(registry.get("DHL").is_none());
+        assert!(CarrierRegistry::from_names("", "", Duration::from_secs(1)).is_empty());
+    }
+
+    #[test]
+    fn test_tracking_update_deserialization() {
+        let update: TrackingUpdate =
+            serde_json::from_str(r#"{"tracking_number": "1Z999", "status": "OUT_FOR_DELIVERY"}"#)
+                .unwrap();
+        assert_eq!(update.status, TrackingStatus::OutForDelivery);
+        assert_eq!(update.status.as_str(), "OUT_FOR_DELIVERY");
+        assert!(serde_json::from_str::<TrackingUpdate>(
+            r#"{"tracking_number": "1Z999", "status": "LOST"}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use super::{CarrierError, CarrierGateway, Shipment, ShipmentRequest, TrackingUpdate};
+
+/// Carrier that pushes tracking updates to our webhook instead of being polled
+///
+/// Shipments are booked with the carrier's own tools. The latest update received
+/// per tracking number is kept in memory, so a delivery that failed to apply when
+/// it arrived is retried by the next poll on this instance.
+pub struct WebhookCarrierGateway {
+    carrier: String,
+    updates: RwLock<HashMap<String, TrackingUpdate>>,
+}
+
+impl WebhookCarrierGateway {
+    pub fn new(carrier: impl Into<String>) -> Self {
+        Self {
+            carrier: carrier.into(),
+            updates: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl CarrierGateway for WebhookCarrierGateway {
+    fn carrier(&self) -> &str {
+        &self.carrier
+    }
+
+    async fn create_shipment(&self, _request: &ShipmentRequest) -> Result<Shipment, CarrierError> {
+        Err(CarrierError::Unsupported {
+            carrier: self.carrier.clone(),
+            operation: "booking shipments",
+        })
+    }
+
+    async fn tracking_status(
+        &self,
+        tracking_number: &str,
+    ) -> Result<Option<TrackingUpdate>, CarrierError> {
+        Ok(self
+            .updates
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(tracking_number)
+            .cloned())
+    }
+
+    /// Keep the update unless a later one was already received
+    fn receive(&self, update: TrackingUpdate) -> Result<(), CarrierError> {
+        let mut updates = self.updates.write().unwrap_or_else(|e| e.into_inner());
+        let newer = updates
+            .get(&update.tracking_number)
+            .is_none_or(|current| current.occurred_at <= update.occurred_at);
+        if newer {
+            updates.insert(update.tracking_number.clone(), update);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::carriers::TrackingStatus;
+    use chrono::{Duration, Utc};
+
+    #[tokio::test]
+    async fn test_latest_received_update_wins() {
+        let gateway = WebhookCarrierGateway::new("UPS");
+        assert_eq!(gateway.tracking_status("1Z999").await.unwrap(), None);
+
+        let now = Utc::now();
+        let update = |status, occurred_at| TrackingUpdate {
+            tracking_number: "1Z999".to_string(),
+            status,
+            occurred_at,
+        };
+        gateway
+            .receive(update(TrackingStatus::Delivered, now))
+            .unwrap();
+        // Arrives late but happened earlier
+        gateway
+            .receive(update(TrackingStatus::InTransit, now - Duration::hours(1)))
+            .unwrap();
+
+        let latest = gateway.tracking_status("1Z999").await.unwrap().unwrap();
+        assert_eq!(latest.status, TrackingStatus::Delivered);
+    }
+}
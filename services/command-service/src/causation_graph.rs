@@ -0,0 +1,384 @@
+use chrono::{DateTime, Utc};
+use event_store::Event;
+use saga::SagaState;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt::Write;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Command,
+    /// Event read from the event store
+    Event,
+    Saga,
+    SagaStep,
+    /// Event a saga step published, known only from the step's result
+    EmittedEvent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// Command to the events it appended
+    Produced,
+    /// Event to the events whose causation ID points at it
+    Caused,
+    /// Event to the saga it started
+    Started,
+    /// Saga to its first step, and each step to the next
+    Next,
+    /// Saga or step to an event it published
+    Emitted,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub kind: NodeKind,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+/// Command → events → saga steps → emitted events for one correlation ID
+#[derive(Debug, Clone, Serialize)]
+pub struct CausationGraph {
+    pub correlation_id: Uuid,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+fn event_node(id: Uuid) -> String {
+    format!("event:{}", id)
+}
+
+fn saga_node(id: Uuid) -> String {
+    format!("saga:{}", id)
+}
+
+impl CausationGraph {
+    /// Build the graph from the correlation's stored events (oldest first) and sagas
+    ///
+    /// Events are attributed to the command they carry the ID of, or else to the
+    /// event or saga their causation ID names; anything else is caused by a command
+    /// identified only by that causation ID. A saga hangs off the latest event
+    /// before it started, preferring events of the aggregate it is keyed by.
+    pub fn build(correlation_id: Uuid, events: &[Event], sagas: &[SagaState]) -> Self {
+        let mut graph = Self {
+            correlation_id,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+        let mut seen = HashSet::new();
+        let event_ids: HashSet<Uuid> = events.iter().map(|event| event.event_id).collect();
+        let saga_ids: HashSet<Uuid> = sagas.iter().map(|saga| saga.saga_id).collect();
+
+        for event in events {
+            let id = event_node(event.event_id);
+            graph.add_node(
+                &mut seen,
+                GraphNode {
+                    id: id.clone(),
+                    kind: NodeKind::Event,
+                    label: format!(
+                        "{} v{}\n{} {}",
+                        event.event_type,
+                        event.sequence_number,
+                        event.aggregate_type,
+                        event.aggregate_id
+                    ),
+                    at: Some(event.created_at),
+                },
+            );
+
+            let cause = event.causation_id();
+            let (from, kind) = match event.command_id() {
+                Some(command_id) => (
+                    graph.command(&mut seen, command_id, event),
+                    EdgeKind::Produced,
+                ),
+                None if event_ids.contains(&cause) && cause != event.event_id => {
+                    (event_node(cause), EdgeKind::Caused)
+                }
+                None if saga_ids.contains(&cause) => (saga_node(cause), EdgeKind::Emitted),
+                None => (graph.command(&mut seen, cause, event), EdgeKind::Produced),
+            };
+            graph.add_edge(from, id, kind);
+        }
+
+        for saga in sagas {
+            graph.add_saga(&mut seen, saga, events);
+        }
+
+        graph
+    }
+
+    fn command(&mut self, seen: &mut HashSet<String>, command_id: Uuid, event: &Event) -> String {
+        let id = format!("command:{}", command_id);
+        self.add_node(
+            seen,
+            GraphNode {
+                id: id.clone(),
+                kind: NodeKind::Command,
+                label: format!("Command {}", command_id),
+                at: Some(event.created_at),
+            },
+        );
+        id
+    }
+
+    fn add_saga(&mut self, seen: &mut HashSet<String>, saga: &SagaState, events: &[Event]) {
+        let id = saga_node(saga.saga_id);
+        self.add_node(
+            seen,
+            GraphNode {
+                id: id.clone(),
+                kind: NodeKind::Saga,
+                label: format!("{} ({})", saga.saga_type, saga.status),
+                at: Some(saga.created_at),
+            },
+        );
+
+        let before_start = || {
+            events
+                .iter()
+                .rev()
+                .filter(|event| event.created_at <= saga.created_at)
+        };
+        let trigger = before_start()
+            .find(|event| {
+                saga.correlation_key.as_deref() == Some(event.aggregate_id.to_string().as_str())
+            })
+            .or_else(|| before_start().next());
+        if let Some(trigger) = trigger {
+            self.add_edge(event_node(trigger.event_id), id.clone(), EdgeKind::Started);
+        }
+
+        let mut previous = id.clone();
+        for (index, step) in saga.steps.iter().enumerate() {
+            let step_id = format!("{}:step:{}", id, index);
+            self.add_node(
+                seen,
+                GraphNode {
+                    id: step_id.clone(),
+                    kind: NodeKind::SagaStep,
+                    label: format!("{} ({})", step.name, step.status),
+                    at: None,
+                },
+            );
+            self.add_edge(previous, step_id.clone(), EdgeKind::Next);
+
+            if let Some(emitted) = step.emitted_event() {
+                let emitted_id = event_node(emitted.event_id);
+                self.add_node(
+                    seen,
+                    GraphNode {
+                        id: emitted_id.clone(),
+                        kind: NodeKind::EmittedEvent,
+                        label: format!("{}\n{}", emitted.event_type, emitted.aggregate_id),
+                        at: None,
+                    },
+                );
+                self.add_edge(step_id.clone(), emitted_id, EdgeKind::Emitted);
+            }
+            previous = step_id;
+        }
+    }
+
+    fn add_node(&mut self, seen: &mut HashSet<String>, node: GraphNode) {
+        if seen.insert(node.id.clone()) {
+            self.nodes.push(node);
+        }
+    }
+
+    fn add_edge(&mut self, from: String, to: String, kind: EdgeKind) {
+        let edge = GraphEdge { from, to, kind };
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    /// Render as a Graphviz DOT digraph, laid out left to right
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph causation {\n    rankdir=LR;\n");
+        let _ = writeln!(dot, "    label=\"correlation {}\";", self.correlation_id);
+
+        for node in &self.nodes {
+            let shape = match node.kind {
+                NodeKind::Command => "ellipse",
+                NodeKind::Event => "box",
+                NodeKind::Saga => "hexagon",
+                NodeKind::SagaStep => "box, style=rounded",
+                NodeKind::EmittedEvent => "box, style=dashed",
+            };
+            let _ = writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\", shape={}];",
+                escape(&node.id),
+                escape(&node.label),
+                shape
+            );
+        }
+        for edge in &self.edges {
+            let label = serde_json::to_value(edge.kind)
+                .ok()
+                .and_then(|kind| kind.as_str().map(String::from))
+                .unwrap_or_default();
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                escape(&edge.from),
+                escape(&edge.to),
+                label
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Escape a string for a quoted DOT identifier or label
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::events::EventMetadata;
+    use saga::{EmittedEvent, SagaStep, EMITTED_EVENT_KEY};
+    use serde_json::json;
+
+    fn event(
+        aggregate_id: Uuid,
+        event_type: &str,
+        correlation_id: Uuid,
+        causation_id: Uuid,
+    ) -> Event {
+        let mut metadata = EventMetadata::with_correlation(correlation_id);
+        metadata.causation_id = causation_id;
+        let mut event = Event::new(
+            aggregate_id,
+            "Order".to_string(),
+            event_type.to_string(),
+            1,
+            json!({}),
+            metadata,
+        );
+        event.sequence_number = 1;
+        event
+    }
+
+    fn has_edge(graph: &CausationGraph, from: &str, to: &str, kind: EdgeKind) -> bool {
+        graph.edges.contains(&GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+        })
+    }
+
+    #[test]
+    fn test_command_to_saga_steps_to_emitted_events() {
+        let correlation_id = Uuid::new_v4();
+        let command_id = Uuid::new_v4();
+        let order_id = Uuid::new_v4();
+        let mut created = event(order_id, "OrderCreated", correlation_id, correlation_id);
+        created.metadata.command_id = Some(command_id);
+
+        let emitted = EmittedEvent {
+            event_id: Uuid::new_v4(),
+            event_type: "InventoryReserved".to_string(),
+            aggregate_id: order_id,
+        };
+        let mut reserve = SagaStep::new("reserve_inventory".to_string(), 3);
+        reserve.mark_completed(json!({ EMITTED_EVENT_KEY: emitted }));
+        let mut saga = SagaState::new(
+            Uuid::new_v4(),
+            "OrderProcessingSaga".to_string(),
+            vec![reserve, SagaStep::new("process_payment".to_string(), 3)],
+            json!({ "correlation_id": correlation_id }),
+        );
+        saga.correlation_key = Some(order_id.to_string());
+
+        let graph = CausationGraph::build(
+            correlation_id,
+            std::slice::from_ref(&created),
+            std::slice::from_ref(&saga),
+        );
+
+        let command = format!("command:{}", command_id);
+        let created_node = event_node(created.event_id);
+        let saga_id = saga_node(saga.saga_id);
+        let step_0 = format!("{}:step:0", saga_id);
+        let step_1 = format!("{}:step:1", saga_id);
+        assert!(has_edge(
+            &graph,
+            &command,
+            &created_node,
+            EdgeKind::Produced
+        ));
+        assert!(has_edge(&graph, &created_node, &saga_id, EdgeKind::Started));
+        assert!(has_edge(&graph, &saga_id, &step_0, EdgeKind::Next));
+        assert!(has_edge(&graph, &step_0, &step_1, EdgeKind::Next));
+        assert!(has_edge(
+            &graph,
+            &step_0,
+            &event_node(emitted.event_id),
+            EdgeKind::Emitted
+        ));
+        assert_eq!(graph.nodes.len(), 6);
+    }
+
+    #[test]
+    fn test_event_caused_by_another_event() {
+        let correlation_id = Uuid::new_v4();
+        let order_id = Uuid::new_v4();
+        let first = event(order_id, "OrderCreated", correlation_id, correlation_id);
+        let second = event(order_id, "OrderConfirmed", correlation_id, first.event_id);
+
+        let graph = CausationGraph::build(correlation_id, &[first.clone(), second.clone()], &[]);
+
+        assert!(has_edge(
+            &graph,
+            &format!("command:{}", correlation_id),
+            &event_node(first.event_id),
+            EdgeKind::Produced
+        ));
+        assert!(has_edge(
+            &graph,
+            &event_node(first.event_id),
+            &event_node(second.event_id),
+            EdgeKind::Caused
+        ));
+    }
+
+    #[test]
+    fn test_dot_output_escapes_labels() {
+        let correlation_id = Uuid::new_v4();
+        let created = event(
+            Uuid::new_v4(),
+            "OrderCreated",
+            correlation_id,
+            correlation_id,
+        );
+
+        let dot = CausationGraph::build(correlation_id, &[created], &[]).to_dot();
+        assert!(dot.starts_with("digraph causation {"));
+        assert!(dot.contains("OrderCreated v1\\nOrder"));
+        assert!(dot.contains("[label=\"produced\"]"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+}
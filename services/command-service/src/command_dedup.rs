@@ -0,0 +1,217 @@
+use async_trait::async_trait;
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::StatusCode,
+    Json,
+};
+use common::metrics;
+use event_store::{Event, EventStore, EventStoreError};
+use serde::Deserialize;
+use thiserror::Error;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::handlers::errors::{ConflictDetails, ErrorResponse};
+
+/// Optional request body for commands that otherwise only take a path
+///
+/// An empty body means no command ID; a body that is not valid JSON for this type
+/// is rejected with 400 rather than treated as empty.
+#[derive(Debug, Default, Deserialize)]
+pub struct CommandIdRequest {
+    #[serde(default)]
+    pub command_id: Option<Uuid>,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequest<S> for CommandIdRequest {
+    type Rejection = (StatusCode, Json<ErrorResponse>);
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let body = Bytes::from_request(request, state)
+            .await
+            .map_err(|e| (e.status(), Json(ErrorResponse::new(e.body_text()))))?;
+        Self::parse(&body)
+    }
+}
+
+impl CommandIdRequest {
+    fn parse(body: &[u8]) -> Result<Self, (StatusCode, Json<ErrorResponse>)> {
+        if body.trim_ascii().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_json::from_slice(body).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new(format!("Invalid request body: {}", e))),
+            )
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DedupError {
+    #[error("Command ID {0} was already used for a different command")]
+    Mismatch(Uuid),
+
+    #[error("Failed to look up command: {0}")]
+    Store(#[from] EventStoreError),
+}
+
+impl DedupError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            DedupError::Mismatch(_) => StatusCode::CONFLICT,
+            DedupError::Store(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Find the event a command ID already produced, so its response can be replayed
+///
+/// This is backed by the event store rather than Redis, so a retried command is
+/// still recognised after the idempotency cache has been flushed. Reusing a
+/// command ID for a different command or order is rejected.
+pub async fn find_processed(
+    event_store: &dyn EventStore,
+    command_id: Option<Uuid>,
+    event_type: &str,
+    order_id: Option<Uuid>,
+) -> Result<Option<Event>, DedupError> {
+    let Some(command_id) = command_id else {
+        return Ok(None);
+    };
+
+    let events = event_store.find_events_by_command_id(command_id).await?;
+    let Some(event) = events.into_iter().next() else {
+        return Ok(None);
+    };
+
+    let same_order = order_id.is_none_or(|id| id == event.aggregate_id);
+    if event.event_type != event_type || !same_order {
+        return Err(DedupError::Mismatch(command_id));
+    }
+
+    Ok(Some(event))
+}
+
+/// Replay the response to a command that was already processed
+///
+/// Looks the command up with [`find_processed`] and builds the response from the
+/// event it produced with `replay`. `Ok(None)` means the command is new and should
+/// be handled.
+pub async fn replay_processed<T>(
+    event_store: &dyn EventStore,
+    command_id: Option<Uuid>,
+    event_type: &str,
+    order_id: Option<Uuid>,
+    replay: impl FnOnce(Event) -> Result<T, (StatusCode, Json<ErrorResponse>)>,
+) -> Result<Option<T>, (StatusCode, Json<ErrorResponse>)> {
+    match find_processed(event_store, command_id, event_type, order_id).await {
+        Ok(Some(event)) => {
+            info!("Command already processed, replaying response: {:?}", command_id);
+            replay(event).map(Some)
+        }
+        Ok(None) => Ok(None),
+        Err(e) => {
+            error!("Command deduplication failed: {}", e);
+            Err((e.status_code(), Json(ErrorResponse::new(e.to_string()))))
+        }
+    }
+}
+
+/// HTTP status for a failed append
+pub fn append_error_status(error: &EventStoreError) -> StatusCode {
+    match error {
+        EventStoreError::DuplicateCommand(_) => StatusCode::CONFLICT,
+        EventStoreError::ConcurrencyConflict { .. } => StatusCode::CONFLICT,
+        EventStoreError::Overloaded(_) => StatusCode::SERVICE_UNAVAILABLE,
+        EventStoreError::DeadlineExceeded(_) => StatusCode::GATEWAY_TIMEOUT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Error response for a failed append of an `event_type` event
+///
+/// A concurrency conflict carries the events appended since the command loaded the
+/// aggregate, so a client can tell "already confirmed by someone else" from a race
+/// worth retrying.
+pub fn append_error(error: EventStoreError, event_type: &str) -> (StatusCode, Json<ErrorResponse>) {
+    let status = append_error_status(&error);
+    let message = format!("Failed to persist event: {}", error);
+
+    match error {
+        EventStoreError::ConcurrencyConflict {
+            expected,
+            actual,
+            conflicting,
+        } => {
+            let conflict = ConflictDetails::new(event_type, expected, actual, conflicting);
+            metrics::record_command_conflict(event_type, conflict.kind.as_str());
+            (status, Json(ErrorResponse::conflict(message, conflict)))
+        }
+        _ => (status, Json(ErrorResponse::new(message))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::bulkhead::BulkheadFull;
+    use common::deadline::DeadlineExceeded;
+
+    #[test]
+    fn test_command_id_request_defaults_to_none() {
+        let request: CommandIdRequest = serde_json::from_str("{}").unwrap();
+        assert!(request.command_id.is_none());
+    }
+
+    #[test]
+    fn test_command_id_request_body() {
+        assert!(CommandIdRequest::parse(b"").unwrap().command_id.is_none());
+
+        let command_id = Uuid::new_v4();
+        let body = format!(r#"{{"command_id": "{}"}}"#, command_id);
+        let request = CommandIdRequest::parse(body.as_bytes()).unwrap();
+        assert_eq!(request.command_id, Some(command_id));
+
+        let (status, _) = CommandIdRequest::parse(br#"{"command_id": "not-a-uuid"}"#).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = CommandIdRequest::parse(b"{").unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_status_codes() {
+        let command_id = Uuid::new_v4();
+        assert_eq!(DedupError::Mismatch(command_id).status_code(), StatusCode::CONFLICT);
+        assert_eq!(
+            append_error_status(&EventStoreError::DuplicateCommand(command_id)),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            append_error_status(&EventStoreError::AggregateNotFound(command_id)),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(
+            append_error_status(&EventStoreError::ConcurrencyConflict {
+                expected: 1,
+                actual: 2,
+                conflicting: vec![],
+            }),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            append_error_status(&EventStoreError::Overloaded(BulkheadFull {
+                name: "event-store-append".to_string(),
+                max_concurrent: 32,
+            })),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            append_error_status(&EventStoreError::DeadlineExceeded(DeadlineExceeded)),
+            StatusCode::GATEWAY_TIMEOUT
+        );
+    }
+}
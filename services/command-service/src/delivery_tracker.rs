@@ -0,0 +1,358 @@
+use axum::Json;
+use chrono::{DateTime, Utc};
+use common::distributed_lock::{DistributedLock, PgAdvisoryLock};
+use domain::commands::order_commands::DeliverOrderCommand;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::carriers::{CarrierRegistry, TrackingStatus, TrackingUpdate};
+use crate::handlers::deliver_order;
+use crate::state::AppState;
+
+/// Pending shipments checked with their carrier per poll
+const POLL_BATCH_SIZE: i64 = 100;
+
+#[derive(Debug, Error)]
+pub enum DeliveryTrackerError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Shipment whose delivery is being followed
+#[derive(Debug, Clone)]
+struct TrackedShipment {
+    order_id: Uuid,
+    carrier: String,
+    tracking_number: String,
+}
+
+/// What became of a shipment after a tracking update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShipmentOutcome {
+    /// Not delivered yet, or delivering failed in a way worth retrying
+    Pending,
+    Delivered,
+    /// The order can no longer be delivered, e.g. it was cancelled or already delivered
+    Closed,
+}
+
+impl ShipmentOutcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ShipmentOutcome::Pending => "PENDING",
+            ShipmentOutcome::Delivered => "DELIVERED",
+            ShipmentOutcome::Closed => "CLOSED",
+        }
+    }
+}
+
+/// Process manager turning carrier tracking updates into deliver commands
+///
+/// Shipped orders are recorded in the `shipments` table. Carriers are polled for
+/// pending shipments, and webhook carriers push updates through `ingest`; once a
+/// carrier reports a shipment delivered, the order is delivered like a manual
+/// `PUT /orders/:id/deliver` would.
+pub struct DeliveryTracker {
+    pool: PgPool,
+    carriers: CarrierRegistry,
+}
+
+impl DeliveryTracker {
+    pub fn new(pool: PgPool, carriers: CarrierRegistry) -> Self {
+        Self { pool, carriers }
+    }
+
+    pub fn carriers(&self) -> &CarrierRegistry {
+        &self.carriers
+    }
+
+    /// Start following a shipped order; returns `false` when its carrier has no gateway
+    pub async fn track(
+        &self,
+        order_id: Uuid,
+        carrier: &str,
+        tracking_number: &str,
+    ) -> Result<bool, DeliveryTrackerError> {
+        if self.carriers.get(carrier).is_none() {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO shipments (order_id, carrier, tracking_number)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (order_id) DO UPDATE SET
+                carrier = EXCLUDED.carrier,
+                tracking_number = EXCLUDED.tracking_number,
+                tracking_status = NULL,
+                status = 'PENDING',
+                last_checked_at = NULL,
+                delivered_at = NULL,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(order_id)
+        .bind(carrier.to_lowercase())
+        .bind(tracking_number)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Pending shipments, least recently checked first
+    async fn due(&self, limit: i64) -> Result<Vec<TrackedShipment>, DeliveryTrackerError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT order_id, carrier, tracking_number
+            FROM shipments
+            WHERE status = 'PENDING'
+            ORDER BY last_checked_at NULLS FIRST
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(shipment_from_row).collect())
+    }
+
+    async fn find_pending(
+        &self,
+        carrier: &str,
+        tracking_number: &str,
+    ) -> Result<Option<TrackedShipment>, DeliveryTrackerError> {
+        let row = sqlx::query(
+            r#"
+            SELECT order_id, carrier, tracking_number
+            FROM shipments
+            WHERE carrier = $1 AND tracking_number = $2 AND status = 'PENDING'
+            "#,
+        )
+        .bind(carrier.to_lowercase())
+        .bind(tracking_number)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(shipment_from_row))
+    }
+
+    async fn record(
+        &self,
+        order_id: Uuid,
+        tracking_status: Option<TrackingStatus>,
+        outcome: ShipmentOutcome,
+        delivered_at: Option<DateTime<Utc>>,
+    ) -> Result<(), DeliveryTrackerError> {
+        sqlx::query(
+            r#"
+            UPDATE shipments SET
+                tracking_status = COALESCE($2, tracking_status),
+                status = $3,
+                delivered_at = COALESCE($4, delivered_at),
+                last_checked_at = NOW(),
+                updated_at = NOW()
+            WHERE order_id = $1
+            "#,
+        )
+        .bind(order_id)
+        .bind(tracking_status.map(|status| status.as_str()))
+        .bind(outcome.as_str())
+        .bind(delivered_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn shipment_from_row(row: &sqlx::postgres::PgRow) -> TrackedShipment {
+    TrackedShipment {
+        order_id: row.get("order_id"),
+        carrier: row.get("carrier"),
+        tracking_number: row.get("tracking_number"),
+    }
+}
+
+/// Check a batch of pending shipments with their carriers; returns how many were delivered
+pub async fn poll_once(
+    state: &AppState,
+    tracker: &DeliveryTracker,
+) -> Result<usize, DeliveryTrackerError> {
+    let mut delivered = 0;
+
+    for shipment in tracker.due(POLL_BATCH_SIZE).await? {
+        let Some(gateway) = tracker.carriers.get(&shipment.carrier) else {
+            // Carrier was removed from the configuration; look again next time round
+            tracker
+                .record(shipment.order_id, None, ShipmentOutcome::Pending, None)
+                .await?;
+            continue;
+        };
+
+        match gateway.tracking_status(&shipment.tracking_number).await {
+            Ok(Some(update)) => {
+                if apply(state, tracker, &shipment, &update).await? == ShipmentOutcome::Delivered {
+                    delivered += 1;
+                }
+            }
+            Ok(None) => {
+                tracker
+                    .record(shipment.order_id, None, ShipmentOutcome::Pending, None)
+                    .await?;
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to fetch tracking status for order {} from {}: {}",
+                    shipment.order_id, shipment.carrier, e
+                );
+                tracker
+                    .record(shipment.order_id, None, ShipmentOutcome::Pending, None)
+                    .await?;
+            }
+        }
+    }
+
+    Ok(delivered)
+}
+
+/// Apply an update a carrier pushed; `None` when no pending shipment has its tracking number
+pub async fn ingest(
+    state: &AppState,
+    tracker: &DeliveryTracker,
+    carrier: &str,
+    update: &TrackingUpdate,
+) -> Result<Option<(Uuid, ShipmentOutcome)>, DeliveryTrackerError> {
+    let Some(shipment) = tracker
+        .find_pending(carrier, &update.tracking_number)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let outcome = apply(state, tracker, &shipment, update).await?;
+    Ok(Some((shipment.order_id, outcome)))
+}
+
+async fn apply(
+    state: &AppState,
+    tracker: &DeliveryTracker,
+    shipment: &TrackedShipment,
+    update: &TrackingUpdate,
+) -> Result<ShipmentOutcome, DeliveryTrackerError> {
+    if update.status != TrackingStatus::Delivered {
+        tracker
+            .record(
+                shipment.order_id,
+                Some(update.status),
+                ShipmentOutcome::Pending,
+                None,
+            )
+            .await?;
+        return Ok(ShipmentOutcome::Pending);
+    }
+
+    let cmd = DeliverOrderCommand {
+        order_id: shipment.order_id,
+        command_id: None,
+    };
+    let (outcome, delivered_at) = match deliver_order::execute(state, cmd).await {
+        Ok(_) => {
+            info!(
+                "Order {} delivered by {} tracking {}",
+                shipment.order_id, shipment.carrier, shipment.tracking_number
+            );
+            (ShipmentOutcome::Delivered, Some(update.occurred_at))
+        }
+        Err((status, Json(e))) => {
+            let outcome = outcome_of_failure(status);
+            if outcome == ShipmentOutcome::Closed {
+                warn!(
+                    "Order {} reported delivered but could not be delivered, no longer tracking it: {}",
+                    shipment.order_id, e.error
+                );
+            } else {
+                error!(
+                    "Failed to deliver order {}, will retry: {}",
+                    shipment.order_id, e.error
+                );
+            }
+            (outcome, None)
+        }
+    };
+
+    tracker
+        .record(
+            shipment.order_id,
+            Some(update.status),
+            outcome,
+            delivered_at,
+        )
+        .await?;
+    Ok(outcome)
+}
+
+/// Retry conflicts and server errors; any other rejection will not change on retry
+fn outcome_of_failure(status: axum::http::StatusCode) -> ShipmentOutcome {
+    if status.is_client_error() && status != axum::http::StatusCode::CONFLICT {
+        ShipmentOutcome::Closed
+    } else {
+        ShipmentOutcome::Pending
+    }
+}
+
+/// Poll carriers every `interval`, one replica at a time
+pub fn spawn(state: AppState, tracker: Arc<DeliveryTracker>, interval: Duration) {
+    let lock = PgAdvisoryLock::new(tracker.pool.clone());
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            let task = Box::pin(async {
+                match poll_once(&state, &tracker).await {
+                    Ok(0) => {}
+                    Ok(delivered) => info!("Delivered {} orders from carrier tracking", delivered),
+                    Err(e) => error!("Failed to poll carrier tracking: {}", e),
+                }
+            });
+            if let Err(e) = lock.run_exclusive("delivery-tracker", task).await {
+                warn!("Failed to acquire delivery tracker lock: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    #[test]
+    fn test_only_lasting_rejections_close_a_shipment() {
+        assert_eq!(
+            outcome_of_failure(StatusCode::BAD_REQUEST),
+            ShipmentOutcome::Closed
+        );
+        assert_eq!(
+            outcome_of_failure(StatusCode::NOT_FOUND),
+            ShipmentOutcome::Closed
+        );
+        assert_eq!(
+            outcome_of_failure(StatusCode::CONFLICT),
+            ShipmentOutcome::Pending
+        );
+        assert_eq!(
+            outcome_of_failure(StatusCode::INTERNAL_SERVER_ERROR),
+            ShipmentOutcome::Pending
+        );
+        assert_eq!(
+            outcome_of_failure(StatusCode::SERVICE_UNAVAILABLE),
+            ShipmentOutcome::Pending
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use domain::aggregates::order::OrderAggregate;
+use domain::events::order_events::OrderItem;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::aggregate_cache::AggregateCache;
+use crate::aggregate_loader::{load_aggregate_at, load_order, HistoryPoint};
+use crate::handlers::errors::ErrorResponse;
+use crate::state::AppState;
+
+/// An order aggregate as the command side sees it
+#[derive(Debug, Serialize)]
+pub struct OrderAggregateResponse {
+    pub aggregate_id: Uuid,
+    pub aggregate_type: &'static str,
+    /// Stream version of the last event applied
+    pub version: i64,
+    pub customer_id: Uuid,
+    pub order_number: String,
+    pub status: &'static str,
+    pub items: Vec<OrderItem>,
+    pub total_amount: f64,
+    /// Version of the copy in this instance's aggregate cache, if it holds one
+    pub cached_version: Option<i64>,
+}
+
+impl OrderAggregateResponse {
+    fn new(aggregate: OrderAggregate, version: i64, cached_version: Option<i64>) -> Self {
+        Self {
+            aggregate_id: aggregate.id,
+            aggregate_type: "Order",
+            version,
+            customer_id: aggregate.customer_id,
+            status: aggregate.status.as_str(),
+            order_number: aggregate.order_number,
+            items: aggregate.items,
+            total_amount: aggregate.total_amount,
+            cached_version,
+        }
+    }
+}
+
+/// Admin: rehydrate an order from its events and return its current state
+///
+/// Always reads the whole stream from the event store rather than starting from the
+/// aggregate cache, so the result is exactly what the next command would act on,
+/// unlike the eventually consistent read model.
+pub async fn get_order(
+    State(state): State<AppState>,
+    Path(order_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<OrderAggregateResponse>), (StatusCode, Json<ErrorResponse>)> {
+    info!("Rehydrating order aggregate: {}", order_id);
+
+    let internal = |e: event_store::EventStoreError| {
+        error!("Failed to rehydrate order {}: {}", order_id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!("Failed to load events: {}", e))),
+        )
+    };
+
+    let cached_version = state
+        .aggregate_cache
+        .get(order_id)
+        .map(|(_, version)| version);
+
+    // A throwaway cache, so nothing cached influences the result
+    let loaded = load_order(state.event_store.as_ref(), &AggregateCache::new(1), order_id)
+        .await
+        .map_err(internal)?;
+
+    match loaded {
+        Some((aggregate, version)) => Ok((
+            StatusCode::OK,
+            Json(OrderAggregateResponse::new(aggregate, version, cached_version)),
+        )),
+        None => {
+            let deleted = state
+                .event_store
+                .is_stream_deleted(order_id)
+                .await
+                .map_err(internal)?;
+            let (status, message) = if deleted {
+                (StatusCode::GONE, format!("Order stream was deleted: {}", order_id))
+            } else {
+                (StatusCode::NOT_FOUND, format!("Order not found: {}", order_id))
+            };
+            Err((status, Json(ErrorResponse::new(message))))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryParams {
+    /// Stream version to rebuild the order at
+    pub version: Option<i64>,
+    /// Instant to rebuild the order at (RFC 3339)
+    pub at: Option<DateTime<Utc>>,
+}
+
+impl HistoryParams {
+    fn point(&self) -> Option<HistoryPoint> {
+        match (self.version, self.at) {
+            (Some(version), None) if version > 0 => Some(HistoryPoint::Version(version)),
+            (None, Some(at)) => Some(HistoryPoint::Timestamp(at)),
+            _ => None,
+        }
+    }
+}
+
+/// An order aggregate as it stood at an earlier version or instant
+#[derive(Debug, Serialize)]
+pub struct OrderHistoryResponse {
+    #[serde(flatten)]
+    pub order: OrderAggregateResponse,
+    /// When the last event applied was recorded
+    pub recorded_at: DateTime<Utc>,
+    /// Version of the stream today
+    pub current_version: i64,
+}
+
+/// Admin: rehydrate an order as it stood at `?version=N` or `?at=<timestamp>`
+///
+/// Replays the stream from the start up to that point, e.g. to see what a command
+/// acted on or what state an order was in when a customer complained.
+pub async fn get_order_at(
+    State(state): State<AppState>,
+    Path(order_id): Path<Uuid>,
+    Query(params): Query<HistoryParams>,
+) -> Result<(StatusCode, Json<OrderHistoryResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let Some(point) = params.point() else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(
+                "Pass either a positive version or an at timestamp".to_string(),
+            )),
+        ));
+    };
+    info!("Rehydrating order aggregate {} at {:?}", order_id, point);
+
+    let internal = |e: event_store::EventStoreError| {
+        error!("Failed to rehydrate order {}: {}", order_id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!("Failed to load events: {}", e))),
+        )
+    };
+
+    let current_version = state
+        .event_store
+        .get_current_version(order_id)
+        .await
+        .map_err(internal)?;
+    if let HistoryPoint::Version(version) = point {
+        if version > current_version {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(format!(
+                    "Order {} has no version {} (current version {})",
+                    order_id, version, current_version
+                ))),
+            ));
+        }
+    }
+
+    let loaded = load_aggregate_at(state.event_store.as_ref(), order_id, point)
+        .await
+        .map_err(internal)?;
+
+    match loaded {
+        Some(historical) => {
+            let cached_version = state
+                .aggregate_cache
+                .get(order_id)
+                .map(|(_, version)| version);
+            Ok((
+                StatusCode::OK,
+                Json(OrderHistoryResponse {
+                    order: OrderAggregateResponse::new(
+                        historical.aggregate,
+                        historical.version,
+                        cached_version,
+                    ),
+                    recorded_at: historical.recorded_at,
+                    current_version,
+                }),
+            ))
+        }
+        None if current_version == 0 => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Order not found: {}", order_id))),
+        )),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!(
+                "Order {} did not exist or was deleted at that point",
+                order_id
+            ))),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use domain::events::order_events::{OrderConfirmedEvent, OrderCreatedEvent};
+
+    #[test]
+    fn test_response_reflects_aggregate() {
+        let order_id = Uuid::new_v4();
+        let mut aggregate = OrderAggregate::new();
+        aggregate.apply_order_created(&OrderCreatedEvent {
+            order_id,
+            customer_id: Uuid::new_v4(),
+            order_number: "ORD-1".to_string(),
+            items: vec![],
+            total_amount: 25.0,
+            currency: "USD".to_string(),
+            created_at: Utc::now(),
+        });
+        aggregate.apply_order_confirmed(&OrderConfirmedEvent {
+            order_id,
+            confirmed_at: Utc::now(),
+        });
+
+        let response = OrderAggregateResponse::new(aggregate, 2, Some(1));
+        assert_eq!(response.aggregate_id, order_id);
+        assert_eq!(response.status, "CONFIRMED");
+        assert_eq!(response.version, 2);
+        assert_eq!(response.cached_version, Some(1));
+    }
+
+    #[test]
+    fn test_history_params_need_exactly_one_point() {
+        let params = |version, at| HistoryParams { version, at };
+        let now = Utc::now();
+
+        assert_eq!(
+            params(Some(2), None).point(),
+            Some(HistoryPoint::Version(2))
+        );
+        assert_eq!(
+            params(None, Some(now)).point(),
+            Some(HistoryPoint::Timestamp(now))
+        );
+        assert_eq!(params(Some(2), Some(now)).point(), None);
+        assert_eq!(params(Some(0), None).point(), None);
+        assert_eq!(params(None, None).point(), None);
+    }
+}
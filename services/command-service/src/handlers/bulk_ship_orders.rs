@@ -0,0 +1,194 @@
+use axum::{extract::State, http::StatusCode, Json};
+use domain::commands::order_commands::ShipOrderCommand;
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::handlers::errors::{field_errors, ErrorResponse, FieldError};
+use crate::handlers::ship_order;
+use crate::state::AppState;
+
+/// Maximum number of orders accepted in a single bulk request
+const MAX_BULK_ORDERS: usize = 1000;
+
+/// Number of orders shipped concurrently within a bulk request
+const BULK_CONCURRENCY: usize = 16;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkShipOrderItem {
+    pub order_id: Uuid,
+
+    #[validate(length(min = 1, message = "Tracking number cannot be empty"))]
+    pub tracking_number: String,
+
+    #[validate(length(min = 1, message = "Carrier cannot be empty"))]
+    pub carrier: String,
+
+    #[serde(default)]
+    pub command_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkShipOrdersRequest {
+    pub orders: Vec<BulkShipOrderItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkShipOrderResult {
+    pub order_id: Uuid,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkShipOrdersResponse {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkShipOrderResult>,
+}
+
+/// Handle bulk ship command
+///
+/// Each order is shipped independently; a failure for one order is reported in
+/// its result and does not affect the others. Results are returned in request
+/// order.
+pub async fn handle(
+    State(state): State<AppState>,
+    Json(request): Json<BulkShipOrdersRequest>,
+) -> Result<(StatusCode, Json<BulkShipOrdersResponse>), (StatusCode, Json<ErrorResponse>)> {
+    info!("Received bulk ship command for {} orders", request.orders.len());
+
+    if let Err(e) = validate_request(&request) {
+        error!("Validation error: {}", e.message);
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::validation(vec![e])),
+        ));
+    }
+
+    let results: Vec<BulkShipOrderResult> = stream::iter(request.orders)
+        .map(|item| ship_one(&state, item))
+        .buffered(BULK_CONCURRENCY)
+        .collect()
+        .await;
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    let failed = results.len() - succeeded;
+
+    info!(
+        "Bulk ship completed: {} succeeded, {} failed",
+        succeeded, failed
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(BulkShipOrdersResponse {
+            total: results.len(),
+            succeeded,
+            failed,
+            results,
+        }),
+    ))
+}
+
+fn validate_request(request: &BulkShipOrdersRequest) -> Result<(), FieldError> {
+    if request.orders.is_empty() {
+        return Err(FieldError::new("orders", "length", "At least one order is required"));
+    }
+    if request.orders.len() > MAX_BULK_ORDERS {
+        return Err(FieldError::new(
+            "orders",
+            "length",
+            format!("At most {} orders are allowed per request", MAX_BULK_ORDERS),
+        ));
+    }
+    Ok(())
+}
+
+/// Ship a single order from a bulk request, capturing the outcome
+async fn ship_one(state: &AppState, item: BulkShipOrderItem) -> BulkShipOrderResult {
+    let order_id = item.order_id;
+
+    if let Err(e) = item.validate() {
+        return BulkShipOrderResult::failure(order_id, ErrorResponse::validation(field_errors(&e)));
+    }
+
+    let cmd = ShipOrderCommand {
+        order_id,
+        tracking_number: item.tracking_number,
+        carrier: item.carrier,
+        command_id: item.command_id,
+    };
+
+    match ship_order::execute(state, cmd).await {
+        Ok((_, Json(response))) => BulkShipOrderResult {
+            order_id,
+            success: true,
+            status: Some(response.status),
+            tracking_number: Some(response.tracking_number),
+            error: None,
+            details: Vec::new(),
+        },
+        Err((_, Json(e))) => BulkShipOrderResult::failure(order_id, e),
+    }
+}
+
+impl BulkShipOrderResult {
+    fn failure(order_id: Uuid, error: ErrorResponse) -> Self {
+        Self {
+            order_id,
+            success: false,
+            status: None,
+            tracking_number: None,
+            error: Some(error.error),
+            details: error.details,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item() -> BulkShipOrderItem {
+        BulkShipOrderItem {
+            order_id: Uuid::new_v4(),
+            tracking_number: "1Z999AA10123456784".to_string(),
+            carrier: "UPS".to_string(),
+            command_id: None,
+        }
+    }
+
+    #[test]
+    fn test_empty_bulk_request_fails_validation() {
+        let request = BulkShipOrdersRequest { orders: vec![] };
+        assert!(validate_request(&request).is_err());
+    }
+
+    #[test]
+    fn test_oversized_bulk_request_fails_validation() {
+        let request = BulkShipOrdersRequest {
+            orders: (0..=MAX_BULK_ORDERS).map(|_| item()).collect(),
+        };
+        assert!(validate_request(&request).is_err());
+    }
+
+    #[test]
+    fn test_bulk_item_validation() {
+        assert!(item().validate().is_ok());
+
+        let mut invalid = item();
+        invalid.carrier = String::new();
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use common::admin_auth::constant_time_eq;
+use serde::Serialize;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::carriers::TrackingUpdate;
+use crate::delivery_tracker::{self, ShipmentOutcome};
+use crate::handlers::errors::ErrorResponse;
+use crate::state::AppState;
+
+/// Header carrying the shared secret configured as `CARRIER_WEBHOOK_TOKEN`
+pub const WEBHOOK_TOKEN_HEADER: &str = "x-webhook-token";
+
+#[derive(Debug, Serialize)]
+pub struct CarrierWebhookResponse {
+    /// Order the tracking number belongs to, if it is being tracked
+    pub order_id: Option<Uuid>,
+    pub delivered: bool,
+}
+
+/// Receive a tracking update pushed by a carrier
+///
+/// Updates for tracking numbers not (yet) tracked are kept by the carrier's
+/// gateway and picked up by the next poll.
+pub async fn handle(
+    State(state): State<AppState>,
+    Path(carrier): Path<String>,
+    headers: HeaderMap,
+    Json(update): Json<TrackingUpdate>,
+) -> Result<(StatusCode, Json<CarrierWebhookResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let Some(token) = state.carrier_webhook_token.as_deref() else {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::new(
+                "Carrier webhooks are disabled: no webhook token configured",
+            )),
+        ));
+    };
+    if !is_authorized(&headers, token) {
+        warn!(carrier = %carrier, "Rejected carrier webhook without a valid token");
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse::new("Missing or invalid webhook token")),
+        ));
+    }
+
+    let Some(tracker) = state.delivery_tracker.as_deref() else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("Delivery tracking is disabled")),
+        ));
+    };
+    let Some(gateway) = tracker.carriers().get(&carrier) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Unknown carrier: {}", carrier))),
+        ));
+    };
+
+    info!(
+        "Received {} update for tracking number {} from {}",
+        update.status.as_str(),
+        update.tracking_number,
+        carrier
+    );
+    if let Err(e) = gateway.receive(update.clone()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(e.to_string())),
+        ));
+    }
+
+    match delivery_tracker::ingest(&state, tracker, &carrier, &update).await {
+        Ok(tracked) => Ok((
+            StatusCode::ACCEPTED,
+            Json(CarrierWebhookResponse {
+                order_id: tracked.map(|(order_id, _)| order_id),
+                delivered: matches!(tracked, Some((_, ShipmentOutcome::Delivered))),
+            }),
+        )),
+        Err(e) => {
+            error!("Failed to apply carrier tracking update: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!(
+                    "Failed to apply tracking update: {}",
+                    e
+                ))),
+            ))
+        }
+    }
+}
+
+fn is_authorized(headers: &HeaderMap, token: &str) -> bool {
+    headers
+        .get(WEBHOOK_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|presented| constant_time_eq(presented.trim().as_bytes(), token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_webhook_token_must_match() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized(&headers, "s3cret"));
+
+        headers.insert(WEBHOOK_TOKEN_HEADER, HeaderValue::from_static("wrong"));
+        assert!(!is_authorized(&headers, "s3cret"));
+
+        headers.insert(WEBHOOK_TOKEN_HEADER, HeaderValue::from_static("s3cret"));
+        assert!(is_authorized(&headers, "s3cret"));
+    }
+}
@@ -0,0 +1,115 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::causation_graph::CausationGraph;
+use crate::handlers::errors::ErrorResponse;
+use crate::state::AppState;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    #[default]
+    Json,
+    /// Graphviz, e.g. `dot -Tsvg`
+    Dot,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GraphParams {
+    #[serde(default)]
+    pub format: GraphFormat,
+    /// Maximum number of events and of sagas to include
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    500
+}
+
+/// Admin: causality graph of a correlation, from the command through its events and sagas
+pub async fn handle(
+    State(state): State<AppState>,
+    Path(correlation_id): Path<Uuid>,
+    Query(params): Query<GraphParams>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    info!(
+        "Building causation graph for correlation: {}",
+        correlation_id
+    );
+
+    if params.limit < 1 || params.limit > 5000 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new("Limit must be between 1 and 5000")),
+        ));
+    }
+
+    let internal = |what: &str, e: String| {
+        error!(
+            "Failed to load {} for correlation {}: {}",
+            what, correlation_id, e
+        );
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!(
+                "Failed to load {}: {}",
+                what, e
+            ))),
+        )
+    };
+
+    let events = state
+        .event_store
+        .find_events_by_correlation_id(correlation_id, params.limit)
+        .await
+        .map_err(|e| internal("events", e.to_string()))?;
+    let sagas = state
+        .saga_repository
+        .find_by_correlation_id(correlation_id, params.limit)
+        .await
+        .map_err(|e| internal("sagas", e.to_string()))?;
+
+    if events.is_empty() && sagas.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!(
+                "No events found for correlation: {}",
+                correlation_id
+            ))),
+        ));
+    }
+
+    let graph = CausationGraph::build(correlation_id, &events, &sagas);
+    Ok(match params.format {
+        GraphFormat::Json => (StatusCode::OK, Json(graph)).into_response(),
+        GraphFormat::Dot => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/vnd.graphviz; charset=utf-8")],
+            graph.to_dot(),
+        )
+            .into_response(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_params() {
+        let params: GraphParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(params.format, GraphFormat::Json);
+        assert_eq!(params.limit, 500);
+
+        let params: GraphParams = serde_json::from_str(r#"{"format":"dot"}"#).unwrap();
+        assert_eq!(params.format, GraphFormat::Dot);
+    }
+}
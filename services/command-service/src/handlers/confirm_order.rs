@@ -1,14 +1,15 @@
 use axum::{extract::{Path, State}, http::StatusCode, Json};
 use domain::{
-    aggregates::order::OrderAggregate,
     commands::order_commands::ConfirmOrderCommand,
-    events::{order_events::*, EventEnvelope, EventMetadata},
+    events::{EventEnvelope, EventMetadata},
 };
-use event_store::Event;
 use serde::Serialize;
 use tracing::{error, info};
 use uuid::Uuid;
 
+use crate::aggregate_loader;
+use crate::command_dedup::{self, CommandIdRequest};
+use crate::handlers::errors::ErrorResponse;
 use crate::state::AppState;
 
 #[derive(Debug, Serialize)]
@@ -17,68 +18,61 @@ pub struct ConfirmOrderResponse {
     pub status: String,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    pub error: String,
-}
-
 /// Handle confirm order command
 pub async fn handle(
     State(state): State<AppState>,
     Path(order_id): Path<Uuid>,
+    request: CommandIdRequest,
 ) -> Result<(StatusCode, Json<ConfirmOrderResponse>), (StatusCode, Json<ErrorResponse>)> {
     info!("Received confirm order command for order: {}", order_id);
 
-    let cmd = ConfirmOrderCommand { order_id };
-
-    // Load existing events
-    let events = match state.event_store.load_events(cmd.order_id).await {
-        Ok(events) => events,
-        Err(e) => {
-            error!("Failed to load events: {}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Failed to load order: {}", e),
-                }),
-            ));
-        }
+    let cmd = ConfirmOrderCommand {
+        order_id,
+        command_id: request.command_id,
     };
 
-    if events.is_empty() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "Order not found".to_string(),
-            }),
-        ));
+    // Replay the response if this command was already processed
+    let replayed = command_dedup::replay_processed(
+        state.event_store.as_ref(),
+        cmd.command_id,
+        "OrderConfirmed",
+        Some(cmd.order_id),
+        |_| {
+            Ok(ConfirmOrderResponse {
+                order_id: cmd.order_id,
+                status: "CONFIRMED".to_string(),
+            })
+        },
+    )
+    .await?;
+    if let Some(response) = replayed {
+        return Ok((StatusCode::OK, Json(response)));
     }
 
     // Rebuild aggregate from events
-    let mut aggregate = OrderAggregate::default();
-    let mut version = 0i64;
-
-    for event in events {
-        version = event.sequence_number;
-        match event.event_type.as_str() {
-            "OrderCreated" => {
-                let domain_event: OrderCreatedEvent =
-                    serde_json::from_value(event.payload).unwrap();
-                aggregate.apply_order_created(&domain_event);
-            }
-            "OrderConfirmed" => {
-                let domain_event: OrderConfirmedEvent =
-                    serde_json::from_value(event.payload).unwrap();
-                aggregate.apply_order_confirmed(&domain_event);
+    let (aggregate, version) =
+        match aggregate_loader::load_order(
+            state.event_store.as_ref(),
+            &state.aggregate_cache,
+            cmd.order_id,
+        )
+        .await
+        {
+            Ok(Some(loaded)) => loaded,
+            Ok(None) => {
+                return Err((
+                    StatusCode::NOT_FOUND,
+                    Json(ErrorResponse::new("Order not found")),
+                ));
             }
-            "OrderCancelled" => {
-                let domain_event: OrderCancelledEvent =
-                    serde_json::from_value(event.payload).unwrap();
-                aggregate.apply_order_cancelled(&domain_event);
+            Err(e) => {
+                error!("Failed to load events: {}", e);
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse::new(format!("Failed to load order: {}", e))),
+                ));
             }
-            _ => {}
-        }
-    }
+        };
 
     // Execute command
     let event = match aggregate.confirm() {
@@ -87,57 +81,38 @@ pub async fn handle(
             error!("Failed to confirm order: {}", e);
             return Err((
                 StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                }),
+                Json(ErrorResponse::new(e.to_string())),
             ));
         }
     };
 
     // Create event envelope
-    let correlation_id = Uuid::new_v4();
-    let event_envelope = EventEnvelope::new(
-        cmd.order_id,
-        "Order".to_string(),
-        event,
-        EventMetadata {
-            correlation_id,
-            causation_id: correlation_id,
-            user_id: None,
-        },
-    );
-
-    // Convert to event store event
-    let store_event = Event {
-        event_id: event_envelope.event_id,
-        aggregate_id: event_envelope.aggregate_id,
-        aggregate_type: event_envelope.aggregate_type.clone(),
-        event_type: event_envelope.event_type.clone(),
-        event_version: event_envelope.event_version,
-        payload: event_envelope.payload.clone(),
-        metadata: serde_json::to_value(&event_envelope.metadata).unwrap(),
-        sequence_number: version + 1,
-        created_at: event_envelope.timestamp,
+    let event_envelope = match EventEnvelope::builder(cmd.order_id, "Order")
+        .with_metadata(EventMetadata::new().with_command_id(cmd.command_id))
+        .with_sequence_number(version + 1)
+        .build(&event)
+    {
+        Ok(envelope) => envelope,
+        Err(e) => {
+            error!("Failed to serialize event: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Failed to serialize event: {}", e))),
+            ));
+        }
     };
 
-    // Persist event
-    if let Err(e) = state
-        .event_store
-        .append_events(cmd.order_id, version, vec![store_event])
-        .await
-    {
-        error!("Failed to append events: {}", e);
-        return Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to persist event: {}", e),
-            }),
-        ));
+    // Persist the event and queue it for publishing in one transaction
+    let persisted = async {
+        let mut uow = state.unit_of_work.begin().await?;
+        uow.record(cmd.order_id, version, &[event_envelope]).await?;
+        uow.commit().await
     }
-
-    // Publish to Kafka
-    if let Err(e) = state.event_publisher.publish(cmd.order_id, &event_envelope).await {
-        error!("Failed to publish event to Kafka: {}", e);
+    .await;
+    if let Err(e) = persisted {
+        error!("Failed to append events: {}", e);
+        state.aggregate_cache.invalidate(cmd.order_id);
+        return Err(command_dedup::append_error(e, "OrderConfirmed"));
     }
 
     info!("Order confirmed successfully: {}", cmd.order_id);
@@ -0,0 +1,184 @@
+use axum::{extract::{Path, State}, http::StatusCode, Json};
+use domain::events::{order_events::OrderItemPriceCorrectedEvent, EventEnvelope, EventMetadata};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::aggregate_loader;
+use crate::command_dedup;
+use crate::handlers::errors::{validation_error, ErrorResponse};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CorrectItemPriceRequest {
+    pub product_id: Uuid,
+
+    #[validate(range(min = 0.01, message = "Unit price must be greater than 0"))]
+    pub corrected_unit_price: f64,
+
+    #[validate(length(min = 1, message = "Correction reason cannot be empty"))]
+    pub reason: String,
+
+    #[serde(default)]
+    pub command_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CorrectItemPriceResponse {
+    pub order_id: Uuid,
+    pub product_id: Uuid,
+    pub corrected_unit_price: f64,
+    pub total_amount: f64,
+    /// Event that recorded the price being corrected
+    pub corrects_event_id: Option<Uuid>,
+}
+
+impl CorrectItemPriceResponse {
+    fn new(event: &OrderItemPriceCorrectedEvent, corrects_event_id: Option<Uuid>) -> Self {
+        Self {
+            order_id: event.order_id,
+            product_id: event.product_id,
+            corrected_unit_price: event.corrected_unit_price,
+            total_amount: event.total_amount,
+            corrects_event_id,
+        }
+    }
+}
+
+/// Admin: correct the unit price an order item was recorded with
+///
+/// Appends an `OrderItemPriceCorrected` event annotated with the ID of the event
+/// that recorded the old price, leaving that event untouched.
+pub async fn handle(
+    State(state): State<AppState>,
+    Path(order_id): Path<Uuid>,
+    Json(request): Json<CorrectItemPriceRequest>,
+) -> Result<(StatusCode, Json<CorrectItemPriceResponse>), (StatusCode, Json<ErrorResponse>)> {
+    info!("Received item price correction for order: {}", order_id);
+
+    if let Err(e) = request.validate() {
+        error!("Validation error: {}", e);
+        return Err(validation_error(&e));
+    }
+
+    // Replay the response if this command was already processed
+    let replayed = command_dedup::replay_processed(
+        state.event_store.as_ref(),
+        request.command_id,
+        "OrderItemPriceCorrected",
+        Some(order_id),
+        |processed| {
+            let corrects_event_id = processed.corrects_event_id();
+            let event: OrderItemPriceCorrectedEvent =
+                serde_json::from_value(processed.payload).map_err(|e| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ErrorResponse::new(format!("Failed to read stored event: {}", e))),
+                    )
+                })?;
+            Ok(CorrectItemPriceResponse::new(&event, corrects_event_id))
+        },
+    )
+    .await?;
+    if let Some(response) = replayed {
+        return Ok((StatusCode::OK, Json(response)));
+    }
+
+    let (aggregate, version) = match aggregate_loader::load_order(
+        state.event_store.as_ref(),
+        &state.aggregate_cache,
+        order_id,
+    )
+    .await
+    {
+        Ok(Some(loaded)) => loaded,
+        Ok(None) => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new("Order not found")),
+            ));
+        }
+        Err(e) => {
+            error!("Failed to load events: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Failed to load order: {}", e))),
+            ));
+        }
+    };
+
+    let event = match aggregate.correct_item_price(
+        request.product_id,
+        request.corrected_unit_price,
+        request.reason,
+    ) {
+        Ok(event) => event,
+        Err(e) => {
+            error!("Failed to correct item price: {}", e);
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new(e.to_string())),
+            ));
+        }
+    };
+
+    let corrects_event_id = match aggregate_loader::find_price_source(
+        state.event_store.as_ref(),
+        order_id,
+        request.product_id,
+    )
+    .await
+    {
+        Ok(event_id) => event_id,
+        Err(e) => {
+            error!("Failed to find the corrected event: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Failed to load order: {}", e))),
+            ));
+        }
+    };
+
+    let mut metadata = EventMetadata::new().with_command_id(request.command_id);
+    if let Some(event_id) = corrects_event_id {
+        metadata = metadata.correcting(event_id);
+    }
+    let event_envelope = match EventEnvelope::builder(order_id, "Order")
+        .with_metadata(metadata)
+        .with_sequence_number(version + 1)
+        .build(&event)
+    {
+        Ok(envelope) => envelope,
+        Err(e) => {
+            error!("Failed to serialize event: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Failed to serialize event: {}", e))),
+            ));
+        }
+    };
+
+    // Persist the event and queue it for publishing in one transaction
+    let persisted = async {
+        let mut uow = state.unit_of_work.begin().await?;
+        uow.record(order_id, version, &[event_envelope]).await?;
+        uow.commit().await
+    }
+    .await;
+    if let Err(e) = persisted {
+        error!("Failed to append events: {}", e);
+        state.aggregate_cache.invalidate(order_id);
+        return Err(command_dedup::append_error(e, "OrderItemPriceCorrected"));
+    }
+
+    info!(
+        "Corrected price of product {} on order {}, correcting event {:?}",
+        event.product_id, order_id, corrects_event_id
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(CorrectItemPriceResponse::new(&event, corrects_event_id)),
+    ))
+}
@@ -4,12 +4,13 @@ use domain::{
     commands::order_commands::CreateOrderCommand,
     events::{order_events::OrderItem, EventEnvelope, EventMetadata},
 };
-use event_store::Event;
 use serde::Serialize;
 use tracing::{error, info};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::command_dedup;
+use crate::handlers::errors::{validation_error, ErrorResponse};
 use crate::state::AppState;
 
 #[derive(Debug, Serialize)]
@@ -19,11 +20,6 @@ pub struct CreateOrderResponse {
     pub status: String,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    pub error: String,
-}
-
 /// Handle create order command
 pub async fn handle(
     State(state): State<AppState>,
@@ -34,12 +30,58 @@ pub async fn handle(
     // Validate command
     if let Err(e) = cmd.validate() {
         error!("Validation error: {}", e);
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: format!("Validation error: {}", e),
-            }),
-        ));
+        return Err(validation_error(&e));
+    }
+
+    // Replay the response if this command was already processed
+    let replayed = command_dedup::replay_processed(
+        state.event_store.as_ref(),
+        cmd.command_id,
+        "OrderCreated",
+        None,
+        |event| {
+            Ok(CreateOrderResponse {
+                order_id: event.aggregate_id,
+                order_number: event.payload["order_number"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                status: "CREATED".to_string(),
+            })
+        },
+    )
+    .await?;
+    if let Some(response) = replayed {
+        return Ok((StatusCode::CREATED, Json(response)));
+    }
+
+    // Reject lines whose product or price does not match the catalog
+    if let Some(verifier) = &state.price_verifier {
+        match verifier.verify(&cmd.items).await {
+            Ok(reasons) if reasons.is_empty() => {}
+            Ok(reasons) => {
+                info!(
+                    "Rejecting order for customer {}: {} item problem(s) against the catalog",
+                    cmd.customer_id,
+                    reasons.len()
+                );
+                return Err((
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(ErrorResponse {
+                        error: "Order items do not match the catalog".to_string(),
+                        details: reasons,
+                        conflict: None,
+                    }),
+                ));
+            }
+            Err(e) => {
+                error!("Price verification failed: {}", e);
+                return Err((
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(ErrorResponse::new(format!("Failed to verify prices: {}", e))),
+                ));
+            }
+        }
     }
 
     // Convert command items to domain items
@@ -61,59 +103,37 @@ pub async fn handle(
             error!("Failed to create order aggregate: {}", e);
             return Err((
                 StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                }),
+                Json(ErrorResponse::new(e.to_string())),
             ));
         }
     };
 
     // Create event envelope
-    let correlation_id = Uuid::new_v4();
-    let event_envelope = EventEnvelope::new(
-        aggregate.id,
-        "Order".to_string(),
-        event,
-        EventMetadata {
-            correlation_id,
-            causation_id: correlation_id,
-            user_id: None,
-        },
-    );
-
-    // Convert to event store event
-    let store_event = Event {
-        event_id: event_envelope.event_id,
-        aggregate_id: event_envelope.aggregate_id,
-        aggregate_type: event_envelope.aggregate_type.clone(),
-        event_type: event_envelope.event_type.clone(),
-        event_version: event_envelope.event_version,
-        payload: event_envelope.payload.clone(),
-        metadata: serde_json::to_value(&event_envelope.metadata).unwrap(),
-        sequence_number: 1,
-        created_at: event_envelope.timestamp,
+    let event_envelope = match EventEnvelope::builder(aggregate.id, "Order")
+        .with_metadata(EventMetadata::new().with_command_id(cmd.command_id))
+        .with_sequence_number(1)
+        .build(&event)
+    {
+        Ok(envelope) => envelope,
+        Err(e) => {
+            error!("Failed to serialize event: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Failed to serialize event: {}", e))),
+            ));
+        }
     };
 
-    // Persist event to event store
-    if let Err(e) = state
-        .event_store
-        .append_events(aggregate.id, 0, vec![store_event])
-        .await
-    {
-        error!("Failed to append events: {}", e);
-        return Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to persist event: {}", e),
-            }),
-        ));
+    // Persist the event and queue it for publishing in one transaction
+    let persisted = async {
+        let mut uow = state.unit_of_work.begin().await?;
+        uow.record(aggregate.id, 0, &[event_envelope]).await?;
+        uow.commit().await
     }
-
-    // Publish to Kafka
-    if let Err(e) = state.event_publisher.publish(aggregate.id, &event_envelope).await {
-        error!("Failed to publish event to Kafka: {}", e);
-        // Note: Event is already persisted, so we don't fail the request
-        // In production, you might want to implement a retry mechanism
+    .await;
+    if let Err(e) = persisted {
+        error!("Failed to append events: {}", e);
+        return Err(command_dedup::append_error(e, "OrderCreated"));
     }
 
     info!("Order created successfully: {}", aggregate.id);
@@ -132,6 +152,9 @@ pub async fn handle(
 mod tests {
     use super::*;
     use domain::commands::order_commands::{CreateOrderItem, ShippingAddress};
+    use event_store::{EventStore, InMemoryEventStore};
+    use messaging::InMemoryPublisher;
+    use std::sync::Arc;
 
     #[test]
     fn test_create_order_command_validation() {
@@ -150,6 +173,7 @@ mod tests {
                 zip: "62701".to_string(),
                 country: "US".to_string(),
             },
+            command_id: None,
         };
 
         assert!(cmd.validate().is_ok());
@@ -167,8 +191,52 @@ mod tests {
                 zip: "62701".to_string(),
                 country: "US".to_string(),
             },
+            command_id: None,
         };
 
         assert!(cmd.validate().is_err());
     }
+
+    #[tokio::test]
+    async fn test_create_order_records_and_publishes_once() {
+        let event_store = Arc::new(InMemoryEventStore::new());
+        let publisher = Arc::new(InMemoryPublisher::new("order-events"));
+        let state = AppState::builder()
+            .with_event_store(event_store.clone())
+            .with_event_publisher(publisher.clone())
+            .build();
+        let cmd = CreateOrderCommand {
+            customer_id: Uuid::new_v4(),
+            items: vec![CreateOrderItem {
+                product_id: Uuid::new_v4(),
+                sku: "SKU-001".to_string(),
+                quantity: 2,
+                unit_price: 10.50,
+            }],
+            shipping_address: ShippingAddress {
+                street: "123 Main St".to_string(),
+                city: "Springfield".to_string(),
+                state: "IL".to_string(),
+                zip: "62701".to_string(),
+                country: "US".to_string(),
+            },
+            command_id: Some(Uuid::new_v4()),
+        };
+
+        let (status, created) = handle(State(state.clone()), Json(cmd.clone())).await.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+
+        // A retry replays the first response instead of creating a second order
+        let (_, replayed) = handle(State(state), Json(cmd)).await.unwrap();
+        assert_eq!(replayed.order_id, created.order_id);
+
+        let events = event_store.load_events(created.order_id).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, "OrderCreated");
+
+        let published = publisher.messages();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].key, created.order_id);
+        assert_eq!(published[0].payload["event_type"], "OrderCreated");
+    }
 }
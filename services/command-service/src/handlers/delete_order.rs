@@ -0,0 +1,100 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use domain::events::{stream_events::StreamDeletedEvent, EventEnvelope};
+use event_store::{DeleteMode, EventStoreError};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::handlers::errors::ErrorResponse;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteOrderParams {
+    #[serde(default = "default_mode")]
+    pub mode: DeleteMode,
+}
+
+fn default_mode() -> DeleteMode {
+    DeleteMode::Soft
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteOrderResponse {
+    pub order_id: Uuid,
+    pub mode: DeleteMode,
+}
+
+/// Handle delete order request (soft tombstone or hard scavenge)
+pub async fn handle(
+    State(state): State<AppState>,
+    Path(order_id): Path<Uuid>,
+    Query(params): Query<DeleteOrderParams>,
+) -> Result<(StatusCode, Json<DeleteOrderResponse>), (StatusCode, Json<ErrorResponse>)> {
+    warn!("Received {:?} delete request for order: {}", params.mode, order_id);
+
+    state.aggregate_cache.invalidate(order_id);
+
+    // Let projections drop their read model rows; the event goes out through the
+    // outbox in the same transaction as the delete
+    let event = StreamDeletedEvent {
+        aggregate_id: order_id,
+        mode: match params.mode {
+            DeleteMode::Soft => "soft".to_string(),
+            DeleteMode::Hard => "hard".to_string(),
+        },
+        deleted_at: Utc::now(),
+    };
+    let event_envelope = EventEnvelope::builder(order_id, "Order")
+        .build(&event)
+        .map_err(|e| {
+            error!("Failed to serialize event: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Failed to serialize event: {}", e))),
+            )
+        })?;
+
+    let delete = async {
+        let mut uow = state.unit_of_work.begin().await?;
+        uow.delete_stream(order_id, params.mode, &event_envelope).await?;
+        uow.commit().await
+    };
+    if let Err(e) = delete.await {
+        error!("Failed to delete stream: {}", e);
+        let status = match e {
+            EventStoreError::AggregateNotFound(_) => StatusCode::NOT_FOUND,
+            EventStoreError::ConcurrencyConflict { .. } => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        return Err((
+            status,
+            Json(ErrorResponse::new(format!("Failed to delete order: {}", e))),
+        ));
+    }
+
+    info!("Order deleted successfully: {}", order_id);
+
+    Ok((
+        StatusCode::OK,
+        Json(DeleteOrderResponse {
+            order_id,
+            mode: params.mode,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_mode_is_soft() {
+        let params: DeleteOrderParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(params.mode, DeleteMode::Soft);
+    }
+}
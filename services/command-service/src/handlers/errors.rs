@@ -0,0 +1,87 @@
+use axum::{http::StatusCode, Json};
+use validator::{ValidationErrors, ValidationErrorsKind};
+
+pub use domain::api::{ConflictDetails, ErrorResponse, FieldError};
+
+/// Flatten `validator` errors, including nested structs and lists, sorted by field path
+pub fn field_errors(errors: &ValidationErrors) -> Vec<FieldError> {
+    let mut details = Vec::new();
+    collect_field_errors(errors, "", &mut details);
+    details.sort_by(|a, b| a.field.cmp(&b.field).then_with(|| a.code.cmp(&b.code)));
+    details
+}
+
+fn collect_field_errors(errors: &ValidationErrors, prefix: &str, details: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{}", prefix, field)
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => {
+                for error in field_errors {
+                    let message = error
+                        .message
+                        .as_ref()
+                        .map(|m| m.to_string())
+                        .unwrap_or_else(|| format!("Invalid value ({})", error.code));
+                    details.push(FieldError::new(path.clone(), error.code.to_string(), message));
+                }
+            }
+            ValidationErrorsKind::Struct(nested) => collect_field_errors(nested, &path, details),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(nested, &format!("{}[{}]", path, index), details);
+                }
+            }
+        }
+    }
+}
+
+/// 400 response for a request that failed `validator` checks
+pub fn validation_error(errors: &ValidationErrors) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse::validation(field_errors(errors))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use validator::Validate;
+
+    #[derive(Serialize, Validate)]
+    struct Line {
+        #[validate(range(min = 1, message = "Quantity must be at least 1"))]
+        quantity: u32,
+    }
+
+    #[derive(Serialize, Validate)]
+    struct Request {
+        #[validate(length(min = 1, message = "Name cannot be empty"))]
+        name: String,
+        #[validate(length(min = 1), nested)]
+        lines: Vec<Line>,
+    }
+
+    #[test]
+    fn test_field_errors_are_flattened_with_paths() {
+        let request = Request {
+            name: String::new(),
+            lines: vec![Line { quantity: 1 }, Line { quantity: 0 }],
+        };
+
+        let details = field_errors(&request.validate().unwrap_err());
+        assert_eq!(
+            details,
+            vec![
+                FieldError::new("lines[1].quantity", "range", "Quantity must be at least 1"),
+                FieldError::new("name", "length", "Name cannot be empty"),
+            ]
+        );
+    }
+}
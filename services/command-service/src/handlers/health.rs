@@ -1,5 +1,10 @@
-use axum::{http::StatusCode, Json};
+use axum::{extract::State, http::StatusCode, Json};
+use common::circuit_breaker::CircuitBreakerSnapshot;
+use messaging::BrokerHealth;
 use serde::Serialize;
+use tracing::warn;
+
+use crate::state::AppState;
 
 #[derive(Serialize)]
 pub struct HealthResponse {
@@ -20,9 +25,78 @@ pub async fn health_check() -> (StatusCode, Json<HealthResponse>) {
     )
 }
 
+#[derive(Serialize)]
+pub struct ReadinessResponse {
+    pub status: String,
+    pub kafka: KafkaReadiness,
+    /// Breakers are reported but do not affect readiness: an open one already sheds calls
+    pub circuit_breakers: Vec<CircuitBreakerSnapshot>,
+}
+
+#[derive(Serialize)]
+pub struct KafkaReadiness {
+    pub healthy: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brokers: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partitions: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leaderless_partitions: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<BrokerHealth> for KafkaReadiness {
+    fn from(health: BrokerHealth) -> Self {
+        Self {
+            healthy: health.is_healthy(),
+            brokers: Some(health.brokers),
+            partitions: Some(health.partitions),
+            leaderless_partitions: Some(health.leaderless_partitions),
+            error: None,
+        }
+    }
+}
+
+/// Readiness probe: 503 unless the brokers report the order topic with a leader
+/// for every partition
+pub async fn readiness_check(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let kafka = match state.event_publisher.healthcheck().await {
+        Ok(health) => KafkaReadiness::from(health),
+        Err(e) => {
+            warn!("Kafka readiness check failed: {}", e);
+            KafkaReadiness {
+                healthy: false,
+                brokers: None,
+                partitions: None,
+                leaderless_partitions: None,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+    let (status, label) = if kafka.healthy {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "kafka_unavailable")
+    };
+
+    (
+        status,
+        Json(ReadinessResponse {
+            status: label.to_string(),
+            kafka,
+            circuit_breakers: state.circuit_breakers.snapshot().await,
+        }),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use messaging::InMemoryPublisher;
+    use std::sync::Arc;
 
     #[tokio::test]
     async fn test_health_check() {
@@ -31,4 +105,23 @@ mod tests {
         assert_eq!(response.status, "healthy");
         assert_eq!(response.service, "command-service");
     }
+
+    #[tokio::test]
+    async fn test_readiness_reflects_publisher_health() {
+        let publisher = Arc::new(InMemoryPublisher::new("order-events"));
+        let state = AppState::builder()
+            .with_event_publisher(publisher.clone())
+            .build();
+
+        let (status, response) = readiness_check(State(state.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(response.kafka.healthy);
+        assert_eq!(response.circuit_breakers[0].name, "kafka-publisher");
+
+        publisher.set_unavailable(true);
+        let (status, response) = readiness_check(State(state)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.status, "kafka_unavailable");
+        assert!(response.kafka.error.is_some());
+    }
 }
@@ -1,6 +1,18 @@
+pub mod aggregate_state;
+pub mod bulk_ship_orders;
+pub mod carrier_webhook;
 pub mod cancel_order;
+pub mod causation_graph;
 pub mod confirm_order;
+pub mod correct_item_price;
 pub mod create_order;
+pub mod delete_order;
 pub mod deliver_order;
+pub mod errors;
 pub mod health;
+pub mod saga_approvals;
+pub mod saga_definitions;
+pub mod saga_interventions;
 pub mod ship_order;
+pub mod stream_events;
+pub mod trace_correlation;
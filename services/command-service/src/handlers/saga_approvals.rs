@@ -0,0 +1,168 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::Utc;
+use common::admin_auth::AdminIdentity;
+use saga::{PendingSagas, SagaError, SagaState, SagaStatus, StepApproval};
+use serde::Deserialize;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::handlers::errors::ErrorResponse;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct ApprovalParams {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApproveStepRequest {
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RejectStepRequest {
+    pub reason: String,
+}
+
+/// Admin: list sagas paused until someone approves their current step
+pub async fn list(
+    State(state): State<AppState>,
+    Query(params): Query<ApprovalParams>,
+) -> Result<(StatusCode, Json<PendingSagas>), (StatusCode, Json<ErrorResponse>)> {
+    if params.limit < 1 || params.limit > 1000 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new("Limit must be between 1 and 1000")),
+        ));
+    }
+
+    let total = state
+        .saga_repository
+        .count_by_status(SagaStatus::PausedAwaitingApproval)
+        .await
+        .map_err(internal_error)?;
+
+    let sagas = state
+        .saga_repository
+        .find_by_status(SagaStatus::PausedAwaitingApproval, params.limit)
+        .await
+        .map_err(internal_error)?;
+
+    Ok((StatusCode::OK, Json(PendingSagas { total, sagas })))
+}
+
+/// Admin: approve the step a saga is paused at
+///
+/// The saga goes back to RUNNING and the orchestrator picks it up on its next recovery pass.
+/// The decision is recorded under the admin the request authenticated as.
+pub async fn approve(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminIdentity>,
+    Path(saga_id): Path<Uuid>,
+    Json(request): Json<ApproveStepRequest>,
+) -> Result<(StatusCode, Json<SagaState>), (StatusCode, Json<ErrorResponse>)> {
+    info!("Approving saga step: {} by {}", saga_id, admin.name());
+
+    let approval = StepApproval {
+        approved: true,
+        approver: admin.name().to_string(),
+        comment: request.comment.filter(|c| !c.trim().is_empty()),
+        decided_at: Utc::now(),
+    };
+    decide(&state, saga_id, approval).await
+}
+
+/// Admin: reject the step a saga is paused at, which compensates the saga
+pub async fn reject(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminIdentity>,
+    Path(saga_id): Path<Uuid>,
+    Json(request): Json<RejectStepRequest>,
+) -> Result<(StatusCode, Json<SagaState>), (StatusCode, Json<ErrorResponse>)> {
+    warn!("Rejecting saga step: {} by {}", saga_id, admin.name());
+
+    if request.reason.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new("Reason must not be empty")),
+        ));
+    }
+
+    let approval = StepApproval {
+        approved: false,
+        approver: admin.name().to_string(),
+        comment: Some(request.reason),
+        decided_at: Utc::now(),
+    };
+    decide(&state, saga_id, approval).await
+}
+
+async fn decide(
+    state: &AppState,
+    saga_id: Uuid,
+    approval: StepApproval,
+) -> Result<(StatusCode, Json<SagaState>), (StatusCode, Json<ErrorResponse>)> {
+    let mut saga = state.saga_repository.load(saga_id).await.map_err(|e| match e {
+        SagaError::SagaNotFound(_) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Saga not found: {}", saga_id))),
+        ),
+        e => internal_error(e),
+    })?;
+
+    if let Err(e) = saga.decide_approval(approval) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse::new(format!("Saga is not awaiting approval: {}", e))),
+        ));
+    }
+
+    // Only one decision wins: a concurrent approve/reject, or the orchestrator moving
+    // the saga on, leaves it no longer paused and this update matches nothing
+    let updated = state
+        .saga_repository
+        .update_if_status(&saga, SagaStatus::PausedAwaitingApproval)
+        .await
+        .map_err(internal_error)?;
+    if !updated {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse::new(format!(
+                "Saga {} was decided or changed concurrently",
+                saga_id
+            ))),
+        ));
+    }
+
+    info!("Saga approval recorded: {}", saga_id);
+    Ok((StatusCode::ACCEPTED, Json(saga)))
+}
+
+fn internal_error(e: SagaError) -> (StatusCode, Json<ErrorResponse>) {
+    error!("Saga repository error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse::new(format!("Failed to access sagas: {}", e))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approve_request_comment_is_optional() {
+        let request: ApproveStepRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(request.comment, None);
+    }
+}
@@ -0,0 +1,34 @@
+use axum::{extract::State, http::StatusCode, Json};
+use saga::SagaDefinition;
+use serde::Serialize;
+use tracing::error;
+
+use crate::handlers::errors::ErrorResponse;
+use crate::state::AppState;
+
+#[derive(Debug, Serialize)]
+pub struct SagaDefinitionListResponse {
+    pub definitions: Vec<SagaDefinition>,
+}
+
+/// Admin: the saga flows the orchestrator last published on startup
+///
+/// Step order, compensations, retries, timeouts and approval conditions as JSON,
+/// for rendering flow diagrams.
+pub async fn list(
+    State(state): State<AppState>,
+) -> Result<(StatusCode, Json<SagaDefinitionListResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let definitions = state
+        .saga_repository
+        .list_definitions()
+        .await
+        .map_err(|e| {
+            error!("Saga repository error: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Failed to load saga definitions: {}", e))),
+            )
+        })?;
+
+    Ok((StatusCode::OK, Json(SagaDefinitionListResponse { definitions })))
+}
@@ -0,0 +1,123 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use common::admin_auth::AdminIdentity;
+use common::metrics;
+use saga::{PendingSagas, SagaError, SagaRepository, SagaState, SagaStatus};
+use serde::Deserialize;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::handlers::errors::ErrorResponse;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct InterventionParams {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveInterventionRequest {
+    pub resolution: String,
+}
+
+/// Refresh the Prometheus gauge tracking the manual intervention queue
+pub async fn refresh_intervention_gauge(repository: &dyn SagaRepository) -> Result<i64, SagaError> {
+    let total = repository
+        .count_by_status(SagaStatus::RequiresIntervention)
+        .await?;
+    metrics::record_sagas_requiring_intervention(total);
+    Ok(total)
+}
+
+/// Admin: list sagas whose compensation failed and need an operator
+pub async fn list(
+    State(state): State<AppState>,
+    Query(params): Query<InterventionParams>,
+) -> Result<(StatusCode, Json<PendingSagas>), (StatusCode, Json<ErrorResponse>)> {
+    if params.limit < 1 || params.limit > 1000 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new("Limit must be between 1 and 1000")),
+        ));
+    }
+
+    let total = refresh_intervention_gauge(state.saga_repository.as_ref())
+        .await
+        .map_err(internal_error)?;
+
+    let sagas = state
+        .saga_repository
+        .find_by_status(SagaStatus::RequiresIntervention, params.limit)
+        .await
+        .map_err(internal_error)?;
+
+    Ok((StatusCode::OK, Json(PendingSagas { total, sagas })))
+}
+
+/// Admin: mark a saga in the intervention queue as resolved
+pub async fn resolve(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminIdentity>,
+    Path(saga_id): Path<Uuid>,
+    Json(request): Json<ResolveInterventionRequest>,
+) -> Result<(StatusCode, Json<SagaState>), (StatusCode, Json<ErrorResponse>)> {
+    warn!("Resolving saga intervention: {} by {}", saga_id, admin.name());
+
+    if request.resolution.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new("Resolution must not be empty")),
+        ));
+    }
+
+    // Emits SagaCompensated, and of concurrent resolves only one is recorded
+    let saga = state
+        .saga_coordinator
+        .resolve_intervention(saga_id, request.resolution)
+        .await
+        .map_err(|e| match e {
+            SagaError::SagaNotFound(_) => (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(format!("Saga not found: {}", saga_id))),
+            ),
+            SagaError::InvalidStateTransition { .. } => (
+                StatusCode::CONFLICT,
+                Json(ErrorResponse::new(format!("Saga does not require intervention: {}", e))),
+            ),
+            e => internal_error(e),
+        })?;
+
+    if let Err(e) = refresh_intervention_gauge(state.saga_repository.as_ref()).await {
+        warn!("Failed to refresh intervention gauge: {}", e);
+    }
+
+    info!("Saga intervention resolved: {}", saga_id);
+    Ok((StatusCode::OK, Json(saga)))
+}
+
+fn internal_error(e: SagaError) -> (StatusCode, Json<ErrorResponse>) {
+    error!("Saga repository error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse::new(format!("Failed to access sagas: {}", e))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_limit() {
+        let params: InterventionParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(params.limit, 100);
+    }
+}
@@ -1,24 +1,31 @@
 use axum::{extract::{Path, State}, http::StatusCode, Json};
 use domain::{
-    aggregates::order::OrderAggregate,
     commands::order_commands::ShipOrderCommand,
-    events::{order_events::*, EventEnvelope, EventMetadata},
+    events::{order_events::OrderShippedEvent, EventEnvelope, EventMetadata},
 };
-use event_store::Event;
 use serde::{Deserialize, Serialize};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::aggregate_loader;
+use crate::carriers::ShipmentRequest;
+use crate::command_dedup;
+use crate::handlers::errors::{validation_error, ErrorResponse};
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct ShipOrderRequest {
+    /// Booked with the carrier's gateway when omitted
     #[validate(length(min = 1, message = "Tracking number cannot be empty"))]
-    pub tracking_number: String,
+    #[serde(default)]
+    pub tracking_number: Option<String>,
 
     #[validate(length(min = 1, message = "Carrier cannot be empty"))]
     pub carrier: String,
+
+    #[serde(default)]
+    pub command_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize)]
@@ -28,11 +35,6 @@ pub struct ShipOrderResponse {
     pub tracking_number: String,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    pub error: String,
-}
-
 /// Handle ship order command
 pub async fn handle(
     State(state): State<AppState>,
@@ -44,132 +46,131 @@ pub async fn handle(
     // Validate request
     if let Err(e) = request.validate() {
         error!("Validation error: {}", e);
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: format!("Validation error: {}", e),
-            }),
-        ));
+        return Err(validation_error(&e));
     }
 
+    let tracking_number = match request.tracking_number {
+        Some(tracking_number) => tracking_number,
+        None => book_shipment(&state, order_id, &request.carrier).await?,
+    };
+
     let cmd = ShipOrderCommand {
         order_id,
-        tracking_number: request.tracking_number.clone(),
+        tracking_number,
         carrier: request.carrier,
+        command_id: request.command_id,
     };
 
-    // Load existing events
-    let events = match state.event_store.load_events(cmd.order_id).await {
-        Ok(events) => events,
-        Err(e) => {
-            error!("Failed to load events: {}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Failed to load order: {}", e),
-                }),
-            ));
-        }
-    };
+    execute(&state, cmd).await
+}
 
-    if events.is_empty() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "Order not found".to_string(),
-            }),
-        ));
+/// Ship a single order; shared by the single and bulk ship endpoints
+pub async fn execute(
+    state: &AppState,
+    cmd: ShipOrderCommand,
+) -> Result<(StatusCode, Json<ShipOrderResponse>), (StatusCode, Json<ErrorResponse>)> {
+    // Replay the response if this command was already processed
+    let replayed = command_dedup::replay_processed(
+        state.event_store.as_ref(),
+        cmd.command_id,
+        "OrderShipped",
+        Some(cmd.order_id),
+        |processed| {
+            // The retry may carry a different (or newly booked) tracking number than
+            // the one the order was shipped with
+            let event: OrderShippedEvent =
+                serde_json::from_value(processed.payload).map_err(|e| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ErrorResponse::new(format!("Failed to read stored event: {}", e))),
+                    )
+                })?;
+            Ok(ShipOrderResponse {
+                order_id: cmd.order_id,
+                status: "SHIPPED".to_string(),
+                tracking_number: event.tracking_number,
+            })
+        },
+    )
+    .await?;
+    if let Some(response) = replayed {
+        return Ok((StatusCode::OK, Json(response)));
     }
 
     // Rebuild aggregate from events
-    let mut aggregate = OrderAggregate::default();
-    let mut version = 0i64;
-
-    for event in events {
-        version = event.sequence_number;
-        match event.event_type.as_str() {
-            "OrderCreated" => {
-                let domain_event: OrderCreatedEvent =
-                    serde_json::from_value(event.payload).unwrap();
-                aggregate.apply_order_created(&domain_event);
-            }
-            "OrderConfirmed" => {
-                let domain_event: OrderConfirmedEvent =
-                    serde_json::from_value(event.payload).unwrap();
-                aggregate.apply_order_confirmed(&domain_event);
-            }
-            "OrderCancelled" => {
-                let domain_event: OrderCancelledEvent =
-                    serde_json::from_value(event.payload).unwrap();
-                aggregate.apply_order_cancelled(&domain_event);
+    let (aggregate, version) =
+        match aggregate_loader::load_order(
+            state.event_store.as_ref(),
+            &state.aggregate_cache,
+            cmd.order_id,
+        )
+        .await
+        {
+            Ok(Some(loaded)) => loaded,
+            Ok(None) => {
+                return Err((
+                    StatusCode::NOT_FOUND,
+                    Json(ErrorResponse::new("Order not found")),
+                ));
             }
-            "OrderShipped" => {
-                let domain_event: OrderShippedEvent =
-                    serde_json::from_value(event.payload).unwrap();
-                aggregate.apply_order_shipped(&domain_event);
+            Err(e) => {
+                error!("Failed to load events: {}", e);
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse::new(format!("Failed to load order: {}", e))),
+                ));
             }
-            _ => {}
-        }
-    }
+        };
 
     // Execute command
-    let event = match aggregate.ship(cmd.tracking_number.clone(), cmd.carrier) {
+    let event = match aggregate.ship(cmd.tracking_number.clone(), cmd.carrier.clone()) {
         Ok(event) => event,
         Err(e) => {
             error!("Failed to ship order: {}", e);
             return Err((
                 StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                }),
+                Json(ErrorResponse::new(e.to_string())),
             ));
         }
     };
 
     // Create event envelope
-    let correlation_id = Uuid::new_v4();
-    let event_envelope = EventEnvelope::new(
-        cmd.order_id,
-        "Order".to_string(),
-        event,
-        EventMetadata {
-            correlation_id,
-            causation_id: correlation_id,
-            user_id: None,
-        },
-    );
-
-    // Convert to event store event
-    let store_event = Event {
-        event_id: event_envelope.event_id,
-        aggregate_id: event_envelope.aggregate_id,
-        aggregate_type: event_envelope.aggregate_type.clone(),
-        event_type: event_envelope.event_type.clone(),
-        event_version: event_envelope.event_version,
-        payload: event_envelope.payload.clone(),
-        metadata: serde_json::to_value(&event_envelope.metadata).unwrap(),
-        sequence_number: version + 1,
-        created_at: event_envelope.timestamp,
+    let event_envelope = match EventEnvelope::builder(cmd.order_id, "Order")
+        .with_metadata(EventMetadata::new().with_command_id(cmd.command_id))
+        .with_sequence_number(version + 1)
+        .build(&event)
+    {
+        Ok(envelope) => envelope,
+        Err(e) => {
+            error!("Failed to serialize event: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Failed to serialize event: {}", e))),
+            ));
+        }
     };
 
-    // Persist event
-    if let Err(e) = state
-        .event_store
-        .append_events(cmd.order_id, version, vec![store_event])
-        .await
-    {
+    // Persist the event and queue it for publishing in one transaction
+    let persisted = async {
+        let mut uow = state.unit_of_work.begin().await?;
+        uow.record(cmd.order_id, version, &[event_envelope]).await?;
+        uow.commit().await
+    }
+    .await;
+    if let Err(e) = persisted {
         error!("Failed to append events: {}", e);
-        return Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to persist event: {}", e),
-            }),
-        ));
+        state.aggregate_cache.invalidate(cmd.order_id);
+        return Err(command_dedup::append_error(e, "OrderShipped"));
     }
 
-    // Publish to Kafka
-    if let Err(e) = state.event_publisher.publish(cmd.order_id, &event_envelope).await {
-        error!("Failed to publish event to Kafka: {}", e);
+    // Follow the shipment so the order is delivered once the carrier says so
+    if let Some(tracker) = &state.delivery_tracker {
+        if let Err(e) = tracker
+            .track(cmd.order_id, &cmd.carrier, &cmd.tracking_number)
+            .await
+        {
+            warn!("Failed to start delivery tracking for order {}: {}", cmd.order_id, e);
+        }
     }
 
     info!("Order shipped successfully: {}", cmd.order_id);
@@ -183,3 +184,78 @@ pub async fn handle(
         }),
     ))
 }
+
+/// Book a shipment with the carrier's gateway to get a tracking number
+async fn book_shipment(
+    state: &AppState,
+    order_id: Uuid,
+    carrier: &str,
+) -> Result<String, (StatusCode, Json<ErrorResponse>)> {
+    let gateway = state
+        .delivery_tracker
+        .as_ref()
+        .and_then(|tracker| tracker.carriers().get(carrier))
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new(format!(
+                    "Tracking number is required: no gateway for carrier {}",
+                    carrier
+                ))),
+            )
+        })?;
+
+    match gateway.create_shipment(&ShipmentRequest { order_id }).await {
+        Ok(shipment) => {
+            info!(
+                "Booked shipment {} with {} for order {}",
+                shipment.tracking_number, shipment.carrier, order_id
+            );
+            Ok(shipment.tracking_number)
+        }
+        Err(e) => {
+            error!("Failed to book shipment for order {}: {}", order_id, e);
+            Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new(format!("Failed to book shipment: {}", e))),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::aggregates::order::OrderAggregate;
+    use domain::events::order_events::OrderItem;
+    use event_store::{Event, EventStore, InMemoryEventStore};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_retry_replays_the_stored_tracking_number() {
+        let event_store = Arc::new(InMemoryEventStore::new());
+        let items = vec![OrderItem::new(Uuid::new_v4(), "SKU-001".to_string(), 1, 10.0)];
+        let (order, created) = OrderAggregate::create(Uuid::new_v4(), items).unwrap();
+        let confirmed = order.confirm().unwrap();
+        let events = vec![
+            Event::from(&EventEnvelope::builder(order.id, "Order").build(&created).unwrap()),
+            Event::from(&EventEnvelope::builder(order.id, "Order").build(&confirmed).unwrap()),
+        ];
+        event_store.append_events(order.id, 0, events).await.unwrap();
+        let state = AppState::builder().with_event_store(event_store.clone()).build();
+
+        let cmd = |tracking_number: &str| ShipOrderCommand {
+            order_id: order.id,
+            tracking_number: tracking_number.to_string(),
+            carrier: "UPS".to_string(),
+            command_id: Some(Uuid::nil()),
+        };
+        let (_, shipped) = execute(&state, cmd("1Z-FIRST")).await.unwrap();
+        assert_eq!(shipped.tracking_number, "1Z-FIRST");
+
+        let (status, replayed) = execute(&state, cmd("1Z-RETRY")).await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(replayed.tracking_number, "1Z-FIRST");
+        assert_eq!(event_store.get_current_version(order.id).await.unwrap(), 3);
+    }
+}
@@ -0,0 +1,246 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use domain::events::EventMetadata;
+use domain::schema::{event_schemas, EventSchema};
+use domain::schema_compat::{upcasters, UpcasterRegistry};
+use event_store::Event;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::handlers::errors::ErrorResponse;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct StreamParams {
+    /// First stream version to return
+    #[serde(default = "default_from")]
+    pub from: i64,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_from() -> i64 {
+    1
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+/// A stored event as kept in the event store, alongside its decoded payload
+#[derive(Debug, Serialize)]
+pub struct StoredEventView {
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub aggregate_type: String,
+    /// Position of the event in its stream
+    pub version: i64,
+    /// Payload schema version the event was stored with
+    pub schema_version: i32,
+    /// Payload schema version it decodes to; `None` for unknown event types
+    pub current_schema_version: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub metadata: EventMetadata,
+    /// Payload exactly as stored
+    pub raw_payload: serde_json::Value,
+    /// Payload upcast to the current schema and read through the event struct
+    pub payload: Option<serde_json::Value>,
+    /// Why the payload could not be decoded, if it could not
+    pub decode_error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreamEventsResponse {
+    pub aggregate_id: Uuid,
+    pub current_version: i64,
+    pub deleted: bool,
+    pub events: Vec<StoredEventView>,
+    /// `from` for the next page, if there are more events
+    pub next_from: Option<i64>,
+}
+
+/// Decodes stored payloads into their current shape
+struct PayloadDecoder {
+    upcasters: UpcasterRegistry,
+    schemas: HashMap<&'static str, EventSchema>,
+}
+
+impl PayloadDecoder {
+    fn new() -> Self {
+        Self {
+            upcasters: upcasters(),
+            schemas: event_schemas()
+                .into_iter()
+                .map(|schema| (schema.event_type, schema))
+                .collect(),
+        }
+    }
+
+    fn view(&self, event: Event) -> StoredEventView {
+        let (payload, decode_error) =
+            match self.decode(&event.event_type, event.event_version, event.payload.clone()) {
+                Ok(payload) => (Some(payload), None),
+                Err(e) => (None, Some(e)),
+            };
+
+        StoredEventView {
+            event_id: event.event_id,
+            current_schema_version: self.upcasters.current_version(&event.event_type),
+            event_type: event.event_type,
+            aggregate_type: event.aggregate_type,
+            version: event.sequence_number,
+            schema_version: event.event_version,
+            created_at: event.created_at,
+            metadata: event.metadata,
+            raw_payload: event.payload,
+            payload,
+            decode_error,
+        }
+    }
+
+    fn decode(
+        &self,
+        event_type: &str,
+        version: i32,
+        payload: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        let schema = self
+            .schemas
+            .get(event_type)
+            .ok_or_else(|| format!("unknown event type {}", event_type))?;
+        let (_, payload) = self
+            .upcasters
+            .upcast(event_type, version, payload)
+            .map_err(|e| e.to_string())?;
+        schema.decode(payload).map_err(|e| e.to_string())
+    }
+}
+
+/// Admin: browse the raw events of one stream, a page at a time
+pub async fn handle(
+    State(state): State<AppState>,
+    Path(aggregate_id): Path<Uuid>,
+    Query(params): Query<StreamParams>,
+) -> Result<(StatusCode, Json<StreamEventsResponse>), (StatusCode, Json<ErrorResponse>)> {
+    info!(
+        "Browsing stream {} from version {} (limit: {})",
+        aggregate_id, params.from, params.limit
+    );
+
+    if params.from < 1 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new("from must be at least 1")),
+        ));
+    }
+    if params.limit < 1 || params.limit > 1000 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new("Limit must be between 1 and 1000")),
+        ));
+    }
+
+    let internal = |e: event_store::EventStoreError| {
+        error!("Failed to browse stream {}: {}", aggregate_id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!("Failed to load events: {}", e))),
+        )
+    };
+
+    let current_version = state
+        .event_store
+        .get_current_version(aggregate_id)
+        .await
+        .map_err(internal)?;
+    if current_version == 0 {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("No events found for stream: {}", aggregate_id))),
+        ));
+    }
+
+    let events = state
+        .event_store
+        .load_events_page(aggregate_id, params.from - 1, params.limit)
+        .await
+        .map_err(internal)?;
+    let deleted = state
+        .event_store
+        .is_stream_deleted(aggregate_id)
+        .await
+        .map_err(internal)?;
+
+    let next_from = events
+        .last()
+        .map(|event| event.sequence_number + 1)
+        .filter(|next| *next <= current_version);
+
+    let decoder = PayloadDecoder::new();
+    Ok((
+        StatusCode::OK,
+        Json(StreamEventsResponse {
+            aggregate_id,
+            current_version,
+            deleted,
+            events: events.into_iter().map(|event| decoder.view(event)).collect(),
+            next_from,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_default_params() {
+        let params: StreamParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(params.from, 1);
+        assert_eq!(params.limit, 100);
+    }
+
+    #[test]
+    fn test_view_decodes_known_events() {
+        let order_id = Uuid::new_v4();
+        let mut event = Event::new(
+            order_id,
+            "Order".to_string(),
+            "OrderConfirmed".to_string(),
+            1,
+            json!({ "order_id": order_id, "confirmed_at": "2024-01-01T00:00:00Z" }),
+            EventMetadata::new(),
+        );
+        event.sequence_number = 2;
+
+        let view = PayloadDecoder::new().view(event);
+        assert_eq!(view.version, 2);
+        assert_eq!(view.current_schema_version, Some(1));
+        assert!(view.payload.is_some());
+        assert!(view.decode_error.is_none());
+    }
+
+    #[test]
+    fn test_view_keeps_raw_payload_when_decoding_fails() {
+        let event = Event::new(
+            Uuid::new_v4(),
+            "Order".to_string(),
+            "SomethingElse".to_string(),
+            1,
+            json!({ "anything": true }),
+            EventMetadata::new(),
+        );
+
+        let view = PayloadDecoder::new().view(event);
+        assert_eq!(view.raw_payload, json!({ "anything": true }));
+        assert!(view.payload.is_none());
+        assert_eq!(view.decode_error.as_deref(), Some("unknown event type SomethingElse"));
+    }
+}
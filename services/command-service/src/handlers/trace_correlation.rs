@@ -0,0 +1,95 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use event_store::Event;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::handlers::errors::ErrorResponse;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct TraceParams {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    500
+}
+
+#[derive(Debug, Serialize)]
+pub struct TraceCorrelationResponse {
+    pub correlation_id: Uuid,
+    pub aggregate_ids: Vec<Uuid>,
+    pub events: Vec<Event>,
+}
+
+/// Admin: list every event sharing a correlation ID across order, payment and inventory streams
+pub async fn handle(
+    State(state): State<AppState>,
+    Path(correlation_id): Path<Uuid>,
+    Query(params): Query<TraceParams>,
+) -> Result<(StatusCode, Json<TraceCorrelationResponse>), (StatusCode, Json<ErrorResponse>)> {
+    info!("Tracing events for correlation: {}", correlation_id);
+
+    if params.limit < 1 || params.limit > 5000 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new("Limit must be between 1 and 5000")),
+        ));
+    }
+
+    let events = match state
+        .event_store
+        .find_events_by_correlation_id(correlation_id, params.limit)
+        .await
+    {
+        Ok(events) => events,
+        Err(e) => {
+            error!("Failed to load events for correlation {}: {}", correlation_id, e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Failed to load events: {}", e))),
+            ));
+        }
+    };
+
+    if events.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("No events found for correlation: {}", correlation_id))),
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    let aggregate_ids = events
+        .iter()
+        .map(|event| event.aggregate_id)
+        .filter(|id| seen.insert(*id))
+        .collect();
+
+    Ok((
+        StatusCode::OK,
+        Json(TraceCorrelationResponse {
+            correlation_id,
+            aggregate_ids,
+            events,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_limit() {
+        let params: TraceParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(params.limit, 500);
+    }
+}
@@ -0,0 +1,168 @@
+use async_trait::async_trait;
+use domain::events::EventEnvelope;
+use messaging::{Job, JobError, JobHandler, NewJob, Publisher};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// Queue committed events wait in until they are published
+pub const OUTBOX_QUEUE: &str = "outbox";
+
+/// Outbox job publishing `envelope`, keyed by its aggregate
+///
+/// The job shares the event's ID, and jobs of an aggregate run one at a time in
+/// the order they were enqueued, so each aggregate's events reach Kafka in commit
+/// order while different aggregates are published in parallel.
+pub fn outbox_job(
+    aggregate_id: Uuid,
+    envelope: &EventEnvelope,
+) -> Result<NewJob, serde_json::Error> {
+    Ok(NewJob::new(OUTBOX_QUEUE, serde_json::to_value(envelope)?)
+        .with_id(envelope.event_id)
+        .with_ordering_key(aggregate_id.to_string()))
+}
+
+/// Publishes the events of the outbox queue to Kafka
+///
+/// A job is completed only after Kafka acknowledges the event; a failed publish is
+/// retried, holding back later events of the same aggregate until it goes through.
+pub struct OutboxRelay {
+    publisher: Arc<dyn Publisher>,
+}
+
+impl OutboxRelay {
+    pub fn new(publisher: Arc<dyn Publisher>) -> Self {
+        Self { publisher }
+    }
+}
+
+#[async_trait]
+impl JobHandler for OutboxRelay {
+    async fn handle(&self, job: &Job) -> Result<(), JobError> {
+        let aggregate_id = job
+            .ordering_key
+            .as_deref()
+            .and_then(|key| Uuid::parse_str(key).ok())
+            .ok_or_else(|| JobError::Rejected("outbox job without an aggregate ID".to_string()))?;
+        self.publisher
+            .publish(aggregate_id, &job.payload)
+            .await
+            .map_err(|e| JobError::Failed(e.to_string()))
+    }
+}
+
+/// Rows moved from `event_outbox` per statement
+const LEGACY_DRAIN_BATCH: i64 = 500;
+
+/// Move events from the `event_outbox` table of earlier releases onto the outbox queue
+///
+/// Replicas of the previous release keep writing `event_outbox` during a rolling
+/// deploy, so its rows are moved over, oldest first, for as long as the table exists;
+/// a later migration drops it. Returns how many rows were moved, or `None` once the
+/// table is gone.
+pub async fn drain_legacy_outbox(pool: &PgPool) -> Result<Option<u64>, sqlx::Error> {
+    let exists: bool = sqlx::query_scalar("SELECT to_regclass('event_outbox') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+    if !exists {
+        return Ok(None);
+    }
+
+    let moved = sqlx::query(
+        r#"
+        WITH moved AS (
+            DELETE FROM event_outbox
+            WHERE id IN (
+                SELECT id FROM event_outbox
+                ORDER BY id
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, event_id, aggregate_id, payload, created_at
+        )
+        INSERT INTO jobs (id, queue, payload, ordering_key, created_at)
+        SELECT event_id, $1, payload, aggregate_id::text, created_at
+        FROM moved
+        ORDER BY id
+        ON CONFLICT (id) DO NOTHING
+        "#,
+    )
+    .bind(OUTBOX_QUEUE)
+    .bind(LEGACY_DRAIN_BATCH)
+    .execute(pool)
+    .await?;
+    Ok(Some(moved.rows_affected()))
+}
+
+/// Drain `event_outbox` every `poll_interval` until the table is dropped
+pub fn spawn_legacy_drain(pool: PgPool, poll_interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            match drain_legacy_outbox(&pool).await {
+                Ok(None) => {
+                    info!("event_outbox is gone, stopping its drain");
+                    return;
+                }
+                Ok(Some(0)) => {}
+                Ok(Some(moved)) => {
+                    info!("Moved {} events from event_outbox to the outbox queue", moved)
+                }
+                Err(e) => error!("Failed to drain event_outbox: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::events::EventMetadata;
+    use messaging::{InMemoryJobStore, InMemoryPublisher, JobStore, JobWorker};
+
+    #[tokio::test]
+    async fn test_relay_publishes_queued_events() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let publisher = Arc::new(InMemoryPublisher::new("order-events"));
+        let aggregate_id = Uuid::new_v4();
+        let envelope = EventEnvelope {
+            event_id: Uuid::new_v4(),
+            aggregate_id,
+            aggregate_type: "Order".to_string(),
+            event_type: "OrderCreated".to_string(),
+            event_version: 1,
+            payload: serde_json::json!({ "total": 10 }),
+            metadata: EventMetadata::new(),
+            timestamp: chrono::Utc::now(),
+            sequence_number: Some(1),
+        };
+        store
+            .enqueue(outbox_job(aggregate_id, &envelope).unwrap())
+            .await
+            .unwrap();
+        store
+            .enqueue(outbox_job(aggregate_id, &envelope).unwrap())
+            .await
+            .unwrap();
+
+        let relay = Arc::new(OutboxRelay::new(publisher.clone()));
+        let ran = JobWorker::new(store.clone(), OUTBOX_QUEUE, relay)
+            .run_once()
+            .await
+            .unwrap();
+
+        assert_eq!(ran, 1);
+        assert!(store.is_empty());
+        let messages = publisher.messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].key, aggregate_id);
+        assert_eq!(
+            messages[0].payload["event_id"],
+            envelope.event_id.to_string()
+        );
+    }
+}
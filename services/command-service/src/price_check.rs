@@ -0,0 +1,179 @@
+use domain::commands::order_commands::CreateOrderItem;
+use read_model::{ProductView, ProductViewRepository, ReadModelError};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::handlers::errors::FieldError;
+
+/// Currency every order is priced in (see `OrderAggregate::create`)
+const ORDER_CURRENCY: &str = "USD";
+
+/// Checks submitted order lines against the product catalog projection
+pub struct PriceVerifier {
+    products: Arc<dyn ProductViewRepository>,
+    /// Allowed deviation from the catalog price, in percent
+    tolerance_percent: f64,
+}
+
+impl PriceVerifier {
+    pub fn new(products: Arc<dyn ProductViewRepository>) -> Self {
+        Self {
+            products,
+            tolerance_percent: 0.0,
+        }
+    }
+
+    pub fn with_tolerance_percent(mut self, tolerance_percent: f64) -> Self {
+        self.tolerance_percent = tolerance_percent.max(0.0);
+        self
+    }
+
+    /// Every reason the items cannot be ordered as submitted; empty if they all check out
+    pub async fn verify(
+        &self,
+        items: &[CreateOrderItem],
+    ) -> Result<Vec<FieldError>, ReadModelError> {
+        let product_ids: Vec<Uuid> = items.iter().map(|item| item.product_id).collect();
+        let products = self.products.get_by_ids(&product_ids).await?;
+        Ok(rejection_reasons(items, &products, self.tolerance_percent))
+    }
+}
+
+fn rejection_reasons(
+    items: &[CreateOrderItem],
+    products: &[ProductView],
+    tolerance_percent: f64,
+) -> Vec<FieldError> {
+    let catalog: HashMap<Uuid, &ProductView> = products
+        .iter()
+        .map(|product| (product.product_id, product))
+        .collect();
+
+    let mut reasons = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let field = |name: &str| format!("items[{}].{}", index, name);
+
+        let Some(product) = catalog.get(&item.product_id) else {
+            reasons.push(FieldError::new(
+                field("product_id"),
+                "unknown_product",
+                format!("Product {} does not exist", item.product_id),
+            ));
+            continue;
+        };
+
+        if product.sku != item.sku {
+            reasons.push(FieldError::new(
+                field("sku"),
+                "sku_mismatch",
+                format!("SKU {} does not match product SKU {}", item.sku, product.sku),
+            ));
+        }
+
+        if !product.active {
+            reasons.push(FieldError::new(
+                field("product_id"),
+                "product_discontinued",
+                format!("Product {} is discontinued", product.sku),
+            ));
+        }
+
+        if product.currency != ORDER_CURRENCY {
+            reasons.push(FieldError::new(
+                field("unit_price"),
+                "currency_mismatch",
+                format!(
+                    "Product {} is priced in {}, orders are priced in {}",
+                    product.sku, product.currency, ORDER_CURRENCY
+                ),
+            ));
+        } else if !within_tolerance(item.unit_price, product.unit_price, tolerance_percent) {
+            reasons.push(FieldError::new(
+                field("unit_price"),
+                "price_mismatch",
+                format!(
+                    "Unit price {:.2} does not match catalog price {:.2} for {}",
+                    item.unit_price, product.unit_price, product.sku
+                ),
+            ));
+        }
+    }
+
+    reasons
+}
+
+fn within_tolerance(submitted: f64, catalog: f64, tolerance_percent: f64) -> bool {
+    // Half a cent absorbs rounding in the catalog's DECIMAL to f64 conversion
+    let allowed = (catalog.abs() * tolerance_percent / 100.0).max(0.005);
+    (submitted - catalog).abs() <= allowed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn product(unit_price: f64) -> ProductView {
+        ProductView {
+            product_id: Uuid::new_v4(),
+            sku: "SKU-001".to_string(),
+            name: "Widget".to_string(),
+            description: None,
+            unit_price,
+            currency: "USD".to_string(),
+            active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn item(product: &ProductView, unit_price: f64) -> CreateOrderItem {
+        CreateOrderItem {
+            product_id: product.product_id,
+            sku: product.sku.clone(),
+            quantity: 1,
+            unit_price,
+        }
+    }
+
+    #[test]
+    fn test_matching_items_pass() {
+        let widget = product(19.99);
+        assert!(rejection_reasons(&[item(&widget, 19.99)], &[widget], 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_price_tolerance() {
+        let widget = product(100.0);
+        let items = [item(&widget, 99.0)];
+        let products = [widget];
+
+        let reasons = rejection_reasons(&items, &products, 0.5);
+        assert_eq!(reasons.len(), 1);
+        assert_eq!(reasons[0].field, "items[0].unit_price");
+        assert_eq!(reasons[0].code, "price_mismatch");
+
+        assert!(rejection_reasons(&items, &products, 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_every_rejection_reason_is_reported() {
+        let mut discontinued = product(10.0);
+        discontinued.active = false;
+        let mut wrong_sku = item(&discontinued, 10.0);
+        wrong_sku.sku = "SKU-999".to_string();
+        let unknown = CreateOrderItem {
+            product_id: Uuid::new_v4(),
+            sku: "SKU-404".to_string(),
+            quantity: 1,
+            unit_price: 1.0,
+        };
+
+        let codes: Vec<String> = rejection_reasons(&[wrong_sku, unknown], &[discontinued], 0.0)
+            .into_iter()
+            .map(|reason| reason.code)
+            .collect();
+        assert_eq!(codes, vec!["sku_mismatch", "product_discontinued", "unknown_product"]);
+    }
+}
@@ -1,12 +1,20 @@
 use axum::{
     http::StatusCode,
+    middleware,
     response::IntoResponse,
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
     Router,
 };
-use common::metrics;
+use common::api_version::{self, ApiVersion};
+use common::{admin_auth, http_metrics, metrics};
 
-use crate::handlers::{cancel_order, confirm_order, create_order, deliver_order, health, ship_order};
+use crate::admission;
+use crate::handlers::{
+    aggregate_state, bulk_ship_orders, cancel_order, carrier_webhook, causation_graph,
+    confirm_order, correct_item_price, create_order, delete_order, deliver_order, health,
+    saga_approvals, saga_definitions, saga_interventions, ship_order, stream_events,
+    trace_correlation,
+};
 use crate::state::AppState;
 
 /// Prometheus metrics endpoint handler
@@ -20,15 +28,75 @@ async fn metrics_handler() -> impl IntoResponse {
     }
 }
 
+/// API versions the service serves, each under `/api/vN`
+pub const API_VERSIONS: &[ApiVersion] = &[ApiVersion::V1];
+
 /// Build the application router with all routes
 pub fn build_router(state: AppState) -> Router {
     Router::new()
         .route("/health", get(health::health_check))
+        .route("/ready", get(health::readiness_check))
         .route("/metrics", get(metrics_handler))
-        .route("/api/v1/orders", post(create_order::handle))
-        .route("/api/v1/orders/:id/confirm", put(confirm_order::handle))
-        .route("/api/v1/orders/:id/cancel", put(cancel_order::handle))
-        .route("/api/v1/orders/:id/ship", put(ship_order::handle))
-        .route("/api/v1/orders/:id/deliver", put(deliver_order::handle))
+        .merge(api_version::mount(API_VERSIONS, |version| {
+            api_routes(state.clone(), version)
+        }))
+        .route_layer(middleware::from_fn(http_metrics::track_http_metrics))
         .with_state(state)
 }
+
+/// Routes of one API version, relative to its `/api/vN` prefix
+fn api_routes(state: AppState, _version: ApiVersion) -> Router<AppState> {
+    // Shed first when the event store or Kafka falls behind
+    let non_critical = Router::new()
+        .route("/orders", post(create_order::handle))
+        .route("/orders/bulk/ship", post(bulk_ship_orders::handle))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            admission::admit_non_critical,
+        ));
+
+    // Expose stored event data, or change orders and sagas outside their normal
+    // flow, so only with the admin token
+    let protected_admin = Router::new()
+        .route(
+            "/admin/streams/:aggregate_id/events",
+            get(stream_events::handle),
+        )
+        .route(
+            "/admin/events/correlation/:correlation_id",
+            get(trace_correlation::handle),
+        )
+        .route(
+            "/admin/events/correlation/:correlation_id/graph",
+            get(causation_graph::handle),
+        )
+        .route("/admin/aggregates/order/:id", get(aggregate_state::get_order))
+        .route(
+            "/admin/aggregates/order/:id/history",
+            get(aggregate_state::get_order_at),
+        )
+        .route(
+            "/admin/orders/:id/corrections/item-price",
+            post(correct_item_price::handle),
+        )
+        .route("/orders/:id", delete(delete_order::handle))
+        .route("/admin/sagas/interventions", get(saga_interventions::list))
+        .route("/admin/sagas/:saga_id/resolve", post(saga_interventions::resolve))
+        .route("/admin/sagas/approvals", get(saga_approvals::list))
+        .route("/admin/sagas/:saga_id/approve", post(saga_approvals::approve))
+        .route("/admin/sagas/:saga_id/reject", post(saga_approvals::reject))
+        .route_layer(middleware::from_fn_with_state(
+            state.admin_tokens.clone(),
+            admin_auth::require_admin_token,
+        ));
+
+    Router::new()
+        .merge(non_critical)
+        .merge(protected_admin)
+        .route("/orders/:id/confirm", put(confirm_order::handle))
+        .route("/orders/:id/cancel", put(cancel_order::handle))
+        .route("/orders/:id/ship", put(ship_order::handle))
+        .route("/orders/:id/deliver", put(deliver_order::handle))
+        .route("/webhooks/carriers/:carrier", post(carrier_webhook::handle))
+        .route("/admin/sagas/definitions", get(saga_definitions::list))
+}
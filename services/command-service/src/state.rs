@@ -1,22 +1,70 @@
 use anyhow::Result;
+use common::admin_auth::{AdminToken, AdminTokens};
+use common::bulkhead::{Bulkhead, BulkheadConfig};
 use common::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
-use event_store::{EventStore, IdempotencyChecker, PostgresEventStore};
-use messaging::EventPublisher;
+use common::circuit_breaker_registry::CircuitBreakerRegistry;
+use common::redis_connection::RedisConfig;
+use event_store::{
+    AggregateTypeRegistry, EventStore, IdempotencyBackend, IdempotencyChecker, IdempotencyStore,
+    InMemoryEventStore, PayloadValidator, PgIdempotencyStore, PostgresEventStore,
+};
+use messaging::{EventPublisher, InMemoryPublisher, Publisher, TopicManager, TopicSettings};
+use read_model::{PostgresProductViewRepository, ProductViewRepository};
+use saga::repository::PostgresSagaRepository;
+use saga::{
+    InMemorySagaRepository, PublisherEventSink, SagaCoordinator, SagaEventSink, SagaRepository,
+    StaticKeyProvider,
+};
 use sqlx::PgPool;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::info;
 
+use crate::admission::{AdmissionConfig, AdmissionController};
+use crate::aggregate_cache::AggregateCache;
+use crate::carriers::CarrierRegistry;
+use crate::delivery_tracker::DeliveryTracker;
+use crate::price_check::PriceVerifier;
+use crate::unit_of_work::UnitOfWorkFactory;
+
+/// Name of the breaker guarding publishes to Kafka
+pub const KAFKA_PUBLISHER_BREAKER: &str = "kafka-publisher";
+
 /// Application state shared across handlers
 #[derive(Clone)]
 pub struct AppState {
     pub event_store: Arc<dyn EventStore>,
-    pub event_publisher: Arc<EventPublisher>,
-    pub idempotency_checker: Option<Arc<IdempotencyChecker>>,
+    /// Transactions spanning the event append and its outbox entry
+    pub unit_of_work: Arc<UnitOfWorkFactory>,
+    pub event_publisher: Arc<dyn Publisher>,
+    /// Redis or Postgres, per `IDEMPOTENCY_BACKEND`; `None` when disabled
+    pub idempotency_store: Option<Arc<dyn IdempotencyStore>>,
     pub kafka_circuit_breaker: Arc<CircuitBreaker>,
+    /// Breakers for every dependency, reported by the readiness endpoint
+    pub circuit_breakers: Arc<CircuitBreakerRegistry>,
+    pub aggregate_cache: Arc<AggregateCache>,
+    pub saga_repository: Arc<dyn SagaRepository>,
+    /// Applies admin decisions on sagas, emitting their lifecycle events
+    pub saga_coordinator: Arc<SagaCoordinator<dyn SagaRepository>>,
+    /// Checks order lines against the product catalog; `None` when disabled
+    pub price_verifier: Option<Arc<PriceVerifier>>,
+    /// Sheds non-critical commands under load; `None` when disabled
+    pub admission: Option<Arc<AdmissionController>>,
+    /// Bearer tokens for admin routes, each naming the admin it belongs to; the
+    /// routes are refused while there are none
+    pub admin_tokens: AdminTokens,
+    /// Delivers shipped orders from carrier tracking updates; `None` when disabled
+    pub delivery_tracker: Option<Arc<DeliveryTracker>>,
+    /// Shared secret carriers present on tracking webhooks; they are refused without one
+    pub carrier_webhook_token: Option<Arc<str>>,
 }
 
 impl AppState {
+    /// State backed by in-memory stores unless given others, for tests
+    pub fn builder() -> AppStateBuilder {
+        AppStateBuilder::default()
+    }
+
     /// Create a new application state
     pub async fn new() -> Result<Self> {
         dotenv::dotenv().ok();
@@ -30,55 +78,479 @@ impl AppState {
         let kafka_topic = std::env::var("KAFKA_TOPIC")
             .unwrap_or_else(|_| "order-events".to_string());
 
-        let redis_url = std::env::var("REDIS_URL")
-            .unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let saga_events_topic = std::env::var("SAGA_EVENTS_TOPIC")
+            .unwrap_or_else(|_| "saga-events".to_string());
+
+        let manage_topics = std::env::var("KAFKA_MANAGE_TOPICS")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .unwrap_or(true);
+
+        let redis = RedisConfig::from_env();
 
         let enable_idempotency = std::env::var("ENABLE_IDEMPOTENCY")
             .unwrap_or_else(|_| "false".to_string())
             .parse()
             .unwrap_or(false);
 
+        let idempotency_cleanup_interval_secs: u64 = std::env::var("IDEMPOTENCY_CLEANUP_INTERVAL_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .unwrap_or(300);
+
+        let enable_hash_chaining = std::env::var("ENABLE_HASH_CHAINING")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+
+        let enable_schema_validation = std::env::var("ENABLE_SCHEMA_VALIDATION")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+
+        let enforce_aggregate_types = std::env::var("ENFORCE_AGGREGATE_TYPES")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .unwrap_or(true);
+
+        let slow_append_threshold_ms: u64 = std::env::var("SLOW_APPEND_THRESHOLD_MS")
+            .unwrap_or_else(|_| "500".to_string())
+            .parse()
+            .unwrap_or(500);
+
+        let aggregate_cache_size: usize = std::env::var("AGGREGATE_CACHE_SIZE")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()
+            .unwrap_or(1000);
+
+        let enable_price_verification = std::env::var("ENABLE_PRICE_VERIFICATION")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+
+        let price_tolerance_percent: f64 = std::env::var("PRICE_TOLERANCE_PERCENT")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .unwrap_or(0.0);
+
+        let enable_admission_control = std::env::var("ENABLE_ADMISSION_CONTROL")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .unwrap_or(true);
+
+        let admission_latency_threshold_ms: u64 = std::env::var("ADMISSION_LATENCY_THRESHOLD_MS")
+            .unwrap_or_else(|_| "250".to_string())
+            .parse()
+            .unwrap_or(250);
+
+        let admission_backlog_threshold: usize = std::env::var("ADMISSION_BACKLOG_THRESHOLD")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()
+            .unwrap_or(1000);
+
+        let admission_max_delay_ms: u64 = std::env::var("ADMISSION_MAX_DELAY_MS")
+            .unwrap_or_else(|_| "200".to_string())
+            .parse()
+            .unwrap_or(200);
+
+        let admission_retry_after_secs: u64 = std::env::var("ADMISSION_RETRY_AFTER_SECS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .unwrap_or(5);
+
+        let db_max_concurrent_appends: usize = std::env::var("DB_MAX_CONCURRENT_APPENDS")
+            .unwrap_or_else(|_| "32".to_string())
+            .parse()
+            .unwrap_or(32);
+
+        let kafka_max_concurrent_publishes: usize = std::env::var("KAFKA_MAX_CONCURRENT_PUBLISHES")
+            .unwrap_or_else(|_| "64".to_string())
+            .parse()
+            .unwrap_or(64);
+
+        let kafka_healthcheck_timeout_ms: u64 = std::env::var("KAFKA_HEALTHCHECK_TIMEOUT_MS")
+            .unwrap_or_else(|_| "3000".to_string())
+            .parse()
+            .unwrap_or(3000);
+
+        let bulkhead_max_wait_ms: u64 = std::env::var("BULKHEAD_MAX_WAIT_MS")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()
+            .unwrap_or(1000);
+
+        let enable_delivery_tracking = std::env::var("ENABLE_DELIVERY_TRACKING")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+
+        let mock_carrier_delivery_secs: u64 = std::env::var("MOCK_CARRIER_DELIVERY_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .unwrap_or(300);
+
         info!("Connecting to database: {}", database_url);
         let pool = PgPool::connect(&database_url).await?;
 
-        info!("Creating event store");
-        let event_store = Arc::new(PostgresEventStore::new(pool)) as Arc<dyn EventStore>;
+        info!(
+            "Creating event store (hash chaining: {}, schema validation: {}, aggregate types: {})",
+            enable_hash_chaining, enable_schema_validation, enforce_aggregate_types
+        );
+        // Saga states may be encrypted by the orchestrator; reading them needs its keys
+        let mut saga_repository = PostgresSagaRepository::new(pool.clone());
+        if let Some(keys) = StaticKeyProvider::from_env()? {
+            saga_repository = saga_repository.with_encryption(Arc::new(keys));
+        }
+        let saga_repository = Arc::new(saga_repository) as Arc<dyn SagaRepository>;
 
-        info!("Creating Kafka event publisher");
-        let event_publisher = Arc::new(EventPublisher::new(&kafka_brokers, kafka_topic)?);
+        // Prices are checked against the product projection in the same database
+        let price_verifier = if enable_price_verification {
+            info!("Price verification enabled (tolerance: {}%)", price_tolerance_percent);
+            let products = Arc::new(PostgresProductViewRepository::new(pool.clone()))
+                as Arc<dyn ProductViewRepository>;
+            Some(Arc::new(
+                PriceVerifier::new(products).with_tolerance_percent(price_tolerance_percent),
+            ))
+        } else {
+            info!("Price verification disabled");
+            None
+        };
+
+        let delivery_tracker = if enable_delivery_tracking {
+            let carriers = CarrierRegistry::from_names(
+                &std::env::var("MOCK_CARRIERS").unwrap_or_default(),
+                &std::env::var("WEBHOOK_CARRIERS").unwrap_or_default(),
+                Duration::from_secs(mock_carrier_delivery_secs),
+            );
+            if carriers.is_empty() {
+                tracing::warn!("Delivery tracking enabled but no carriers configured");
+            }
+            info!("Delivery tracking enabled");
+            Some(Arc::new(DeliveryTracker::new(pool.clone(), carriers)))
+        } else {
+            info!("Delivery tracking disabled");
+            None
+        };
 
-        // Initialize idempotency checker if enabled
-        let idempotency_checker = if enable_idempotency {
+        let admission = if enable_admission_control {
+            info!(
+                "Admission control enabled (append latency: {}ms, outbox backlog: {})",
+                admission_latency_threshold_ms, admission_backlog_threshold
+            );
+            Some(Arc::new(AdmissionController::new(AdmissionConfig {
+                latency_threshold: Duration::from_millis(admission_latency_threshold_ms),
+                backlog_threshold: admission_backlog_threshold,
+                max_delay: Duration::from_millis(admission_max_delay_ms),
+                retry_after: Duration::from_secs(admission_retry_after_secs),
+                ..AdmissionConfig::default()
+            })))
+        } else {
+            info!("Admission control disabled");
+            None
+        };
+
+        // Bulkheads keep a slow database or broker from tying up every request task
+        info!(
+            "Bulkheads: {} concurrent appends, {} concurrent Kafka publishes",
+            db_max_concurrent_appends, kafka_max_concurrent_publishes
+        );
+        let append_bulkhead = Arc::new(Bulkhead::new(
+            "event-store-append".to_string(),
+            BulkheadConfig {
+                max_concurrent: db_max_concurrent_appends,
+                max_wait: Duration::from_millis(bulkhead_max_wait_ms),
+            },
+        ));
+        let publish_bulkhead = Arc::new(Bulkhead::new(
+            "kafka-publish".to_string(),
+            BulkheadConfig {
+                max_concurrent: kafka_max_concurrent_publishes,
+                max_wait: Duration::from_millis(bulkhead_max_wait_ms),
+            },
+        ));
+
+        let mut event_store = PostgresEventStore::new(pool.clone())
+            .with_hash_chaining(enable_hash_chaining)
+            .with_slow_append_threshold(Duration::from_millis(slow_append_threshold_ms))
+            .with_append_bulkhead(append_bulkhead);
+        if enable_schema_validation {
+            let validator = PayloadValidator::from_event_schemas()?;
+            event_store = event_store.with_payload_validator(Arc::new(validator));
+        }
+        if enforce_aggregate_types {
+            event_store =
+                event_store.with_aggregate_types(Arc::new(AggregateTypeRegistry::from_domain()));
+        }
+        if let Some(admission) = admission.clone() {
+            event_store = event_store
+                .with_append_observer(Arc::new(move |elapsed| admission.record_append_latency(elapsed)));
+        }
+        let event_store = Arc::new(event_store);
+        let unit_of_work = Arc::new(UnitOfWorkFactory::new(pool.clone(), event_store.clone()));
+        let event_store = event_store as Arc<dyn EventStore>;
+
+        // Create the order topic with explicit settings rather than leaving it to broker auto-create
+        if manage_topics {
+            let spec = TopicSettings::from_env().spec(&kafka_topic);
+            let ensured = async { TopicManager::new(&kafka_brokers)?.ensure(&[spec]).await }.await;
+            if let Err(e) = ensured {
+                tracing::warn!("Failed to ensure Kafka topics, continuing with them as they are: {}", e);
+            }
+        }
+
+        // Resolving an intervention emits the saga's lifecycle event like the orchestrator does
+        let saga_event_sink = Arc::new(PublisherEventSink::new(Arc::new(EventPublisher::new(
+            &kafka_brokers,
+            saga_events_topic,
+        )?)));
+
+        // Initialize the idempotency store if enabled
+        let idempotency_store = if !enable_idempotency {
+            info!("Idempotency checking disabled");
+            None
+        } else if IdempotencyBackend::from_env() == IdempotencyBackend::Postgres {
+            info!("Initializing idempotency store with Postgres");
+            let store = Arc::new(PgIdempotencyStore::new(pool.clone(), 3600));
+            store
+                .clone()
+                .spawn_cleanup(Duration::from_secs(idempotency_cleanup_interval_secs.max(1)));
+            Some(store as Arc<dyn IdempotencyStore>)
+        } else {
             info!("Initializing idempotency checker with Redis");
-            match IdempotencyChecker::new(&redis_url, 3600) {
-                Ok(checker) => Some(Arc::new(checker)),
+            match IdempotencyChecker::with_config(redis, 3600) {
+                Ok(checker) => Some(Arc::new(checker) as Arc<dyn IdempotencyStore>),
                 Err(e) => {
                     tracing::warn!("Failed to initialize idempotency checker: {}. Continuing without idempotency.", e);
                     None
                 }
             }
-        } else {
-            info!("Idempotency checking disabled");
-            None
         };
 
-        // Initialize circuit breaker for Kafka
+        // Initialize circuit breakers, overridable per dependency from the environment
         info!("Initializing circuit breaker for Kafka");
-        let kafka_circuit_breaker = Arc::new(CircuitBreaker::new(
-            "kafka-publisher".to_string(),
-            CircuitBreakerConfig {
-                failure_threshold: 5,
-                success_threshold: 2,
-                timeout: Duration::from_secs(5),
-                half_open_timeout: Duration::from_secs(30),
-            },
-        ));
+        let circuit_breakers = Arc::new(
+            CircuitBreakerRegistry::default()
+                .with_config(
+                    KAFKA_PUBLISHER_BREAKER,
+                    CircuitBreakerConfig {
+                        failure_threshold: 5,
+                        success_threshold: 2,
+                        timeout: Duration::from_secs(5),
+                        half_open_timeout: Duration::from_secs(30),
+                        ..Default::default()
+                    },
+                )
+                .with_env_overrides(&[KAFKA_PUBLISHER_BREAKER]),
+        );
+        let kafka_circuit_breaker = circuit_breakers.get(KAFKA_PUBLISHER_BREAKER);
+
+        // Each publish attempt goes through the breaker, whose timeout bounds it
+        info!("Creating Kafka event publisher");
+        let event_publisher = Arc::new(
+            EventPublisher::new(&kafka_brokers, kafka_topic)?
+                .with_bulkhead(publish_bulkhead)
+                .with_circuit_breaker(kafka_circuit_breaker.clone())
+                .with_healthcheck_timeout(Duration::from_millis(kafka_healthcheck_timeout_ms)),
+        );
+
+        // Warm the breaker up with a metadata check so a misconfigured broker shows
+        // up at startup rather than on the first publish
+        let warmup = kafka_circuit_breaker
+            .call(async {
+                let health = event_publisher.healthcheck().await?;
+                if health.is_healthy() {
+                    Ok(health)
+                } else {
+                    Err(anyhow::anyhow!("topic not ready: {:?}", health))
+                }
+            })
+            .await;
+        match warmup {
+            Ok(health) => info!(
+                "Kafka reachable: {} brokers, {} partitions",
+                health.brokers, health.partitions
+            ),
+            Err(e) => tracing::warn!("Kafka health check failed at startup: {}", e),
+        }
+
+        let carrier_webhook_token = std::env::var("CARRIER_WEBHOOK_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty());
+
+        info!("Aggregate cache capacity: {}", aggregate_cache_size);
+        let aggregate_cache = Arc::new(AggregateCache::new(aggregate_cache_size));
+
+        let mut builder = AppState::builder()
+            .with_event_store(event_store)
+            .with_unit_of_work(unit_of_work)
+            .with_event_publisher(event_publisher)
+            .with_kafka_circuit_breaker(kafka_circuit_breaker)
+            .with_circuit_breakers(circuit_breakers)
+            .with_aggregate_cache(aggregate_cache)
+            .with_saga_repository(saga_repository)
+            .with_saga_event_sink(saga_event_sink);
+        if let Some(store) = idempotency_store {
+            builder = builder.with_idempotency_store(store);
+        }
+        if let Some(price_verifier) = price_verifier {
+            builder = builder.with_price_verifier(price_verifier);
+        }
+        if let Some(admission) = admission {
+            builder = builder.with_admission(admission);
+        }
+        for token in AdminToken::from_env() {
+            builder = builder.with_named_admin_token(token);
+        }
+        if let Some(tracker) = delivery_tracker {
+            builder = builder.with_delivery_tracker(tracker);
+        }
+        if let Some(token) = carrier_webhook_token {
+            builder = builder.with_carrier_webhook_token(&token);
+        }
+
+        Ok(builder.build())
+    }
+}
+
+/// Builds an [`AppState`] from the dependencies given, without reading the environment
+///
+/// Anything not set gets an in-memory or disabled default: events go to an
+/// [`InMemoryEventStore`] and an [`InMemoryPublisher`], sagas to an
+/// [`InMemorySagaRepository`], and optional features such as admission control and
+/// price verification are off.
+#[derive(Default)]
+pub struct AppStateBuilder {
+    event_store: Option<Arc<dyn EventStore>>,
+    unit_of_work: Option<Arc<UnitOfWorkFactory>>,
+    event_publisher: Option<Arc<dyn Publisher>>,
+    idempotency_store: Option<Arc<dyn IdempotencyStore>>,
+    kafka_circuit_breaker: Option<Arc<CircuitBreaker>>,
+    circuit_breakers: Option<Arc<CircuitBreakerRegistry>>,
+    aggregate_cache: Option<Arc<AggregateCache>>,
+    saga_repository: Option<Arc<dyn SagaRepository>>,
+    saga_event_sink: Option<Arc<dyn SagaEventSink>>,
+    price_verifier: Option<Arc<PriceVerifier>>,
+    admission: Option<Arc<AdmissionController>>,
+    admin_tokens: Vec<AdminToken>,
+    delivery_tracker: Option<Arc<DeliveryTracker>>,
+    carrier_webhook_token: Option<Arc<str>>,
+}
+
+impl AppStateBuilder {
+    pub fn with_event_store(mut self, event_store: Arc<dyn EventStore>) -> Self {
+        self.event_store = Some(event_store);
+        self
+    }
+
+    /// Units of work to record commands through; by default events are appended to
+    /// the event store and published when a unit of work commits
+    pub fn with_unit_of_work(mut self, unit_of_work: Arc<UnitOfWorkFactory>) -> Self {
+        self.unit_of_work = Some(unit_of_work);
+        self
+    }
+
+    pub fn with_event_publisher(mut self, event_publisher: Arc<dyn Publisher>) -> Self {
+        self.event_publisher = Some(event_publisher);
+        self
+    }
+
+    pub fn with_idempotency_store(mut self, store: Arc<dyn IdempotencyStore>) -> Self {
+        self.idempotency_store = Some(store);
+        self
+    }
+
+    pub fn with_kafka_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.kafka_circuit_breaker = Some(breaker);
+        self
+    }
+
+    pub fn with_circuit_breakers(mut self, registry: Arc<CircuitBreakerRegistry>) -> Self {
+        self.circuit_breakers = Some(registry);
+        self
+    }
+
+    pub fn with_aggregate_cache(mut self, cache: Arc<AggregateCache>) -> Self {
+        self.aggregate_cache = Some(cache);
+        self
+    }
+
+    pub fn with_saga_repository(mut self, saga_repository: Arc<dyn SagaRepository>) -> Self {
+        self.saga_repository = Some(saga_repository);
+        self
+    }
+
+    /// Where admin decisions on sagas emit lifecycle events; none are emitted by default
+    pub fn with_saga_event_sink(mut self, sink: Arc<dyn SagaEventSink>) -> Self {
+        self.saga_event_sink = Some(sink);
+        self
+    }
+
+    pub fn with_price_verifier(mut self, price_verifier: Arc<PriceVerifier>) -> Self {
+        self.price_verifier = Some(price_verifier);
+        self
+    }
+
+    pub fn with_admission(mut self, admission: Arc<AdmissionController>) -> Self {
+        self.admission = Some(admission);
+        self
+    }
+
+    /// Admin token of a named admin, recorded on the decisions they make
+    pub fn with_named_admin_token(mut self, token: AdminToken) -> Self {
+        self.admin_tokens.push(token);
+        self
+    }
+
+    pub fn with_delivery_tracker(mut self, tracker: Arc<DeliveryTracker>) -> Self {
+        self.delivery_tracker = Some(tracker);
+        self
+    }
+
+    pub fn with_carrier_webhook_token(mut self, token: &str) -> Self {
+        self.carrier_webhook_token = Some(Arc::from(token));
+        self
+    }
+
+    pub fn build(self) -> AppState {
+        let event_store = self
+            .event_store
+            .unwrap_or_else(|| Arc::new(InMemoryEventStore::new()));
+        let event_publisher = self
+            .event_publisher
+            .unwrap_or_else(|| Arc::new(InMemoryPublisher::new("order-events")));
+        let circuit_breakers = self.circuit_breakers.unwrap_or_default();
+        let saga_repository = self
+            .saga_repository
+            .unwrap_or_else(|| Arc::new(InMemorySagaRepository::new()));
+        let mut saga_coordinator = SagaCoordinator::new(saga_repository.clone());
+        if let Some(sink) = self.saga_event_sink {
+            saga_coordinator = saga_coordinator.with_event_sink(sink);
+        }
+        let unit_of_work = self.unit_of_work.unwrap_or_else(|| {
+            Arc::new(UnitOfWorkFactory::in_memory(
+                event_store.clone(),
+                event_publisher.clone(),
+            ))
+        });
 
-        Ok(Self {
+        AppState {
             event_store,
+            unit_of_work,
             event_publisher,
-            idempotency_checker,
-            kafka_circuit_breaker,
-        })
+            idempotency_store: self.idempotency_store,
+            kafka_circuit_breaker: self
+                .kafka_circuit_breaker
+                .unwrap_or_else(|| circuit_breakers.get(KAFKA_PUBLISHER_BREAKER)),
+            circuit_breakers,
+            aggregate_cache: self
+                .aggregate_cache
+                .unwrap_or_else(|| Arc::new(AggregateCache::new(1000))),
+            saga_repository,
+            saga_coordinator: Arc::new(saga_coordinator),
+            price_verifier: self.price_verifier,
+            admission: self.admission,
+            admin_tokens: AdminTokens::from(self.admin_tokens),
+            delivery_tracker: self.delivery_tracker,
+            carrier_webhook_token: self.carrier_webhook_token,
+        }
     }
 }
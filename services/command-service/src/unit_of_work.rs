@@ -0,0 +1,207 @@
+use common::deadline;
+use domain::events::EventEnvelope;
+use event_store::{DeleteMode, Event, EventStore, EventStoreError, PostgresEventStore};
+use messaging::{PgJobStore, Publisher};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::outbox::outbox_job;
+
+enum Backend {
+    Postgres {
+        pool: PgPool,
+        event_store: Arc<PostgresEventStore>,
+    },
+    /// Appends on commit and publishes straight away, without an outbox
+    InMemory {
+        event_store: Arc<dyn EventStore>,
+        publisher: Arc<dyn Publisher>,
+    },
+}
+
+/// Opens one Postgres transaction per command
+///
+/// The event append, its processed-command record and the outbox entry the relay
+/// publishes from all go through the same transaction, so a command either happens
+/// completely or not at all.
+pub struct UnitOfWorkFactory {
+    backend: Backend,
+    outbox_wake: Arc<Notify>,
+}
+
+impl UnitOfWorkFactory {
+    pub fn new(pool: PgPool, event_store: Arc<PostgresEventStore>) -> Self {
+        Self {
+            backend: Backend::Postgres { pool, event_store },
+            outbox_wake: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Units of work over any event store, for tests and local demos
+    ///
+    /// Events are appended when the unit of work commits, then published. Appends to
+    /// several aggregates are not atomic, and a failed publish is only logged.
+    pub fn in_memory(event_store: Arc<dyn EventStore>, publisher: Arc<dyn Publisher>) -> Self {
+        Self {
+            backend: Backend::InMemory {
+                event_store,
+                publisher,
+            },
+            outbox_wake: Arc::new(Notify::new()),
+        }
+    }
+
+    /// The database the outbox lives in; `None` without one
+    pub fn pool(&self) -> Option<&PgPool> {
+        match &self.backend {
+            Backend::Postgres { pool, .. } => Some(pool),
+            Backend::InMemory { .. } => None,
+        }
+    }
+
+    /// Woken after each commit that left events in the outbox
+    pub fn outbox_wake(&self) -> Arc<Notify> {
+        self.outbox_wake.clone()
+    }
+
+    pub async fn begin(&self) -> Result<UnitOfWork<'_>, EventStoreError> {
+        let tx = match &self.backend {
+            Backend::Postgres { pool, .. } => {
+                Some(deadline::within("event_store.begin", pool.begin()).await??)
+            }
+            Backend::InMemory { .. } => None,
+        };
+        Ok(UnitOfWork {
+            tx,
+            pending: Vec::new(),
+            factory: self,
+            enqueued: false,
+        })
+    }
+}
+
+/// A change held back until commit when there is no transaction
+enum Pending {
+    Append(Uuid, i64, Vec<EventEnvelope>),
+    /// Delete the stream, then publish the envelope announcing it
+    Delete(Uuid, DeleteMode, Box<EventEnvelope>),
+}
+
+/// A command's transaction; dropped without [`UnitOfWork::commit`] it rolls back
+pub struct UnitOfWork<'a> {
+    tx: Option<Transaction<'static, Postgres>>,
+    pending: Vec<Pending>,
+    factory: &'a UnitOfWorkFactory,
+    enqueued: bool,
+}
+
+impl UnitOfWork<'_> {
+    /// Append `envelopes` to the aggregate's stream and queue them for publishing
+    pub async fn record(
+        &mut self,
+        aggregate_id: Uuid,
+        expected_version: i64,
+        envelopes: &[EventEnvelope],
+    ) -> Result<(), EventStoreError> {
+        let (Some(tx), Backend::Postgres { event_store, .. }) = (&mut self.tx, &self.factory.backend)
+        else {
+            self.pending
+                .push(Pending::Append(aggregate_id, expected_version, envelopes.to_vec()));
+            return Ok(());
+        };
+
+        let append = async {
+            let events = envelopes.iter().map(Event::from).collect();
+            event_store
+                .append_events_in(tx, aggregate_id, expected_version, events)
+                .await?;
+
+            for envelope in envelopes {
+                PgJobStore::enqueue_in(tx, &outbox_job(aggregate_id, envelope)?).await?;
+            }
+            Ok::<_, EventStoreError>(())
+        };
+        deadline::within("event_store.append", append).await??;
+        self.enqueued |= !envelopes.is_empty();
+
+        Ok(())
+    }
+
+    /// Delete the aggregate's stream and queue `envelope`, which announces the
+    /// delete to projections, for publishing
+    ///
+    /// The announcement commits with the delete, so the relay keeps retrying it
+    /// until projections have been told to drop the aggregate.
+    pub async fn delete_stream(
+        &mut self,
+        aggregate_id: Uuid,
+        mode: DeleteMode,
+        envelope: &EventEnvelope,
+    ) -> Result<(), EventStoreError> {
+        let (Some(tx), Backend::Postgres { event_store, .. }) = (&mut self.tx, &self.factory.backend)
+        else {
+            self.pending
+                .push(Pending::Delete(aggregate_id, mode, Box::new(envelope.clone())));
+            return Ok(());
+        };
+
+        let delete = async {
+            event_store.delete_stream_in(tx, aggregate_id, mode).await?;
+            PgJobStore::enqueue_in(tx, &outbox_job(aggregate_id, envelope)?).await?;
+            Ok::<_, EventStoreError>(())
+        };
+        deadline::within("event_store.delete_stream", delete).await??;
+        self.enqueued = true;
+
+        Ok(())
+    }
+
+    /// Commit the transaction, or apply and publish the held-back changes
+    ///
+    /// The commit itself has no deadline and runs on its own task, so a request
+    /// timing out can't drop it halfway: COMMIT may already have reached the
+    /// database, and cancelling it would leave the outcome unknown.
+    pub async fn commit(self) -> Result<(), EventStoreError> {
+        if let Some(tx) = self.tx {
+            tokio::spawn(tx.commit())
+                .await
+                .map_err(|_| sqlx::Error::WorkerCrashed)??;
+            if self.enqueued {
+                self.factory.outbox_wake.notify_one();
+            }
+            return Ok(());
+        }
+
+        let Backend::InMemory { event_store, publisher } = &self.factory.backend else {
+            return Ok(());
+        };
+        for pending in &self.pending {
+            match pending {
+                Pending::Append(aggregate_id, expected_version, envelopes) => {
+                    let events = envelopes.iter().map(Event::from).collect();
+                    let append = event_store.append_events(*aggregate_id, *expected_version, events);
+                    deadline::within("event_store.append", append).await??;
+                }
+                Pending::Delete(aggregate_id, mode, _) => {
+                    let delete = event_store.delete_stream(*aggregate_id, *mode);
+                    deadline::within("event_store.delete_stream", delete).await??;
+                }
+            }
+        }
+        for pending in &self.pending {
+            let (aggregate_id, envelopes) = match pending {
+                Pending::Append(aggregate_id, _, envelopes) => (aggregate_id, envelopes.as_slice()),
+                Pending::Delete(aggregate_id, _, envelope) => (aggregate_id, std::slice::from_ref(envelope.as_ref())),
+            };
+            for envelope in envelopes {
+                if let Err(e) = publisher.publish(*aggregate_id, envelope).await {
+                    warn!("Failed to publish event {}: {}", envelope.event_id, e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,281 @@
+use chrono::{DateTime, Utc};
+use common::distributed_lock::{DistributedLock, PgAdvisoryLock};
+use messaging::Publisher;
+use serde_json::{json, Value};
+use sqlx::{FromRow, PgPool};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Changes published per pass
+const CDC_BATCH_SIZE: i64 = 100;
+
+/// Rows of `order_views` read per snapshot transaction
+const SNAPSHOT_CHUNK_SIZE: i64 = 1000;
+
+/// Trigger on `order_views` feeding `order_view_changes` (migrations/034)
+const CAPTURE_TRIGGER: &str = "order_views_cdc";
+
+/// Comment set on the trigger once the snapshot taken with it has finished
+const SNAPSHOT_DONE: &str = "snapshot complete";
+
+/// One captured row change of `order_views`
+#[derive(Debug, Clone, FromRow)]
+pub struct OrderViewChange {
+    pub id: i64,
+    pub order_id: Uuid,
+    /// `c`, `u`, `d`, or `r` for rows read by a snapshot
+    pub op: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub transaction_id: i64,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl OrderViewChange {
+    /// Debezium-style envelope, so consumers written against Debezium topics work
+    /// unchanged: the row `before` and `after` the change, the operation, and where
+    /// and when it happened
+    pub fn envelope(&self, database: &str, emitted_at: DateTime<Utc>) -> Value {
+        json!({
+            "before": self.before,
+            "after": self.after,
+            "source": {
+                "connector": "postgresql",
+                "name": "projection-service",
+                "db": database,
+                "schema": "public",
+                "table": "order_views",
+                "txId": self.transaction_id,
+                "ts_ms": self.changed_at.timestamp_millis(),
+                "snapshot": self.op == "r",
+            },
+            "op": self.op,
+            "ts_ms": emitted_at.timestamp_millis(),
+        })
+    }
+}
+
+/// Publishes row-level changes of `order_views` in the order they were committed
+///
+/// A trigger records each change in `order_view_changes` in the projection's own
+/// transaction, and the emitter relays them keyed by order id, one instance at a
+/// time under an advisory lock. Whenever the trigger is missing, because the export
+/// was just turned on or a shadow rebuild swapped the table, it is installed and
+/// followed by a snapshot of every row as `r` changes, so consumers can always rebuild
+/// their copy from the topic.
+pub struct CdcEmitter {
+    pool: PgPool,
+    publisher: Arc<dyn Publisher>,
+}
+
+impl CdcEmitter {
+    pub fn new(pool: PgPool, publisher: Arc<dyn Publisher>) -> Self {
+        Self { pool, publisher }
+    }
+
+    /// Stop capturing changes; the ones already captured stay until the export is
+    /// turned on again
+    ///
+    /// The trigger is shared by every replica, so this is only run when an operator
+    /// asks for it (`CDC_UNINSTALL=true`), never merely because one replica has the
+    /// export turned off.
+    pub async fn uninstall(pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query(&format!(
+            "DROP TRIGGER IF EXISTS {} ON order_views",
+            CAPTURE_TRIGGER
+        ))
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Emit every `interval`
+    pub fn spawn(self, interval: Duration) {
+        let lock = PgAdvisoryLock::new(self.pool.clone());
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                let task = Box::pin(async {
+                    if let Err(e) = self.ensure_capture().await {
+                        error!("Failed to install the order_views CDC trigger: {}", e);
+                        return;
+                    }
+                    match self.emit_once().await {
+                        Ok(0) => {}
+                        Ok(published) => info!("Published {} order view changes", published),
+                        Err(e) => error!("Failed to read order view changes: {}", e),
+                    }
+                });
+                if let Err(e) = lock.run_exclusive("order-views-cdc", task).await {
+                    warn!("Failed to acquire order views CDC lock: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Install the capture trigger if it is missing, and snapshot the table unless a
+    /// snapshot taken with the installed trigger has finished
+    async fn ensure_capture(&self) -> Result<(), sqlx::Error> {
+        let comment: Option<Option<String>> = sqlx::query_scalar(
+            r#"
+            SELECT obj_description(oid, 'pg_trigger') FROM pg_trigger
+            WHERE tgrelid = 'public.order_views'::regclass AND tgname = $1
+            "#,
+        )
+        .bind(CAPTURE_TRIGGER)
+        .fetch_optional(&self.pool)
+        .await?;
+        match comment {
+            Some(Some(comment)) if comment == SNAPSHOT_DONE => return Ok(()),
+            Some(_) => info!("Resuming the order_views CDC snapshot"),
+            None => {
+                // Only the DDL holds its lock on order_views, briefly
+                sqlx::query(&format!(
+                    r#"
+                    CREATE OR REPLACE TRIGGER {}
+                        AFTER INSERT OR UPDATE OR DELETE ON order_views
+                        FOR EACH ROW EXECUTE FUNCTION capture_order_view_change()
+                    "#,
+                    CAPTURE_TRIGGER
+                ))
+                .execute(&self.pool)
+                .await?;
+            }
+        }
+
+        let rows = self.snapshot().await?;
+        sqlx::query(&format!(
+            "COMMENT ON TRIGGER {} ON order_views IS '{}'",
+            CAPTURE_TRIGGER, SNAPSHOT_DONE
+        ))
+        .execute(&self.pool)
+        .await?;
+
+        info!("Installed the order_views CDC trigger with a snapshot of {} rows", rows);
+        Ok(())
+    }
+
+    /// Enqueue every row of `order_views` as an `r` change, a chunk per transaction
+    ///
+    /// Runs with the trigger already installed. Each chunk share-locks its rows, so a
+    /// change to one of them either committed before it was read, and the snapshot row
+    /// holds the result, or waits and is captured after it; consumers never see a
+    /// snapshot row older than a change before it. Rerunning it only repeats rows.
+    async fn snapshot(&self) -> Result<u64, sqlx::Error> {
+        let mut after = Uuid::nil();
+        let mut rows = 0;
+        loop {
+            let (captured, last): (i64, Option<Uuid>) = sqlx::query_as(
+                r#"
+                WITH chunk AS (
+                    SELECT v.order_id, to_jsonb(v) AS row
+                    FROM order_views v
+                    WHERE v.order_id > $1
+                    ORDER BY v.order_id
+                    LIMIT $2
+                    FOR SHARE
+                ),
+                captured AS (
+                    INSERT INTO order_view_changes (order_id, op, before, after)
+                    SELECT order_id, 'r', NULL, row FROM chunk ORDER BY order_id
+                    RETURNING order_id
+                )
+                SELECT count(*), (array_agg(order_id ORDER BY order_id DESC))[1]
+                FROM captured
+                "#,
+            )
+            .bind(after)
+            .bind(SNAPSHOT_CHUNK_SIZE)
+            .fetch_one(&self.pool)
+            .await?;
+
+            rows += captured as u64;
+            match last {
+                Some(last) => after = last,
+                None => return Ok(rows),
+            }
+        }
+    }
+
+    /// Publish until no changes are left or a publish fails
+    async fn emit_once(&self) -> Result<usize, sqlx::Error> {
+        let database: String = sqlx::query_scalar("SELECT current_database()")
+            .fetch_one(&self.pool)
+            .await?;
+        let mut published = 0;
+
+        loop {
+            let rows: Vec<OrderViewChange> = sqlx::query_as(
+                r#"
+                SELECT id, order_id, op, before, after,
+                       transaction_id::text::bigint AS transaction_id, changed_at
+                FROM order_view_changes
+                ORDER BY id
+                LIMIT $1
+                "#,
+            )
+            .bind(CDC_BATCH_SIZE)
+            .fetch_all(&self.pool)
+            .await?;
+
+            let mut sent = Vec::with_capacity(rows.len());
+            let mut failed = false;
+            for change in &rows {
+                let envelope = change.envelope(&database, Utc::now());
+                if let Err(e) = self.publisher.publish(change.order_id, &envelope).await {
+                    warn!(
+                        "Failed to publish order view change {}, will retry: {}",
+                        change.id, e
+                    );
+                    failed = true;
+                    break;
+                }
+                sent.push(change.id);
+            }
+
+            if !sent.is_empty() {
+                sqlx::query("DELETE FROM order_view_changes WHERE id = ANY($1)")
+                    .bind(&sent)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            published += sent.len();
+
+            if failed || (rows.len() as i64) < CDC_BATCH_SIZE {
+                return Ok(published);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_is_debezium_shaped() {
+        let changed_at = Utc::now();
+        let change = OrderViewChange {
+            id: 7,
+            order_id: Uuid::new_v4(),
+            op: "u".to_string(),
+            before: Some(json!({"status": "CREATED"})),
+            after: Some(json!({"status": "CONFIRMED"})),
+            transaction_id: 42,
+            changed_at,
+        };
+        let envelope = change.envelope("cqrs", changed_at);
+
+        assert_eq!(envelope["op"], "u");
+        assert_eq!(envelope["before"]["status"], "CREATED");
+        assert_eq!(envelope["after"]["status"], "CONFIRMED");
+        assert_eq!(envelope["source"]["table"], "order_views");
+        assert_eq!(envelope["source"]["db"], "cqrs");
+        assert_eq!(envelope["source"]["txId"], 42);
+        assert_eq!(envelope["source"]["snapshot"], false);
+        assert_eq!(envelope["ts_ms"], changed_at.timestamp_millis());
+    }
+}
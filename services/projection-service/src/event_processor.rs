@@ -1,50 +1,664 @@
-use domain::events::order_events::*;
-use read_model::OrderProjection;
+use chrono::{DateTime, Utc};
+use common::metrics;
+use common::request_log::message_span;
+use common::retry::{retry, Backoff, RetryPolicy};
+use domain::events::customer_events::*;
+use domain::events::inventory_events::*;
+use domain::events::payment_events::*;
+use domain::events::product_events::*;
+use domain::events::saga_events::*;
+use read_model::{
+    CustomerProjection, InventoryProjection, OrderProjection, PaymentProjection, PostgresProjectionErrorRepository,
+    ProductProjection, ProjectedEvent, Projection, ProjectionError, ProjectionErrorRepository,
+    ProjectionOutcome, SagaProjection,
+};
 use serde_json::Value;
-use tracing::{error, info, warn};
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn, Instrument};
+use uuid::Uuid;
+
+/// Batches of order events queued for a further projection; once it is this far
+/// behind, new events are quarantined instead
+const PROJECTION_QUEUE_BATCHES: usize = 1024;
+
+/// Most order events handed to a further projection in one call
+const MAX_EVENTS_PER_PROJECTION_CALL: usize = 1000;
+
+/// A further projection, fed through a bounded queue by its own task so a slow or
+/// failing target never holds up the Postgres views
+struct ProjectionFeed {
+    name: String,
+    batches: mpsc::Sender<Vec<ProjectedEvent>>,
+}
+
+impl ProjectionFeed {
+    fn spawn(projection: Arc<dyn Projection>) -> Self {
+        let (batches, queued) = mpsc::channel(PROJECTION_QUEUE_BATCHES);
+        let name = projection.name().to_string();
+        tokio::spawn(run_projection(projection, queued));
+        Self { name, batches }
+    }
+}
+
+/// Hand queued batches to `projection`, retrying a failed call until it succeeds
+///
+/// Projections read current state, so a late retry is still correct.
+async fn run_projection(
+    projection: Arc<dyn Projection>,
+    mut queued: mpsc::Receiver<Vec<ProjectedEvent>>,
+) {
+    let backoff = Backoff::ExponentialJitter {
+        initial: Duration::from_millis(200),
+        max: Duration::from_secs(30),
+    };
+    while let Some(mut pending) = queued.recv().await {
+        while pending.len() < MAX_EVENTS_PER_PROJECTION_CALL {
+            match queued.try_recv() {
+                Ok(batch) => pending.extend(batch),
+                Err(_) => break,
+            }
+        }
+
+        let mut failures = 0;
+        while let Err(e) = projection.project(&pending).await {
+            failures += 1;
+            error!(
+                "Projection {} failed on {} order events (attempt {}), retrying: {}",
+                projection.name(),
+                pending.len(),
+                failures,
+                e
+            );
+            tokio::time::sleep(backoff.delay(failures)).await;
+        }
+    }
+}
+
+/// An event taken from its Kafka envelope, waiting to be projected
+#[derive(Debug, Clone)]
+pub struct PendingEvent {
+    /// Topic the event was consumed from, which decides the projection it goes to
+    pub topic: String,
+    pub aggregate_id: Uuid,
+    pub event_type: String,
+    pub payload: Value,
+    /// Aggregate version the event was stored at, if the publisher stamped it
+    pub version: Option<i64>,
+    /// When the event was stored; unknown for events requeued from quarantine
+    pub created_at: Option<DateTime<Utc>>,
+    /// From the event's metadata; unknown for events requeued from quarantine
+    pub correlation_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+}
+
+impl PendingEvent {
+    /// Span the event is applied in, see [`message_span`]
+    fn span(&self) -> tracing::Span {
+        message_span(self.correlation_id, self.user_id)
+    }
+}
+
+impl From<ProjectionError> for PendingEvent {
+    fn from(error: ProjectionError) -> Self {
+        Self {
+            topic: error.topic.unwrap_or_default(),
+            aggregate_id: error.aggregate_id,
+            event_type: error.event_type,
+            payload: error.payload,
+            version: error.event_version,
+            created_at: None,
+            correlation_id: None,
+            user_id: None,
+        }
+    }
+}
+
+/// Why one attempt at applying an event failed
+#[derive(Debug, thiserror::Error)]
+enum AttemptError {
+    /// The event itself could not be applied; retried, then quarantined
+    #[error("{0}")]
+    Event(String),
+    /// The transaction could not be started or finished
+    #[error("database unavailable: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Projection that handles the events of a topic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicHandler {
+    Orders,
+    Payments,
+    Inventory,
+    Products,
+    Customers,
+    Sagas,
+}
+
+impl TopicHandler {
+    /// Projection name in metrics
+    pub fn name(&self) -> &'static str {
+        match self {
+            TopicHandler::Orders => "orders",
+            TopicHandler::Payments => "payments",
+            TopicHandler::Inventory => "inventory",
+            TopicHandler::Products => "products",
+            TopicHandler::Customers => "customers",
+            TopicHandler::Sagas => "sagas",
+        }
+    }
+}
 
 /// Processes events and updates projections
 pub struct EventProcessor {
+    pool: PgPool,
+    routes: HashMap<String, TopicHandler>,
     projection: OrderProjection,
+    payment_projection: PaymentProjection,
+    inventory_projection: InventoryProjection,
+    product_projection: ProductProjection,
+    customer_projection: CustomerProjection,
+    saga_projection: SagaProjection,
+    errors: Arc<dyn ProjectionErrorRepository>,
+    max_attempts: u32,
+    /// Further projections of committed order events
+    projections: Vec<ProjectionFeed>,
 }
 
 impl EventProcessor {
-    pub fn new(projection: OrderProjection) -> Self {
-        Self { projection }
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            errors: Arc::new(PostgresProjectionErrorRepository::new(pool.clone())),
+            pool,
+            routes: HashMap::new(),
+            projection: OrderProjection::new(),
+            payment_projection: PaymentProjection::new(),
+            inventory_projection: InventoryProjection::new(),
+            product_projection: ProductProjection::new(),
+            customer_projection: CustomerProjection::new(),
+            saga_projection: SagaProjection::new(),
+            max_attempts: 3,
+            projections: Vec::new(),
+        }
+    }
+
+    /// Route events consumed from `topic` to a projection
+    pub fn with_topic(mut self, topic: impl Into<String>, handler: TopicHandler) -> Self {
+        self.routes.insert(topic.into(), handler);
+        self
+    }
+
+    /// Topics with a route, to subscribe the consumer to
+    pub fn topics(&self) -> Vec<&str> {
+        self.routes.keys().map(String::as_str).collect()
+    }
+
+    /// How many times an event is tried before it is quarantined
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Also hand committed order events to `projection`, e.g. the search index
+    ///
+    /// The projection runs on its own task; must be called within a Tokio runtime.
+    pub fn with_projection(mut self, projection: Arc<dyn Projection>) -> Self {
+        self.projections.push(ProjectionFeed::spawn(projection));
+        self
+    }
+
+    /// Apply a batch of events inside one transaction, returning how many were applied
+    ///
+    /// If any event fails the transaction is rolled back and the batch is replayed one
+    /// event per transaction; an event that keeps failing is written to the
+    /// `projection_errors` table and skipped without losing the rest. An error means the database itself is unavailable and the batch should be
+    /// retried before offsets are committed.
+    pub async fn process_batch(&self, events: &[PendingEvent]) -> anyhow::Result<usize> {
+        if events.is_empty() {
+            return Ok(0);
+        }
+
+        let mut tx = self.pool.begin().await?;
+        let mut failed = None;
+        for event in events {
+            if let Err(e) = self.process_event(&mut tx, event).instrument(event.span()).await {
+                failed = Some((&event.event_type, e));
+                break;
+            }
+        }
+
+        match failed {
+            None => {
+                tx.commit().await?;
+                events.iter().for_each(|event| self.record_drift(event));
+                self.project(events.iter()).await?;
+                info!("Applied batch of {} events", events.len());
+                Ok(events.len())
+            }
+            Some((event_type, e)) => {
+                tx.rollback().await?;
+                warn!(
+                    "Batch of {} events failed on {} ({}), applying events individually",
+                    events.len(),
+                    event_type,
+                    e
+                );
+                self.process_individually(events).await
+            }
+        }
+    }
+
+    async fn process_individually(&self, events: &[PendingEvent]) -> anyhow::Result<usize> {
+        let mut applied = Vec::with_capacity(events.len());
+        for event in events {
+            match self.process_with_retries(event).instrument(event.span()).await? {
+                None => {
+                    self.record_drift(event);
+                    applied.push(event);
+                }
+                Some(e) => {
+                    let error_id = self
+                        .errors
+                        .quarantine(
+                            event.aggregate_id,
+                            &event.topic,
+                            &event.event_type,
+                            event.version,
+                            &event.payload,
+                            &e,
+                            self.max_attempts as i32,
+                        )
+                        .await?;
+                    error!(
+                        "Quarantined {} for aggregate {} after {} attempts (error_id: {}): {}",
+                        event.event_type, event.aggregate_id, self.max_attempts, error_id, e
+                    );
+                }
+            }
+        }
+
+        self.project(applied.iter().copied()).await?;
+        Ok(applied.len())
+    }
+
+    /// Queue committed order events for the further projections
+    ///
+    /// Never waits on a projection. If one has fallen so far behind that its queue is
+    /// full, the events are quarantined in `projection_errors` for it instead, so
+    /// requeueing them once it recovers applies them again (the views skip versions
+    /// they already have) and hands them over. An error means they could not be
+    /// recorded and the batch should be retried.
+    async fn project<'a>(
+        &self,
+        events: impl Iterator<Item = &'a PendingEvent>,
+    ) -> anyhow::Result<()> {
+        if self.projections.is_empty() {
+            return Ok(());
+        }
+        let order_events: Vec<&PendingEvent> = events
+            .filter(|event| self.routes.get(&event.topic) == Some(&TopicHandler::Orders))
+            .collect();
+        if order_events.is_empty() {
+            return Ok(());
+        }
+
+        for feed in &self.projections {
+            let batch = order_events
+                .iter()
+                .map(|event| ProjectedEvent {
+                    aggregate_id: event.aggregate_id,
+                    event_type: event.event_type.clone(),
+                    payload: event.payload.clone(),
+                    version: event.version,
+                })
+                .collect();
+            if feed.batches.try_send(batch).is_ok() {
+                continue;
+            }
+
+            let reason = format!("projection {} is too far behind to queue the event", feed.name);
+            for event in &order_events {
+                self.errors
+                    .quarantine(
+                        event.aggregate_id,
+                        &event.topic,
+                        &event.event_type,
+                        event.version,
+                        &event.payload,
+                        &reason,
+                        0,
+                    )
+                    .await?;
+            }
+            warn!(
+                "Quarantined {} order events for projection {}, whose queue is full",
+                order_events.len(),
+                feed.name
+            );
+        }
+        Ok(())
+    }
+
+    /// Record how long after it was stored a committed event was projected
+    fn record_drift(&self, event: &PendingEvent) {
+        let handler = self.routes.get(&event.topic);
+        if let (Some(handler), Some(created_at)) = (handler, event.created_at) {
+            metrics::record_projection_drift(
+                handler.name(),
+                created_at.timestamp_millis() as f64 / 1000.0,
+                Utc::now().timestamp_millis() as f64 / 1000.0,
+            );
+        }
+    }
+
+    /// Try an event in its own transaction up to `max_attempts` times,
+    /// returning the last error if it never applied
+    async fn process_with_retries(&self, event: &PendingEvent) -> anyhow::Result<Option<String>> {
+        let policy = RetryPolicy::new(format!("projection.{}", event.event_type))
+            .with_max_attempts(self.max_attempts)
+            .with_backoff(Backoff::Exponential {
+                initial: Duration::from_millis(50),
+                max: Duration::from_secs(1),
+            })
+            .with_retryable(|e| matches!(e, AttemptError::Event(_)));
+
+        let result = retry(&policy, || async {
+            let mut tx = self.pool.begin().await?;
+            match self.process_event(&mut tx, event).await {
+                Ok(()) => Ok(tx.commit().await?),
+                Err(e) => {
+                    tx.rollback().await?;
+                    Err(AttemptError::Event(e.to_string()))
+                }
+            }
+        })
+        .await;
+
+        match result {
+            Ok(()) => Ok(None),
+            Err(AttemptError::Event(e)) => Ok(Some(e)),
+            Err(AttemptError::Database(e)) => Err(e.into()),
+        }
+    }
+
+    /// Retry events an operator requeued from quarantine, returning how many applied
+    pub async fn process_requeued(&self, limit: i64) -> anyhow::Result<usize> {
+        let mut applied = 0;
+        for requeued in self.errors.find_requeued(limit).await? {
+            let error_id = requeued.error_id;
+            let event = PendingEvent::from(requeued);
+
+            let mut tx = self.pool.begin().await?;
+            match self.process_event(&mut tx, &event).await {
+                Ok(()) => {
+                    tx.commit().await?;
+                    self.project(std::iter::once(&event)).await?;
+                    self.errors.record_retry(error_id, None).await?;
+                    info!("Requeued {} applied (error_id: {})", event.event_type, error_id);
+                    applied += 1;
+                }
+                Err(e) => {
+                    tx.rollback().await?;
+                    self.errors
+                        .record_retry(error_id, Some(&e.to_string()))
+                        .await?;
+                    warn!(
+                        "Requeued {} failed again (error_id: {}): {}",
+                        event.event_type, error_id, e
+                    );
+                }
+            }
+        }
+
+        Ok(applied)
     }
 
-    /// Process a single event
-    pub async fn process_event(&self, event_type: &str, payload: Value) -> anyhow::Result<()> {
-        info!("Processing event: {}", event_type);
+    /// Quarantine order events buffered for longer than `max_age` and record the
+    /// buffer's depth; returns how many were quarantined
+    pub async fn expire_buffered(&self, max_age: Duration) -> anyhow::Result<u64> {
+        let Some(topic) = self
+            .routes
+            .iter()
+            .find(|(_, handler)| **handler == TopicHandler::Orders)
+            .map(|(topic, _)| topic.clone())
+        else {
+            return Ok(0);
+        };
+
+        let mut conn = self.pool.acquire().await?;
+        let expired = self.projection.expire_buffer(&mut conn, &topic, max_age).await?;
+        let buffered = self.projection.buffered_events(&mut conn).await?;
+        metrics::record_projection_buffer(TopicHandler::Orders.name(), buffered, expired);
+        Ok(expired)
+    }
+
+    /// Process a single event on the given connection, routed by its topic
+    pub async fn process_event(
+        &self,
+        conn: &mut PgConnection,
+        event: &PendingEvent,
+    ) -> anyhow::Result<()> {
+        info!("Processing event: {} from topic: {}", event.event_type, event.topic);
+
+        match self.routes.get(&event.topic) {
+            Some(TopicHandler::Orders) => self.process_order_event(conn, event).await,
+            Some(TopicHandler::Payments) => self.process_payment_event(conn, event).await,
+            Some(TopicHandler::Inventory) => self.process_inventory_event(conn, event).await,
+            Some(TopicHandler::Products) => self.process_product_event(conn, event).await,
+            Some(TopicHandler::Customers) => self.process_customer_event(conn, event).await,
+            Some(TopicHandler::Sagas) => self.process_saga_event(conn, event).await,
+            None => {
+                warn!(
+                    "No projection routed for topic: {}, skipping {}",
+                    event.topic, event.event_type
+                );
+                Ok(())
+            }
+        }
+    }
+
+    async fn process_order_event(
+        &self,
+        conn: &mut PgConnection,
+        event: &PendingEvent,
+    ) -> anyhow::Result<()> {
+        let event_type = event.event_type.as_str();
 
         match event_type {
-            "OrderCreated" => {
-                let event: OrderCreatedEvent = serde_json::from_value(payload)?;
-                self.projection.handle_order_created(&event).await?;
-                info!("Successfully processed OrderCreated for order_id: {}", event.order_id);
-            }
-            "OrderConfirmed" => {
-                let event: OrderConfirmedEvent = serde_json::from_value(payload)?;
-                self.projection.handle_order_confirmed(&event).await?;
-                info!("Successfully processed OrderConfirmed for order_id: {}", event.order_id);
-            }
-            "OrderCancelled" => {
-                let event: OrderCancelledEvent = serde_json::from_value(payload)?;
-                self.projection.handle_order_cancelled(&event).await?;
-                info!("Successfully processed OrderCancelled for order_id: {}", event.order_id);
-            }
-            "OrderShipped" => {
-                let event: OrderShippedEvent = serde_json::from_value(payload)?;
-                self.projection.handle_order_shipped(&event).await?;
-                info!("Successfully processed OrderShipped for order_id: {}", event.order_id);
-            }
-            "OrderDelivered" => {
-                let event: OrderDeliveredEvent = serde_json::from_value(payload)?;
-                self.projection.handle_order_delivered(&event).await?;
-                info!("Successfully processed OrderDelivered for order_id: {}", event.order_id);
+            "OrderCreated" | "OrderConfirmed" | "OrderCancelled" | "OrderShipped"
+            | "OrderDelivered" | "OrderItemPriceCorrected" | "StreamDeleted" => {
+                let outcome = self
+                    .projection
+                    .apply_at(
+                        conn,
+                        event.aggregate_id,
+                        event_type,
+                        event.payload.clone(),
+                        event.version,
+                        event.created_at,
+                    )
+                    .await?;
+                match outcome {
+                    ProjectionOutcome::Applied => info!(
+                        "Successfully processed {} for order_id: {}",
+                        event_type, event.aggregate_id
+                    ),
+                    conflict => warn!(
+                        "{} for order_id: {} (version {:?}) was not applied: {:?}",
+                        event_type, event.aggregate_id, event.version, conflict
+                    ),
+                }
             }
             _ => {
-                warn!("Unknown event type: {}, skipping", event_type);
+                warn!("Unknown order event type: {}, skipping", event_type);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn process_payment_event(
+        &self,
+        conn: &mut PgConnection,
+        event: &PendingEvent,
+    ) -> anyhow::Result<()> {
+        let payload = event.payload.clone();
+
+        match event.event_type.as_str() {
+            "PaymentAuthorized" => {
+                let event: PaymentAuthorizedEvent = serde_json::from_value(payload)?;
+                self.payment_projection.handle_payment_authorized(conn, &event).await?;
+            }
+            "PaymentCaptured" => {
+                let event: PaymentCapturedEvent = serde_json::from_value(payload)?;
+                self.payment_projection.handle_payment_captured(conn, &event).await?;
+            }
+            "PaymentVoided" => {
+                let event: PaymentVoidedEvent = serde_json::from_value(payload)?;
+                self.payment_projection.handle_payment_voided(conn, &event).await?;
+            }
+            "PaymentFailed" => {
+                let event: PaymentFailedEvent = serde_json::from_value(payload)?;
+                self.payment_projection.handle_payment_failed(conn, &event).await?;
+            }
+            "PaymentRefunded" => {
+                let event: PaymentRefundedEvent = serde_json::from_value(payload)?;
+                self.payment_projection.handle_payment_refunded(conn, &event).await?;
+            }
+            other => {
+                warn!("Unknown payment event type: {}, skipping", other);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn process_inventory_event(
+        &self,
+        conn: &mut PgConnection,
+        event: &PendingEvent,
+    ) -> anyhow::Result<()> {
+        let payload = event.payload.clone();
+
+        match event.event_type.as_str() {
+            "InventoryReserved" => {
+                let event: InventoryReservedEvent = serde_json::from_value(payload)?;
+                self.inventory_projection.handle_inventory_reserved(conn, &event).await?;
+            }
+            "InventoryReleased" => {
+                let event: InventoryReleasedEvent = serde_json::from_value(payload)?;
+                self.inventory_projection.handle_inventory_released(conn, &event).await?;
+            }
+            "InventoryReservationFailed" => {
+                let event: InventoryReservationFailedEvent = serde_json::from_value(payload)?;
+                self.inventory_projection.handle_reservation_failed(conn, &event).await?;
+            }
+            "StockReplenished" => {
+                let event: StockReplenishedEvent = serde_json::from_value(payload)?;
+                self.inventory_projection.handle_stock_replenished(conn, &event).await?;
+            }
+            other => {
+                warn!("Unknown inventory event type: {}, skipping", other);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn process_product_event(
+        &self,
+        conn: &mut PgConnection,
+        event: &PendingEvent,
+    ) -> anyhow::Result<()> {
+        let payload = event.payload.clone();
+
+        match event.event_type.as_str() {
+            "ProductCreated" => {
+                let event: ProductCreatedEvent = serde_json::from_value(payload)?;
+                self.product_projection.handle_product_created(conn, &event).await?;
+            }
+            "ProductPriceChanged" => {
+                let event: ProductPriceChangedEvent = serde_json::from_value(payload)?;
+                self.product_projection.handle_price_changed(conn, &event).await?;
+            }
+            "ProductDiscontinued" => {
+                let event: ProductDiscontinuedEvent = serde_json::from_value(payload)?;
+                self.product_projection.handle_product_discontinued(conn, &event).await?;
+            }
+            other => {
+                warn!("Unknown product event type: {}, skipping", other);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn process_customer_event(
+        &self,
+        conn: &mut PgConnection,
+        event: &PendingEvent,
+    ) -> anyhow::Result<()> {
+        let payload = event.payload.clone();
+
+        match event.event_type.as_str() {
+            "CustomerRegistered" => {
+                let event: CustomerRegisteredEvent = serde_json::from_value(payload)?;
+                self.customer_projection.handle_customer_registered(conn, &event).await?;
+            }
+            "CustomerProfileUpdated" => {
+                let event: CustomerProfileUpdatedEvent = serde_json::from_value(payload)?;
+                self.customer_projection.handle_profile_updated(conn, &event).await?;
+            }
+            other => {
+                warn!("Unknown customer event type: {}, skipping", other);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn process_saga_event(
+        &self,
+        conn: &mut PgConnection,
+        event: &PendingEvent,
+    ) -> anyhow::Result<()> {
+        let payload = event.payload.clone();
+
+        match event.event_type.as_str() {
+            "SagaStarted" => {
+                let event: SagaStartedEvent = serde_json::from_value(payload)?;
+                self.saga_projection.handle_saga_started(conn, &event).await?;
+            }
+            "SagaStepCompleted" => {
+                let event: SagaStepCompletedEvent = serde_json::from_value(payload)?;
+                self.saga_projection.handle_step_completed(conn, &event).await?;
+            }
+            "SagaStepFailed" => {
+                let event: SagaStepFailedEvent = serde_json::from_value(payload)?;
+                self.saga_projection.handle_step_failed(conn, &event).await?;
+            }
+            "SagaCompleted" => {
+                let event: SagaCompletedEvent = serde_json::from_value(payload)?;
+                self.saga_projection.handle_saga_completed(conn, &event).await?;
+            }
+            "SagaCompensated" => {
+                let event: SagaCompensatedEvent = serde_json::from_value(payload)?;
+                self.saga_projection.handle_saga_compensated(conn, &event).await?;
+            }
+            "SagaInterventionRequired" => {
+                let event: SagaInterventionRequiredEvent = serde_json::from_value(payload)?;
+                self.saga_projection.handle_intervention_required(conn, &event).await?;
+            }
+            "SagaPaused" => {
+                let event: SagaPausedEvent = serde_json::from_value(payload)?;
+                self.saga_projection.handle_saga_paused(conn, &event).await?;
+            }
+            other => {
+                warn!("Unknown saga event type: {}, skipping", other);
             }
         }
 
@@ -55,15 +669,25 @@ impl EventProcessor {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
-    use sqlx::PgPool;
-    use uuid::Uuid;
+    use domain::events::order_events::OrderCreatedEvent;
 
-    #[test]
-    fn test_processor_creation() {
+    #[tokio::test]
+    async fn test_processor_creation() {
         let pool = PgPool::connect_lazy("postgresql://test").unwrap();
-        let projection = OrderProjection::new(pool);
-        let _processor = EventProcessor::new(projection);
+        let _processor = EventProcessor::new(pool);
+    }
+
+    #[tokio::test]
+    async fn test_topic_routes() {
+        let pool = PgPool::connect_lazy("postgresql://test").unwrap();
+        let processor = EventProcessor::new(pool)
+            .with_topic("order-events", TopicHandler::Orders)
+            .with_topic("payment-events", TopicHandler::Payments);
+
+        let mut topics = processor.topics();
+        topics.sort();
+        assert_eq!(topics, vec!["order-events", "payment-events"]);
+        assert_eq!(processor.routes.get("payment-events"), Some(&TopicHandler::Payments));
     }
 
     #[test]
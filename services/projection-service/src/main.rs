@@ -1,25 +1,29 @@
 use anyhow::Result;
+use common::distributed_lock::{DistributedLock, PgAdvisoryLock};
+use common::preflight::{Preflight, Severity};
+use common::retry::{retry, Backoff, RetryPolicy};
 use common::telemetry::{TelemetryConfig, init_telemetry, shutdown_telemetry};
 use domain::events::order_events::*;
-use messaging::EventConsumer;
-use read_model::OrderProjection;
-use serde_json::Value;
+use domain::events::EventEnvelope;
+use messaging::{
+    ConsumerHealth, DeadLetterPublisher, EventConsumer, EventPublisher, PoisonPillDetector, PoisonVerdict,
+    Publisher, ReceivedMessage, ReconnectBackoff, TopicManager, TopicSettings,
+};
+use read_model::search::{OpenSearchClient, OpenSearchConfig, OrderSearchProjection};
+use read_model::{ConsistencyChecker, CustomerProjection};
 use signal_hook::consts::signal::*;
 use signal_hook_tokio::Signals;
 use sqlx::PgPool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 use tracing::{error, info, warn};
 
+mod cdc;
 mod event_processor;
-use event_processor::EventProcessor;
-
-#[derive(serde::Deserialize)]
-struct EventEnvelope {
-    event_type: String,
-    payload: Value,
-}
+use cdc::CdcEmitter;
+use event_processor::{EventProcessor, PendingEvent, TopicHandler};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -51,36 +55,362 @@ async fn main() -> Result<()> {
         .unwrap_or_else(|_| "localhost:9092".to_string());
     let kafka_topic = std::env::var("KAFKA_TOPIC")
         .unwrap_or_else(|_| "order-events".to_string());
+    let payment_events_topic = std::env::var("PAYMENT_EVENTS_TOPIC")
+        .unwrap_or_else(|_| "payment-events".to_string());
+    let inventory_events_topic = std::env::var("INVENTORY_EVENTS_TOPIC")
+        .unwrap_or_else(|_| "inventory-events".to_string());
+    let product_events_topic = std::env::var("PRODUCT_EVENTS_TOPIC")
+        .unwrap_or_else(|_| "product-events".to_string());
+    let customer_events_topic = std::env::var("CUSTOMER_EVENTS_TOPIC")
+        .unwrap_or_else(|_| "customer-events".to_string());
+    let saga_events_topic = std::env::var("SAGA_EVENTS_TOPIC")
+        .unwrap_or_else(|_| "saga-events".to_string());
     let consumer_group = std::env::var("CONSUMER_GROUP")
         .unwrap_or_else(|_| "projection-service".to_string());
+    let batch_size: usize = std::env::var("PROJECTION_BATCH_SIZE")
+        .unwrap_or_else(|_| "500".to_string())
+        .parse()
+        .unwrap_or(500);
+    let batch_linger_ms: u64 = std::env::var("PROJECTION_BATCH_LINGER_MS")
+        .unwrap_or_else(|_| "50".to_string())
+        .parse()
+        .unwrap_or(50);
+    let max_attempts: u32 = std::env::var("PROJECTION_MAX_ATTEMPTS")
+        .unwrap_or_else(|_| "3".to_string())
+        .parse()
+        .unwrap_or(3);
+    let requeue_interval_secs: u64 = std::env::var("PROJECTION_REQUEUE_INTERVAL_SECS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse()
+        .unwrap_or(30);
+    let buffer_max_age_secs: u64 = std::env::var("PROJECTION_BUFFER_MAX_AGE_SECS")
+        .unwrap_or_else(|_| "3600".to_string())
+        .parse()
+        .unwrap_or(3600);
+    let dead_letter_topic = std::env::var("PROJECTION_DEAD_LETTER_TOPIC")
+        .unwrap_or_else(|_| "projection-service-dlq".to_string());
+    let poison_max_attempts: u32 = std::env::var("POISON_MAX_ATTEMPTS")
+        .unwrap_or_else(|_| "3".to_string())
+        .parse()
+        .unwrap_or(3);
+    let consistency_interval_secs: u64 = std::env::var("CONSISTENCY_CHECK_INTERVAL_SECS")
+        .unwrap_or_else(|_| "0".to_string())
+        .parse()
+        .unwrap_or(0);
+    let consistency_sample_size: i64 = std::env::var("CONSISTENCY_CHECK_SAMPLE_SIZE")
+        .unwrap_or_else(|_| "100".to_string())
+        .parse()
+        .unwrap_or(100);
+    let consistency_settle_secs: i64 = std::env::var("CONSISTENCY_CHECK_SETTLE_SECS")
+        .unwrap_or_else(|_| "60".to_string())
+        .parse()
+        .unwrap_or(60);
+    let consistency_repair = std::env::var("CONSISTENCY_CHECK_REPAIR")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .unwrap_or(false);
+    let manage_topics = std::env::var("KAFKA_MANAGE_TOPICS")
+        .unwrap_or_else(|_| "true".to_string())
+        .parse()
+        .unwrap_or(true);
+    let cdc_enabled = std::env::var("CDC_ENABLED")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .unwrap_or(false);
+    let cdc_topic = std::env::var("CDC_TOPIC")
+        .unwrap_or_else(|_| "order-views-changed".to_string());
+    let cdc_interval_ms: u64 = std::env::var("CDC_POLL_INTERVAL_MS")
+        .unwrap_or_else(|_| "1000".to_string())
+        .parse()
+        .unwrap_or(1000);
+    let cdc_uninstall = std::env::var("CDC_UNINSTALL")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .unwrap_or(false);
+    let search_config = OpenSearchConfig::from_env();
+    let search_reindex = std::env::var("OPENSEARCH_REINDEX")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .unwrap_or(false);
 
     info!("Configuration:");
     info!("  Database URL: {}", database_url);
     info!("  Kafka Brokers: {}", kafka_brokers);
     info!("  Kafka Topic: {}", kafka_topic);
+    info!("  Payment Events Topic: {}", payment_events_topic);
+    info!("  Inventory Events Topic: {}", inventory_events_topic);
+    info!("  Product Events Topic: {}", product_events_topic);
+    info!("  Customer Events Topic: {}", customer_events_topic);
+    info!("  Saga Events Topic: {}", saga_events_topic);
     info!("  Consumer Group: {}", consumer_group);
+    info!("  Batch Size: {} (linger: {}ms)", batch_size, batch_linger_ms);
+    info!("  Max Attempts Before Quarantine: {}", max_attempts);
+    info!("  Dead Letter Topic: {} (after {} deserialization failures)", dead_letter_topic, poison_max_attempts);
+
+    // Report every missing dependency at once instead of failing on the first event
+    if Preflight::enabled() {
+        let mut preflight = Preflight::new("projection-service");
+        preflight.config::<bool>(&[
+            "ENABLE_JAEGER",
+            "CONSISTENCY_CHECK_REPAIR",
+            "KAFKA_MANAGE_TOPICS",
+            "CDC_ENABLED",
+            "CDC_UNINSTALL",
+            "OPENSEARCH_REINDEX",
+        ]);
+        preflight.config::<usize>(&["PROJECTION_BATCH_SIZE"]);
+        preflight.config::<u64>(&[
+            "PROJECTION_BATCH_LINGER_MS",
+            "PROJECTION_REQUEUE_INTERVAL_SECS",
+            "CONSISTENCY_CHECK_INTERVAL_SECS",
+            "CDC_POLL_INTERVAL_MS",
+            "OPENSEARCH_TIMEOUT_MS",
+        ]);
+        preflight.config::<u32>(&["PROJECTION_MAX_ATTEMPTS", "POISON_MAX_ATTEMPTS"]);
+        preflight.config::<i64>(&["CONSISTENCY_CHECK_SAMPLE_SIZE", "CONSISTENCY_CHECK_SETTLE_SECS"]);
+        preflight
+            .database(
+                &database_url,
+                &[
+                    "events",
+                    "order_views",
+                    "order_views.customer_name",
+                    "order_projection_buffer",
+                    "projection_errors",
+                    "projection_errors.topic",
+                    "payment_views",
+                    "payment_history",
+                    "inventory_views",
+                    "inventory_reservation_views",
+                    "product_views",
+                    "customer_views",
+                    "saga_views",
+                    "business_metrics_minutely",
+                    "business_metrics_order_value",
+                    "order_projection_buffer.event_created_at",
+                    "order_view_changes",
+                ],
+            )
+            .await;
+        messaging::preflight_topics(
+            &mut preflight,
+            &kafka_brokers,
+            &[
+                &kafka_topic,
+                &payment_events_topic,
+                &inventory_events_topic,
+                &product_events_topic,
+                &customer_events_topic,
+                &saga_events_topic,
+            ],
+            Severity::Fatal,
+            manage_topics,
+        )
+        .await;
+        if cdc_enabled {
+            messaging::preflight_topics(
+                &mut preflight,
+                &kafka_brokers,
+                &[&cdc_topic],
+                Severity::Fatal,
+                manage_topics,
+            )
+            .await;
+        }
+        preflight.finish();
+    }
 
     // Connect to database
     info!("Connecting to database...");
     let pool = PgPool::connect(&database_url).await?;
     info!("Database connected successfully");
 
-    // Create projection
-    let projection = OrderProjection::new(pool.clone());
+    // Create event processor routing each topic to its projection; events that keep
+    // failing go to the projection_errors table
+    let processor = EventProcessor::new(pool.clone())
+        .with_topic(kafka_topic, TopicHandler::Orders)
+        .with_topic(payment_events_topic, TopicHandler::Payments)
+        .with_topic(inventory_events_topic, TopicHandler::Inventory)
+        .with_topic(product_events_topic, TopicHandler::Products)
+        .with_topic(customer_events_topic, TopicHandler::Customers)
+        .with_topic(saga_events_topic, TopicHandler::Sagas)
+        .with_max_attempts(max_attempts);
+
+    // Index orders into OpenSearch for /orders/search, if configured; a new index is
+    // filled from order_views by one replica
+    let processor = match search_config {
+        Some(config) => {
+            info!("  Order Search Index: {} at {}", config.index, config.url);
+            let search = Arc::new(OrderSearchProjection::new(pool.clone(), OpenSearchClient::new(config)));
+            let reindex = match search.ensure_index().await {
+                Ok(created) => created || search_reindex,
+                Err(e) => {
+                    warn!("Failed to prepare the order search index: {}", e);
+                    search_reindex
+                }
+            };
+            if reindex {
+                let reindex_search = search.clone();
+                let reindex_lock = PgAdvisoryLock::new(pool.clone());
+                tokio::spawn(async move {
+                    let task = Box::pin(async {
+                        if let Err(e) = reindex_search.reindex(1000).await {
+                            error!("Failed to reindex orders into the search index: {}", e);
+                        }
+                    });
+                    if let Err(e) = reindex_lock.run_exclusive("order-search-reindex", task).await {
+                        warn!("Failed to acquire order search reindex lock: {}", e);
+                    }
+                });
+            }
+            processor.with_projection(search)
+        }
+        None => processor,
+    };
+    let topics: Vec<String> = processor.topics().into_iter().map(String::from).collect();
+    let processor = Arc::new(Mutex::new(processor));
+
+    // Retry events requeued by an operator, one replica at a time
+    let requeue_processor = processor.clone();
+    let requeue_lock = PgAdvisoryLock::new(pool.clone());
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(requeue_interval_secs));
+        loop {
+            interval.tick().await;
+            let task = Box::pin(async {
+                match requeue_processor.lock().await.process_requeued(100).await {
+                    Ok(0) => {}
+                    Ok(applied) => info!("Applied {} requeued events", applied),
+                    Err(e) => error!("Failed to process requeued events: {}", e),
+                }
+            });
+            if let Err(e) = requeue_lock.run_exclusive("projection-requeue", task).await {
+                warn!("Failed to acquire projection requeue lock: {}", e);
+            }
+        }
+    });
+
+    // Quarantine out-of-order events whose gap never filled, one replica at a time
+    let buffer_processor = processor.clone();
+    let buffer_lock = PgAdvisoryLock::new(pool.clone());
+    tokio::spawn(async move {
+        let max_age = Duration::from_secs(buffer_max_age_secs);
+        let mut interval = tokio::time::interval(Duration::from_secs(requeue_interval_secs));
+        loop {
+            interval.tick().await;
+            let task = Box::pin(async {
+                if let Err(e) = buffer_processor.lock().await.expire_buffered(max_age).await {
+                    error!("Failed to expire buffered order events: {}", e);
+                }
+            });
+            if let Err(e) = buffer_lock.run_exclusive("projection-buffer-expiry", task).await {
+                warn!("Failed to acquire projection buffer expiry lock: {}", e);
+            }
+        }
+    });
 
-    // Create event processor
-    let processor = Arc::new(Mutex::new(EventProcessor::new(projection)));
+    // Fill in customer info on orders projected before their customer was known
+    let backfill_pool = pool.clone();
+    tokio::spawn(async move {
+        let backfill_lock = PgAdvisoryLock::new(backfill_pool.clone());
+        let task = Box::pin(async {
+            if let Err(e) = CustomerProjection::new()
+                .backfill_order_views(&backfill_pool, 1000)
+                .await
+            {
+                error!("Failed to backfill customer info on order views: {}", e);
+            }
+        });
+        if let Err(e) = backfill_lock.run_exclusive("projection-customer-backfill", task).await {
+            warn!("Failed to acquire customer backfill lock: {}", e);
+        }
+    });
 
-    // Create Kafka consumer
+    // Compare a sample of order views with their events, one replica at a time
+    if consistency_interval_secs > 0 {
+        info!(
+            "  Consistency Check: {} orders every {}s (repair: {})",
+            consistency_sample_size, consistency_interval_secs, consistency_repair
+        );
+        let checker = ConsistencyChecker::new(pool.clone(), consistency_sample_size)
+            .with_settle_period(chrono::Duration::seconds(consistency_settle_secs))
+            .with_repair(consistency_repair);
+        let consistency_lock = PgAdvisoryLock::new(pool.clone());
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(consistency_interval_secs));
+            loop {
+                interval.tick().await;
+                let task = Box::pin(async {
+                    if let Err(e) = checker.check().await {
+                        error!("Projection consistency check failed: {}", e);
+                    }
+                });
+                if let Err(e) = consistency_lock.run_exclusive("projection-consistency", task).await {
+                    warn!("Failed to acquire projection consistency lock: {}", e);
+                }
+            }
+        });
+    }
+
+    // Export row-level changes of order_views for search indexing and the data lake
+    if cdc_enabled {
+        info!("  CDC Topic: {} (every {}ms)", cdc_topic, cdc_interval_ms);
+        let publisher: Arc<dyn Publisher> = Arc::new(EventPublisher::new(&kafka_brokers, cdc_topic.clone())?);
+        CdcEmitter::new(pool.clone(), publisher).spawn(Duration::from_millis(cdc_interval_ms));
+    } else if cdc_uninstall {
+        // The trigger is shared by every replica, so it is only removed when asked for
+        warn!("CDC_UNINSTALL is set, removing the order_views CDC trigger");
+        if let Err(e) = CdcEmitter::uninstall(&pool).await {
+            warn!("Failed to remove the order_views CDC trigger: {}", e);
+        }
+    }
+
+    // Consumer health is mirrored to a file for exec readiness probes, if configured
+    let mut health = ConsumerHealth::new();
+    if let Ok(readiness_file) = std::env::var("READINESS_FILE") {
+        info!("  Readiness File: {}", readiness_file);
+        health = health.with_marker_file(readiness_file);
+    }
+
+    // Create the consumed topics, the dead letter topic and the CDC topic with explicit settings
+    // rather than leaving it to broker auto-create
+    let topic_refs: Vec<&str> = topics.iter().map(String::as_str).collect();
+    if manage_topics {
+        let settings = TopicSettings::from_env();
+        let specs: Vec<_> = topic_refs
+            .iter()
+            .copied()
+            .chain([dead_letter_topic.as_str()])
+            .chain(cdc_enabled.then_some(cdc_topic.as_str()))
+            .map(|topic| settings.spec(topic))
+            .collect();
+        let ensured = async { TopicManager::new(&kafka_brokers)?.ensure(&specs).await }.await;
+        if let Err(e) = ensured {
+            warn!("Failed to ensure Kafka topics, continuing with them as they are: {}", e);
+        }
+    }
+
+    // Create Kafka consumer; offsets are committed only after a batch is applied
     info!("Creating Kafka consumer...");
-    let consumer = EventConsumer::new(&kafka_brokers, &consumer_group, &[&kafka_topic])?;
+    let mut consumer = EventConsumer::new_with_manual_commit(
+        &kafka_brokers,
+        &consumer_group,
+        &topic_refs,
+    )?
+    .with_health(health);
     info!("Kafka consumer created successfully");
 
+    // Messages that keep failing to deserialize are moved to the dead letter topic
+    let dead_letters = DeadLetterPublisher::new(&kafka_brokers, dead_letter_topic)?;
+    let mut poison = PoisonPillDetector::new(&consumer_group, poison_max_attempts);
+
     // Setup signal handling
     let signals = Signals::new(&[SIGTERM, SIGINT])?;
     let handle = signals.handle();
 
     let processor_clone = processor.clone();
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let shutdown_flag = shutdown.clone();
     let signal_task = tokio::spawn(async move {
         use futures_util::stream::StreamExt;
         let mut signals = signals;
@@ -88,6 +418,7 @@ async fn main() -> Result<()> {
             match signal {
                 SIGTERM | SIGINT => {
                     info!("Received shutdown signal, stopping...");
+                    shutdown_flag.store(true, Ordering::SeqCst);
                     break;
                 }
                 _ => {}
@@ -95,9 +426,19 @@ async fn main() -> Result<()> {
         }
     });
 
+    // A batch that fails to apply is retried until it does or the service shuts down
+    let batch_retry = RetryPolicy::new("projection.apply_batch")
+        .with_max_attempts(u32::MAX)
+        .with_backoff(Backoff::Exponential {
+            initial: Duration::from_millis(500),
+            max: Duration::from_secs(30),
+        })
+        .with_retryable(move |_: &anyhow::Error| !shutdown.load(Ordering::SeqCst));
+
     // Start consuming events
     info!("Starting event consumption loop...");
     let mut running = true;
+    let mut backoff = ReconnectBackoff::default();
 
     while running {
         if signal_task.is_finished() {
@@ -106,28 +447,109 @@ async fn main() -> Result<()> {
             break;
         }
 
-        match consumer.poll(Duration::from_millis(100)).await {
-            Ok(Some(payload)) => {
-                // Deserialize event envelope
-                match serde_json::from_slice::<EventEnvelope>(&payload) {
-                    Ok(envelope) => {
-                        let processor = processor.lock().await;
-                        if let Err(e) = processor.process_event(&envelope.event_type, envelope.payload).await {
-                            error!("Failed to process event: {}", e);
+        match consumer
+            .poll_batch(batch_size, Duration::from_millis(batch_linger_ms))
+            .await
+        {
+            Ok(messages) if messages.is_empty() => {
+                // No messages within the linger window, continue polling
+            }
+            Ok(messages) => {
+                backoff.reset();
+
+                // A message that fails to deserialize is delivered again, along with
+                // the rest of its partition, until it is dead-lettered
+                let mut events = Vec::with_capacity(messages.len());
+                let mut redeliver: Vec<ReceivedMessage> = Vec::new();
+                for message in messages {
+                    if redeliver
+                        .iter()
+                        .any(|m| m.topic == message.topic && m.partition == message.partition)
+                    {
+                        continue;
+                    }
+
+                    match serde_json::from_slice::<EventEnvelope>(&message.payload) {
+                        Ok(envelope) => {
+                            poison.clear(&message);
+                            events.push(PendingEvent {
+                                topic: message.topic,
+                                aggregate_id: envelope.aggregate_id,
+                                event_type: envelope.event_type,
+                                payload: envelope.payload,
+                                version: envelope.sequence_number,
+                                created_at: Some(envelope.timestamp),
+                                correlation_id: Some(envelope.metadata.correlation_id),
+                                user_id: envelope.metadata.user_id,
+                            });
+                        }
+                        Err(e) => {
+                            let error = format!("Failed to deserialize event envelope: {}", e);
+                            match poison.record_failure(&message, &error) {
+                                PoisonVerdict::Retry { .. } => redeliver.push(message),
+                                PoisonVerdict::Poison { attempts } => {
+                                    if let Err(e) =
+                                        dead_letters.publish(&message, &error, attempts).await
+                                    {
+                                        error!("Failed to dead-letter poison message: {}", e);
+                                        redeliver.push(message);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Offsets are only committed once the batch is in the database
+                let applied = match retry(&batch_retry, || async {
+                    processor.lock().await.process_batch(&events).await
+                })
+                .await
+                {
+                    Ok(count) => {
+                        if count < events.len() {
+                            warn!("Skipped {} failed events in batch", events.len() - count);
                         }
+                        true
                     }
                     Err(e) => {
-                        error!("Failed to deserialize event envelope: {}", e);
+                        error!("Stopped applying batch during shutdown: {}", e);
+                        false
+                    }
+                };
+
+                if applied && redeliver.is_empty() {
+                    if let Err(e) = consumer.commit() {
+                        warn!("Failed to commit Kafka offsets: {}", e);
+                    }
+                }
+
+                // Offsets stay uncommitted until the rewound messages are handled
+                for message in &redeliver {
+                    if let Err(e) = consumer.redeliver(message) {
+                        error!(
+                            "Failed to rewind {}/{} to offset {}: {}",
+                            message.topic, message.partition, message.offset, e
+                        );
                     }
                 }
-            }
-            Ok(None) => {
-                // No message, continue polling
-                tokio::time::sleep(Duration::from_millis(10)).await;
             }
             Err(e) => {
-                error!("Error polling Kafka: {}", e);
-                tokio::time::sleep(Duration::from_secs(1)).await;
+                let delay = backoff.next_delay();
+                error!(
+                    "Error polling Kafka (attempt {}), retrying in {:?}: {}",
+                    backoff.failures(),
+                    delay,
+                    e
+                );
+
+                if e.is_fatal() {
+                    if let Err(e) = consumer.reconnect() {
+                        error!("Failed to recreate Kafka consumer: {}", e);
+                    }
+                }
+
+                tokio::time::sleep(delay).await;
             }
         }
     }
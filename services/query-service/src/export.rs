@@ -0,0 +1,314 @@
+use arrow_array::{
+    ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use read_model::{OrderField, OrderView, ReadModelError};
+use serde::Deserialize;
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Failed to read orders: {0}")]
+    ReadModel(#[from] ReadModelError),
+
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] ArrowError),
+
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] ParquetError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// File format of an order export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+
+    pub fn encoder(&self) -> Result<Box<dyn ExportEncoder>, ExportError> {
+        Ok(match self {
+            ExportFormat::Csv => Box::new(CsvEncoder::default()),
+            ExportFormat::Parquet => Box::new(ParquetEncoder::new()?),
+        })
+    }
+}
+
+/// Turns pages of orders into consecutive chunks of an export file
+pub trait ExportEncoder: Send {
+    fn encode_page(&mut self, orders: &[OrderView]) -> Result<Vec<u8>, ExportError>;
+
+    /// Whatever is left of the file once every page is encoded
+    fn finish(self: Box<Self>) -> Result<Vec<u8>, ExportError>;
+}
+
+/// One row per order, with a header of `OrderField` names; JSON columns stay JSON
+#[derive(Default)]
+struct CsvEncoder {
+    header_written: bool,
+}
+
+impl CsvEncoder {
+    fn write(&mut self, orders: &[OrderView]) -> Result<Vec<u8>, ExportError> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        if !self.header_written {
+            writer.write_record(OrderField::ALL.iter().map(|field| field.name()))?;
+            self.header_written = true;
+        }
+        for order in orders {
+            writer.write_record(csv_record(order))?;
+        }
+        writer
+            .into_inner()
+            .map_err(|e| ExportError::Io(e.into_error()))
+    }
+}
+
+impl ExportEncoder for CsvEncoder {
+    fn encode_page(&mut self, orders: &[OrderView]) -> Result<Vec<u8>, ExportError> {
+        self.write(orders)
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<Vec<u8>, ExportError> {
+        // An empty export still gets its header
+        self.write(&[])
+    }
+}
+
+fn csv_record(order: &OrderView) -> [String; 15] {
+    let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+    [
+        order.order_id.to_string(),
+        order.customer_id.to_string(),
+        order.order_number.clone(),
+        order.status.clone(),
+        order.total_amount.to_string(),
+        order.currency.clone(),
+        order.items.to_string(),
+        order
+            .shipping_address
+            .as_ref()
+            .map(|address| address.to_string())
+            .unwrap_or_default(),
+        optional(&order.tracking_number),
+        optional(&order.carrier),
+        optional(&order.customer_name),
+        optional(&order.customer_email),
+        order.created_at.to_rfc3339(),
+        order.updated_at.to_rfc3339(),
+        order.version.to_string(),
+    ]
+}
+
+/// Snappy-compressed Parquet, one row group per page
+struct ParquetEncoder {
+    schema: SchemaRef,
+    writer: ArrowWriter<Vec<u8>>,
+}
+
+impl ParquetEncoder {
+    fn new() -> Result<Self, ExportError> {
+        let schema = parquet_schema();
+        let props = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let writer = ArrowWriter::try_new(Vec::new(), schema.clone(), Some(props))?;
+        Ok(Self { schema, writer })
+    }
+}
+
+impl ExportEncoder for ParquetEncoder {
+    fn encode_page(&mut self, orders: &[OrderView]) -> Result<Vec<u8>, ExportError> {
+        if !orders.is_empty() {
+            self.writer.write(&record_batch(&self.schema, orders)?)?;
+            self.writer.flush()?;
+        }
+        // Hand over what the writer has produced so far; it only tracks offsets
+        Ok(std::mem::take(self.writer.inner_mut()))
+    }
+
+    fn finish(self: Box<Self>) -> Result<Vec<u8>, ExportError> {
+        Ok(self.writer.into_inner()?)
+    }
+}
+
+fn parquet_schema() -> SchemaRef {
+    let timestamp = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+    let field = |field: OrderField, data_type: DataType, nullable: bool| {
+        Field::new(field.name(), data_type, nullable)
+    };
+    Arc::new(Schema::new(vec![
+        field(OrderField::OrderId, DataType::Utf8, false),
+        field(OrderField::CustomerId, DataType::Utf8, false),
+        field(OrderField::OrderNumber, DataType::Utf8, false),
+        field(OrderField::Status, DataType::Utf8, false),
+        field(OrderField::TotalAmount, DataType::Float64, false),
+        field(OrderField::Currency, DataType::Utf8, false),
+        field(OrderField::Items, DataType::Utf8, false),
+        field(OrderField::ShippingAddress, DataType::Utf8, true),
+        field(OrderField::TrackingNumber, DataType::Utf8, true),
+        field(OrderField::Carrier, DataType::Utf8, true),
+        field(OrderField::CustomerName, DataType::Utf8, true),
+        field(OrderField::CustomerEmail, DataType::Utf8, true),
+        field(OrderField::CreatedAt, timestamp.clone(), false),
+        field(OrderField::UpdatedAt, timestamp, false),
+        field(OrderField::Version, DataType::Int64, false),
+    ]))
+}
+
+fn record_batch(schema: &SchemaRef, orders: &[OrderView]) -> Result<RecordBatch, ArrowError> {
+    let strings = |value: fn(&OrderView) -> String| -> ArrayRef {
+        Arc::new(StringArray::from(
+            orders.iter().map(value).collect::<Vec<_>>(),
+        ))
+    };
+    let optional_strings = |value: fn(&OrderView) -> Option<String>| -> ArrayRef {
+        Arc::new(StringArray::from(
+            orders.iter().map(value).collect::<Vec<_>>(),
+        ))
+    };
+    let timestamps = |value: fn(&OrderView) -> i64| -> ArrayRef {
+        Arc::new(
+            TimestampMicrosecondArray::from(orders.iter().map(value).collect::<Vec<_>>())
+                .with_timezone("UTC"),
+        )
+    };
+
+    let columns: Vec<ArrayRef> = vec![
+        strings(|order| order.order_id.to_string()),
+        strings(|order| order.customer_id.to_string()),
+        strings(|order| order.order_number.clone()),
+        strings(|order| order.status.clone()),
+        Arc::new(Float64Array::from(
+            orders
+                .iter()
+                .map(|order| order.total_amount)
+                .collect::<Vec<_>>(),
+        )),
+        strings(|order| order.currency.clone()),
+        strings(|order| order.items.to_string()),
+        optional_strings(|order| {
+            order
+                .shipping_address
+                .as_ref()
+                .map(|address| address.to_string())
+        }),
+        optional_strings(|order| order.tracking_number.clone()),
+        optional_strings(|order| order.carrier.clone()),
+        optional_strings(|order| order.customer_name.clone()),
+        optional_strings(|order| order.customer_email.clone()),
+        timestamps(|order| order.created_at.timestamp_micros()),
+        timestamps(|order| order.updated_at.timestamp_micros()),
+        Arc::new(Int64Array::from(
+            orders.iter().map(|order| order.version).collect::<Vec<_>>(),
+        )),
+    ];
+
+    RecordBatch::try_new(schema.clone(), columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use chrono::Utc;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn order(order_number: &str) -> OrderView {
+        OrderView {
+            order_id: Uuid::new_v4(),
+            customer_id: Uuid::new_v4(),
+            order_number: order_number.to_string(),
+            status: "DELIVERED".to_string(),
+            total_amount: 12.5,
+            currency: "EUR".to_string(),
+            items: json!([{ "sku": "A", "quantity": 1 }]),
+            shipping_address: None,
+            tracking_number: Some("TRK, 1".to_string()),
+            carrier: None,
+            customer_name: None,
+            customer_email: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 4,
+        }
+    }
+
+    fn export(format: ExportFormat, pages: &[Vec<OrderView>]) -> Vec<u8> {
+        let mut encoder = format.encoder().unwrap();
+        let mut file = Vec::new();
+        for page in pages {
+            file.extend(encoder.encode_page(page).unwrap());
+        }
+        file.extend(encoder.finish().unwrap());
+        file
+    }
+
+    #[test]
+    fn test_csv_export_has_header_and_quoted_rows() {
+        let file = export(
+            ExportFormat::Csv,
+            &[vec![order("ORD-1")], vec![order("ORD-2")]],
+        );
+        let text = String::from_utf8(file).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("order_id,customer_id,order_number,status"));
+        assert!(lines[1].contains(",ORD-1,DELIVERED,12.5,EUR,"));
+        assert!(lines[1].contains("\"TRK, 1\""));
+        assert!(lines[2].contains(",ORD-2,"));
+    }
+
+    #[test]
+    fn test_empty_csv_export_is_just_the_header() {
+        let text = String::from_utf8(export(ExportFormat::Csv, &[])).unwrap();
+        assert_eq!(text.lines().count(), 1);
+    }
+
+    #[test]
+    fn test_parquet_export_round_trips() {
+        let file = export(
+            ExportFormat::Parquet,
+            &[vec![order("ORD-1"), order("ORD-2")], vec![order("ORD-3")]],
+        );
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(file))
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.collect::<Result<_, _>>().unwrap();
+        let rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(rows, 3);
+        assert_eq!(batches[0].schema(), parquet_schema());
+    }
+}
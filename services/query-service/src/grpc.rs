@@ -0,0 +1,268 @@
+use read_model::OrderView;
+use std::net::SocketAddr;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{metadata::MetadataValue, Request, Response, Status};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::handlers::get_order::{fetch_order, STALE_HEADER};
+use crate::handlers::list_by_status::VALID_STATUSES;
+use crate::hedging::HedgedRead;
+use crate::state::AppState;
+
+pub mod proto {
+    tonic::include_proto!("cqrs.query.v1");
+}
+
+use proto::list_orders_request::Filter;
+use proto::order_query_server::{OrderQuery, OrderQueryServer};
+use proto::{GetOrderRequest, ListOrdersRequest, Order};
+
+/// Orders fetched from the read model per round trip while streaming a list
+const PAGE_SIZE: i64 = 100;
+
+/// gRPC counterpart of the REST order endpoints, backed by the same repository and cache
+pub struct OrderQueryService {
+    state: AppState,
+}
+
+impl OrderQueryService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl OrderQuery for OrderQueryService {
+    async fn get_order(
+        &self,
+        request: Request<GetOrderRequest>,
+    ) -> Result<Response<Order>, Status> {
+        let order_id = parse_id("order_id", &request.get_ref().order_id)?;
+
+        match fetch_order(&self.state, order_id).await {
+            Ok(Some(HedgedRead { value, stale })) => {
+                let mut response = Response::new(Order::from(value));
+                if stale {
+                    response
+                        .metadata_mut()
+                        .insert(STALE_HEADER, MetadataValue::from_static("true"));
+                }
+                Ok(response)
+            }
+            Ok(None) => Err(Status::not_found(format!("Order not found: {}", order_id))),
+            Err(e) => Err(Status::internal(format!("Failed to fetch order: {}", e))),
+        }
+    }
+
+    type ListOrdersStream = ReceiverStream<Result<Order, Status>>;
+
+    async fn list_orders(
+        &self,
+        request: Request<ListOrdersRequest>,
+    ) -> Result<Response<Self::ListOrdersStream>, Status> {
+        let request = request.into_inner();
+        let filter = ListFilter::parse(request.filter)?;
+        if request.limit < 0 {
+            return Err(Status::invalid_argument("Limit must be >= 0"));
+        }
+        if request.offset < 0 {
+            return Err(Status::invalid_argument("Offset must be >= 0"));
+        }
+
+        info!(
+            "Streaming orders for {:?} (limit: {}, offset: {})",
+            filter, request.limit, request.offset
+        );
+
+        let (tx, rx) = mpsc::channel(PAGE_SIZE as usize);
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            let mut offset = request.offset;
+            let mut remaining = (request.limit > 0).then_some(request.limit);
+
+            loop {
+                let page_size = remaining.map_or(PAGE_SIZE, |n| n.min(PAGE_SIZE));
+                if page_size == 0 {
+                    break;
+                }
+
+                let page = match &filter {
+                    ListFilter::Customer(customer_id) => {
+                        state
+                            .repository
+                            .list_by_customer(*customer_id, page_size, offset)
+                            .await
+                    }
+                    ListFilter::Status(status) => {
+                        state
+                            .repository
+                            .list_by_status(status, page_size, offset)
+                            .await
+                    }
+                };
+                let orders = match page {
+                    Ok(orders) => orders,
+                    Err(e) => {
+                        error!("Failed to list orders for {:?}: {}", filter, e);
+                        let _ = tx
+                            .send(Err(Status::internal(format!(
+                                "Failed to list orders: {}",
+                                e
+                            ))))
+                            .await;
+                        return;
+                    }
+                };
+
+                let fetched = orders.len() as i64;
+                for order in orders {
+                    if tx.send(Ok(Order::from(order))).await.is_err() {
+                        // Client went away
+                        return;
+                    }
+                }
+
+                if fetched < page_size {
+                    break;
+                }
+                offset += fetched;
+                remaining = remaining.map(|n| n - fetched);
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ListFilter {
+    Customer(Uuid),
+    Status(String),
+}
+
+impl ListFilter {
+    fn parse(filter: Option<Filter>) -> Result<Self, Status> {
+        match filter {
+            Some(Filter::CustomerId(id)) => Ok(Self::Customer(parse_id("customer_id", &id)?)),
+            Some(Filter::Status(status)) => {
+                let status = status.to_uppercase();
+                if !VALID_STATUSES.contains(&status.as_str()) {
+                    return Err(Status::invalid_argument(format!(
+                        "Invalid status. Must be one of: {:?}",
+                        VALID_STATUSES
+                    )));
+                }
+                Ok(Self::Status(status))
+            }
+            None => Err(Status::invalid_argument(
+                "Either customer_id or status is required",
+            )),
+        }
+    }
+}
+
+fn parse_id(field: &str, value: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(value)
+        .map_err(|_| Status::invalid_argument(format!("Invalid {}: {}", field, value)))
+}
+
+impl From<OrderView> for Order {
+    fn from(order: OrderView) -> Self {
+        Self {
+            order_id: order.order_id.to_string(),
+            customer_id: order.customer_id.to_string(),
+            order_number: order.order_number,
+            status: order.status,
+            total_amount: order.total_amount,
+            currency: order.currency,
+            items_json: order.items.to_string(),
+            shipping_address_json: order.shipping_address.map(|address| address.to_string()),
+            tracking_number: order.tracking_number,
+            carrier: order.carrier,
+            customer_name: order.customer_name,
+            customer_email: order.customer_email,
+            created_at: order.created_at.to_rfc3339(),
+            updated_at: order.updated_at.to_rfc3339(),
+            version: order.version,
+        }
+    }
+}
+
+/// Serve the gRPC API on `addr` in the background
+pub fn spawn(state: AppState, addr: SocketAddr) {
+    tokio::spawn(async move {
+        info!("Query service gRPC listening on {}", addr);
+        if let Err(e) = tonic::transport::Server::builder()
+            .add_service(OrderQueryServer::new(OrderQueryService::new(state)))
+            .serve(addr)
+            .await
+        {
+            error!("gRPC server error: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    #[test]
+    fn test_order_view_conversion() {
+        let now = Utc::now();
+        let view = OrderView {
+            order_id: Uuid::new_v4(),
+            customer_id: Uuid::new_v4(),
+            order_number: "ORD-1".to_string(),
+            status: "CREATED".to_string(),
+            total_amount: 42.5,
+            currency: "USD".to_string(),
+            items: json!([{ "sku": "A", "quantity": 2 }]),
+            shipping_address: None,
+            tracking_number: None,
+            carrier: None,
+            customer_name: Some("Ada".to_string()),
+            customer_email: None,
+            created_at: now,
+            updated_at: now,
+            version: 3,
+        };
+
+        let order = Order::from(view.clone());
+        assert_eq!(order.order_id, view.order_id.to_string());
+        assert_eq!(order.total_amount, 42.5);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&order.items_json).unwrap(),
+            view.items
+        );
+        assert_eq!(order.shipping_address_json, None);
+        assert_eq!(order.customer_name.as_deref(), Some("Ada"));
+        assert_eq!(order.created_at, now.to_rfc3339());
+        assert_eq!(order.version, 3);
+    }
+
+    #[test]
+    fn test_list_filter_validation() {
+        let customer_id = Uuid::new_v4();
+        assert_eq!(
+            ListFilter::parse(Some(Filter::CustomerId(customer_id.to_string()))).unwrap(),
+            ListFilter::Customer(customer_id)
+        );
+        assert_eq!(
+            ListFilter::parse(Some(Filter::Status("shipped".to_string()))).unwrap(),
+            ListFilter::Status("SHIPPED".to_string())
+        );
+
+        for filter in [
+            None,
+            Some(Filter::CustomerId("not-a-uuid".to_string())),
+            Some(Filter::Status("LOST".to_string())),
+        ] {
+            let status = ListFilter::parse(filter).unwrap_err();
+            assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        }
+    }
+}
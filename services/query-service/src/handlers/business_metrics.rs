@@ -0,0 +1,75 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use read_model::BusinessKpis;
+use serde::Deserialize;
+use tracing::{error, info};
+
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct BusinessMetricsParams {
+    #[serde(default = "default_window_minutes")]
+    pub window_minutes: i64,
+}
+
+fn default_window_minutes() -> i64 {
+    60
+}
+
+/// Orders per minute, average order value and cancellation rate over a trailing window
+///
+/// Computed from the per-minute counters kept by the order projection and cached in
+/// Redis briefly, so a dashboard polling it costs one small query per TTL.
+pub async fn business_metrics_handler(
+    State(state): State<AppState>,
+    Query(params): Query<BusinessMetricsParams>,
+) -> Result<Json<BusinessKpis>, (StatusCode, String)> {
+    if params.window_minutes < 1 || params.window_minutes > 1440 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Window must be between 1 and 1440 minutes".to_string(),
+        ));
+    }
+
+    let cache_key = format!("business:{}", params.window_minutes);
+    if let Some(cached) = state.cache.get_metrics::<BusinessKpis>(&cache_key).await {
+        return Ok(Json(cached));
+    }
+
+    info!("Computing business metrics over {} minutes", params.window_minutes);
+
+    let now = Utc::now();
+    let since = now - Duration::minutes(params.window_minutes);
+    match state.business_metrics.totals_since(since).await {
+        Ok(totals) => {
+            let kpis = BusinessKpis::from_totals(params.window_minutes, totals, now);
+            state
+                .cache
+                .set_metrics(&cache_key, &kpis, state.business_metrics_ttl)
+                .await;
+            Ok(Json(kpis))
+        }
+        Err(e) => {
+            error!("Failed to compute business metrics: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to compute business metrics: {}", e),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_params() {
+        let params: BusinessMetricsParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(params.window_minutes, 60);
+    }
+}
@@ -0,0 +1,118 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    Json,
+};
+use read_model::{EventCursor, PositionedEvent};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::state::AppState;
+
+/// Cursor of the last event a read reflects, to bookmark it and sync from it later
+pub const EVENT_POSITION_HEADER: &str = "x-event-position";
+
+#[derive(Debug, Deserialize)]
+pub struct EventsParams {
+    /// Cursor of the last event already seen (`{transaction_id}.{position}`); 0 starts
+    /// from the beginning
+    #[serde(default = "default_after")]
+    pub after: String,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_after() -> String {
+    "0".to_string()
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventsPage {
+    pub events: Vec<PositionedEvent>,
+    /// Pass as `after` to fetch the next page; unchanged when there are no new events
+    pub next_cursor: String,
+}
+
+/// List events after a cursor, for incremental sync
+pub async fn list_events_handler(
+    State(state): State<AppState>,
+    Query(params): Query<EventsParams>,
+) -> Result<(HeaderMap, Json<EventsPage>), (StatusCode, String)> {
+    info!("Listing events after {} (limit: {})", params.after, params.limit);
+
+    let after: EventCursor = params
+        .after
+        .parse()
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("after: {}", e)))?;
+    if params.limit < 1 || params.limit > 1000 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Limit must be between 1 and 1000".to_string(),
+        ));
+    }
+
+    match state.events.events_after(after, params.limit).await {
+        Ok(events) => {
+            let next_cursor = events.last().map_or(after, PositionedEvent::cursor);
+            Ok((
+                position_headers(Some(next_cursor)),
+                Json(EventsPage {
+                    events,
+                    next_cursor: next_cursor.to_string(),
+                }),
+            ))
+        }
+        Err(e) => {
+            error!("Failed to list events after {}: {}", after, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to list events: {}", e),
+            ))
+        }
+    }
+}
+
+/// [`EVENT_POSITION_HEADER`] for a view of `aggregate_id` at `version`
+///
+/// The lookup is best effort: without it the read is still served, just unbookmarked.
+pub async fn event_position(state: &AppState, aggregate_id: Uuid, version: i64) -> HeaderMap {
+    match state.events.cursor_of(aggregate_id, version).await {
+        Ok(cursor) => position_headers(cursor),
+        Err(e) => {
+            warn!(
+                "Failed to look up event cursor of {} at version {}: {}",
+                aggregate_id, version, e
+            );
+            HeaderMap::new()
+        }
+    }
+}
+
+fn position_headers(cursor: Option<EventCursor>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    // A cursor is digits and a dot, always a valid header value
+    if let Some(value) = cursor.and_then(|cursor| HeaderValue::from_str(&cursor.to_string()).ok()) {
+        headers.insert(EVENT_POSITION_HEADER, value);
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_headers() {
+        let cursor = EventCursor {
+            transaction_id: 812,
+            position: 42,
+        };
+        assert_eq!(position_headers(Some(cursor))[EVENT_POSITION_HEADER], "812.42");
+        assert!(position_headers(None).is_empty());
+    }
+}
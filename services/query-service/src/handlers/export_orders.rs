@@ -0,0 +1,134 @@
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use futures::{stream, TryStreamExt};
+use read_model::OrderExportFilter;
+use serde::Deserialize;
+use tracing::{error, info};
+
+use crate::export::{ExportError, ExportFormat};
+use crate::handlers::list_by_status::VALID_STATUSES;
+use crate::state::AppState;
+
+/// Orders read from the database per round trip; one CSV chunk or Parquet row group each
+const EXPORT_PAGE_SIZE: i64 = 5000;
+
+#[derive(Debug, Deserialize)]
+pub struct ExportParams {
+    pub status: Option<String>,
+    /// Orders created at or after, RFC 3339
+    pub from: Option<DateTime<Utc>>,
+    /// Orders created before, RFC 3339
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// Stream matching orders, oldest first, as a CSV or Parquet file
+///
+/// Orders are read and encoded a page at a time while the response is sent.
+pub async fn export_orders_handler(
+    State(state): State<AppState>,
+    Query(params): Query<ExportParams>,
+) -> Result<Response, (StatusCode, String)> {
+    let status = match params.status {
+        Some(status) => {
+            let status = status.to_uppercase();
+            if !VALID_STATUSES.contains(&status.as_str()) {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid status. Must be one of: {:?}", VALID_STATUSES),
+                ));
+            }
+            Some(status)
+        }
+        None => None,
+    };
+    if let (Some(from), Some(to)) = (params.from, params.to) {
+        if from >= to {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "from must be before to".to_string(),
+            ));
+        }
+    }
+
+    let encoder = params.format.encoder().map_err(|e| {
+        error!("Failed to start order export: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to start export: {}", e),
+        )
+    })?;
+
+    info!(
+        "Exporting orders as {:?} (status: {:?}, from: {:?}, to: {:?})",
+        params.format, status, params.from, params.to
+    );
+
+    let pages = state.repository.export(
+        OrderExportFilter {
+            status,
+            from: params.from,
+            to: params.to,
+        },
+        EXPORT_PAGE_SIZE,
+    );
+
+    // Encode each page as it arrives, then whatever closes the file
+    let chunks = stream::try_unfold((pages, Some(encoder)), |(mut pages, encoder)| async move {
+        let Some(mut encoder) = encoder else {
+            return Ok(None);
+        };
+        match pages.try_next().await? {
+            Some(orders) => {
+                let chunk = encoder.encode_page(&orders)?;
+                Ok(Some((chunk, (pages, Some(encoder)))))
+            }
+            None => Ok(Some((encoder.finish()?, (pages, None)))),
+        }
+    })
+    .inspect_err(|e: &ExportError| error!("Order export failed: {}", e));
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                params.format.content_type().to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"orders.{}\"",
+                    params.format.extension()
+                ),
+            ),
+        ],
+        Body::from_stream(chunks),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_params_deserialization() {
+        let params: ExportParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(params.format, ExportFormat::Csv);
+        assert!(params.status.is_none() && params.from.is_none() && params.to.is_none());
+
+        let params: ExportParams = serde_json::from_str(
+            r#"{"status": "delivered", "from": "2024-01-01T00:00:00Z", "format": "parquet"}"#,
+        )
+        .unwrap();
+        assert_eq!(params.format, ExportFormat::Parquet);
+        assert_eq!(params.status.as_deref(), Some("delivered"));
+        assert!(params.from.is_some());
+    }
+}
@@ -0,0 +1,55 @@
+use axum::http::StatusCode;
+use read_model::{OrderField, OrderFields, OrderView, PartialOrderView};
+use serde::{Deserialize, Serialize};
+
+/// Sparse fieldset for the order endpoints, e.g. `?fields=order_id,status,total_amount`
+#[derive(Debug, Default, Deserialize)]
+pub struct FieldsParam {
+    pub fields: Option<String>,
+}
+
+/// An order, in full or with only the fields the client selected
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum OrderBody {
+    Full(OrderView),
+    Partial(PartialOrderView),
+}
+
+/// Parse `?fields=`; `None` when the client wants whole orders
+pub fn parse_fields(fields: Option<&str>) -> Result<Option<OrderFields>, (StatusCode, String)> {
+    let Some(fields) = fields else {
+        return Ok(None);
+    };
+
+    OrderFields::parse(fields).map(Some).map_err(|unknown| {
+        let valid: Vec<&str> = OrderField::ALL.iter().map(|field| field.name()).collect();
+        (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Invalid field '{}'. Fields must be a comma-separated list of: {}",
+                unknown,
+                valid.join(", ")
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fields() {
+        assert_eq!(parse_fields(None).unwrap(), None);
+
+        let fields = parse_fields(Some("order_id,status")).unwrap().unwrap();
+        assert_eq!(fields.fields(), &[OrderField::OrderId, OrderField::Status]);
+
+        let (status, message) = parse_fields(Some("order_id,secret")).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(message.contains("'secret'"));
+        assert!(message.contains("shipping_address"));
+    }
+}
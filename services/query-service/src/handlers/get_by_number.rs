@@ -1,27 +1,44 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
     Json,
 };
-use read_model::OrderView;
 use tracing::{error, info};
 
+use crate::handlers::events::event_position;
+use crate::handlers::fields::{parse_fields, FieldsParam, OrderBody};
 use crate::state::AppState;
 
 /// Get an order by order number
 pub async fn get_order_by_number_handler(
     State(state): State<AppState>,
     Path(order_number): Path<String>,
-) -> Result<Json<OrderView>, (StatusCode, String)> {
+    Query(params): Query<FieldsParam>,
+) -> Result<(HeaderMap, Json<OrderBody>), (StatusCode, String)> {
     info!("Searching for order by number: {}", order_number);
 
-    match state.repository.search_by_order_number(&order_number).await {
-        Ok(Some(order)) => {
-            // Update cache
-            state.cache.set(&order.order_id, &order).await;
+    let result = match parse_fields(params.fields.as_deref())? {
+        Some(fields) => state
+            .repository
+            .search_fields_by_order_number(&order_number, &fields)
+            .await
+            .map(|order| order.map(OrderBody::Partial)),
+        None => state
+            .repository
+            .search_by_order_number(&order_number)
+            .await
+            .map(|order| order.map(OrderBody::Full)),
+    };
 
+    match result {
+        Ok(Some(OrderBody::Full(order))) => {
             info!("Successfully found order: {} ({})", order_number, order.order_id);
-            Ok(Json(order))
+            let headers = event_position(&state, order.order_id, order.version).await;
+            Ok((headers, Json(OrderBody::Full(order))))
+        }
+        Ok(Some(order)) => {
+            info!("Successfully found order: {}", order_number);
+            Ok((HeaderMap::new(), Json(order)))
         }
         Ok(None) => {
             info!("Order not found with number: {}", order_number);
@@ -0,0 +1,32 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use read_model::InventoryView;
+use tracing::{error, info};
+
+use crate::state::AppState;
+
+/// Get the on-hand, reserved and available stock of a SKU
+pub async fn get_inventory_handler(
+    State(state): State<AppState>,
+    Path(sku): Path<String>,
+) -> Result<Json<InventoryView>, (StatusCode, String)> {
+    info!("Fetching inventory for sku: {}", sku);
+
+    match state.inventory.get_by_sku(&sku).await {
+        Ok(Some(view)) => Ok(Json(view)),
+        Ok(None) => {
+            info!("Inventory not found for sku: {}", sku);
+            Err((StatusCode::NOT_FOUND, format!("Inventory not found for sku: {}", sku)))
+        }
+        Err(e) => {
+            error!("Failed to fetch inventory for sku {}: {}", sku, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch inventory: {}", e),
+            ))
+        }
+    }
+}
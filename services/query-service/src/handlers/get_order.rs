@@ -1,56 +1,126 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
     Json,
 };
-use read_model::OrderView;
+use read_model::{OrderFields, OrderView, PartialOrderView, ReadModelError};
 use tracing::{error, info};
 use uuid::Uuid;
 
+use crate::handlers::events::event_position;
+use crate::handlers::fields::{parse_fields, FieldsParam, OrderBody};
+use crate::hedging::HedgedRead;
 use crate::state::AppState;
 
+/// Set to `true` on responses served from the fallback copy while the database was slow
+pub const STALE_HEADER: &str = "x-data-stale";
+
 /// Get a single order by ID
 pub async fn get_order_handler(
     State(state): State<AppState>,
     Path(order_id): Path<Uuid>,
-) -> Result<Json<OrderView>, (StatusCode, String)> {
-    info!("Fetching order: {}", order_id);
+    Query(params): Query<FieldsParam>,
+) -> Result<(HeaderMap, Json<OrderBody>), (StatusCode, String)> {
+    let result = match parse_fields(params.fields.as_deref())? {
+        Some(fields) => fetch_order_fields(&state, order_id, &fields)
+            .await
+            .map(|order| order.map(|order| (HeaderMap::new(), OrderBody::Partial(order)))),
+        None => match fetch_order(&state, order_id).await {
+            Ok(Some(HedgedRead { value, stale })) => {
+                let mut headers = event_position(&state, order_id, value.version).await;
+                if stale {
+                    headers.extend(stale_headers());
+                }
+                Ok(Some((headers, OrderBody::Full(value))))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        },
+    };
 
-    // Try cache first
-    if let Some(cached) = state.cache.get::<OrderView>(&order_id).await {
-        info!("Cache hit for order: {}", order_id);
-        return Ok(Json(cached));
+    match result {
+        Ok(Some((headers, order))) => Ok((headers, Json(order))),
+        Ok(None) => Err((StatusCode::NOT_FOUND, format!("Order not found: {}", order_id))),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch order: {}", e),
+        )),
     }
+}
 
-    info!("Cache miss for order: {}, querying database", order_id);
-
-    // Query database
-    match state.repository.get_by_id(order_id).await {
-        Ok(Some(order)) => {
-            // Update cache
-            state.cache.set(&order_id, &order).await;
+/// Look an order up through the cache, then the database hedged with the fallback copy
+///
+/// Shared by the REST and gRPC APIs.
+pub async fn fetch_order(
+    state: &AppState,
+    order_id: Uuid,
+) -> Result<Option<HedgedRead<OrderView>>, ReadModelError> {
+    info!("Fetching order: {}", order_id);
 
-            info!("Successfully retrieved order: {}", order_id);
-            Ok(Json(order))
+    // The repository reads through the cache; hedge it with the fallback copy if it is slow
+    let primary = state.repository.get_by_id(order_id);
+    let result = match &state.hedge {
+        Some(hedge) => {
+            hedge
+                .read("get_order", primary, state.cache.get_fallback(&order_id))
+                .await
         }
-        Ok(None) => {
-            info!("Order not found: {}", order_id);
-            Err((StatusCode::NOT_FOUND, format!("Order not found: {}", order_id)))
+        None => primary
+            .await
+            .map(|order| order.map(|value| HedgedRead { value, stale: false })),
+    };
+
+    match &result {
+        Ok(Some(HedgedRead { stale: true, .. })) => {
+            info!("Served fallback copy of order: {}", order_id);
         }
-        Err(e) => {
-            error!("Failed to fetch order {}: {}", order_id, e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to fetch order: {}", e),
-            ))
+        Ok(Some(HedgedRead { value: order, .. })) => {
+            if state.hedge.is_some() {
+                state
+                    .cache
+                    .set_fallback(&order_id, order, state.fallback_ttl)
+                    .await;
+            }
+
+            info!("Successfully retrieved order: {}", order_id);
         }
+        Ok(None) => info!("Order not found: {}", order_id),
+        Err(e) => error!("Failed to fetch order {}: {}", order_id, e),
     }
+
+    result
+}
+
+/// Selected fields of an order, projected from the cached copy or read with a narrower query
+///
+/// Partial reads are neither cached nor hedged.
+async fn fetch_order_fields(
+    state: &AppState,
+    order_id: Uuid,
+    fields: &OrderFields,
+) -> Result<Option<PartialOrderView>, ReadModelError> {
+    let result = state.repository.get_fields_by_id(order_id, fields).await;
+    if let Err(e) = &result {
+        error!("Failed to fetch fields of order {}: {}", order_id, e);
+    }
+    result
+}
+
+fn stale_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(STALE_HEADER, HeaderValue::from_static("true"));
+    headers
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_stale_headers_flag_response() {
+        assert_eq!(stale_headers()[STALE_HEADER], "true");
+    }
+
     #[test]
     fn test_order_id_parsing() {
         let id = Uuid::new_v4();
@@ -0,0 +1,189 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use domain::events::order_events::OrderItem;
+use read_model::{OrderView, ProductView};
+use serde::Serialize;
+use std::collections::HashMap;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::state::AppState;
+
+/// Catalog details of an ordered product
+#[derive(Debug, Serialize)]
+pub struct ProductInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub active: bool,
+}
+
+/// One line of an order with its total
+#[derive(Debug, Serialize)]
+pub struct OrderItemView {
+    pub product_id: Uuid,
+    pub sku: String,
+    pub quantity: u32,
+    pub unit_price: f64,
+    pub line_total: f64,
+    /// `None` when the product is not in the catalog projection
+    pub product: Option<ProductInfo>,
+}
+
+/// Customer-facing view of an order
+#[derive(Debug, Serialize)]
+pub struct OrderSummaryResponse {
+    pub order_id: Uuid,
+    pub order_number: String,
+    pub status: String,
+    pub currency: String,
+    pub items: Vec<OrderItemView>,
+    pub item_count: u32,
+    pub total_amount: f64,
+    pub shipping_address: Option<serde_json::Value>,
+    pub tracking_number: Option<String>,
+    pub carrier: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Get an order with typed line items and product details
+pub async fn get_order_summary_handler(
+    State(state): State<AppState>,
+    Path(order_id): Path<Uuid>,
+) -> Result<Json<OrderSummaryResponse>, (StatusCode, String)> {
+    info!("Fetching order summary: {}", order_id);
+
+    let order = match state.repository.get_by_id(order_id).await {
+        Ok(Some(order)) => order,
+        Ok(None) => {
+            info!("Order not found: {}", order_id);
+            return Err((StatusCode::NOT_FOUND, format!("Order not found: {}", order_id)));
+        }
+        Err(e) => {
+            error!("Failed to fetch order {}: {}", order_id, e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch order: {}", e),
+            ));
+        }
+    };
+
+    let items: Vec<OrderItem> = serde_json::from_value(order.items.clone()).map_err(|e| {
+        error!("Stored items of order {} are malformed: {}", order_id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to read order items: {}", e),
+        )
+    })?;
+
+    let product_ids: Vec<Uuid> = items.iter().map(|item| item.product_id).collect();
+    // Product details are optional decoration; the summary is still useful without them
+    let products = match state.products.get_by_ids(&product_ids).await {
+        Ok(products) => products,
+        Err(e) => {
+            warn!("Failed to load products for order {}: {}", order_id, e);
+            Vec::new()
+        }
+    };
+
+    Ok(Json(build_summary(order, items, products)))
+}
+
+fn build_summary(
+    order: OrderView,
+    items: Vec<OrderItem>,
+    products: Vec<ProductView>,
+) -> OrderSummaryResponse {
+    let mut products: HashMap<Uuid, ProductView> = products
+        .into_iter()
+        .map(|product| (product.product_id, product))
+        .collect();
+
+    let items: Vec<OrderItemView> = items
+        .into_iter()
+        .map(|item| OrderItemView {
+            line_total: round_cents(item.unit_price * f64::from(item.quantity)),
+            product: products.remove(&item.product_id).map(|product| ProductInfo {
+                name: product.name,
+                description: product.description,
+                active: product.active,
+            }),
+            product_id: item.product_id,
+            sku: item.sku,
+            quantity: item.quantity,
+            unit_price: item.unit_price,
+        })
+        .collect();
+
+    OrderSummaryResponse {
+        order_id: order.order_id,
+        order_number: order.order_number,
+        status: order.status,
+        currency: order.currency,
+        item_count: items.iter().map(|item| item.quantity).sum(),
+        items,
+        total_amount: order.total_amount,
+        shipping_address: order.shipping_address,
+        tracking_number: order.tracking_number,
+        carrier: order.carrier,
+        created_at: order.created_at,
+        updated_at: order.updated_at,
+    }
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_expands_items_with_products() {
+        let product_id = Uuid::new_v4();
+        let unknown_id = Uuid::new_v4();
+        let items = vec![
+            OrderItem::new(product_id, "SKU-001".to_string(), 3, 19.99),
+            OrderItem::new(unknown_id, "SKU-002".to_string(), 1, 5.0),
+        ];
+        let order = OrderView {
+            order_id: Uuid::new_v4(),
+            customer_id: Uuid::new_v4(),
+            order_number: "ORD-1".to_string(),
+            status: "CREATED".to_string(),
+            total_amount: 64.97,
+            currency: "USD".to_string(),
+            items: serde_json::to_value(&items).unwrap(),
+            shipping_address: None,
+            tracking_number: None,
+            carrier: None,
+            customer_name: None,
+            customer_email: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 1,
+        };
+        let products = vec![ProductView {
+            product_id,
+            sku: "SKU-001".to_string(),
+            name: "Widget".to_string(),
+            description: None,
+            unit_price: 19.99,
+            currency: "USD".to_string(),
+            active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }];
+
+        let summary = build_summary(order, items, products);
+        assert_eq!(summary.item_count, 4);
+        assert_eq!(summary.items[0].line_total, 59.97);
+        assert_eq!(summary.items[0].product.as_ref().unwrap().name, "Widget");
+        assert!(summary.items[1].product.is_none());
+    }
+}
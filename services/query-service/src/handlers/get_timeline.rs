@@ -0,0 +1,47 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use read_model::TimelineEntry;
+use serde::Serialize;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::state::AppState;
+
+#[derive(Debug, Serialize)]
+pub struct OrderTimelineResponse {
+    pub order_id: Uuid,
+    pub entries: Vec<TimelineEntry>,
+}
+
+/// Chronological view of an order's events and related saga progress
+pub async fn get_order_timeline_handler(
+    State(state): State<AppState>,
+    Path(order_id): Path<Uuid>,
+) -> Result<Json<OrderTimelineResponse>, (StatusCode, String)> {
+    info!("Fetching timeline for order: {}", order_id);
+
+    match state.timeline.get_order_timeline(order_id).await {
+        Ok(entries) if entries.is_empty() => {
+            info!("Order not found: {}", order_id);
+            Err((StatusCode::NOT_FOUND, format!("Order not found: {}", order_id)))
+        }
+        Ok(entries) => {
+            info!(
+                "Successfully retrieved {} timeline entries for order: {}",
+                entries.len(),
+                order_id
+            );
+            Ok(Json(OrderTimelineResponse { order_id, entries }))
+        }
+        Err(e) => {
+            error!("Failed to fetch timeline for order {}: {}", order_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch order timeline: {}", e),
+            ))
+        }
+    }
+}
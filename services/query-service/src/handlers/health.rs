@@ -1,6 +1,8 @@
-use axum::{http::StatusCode, Json};
+use axum::{extract::State, http::StatusCode, Json};
 use serde::Serialize;
 
+use crate::state::AppState;
+
 #[derive(Serialize)]
 pub struct HealthResponse {
     pub status: String,
@@ -19,6 +21,35 @@ pub async fn health_check() -> (StatusCode, Json<HealthResponse>) {
     )
 }
 
+#[derive(Serialize)]
+pub struct ReadinessResponse {
+    pub status: String,
+    /// Orders cached by the start-up warm-up so far, out of `warmup_target`
+    pub warmed_orders: usize,
+    pub warmup_target: usize,
+}
+
+/// Readiness probe: 503 until the start-up cache warm-up has finished
+pub async fn readiness_check(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let (warmed_orders, warmup_target) = state.readiness.progress();
+    let (status, label) = if state.readiness.is_ready() {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "warming_up")
+    };
+
+    (
+        status,
+        Json(ReadinessResponse {
+            status: label.to_string(),
+            warmed_orders,
+            warmup_target,
+        }),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
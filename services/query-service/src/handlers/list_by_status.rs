@@ -3,10 +3,10 @@ use axum::{
     http::StatusCode,
     Json,
 };
-use read_model::OrderView;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 
+use crate::handlers::fields::{parse_fields, OrderBody};
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize)]
@@ -15,15 +15,20 @@ pub struct PaginationParams {
     pub limit: i64,
     #[serde(default)]
     pub offset: i64,
+    /// Comma-separated fields to return instead of whole orders
+    pub fields: Option<String>,
 }
 
 fn default_limit() -> i64 {
     20
 }
 
+/// Order statuses accepted by status filters
+pub const VALID_STATUSES: [&str; 5] = ["CREATED", "CONFIRMED", "CANCELLED", "SHIPPED", "DELIVERED"];
+
 #[derive(Debug, Serialize)]
 pub struct OrderListResponse {
-    pub orders: Vec<OrderView>,
+    pub orders: Vec<OrderBody>,
     pub status: String,
     pub limit: i64,
     pub offset: i64,
@@ -42,11 +47,10 @@ pub async fn list_orders_by_status_handler(
 
     // Validate status
     let status_upper = status.to_uppercase();
-    let valid_statuses = ["CREATED", "CONFIRMED", "CANCELLED", "SHIPPED", "DELIVERED"];
-    if !valid_statuses.contains(&status_upper.as_str()) {
+    if !VALID_STATUSES.contains(&status_upper.as_str()) {
         return Err((
             StatusCode::BAD_REQUEST,
-            format!("Invalid status. Must be one of: {:?}", valid_statuses),
+            format!("Invalid status. Must be one of: {:?}", VALID_STATUSES),
         ));
     }
 
@@ -62,12 +66,23 @@ pub async fn list_orders_by_status_handler(
         return Err((StatusCode::BAD_REQUEST, "Offset must be >= 0".to_string()));
     }
 
+    let fields = parse_fields(params.fields.as_deref())?;
+
     // Fetch orders
-    match state
-        .repository
-        .list_by_status(&status_upper, params.limit, params.offset)
-        .await
-    {
+    let result = match &fields {
+        Some(fields) => state
+            .repository
+            .list_fields_by_status(&status_upper, fields, params.limit, params.offset)
+            .await
+            .map(|orders| orders.into_iter().map(OrderBody::Partial).collect::<Vec<_>>()),
+        None => state
+            .repository
+            .list_by_status(&status_upper, params.limit, params.offset)
+            .await
+            .map(|orders| orders.into_iter().map(OrderBody::Full).collect()),
+    };
+
+    match result {
         Ok(orders) => {
             info!(
                 "Successfully retrieved {} orders with status: {}",
@@ -3,11 +3,11 @@ use axum::{
     http::StatusCode,
     Json,
 };
-use read_model::OrderView;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 use uuid::Uuid;
 
+use crate::handlers::fields::{parse_fields, OrderBody};
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize)]
@@ -16,6 +16,8 @@ pub struct PaginationParams {
     pub limit: i64,
     #[serde(default)]
     pub offset: i64,
+    /// Comma-separated fields to return instead of whole orders
+    pub fields: Option<String>,
 }
 
 fn default_limit() -> i64 {
@@ -24,7 +26,7 @@ fn default_limit() -> i64 {
 
 #[derive(Debug, Serialize)]
 pub struct OrderListResponse {
-    pub orders: Vec<OrderView>,
+    pub orders: Vec<OrderBody>,
     pub total: i64,
     pub limit: i64,
     pub offset: i64,
@@ -53,12 +55,23 @@ pub async fn list_customer_orders_handler(
         return Err((StatusCode::BAD_REQUEST, "Offset must be >= 0".to_string()));
     }
 
+    let fields = parse_fields(params.fields.as_deref())?;
+
     // Fetch orders
-    match state
-        .repository
-        .list_by_customer(customer_id, params.limit, params.offset)
-        .await
-    {
+    let result = match &fields {
+        Some(fields) => state
+            .repository
+            .list_fields_by_customer(customer_id, fields, params.limit, params.offset)
+            .await
+            .map(|orders| orders.into_iter().map(OrderBody::Partial).collect::<Vec<_>>()),
+        None => state
+            .repository
+            .list_by_customer(customer_id, params.limit, params.offset)
+            .await
+            .map(|orders| orders.into_iter().map(OrderBody::Full).collect()),
+    };
+
+    match result {
         Ok(orders) => {
             // Get total count
             let total = state
@@ -105,5 +118,11 @@ mod tests {
         let params: PaginationParams = serde_json::from_str(json).unwrap();
         assert_eq!(params.limit, 10);
         assert_eq!(params.offset, 5);
+        assert_eq!(params.fields, None);
+
+        let json = r#"{"fields": "order_id,status"}"#;
+        let params: PaginationParams = serde_json::from_str(json).unwrap();
+        assert_eq!(params.limit, 20);
+        assert_eq!(params.fields.as_deref(), Some("order_id,status"));
     }
 }
@@ -0,0 +1,112 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use read_model::{SagaTypeStats, SagaView};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::state::AppState;
+
+const VALID_STATUSES: [&str; 6] = [
+    "RUNNING",
+    "COMPENSATING",
+    "COMPLETED",
+    "COMPENSATED",
+    "REQUIRES_INTERVENTION",
+    "PAUSED_AWAITING_APPROVAL",
+];
+
+#[derive(Debug, Deserialize)]
+pub struct SagaListParams {
+    pub status: Option<String>,
+    #[serde(rename = "type")]
+    pub saga_type: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    20
+}
+
+#[derive(Debug, Serialize)]
+pub struct SagaListResponse {
+    pub sagas: Vec<SagaView>,
+    pub stats: Vec<SagaTypeStats>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// List sagas by status and type, with per-type failure rates and durations
+pub async fn list_sagas_handler(
+    State(state): State<AppState>,
+    Query(params): Query<SagaListParams>,
+) -> Result<Json<SagaListResponse>, (StatusCode, String)> {
+    info!(
+        "Listing sagas (status: {:?}, type: {:?}, limit: {}, offset: {})",
+        params.status, params.saga_type, params.limit, params.offset
+    );
+
+    let status = params.status.as_deref().map(str::to_uppercase);
+    if let Some(status) = &status {
+        if !VALID_STATUSES.contains(&status.as_str()) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Invalid status. Must be one of: {:?}", VALID_STATUSES),
+            ));
+        }
+    }
+
+    if params.limit < 1 || params.limit > 100 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Limit must be between 1 and 100".to_string(),
+        ));
+    }
+
+    if params.offset < 0 {
+        return Err((StatusCode::BAD_REQUEST, "Offset must be >= 0".to_string()));
+    }
+
+    let saga_type = params.saga_type.as_deref();
+    let sagas = state
+        .sagas
+        .list_sagas(status.as_deref(), saga_type, params.limit, params.offset)
+        .await;
+    let stats = state.sagas.saga_type_stats(saga_type).await;
+
+    match (sagas, stats) {
+        (Ok(sagas), Ok(stats)) => Ok(Json(SagaListResponse {
+            sagas,
+            stats,
+            limit: params.limit,
+            offset: params.offset,
+        })),
+        (Err(e), _) | (_, Err(e)) => {
+            error!("Failed to list sagas: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to list sagas: {}", e),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params_use_type_alias() {
+        let params: SagaListParams =
+            serde_json::from_str(r#"{"status": "completed", "type": "OrderProcessingSaga"}"#)
+                .unwrap();
+        assert_eq!(params.saga_type.as_deref(), Some("OrderProcessingSaga"));
+        assert_eq!(params.limit, 20);
+        assert_eq!(params.offset, 0);
+    }
+}
@@ -1,5 +1,18 @@
 pub mod health;
+pub mod events;
+pub mod export_orders;
+pub mod fields;
 pub mod get_order;
+pub mod get_order_summary;
+pub mod order_status;
 pub mod get_by_number;
 pub mod list_customer_orders;
 pub mod list_by_status;
+pub mod order_stats;
+pub mod search_orders;
+pub mod get_timeline;
+pub mod list_sagas;
+pub mod projection_errors;
+pub mod get_inventory;
+pub mod payments;
+pub mod business_metrics;
@@ -0,0 +1,85 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use read_model::{OrderStatsBucket, StatsGroupBy};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct StatsParams {
+    #[serde(default = "default_group_by")]
+    pub group_by: StatsGroupBy,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_group_by() -> StatsGroupBy {
+    StatsGroupBy::Status
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrderStatsResponse {
+    pub group_by: StatsGroupBy,
+    pub buckets: Vec<OrderStatsBucket>,
+    pub total_count: i64,
+    pub total_amount: f64,
+}
+
+/// Order counts and amount sums grouped by status, day or customer
+pub async fn order_stats_handler(
+    State(state): State<AppState>,
+    Query(params): Query<StatsParams>,
+) -> Result<Json<OrderStatsResponse>, (StatusCode, String)> {
+    info!(
+        "Computing order stats grouped by {:?} (limit: {})",
+        params.group_by, params.limit
+    );
+
+    if params.limit < 1 || params.limit > 1000 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Limit must be between 1 and 1000".to_string(),
+        ));
+    }
+
+    // Totals come from their own query so they include the buckets cut by the limit
+    let stats = tokio::try_join!(
+        state.repository.order_stats(params.group_by, params.limit),
+        state.repository.order_totals(),
+    );
+    match stats {
+        Ok((buckets, totals)) => Ok(Json(OrderStatsResponse {
+            group_by: params.group_by,
+            buckets,
+            total_count: totals.count,
+            total_amount: totals.total_amount,
+        })),
+        Err(e) => {
+            error!("Failed to compute order stats: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to compute order stats: {}", e),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_params() {
+        let params: StatsParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(params.group_by, StatsGroupBy::Status);
+        assert_eq!(params.limit, 100);
+    }
+}
@@ -0,0 +1,203 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use read_model::OrderStatusChange;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::handlers::list_by_status::VALID_STATUSES;
+use crate::state::AppState;
+
+/// Longest a client may hold a status request open
+const MAX_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Statuses an order never leaves
+const TERMINAL_STATUSES: [&str; 2] = ["CANCELLED", "DELIVERED"];
+
+#[derive(Debug, Deserialize)]
+pub struct StatusParams {
+    /// Status to wait for; without it the current status is returned right away
+    pub wait_for: Option<String>,
+    /// How long to wait, e.g. `30s`, `500ms` or `30` (seconds)
+    #[serde(default = "default_timeout")]
+    pub timeout: String,
+}
+
+fn default_timeout() -> String {
+    "30s".to_string()
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrderStatusResponse {
+    pub order_id: Uuid,
+    pub status: String,
+    pub version: i64,
+    /// Whether the order is in the awaited status; `false` after a timeout or when the
+    /// order settled in a different terminal status
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reached: Option<bool>,
+}
+
+/// Get an order's status, optionally holding the request until it reaches `wait_for`
+///
+/// Woken by the projection's status notifications rather than by polling the database.
+pub async fn get_order_status_handler(
+    State(state): State<AppState>,
+    Path(order_id): Path<Uuid>,
+    Query(params): Query<StatusParams>,
+) -> Result<Json<OrderStatusResponse>, (StatusCode, String)> {
+    let wait_for = match params.wait_for {
+        Some(status) => {
+            let status = status.to_uppercase();
+            if !VALID_STATUSES.contains(&status.as_str()) {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid status. Must be one of: {:?}", VALID_STATUSES),
+                ));
+            }
+            Some(status)
+        }
+        None => None,
+    };
+    let timeout = parse_timeout(&params.timeout).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Invalid timeout: {}. Use e.g. 30s or 500ms, up to {}s",
+                params.timeout,
+                MAX_TIMEOUT.as_secs()
+            ),
+        )
+    })?;
+
+    // Subscribe before reading so a change in between is not missed
+    let mut changes = state.status_changes.subscribe();
+    let mut current = current_status(&state, order_id).await?;
+
+    let Some(target) = wait_for else {
+        return Ok(Json(response(current, None)));
+    };
+
+    info!(
+        "Waiting up to {:?} for order {} to reach {}",
+        timeout, order_id, target
+    );
+    let deadline = Instant::now() + timeout;
+    while !is_settled(&current.status, &target) {
+        match tokio::time::timeout_at(deadline, changes.recv()).await {
+            Ok(Ok(change)) => {
+                if change.order_id == order_id && change.version >= current.version {
+                    current = change;
+                }
+            }
+            // Missed some changes; catch up from the read model
+            Ok(Err(RecvError::Lagged(_))) => current = current_status(&state, order_id).await?,
+            Ok(Err(RecvError::Closed)) | Err(_) => break,
+        }
+    }
+
+    if !is_settled(&current.status, &target) {
+        // Covers changes made while the listener was reconnecting
+        current = current_status(&state, order_id).await?;
+    }
+
+    Ok(Json(response(current, Some(&target))))
+}
+
+async fn current_status(
+    state: &AppState,
+    order_id: Uuid,
+) -> Result<OrderStatusChange, (StatusCode, String)> {
+    match state.repository.get_by_id(order_id).await {
+        Ok(Some(order)) => Ok(OrderStatusChange {
+            order_id,
+            status: order.status,
+            version: order.version,
+        }),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            format!("Order not found: {}", order_id),
+        )),
+        Err(e) => {
+            error!("Failed to fetch status of order {}: {}", order_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch order: {}", e),
+            ))
+        }
+    }
+}
+
+fn response(current: OrderStatusChange, target: Option<&str>) -> OrderStatusResponse {
+    OrderStatusResponse {
+        reached: target.map(|target| current.status == target),
+        order_id: current.order_id,
+        status: current.status,
+        version: current.version,
+    }
+}
+
+/// Nothing more to wait for: the order is in the target status or can no longer change
+fn is_settled(status: &str, target: &str) -> bool {
+    status == target || TERMINAL_STATUSES.contains(&status)
+}
+
+fn parse_timeout(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let timeout = match value.strip_suffix("ms") {
+        Some(millis) => Duration::from_millis(millis.parse().ok()?),
+        None => Duration::from_secs(value.strip_suffix('s').unwrap_or(value).parse().ok()?),
+    };
+    (timeout <= MAX_TIMEOUT).then_some(timeout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(
+            parse_timeout(&default_timeout()),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(parse_timeout("500ms"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_timeout("45"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_timeout("61s"), None);
+        assert_eq!(parse_timeout("soon"), None);
+        assert_eq!(parse_timeout("-1s"), None);
+    }
+
+    #[test]
+    fn test_is_settled() {
+        assert!(is_settled("SHIPPED", "SHIPPED"));
+        assert!(!is_settled("CONFIRMED", "SHIPPED"));
+        // Will never ship
+        assert!(is_settled("CANCELLED", "SHIPPED"));
+        assert!(is_settled("DELIVERED", "SHIPPED"));
+    }
+
+    #[test]
+    fn test_response_reports_whether_target_was_reached() {
+        let change = OrderStatusChange {
+            order_id: Uuid::new_v4(),
+            status: "CANCELLED".to_string(),
+            version: 4,
+        };
+        assert_eq!(
+            response(change.clone(), Some("SHIPPED")).reached,
+            Some(false)
+        );
+        assert_eq!(
+            response(change.clone(), Some("CANCELLED")).reached,
+            Some(true)
+        );
+        assert_eq!(response(change, None).reached, None);
+    }
+}
@@ -0,0 +1,132 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use read_model::{PaymentHistoryEntry, PaymentView};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::state::AppState;
+
+#[derive(Debug, Serialize)]
+pub struct OrderPaymentsResponse {
+    pub order_id: Uuid,
+    pub payments: Vec<PaymentView>,
+    pub history: Vec<PaymentHistoryEntry>,
+}
+
+/// Payments of an order with their authorization, capture and refund history
+pub async fn get_order_payments_handler(
+    State(state): State<AppState>,
+    Path(order_id): Path<Uuid>,
+) -> Result<Json<OrderPaymentsResponse>, (StatusCode, String)> {
+    info!("Fetching payments for order: {}", order_id);
+
+    let payments = state.payments.list_by_order(order_id).await;
+    let history = state.payments.history_by_order(order_id).await;
+
+    match (payments, history) {
+        (Ok(payments), _) if payments.is_empty() => {
+            info!("No payments found for order: {}", order_id);
+            Err((
+                StatusCode::NOT_FOUND,
+                format!("No payments found for order: {}", order_id),
+            ))
+        }
+        (Ok(payments), Ok(history)) => Ok(Json(OrderPaymentsResponse {
+            order_id,
+            payments,
+            history,
+        })),
+        (Err(e), _) | (_, Err(e)) => {
+            error!("Failed to fetch payments for order {}: {}", order_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch payments: {}", e),
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnsettledParams {
+    #[serde(default = "default_older_than_hours")]
+    pub older_than_hours: i64,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_older_than_hours() -> i64 {
+    24
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnsettledAuthorizationsResponse {
+    pub payments: Vec<PaymentView>,
+    pub count: usize,
+    pub older_than_hours: i64,
+}
+
+/// Reconciliation: authorizations older than N hours that were never captured or voided
+pub async fn list_unsettled_authorizations_handler(
+    State(state): State<AppState>,
+    Query(params): Query<UnsettledParams>,
+) -> Result<Json<UnsettledAuthorizationsResponse>, (StatusCode, String)> {
+    info!(
+        "Listing unsettled authorizations (older than: {}h, limit: {})",
+        params.older_than_hours, params.limit
+    );
+
+    if params.older_than_hours < 0 || params.older_than_hours > 24 * 365 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "older_than_hours must be between 0 and 8760".to_string(),
+        ));
+    }
+
+    if params.limit < 1 || params.limit > 500 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Limit must be between 1 and 500".to_string(),
+        ));
+    }
+
+    let cutoff = Utc::now() - Duration::hours(params.older_than_hours);
+    match state
+        .payments
+        .list_unsettled_authorizations(cutoff, params.limit)
+        .await
+    {
+        Ok(payments) => Ok(Json(UnsettledAuthorizationsResponse {
+            count: payments.len(),
+            payments,
+            older_than_hours: params.older_than_hours,
+        })),
+        Err(e) => {
+            error!("Failed to list unsettled authorizations: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to list unsettled authorizations: {}", e),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsettled_params_defaults() {
+        let params: UnsettledParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(params.older_than_hours, 24);
+        assert_eq!(params.limit, 100);
+    }
+}
@@ -0,0 +1,111 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use read_model::ProjectionError;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::state::AppState;
+
+const VALID_STATUSES: [&str; 3] = ["QUARANTINED", "REQUEUED", "RESOLVED"];
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectionErrorParams {
+    pub status: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectionErrorListResponse {
+    pub errors: Vec<ProjectionError>,
+    pub count: usize,
+}
+
+/// Admin: list events the projection service quarantined after repeated failures
+pub async fn list_projection_errors_handler(
+    State(state): State<AppState>,
+    Query(params): Query<ProjectionErrorParams>,
+) -> Result<Json<ProjectionErrorListResponse>, (StatusCode, String)> {
+    info!(
+        "Listing projection errors (status: {:?}, limit: {})",
+        params.status, params.limit
+    );
+
+    let status = params.status.as_deref().map(str::to_uppercase);
+    if let Some(status) = &status {
+        if !VALID_STATUSES.contains(&status.as_str()) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Invalid status. Must be one of: {:?}", VALID_STATUSES),
+            ));
+        }
+    }
+
+    if params.limit < 1 || params.limit > 500 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Limit must be between 1 and 500".to_string(),
+        ));
+    }
+
+    match state
+        .projection_errors
+        .list(status.as_deref(), params.limit)
+        .await
+    {
+        Ok(errors) => Ok(Json(ProjectionErrorListResponse {
+            count: errors.len(),
+            errors,
+        })),
+        Err(e) => {
+            error!("Failed to list projection errors: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to list projection errors: {}", e),
+            ))
+        }
+    }
+}
+
+/// Admin: queue a quarantined event to be retried by the projection service
+pub async fn requeue_projection_error_handler(
+    State(state): State<AppState>,
+    Path(error_id): Path<Uuid>,
+) -> Result<Json<ProjectionError>, (StatusCode, String)> {
+    warn!("Requeueing quarantined projection event: {}", error_id);
+
+    match state.projection_errors.requeue(error_id).await {
+        Ok(Some(requeued)) => Ok(Json(requeued)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            format!("No quarantined projection event: {}", error_id),
+        )),
+        Err(e) => {
+            error!("Failed to requeue projection error {}: {}", error_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to requeue projection error: {}", e),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_params() {
+        let params: ProjectionErrorParams = serde_json::from_str("{}").unwrap();
+        assert!(params.status.is_none());
+        assert_eq!(params.limit, 50);
+    }
+}
@@ -0,0 +1,169 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use read_model::{OrderSearchQuery, OrderSearchResults, OrderSearchSort};
+use serde::Deserialize;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::state::AppState;
+
+/// Deepest result the index pages to (its `max_result_window`)
+const MAX_RESULT_WINDOW: i64 = 10_000;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchParams {
+    /// Free text: order number, tracking number, customer name or email
+    pub q: Option<String>,
+    /// Comma-separated facet values; an order matches any of them
+    pub status: Option<String>,
+    pub currency: Option<String>,
+    pub carrier: Option<String>,
+    pub customer_id: Option<Uuid>,
+    pub sku: Option<String>,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub sort: OrderSearchSort,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    20
+}
+
+impl SearchParams {
+    fn validate(&self) -> Result<(), (StatusCode, String)> {
+        if self.limit < 1 || self.limit > 100 {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Limit must be between 1 and 100".to_string(),
+            ));
+        }
+        if self.offset < 0 {
+            return Err((StatusCode::BAD_REQUEST, "Offset must be >= 0".to_string()));
+        }
+        let end = self.offset.checked_add(self.limit);
+        if end.is_none_or(|end| end > MAX_RESULT_WINDOW) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Search results are limited to the first {}; narrow the query",
+                    MAX_RESULT_WINDOW
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    fn into_query(self) -> OrderSearchQuery {
+        OrderSearchQuery {
+            text: self.q,
+            statuses: list(self.status.as_deref(), true),
+            currencies: list(self.currency.as_deref(), true),
+            carriers: list(self.carrier.as_deref(), false),
+            customer_id: self.customer_id,
+            sku: self.sku,
+            min_amount: self.min_amount,
+            max_amount: self.max_amount,
+            created_from: self.from,
+            created_to: self.to,
+            sort: self.sort,
+            limit: self.limit,
+            offset: self.offset,
+        }
+    }
+}
+
+fn list(values: Option<&str>, uppercase: bool) -> Vec<String> {
+    values
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            if uppercase {
+                value.to_uppercase()
+            } else {
+                value.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Search orders by text and facets in the search index
+pub async fn search_orders_handler(
+    State(state): State<AppState>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<OrderSearchResults>, (StatusCode, String)> {
+    let Some(search) = state.search.clone() else {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Order search is not configured".to_string(),
+        ));
+    };
+
+    params.validate()?;
+
+    // The free text can hold names and emails, so only the facets are logged
+    let query = params.into_query();
+    info!(
+        "Searching orders (statuses: {:?}, currencies: {:?}, carriers: {:?}, limit: {}, offset: {})",
+        query.statuses, query.currencies, query.carriers, query.limit, query.offset
+    );
+
+    match search.search(&query).await {
+        Ok(results) => Ok(Json(results)),
+        Err(e) => {
+            error!("Failed to search orders: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to search orders: {}", e),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_facet_lists() {
+        let uri = "/orders/search?status=shipped,%20delivered&carrier=UPS&sort=amount_desc"
+            .parse()
+            .unwrap();
+        let Query(params) = Query::<SearchParams>::try_from_uri(&uri).unwrap();
+        let query = params.into_query();
+
+        assert_eq!(query.statuses, vec!["SHIPPED", "DELIVERED"]);
+        assert_eq!(query.carriers, vec!["UPS"]);
+        assert!(query.currencies.is_empty());
+        assert_eq!(query.sort, OrderSearchSort::AmountDesc);
+        assert_eq!(query.limit, 20);
+    }
+
+    #[test]
+    fn test_result_window() {
+        let params = |query: &str| {
+            let uri = format!("/orders/search?{}", query).parse().unwrap();
+            Query::<SearchParams>::try_from_uri(&uri).unwrap().0
+        };
+
+        assert!(params("offset=9900&limit=100").validate().is_ok());
+        let (status, _) = params("offset=9901&limit=100").validate().unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = params(&format!("offset={}&limit=100", i64::MAX))
+            .validate()
+            .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
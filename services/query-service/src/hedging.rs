@@ -0,0 +1,174 @@
+use common::metrics;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Caps hedged reads to a share of all reads
+///
+/// Every read deposits `ratio` tokens, up to `max_tokens`, and every hedge spends
+/// one. When the database is slow for everyone the budget runs dry and reads stop
+/// doubling the load on Redis.
+pub struct RetryBudget {
+    ratio: f64,
+    max_tokens: f64,
+    tokens: Mutex<f64>,
+}
+
+impl RetryBudget {
+    /// Allow hedging `ratio` of reads, with a burst of `max_tokens` on a cold start
+    pub fn new(ratio: f64, max_tokens: f64) -> Self {
+        let max_tokens = max_tokens.max(1.0);
+        Self {
+            ratio: ratio.clamp(0.0, 1.0),
+            max_tokens,
+            tokens: Mutex::new(max_tokens),
+        }
+    }
+
+    /// Count a read towards the budget
+    pub fn deposit(&self) {
+        let mut tokens = self.tokens.lock().unwrap_or_else(|e| e.into_inner());
+        *tokens = (*tokens + self.ratio).min(self.max_tokens);
+    }
+
+    /// Take one hedge out of the budget, if there is one left
+    pub fn try_withdraw(&self) -> bool {
+        let mut tokens = self.tokens.lock().unwrap_or_else(|e| e.into_inner());
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A value read through [`HedgePolicy::read`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgedRead<T> {
+    pub value: T,
+    /// Served from the fallback copy, which may lag the database
+    pub stale: bool,
+}
+
+/// Races a fallback read against a primary read that is slower than `delay`
+pub struct HedgePolicy {
+    delay: Duration,
+    budget: RetryBudget,
+}
+
+impl HedgePolicy {
+    pub fn new(delay: Duration, budget: RetryBudget) -> Self {
+        Self { delay, budget }
+    }
+
+    /// Read from `primary`, falling back to `fallback` if the primary is slow
+    ///
+    /// The fallback only starts once the primary has taken longer than the delay
+    /// and the budget allows it. It wins only if it finds a value before the
+    /// primary finishes; otherwise the primary result is returned, errors included.
+    pub async fn read<T, E, P, F>(
+        &self,
+        query_type: &str,
+        primary: P,
+        fallback: F,
+    ) -> Result<Option<HedgedRead<T>>, E>
+    where
+        P: Future<Output = Result<Option<T>, E>>,
+        F: Future<Output = Option<T>>,
+    {
+        self.budget.deposit();
+        let fresh = |value: Option<T>| value.map(|value| HedgedRead { value, stale: false });
+
+        tokio::pin!(primary);
+        tokio::select! {
+            result = &mut primary => return result.map(fresh),
+            _ = tokio::time::sleep(self.delay) => {}
+        }
+
+        if !self.budget.try_withdraw() {
+            metrics::record_query_hedge(query_type, "budget_exhausted");
+            return primary.await.map(fresh);
+        }
+
+        // A fallback miss disables its branch, leaving the primary to answer
+        tokio::pin!(fallback);
+        tokio::select! {
+            result = &mut primary => {
+                metrics::record_query_hedge(query_type, "primary");
+                result.map(fresh)
+            }
+            Some(value) = &mut fallback => {
+                metrics::record_query_hedge(query_type, "fallback");
+                tracing::warn!(
+                    query_type,
+                    delay_ms = self.delay.as_millis() as u64,
+                    "Primary read slow, serving fallback copy"
+                );
+                Ok(Some(HedgedRead { value, stale: true }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(ratio: f64, max_tokens: f64) -> HedgePolicy {
+        HedgePolicy::new(Duration::from_millis(20), RetryBudget::new(ratio, max_tokens))
+    }
+
+    async fn slow<T>(value: T) -> Result<Option<T>, String> {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        Ok(Some(value))
+    }
+
+    #[tokio::test]
+    async fn test_fast_primary_is_not_hedged() {
+        let result = policy(0.1, 1.0)
+            .read("test", async { Ok::<_, String>(Some(1)) }, async {
+                panic!("fallback should not run")
+            })
+            .await;
+        assert_eq!(result, Ok(Some(HedgedRead { value: 1, stale: false })));
+    }
+
+    #[tokio::test]
+    async fn test_slow_primary_serves_stale_fallback() {
+        let result = policy(0.1, 1.0)
+            .read("test", slow(1), async { Some(2) })
+            .await;
+        assert_eq!(result, Ok(Some(HedgedRead { value: 2, stale: true })));
+    }
+
+    #[tokio::test]
+    async fn test_empty_fallback_waits_for_primary() {
+        let result = policy(0.1, 1.0)
+            .read("test", slow(1), async { None })
+            .await;
+        assert_eq!(result, Ok(Some(HedgedRead { value: 1, stale: false })));
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_budget_skips_fallback() {
+        let policy = policy(0.0, 1.0);
+        let first = policy.read("test", slow(1), async { Some(2) }).await;
+        assert!(first.unwrap().unwrap().stale);
+
+        let second = policy.read("test", slow(1), async { Some(2) }).await;
+        assert_eq!(second, Ok(Some(HedgedRead { value: 1, stale: false })));
+    }
+
+    #[test]
+    fn test_budget_refills_with_reads() {
+        let budget = RetryBudget::new(0.5, 1.0);
+        assert!(budget.try_withdraw());
+        assert!(!budget.try_withdraw());
+
+        budget.deposit();
+        assert!(!budget.try_withdraw());
+        budget.deposit();
+        assert!(budget.try_withdraw());
+    }
+}
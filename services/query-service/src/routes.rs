@@ -1,11 +1,13 @@
 use axum::{
     http::StatusCode,
+    middleware,
     response::IntoResponse,
-    routing::get,
+    routing::{get, post},
     Router,
 };
-use common::metrics;
-use tower_http::trace::TraceLayer;
+use common::api_version::{self, ApiVersion};
+use common::http_security::CorsConfig;
+use common::{admin_auth, http_metrics, metrics};
 
 use crate::handlers;
 use crate::state::AppState;
@@ -21,19 +23,71 @@ async fn metrics_handler() -> impl IntoResponse {
     }
 }
 
+/// API versions the service serves, each under `/api/vN`
+pub const API_VERSIONS: &[ApiVersion] = &[ApiVersion::V1];
+
 pub fn create_router(state: AppState) -> Router {
     Router::new()
         // Health check
         .route("/health", get(handlers::health::health_check))
+        .route("/ready", get(handlers::health::readiness_check))
         .route("/metrics", get(metrics_handler))
+        .route("/metrics/business", get(handlers::business_metrics::business_metrics_handler))
 
-        // Order queries
-        .route("/api/v1/orders/:id", get(handlers::get_order::get_order_handler))
-        .route("/api/v1/orders/number/:order_number", get(handlers::get_by_number::get_order_by_number_handler))
-        .route("/api/v1/customers/:customer_id/orders", get(handlers::list_customer_orders::list_customer_orders_handler))
-        .route("/api/v1/orders/status/:status", get(handlers::list_by_status::list_orders_by_status_handler))
+        .merge(api_version::mount(API_VERSIONS, |version| {
+            api_routes(state.clone(), version)
+        }))
 
-        // Middleware
-        .layer(TraceLayer::new_for_http())
+        // Middleware; logging, security headers and CORS wrap version negotiation in main
+        .route_layer(middleware::from_fn(http_metrics::track_http_metrics))
         .with_state(state)
 }
+
+/// CORS for browser clients, exposing the headers handlers add
+pub fn cors() -> CorsConfig {
+    CorsConfig::from_env()
+        .with_exposed_header(handlers::get_order::STALE_HEADER)
+        .with_exposed_header(handlers::events::EVENT_POSITION_HEADER)
+}
+
+/// Routes of one API version, relative to its `/api/vN` prefix
+fn api_routes(state: AppState, _version: ApiVersion) -> Router<AppState> {
+    // Stored payloads, bulk exports and quarantine changes, behind ADMIN_API_TOKEN
+    let admin = Router::new()
+        // Global event log, for incremental sync from an X-Event-Position bookmark
+        .route("/events", get(handlers::events::list_events_handler))
+
+        // Every order in a range, with customer and shipping details
+        .route("/orders/export", get(handlers::export_orders::export_orders_handler))
+
+        // Projection quarantine
+        .route("/admin/projection-errors", get(handlers::projection_errors::list_projection_errors_handler))
+        .route("/admin/projection-errors/:error_id/requeue", post(handlers::projection_errors::requeue_projection_error_handler))
+        .route_layer(middleware::from_fn_with_state(
+            state.admin_tokens,
+            admin_auth::require_admin_token,
+        ));
+
+    Router::new()
+        .merge(admin)
+        // Order queries
+        .route("/orders/stats", get(handlers::order_stats::order_stats_handler))
+        .route("/orders/search", get(handlers::search_orders::search_orders_handler))
+        .route("/orders/:id", get(handlers::get_order::get_order_handler))
+        .route("/orders/:id/status", get(handlers::order_status::get_order_status_handler))
+        .route("/orders/:id/summary", get(handlers::get_order_summary::get_order_summary_handler))
+        .route("/orders/:id/timeline", get(handlers::get_timeline::get_order_timeline_handler))
+        .route("/orders/number/:order_number", get(handlers::get_by_number::get_order_by_number_handler))
+        .route("/customers/:customer_id/orders", get(handlers::list_customer_orders::list_customer_orders_handler))
+        .route("/orders/status/:status", get(handlers::list_by_status::list_orders_by_status_handler))
+
+        // Saga queries
+        .route("/sagas", get(handlers::list_sagas::list_sagas_handler))
+
+        // Payment queries
+        .route("/orders/:id/payments", get(handlers::payments::get_order_payments_handler))
+        .route("/payments/unsettled", get(handlers::payments::list_unsettled_authorizations_handler))
+
+        // Inventory queries
+        .route("/inventory/:sku", get(handlers::get_inventory::get_inventory_handler))
+}
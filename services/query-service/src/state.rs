@@ -1,17 +1,63 @@
 use anyhow::Result;
-use read_model::{OrderViewRepository, PostgresOrderViewRepository, RedisCache};
+use common::admin_auth::AdminTokens;
+use common::redis_connection::RedisConfig;
+use read_model::{
+    BusinessMetricsRepository, CachedOrderViewRepository, EventLogRepository, InstrumentedOrderViewRepository,
+    InventoryViewRepository, OrderSearchRepository, OrderStatusNotifier, OrderViewRepository,
+    PaymentViewRepository, PostgresBusinessMetricsRepository, PostgresEventLogRepository, PostgresInventoryViewRepository,
+    PostgresOrderViewRepository, PostgresPaymentViewRepository, PostgresProductViewRepository,
+    PostgresProjectionErrorRepository, PostgresSagaViewRepository, PostgresTimelineRepository,
+    ProductViewRepository, ProjectionErrorRepository, ReadReplica, RedisCache,
+    SagaViewRepository, TimelineRepository,
+};
 use sqlx::PgPool;
 use std::sync::Arc;
+use std::time::Duration;
+
+use crate::hedging::HedgePolicy;
+use crate::warmup::Readiness;
 
 /// Application state shared across handlers
 #[derive(Clone)]
 pub struct AppState {
+    /// Order views, read through the Redis cache
     pub repository: Arc<dyn OrderViewRepository>,
+    pub timeline: Arc<dyn TimelineRepository>,
+    /// The global event log, for event positions and incremental sync
+    pub events: Arc<dyn EventLogRepository>,
+    pub sagas: Arc<dyn SagaViewRepository>,
+    pub projection_errors: Arc<dyn ProjectionErrorRepository>,
+    pub inventory: Arc<dyn InventoryViewRepository>,
+    pub payments: Arc<dyn PaymentViewRepository>,
+    pub products: Arc<dyn ProductViewRepository>,
+    pub business_metrics: Arc<dyn BusinessMetricsRepository>,
+    /// Full-text and faceted order search; `None` without a search index
+    pub search: Option<Arc<dyn OrderSearchRepository>>,
     pub cache: Arc<RedisCache>,
+    /// Order status changes announced by the primary, for long-polling status requests
+    pub status_changes: OrderStatusNotifier,
+    /// Hedges slow order lookups with the Redis fallback copy; `None` when disabled
+    pub hedge: Option<Arc<HedgePolicy>>,
+    /// How long the fallback copy of an order is kept
+    pub fallback_ttl: usize,
+    /// How long computed business KPIs are served from Redis
+    pub business_metrics_ttl: usize,
+    /// Held back while the cache is being warmed after a start
+    pub readiness: Readiness,
+    /// Bearer tokens accepted on the admin routes; none disables them
+    pub admin_tokens: AdminTokens,
 }
 
 impl AppState {
-    pub async fn new(database_url: &str, redis_url: &str, cache_ttl: usize) -> Result<Self> {
+    /// `replica_url` is an optional read replica for order queries, used while it lags
+    /// the primary by no more than `replica_max_lag`
+    pub async fn new(
+        database_url: &str,
+        replica_url: Option<&str>,
+        replica_max_lag: Duration,
+        redis: &RedisConfig,
+        cache_ttl: usize,
+    ) -> Result<Self> {
         tracing::info!("Initializing application state...");
 
         // Connect to database
@@ -19,14 +65,82 @@ impl AppState {
         let pool = PgPool::connect(database_url).await?;
         tracing::info!("Database connected");
 
-        // Create repository
-        let repository = Arc::new(PostgresOrderViewRepository::new(pool)) as Arc<dyn OrderViewRepository>;
+        // Create repositories
+        let business_metrics = Arc::new(PostgresBusinessMetricsRepository::new(pool.clone())) as Arc<dyn BusinessMetricsRepository>;
+        let products = Arc::new(PostgresProductViewRepository::new(pool.clone())) as Arc<dyn ProductViewRepository>;
+        let payments = Arc::new(PostgresPaymentViewRepository::new(pool.clone())) as Arc<dyn PaymentViewRepository>;
+        let inventory = Arc::new(PostgresInventoryViewRepository::new(pool.clone())) as Arc<dyn InventoryViewRepository>;
+        let projection_errors = Arc::new(PostgresProjectionErrorRepository::new(pool.clone())) as Arc<dyn ProjectionErrorRepository>;
+        let sagas = Arc::new(PostgresSagaViewRepository::new(pool.clone())) as Arc<dyn SagaViewRepository>;
+        let timeline = Arc::new(PostgresTimelineRepository::new(pool.clone())) as Arc<dyn TimelineRepository>;
+        let events = Arc::new(PostgresEventLogRepository::new(pool.clone())) as Arc<dyn EventLogRepository>;
+
+        let status_changes = OrderStatusNotifier::spawn(pool.clone());
+
+        let mut orders = PostgresOrderViewRepository::new(pool);
+        if let Some(replica_url) = replica_url {
+            tracing::info!("Connecting to read replica (max lag: {}ms)...", replica_max_lag.as_millis());
+            let replica_pool = PgPool::connect(replica_url).await?;
+            orders = orders.with_read_replica(ReadReplica::new(replica_pool, replica_max_lag));
+            tracing::info!("Read replica connected");
+        }
+        let orders = Arc::new(orders) as Arc<dyn OrderViewRepository>;
 
         // Connect to Redis
         tracing::info!("Connecting to Redis...");
-        let cache = Arc::new(RedisCache::new(redis_url, cache_ttl).await?);
+        let cache = Arc::new(RedisCache::connect(redis, cache_ttl).await?);
         tracing::info!("Redis connected");
 
-        Ok(Self { repository, cache })
+        // Order lookups are served from Redis first; only queries that reach the
+        // database are timed
+        let repository = Arc::new(CachedOrderViewRepository::new(
+            Arc::new(InstrumentedOrderViewRepository::new(orders)),
+            cache.clone(),
+        )) as Arc<dyn OrderViewRepository>;
+
+        Ok(Self {
+            repository,
+            timeline,
+            events,
+            sagas,
+            projection_errors,
+            inventory,
+            payments,
+            products,
+            business_metrics,
+            search: None,
+            cache,
+            status_changes,
+            hedge: None,
+            fallback_ttl: 0,
+            business_metrics_ttl: 15,
+            readiness: Readiness::ready(),
+            admin_tokens: AdminTokens::default(),
+        })
+    }
+
+    /// Hedge slow order lookups, keeping fallback copies for `fallback_ttl` seconds
+    pub fn with_hedged_reads(mut self, policy: HedgePolicy, fallback_ttl: usize) -> Self {
+        self.hedge = Some(Arc::new(policy));
+        self.fallback_ttl = fallback_ttl;
+        self
+    }
+
+    /// Serve `/orders/search` from `search`
+    pub fn with_order_search(mut self, search: Arc<dyn OrderSearchRepository>) -> Self {
+        self.search = Some(search);
+        self
+    }
+
+    /// Accept `tokens` on the admin routes
+    pub fn with_admin_tokens(mut self, tokens: AdminTokens) -> Self {
+        self.admin_tokens = tokens;
+        self
+    }
+
+    /// Cache computed business KPIs for `ttl` seconds
+    pub fn with_business_metrics_ttl(mut self, ttl: usize) -> Self {
+        self.business_metrics_ttl = ttl.max(1);
+        self
     }
 }
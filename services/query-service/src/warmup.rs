@@ -0,0 +1,164 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::state::AppState;
+
+/// Whether the service is ready for traffic, shared with the readiness endpoint
+///
+/// Ready from the start unless a cache warm-up is running.
+#[derive(Clone)]
+pub struct Readiness {
+    inner: Arc<ReadinessInner>,
+}
+
+struct ReadinessInner {
+    ready: AtomicBool,
+    warmed: AtomicUsize,
+    target: AtomicUsize,
+}
+
+impl Readiness {
+    pub fn ready() -> Self {
+        Self {
+            inner: Arc::new(ReadinessInner {
+                ready: AtomicBool::new(true),
+                warmed: AtomicUsize::new(0),
+                target: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.inner.ready.load(Ordering::Acquire)
+    }
+
+    /// Orders cached so far and the number the warm-up aims for
+    pub fn progress(&self) -> (usize, usize) {
+        (
+            self.inner.warmed.load(Ordering::Relaxed),
+            self.inner.target.load(Ordering::Relaxed),
+        )
+    }
+
+    fn start_warmup(&self, target: usize) {
+        self.inner.target.store(target, Ordering::Relaxed);
+        self.inner.warmed.store(0, Ordering::Relaxed);
+        self.inner.ready.store(false, Ordering::Release);
+    }
+
+    fn record_warmed(&self, count: usize) {
+        self.inner.warmed.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn mark_ready(&self) {
+        self.inner.ready.store(true, Ordering::Release);
+    }
+}
+
+/// Pre-loads the most recently active orders into Redis after a deploy
+///
+/// Without it every order misses the cache once after a restart, which shows up as a
+/// latency spike on the database until the working set is cached again.
+pub struct CacheWarmup {
+    orders: usize,
+    batch_size: usize,
+}
+
+impl CacheWarmup {
+    pub fn new(orders: usize, batch_size: usize) -> Self {
+        Self {
+            orders,
+            batch_size: batch_size.max(1),
+        }
+    }
+
+    /// Sizes of the batches needed to load `orders` orders
+    fn batches(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.orders)
+            .step_by(self.batch_size)
+            .map(move |start| self.batch_size.min(self.orders - start))
+    }
+
+    /// Warm the cache in the background, holding `state.readiness` until it is done
+    ///
+    /// A failed warm-up is logged and the service reports ready anyway; a cold cache
+    /// is slower, not broken.
+    pub fn spawn(self, state: AppState) {
+        state.readiness.start_warmup(self.orders);
+        tokio::spawn(async move {
+            self.run(&state).await;
+            state.readiness.mark_ready();
+        });
+    }
+
+    async fn run(&self, state: &AppState) {
+        tracing::info!("Warming cache with up to {} recently active orders...", self.orders);
+        let started = Instant::now();
+        let mut offset = 0usize;
+
+        for batch_size in self.batches() {
+            let orders = match state
+                .repository
+                .list_recently_updated(batch_size as i64, offset as i64)
+                .await
+            {
+                Ok(orders) => orders,
+                Err(e) => {
+                    tracing::warn!("Cache warm-up stopped after {} orders: {}", offset, e);
+                    return;
+                }
+            };
+
+            for order in &orders {
+                state.cache.set(&order.order_id, order).await;
+                if state.hedge.is_some() {
+                    state
+                        .cache
+                        .set_fallback(&order.order_id, order, state.fallback_ttl)
+                        .await;
+                }
+            }
+            offset += orders.len();
+            state.readiness.record_warmed(orders.len());
+            tracing::info!("Cache warm-up: {}/{} orders", offset, self.orders);
+
+            if orders.len() < batch_size {
+                break;
+            }
+        }
+
+        tracing::info!(
+            "Cache warm-up finished: {} orders in {}ms",
+            offset,
+            started.elapsed().as_millis()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batches_cover_requested_orders() {
+        let batches: Vec<_> = CacheWarmup::new(1_200, 500).batches().collect();
+        assert_eq!(batches, vec![500, 500, 200]);
+
+        assert_eq!(CacheWarmup::new(0, 500).batches().count(), 0);
+    }
+
+    #[test]
+    fn test_readiness_waits_for_warmup() {
+        let readiness = Readiness::ready();
+        assert!(readiness.is_ready());
+
+        readiness.start_warmup(100);
+        readiness.record_warmed(40);
+        assert!(!readiness.is_ready());
+        assert_eq!(readiness.progress(), (40, 100));
+
+        readiness.mark_ready();
+        assert!(readiness.is_ready());
+    }
+}
@@ -1,23 +1,61 @@
 use rdkafka::config::ClientConfig;
 use rdkafka::consumer::{Consumer, StreamConsumer};
-use rdkafka::message::Message;
-use std::sync::Arc;
+use rdkafka::error::KafkaResult;
+use rdkafka::Offset;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
-use tracing::{error, info, warn};
+use tracing::{error, info, warn, Instrument};
 use uuid::Uuid;
 
+use common::distributed_lock::{DistributedLock, LocalLock};
+use common::request_log::message_span;
 use domain::events::order_events::{OrderCreatedEvent, OrderItem};
 use domain::events::EventEnvelope;
-use messaging::producer::EventPublisher;
+use messaging::reconnect::{self, ConsumerHealth, ReconnectBackoff};
+use messaging::{DeadLetterPublisher, PoisonPillDetector, PoisonVerdict, ReceivedMessage};
 use saga::coordinator::SagaCoordinator;
 use saga::repository::PostgresSagaRepository;
 
 use crate::sagas::{OrderProcessingSaga, OrderSagaData};
 
+const ORDER_EVENTS_TOPIC: &str = "order-events";
+const DEFAULT_DEAD_LETTER_TOPIC: &str = "saga-orchestrator-dlq";
+
+/// Longest wait for a message; an idle topic still reports the consumer healthy this often
+const RECV_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// An event decoded far enough to be handled
+struct DecodedEvent {
+    envelope: EventEnvelope,
+    /// Set for OrderCreated, the only event that starts a saga
+    order_created: Option<OrderCreatedEvent>,
+}
+
+fn decode_event(payload: &[u8]) -> Result<DecodedEvent, serde_json::Error> {
+    let envelope: EventEnvelope = serde_json::from_slice(payload)?;
+    let order_created = match envelope.event_type.as_str() {
+        "OrderCreated" => Some(serde_json::from_value(envelope.payload.clone())?),
+        _ => None,
+    };
+    Ok(DecodedEvent {
+        envelope,
+        order_created,
+    })
+}
+
 pub struct SagaEventConsumer {
-    consumer: StreamConsumer,
+    /// Swapped for a fresh client after a fatal Kafka error
+    consumer: RwLock<Arc<StreamConsumer>>,
+    brokers: String,
+    group_id: String,
+    health: ConsumerHealth,
     coordinator: Arc<SagaCoordinator<PostgresSagaRepository>>,
     order_saga: Arc<OrderProcessingSaga>,
+    recovery_interval: Duration,
+    stale_after: Duration,
+    recovery_lock: Arc<dyn DistributedLock>,
+    dead_letters: DeadLetterPublisher,
+    poison: Mutex<PoisonPillDetector>,
 }
 
 impl SagaEventConsumer {
@@ -25,49 +63,197 @@ impl SagaEventConsumer {
         brokers: &str,
         group_id: &str,
         coordinator: Arc<SagaCoordinator<PostgresSagaRepository>>,
-        event_publisher: Arc<EventPublisher>,
+        order_saga: OrderProcessingSaga,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let consumer: StreamConsumer = ClientConfig::new()
-            .set("group.id", group_id)
-            .set("bootstrap.servers", brokers)
-            .set("enable.auto.commit", "true")
-            .set("auto.offset.reset", "earliest")
-            .set("session.timeout.ms", "6000")
-            .create()?;
-
-        consumer.subscribe(&["order-events"])?;
+        let consumer = create_consumer(brokers, group_id)?;
 
-        let order_saga = Arc::new(OrderProcessingSaga::new(event_publisher));
+        let order_saga = Arc::new(order_saga);
 
         Ok(Self {
-            consumer,
+            consumer: RwLock::new(Arc::new(consumer)),
+            brokers: brokers.to_string(),
+            group_id: group_id.to_string(),
+            health: ConsumerHealth::new(),
             coordinator,
             order_saga,
+            recovery_interval: Duration::from_secs(30),
+            stale_after: Duration::from_secs(60),
+            recovery_lock: Arc::new(LocalLock::default()),
+            dead_letters: DeadLetterPublisher::new(brokers, DEFAULT_DEAD_LETTER_TOPIC.to_string())?,
+            poison: Mutex::new(PoisonPillDetector::new(group_id, 3)),
         })
     }
 
+    /// Dead letter topic for messages that fail to deserialize `max_attempts` times
+    pub fn with_dead_letters(mut self, dead_letters: DeadLetterPublisher, max_attempts: u32) -> Self {
+        self.dead_letters = dead_letters;
+        self.poison = Mutex::new(PoisonPillDetector::new(&self.group_id, max_attempts));
+        self
+    }
+
+    /// Configure how often stalled sagas are recovered and when a saga counts as stalled
+    pub fn with_recovery(mut self, interval: Duration, stale_after: Duration) -> Self {
+        self.recovery_interval = interval;
+        self.stale_after = stale_after;
+        self
+    }
+
+    /// Lock guarding partition recovery across replicas
+    pub fn with_recovery_lock(mut self, lock: Arc<dyn DistributedLock>) -> Self {
+        self.recovery_lock = lock;
+        self
+    }
+
+    /// Report consumer health through a shared handle (e.g. one mirrored to a readiness file)
+    pub fn with_health(mut self, health: ConsumerHealth) -> Self {
+        self.health = health;
+        self
+    }
+
+    fn current_consumer(&self) -> Arc<StreamConsumer> {
+        self.consumer.read().unwrap().clone()
+    }
+
+    /// Replace the Kafka client after a fatal error; librdkafka cannot recover it
+    fn reconnect(&self) {
+        warn!(group_id = %self.group_id, "Recreating Kafka consumer after fatal error");
+        match create_consumer(&self.brokers, &self.group_id) {
+            Ok(consumer) => *self.consumer.write().unwrap() = Arc::new(consumer),
+            Err(e) => error!(kafka_error = %e, "Failed to recreate Kafka consumer"),
+        }
+    }
+
     pub async fn start(self: Arc<Self>) {
         info!("Starting saga event consumer...");
 
+        let recovery = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(recovery.recovery_interval);
+            loop {
+                interval.tick().await;
+                recovery.recover_owned_sagas().await;
+            }
+        });
+
+        let mut backoff = ReconnectBackoff::default();
         loop {
-            match self.consumer.recv().await {
+            let consumer = self.current_consumer();
+            let Ok(received) = tokio::time::timeout(RECV_TIMEOUT, consumer.recv()).await else {
+                // Nothing to consume, but the client is polling without errors
+                self.health.set_healthy(true);
+                continue;
+            };
+            match received {
                 Ok(msg) => {
-                    if let Some(payload) = msg.payload() {
-                        if let Err(e) = self.process_message(payload).await {
-                            error!(error = %e, "Error processing message");
-                        }
+                    backoff.reset();
+                    self.health.set_healthy(true);
+
+                    if let Some(message) = ReceivedMessage::from_message(&msg) {
+                        self.handle_message(&consumer, message).await;
                     }
                 }
                 Err(e) => {
-                    error!(kafka_error = %e, "Kafka consumer error");
-                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    self.health.set_healthy(false);
+                    let delay = backoff.next_delay();
+                    error!(
+                        kafka_error = %e,
+                        attempt = backoff.failures(),
+                        retry_in_ms = delay.as_millis() as u64,
+                        "Kafka consumer error"
+                    );
+
+                    if reconnect::is_fatal(&e) {
+                        self.reconnect();
+                    }
+
+                    tokio::time::sleep(delay).await;
                 }
             }
         }
     }
 
-    async fn process_message(&self, payload: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
-        let envelope: EventEnvelope = serde_json::from_slice(payload)?;
+    /// Partitions of the order events topic currently assigned to this instance
+    fn owned_partitions(&self) -> Vec<i32> {
+        match self.current_consumer().assignment() {
+            Ok(assignment) => assignment
+                .elements_for_topic(ORDER_EVENTS_TOPIC)
+                .iter()
+                .map(|element| element.partition())
+                .collect(),
+            Err(e) => {
+                warn!(kafka_error = %e, "Failed to read partition assignment");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Resume stalled sagas whose triggering partition is assigned to this instance,
+    /// so replicas never pick up each other's sagas
+    ///
+    /// Each partition is also guarded by a distributed lock: during a rebalance the
+    /// previous and new owner may both briefly believe they hold a partition.
+    async fn recover_owned_sagas(&self) {
+        for partition in self.owned_partitions() {
+            let lock_name = format!("saga-recovery:{}:{}", ORDER_EVENTS_TOPIC, partition);
+            let task = Box::pin(async move {
+                match self
+                    .coordinator
+                    .recover_partitions(&*self.order_saga, &[partition], self.stale_after, 100)
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(recovered) => info!(recovered, partition, "Recovered stalled sagas"),
+                    Err(e) => error!(error = %e, partition, "Saga recovery failed"),
+                }
+            });
+
+            if let Err(e) = self.recovery_lock.run_exclusive(&lock_name, task).await {
+                warn!(error = %e, partition, "Failed to acquire saga recovery lock");
+            }
+        }
+    }
+
+    /// Decode and process a message; one that keeps failing to decode is rewound
+    /// until the poison pill detector gives up on it and it is dead-lettered
+    async fn handle_message(&self, consumer: &StreamConsumer, message: ReceivedMessage) {
+        let decoded = match decode_event(&message.payload) {
+            Ok(decoded) => {
+                self.poison.lock().unwrap().clear(&message);
+                decoded
+            }
+            Err(e) => {
+                let error = format!("Failed to deserialize event: {}", e);
+                let verdict = self.poison.lock().unwrap().record_failure(&message, &error);
+                match verdict {
+                    PoisonVerdict::Retry { .. } => redeliver(consumer, &message),
+                    PoisonVerdict::Poison { attempts } => {
+                        if let Err(e) = self.dead_letters.publish(&message, &error, attempts).await {
+                            error!(error = %e, "Failed to dead-letter poison message");
+                            redeliver(consumer, &message);
+                        }
+                    }
+                }
+                return;
+            }
+        };
+
+        let metadata = &decoded.envelope.metadata;
+        let span = message_span(Some(metadata.correlation_id), metadata.user_id);
+        async {
+            if let Err(e) = self.process_message(decoded, message.partition).await {
+                error!(error = %e, "Error processing message");
+            }
+        }
+        .instrument(span)
+        .await;
+    }
+
+    async fn process_message(
+        &self,
+        decoded: DecodedEvent,
+        partition: i32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let envelope = decoded.envelope;
 
         info!(
             event_type = %envelope.event_type,
@@ -75,13 +261,9 @@ impl SagaEventConsumer {
             "Received event"
         );
 
-        match envelope.event_type.as_str() {
-            "OrderCreated" => {
-                self.handle_order_created(&envelope).await?;
-            }
-            _ => {
-                // Ignore other events
-            }
+        // Other events are ignored
+        if let Some(event) = decoded.order_created {
+            self.handle_order_created(&envelope, event, partition).await?;
         }
 
         Ok(())
@@ -90,15 +272,14 @@ impl SagaEventConsumer {
     async fn handle_order_created(
         &self,
         envelope: &EventEnvelope,
+        event: OrderCreatedEvent,
+        partition: i32,
     ) -> Result<(), Box<dyn std::error::Error>> {
         info!(
             order_id = %envelope.aggregate_id,
             "Handling OrderCreated event - starting saga"
         );
 
-        // Deserialize the event
-        let event: OrderCreatedEvent = serde_json::from_value(envelope.payload.clone())?;
-
         // Create saga data
         let saga_data = OrderSagaData {
             order_id: event.order_id,
@@ -113,12 +294,23 @@ impl SagaEventConsumer {
         let saga_id = Uuid::new_v4();
         let saga_data_json = serde_json::to_value(&saga_data)?;
 
-        // Start the saga
-        let state = self
+        // Start the saga; a replayed OrderCreated attaches to the existing one
+        let (state, created) = self
             .coordinator
-            .start_saga(&*self.order_saga, saga_id, saga_data_json)
+            .start_or_attach(&*self.order_saga, saga_id, saga_data_json, Some(partition))
             .await?;
 
+        if !created {
+            // The owning instance (or the recovery sweeper) drives the existing saga
+            info!(
+                saga_id = %state.saga_id,
+                order_id = %event.order_id,
+                status = %state.status,
+                "Saga already exists for order, attached to it"
+            );
+            return Ok(());
+        }
+
         info!(
             saga_id = %saga_id,
             order_id = %event.order_id,
@@ -147,3 +339,69 @@ impl SagaEventConsumer {
         Ok(())
     }
 }
+
+/// Rewind the message's partition so the message is received again
+fn redeliver(consumer: &StreamConsumer, message: &ReceivedMessage) {
+    if let Err(e) = consumer.seek(
+        &message.topic,
+        message.partition,
+        Offset::Offset(message.offset),
+        Duration::from_secs(5),
+    ) {
+        error!(
+            kafka_error = %e,
+            topic = %message.topic,
+            partition = message.partition,
+            offset = message.offset,
+            "Failed to rewind partition"
+        );
+    }
+}
+
+fn create_consumer(brokers: &str, group_id: &str) -> KafkaResult<StreamConsumer> {
+    let consumer: StreamConsumer = ClientConfig::new()
+        .set("group.id", group_id)
+        .set("bootstrap.servers", brokers)
+        .set("enable.auto.commit", "true")
+        .set("auto.offset.reset", "earliest")
+        .set("session.timeout.ms", "6000")
+        .create()?;
+
+    consumer.subscribe(&[ORDER_EVENTS_TOPIC])?;
+    Ok(consumer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn envelope(event_type: &str, payload: serde_json::Value) -> Vec<u8> {
+        serde_json::to_vec(&json!({
+            "event_id": Uuid::new_v4(),
+            "aggregate_id": Uuid::new_v4(),
+            "aggregate_type": "Order",
+            "event_type": event_type,
+            "event_version": 1,
+            "payload": payload,
+            "metadata": {
+                "correlation_id": Uuid::new_v4(),
+                "causation_id": Uuid::new_v4(),
+                "user_id": null
+            },
+            "timestamp": "2024-03-14T09:26:53Z",
+            "sequence_number": 1
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_decode_event_checks_order_created_payload() {
+        let decoded = decode_event(&envelope("OrderConfirmed", json!({}))).unwrap();
+        assert!(decoded.order_created.is_none());
+
+        // A malformed OrderCreated payload is a decode failure, not a processing one
+        assert!(decode_event(&envelope("OrderCreated", json!({"order_id": 1}))).is_err());
+        assert!(decode_event(b"not json").is_err());
+    }
+}
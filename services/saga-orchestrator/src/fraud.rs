@@ -0,0 +1,218 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use common::circuit_breaker::CircuitBreaker;
+use http_body_util::{BodyExt, Full};
+use hyper::header::{HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE};
+use hyper::{Method, Request};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Header the fraud service deduplicates retried assessments by
+const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+
+#[derive(Debug, Error)]
+pub enum FraudError {
+    #[error("Fraud service unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Invalid fraud service response: {0}")]
+    InvalidResponse(String),
+}
+
+/// Order details sent for screening
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FraudCheckRequest {
+    pub order_id: Uuid,
+    pub customer_id: Uuid,
+    pub amount: f64,
+    pub currency: String,
+    pub payment_method: String,
+    pub item_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FraudDecision {
+    Approve,
+    /// Neither clearly fine nor clearly fraudulent; a person has to look at it
+    Review,
+    Decline,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FraudAssessment {
+    pub decision: FraudDecision,
+    /// Risk score from 0 (safe) to 1 (fraudulent), if the service reports one
+    #[serde(default)]
+    pub score: Option<f64>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// An external fraud screening service
+#[async_trait]
+pub trait FraudService: Send + Sync {
+    /// Assess `request`; retries of the same check pass the same `idempotency_key`
+    async fn assess(
+        &self,
+        request: &FraudCheckRequest,
+        idempotency_key: &str,
+    ) -> Result<FraudAssessment, FraudError>;
+}
+
+/// Fraud service behind the circuit breaker that guards calls to it
+#[derive(Clone)]
+pub struct FraudScreening {
+    pub service: Arc<dyn FraudService>,
+    pub breaker: Arc<CircuitBreaker>,
+}
+
+/// Fraud service reached over HTTP
+///
+/// Requests are `POST {base_url}/v1/assessments` with a [`FraudCheckRequest`] body and an
+/// `Idempotency-Key` header, and are answered with a [`FraudAssessment`]. Timeouts are left
+/// to the circuit breaker.
+pub struct HttpFraudService {
+    client: Client<HttpConnector, Full<Bytes>>,
+    base_url: String,
+}
+
+impl HttpFraudService {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            client: Client::builder(TokioExecutor::new()).build_http(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl FraudService for HttpFraudService {
+    async fn assess(
+        &self,
+        request: &FraudCheckRequest,
+        idempotency_key: &str,
+    ) -> Result<FraudAssessment, FraudError> {
+        let body = serde_json::to_vec(request)
+            .map_err(|e| FraudError::Unavailable(format!("Failed to encode request: {}", e)))?;
+        let idempotency_key = HeaderValue::from_str(idempotency_key)
+            .map_err(|e| FraudError::Unavailable(format!("Invalid idempotency key: {}", e)))?;
+        let http_request = Request::builder()
+            .method(Method::POST)
+            .uri(format!("{}/v1/assessments", self.base_url))
+            .header(ACCEPT, HeaderValue::from_static("application/json"))
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .header(IDEMPOTENCY_KEY, idempotency_key)
+            .body(Full::new(Bytes::from(body)))
+            .map_err(|e| FraudError::Unavailable(e.to_string()))?;
+
+        let response = self
+            .client
+            .request(http_request)
+            .await
+            .map_err(|e| FraudError::Unavailable(e.to_string()))?;
+        let status = response.status();
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .map_err(|e| FraudError::Unavailable(e.to_string()))?
+            .to_bytes();
+
+        if !status.is_success() {
+            return Err(FraudError::Unavailable(format!(
+                "{}: {}",
+                status,
+                String::from_utf8_lossy(&body)
+            )));
+        }
+        serde_json::from_slice(&body).map_err(|e| FraudError::InvalidResponse(e.to_string()))
+    }
+}
+
+/// Amount-based rules used when no external fraud service is configured
+pub struct ThresholdFraudService {
+    review_above: f64,
+    decline_above: f64,
+}
+
+impl ThresholdFraudService {
+    pub fn new(review_above: f64, decline_above: f64) -> Self {
+        Self {
+            review_above,
+            decline_above,
+        }
+    }
+}
+
+#[async_trait]
+impl FraudService for ThresholdFraudService {
+    async fn assess(
+        &self,
+        request: &FraudCheckRequest,
+        _idempotency_key: &str,
+    ) -> Result<FraudAssessment, FraudError> {
+        let (decision, reason) = if request.amount > self.decline_above {
+            (
+                FraudDecision::Decline,
+                Some(format!("Amount above {:.2}", self.decline_above)),
+            )
+        } else if request.amount > self.review_above {
+            (
+                FraudDecision::Review,
+                Some(format!("Amount above {:.2}", self.review_above)),
+            )
+        } else {
+            (FraudDecision::Approve, None)
+        };
+
+        Ok(FraudAssessment {
+            decision,
+            score: None,
+            reason,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(amount: f64) -> FraudCheckRequest {
+        FraudCheckRequest {
+            order_id: Uuid::new_v4(),
+            customer_id: Uuid::new_v4(),
+            amount,
+            currency: "USD".to_string(),
+            payment_method: "credit_card".to_string(),
+            item_count: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_threshold_decisions() {
+        let service = ThresholdFraudService::new(1_000.0, 10_000.0);
+
+        let decision = |amount| {
+            let service = &service;
+            async move { service.assess(&request(amount), "test").await.unwrap().decision }
+        };
+        assert_eq!(decision(999.99).await, FraudDecision::Approve);
+        assert_eq!(decision(1_000.01).await, FraudDecision::Review);
+        assert_eq!(decision(10_000.01).await, FraudDecision::Decline);
+    }
+
+    #[test]
+    fn test_assessment_deserialization() {
+        let assessment: FraudAssessment =
+            serde_json::from_str(r#"{"decision": "REVIEW", "score": 0.62}"#).unwrap();
+        assert_eq!(assessment.decision, FraudDecision::Review);
+        assert_eq!(assessment.score, Some(0.62));
+        assert_eq!(assessment.reason, None);
+    }
+}
@@ -0,0 +1,73 @@
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use common::circuit_breaker::CircuitBreakerSnapshot;
+use common::circuit_breaker_registry::CircuitBreakerRegistry;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+#[derive(Serialize)]
+pub struct HealthResponse {
+    pub status: String,
+    pub service: String,
+    pub version: String,
+    /// Breakers are reported but do not affect health: an open one already sheds calls
+    pub circuit_breakers: Vec<CircuitBreakerSnapshot>,
+}
+
+/// Health check endpoint, with the state of every circuit breaker
+pub async fn health_check(
+    State(circuit_breakers): State<Arc<CircuitBreakerRegistry>>,
+) -> (StatusCode, Json<HealthResponse>) {
+    (
+        StatusCode::OK,
+        Json(HealthResponse {
+            status: "healthy".to_string(),
+            service: "saga-orchestrator".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            circuit_breakers: circuit_breakers.snapshot().await,
+        }),
+    )
+}
+
+pub fn router(circuit_breakers: Arc<CircuitBreakerRegistry>) -> Router {
+    Router::new()
+        .route("/health", get(health_check))
+        .with_state(circuit_breakers)
+}
+
+/// Serve [`router`] on `addr` until the process exits
+pub fn spawn(circuit_breakers: Arc<CircuitBreakerRegistry>, addr: SocketAddr) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Failed to bind health endpoint on {}: {}", addr, e);
+                return;
+            }
+        };
+        info!("Health endpoint listening on {}", addr);
+        if let Err(e) = axum::serve(listener, router(circuit_breakers)).await {
+            error!("Health endpoint failed: {}", e);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::circuit_breaker::CircuitBreakerState;
+
+    #[tokio::test]
+    async fn test_health_lists_circuit_breakers() {
+        let registry = Arc::new(CircuitBreakerRegistry::default());
+        registry.get("fraud-service");
+
+        let (status, response) = health_check(State(registry)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response.service, "saga-orchestrator");
+        assert_eq!(response.circuit_breakers[0].name, "fraud-service");
+        assert_eq!(response.circuit_breakers[0].state, CircuitBreakerState::Closed);
+    }
+}
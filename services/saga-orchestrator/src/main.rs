@@ -1,24 +1,44 @@
 use std::sync::Arc;
 use tracing::info;
-use common::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
+use common::circuit_breaker::CircuitBreakerConfig;
+use common::circuit_breaker_registry::CircuitBreakerRegistry;
 use common::config::Config;
+use common::distributed_lock::PgAdvisoryLock;
+use common::preflight::{Preflight, Severity};
+use common::redis_connection::RedisConfig;
 use common::telemetry::{TelemetryConfig, init_telemetry, shutdown_telemetry};
+use event_store::{IdempotencyBackend, IdempotencyChecker, PgIdempotencyStore};
 use messaging::producer::EventPublisher;
+use messaging::{ConsumerHealth, DeadLetterPublisher, TopicManager, TopicSettings};
 use saga::coordinator::SagaCoordinator;
-use saga::repository::PostgresSagaRepository;
+use saga::repository::{PostgresSagaRepository, SagaRepository};
+use saga::{KeyProvider, PublisherEventSink, Saga, StaticKeyProvider};
 use sqlx::postgres::PgPoolOptions;
+use std::net::SocketAddr;
 use std::time::Duration;
 
 mod event_consumer;
+mod fraud;
+mod health;
 mod sagas;
 
 use event_consumer::SagaEventConsumer;
+use fraud::{FraudScreening, FraudService, HttpFraudService, ThresholdFraudService};
+use sagas::{OrderProcessingSaga, StepPublishers};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration
     dotenv::dotenv().ok();
 
+    // Print the saga flows as JSON for docs and dashboards, without connecting to anything
+    if std::env::args().skip(1).any(|arg| arg == "--describe") {
+        let fraud_timeout = circuit_breakers().config(FRAUD_SERVICE_BREAKER).timeout;
+        let definitions = [OrderProcessingSaga::describe(order_approval_amount(), fraud_timeout)];
+        println!("{}", serde_json::to_string_pretty(&definitions)?);
+        return Ok(());
+    }
+
     // Initialize telemetry with Jaeger support
     let enable_jaeger = std::env::var("ENABLE_JAEGER")
         .unwrap_or_else(|_| "false".to_string())
@@ -39,6 +59,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let config = Config::from_env()?;
 
+    // Report every missing dependency at once instead of failing on the first saga
+    if Preflight::enabled() {
+        preflight(&config).await.finish();
+    }
+
     // Create database connection pool
     info!("Connecting to PostgreSQL at {}", config.database_url);
     let pool = PgPoolOptions::new()
@@ -48,34 +73,239 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("Database connection established");
 
-    // Create saga repository
-    let saga_repository = Arc::new(PostgresSagaRepository::new(pool));
+    // Create saga repository, encrypting saga data at rest if keys are configured
+    let mut saga_repository = PostgresSagaRepository::new(pool.clone());
+    let encryption_keys = StaticKeyProvider::from_env()?;
+    let encrypt = encryption_keys.is_some();
+    if let Some(keys) = encryption_keys {
+        info!("Saga state encryption enabled (active key: {})", keys.active_key_id());
+        saga_repository = saga_repository.with_encryption(Arc::new(keys));
+    }
+    let saga_repository = Arc::new(saga_repository);
+
+    // Move sagas written before encryption or under a retired key to the active key;
+    // replicas racing here skip sagas another one already rewrote
+    if encrypt {
+        let repository = saga_repository.clone();
+        tokio::spawn(async move {
+            loop {
+                match repository.rotate_keys(100).await {
+                    Ok(0) => break,
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::warn!("Failed to re-encrypt saga states: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    // Create saga coordinator, deduplicating step execution through Redis if enabled
+    let enable_idempotency = std::env::var("ENABLE_IDEMPOTENCY")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .unwrap_or(false);
+
+    let mut coordinator = SagaCoordinator::new(saga_repository.clone());
+    if let Some(timeout) = step_timeout() {
+        info!("Saga step attempts time out after {:?} unless the step sets its own", timeout);
+        coordinator = coordinator.with_step_timeout(timeout);
+    }
+    if enable_idempotency {
+        let redis = RedisConfig::from_env();
+        let ttl_secs: u64 = std::env::var("SAGA_IDEMPOTENCY_TTL_SECS")
+            .unwrap_or_else(|_| "86400".to_string())
+            .parse()
+            .unwrap_or(86400);
 
-    // Create saga coordinator
-    let coordinator = Arc::new(SagaCoordinator::new(saga_repository));
+        match IdempotencyBackend::from_env() {
+            IdempotencyBackend::Redis => {
+                info!("Saga step idempotency enabled (Redis: {:?}, TTL: {}s)", redis.topology, ttl_secs);
+                match IdempotencyChecker::with_config(redis, ttl_secs) {
+                    Ok(checker) => coordinator = coordinator.with_idempotency_store(Arc::new(checker)),
+                    Err(e) => tracing::warn!(
+                        "Failed to initialize saga idempotency store: {}. Continuing without it.",
+                        e
+                    ),
+                }
+            }
+            IdempotencyBackend::Postgres => {
+                info!("Saga step idempotency enabled (Postgres, TTL: {}s)", ttl_secs);
+                let store = Arc::new(PgIdempotencyStore::new(pool.clone(), ttl_secs));
+                store.clone().spawn_cleanup(idempotency_cleanup_interval());
+                coordinator = coordinator.with_idempotency_store(store);
+            }
+        }
+    }
 
-    // Create Kafka event publisher
+    // Create Kafka event publishers; inventory and payment steps publish to their own topics
     info!("Connecting to Kafka at {}", config.kafka_brokers);
-    let event_publisher = Arc::new(EventPublisher::new(
+    let inventory_events_topic = std::env::var("INVENTORY_EVENTS_TOPIC")
+        .unwrap_or_else(|_| "inventory-events".to_string());
+    let payment_events_topic = std::env::var("PAYMENT_EVENTS_TOPIC")
+        .unwrap_or_else(|_| "payment-events".to_string());
+    let saga_events_topic = std::env::var("SAGA_EVENTS_TOPIC")
+        .unwrap_or_else(|_| "saga-events".to_string());
+    let dead_letter_topic = std::env::var("SAGA_DEAD_LETTER_TOPIC")
+        .unwrap_or_else(|_| "saga-orchestrator-dlq".to_string());
+
+    // Create the topics used here with explicit settings rather than leaving it to
+    // broker auto-create
+    let manage_topics = std::env::var("KAFKA_MANAGE_TOPICS")
+        .unwrap_or_else(|_| "true".to_string())
+        .parse()
+        .unwrap_or(true);
+    if manage_topics {
+        let settings = TopicSettings::from_env();
+        let specs: Vec<_> = [
+            "order-events",
+            inventory_events_topic.as_str(),
+            payment_events_topic.as_str(),
+            saga_events_topic.as_str(),
+            dead_letter_topic.as_str(),
+        ]
+        .into_iter()
+        .map(|topic| settings.spec(topic))
+        .collect();
+        let ensured = async { TopicManager::new(&config.kafka_brokers)?.ensure(&specs).await }.await;
+        if let Err(e) = ensured {
+            tracing::warn!("Failed to ensure Kafka topics, continuing with them as they are: {}", e);
+        }
+    }
+
+    let step_publishers = StepPublishers {
+        orders: Arc::new(EventPublisher::new(
+            &config.kafka_brokers,
+            "order-events".to_string(),
+        )?),
+        inventory: Arc::new(EventPublisher::new(
+            &config.kafka_brokers,
+            inventory_events_topic,
+        )?),
+        payments: Arc::new(EventPublisher::new(
+            &config.kafka_brokers,
+            payment_events_topic,
+        )?),
+    };
+
+    // Saga lifecycle events go to their own topic for monitoring projections
+    let saga_event_publisher = Arc::new(EventPublisher::new(
         &config.kafka_brokers,
-        "order-events".to_string(),
+        saga_events_topic.clone(),
     )?);
+    let coordinator = Arc::new(
+        coordinator.with_event_sink(Arc::new(PublisherEventSink::new(saga_event_publisher))),
+    );
 
-    info!("Kafka connection established");
+    info!("Kafka connection established (saga events topic: {})", saga_events_topic);
+
+    // Fraud screening calls are guarded by a circuit breaker so a failing fraud
+    // service is not hammered by every saga
+    let fraud_env = |name: &str, default: &str| {
+        std::env::var(name).unwrap_or_else(|_| default.to_string())
+    };
+    let circuit_breakers = Arc::new(circuit_breakers());
+    let port: u16 = std::env::var("PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(8083);
+    health::spawn(circuit_breakers.clone(), SocketAddr::from(([0, 0, 0, 0], port)));
+    let fraud_service: Arc<dyn FraudService> =
+        match std::env::var("FRAUD_SERVICE_URL").ok().filter(|url| !url.is_empty()) {
+            Some(url) => {
+                info!("Fraud checks use the fraud service at {}", url);
+                Arc::new(HttpFraudService::new(url))
+            }
+            None => {
+                let review_above: f64 = fraud_env("FRAUD_REVIEW_AMOUNT", "1000")
+                    .parse()
+                    .unwrap_or(1000.0);
+                let decline_above: f64 = fraud_env("FRAUD_DECLINE_AMOUNT", "10000")
+                    .parse()
+                    .unwrap_or(10000.0);
+                info!(
+                    "FRAUD_SERVICE_URL not set, reviewing orders above {} and declining above {}",
+                    review_above, decline_above
+                );
+                Arc::new(ThresholdFraudService::new(review_above, decline_above))
+            }
+        };
+    let fraud_breaker = circuit_breakers.get(FRAUD_SERVICE_BREAKER);
+    let fraud = FraudScreening {
+        service: fraud_service,
+        breaker: fraud_breaker,
+    };
+
+    // High-value orders wait for someone to approve them before payment is authorized
+    let mut order_saga = OrderProcessingSaga::new(step_publishers, fraud);
+    if let Some(amount) = order_approval_amount() {
+        info!("Orders above {} require approval before payment", amount);
+        order_saga = order_saga.with_approval_amount(amount);
+    }
+
+    // Publish the saga's flow for the admin API
+    if let Err(e) = saga_repository.save_definition(&order_saga.definition()).await {
+        tracing::warn!("Failed to publish the order saga definition: {}", e);
+    }
+
+    // Stalled sagas are recovered only by the instance owning their partition
+    let recovery_interval_secs: u64 = std::env::var("SAGA_RECOVERY_INTERVAL_SECS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse()
+        .unwrap_or(30);
+    let stale_after_secs: u64 = std::env::var("SAGA_STALE_AFTER_SECS")
+        .unwrap_or_else(|_| "60".to_string())
+        .parse()
+        .unwrap_or(60);
+
+    // Messages that keep failing to deserialize are moved to a dead letter topic
+    let poison_max_attempts: u32 = std::env::var("POISON_MAX_ATTEMPTS")
+        .unwrap_or_else(|_| "3".to_string())
+        .parse()
+        .unwrap_or(3);
+    let dead_letters = DeadLetterPublisher::new(&config.kafka_brokers, dead_letter_topic)?;
+
+    // Consumer health is mirrored to a file for exec readiness probes, if configured
+    let mut consumer_health = ConsumerHealth::new();
+    if let Ok(readiness_file) = std::env::var("READINESS_FILE") {
+        info!("Reporting consumer readiness via {}", readiness_file);
+        consumer_health = consumer_health.with_marker_file(readiness_file);
+    }
 
     // Create and start event consumer
-    let consumer = Arc::new(SagaEventConsumer::new(
-        &config.kafka_brokers,
-        "saga-orchestrator-group",
-        coordinator.clone(),
-        event_publisher,
-    )?);
+    let consumer = Arc::new(
+        SagaEventConsumer::new(
+            &config.kafka_brokers,
+            "saga-orchestrator-group",
+            coordinator.clone(),
+            order_saga,
+        )?
+        .with_recovery(
+            Duration::from_secs(recovery_interval_secs),
+            Duration::from_secs(stale_after_secs),
+        )
+        .with_recovery_lock(Arc::new(PgAdvisoryLock::new(pool)))
+        .with_dead_letters(dead_letters, poison_max_attempts)
+        .with_health(consumer_health),
+    );
 
     info!("Saga Orchestrator Service started successfully");
     info!("Listening for events on topic: order-events");
 
-    // Start consuming events
-    consumer.start().await;
+    // Start consuming events until SIGTERM/SIGINT. Saga steps still running then are
+    // abandoned and left pending for recovery; the consumer gets a grace period to
+    // record that before the process exits.
+    let consuming = consumer.start();
+    tokio::pin!(consuming);
+    tokio::select! {
+        _ = &mut consuming => {}
+        _ = shutdown_signal() => {
+            info!("Received shutdown signal, abandoning running saga steps");
+            coordinator.shutdown();
+            let _ = tokio::time::timeout(shutdown_grace(), &mut consuming).await;
+        }
+    }
 
     // Shutdown telemetry gracefully
     shutdown_telemetry();
@@ -84,3 +314,152 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Settings, database schema, Kafka topics and Redis checked before starting
+async fn preflight(config: &Config) -> Preflight {
+    let mut preflight = Preflight::new("saga-orchestrator");
+    preflight.config::<bool>(&["ENABLE_JAEGER", "ENABLE_IDEMPOTENCY", "KAFKA_MANAGE_TOPICS"]);
+    preflight.config::<u64>(&[
+        "SAGA_IDEMPOTENCY_TTL_SECS",
+        "IDEMPOTENCY_CLEANUP_INTERVAL_SECS",
+        "SAGA_RECOVERY_INTERVAL_SECS",
+        "SAGA_STALE_AFTER_SECS",
+        "SAGA_STEP_TIMEOUT_MS",
+        "SAGA_SHUTDOWN_GRACE_MS",
+        "CIRCUIT_BREAKER_FRAUD_SERVICE_TIMEOUT_MS",
+        "CIRCUIT_BREAKER_FRAUD_SERVICE_RESET_SECS",
+    ]);
+    preflight.config::<u16>(&["PORT"]);
+    preflight.config::<u32>(&[
+        "POISON_MAX_ATTEMPTS",
+        "CIRCUIT_BREAKER_FRAUD_SERVICE_FAILURE_THRESHOLD",
+        "CIRCUIT_BREAKER_FRAUD_SERVICE_SUCCESS_THRESHOLD",
+        "CIRCUIT_BREAKER_FRAUD_SERVICE_HALF_OPEN_CALLS",
+    ]);
+    preflight.config::<IdempotencyBackend>(&["IDEMPOTENCY_BACKEND"]);
+    preflight.config::<f64>(&["FRAUD_REVIEW_AMOUNT", "FRAUD_DECLINE_AMOUNT", "ORDER_APPROVAL_AMOUNT"]);
+
+    preflight
+        .database(
+            &config.database_url,
+            &[
+                "saga_instances",
+                "saga_instances.correlation_key",
+                "saga_instances.kafka_partition",
+                "saga_event_log",
+                "saga_definitions",
+                "idempotency_keys",
+            ],
+        )
+        .await;
+
+    let topic = |name: &str, default: &str| std::env::var(name).unwrap_or_else(|_| default.to_string());
+    let topics = [
+        "order-events".to_string(),
+        topic("INVENTORY_EVENTS_TOPIC", "inventory-events"),
+        topic("PAYMENT_EVENTS_TOPIC", "payment-events"),
+        topic("SAGA_EVENTS_TOPIC", "saga-events"),
+    ];
+    let manage_topics = std::env::var("KAFKA_MANAGE_TOPICS")
+        .unwrap_or_else(|_| "true".to_string())
+        .parse()
+        .unwrap_or(true);
+    messaging::preflight_topics(
+        &mut preflight,
+        &config.kafka_brokers,
+        &topics.iter().map(String::as_str).collect::<Vec<_>>(),
+        Severity::Fatal,
+        manage_topics,
+    )
+    .await;
+
+    let enable_idempotency = std::env::var("ENABLE_IDEMPOTENCY")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .unwrap_or(false);
+    if enable_idempotency && IdempotencyBackend::from_env() == IdempotencyBackend::Redis {
+        let redis = RedisConfig::from_env();
+        // Steps still run without deduplication
+        preflight
+            .check("redis", Severity::Warning, async {
+                IdempotencyChecker::with_config(redis, 0)?.ping().await?;
+                Ok::<_, anyhow::Error>("reachable".to_string())
+            })
+            .await;
+    }
+
+    preflight
+}
+
+/// How often expired keys are purged from the Postgres idempotency store
+fn idempotency_cleanup_interval() -> Duration {
+    let secs: u64 = std::env::var("IDEMPOTENCY_CLEANUP_INTERVAL_SECS")
+        .unwrap_or_else(|_| "300".to_string())
+        .parse()
+        .unwrap_or(300);
+    Duration::from_secs(secs.max(1))
+}
+
+/// Longest a saga step attempt may take when the step sets no timeout; unbounded if unset
+fn step_timeout() -> Option<Duration> {
+    std::env::var("SAGA_STEP_TIMEOUT_MS")
+        .ok()
+        .and_then(|ms| ms.parse().ok())
+        .map(Duration::from_millis)
+}
+
+/// How long in-flight sagas get to record their abandoned steps on shutdown
+fn shutdown_grace() -> Duration {
+    Duration::from_millis(
+        std::env::var("SAGA_SHUTDOWN_GRACE_MS")
+            .unwrap_or_else(|_| "5000".to_string())
+            .parse()
+            .unwrap_or(5000),
+    )
+}
+
+async fn shutdown_signal() {
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+}
+
+/// Order total above which payment authorization waits for approval
+fn order_approval_amount() -> Option<f64> {
+    std::env::var("ORDER_APPROVAL_AMOUNT")
+        .ok()
+        .and_then(|amount| amount.parse::<f64>().ok())
+}
+
+/// Breaker guarding calls to the fraud service
+const FRAUD_SERVICE_BREAKER: &str = "fraud-service";
+
+/// Breakers of the orchestrator's dependencies, overridable through the
+/// `CIRCUIT_BREAKER_<NAME>_*` variables
+///
+/// A fraud service call may take 2s by default (`CIRCUIT_BREAKER_FRAUD_SERVICE_TIMEOUT_MS`).
+fn circuit_breakers() -> CircuitBreakerRegistry {
+    CircuitBreakerRegistry::default()
+        .with_config(
+            FRAUD_SERVICE_BREAKER,
+            CircuitBreakerConfig {
+                failure_threshold: 5,
+                timeout: Duration::from_secs(2),
+                half_open_timeout: Duration::from_secs(30),
+                ..Default::default()
+            },
+        )
+        .with_env_overrides(&[FRAUD_SERVICE_BREAKER])
+}
@@ -1,3 +1,3 @@
 pub mod order_saga;
 
-pub use order_saga::{OrderProcessingSaga, OrderSagaData};
+pub use order_saga::{OrderProcessingSaga, OrderSagaData, StepPublishers};
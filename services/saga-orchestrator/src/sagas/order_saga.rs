@@ -3,7 +3,7 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use domain::events::inventory_events::{
@@ -11,11 +11,15 @@ use domain::events::inventory_events::{
 };
 use domain::events::order_events::{OrderConfirmedEvent, OrderItem};
 use domain::events::payment_events::{PaymentAuthorizedEvent, PaymentVoidedEvent};
+use common::circuit_breaker::CircuitBreakerError;
 use domain::events::{DomainEvent, EventEnvelope, EventMetadata};
-use messaging::producer::EventPublisher;
+use messaging::Publisher;
 use saga::errors::{Result, SagaError};
-use saga::step::{SagaStep, StepContext, StepExecutor};
-use saga::{Saga, SagaState};
+use saga::step::{EmittedEvent, StepContext, StepExecutor, EMITTED_EVENT_KEY};
+use saga::{BackoffPolicy, Saga, SagaDefinition, SagaState, StepDefinition};
+use std::time::Duration;
+
+use crate::fraud::{FraudCheckRequest, FraudDecision, FraudScreening};
 
 /// Data passed to the order processing saga
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,36 +33,103 @@ pub struct OrderSagaData {
     pub correlation_id: Uuid,
 }
 
+/// Publishers the saga steps write their events to, one per topic
+#[derive(Clone)]
+pub struct StepPublishers {
+    pub orders: Arc<dyn Publisher>,
+    pub inventory: Arc<dyn Publisher>,
+    pub payments: Arc<dyn Publisher>,
+}
+
 /// Order Processing Saga
 ///
 /// Steps:
 /// 1. Reserve Inventory → Compensate: Release Inventory
-/// 2. Authorize Payment → Compensate: Void Authorization
-/// 3. Confirm Order → Compensate: Cancel Order
+/// 2. Fraud Check → Declined orders compensate, orders under review pause the saga
+/// 3. Authorize Payment → Compensate: Void Authorization
+/// 4. Confirm Order → Compensate: Cancel Order
+///
+/// Orders above the approval amount pause before payment authorization until
+/// someone approves them.
 pub struct OrderProcessingSaga {
     executors: HashMap<String, Box<dyn StepExecutor>>,
+    approval_amount: Option<f64>,
+    fraud_timeout: Duration,
 }
 
 impl OrderProcessingSaga {
-    pub fn new(event_publisher: Arc<EventPublisher>) -> Self {
+    pub fn new(publishers: StepPublishers, fraud: FraudScreening) -> Self {
+        let fraud_timeout = fraud.breaker.config().timeout;
         let mut executors: HashMap<String, Box<dyn StepExecutor>> = HashMap::new();
 
         executors.insert(
             "reserve_inventory".to_string(),
-            Box::new(ReserveInventoryStep::new(event_publisher.clone())),
+            Box::new(ReserveInventoryStep::new(publishers.inventory)),
+        );
+
+        executors.insert(
+            "fraud_check".to_string(),
+            Box::new(FraudCheckStep::new(fraud)),
         );
 
         executors.insert(
             "authorize_payment".to_string(),
-            Box::new(AuthorizePaymentStep::new(event_publisher.clone())),
+            Box::new(AuthorizePaymentStep::new(publishers.payments)),
         );
 
         executors.insert(
             "confirm_order".to_string(),
-            Box::new(ConfirmOrderStep::new(event_publisher.clone())),
+            Box::new(ConfirmOrderStep::new(publishers.orders)),
         );
 
-        Self { executors }
+        Self {
+            executors,
+            approval_amount: None,
+            fraud_timeout,
+        }
+    }
+
+    /// The saga's flow, given its approval amount and the fraud service timeout
+    ///
+    /// Available without building the saga, which needs Kafka publishers.
+    pub fn describe(approval_amount: Option<f64>, fraud_timeout: Duration) -> SagaDefinition {
+        let mut authorize_payment = StepDefinition::new("authorize_payment", 3)
+            .compensated_by("void the payment authorization");
+        if let Some(amount) = approval_amount {
+            authorize_payment = authorize_payment.with_approval(format!("total_amount > {}", amount));
+        }
+
+        SagaDefinition::new("OrderProcessingSaga")
+            .step(
+                StepDefinition::new("reserve_inventory", 3)
+                    .compensated_by("release the reserved inventory"),
+            )
+            // Spread retries out so an open breaker has a chance to half-open
+            .step(
+                StepDefinition::new("fraud_check", 3)
+                    .with_backoff(BackoffPolicy::Exponential {
+                        initial_delay_ms: 1_000,
+                        max_delay_ms: 30_000,
+                    })
+                    .with_timeout(fraud_timeout)
+                    .with_approval("the fraud service asks for a review"),
+            )
+            .step(authorize_payment)
+            .step(StepDefinition::new("confirm_order", 3).compensated_by("cancel the order"))
+    }
+
+    /// Require approval before authorizing payment for orders above `amount`
+    pub fn with_approval_amount(mut self, amount: f64) -> Self {
+        self.approval_amount = Some(amount);
+        self
+    }
+
+    fn requires_approval(&self, data: &serde_json::Value) -> bool {
+        let total_amount = data.get("total_amount").and_then(|v| v.as_f64());
+        matches!(
+            (self.approval_amount, total_amount),
+            (Some(limit), Some(amount)) if amount > limit
+        )
     }
 }
 
@@ -68,16 +139,21 @@ impl Saga for OrderProcessingSaga {
         "OrderProcessingSaga"
     }
 
+    fn definition(&self) -> SagaDefinition {
+        Self::describe(self.approval_amount, self.fraud_timeout)
+    }
+
     fn step_executors(&self) -> &HashMap<String, Box<dyn StepExecutor>> {
         &self.executors
     }
 
     async fn create_state(&self, saga_id: Uuid, data: serde_json::Value) -> Result<SagaState> {
-        let steps = vec![
-            SagaStep::new("reserve_inventory".to_string(), 3),
-            SagaStep::new("authorize_payment".to_string(), 3),
-            SagaStep::new("confirm_order".to_string(), 3),
-        ];
+        let mut steps = self.definition().create_steps();
+        if self.requires_approval(&data) {
+            if let Some(step) = steps.iter_mut().find(|step| step.name == "authorize_payment") {
+                step.requires_approval = true;
+            }
+        }
 
         Ok(SagaState::new(
             saga_id,
@@ -86,6 +162,11 @@ impl Saga for OrderProcessingSaga {
             data,
         ))
     }
+
+    /// One order processing saga per order
+    fn correlation_key(&self, data: &serde_json::Value) -> Option<String> {
+        data.get("order_id").and_then(|v| v.as_str()).map(str::to_string)
+    }
 }
 
 // ============================================================================
@@ -93,11 +174,11 @@ impl Saga for OrderProcessingSaga {
 // ============================================================================
 
 struct ReserveInventoryStep {
-    event_publisher: Arc<EventPublisher>,
+    event_publisher: Arc<dyn Publisher>,
 }
 
 impl ReserveInventoryStep {
-    fn new(event_publisher: Arc<EventPublisher>) -> Self {
+    fn new(event_publisher: Arc<dyn Publisher>) -> Self {
         Self { event_publisher }
     }
 }
@@ -131,7 +212,8 @@ impl StepExecutor for ReserveInventoryStep {
         };
 
         // Create event envelope
-        let metadata = EventMetadata::with_correlation(saga_data.correlation_id);
+        let metadata = EventMetadata::with_correlation(saga_data.correlation_id)
+            .with_idempotency_key(context.idempotency_key.clone());
         let envelope = event
             .to_envelope(saga_data.order_id, "Order", metadata)
             .map_err(|e| SagaError::InternalError(format!("Failed to create envelope: {}", e)))?;
@@ -141,7 +223,7 @@ impl StepExecutor for ReserveInventoryStep {
             .publish(saga_data.order_id, &envelope)
             .await
             .map_err(|e| {
-                SagaError::StepExecutionFailed(format!("Failed to publish event: {}", e))
+                SagaError::Transient(format!("Failed to publish event: {}", e))
             })?;
 
         info!(
@@ -152,7 +234,8 @@ impl StepExecutor for ReserveInventoryStep {
 
         Ok(serde_json::json!({
             "reservation_id": reservation_id,
-            "items_reserved": event.items.len()
+            "items_reserved": event.items.len(),
+            EMITTED_EVENT_KEY: EmittedEvent::from(&envelope),
         }))
     }
 
@@ -193,7 +276,8 @@ impl StepExecutor for ReserveInventoryStep {
             reason: "Saga compensation - order processing failed".to_string(),
         };
 
-        let metadata = EventMetadata::with_correlation(saga_data.correlation_id);
+        let metadata = EventMetadata::with_correlation(saga_data.correlation_id)
+            .with_idempotency_key(context.idempotency_key.clone());
         let envelope = event
             .to_envelope(saga_data.order_id, "Order", metadata)
             .map_err(|e| SagaError::CompensationFailed(format!("Failed to create envelope: {}", e)))?;
@@ -212,15 +296,124 @@ impl StepExecutor for ReserveInventoryStep {
 }
 
 // ============================================================================
-// Step 2: Authorize Payment
+// Step 2: Fraud Check
+// ============================================================================
+
+struct FraudCheckStep {
+    fraud: FraudScreening,
+}
+
+impl FraudCheckStep {
+    fn new(fraud: FraudScreening) -> Self {
+        Self { fraud }
+    }
+}
+
+#[async_trait]
+impl StepExecutor for FraudCheckStep {
+    async fn execute(&self, context: &StepContext) -> Result<serde_json::Value> {
+        info!(saga_id = %context.saga_id, "Executing: Fraud Check");
+
+        let saga_data: OrderSagaData = serde_json::from_value(context.data.clone())
+            .map_err(|e| SagaError::InternalError(format!("Failed to parse saga data: {}", e)))?;
+
+        // A reviewer already looked at the order after the screening asked for it
+        if let Some(approval) = context.approval.as_ref().filter(|a| a.approved) {
+            info!(
+                saga_id = %context.saga_id,
+                order_id = %saga_data.order_id,
+                approver = %approval.approver,
+                "Fraud review approved"
+            );
+            return Ok(serde_json::json!({
+                "decision": FraudDecision::Approve,
+                "approved_by": approval.approver,
+            }));
+        }
+
+        let request = FraudCheckRequest {
+            order_id: saga_data.order_id,
+            customer_id: saga_data.customer_id,
+            amount: saga_data.total_amount,
+            currency: saga_data.currency.clone(),
+            payment_method: saga_data.payment_method.clone(),
+            item_count: saga_data.items.len(),
+        };
+
+        // An open breaker fails fast without calling the service; the step's
+        // backoff gives it time to recover before the retry budget runs out
+        let assessment = self
+            .fraud
+            .breaker
+            .call(self.fraud.service.assess(&request, &context.idempotency_key))
+            .await
+            .map_err(|e| match e {
+                CircuitBreakerError::Open => {
+                    SagaError::Transient("Fraud service circuit breaker is open".to_string())
+                }
+                CircuitBreakerError::Timeout => {
+                    SagaError::Transient("Fraud service timed out".to_string())
+                }
+                CircuitBreakerError::CallFailed(e) => SagaError::Transient(e.to_string()),
+            })?;
+
+        let reason = assessment.reason.clone().unwrap_or_default();
+        match assessment.decision {
+            FraudDecision::Approve => {
+                info!(
+                    saga_id = %context.saga_id,
+                    order_id = %saga_data.order_id,
+                    score = ?assessment.score,
+                    "Fraud check passed"
+                );
+                Ok(serde_json::json!({
+                    "decision": assessment.decision,
+                    "score": assessment.score,
+                }))
+            }
+            FraudDecision::Review => {
+                warn!(
+                    saga_id = %context.saga_id,
+                    order_id = %saga_data.order_id,
+                    score = ?assessment.score,
+                    reason = %reason,
+                    "Order held for fraud review"
+                );
+                Err(SagaError::PauseRequested(format!("Fraud review: {}", reason)))
+            }
+            FraudDecision::Decline => {
+                warn!(
+                    saga_id = %context.saga_id,
+                    order_id = %saga_data.order_id,
+                    score = ?assessment.score,
+                    reason = %reason,
+                    "Order declined by fraud check"
+                );
+                Err(SagaError::StepExecutionFailed(format!(
+                    "Declined by fraud check: {}",
+                    reason
+                )))
+            }
+        }
+    }
+
+    async fn compensate(&self, context: &StepContext) -> Result<()> {
+        // Screening has no side effects to undo
+        info!(saga_id = %context.saga_id, "Compensating: Fraud Check (nothing to undo)");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Step 3: Authorize Payment
 // ============================================================================
 
 struct AuthorizePaymentStep {
-    event_publisher: Arc<EventPublisher>,
+    event_publisher: Arc<dyn Publisher>,
 }
 
 impl AuthorizePaymentStep {
-    fn new(event_publisher: Arc<EventPublisher>) -> Self {
+    fn new(event_publisher: Arc<dyn Publisher>) -> Self {
         Self { event_publisher }
     }
 }
@@ -247,7 +440,8 @@ impl StepExecutor for AuthorizePaymentStep {
             authorized_at: Utc::now(),
         };
 
-        let metadata = EventMetadata::with_correlation(saga_data.correlation_id);
+        let metadata = EventMetadata::with_correlation(saga_data.correlation_id)
+            .with_idempotency_key(context.idempotency_key.clone());
         let envelope = event
             .to_envelope(saga_data.order_id, "Order", metadata)
             .map_err(|e| SagaError::InternalError(format!("Failed to create envelope: {}", e)))?;
@@ -256,7 +450,7 @@ impl StepExecutor for AuthorizePaymentStep {
             .publish(saga_data.order_id, &envelope)
             .await
             .map_err(|e| {
-                SagaError::StepExecutionFailed(format!("Failed to publish event: {}", e))
+                SagaError::Transient(format!("Failed to publish event: {}", e))
             })?;
 
         info!(
@@ -269,7 +463,8 @@ impl StepExecutor for AuthorizePaymentStep {
         Ok(serde_json::json!({
             "payment_id": payment_id,
             "authorization_code": authorization_code,
-            "amount": saga_data.total_amount
+            "amount": saga_data.total_amount,
+            EMITTED_EVENT_KEY: EmittedEvent::from(&envelope),
         }))
     }
 
@@ -301,7 +496,8 @@ impl StepExecutor for AuthorizePaymentStep {
             voided_at: Utc::now(),
         };
 
-        let metadata = EventMetadata::with_correlation(saga_data.correlation_id);
+        let metadata = EventMetadata::with_correlation(saga_data.correlation_id)
+            .with_idempotency_key(context.idempotency_key.clone());
         let envelope = event
             .to_envelope(saga_data.order_id, "Order", metadata)
             .map_err(|e| SagaError::CompensationFailed(format!("Failed to create envelope: {}", e)))?;
@@ -320,15 +516,15 @@ impl StepExecutor for AuthorizePaymentStep {
 }
 
 // ============================================================================
-// Step 3: Confirm Order
+// Step 4: Confirm Order
 // ============================================================================
 
 struct ConfirmOrderStep {
-    event_publisher: Arc<EventPublisher>,
+    event_publisher: Arc<dyn Publisher>,
 }
 
 impl ConfirmOrderStep {
-    fn new(event_publisher: Arc<EventPublisher>) -> Self {
+    fn new(event_publisher: Arc<dyn Publisher>) -> Self {
         Self { event_publisher }
     }
 }
@@ -347,7 +543,8 @@ impl StepExecutor for ConfirmOrderStep {
             confirmed_at: Utc::now(),
         };
 
-        let metadata = EventMetadata::with_correlation(saga_data.correlation_id);
+        let metadata = EventMetadata::with_correlation(saga_data.correlation_id)
+            .with_idempotency_key(context.idempotency_key.clone());
         let envelope = event
             .to_envelope(saga_data.order_id, "Order", metadata)
             .map_err(|e| SagaError::InternalError(format!("Failed to create envelope: {}", e)))?;
@@ -356,7 +553,7 @@ impl StepExecutor for ConfirmOrderStep {
             .publish(saga_data.order_id, &envelope)
             .await
             .map_err(|e| {
-                SagaError::StepExecutionFailed(format!("Failed to publish event: {}", e))
+                SagaError::Transient(format!("Failed to publish event: {}", e))
             })?;
 
         info!(
@@ -367,7 +564,8 @@ impl StepExecutor for ConfirmOrderStep {
 
         Ok(serde_json::json!({
             "order_id": saga_data.order_id,
-            "confirmed": true
+            "confirmed": true,
+            EMITTED_EVENT_KEY: EmittedEvent::from(&envelope),
         }))
     }
 
@@ -388,6 +586,195 @@ impl StepExecutor for ConfirmOrderStep {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fraud::{FraudAssessment, FraudError, FraudService};
+    use common::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerState};
+    use messaging::InMemoryPublisher;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+
+    /// Answers every assessment with the same decision, or fails
+    struct StubFraudService {
+        decision: Option<FraudDecision>,
+        calls: AtomicU32,
+        last_key: Mutex<Option<String>>,
+    }
+
+    #[async_trait]
+    impl FraudService for StubFraudService {
+        async fn assess(
+            &self,
+            _request: &FraudCheckRequest,
+            idempotency_key: &str,
+        ) -> std::result::Result<FraudAssessment, FraudError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            *self.last_key.lock().unwrap() = Some(idempotency_key.to_string());
+            match self.decision {
+                Some(decision) => Ok(FraudAssessment {
+                    decision,
+                    score: Some(0.5),
+                    reason: Some("stub".to_string()),
+                }),
+                None => Err(FraudError::Unavailable("connection refused".to_string())),
+            }
+        }
+    }
+
+    fn fraud_step(decision: Option<FraudDecision>) -> (FraudCheckStep, Arc<StubFraudService>) {
+        let service = Arc::new(StubFraudService {
+            decision,
+            calls: AtomicU32::new(0),
+            last_key: Mutex::new(None),
+        });
+        let breaker = Arc::new(CircuitBreaker::new(
+            format!("fraud-service-test-{}", Uuid::new_v4()),
+            CircuitBreakerConfig {
+                failure_threshold: 2,
+                ..Default::default()
+            },
+        ));
+        let step = FraudCheckStep::new(FraudScreening {
+            service: service.clone(),
+            breaker,
+        });
+        (step, service)
+    }
+
+    fn context() -> StepContext {
+        let data = OrderSagaData {
+            order_id: Uuid::new_v4(),
+            customer_id: Uuid::new_v4(),
+            items: vec![],
+            total_amount: 2_500.0,
+            currency: "USD".to_string(),
+            payment_method: "credit_card".to_string(),
+            correlation_id: Uuid::new_v4(),
+        };
+        StepContext {
+            saga_id: Uuid::new_v4(),
+            step_name: "fraud_check".to_string(),
+            data: serde_json::to_value(data).unwrap(),
+            idempotency_key: "test".to_string(),
+            approval: None,
+            cancellation: Default::default(),
+            deadline: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fraud_decisions_map_to_saga_outcomes() {
+        let (step, _) = fraud_step(Some(FraudDecision::Approve));
+        let result = step.execute(&context()).await.unwrap();
+        assert_eq!(result["decision"], "APPROVE");
+
+        let (step, _) = fraud_step(Some(FraudDecision::Review));
+        assert!(matches!(
+            step.execute(&context()).await,
+            Err(SagaError::PauseRequested(_))
+        ));
+
+        let (step, _) = fraud_step(Some(FraudDecision::Decline));
+        let declined = step.execute(&context()).await.unwrap_err();
+        assert!(matches!(declined, SagaError::StepExecutionFailed(_)));
+        assert!(!declined.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_steps_send_the_idempotency_key_downstream() {
+        let mut context = context();
+        context.idempotency_key = format!("saga:{}:fraud_check:0", context.saga_id);
+
+        let (step, service) = fraud_step(Some(FraudDecision::Approve));
+        step.execute(&context).await.unwrap();
+        assert_eq!(
+            service.last_key.lock().unwrap().as_deref(),
+            Some(context.idempotency_key.as_str())
+        );
+
+        let payments = Arc::new(InMemoryPublisher::new("payments"));
+        AuthorizePaymentStep::new(payments.clone())
+            .execute(&context)
+            .await
+            .unwrap();
+        let published = payments.messages();
+        assert_eq!(
+            published[0].payload["metadata"]["idempotency_key"],
+            context.idempotency_key.as_str()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_approved_review_skips_screening() {
+        let (step, service) = fraud_step(Some(FraudDecision::Review));
+        let mut context = context();
+        context.approval = Some(saga::StepApproval {
+            approved: true,
+            approver: "ops@example.com".to_string(),
+            comment: None,
+            decided_at: Utc::now(),
+        });
+
+        let result = step.execute(&context).await.unwrap();
+        assert_eq!(result["approved_by"], "ops@example.com");
+        assert_eq!(service.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_high_value_orders_require_approval() {
+        let saga = OrderProcessingSaga {
+            executors: HashMap::new(),
+            approval_amount: Some(5_000.0),
+            fraud_timeout: Duration::from_secs(2),
+        };
+        assert!(saga.requires_approval(&serde_json::json!({"total_amount": 7_500.0})));
+        assert!(!saga.requires_approval(&serde_json::json!({"total_amount": 5_000.0})));
+
+        let saga = OrderProcessingSaga {
+            executors: HashMap::new(),
+            approval_amount: None,
+            fraud_timeout: Duration::from_secs(2),
+        };
+        assert!(!saga.requires_approval(&serde_json::json!({"total_amount": 7_500.0})));
+    }
+
+    #[tokio::test]
+    async fn test_steps_follow_definition() {
+        let saga = OrderProcessingSaga {
+            executors: HashMap::new(),
+            approval_amount: Some(5_000.0),
+            fraud_timeout: Duration::from_secs(2),
+        };
+        let definition = saga.definition();
+        assert_eq!(definition.steps[1].timeout_ms, Some(2_000));
+        assert_eq!(
+            definition.steps[2].approval.as_deref(),
+            Some("total_amount > 5000")
+        );
+
+        let state = saga
+            .create_state(Uuid::new_v4(), serde_json::json!({"total_amount": 7_500.0}))
+            .await
+            .unwrap();
+        let names: Vec<&str> = state.steps.iter().map(|step| step.name.as_str()).collect();
+        let defined: Vec<&str> = definition.steps.iter().map(|step| step.name.as_str()).collect();
+        assert_eq!(names, defined);
+        assert_eq!(state.steps[1].backoff, definition.steps[1].backoff);
+        assert!(state.steps[2].requires_approval);
+    }
+
+    #[tokio::test]
+    async fn test_open_breaker_stops_calling_fraud_service() {
+        let (step, service) = fraud_step(None);
+
+        for _ in 0..2 {
+            let error = step.execute(&context()).await.unwrap_err();
+            assert!(error.is_retryable());
+        }
+        assert_eq!(step.fraud.breaker.get_state().await, CircuitBreakerState::Open);
+
+        let error = step.execute(&context()).await.unwrap_err();
+        assert!(error.to_string().contains("circuit breaker is open"));
+        assert_eq!(service.calls.load(Ordering::SeqCst), 2);
+    }
 
     #[test]
     fn test_order_saga_data_serialization() {
@@ -1,6 +1,10 @@
-use event_store::{Event, EventStore, PostgresEventStore};
+use domain::events::EventMetadata;
+use event_store::{
+    DeleteMode, Event, EventStore, EventStoreError, PayloadValidator, PostgresEventStore,
+};
 use serde_json::json;
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Helper function to create a test database pool
@@ -40,9 +44,7 @@ async fn test_append_and_load_single_event() {
             "customer_id": Uuid::new_v4().to_string(),
             "total_amount": 100.50
         }),
-        json!({
-            "correlation_id": Uuid::new_v4().to_string()
-        }),
+        EventMetadata::with_correlation(Uuid::new_v4()),
     );
 
     // Append event
@@ -74,7 +76,7 @@ async fn test_append_multiple_events() {
             "OrderCreated".to_string(),
             1,
             json!({"status": "created"}),
-            json!({}),
+            EventMetadata::new(),
         ),
         Event::new(
             aggregate_id,
@@ -82,7 +84,7 @@ async fn test_append_multiple_events() {
             "OrderConfirmed".to_string(),
             1,
             json!({"status": "confirmed"}),
-            json!({}),
+            EventMetadata::new(),
         ),
     ];
 
@@ -116,7 +118,7 @@ async fn test_optimistic_concurrency_control() {
         "OrderCreated".to_string(),
         1,
         json!({"status": "created"}),
-        json!({}),
+        EventMetadata::new(),
     );
     store.append_events(aggregate_id, 0, vec![event1]).await.unwrap();
 
@@ -127,7 +129,7 @@ async fn test_optimistic_concurrency_control() {
         "OrderConfirmed".to_string(),
         1,
         json!({"status": "confirmed"}),
-        json!({}),
+        EventMetadata::new(),
     );
     let result = store.append_events(aggregate_id, 0, vec![event2]).await;
     assert!(result.is_err());
@@ -139,7 +141,7 @@ async fn test_optimistic_concurrency_control() {
         "OrderConfirmed".to_string(),
         1,
         json!({"status": "confirmed"}),
-        json!({}),
+        EventMetadata::new(),
     );
     let result = store.append_events(aggregate_id, 1, vec![event3]).await;
     assert!(result.is_ok());
@@ -167,7 +169,7 @@ async fn test_load_events_from_version() {
             "OrderCreated".to_string(),
             1,
             json!({"status": "created"}),
-            json!({}),
+            EventMetadata::new(),
         ),
         Event::new(
             aggregate_id,
@@ -175,7 +177,7 @@ async fn test_load_events_from_version() {
             "OrderConfirmed".to_string(),
             1,
             json!({"status": "confirmed"}),
-            json!({}),
+            EventMetadata::new(),
         ),
         Event::new(
             aggregate_id,
@@ -183,7 +185,7 @@ async fn test_load_events_from_version() {
             "OrderShipped".to_string(),
             1,
             json!({"status": "shipped"}),
-            json!({}),
+            EventMetadata::new(),
         ),
     ];
     store.append_events(aggregate_id, 0, events).await.unwrap();
@@ -219,7 +221,7 @@ async fn test_get_current_version() {
         "OrderCreated".to_string(),
         1,
         json!({"status": "created"}),
-        json!({}),
+        EventMetadata::new(),
     );
     store.append_events(aggregate_id, 0, vec![event]).await.unwrap();
 
@@ -246,7 +248,7 @@ async fn test_event_ordering() {
             format!("Event{}", i),
             1,
             json!({"index": i}),
-            json!({}),
+            EventMetadata::new(),
         );
         store.append_events(aggregate_id, i, vec![event]).await.unwrap();
     }
@@ -263,3 +265,268 @@ async fn test_event_ordering() {
     // Cleanup
     cleanup_aggregate(&pool, aggregate_id).await;
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_load_events_paged() {
+    use futures::TryStreamExt;
+
+    let pool = create_test_pool().await;
+    let store = PostgresEventStore::new(pool.clone());
+    let aggregate_id = Uuid::new_v4();
+
+    for i in 0..5 {
+        let event = Event::new(
+            aggregate_id,
+            "Order".to_string(),
+            format!("Event{}", i),
+            1,
+            json!({"index": i}),
+            EventMetadata::new(),
+        );
+        store.append_events(aggregate_id, i, vec![event]).await.unwrap();
+    }
+
+    // Page size smaller than the stream forces multiple round trips
+    let loaded_events: Vec<Event> = store
+        .load_events_paged(aggregate_id, 0, 2)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(loaded_events.len(), 5);
+
+    for (i, event) in loaded_events.iter().enumerate() {
+        assert_eq!(event.sequence_number, (i + 1) as i64);
+    }
+
+    // Cleanup
+    cleanup_aggregate(&pool, aggregate_id).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_verify_stream_detects_tampering() {
+    let pool = create_test_pool().await;
+    let store = PostgresEventStore::new(pool.clone()).with_hash_chaining(true);
+    let aggregate_id = Uuid::new_v4();
+
+    for i in 0..3 {
+        let event = Event::new(
+            aggregate_id,
+            "Order".to_string(),
+            format!("Event{}", i),
+            1,
+            json!({"index": i}),
+            EventMetadata::new(),
+        );
+        store.append_events(aggregate_id, i, vec![event]).await.unwrap();
+    }
+
+    let verification = store.verify_stream(aggregate_id).await.unwrap();
+    assert!(verification.is_valid());
+    assert_eq!(verification.events_checked, 3);
+
+    // Rewrite history behind the store's back
+    sqlx::query("UPDATE events SET payload = $1 WHERE aggregate_id = $2 AND version = 2")
+        .bind(json!({"index": 42}))
+        .bind(aggregate_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let verification = store.verify_stream(aggregate_id).await.unwrap();
+    assert_eq!(verification.first_invalid_version, Some(2));
+
+    // Cleanup
+    cleanup_aggregate(&pool, aggregate_id).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_load_rejects_invalid_metadata() {
+    let pool = create_test_pool().await;
+    let store = PostgresEventStore::new(pool.clone());
+    let aggregate_id = Uuid::new_v4();
+
+    let event = Event::new(
+        aggregate_id,
+        "Order".to_string(),
+        "OrderCreated".to_string(),
+        1,
+        json!({}),
+        EventMetadata::new().with_extra("tenant", json!("acme")),
+    );
+    let event_id = event.event_id;
+    store.append_events(aggregate_id, 0, vec![event]).await.unwrap();
+
+    let loaded = store.load_events(aggregate_id).await.unwrap();
+    assert_eq!(loaded[0].metadata.extra["tenant"], "acme");
+
+    // Drop the correlation ID behind the store's back
+    sqlx::query("UPDATE events SET metadata = metadata - 'correlation_id' WHERE aggregate_id = $1")
+        .bind(aggregate_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let result = store.load_events(aggregate_id).await;
+    assert!(matches!(result, Err(EventStoreError::InvalidMetadata { event_id: id, .. }) if id == event_id));
+
+    // Cleanup
+    cleanup_aggregate(&pool, aggregate_id).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_append_rejects_payloads_failing_schema() {
+    let pool = create_test_pool().await;
+    let store = PostgresEventStore::new(pool.clone())
+        .with_payload_validator(Arc::new(PayloadValidator::from_event_schemas().unwrap()));
+    let aggregate_id = Uuid::new_v4();
+
+    let malformed = Event::new(
+        aggregate_id,
+        "Order".to_string(),
+        "OrderConfirmed".to_string(),
+        1,
+        json!({"order_id": aggregate_id.to_string()}),
+        EventMetadata::new(),
+    );
+    let result = store.append_events(aggregate_id, 0, vec![malformed]).await;
+    assert!(matches!(result, Err(EventStoreError::InvalidPayload { .. })));
+    assert_eq!(store.get_current_version(aggregate_id).await.unwrap(), 0);
+
+    let valid = Event::new(
+        aggregate_id,
+        "Order".to_string(),
+        "OrderConfirmed".to_string(),
+        1,
+        json!({"order_id": aggregate_id.to_string(), "confirmed_at": "2024-01-01T00:00:00Z"}),
+        EventMetadata::new(),
+    );
+    store.append_events(aggregate_id, 0, vec![valid]).await.unwrap();
+
+    // The store's own tombstone passes validation too
+    store.delete_stream(aggregate_id, DeleteMode::Soft).await.unwrap();
+    assert!(store.is_stream_deleted(aggregate_id).await.unwrap());
+
+    // Cleanup
+    cleanup_aggregate(&pool, aggregate_id).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_delete_stream_soft_and_hard() {
+    let pool = create_test_pool().await;
+    let store = PostgresEventStore::new(pool.clone());
+    let aggregate_id = Uuid::new_v4();
+
+    let event = Event::new(
+        aggregate_id,
+        "Order".to_string(),
+        "OrderCreated".to_string(),
+        1,
+        json!({"status": "created"}),
+        EventMetadata::new(),
+    );
+    store.append_events(aggregate_id, 0, vec![event]).await.unwrap();
+
+    // Soft delete appends a tombstone and closes the stream
+    store.delete_stream(aggregate_id, DeleteMode::Soft).await.unwrap();
+    assert!(store.is_stream_deleted(aggregate_id).await.unwrap());
+    assert_eq!(store.load_events(aggregate_id).await.unwrap().len(), 2);
+
+    let late_event = Event::new(
+        aggregate_id,
+        "Order".to_string(),
+        "OrderConfirmed".to_string(),
+        1,
+        json!({}),
+        EventMetadata::new(),
+    );
+    let result = store.append_events(aggregate_id, 2, vec![late_event]).await;
+    assert!(matches!(result, Err(EventStoreError::StreamDeleted(_))));
+
+    // Hard delete scavenges everything
+    store.delete_stream(aggregate_id, DeleteMode::Hard).await.unwrap();
+    assert!(store.load_events(aggregate_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_duplicate_command_id_is_rejected() {
+    let pool = create_test_pool().await;
+    let store = PostgresEventStore::new(pool.clone());
+    let first_aggregate = Uuid::new_v4();
+    let second_aggregate = Uuid::new_v4();
+    let command_id = Uuid::new_v4();
+
+    let make_event = |aggregate_id| {
+        Event::new(
+            aggregate_id,
+            "Order".to_string(),
+            "OrderCreated".to_string(),
+            1,
+            json!({"order_id": aggregate_id.to_string()}),
+            EventMetadata::new().with_command_id(Some(command_id)),
+        )
+    };
+
+    store
+        .append_events(first_aggregate, 0, vec![make_event(first_aggregate)])
+        .await
+        .unwrap();
+
+    // Same command against a fresh aggregate must not apply twice
+    let result = store
+        .append_events(second_aggregate, 0, vec![make_event(second_aggregate)])
+        .await;
+    assert!(matches!(result, Err(EventStoreError::DuplicateCommand(id)) if id == command_id));
+
+    let found = store.find_events_by_command_id(command_id).await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].aggregate_id, first_aggregate);
+
+    sqlx::query("DELETE FROM processed_commands WHERE command_id = $1")
+        .bind(command_id)
+        .execute(&pool)
+        .await
+        .ok();
+    cleanup_aggregate(&pool, first_aggregate).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_find_events_by_correlation_id() {
+    let pool = create_test_pool().await;
+    let store = PostgresEventStore::new(pool.clone());
+    let order_id = Uuid::new_v4();
+    let payment_id = Uuid::new_v4();
+    let correlation_id = Uuid::new_v4();
+
+    for (aggregate_id, aggregate_type, event_type) in [
+        (order_id, "Order", "OrderCreated"),
+        (payment_id, "Payment", "PaymentAuthorized"),
+    ] {
+        let event = Event::new(
+            aggregate_id,
+            aggregate_type.to_string(),
+            event_type.to_string(),
+            1,
+            json!({}),
+            EventMetadata::with_correlation(correlation_id),
+        );
+        store.append_events(aggregate_id, 0, vec![event]).await.unwrap();
+    }
+
+    let events = store
+        .find_events_by_correlation_id(correlation_id, 100)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].aggregate_id, order_id);
+    assert_eq!(events[1].aggregate_id, payment_id);
+
+    cleanup_aggregate(&pool, order_id).await;
+    cleanup_aggregate(&pool, payment_id).await;
+}
@@ -133,6 +133,7 @@ async fn test_circuit_breaker_success() {
             success_threshold: 2,
             timeout: Duration::from_secs(5),
             half_open_timeout: Duration::from_secs(10),
+            ..Default::default()
         },
     );
 
@@ -165,6 +166,7 @@ async fn test_circuit_breaker_timeout() {
             success_threshold: 2,
             timeout: Duration::from_millis(100),
             half_open_timeout: Duration::from_secs(10),
+            ..Default::default()
         },
     );
 
@@ -207,6 +209,7 @@ async fn test_circuit_breaker_multiple_operations() {
             success_threshold: 2,
             timeout: Duration::from_secs(1),
             half_open_timeout: Duration::from_secs(5),
+            ..Default::default()
         },
     );
 
@@ -1,5 +1,6 @@
 use chrono::Utc;
 use saga::coordinator::SagaCoordinator;
+use saga::definition::SagaDefinition;
 use saga::repository::SagaRepository;
 use saga::saga::{Saga, SagaState, SagaStatus};
 use saga::step::{SagaStep, StepContext, StepExecutor};
@@ -34,6 +35,15 @@ impl SagaRepository for MockSagaRepository {
         Ok(())
     }
 
+    async fn update_if_status(&self, state: &SagaState, expected: SagaStatus) -> Result<bool> {
+        let mut states = self.states.lock().unwrap();
+        if states.get(&state.saga_id).map(|s| s.status) != Some(expected) {
+            return Ok(false);
+        }
+        states.insert(state.saga_id, state.clone());
+        Ok(true)
+    }
+
     async fn load(&self, saga_id: Uuid) -> Result<SagaState> {
         self.states
             .lock()
@@ -43,6 +53,16 @@ impl SagaRepository for MockSagaRepository {
             .ok_or_else(|| SagaError::SagaNotFound(saga_id.to_string()))
     }
 
+    async fn find_by_business_key(&self, saga_type: &str, key: &str) -> Result<Option<SagaState>> {
+        Ok(self
+            .states
+            .lock()
+            .unwrap()
+            .values()
+            .find(|s| s.saga_type == saga_type && s.correlation_key.as_deref() == Some(key))
+            .cloned())
+    }
+
     async fn find_by_status(&self, status: SagaStatus, _limit: i64) -> Result<Vec<SagaState>> {
         Ok(self
             .states
@@ -54,10 +74,59 @@ impl SagaRepository for MockSagaRepository {
             .collect())
     }
 
+    async fn find_by_correlation_id(&self, correlation_id: Uuid, _limit: i64) -> Result<Vec<SagaState>> {
+        Ok(self
+            .states
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|s| s.correlation_id() == Some(correlation_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn find_stale_in_partitions(
+        &self,
+        partitions: &[i32],
+        stale_before: chrono::DateTime<Utc>,
+        _limit: i64,
+    ) -> Result<Vec<SagaState>> {
+        Ok(self
+            .states
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|s| {
+                s.status == SagaStatus::Running
+                    && s.partition.is_some_and(|p| partitions.contains(&p))
+                    && s.updated_at < stale_before
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn count_by_status(&self, status: SagaStatus) -> Result<i64> {
+        Ok(self
+            .states
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|s| s.status == status)
+            .count() as i64)
+    }
+
     async fn delete(&self, saga_id: Uuid) -> Result<()> {
         self.states.lock().unwrap().remove(&saga_id);
         Ok(())
     }
+
+    async fn save_definition(&self, _definition: &SagaDefinition) -> Result<()> {
+        Ok(())
+    }
+
+    async fn list_definitions(&self) -> Result<Vec<SagaDefinition>> {
+        Ok(Vec::new())
+    }
 }
 
 // Mock step executor that succeeds